@@ -0,0 +1,125 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Interfaces of the Frontier client, so that the RPC can be served from a custom storage.
+
+use codec::{Decode, Encode};
+use fp_storage::EthereumStorageSchema;
+use sp_core::H256;
+use sp_runtime::traits::Block as BlockT;
+
+/// Location of an Ethereum transaction in a block.
+#[derive(Clone, Encode, Decode)]
+pub struct TransactionMetadata<Block: BlockT> {
+	pub block_hash: Block::Hash,
+	pub ethereum_block_hash: H256,
+	pub ethereum_index: u32,
+}
+
+/// Fee data of a block, cached by the RPC for `eth_feeHistory`.
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+pub struct FeeHistoryEntry {
+	pub base_fee: u64,
+	/// Bits of the `f64` ratio of the gas used by the block to its gas limit.
+	pub gas_used_ratio: u64,
+	/// Effective priority fees at the cached reward percentiles.
+	pub rewards: Vec<u64>,
+}
+
+/// How the Ethereum transactions are indexed by their hash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransactionIndexing {
+	/// Transactions aren't indexed. Only the transactions mapped before are found by their hash.
+	Off,
+	/// Transactions aren't indexed, but are found by their hash in the `depth` latest blocks
+	/// by scanning their digests.
+	Lazy { depth: u32 },
+	/// All the transactions are indexed.
+	Full,
+}
+
+impl Default for TransactionIndexing {
+	fn default() -> Self {
+		TransactionIndexing::Full
+	}
+}
+
+/// Storage of the mapping between the Ethereum and the Substrate blocks, read by the RPC.
+pub trait Backend<Block: BlockT>: Send + Sync {
+	/// Substrate hash of an Ethereum block.
+	fn block_hash(&self, ethereum_block_hash: &H256) -> Result<Option<Block::Hash>, String>;
+
+	/// Locations of an Ethereum transaction, one per block including it.
+	fn transaction_metadata(
+		&self,
+		ethereum_transaction_hash: &H256,
+	) -> Result<Vec<TransactionMetadata<Block>>, String>;
+
+	/// How the Ethereum transactions are indexed.
+	fn transaction_indexing(&self) -> TransactionIndexing;
+
+	/// Whether the raw payloads of the Ethereum transactions are archived.
+	fn archives_raw_transactions(&self) -> bool {
+		false
+	}
+
+	/// Archived raw payload of an Ethereum transaction, as signed by its sender.
+	fn raw_transaction(
+		&self,
+		_ethereum_transaction_hash: &H256,
+	) -> Result<Option<Vec<u8>>, String> {
+		Ok(None)
+	}
+
+	/// Whether the mapping of a Substrate block is synced.
+	fn is_synced(&self, block_hash: &Block::Hash) -> Result<bool, String>;
+
+	/// Next blocks to map on each branch being synced.
+	fn current_syncing_tips(&self) -> Result<Vec<Block::Hash>, String>;
+
+	/// Size of the storage on disk, in bytes, if known.
+	fn size_on_disk(&self) -> Option<u64> {
+		None
+	}
+
+	/// Ethereum storage schemas, with the Substrate hash of the block introducing each of them.
+	fn ethereum_schema(&self) -> Result<Option<Vec<(EthereumStorageSchema, H256)>>, String>;
+
+	/// Replaces the Ethereum storage schemas.
+	fn write_ethereum_schema(
+		&self,
+		new_cache: Vec<(EthereumStorageSchema, H256)>,
+	) -> Result<(), String>;
+
+	/// Persisted fee data of the latest blocks, by block number, if the storage keeps it
+	/// across restarts.
+	fn fee_history(&self) -> Result<Vec<(u64, FeeHistoryEntry)>, String> {
+		Ok(Vec::new())
+	}
+
+	/// Persists the fee data of a new best block, keeping the ones of the `limit` latest
+	/// blocks.
+	fn write_fee_history(
+		&self,
+		_block_number: u64,
+		_entry: &FeeHistoryEntry,
+		_limit: u64,
+	) -> Result<(), String> {
+		Ok(())
+	}
+}
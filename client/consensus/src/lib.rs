@@ -18,15 +18,22 @@
 
 use std::{collections::HashMap, marker::PhantomData, sync::Arc};
 
+use codec::Decode;
+use ethereum::EnvelopedEncodable;
+use ethereum_types::{Bloom, U256};
+use log::warn;
 use sc_client_api::{backend::AuxStore, BlockOf};
-use sc_consensus::{BlockCheckParams, BlockImport, BlockImportParams, ImportResult};
+use sc_consensus::{
+	BlockCheckParams, BlockImport, BlockImportParams, ImportResult, StateAction, StorageChanges,
+};
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::{well_known_cache_keys::Id as CacheKeyId, HeaderBackend};
 use sp_consensus::Error as ConsensusError;
+use sp_core::hashing::twox_128;
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 
-use fp_consensus::{ensure_log, FindLogError};
+use fp_consensus::{find_log, FindLogError, Hashes};
 use fp_rpc::EthereumRuntimeRPCApi;
 
 #[derive(Debug, thiserror::Error)]
@@ -37,6 +44,16 @@ pub enum Error {
 	NoRuntimeLog,
 	#[error("Cannot access the runtime at genesis, rejecting!")]
 	RuntimeApiCallFailed,
+	#[error("Ethereum block hash in digest does not match the runtime Ethereum block, rejecting!")]
+	EthereumBlockHashMismatch,
+	#[error("Ethereum block receipts root does not match its receipts, rejecting!")]
+	InvalidReceiptsRoot,
+	#[error("Ethereum block logs bloom does not match its receipts, rejecting!")]
+	InvalidLogsBloom,
+	#[error("Ethereum block gas used is invalid or exceeds the gas limit, rejecting!")]
+	InvalidGasUsed,
+	#[error("Ethereum block or receipts missing from the storage changes, rejecting!")]
+	MissingStorageValue,
 }
 
 impl From<Error> for String {
@@ -64,6 +81,8 @@ pub struct FrontierBlockImport<B: BlockT, I, C> {
 	inner: I,
 	client: Arc<C>,
 	backend: Arc<fc_db::Backend<B>>,
+	sync_mapping: bool,
+	pallet_prefix: [u8; 16],
 	_marker: PhantomData<B>,
 }
 
@@ -73,6 +92,8 @@ impl<Block: BlockT, I: Clone + BlockImport<Block>, C> Clone for FrontierBlockImp
 			inner: self.inner.clone(),
 			client: self.client.clone(),
 			backend: self.backend.clone(),
+			sync_mapping: self.sync_mapping,
+			pallet_prefix: self.pallet_prefix,
 			_marker: PhantomData,
 		}
 	}
@@ -87,14 +108,54 @@ where
 	C::Api: EthereumRuntimeRPCApi<B>,
 	C::Api: BlockBuilderApi<B>,
 {
-	pub fn new(inner: I, client: Arc<C>, backend: Arc<fc_db::Backend<B>>) -> Self {
+	/// Create a new Frontier block import.
+	///
+	/// When `sync_mapping` is set, the Ethereum mapping of an imported block is written
+	/// right away instead of waiting for the mapping sync worker, so the transactions of
+	/// a new block are visible to the RPC as soon as it is imported.
+	pub fn new(
+		inner: I,
+		client: Arc<C>,
+		backend: Arc<fc_db::Backend<B>>,
+		sync_mapping: bool,
+	) -> Self {
 		Self {
 			inner,
 			client,
 			backend,
+			sync_mapping,
+			pallet_prefix: twox_128(b"Ethereum"),
 			_marker: PhantomData,
 		}
 	}
+
+	/// Set the name of the pallet-ethereum in the runtime, `Ethereum` by default, under which
+	/// the Ethereum block and receipts written by the block are read from its storage changes.
+	pub fn with_pallet_name(mut self, pallet_name: &str) -> Self {
+		self.pallet_prefix = twox_128(pallet_name.as_bytes());
+		self
+	}
+
+	fn write_mapping(
+		&self,
+		hash: B::Hash,
+		parent_hash: B::Hash,
+		hashes: Hashes,
+	) -> Result<(), String> {
+		// Leave the block to the mapping sync worker if its parent is not synced yet,
+		// as the worker stops walking back the chain at the first synced block.
+		if !self.backend.mapping().is_synced(&parent_hash)? {
+			return Ok(());
+		}
+
+		self.backend
+			.mapping()
+			.write_hashes(fc_db::MappingCommitment {
+				block_hash: hash,
+				ethereum_block_hash: hashes.block_hash,
+				ethereum_transaction_hashes: hashes.transaction_hashes,
+			})
+	}
 }
 
 #[async_trait::async_trait]
@@ -122,14 +183,215 @@ where
 		block: BlockImportParams<B, Self::Transaction>,
 		new_cache: HashMap<CacheKeyId, Vec<u8>>,
 	) -> Result<ImportResult, Self::Error> {
-		// We validate that there are only one frontier log. No other
-		// actions are needed and mapping syncing is delegated to a separate
-		// worker.
-		ensure_log(block.header.digest()).map_err(Error::from)?;
+		// We validate that there are only one frontier log.
+		let hashes = find_log(block.header.digest())
+			.map_err(Error::from)?
+			.into_hashes();
 
-		self.inner
+		// When the block is executed on import, the runtime itself ensures the
+		// digest matches the one it generated. When the storage changes are
+		// provided instead, we check the Ethereum block committed in the
+		// digest against the Ethereum block written by the runtime.
+		if let StateAction::ApplyChanges(StorageChanges::Changes(ref changes)) = block.state_action
+		{
+			let changes = &changes.main_storage_changes;
+			let ethereum_block = changed_storage_value::<ethereum::BlockV2>(
+				changes,
+				&self.pallet_prefix,
+				b"CurrentBlock",
+			)?;
+			if hashes.block_hash != ethereum_block.header.hash() {
+				return Err(Error::EthereumBlockHashMismatch.into());
+			}
+
+			let receipts = changed_storage_value::<Vec<ethereum::ReceiptV3>>(
+				changes,
+				&self.pallet_prefix,
+				b"CurrentReceipts",
+			)?;
+			check_receipts(&ethereum_block.header, &receipts)?;
+		}
+
+		let hash = block.post_hash();
+		let parent_hash = *block.header.parent_hash();
+		let result = self
+			.inner
 			.import_block(block, new_cache)
 			.await
-			.map_err(Into::into)
+			.map_err(Into::into)?;
+
+		if self.sync_mapping {
+			if let ImportResult::Imported(_) = result {
+				if let Err(e) = self.write_mapping(hash, parent_hash, hashes) {
+					warn!(target: "frontier", "Failed to write mapping of block {}: {}", hash, e);
+				}
+			}
+		}
+
+		Ok(result)
+	}
+}
+
+/// Decode a pallet-ethereum storage value written by the block, the pallet-ethereum writing
+/// its block and receipts in every block.
+fn changed_storage_value<T: Decode>(
+	changes: &[(Vec<u8>, Option<Vec<u8>>)],
+	pallet_prefix: &[u8; 16],
+	storage: &[u8],
+) -> Result<T, Error> {
+	let key = [&pallet_prefix[..], &twox_128(storage)[..]].concat();
+	changes
+		.iter()
+		.find(|(k, _)| k == &key)
+		.and_then(|(_, value)| value.as_ref())
+		.and_then(|value| T::decode(&mut &value[..]).ok())
+		.ok_or(Error::MissingStorageValue)
+}
+
+/// Check the Ethereum block header post-conditions against the block receipts.
+fn check_receipts(
+	header: &ethereum::Header,
+	receipts: &[ethereum::ReceiptV3],
+) -> Result<(), Error> {
+	// Receipts are keyed by their RLP encoded index and valued by their EIP-2718 envelope.
+	// Blocks built by older runtimes valued typed receipts by their RLP encoding instead.
+	let receipts_root =
+		ethereum::util::ordered_trie_root(receipts.iter().map(EnvelopedEncodable::encode));
+	let legacy_receipts_root =
+		|| ethereum::util::ordered_trie_root(receipts.iter().map(rlp::encode));
+	if header.receipts_root != receipts_root && header.receipts_root != legacy_receipts_root() {
+		return Err(Error::InvalidReceiptsRoot);
+	}
+
+	let mut logs_bloom = Bloom::default();
+	let mut gas_used = U256::zero();
+	for receipt in receipts {
+		match receipt {
+			ethereum::ReceiptV3::Legacy(d)
+			| ethereum::ReceiptV3::EIP2930(d)
+			| ethereum::ReceiptV3::EIP1559(d) => {
+				logs_bloom.accrue_bloom(&d.logs_bloom);
+				gas_used = d.used_gas;
+			}
+		}
+	}
+
+	if header.logs_bloom != logs_bloom {
+		return Err(Error::InvalidLogsBloom);
+	}
+	if header.gas_used != gas_used || header.gas_used > header.gas_limit {
+		return Err(Error::InvalidGasUsed);
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use ethereum_types::{H160, H256, H64};
+
+	fn receipt(used_gas: u64, logs_bloom: Bloom) -> ethereum::ReceiptV3 {
+		ethereum::ReceiptV3::EIP1559(ethereum::EIP658ReceiptData {
+			status_code: 1,
+			used_gas: U256::from(used_gas),
+			logs_bloom,
+			logs: Vec::new(),
+		})
+	}
+
+	fn receipts() -> Vec<ethereum::ReceiptV3> {
+		vec![
+			receipt(21_000, Bloom::repeat_byte(1)),
+			receipt(42_000, Bloom::repeat_byte(2)),
+		]
+	}
+
+	fn header(receipts: &[ethereum::ReceiptV3]) -> ethereum::Header {
+		ethereum::Header::new(
+			ethereum::PartialHeader {
+				parent_hash: H256::default(),
+				beneficiary: H160::default(),
+				state_root: H256::default(),
+				receipts_root: ethereum::util::ordered_trie_root(
+					receipts.iter().map(EnvelopedEncodable::encode),
+				),
+				logs_bloom: Bloom::repeat_byte(3),
+				difficulty: U256::zero(),
+				number: U256::one(),
+				gas_limit: U256::from(1_000_000),
+				gas_used: U256::from(42_000),
+				timestamp: 0,
+				extra_data: Vec::new(),
+				mix_hash: H256::default(),
+				nonce: H64::default(),
+			},
+			H256::default(),
+			H256::default(),
+		)
+	}
+
+	#[test]
+	fn check_receipts_should_accept_matching_header() {
+		let receipts = receipts();
+		assert!(check_receipts(&header(&receipts), &receipts).is_ok());
+	}
+
+	#[test]
+	fn check_receipts_should_reject_mismatching_header() {
+		let receipts = receipts();
+
+		let mut other_receipts = receipts.clone();
+		other_receipts.pop();
+		assert!(matches!(
+			check_receipts(&header(&receipts), &other_receipts),
+			Err(Error::InvalidReceiptsRoot)
+		));
+
+		let mut wrong_bloom = header(&receipts);
+		wrong_bloom.logs_bloom = Bloom::repeat_byte(1);
+		assert!(matches!(
+			check_receipts(&wrong_bloom, &receipts),
+			Err(Error::InvalidLogsBloom)
+		));
+
+		let mut wrong_gas_used = header(&receipts);
+		wrong_gas_used.gas_used = U256::from(21_000);
+		assert!(matches!(
+			check_receipts(&wrong_gas_used, &receipts),
+			Err(Error::InvalidGasUsed)
+		));
+
+		let mut over_gas_limit = header(&receipts);
+		over_gas_limit.gas_limit = U256::from(21_000);
+		assert!(matches!(
+			check_receipts(&over_gas_limit, &receipts),
+			Err(Error::InvalidGasUsed)
+		));
+	}
+
+	#[test]
+	fn changed_storage_value_should_read_under_the_pallet_prefix() {
+		let prefix = twox_128(b"Ethereum");
+		let changes = vec![(
+			[&prefix[..], &twox_128(b"CurrentReceipts")[..]].concat(),
+			Some(receipts().encode()),
+		)];
+
+		assert!(changed_storage_value::<Vec<ethereum::ReceiptV3>>(
+			&changes,
+			&prefix,
+			b"CurrentReceipts"
+		)
+		.is_ok());
+		assert!(matches!(
+			changed_storage_value::<Vec<ethereum::ReceiptV3>>(
+				&changes,
+				&twox_128(b"Frontier"),
+				b"CurrentReceipts"
+			),
+			Err(Error::MissingStorageValue)
+		));
 	}
 }
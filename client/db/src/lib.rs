@@ -18,17 +18,22 @@
 
 #[cfg(feature = "parity-db")]
 mod parity_db_adapter;
+#[cfg(feature = "kvdb-rocksdb")]
+mod replica;
+mod upgrade;
 mod utils;
 
-use std::{marker::PhantomData, sync::Arc};
+use std::{marker::PhantomData, path::PathBuf, sync::Arc};
 
 use codec::{Decode, Encode};
+pub use fc_api::{FeeHistoryEntry, TransactionIndexing, TransactionMetadata};
 use fp_storage::{EthereumStorageSchema, PALLET_ETHEREUM_SCHEMA_CACHE};
 use parking_lot::Mutex;
 pub use sc_client_db::DatabaseSource;
 use sp_core::H256;
 pub use sp_database::Database;
 use sp_runtime::traits::Block as BlockT;
+pub use upgrade::CURRENT_VERSION;
 
 const DB_HASH_LEN: usize = 32;
 /// Hash type that this backend uses for the database.
@@ -38,6 +43,19 @@ pub type DbHash = [u8; DB_HASH_LEN];
 pub struct DatabaseSettings {
 	/// Where to find the database.
 	pub source: DatabaseSource,
+	/// How the Ethereum transactions are indexed.
+	pub transaction_indexing: TransactionIndexing,
+	/// Whether the raw payloads of the mapped Ethereum transactions are archived.
+	pub archive_raw_transactions: bool,
+	/// Whether the migrations rewriting a large part of the database run on start. If not,
+	/// opening a database requiring such a migration fails.
+	pub allow_large_migrations: bool,
+	/// If set, the RocksDB database of `source` is opened as a read-only replica of the
+	/// database written by another node, keeping the state of the replica in this directory.
+	///
+	/// The replica catches up with the writes of the other node every second. Its mapping
+	/// isn't written, so the blocks must not be mapped by the node opening it.
+	pub replica_path: Option<PathBuf>,
 }
 
 pub(crate) mod columns {
@@ -51,30 +69,97 @@ pub(crate) mod columns {
 
 pub(crate) mod static_keys {
 	pub const CURRENT_SYNCING_TIPS: &[u8] = b"CURRENT_SYNCING_TIPS";
+	/// Number and hash of the last block mapped by a sync going forward from the genesis.
+	pub const FORWARD_SYNC_TIP: &[u8] = b"FORWARD_SYNC_TIP";
+	pub const DATABASE_VERSION: &[u8] = b"DATABASE_VERSION";
+	/// Oldest and latest block numbers of the persisted fee history.
+	pub const FEE_HISTORY_RANGE: &[u8] = b"FEE_HISTORY_RANGE";
+	/// Prefix of the fee history of a block, followed by its number.
+	pub const FEE_HISTORY: &[u8] = b"FEE_HISTORY";
+	/// Prefix of the raw payload of a transaction in the transaction mapping column, followed
+	/// by its hash.
+	pub const RAW_TRANSACTION: &[u8] = b"RAW_TRANSACTION";
+	/// Prefix of the marker of a block whose raw transactions are archived in the transaction
+	/// mapping column, followed by its hash.
+	pub const RAW_TRANSACTIONS_ARCHIVED: &[u8] = b"RAW_TRANSACTIONS_ARCHIVED";
 }
 
 pub struct Backend<Block: BlockT> {
 	meta: Arc<MetaDb<Block>>,
 	mapping: Arc<MappingDb<Block>>,
+	/// Path of the database, if it is stored on disk.
+	path: Option<PathBuf>,
 }
 
 impl<Block: BlockT> Backend<Block> {
 	pub fn new(config: &DatabaseSettings) -> Result<Self, String> {
-		let db = utils::open_database(config)?;
+		let (db, path) = utils::open_database(config)?;
+
+		Self::from_database(
+			db,
+			Some(path),
+			config.transaction_indexing,
+			config.archive_raw_transactions,
+			config.allow_large_migrations,
+			config.replica_path.is_some(),
+		)
+	}
+
+	/// Creates a backend kept in memory, which is lost when dropped, for tests and ephemeral
+	/// nodes.
+	pub fn new_in_memory(
+		transaction_indexing: TransactionIndexing,
+		archive_raw_transactions: bool,
+	) -> Self {
+		Self::from_database(
+			Arc::new(sp_database::MemDb::default()),
+			None,
+			transaction_indexing,
+			archive_raw_transactions,
+			false,
+			false,
+		)
+		.expect("an empty in-memory database is at the current version; qed")
+	}
+
+	fn from_database(
+		db: Arc<dyn Database<DbHash>>,
+		path: Option<PathBuf>,
+		transaction_indexing: TransactionIndexing,
+		archive_raw_transactions: bool,
+		allow_large_migrations: bool,
+		read_only: bool,
+	) -> Result<Self, String> {
+		if read_only {
+			// The database is migrated by its writer.
+			upgrade::check_database_version(db.as_ref())?;
+		} else {
+			upgrade::upgrade_database(db.as_ref(), allow_large_migrations)?;
+		}
 
 		Ok(Self {
 			mapping: Arc::new(MappingDb {
 				db: db.clone(),
 				write_lock: Arc::new(Mutex::new(())),
+				transaction_indexing,
+				archive_raw_transactions,
+				read_only,
 				_marker: PhantomData,
 			}),
 			meta: Arc::new(MetaDb {
 				db: db.clone(),
+				read_only,
 				_marker: PhantomData,
 			}),
+			path,
 		})
 	}
 
+	/// Whether the database is a read-only replica of the database of another node.
+	pub fn is_read_only(&self) -> bool {
+		self.mapping.read_only
+	}
+
 	pub fn mapping(&self) -> &Arc<MappingDb<Block>> {
 		&self.mapping
 	}
@@ -84,12 +169,80 @@ impl<Block: BlockT> Backend<Block> {
 	}
 }
 
+impl<Block: BlockT> fc_api::Backend<Block> for Backend<Block> {
+	fn block_hash(&self, ethereum_block_hash: &H256) -> Result<Option<Block::Hash>, String> {
+		self.mapping.block_hash(ethereum_block_hash)
+	}
+
+	fn transaction_metadata(
+		&self,
+		ethereum_transaction_hash: &H256,
+	) -> Result<Vec<TransactionMetadata<Block>>, String> {
+		self.mapping.transaction_metadata(ethereum_transaction_hash)
+	}
+
+	fn transaction_indexing(&self) -> TransactionIndexing {
+		self.mapping.transaction_indexing()
+	}
+
+	fn archives_raw_transactions(&self) -> bool {
+		self.mapping.archives_raw_transactions()
+	}
+
+	fn raw_transaction(&self, ethereum_transaction_hash: &H256) -> Result<Option<Vec<u8>>, String> {
+		self.mapping.raw_transaction(ethereum_transaction_hash)
+	}
+
+	fn is_synced(&self, block_hash: &Block::Hash) -> Result<bool, String> {
+		self.mapping.is_synced(block_hash)
+	}
+
+	fn current_syncing_tips(&self) -> Result<Vec<Block::Hash>, String> {
+		self.meta.current_syncing_tips()
+	}
+
+	fn size_on_disk(&self) -> Option<u64> {
+		utils::directory_size(self.path.as_ref()?).ok()
+	}
+
+	fn ethereum_schema(&self) -> Result<Option<Vec<(EthereumStorageSchema, H256)>>, String> {
+		self.meta.ethereum_schema()
+	}
+
+	fn write_ethereum_schema(
+		&self,
+		new_cache: Vec<(EthereumStorageSchema, H256)>,
+	) -> Result<(), String> {
+		self.meta.write_ethereum_schema(new_cache)
+	}
+
+	fn fee_history(&self) -> Result<Vec<(u64, FeeHistoryEntry)>, String> {
+		self.meta.fee_history()
+	}
+
+	fn write_fee_history(
+		&self,
+		block_number: u64,
+		entry: &FeeHistoryEntry,
+		limit: u64,
+	) -> Result<(), String> {
+		self.meta.write_fee_history(block_number, entry, limit)
+	}
+}
+
 pub struct MetaDb<Block: BlockT> {
 	db: Arc<dyn Database<DbHash>>,
+	/// The caches of a read-only replica are written by its writer, so that their writes are
+	/// ignored.
+	read_only: bool,
 	_marker: PhantomData<Block>,
 }
 
 impl<Block: BlockT> MetaDb<Block> {
+	pub fn database_version(&self) -> Result<Option<u32>, String> {
+		upgrade::database_version(self.db.as_ref())
+	}
+
 	pub fn current_syncing_tips(&self) -> Result<Vec<Block::Hash>, String> {
 		match self.db.get(
 			crate::columns::META,
@@ -103,6 +256,8 @@ impl<Block: BlockT> MetaDb<Block> {
 	}
 
 	pub fn write_current_syncing_tips(&self, tips: Vec<Block::Hash>) -> Result<(), String> {
+		check_writable(self.read_only)?;
+
 		let mut transaction = sp_database::Transaction::new();
 
 		transaction.set(
@@ -118,6 +273,38 @@ impl<Block: BlockT> MetaDb<Block> {
 		Ok(())
 	}
 
+	/// Number and hash of the last block mapped by a sync going forward from the genesis, all
+	/// the blocks before it being mapped.
+	pub fn forward_sync_tip(&self) -> Result<Option<(u64, Block::Hash)>, String> {
+		match self
+			.db
+			.get(crate::columns::META, crate::static_keys::FORWARD_SYNC_TIP)
+		{
+			Some(raw) => Ok(Some(
+				Decode::decode(&mut &raw[..]).map_err(|e| format!("{:?}", e))?,
+			)),
+			None => Ok(None),
+		}
+	}
+
+	pub fn write_forward_sync_tip(&self, number: u64, hash: Block::Hash) -> Result<(), String> {
+		check_writable(self.read_only)?;
+
+		let mut transaction = sp_database::Transaction::new();
+
+		transaction.set(
+			crate::columns::META,
+			crate::static_keys::FORWARD_SYNC_TIP,
+			&(number, hash).encode(),
+		);
+
+		self.db
+			.commit(transaction)
+			.map_err(|e| format!("{:?}", e))?;
+
+		Ok(())
+	}
+
 	pub fn ethereum_schema(&self) -> Result<Option<Vec<(EthereumStorageSchema, H256)>>, String> {
 		match self
 			.db
@@ -134,6 +321,10 @@ impl<Block: BlockT> MetaDb<Block> {
 		&self,
 		new_cache: Vec<(EthereumStorageSchema, H256)>,
 	) -> Result<(), String> {
+		if self.read_only {
+			return Ok(());
+		}
+
 		let mut transaction = sp_database::Transaction::new();
 
 		transaction.set(
@@ -148,28 +339,194 @@ impl<Block: BlockT> MetaDb<Block> {
 
 		Ok(())
 	}
+
+	fn fee_history_range(&self) -> Result<Option<(u64, u64)>, String> {
+		match self
+			.db
+			.get(crate::columns::META, crate::static_keys::FEE_HISTORY_RANGE)
+		{
+			Some(raw) => Ok(Some(
+				Decode::decode(&mut &raw[..]).map_err(|e| format!("{:?}", e))?,
+			)),
+			None => Ok(None),
+		}
+	}
+
+	pub fn fee_history(&self) -> Result<Vec<(u64, FeeHistoryEntry)>, String> {
+		let (oldest, latest) = match self.fee_history_range()? {
+			Some(range) => range,
+			None => return Ok(Vec::new()),
+		};
+
+		let mut fee_history = Vec::new();
+		for block_number in oldest..=latest {
+			if let Some(raw) = self
+				.db
+				.get(crate::columns::META, &fee_history_key(block_number))
+			{
+				let entry =
+					FeeHistoryEntry::decode(&mut &raw[..]).map_err(|e| format!("{:?}", e))?;
+				fee_history.push((block_number, entry));
+			}
+		}
+		Ok(fee_history)
+	}
+
+	/// Persists the fee history of a new best block, removing the ones older than the `limit`
+	/// latest blocks and the ones of the blocks above it, which have been retracted.
+	pub fn write_fee_history(
+		&self,
+		block_number: u64,
+		entry: &FeeHistoryEntry,
+		limit: u64,
+	) -> Result<(), String> {
+		if self.read_only {
+			return Ok(());
+		}
+
+		let (oldest, latest) = self
+			.fee_history_range()?
+			.unwrap_or((block_number, block_number));
+		let new_oldest = oldest
+			.min(block_number)
+			.max(block_number.saturating_sub(limit.saturating_sub(1)));
+
+		let mut transaction = sp_database::Transaction::new();
+
+		let pruned = oldest..new_oldest.min(latest.saturating_add(1));
+		let retracted = block_number.saturating_add(1)..=latest;
+		for removed in pruned.chain(retracted) {
+			transaction.remove(crate::columns::META, &fee_history_key(removed));
+		}
+		transaction.set(
+			crate::columns::META,
+			&fee_history_key(block_number),
+			&entry.encode(),
+		);
+		transaction.set(
+			crate::columns::META,
+			crate::static_keys::FEE_HISTORY_RANGE,
+			&(new_oldest, block_number).encode(),
+		);
+
+		self.db
+			.commit(transaction)
+			.map_err(|e| format!("{:?}", e))?;
+
+		Ok(())
+	}
 }
 
-pub struct MappingCommitment<Block: BlockT> {
-	pub block_hash: Block::Hash,
-	pub ethereum_block_hash: H256,
-	pub ethereum_transaction_hashes: Vec<H256>,
+fn check_writable(read_only: bool) -> Result<(), String> {
+	if read_only {
+		return Err("The Frontier database is a read-only replica".to_string());
+	}
+	Ok(())
 }
 
-#[derive(Clone, Encode, Decode)]
-pub struct TransactionMetadata<Block: BlockT> {
+fn raw_transaction_key(ethereum_transaction_hash: &H256) -> Vec<u8> {
+	let mut key = crate::static_keys::RAW_TRANSACTION.to_vec();
+	key.extend_from_slice(ethereum_transaction_hash.as_bytes());
+	key
+}
+
+fn raw_transactions_archived_key<Block: BlockT>(block_hash: &Block::Hash) -> Vec<u8> {
+	let mut key = crate::static_keys::RAW_TRANSACTIONS_ARCHIVED.to_vec();
+	key.extend_from_slice(&block_hash.encode());
+	key
+}
+
+fn fee_history_key(block_number: u64) -> Vec<u8> {
+	let mut key = crate::static_keys::FEE_HISTORY.to_vec();
+	key.extend_from_slice(&block_number.encode());
+	key
+}
+
+pub struct MappingCommitment<Block: BlockT> {
 	pub block_hash: Block::Hash,
 	pub ethereum_block_hash: H256,
-	pub ethereum_index: u32,
+	pub ethereum_transaction_hashes: Vec<H256>,
 }
 
 pub struct MappingDb<Block: BlockT> {
 	db: Arc<dyn Database<DbHash>>,
 	write_lock: Arc<Mutex<()>>,
+	transaction_indexing: TransactionIndexing,
+	archive_raw_transactions: bool,
+	read_only: bool,
 	_marker: PhantomData<Block>,
 }
 
 impl<Block: BlockT> MappingDb<Block> {
+	pub fn transaction_indexing(&self) -> TransactionIndexing {
+		self.transaction_indexing
+	}
+
+	pub fn archives_raw_transactions(&self) -> bool {
+		self.archive_raw_transactions
+	}
+
+	pub fn raw_transaction(
+		&self,
+		ethereum_transaction_hash: &H256,
+	) -> Result<Option<Vec<u8>>, String> {
+		Ok(self.db.get(
+			crate::columns::TRANSACTION_MAPPING,
+			&raw_transaction_key(ethereum_transaction_hash),
+		))
+	}
+
+	/// Whether the raw transactions of a block are archived. Always true when the raw
+	/// transactions aren't archived at all.
+	///
+	/// The archive is tracked apart from the mapping, as a block may be mapped on import,
+	/// before its raw transactions are archived by the mapping sync.
+	pub fn is_archived(&self, block_hash: &Block::Hash) -> Result<bool, String> {
+		if !self.archive_raw_transactions {
+			return Ok(true);
+		}
+		Ok(self
+			.db
+			.get(
+				crate::columns::TRANSACTION_MAPPING,
+				&raw_transactions_archived_key::<Block>(block_hash),
+			)
+			.is_some())
+	}
+
+	/// Archives the raw payloads of the Ethereum transactions of a block, by hash, if enabled.
+	pub fn write_raw_transactions(
+		&self,
+		block_hash: Block::Hash,
+		transactions: Vec<(H256, Vec<u8>)>,
+	) -> Result<(), String> {
+		if !self.archive_raw_transactions {
+			return Ok(());
+		}
+		check_writable(self.read_only)?;
+
+		let mut transaction = sp_database::Transaction::new();
+
+		for (ethereum_transaction_hash, raw) in transactions {
+			transaction.set(
+				crate::columns::TRANSACTION_MAPPING,
+				&raw_transaction_key(&ethereum_transaction_hash),
+				&raw,
+			);
+		}
+		transaction.set(
+			crate::columns::TRANSACTION_MAPPING,
+			&raw_transactions_archived_key::<Block>(&block_hash),
+			&true.encode(),
+		);
+
+		self.db
+			.commit(transaction)
+			.map_err(|e| format!("{:?}", e))?;
+
+		Ok(())
+	}
+
 	pub fn is_synced(&self, block_hash: &Block::Hash) -> Result<bool, String> {
 		match self
 			.db
@@ -207,6 +564,7 @@ impl<Block: BlockT> MappingDb<Block> {
 	}
 
 	pub fn write_none(&self, block_hash: Block::Hash) -> Result<(), String> {
+		check_writable(self.read_only)?;
 		let _lock = self.write_lock.lock();
 
 		let mut transaction = sp_database::Transaction::new();
@@ -225,6 +583,7 @@ impl<Block: BlockT> MappingDb<Block> {
 	}
 
 	pub fn write_hashes(&self, commitment: MappingCommitment<Block>) -> Result<(), String> {
+		check_writable(self.read_only)?;
 		let _lock = self.write_lock.lock();
 
 		let mut transaction = sp_database::Transaction::new();
@@ -235,11 +594,11 @@ impl<Block: BlockT> MappingDb<Block> {
 			&commitment.block_hash.encode(),
 		);
 
-		for (i, ethereum_transaction_hash) in commitment
-			.ethereum_transaction_hashes
-			.into_iter()
-			.enumerate()
-		{
+		let ethereum_transaction_hashes = match self.transaction_indexing {
+			TransactionIndexing::Full => commitment.ethereum_transaction_hashes,
+			TransactionIndexing::Off | TransactionIndexing::Lazy { .. } => Vec::new(),
+		};
+		for (i, ethereum_transaction_hash) in ethereum_transaction_hashes.into_iter().enumerate() {
 			let mut metadata = self.transaction_metadata(&ethereum_transaction_hash)?;
 			metadata.push(TransactionMetadata::<Block> {
 				block_hash: commitment.block_hash,
@@ -266,3 +625,129 @@ impl<Block: BlockT> MappingDb<Block> {
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	type Block = sp_runtime::testing::Block<sp_runtime::testing::ExtrinsicWrapper<u64>>;
+
+	fn entry(base_fee: u64) -> FeeHistoryEntry {
+		FeeHistoryEntry {
+			base_fee,
+			gas_used_ratio: 0.5f64.to_bits(),
+			rewards: vec![base_fee; 3],
+		}
+	}
+
+	fn block_numbers(backend: &Backend<Block>) -> Vec<u64> {
+		backend
+			.meta()
+			.fee_history()
+			.unwrap()
+			.into_iter()
+			.map(|(block_number, _)| block_number)
+			.collect()
+	}
+
+	#[test]
+	fn archives_raw_transactions_when_enabled() {
+		let block_hash = H256::repeat_byte(2);
+		let hash = H256::repeat_byte(1);
+		let raw = vec![0x02, 0xc0];
+
+		let backend = Backend::<Block>::new_in_memory(TransactionIndexing::Full, true);
+		assert_eq!(backend.mapping().is_archived(&block_hash), Ok(false));
+		backend
+			.mapping()
+			.write_raw_transactions(block_hash, vec![(hash, raw.clone())])
+			.unwrap();
+		assert_eq!(
+			backend.mapping().raw_transaction(&hash),
+			Ok(Some(raw.clone()))
+		);
+		assert_eq!(backend.mapping().is_archived(&block_hash), Ok(true));
+
+		let backend = Backend::<Block>::new_in_memory(TransactionIndexing::Full, false);
+		backend
+			.mapping()
+			.write_raw_transactions(block_hash, vec![(hash, raw)])
+			.unwrap();
+		assert_eq!(backend.mapping().raw_transaction(&hash), Ok(None));
+		// Nothing is left to archive.
+		assert_eq!(backend.mapping().is_archived(&block_hash), Ok(true));
+	}
+
+	#[test]
+	fn keeps_the_fee_history_of_the_latest_blocks() {
+		let backend = Backend::<Block>::new_in_memory(TransactionIndexing::Full, false);
+		for block_number in 1..=5 {
+			backend
+				.meta()
+				.write_fee_history(block_number, &entry(block_number), 3)
+				.unwrap();
+		}
+		assert_eq!(block_numbers(&backend), vec![3, 4, 5]);
+		assert_eq!(backend.meta().fee_history().unwrap()[0].1, entry(3));
+
+		// A lower limit prunes the oldest blocks on the next write.
+		backend.meta().write_fee_history(6, &entry(6), 2).unwrap();
+		assert_eq!(block_numbers(&backend), vec![5, 6]);
+	}
+
+	#[test]
+	fn removes_the_fee_history_of_retracted_blocks() {
+		let backend = Backend::<Block>::new_in_memory(TransactionIndexing::Full, false);
+		for block_number in 1..=5 {
+			backend
+				.meta()
+				.write_fee_history(block_number, &entry(block_number), 10)
+				.unwrap();
+		}
+		// A re-org to a shorter chain.
+		backend.meta().write_fee_history(4, &entry(40), 10).unwrap();
+		assert_eq!(block_numbers(&backend), vec![1, 2, 3, 4]);
+		assert_eq!(backend.meta().fee_history().unwrap()[3].1, entry(40));
+	}
+
+	#[test]
+	fn replicas_read_the_database_of_their_writer() {
+		let db: Arc<dyn Database<DbHash>> = Arc::new(sp_database::MemDb::default());
+		let writer = Backend::<Block>::from_database(
+			db.clone(),
+			None,
+			TransactionIndexing::Full,
+			false,
+			false,
+			false,
+		)
+		.unwrap();
+		let replica = Backend::<Block>::from_database(
+			db,
+			None,
+			TransactionIndexing::Full,
+			false,
+			false,
+			true,
+		)
+		.unwrap();
+		assert!(replica.is_read_only());
+
+		let commitment = || MappingCommitment::<Block> {
+			block_hash: H256::repeat_byte(1),
+			ethereum_block_hash: H256::repeat_byte(2),
+			ethereum_transaction_hashes: Vec::new(),
+		};
+		writer.mapping().write_hashes(commitment()).unwrap();
+		assert_eq!(
+			replica.mapping().block_hash(&H256::repeat_byte(2)),
+			Ok(Some(H256::repeat_byte(1)))
+		);
+		assert!(replica.mapping().write_hashes(commitment()).is_err());
+
+		// The caches are the ones of the writer.
+		writer.meta().write_fee_history(1, &entry(1), 10).unwrap();
+		replica.meta().write_fee_history(2, &entry(2), 10).unwrap();
+		assert_eq!(block_numbers(&replica), vec![1]);
+	}
+}
@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+	path::Path,
+	sync::{Arc, Weak},
+	thread,
+	time::Duration,
+};
+
+use sp_database::{error::DatabaseError, ColumnId, Transaction};
+
+use crate::Database;
+
+/// Interval between two catch-ups of a replica with the writes of its primary.
+const CATCH_UP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Read-only replica of a RocksDB database written by another process, its primary.
+///
+/// The replica only sees the writes of the primary up to its last catch-up, which runs every
+/// [`CATCH_UP_INTERVAL`] on a dedicated thread.
+pub struct DbReplica(Arc<kvdb_rocksdb::Database>);
+
+impl DbReplica {
+	/// Opens the database at `path` as a replica, keeping its state at `replica_path`.
+	pub fn open(path: &Path, replica_path: &Path, columns: u32) -> Result<Self, String> {
+		let mut db_config = kvdb_rocksdb::DatabaseConfig::with_columns(columns);
+		db_config.create_if_missing = false;
+		db_config.secondary = Some(
+			replica_path
+				.to_str()
+				.ok_or_else(|| "Invalid replica path".to_string())?
+				.into(),
+		);
+
+		let path = path
+			.to_str()
+			.ok_or_else(|| "Invalid database path".to_string())?;
+
+		let db = Arc::new(
+			kvdb_rocksdb::Database::open(&db_config, &path).map_err(|err| format!("{}", err))?,
+		);
+		spawn_catch_up(Arc::downgrade(&db))?;
+		Ok(Self(db))
+	}
+}
+
+/// Catches up with the primary until the replica is dropped.
+fn spawn_catch_up(db: Weak<kvdb_rocksdb::Database>) -> Result<(), String> {
+	thread::Builder::new()
+		.name("frontier-db-replica".into())
+		.spawn(move || {
+			while let Some(db) = db.upgrade() {
+				if let Err(err) = db.try_catch_up_with_primary() {
+					log::warn!(
+						target: "fc-db",
+						"Failed to catch up with the primary database: {}",
+						err
+					);
+				}
+				drop(db);
+				thread::sleep(CATCH_UP_INTERVAL);
+			}
+		})
+		.map(|_| ())
+		.map_err(|err| format!("{}", err))
+}
+
+impl<H: Clone + AsRef<[u8]>> Database<H> for DbReplica {
+	fn commit(&self, _transaction: Transaction<H>) -> Result<(), DatabaseError> {
+		Err(DatabaseError(Box::new(std::io::Error::new(
+			std::io::ErrorKind::PermissionDenied,
+			"read-only replica",
+		))))
+	}
+
+	fn get(&self, col: ColumnId, key: &[u8]) -> Option<Vec<u8>> {
+		match self.0.get(col, key) {
+			Ok(value) => value,
+			Err(e) => panic!("Critical database error: {:?}", e),
+		}
+	}
+}
@@ -0,0 +1,188 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use codec::{Decode, Encode};
+use sp_database::Transaction;
+
+use crate::{Database, DbHash};
+
+/// Version of the key space of the database written by this release.
+pub const CURRENT_VERSION: u32 = 1;
+
+/// Version of the databases written before the key space was versioned.
+const UNVERSIONED: u32 = 1;
+
+/// Forward migration of the database from a version to the next one.
+pub(crate) struct Migration {
+	/// Version migrated from.
+	pub from: u32,
+	/// Whether the migration rewrites a large part of the database, in which case it only runs
+	/// when large migrations are allowed.
+	pub large: bool,
+	/// Adds the changes of the migration to a transaction, committed with the new version.
+	pub migrate: fn(&dyn Database<DbHash>, &mut Transaction<DbHash>) -> Result<(), String>,
+}
+
+/// Migrations of the database, in order.
+const MIGRATIONS: &[Migration] = &[];
+
+/// Migrates the database to the current version.
+pub(crate) fn upgrade_database(
+	db: &dyn Database<DbHash>,
+	allow_large_migrations: bool,
+) -> Result<(), String> {
+	run_migrations(db, MIGRATIONS, CURRENT_VERSION, allow_large_migrations)
+}
+
+/// Checks that a database opened without migrating it, as a replica, is at the current version.
+pub(crate) fn check_database_version(db: &dyn Database<DbHash>) -> Result<(), String> {
+	let version = database_version(db)?.unwrap_or(UNVERSIONED);
+	if version != CURRENT_VERSION {
+		return Err(format!(
+			"Frontier database version {} differs from the supported version {}, and must be migrated by its writer",
+			version, CURRENT_VERSION
+		));
+	}
+	Ok(())
+}
+
+/// Version of the key space of the database.
+pub(crate) fn database_version(db: &dyn Database<DbHash>) -> Result<Option<u32>, String> {
+	match db.get(crate::columns::META, crate::static_keys::DATABASE_VERSION) {
+		Some(raw) => Ok(Some(
+			u32::decode(&mut &raw[..]).map_err(|e| format!("{:?}", e))?,
+		)),
+		None => Ok(None),
+	}
+}
+
+fn run_migrations(
+	db: &dyn Database<DbHash>,
+	migrations: &[Migration],
+	current_version: u32,
+	allow_large_migrations: bool,
+) -> Result<(), String> {
+	let stored_version = database_version(db)?;
+	let mut version = stored_version.unwrap_or(UNVERSIONED);
+	if version > current_version {
+		return Err(format!(
+			"Frontier database version {} is newer than the supported version {}",
+			version, current_version
+		));
+	}
+
+	while version < current_version {
+		let migration = migrations
+			.iter()
+			.find(|migration| migration.from == version)
+			.ok_or_else(|| {
+				format!(
+					"No migration of the Frontier database from version {}",
+					version
+				)
+			})?;
+		if migration.large && !allow_large_migrations {
+			return Err(format!(
+				"Frontier database version {} requires a large migration to version {}, which must be allowed explicitly",
+				version,
+				version + 1
+			));
+		}
+
+		log::info!(
+			target: "fc-db",
+			"Migrating the Frontier database from version {} to {}",
+			version,
+			version + 1
+		);
+		let mut transaction = Transaction::new();
+		(migration.migrate)(db, &mut transaction)?;
+		transaction.set(
+			crate::columns::META,
+			crate::static_keys::DATABASE_VERSION,
+			&(version + 1).encode(),
+		);
+		db.commit(transaction).map_err(|e| format!("{:?}", e))?;
+		version += 1;
+	}
+
+	if stored_version.is_none() {
+		let mut transaction = Transaction::new();
+		transaction.set(
+			crate::columns::META,
+			crate::static_keys::DATABASE_VERSION,
+			&version.encode(),
+		);
+		db.commit(transaction).map_err(|e| format!("{:?}", e))?;
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn bump_key(
+		_db: &dyn Database<DbHash>,
+		transaction: &mut Transaction<DbHash>,
+	) -> Result<(), String> {
+		transaction.set(crate::columns::META, b"MIGRATED", &true.encode());
+		Ok(())
+	}
+
+	const TEST_MIGRATIONS: &[Migration] = &[
+		Migration {
+			from: 1,
+			large: false,
+			migrate: bump_key,
+		},
+		Migration {
+			from: 2,
+			large: true,
+			migrate: bump_key,
+		},
+	];
+
+	#[test]
+	fn versions_unversioned_databases() {
+		let db = sp_database::MemDb::default();
+		run_migrations(&db, TEST_MIGRATIONS, 1, false).unwrap();
+		assert_eq!(database_version(&db), Ok(Some(1)));
+		assert!(db.get(crate::columns::META, b"MIGRATED").is_none());
+	}
+
+	#[test]
+	fn runs_large_migrations_only_when_allowed() {
+		let db = sp_database::MemDb::default();
+		assert!(run_migrations(&db, TEST_MIGRATIONS, 3, false).is_err());
+		// The small migration has been committed.
+		assert_eq!(database_version(&db), Ok(Some(2)));
+		assert!(db.get(crate::columns::META, b"MIGRATED").is_some());
+
+		run_migrations(&db, TEST_MIGRATIONS, 3, true).unwrap();
+		assert_eq!(database_version(&db), Ok(Some(3)));
+	}
+
+	#[test]
+	fn rejects_newer_databases() {
+		let db = sp_database::MemDb::default();
+		run_migrations(&db, TEST_MIGRATIONS, 2, false).unwrap();
+		assert!(run_migrations(&db, TEST_MIGRATIONS, 1, false).is_err());
+	}
+}
@@ -16,27 +16,57 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use std::{path::Path, sync::Arc};
+use std::{
+	path::{Path, PathBuf},
+	sync::Arc,
+};
 
 use crate::{Database, DatabaseSettings, DatabaseSource, DbHash};
 
-pub fn open_database(config: &DatabaseSettings) -> Result<Arc<dyn Database<DbHash>>, String> {
-	let db: Arc<dyn Database<DbHash>> = match &config.source {
-		DatabaseSource::ParityDb { path } => open_parity_db(path)?,
-		DatabaseSource::RocksDb { path, .. } => open_kvdb_rocksdb(path, true)?,
+/// Opens the database, returning it with its path.
+pub fn open_database(
+	config: &DatabaseSettings,
+) -> Result<(Arc<dyn Database<DbHash>>, PathBuf), String> {
+	if let Some(replica_path) = &config.replica_path {
+		let path = match &config.source {
+			DatabaseSource::RocksDb { path, .. } => path,
+			DatabaseSource::Auto { rocksdb_path, .. } => rocksdb_path,
+			_ => return Err("Replicas of the Frontier database require `rocksdb`".to_string()),
+		};
+		return Ok((open_kvdb_rocksdb_replica(path, replica_path)?, path.clone()));
+	}
+
+	let db = match &config.source {
+		DatabaseSource::ParityDb { path } => (open_parity_db(path)?, path.clone()),
+		DatabaseSource::RocksDb { path, .. } => (open_kvdb_rocksdb(path, true)?, path.clone()),
 		DatabaseSource::Auto {
 			paritydb_path,
 			rocksdb_path,
 			..
 		} => match open_kvdb_rocksdb(rocksdb_path, false) {
-			Ok(db) => db,
-			Err(_) => open_parity_db(paritydb_path)?,
+			Ok(db) => (db, rocksdb_path.clone()),
+			Err(_) => (open_parity_db(paritydb_path)?, paritydb_path.clone()),
 		},
-		_ => return Err("Missing feature flags `parity-db`".to_string()),
+		_ => return Err("Supported db sources: `rocksdb` | `paritydb` | `auto`".to_string()),
 	};
 	Ok(db)
 }
 
+/// Total size of the files of a directory, in bytes.
+pub fn directory_size(path: &Path) -> std::io::Result<u64> {
+	let mut size = 0;
+	for entry in std::fs::read_dir(path)? {
+		let entry = entry?;
+		let metadata = entry.metadata()?;
+		size += if metadata.is_dir() {
+			directory_size(&entry.path())?
+		} else {
+			metadata.len()
+		};
+	}
+	Ok(size)
+}
+
 #[cfg(feature = "kvdb-rocksdb")]
 fn open_kvdb_rocksdb(path: &Path, create: bool) -> Result<Arc<dyn Database<DbHash>>, String> {
 	let mut db_config = kvdb_rocksdb::DatabaseConfig::with_columns(crate::columns::NUM_COLUMNS);
@@ -55,6 +85,23 @@ fn open_kvdb_rocksdb(_path: &Path, _create: bool) -> Result<Arc<dyn Database<DbH
 	Err("Missing feature flags `kvdb-rocksdb`".to_string())
 }
 
+#[cfg(feature = "kvdb-rocksdb")]
+fn open_kvdb_rocksdb_replica(
+	path: &Path,
+	replica_path: &Path,
+) -> Result<Arc<dyn Database<DbHash>>, String> {
+	let db = crate::replica::DbReplica::open(path, replica_path, crate::columns::NUM_COLUMNS)?;
+	Ok(Arc::new(db))
+}
+
+#[cfg(not(feature = "kvdb-rocksdb"))]
+fn open_kvdb_rocksdb_replica(
+	_path: &Path,
+	_replica_path: &Path,
+) -> Result<Arc<dyn Database<DbHash>>, String> {
+	Err("Missing feature flags `kvdb-rocksdb`".to_string())
+}
+
 #[cfg(feature = "parity-db")]
 fn open_parity_db(path: &Path) -> Result<Arc<dyn Database<DbHash>>, String> {
 	let config = parity_db::Options::with_columns(path, crate::columns::NUM_COLUMNS as u8);
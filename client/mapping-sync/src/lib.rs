@@ -22,24 +22,48 @@ mod worker;
 
 pub use worker::{MappingSyncWorker, SyncStrategy};
 
+use ethereum::EnvelopedEncodable;
+use fc_rpc::{frontier_backend_client, OverrideHandle};
 use fp_consensus::FindLogError;
 use fp_rpc::EthereumRuntimeRPCApi;
-use sc_client_api::BlockOf;
+use sc_client_api::{
+	backend::{Backend, StateBackend, StorageProvider},
+	BlockOf,
+};
 use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
+use sp_core::H256;
 use sp_runtime::{
 	generic::BlockId,
-	traits::{Block as BlockT, Header as HeaderT, Zero},
+	traits::{BlakeTwo256, Block as BlockT, Header as HeaderT, Zero},
 };
 
-pub fn sync_block<Block: BlockT>(
+pub fn sync_block<Block, C, BE>(
+	client: &C,
+	overrides: &OverrideHandle<Block>,
 	backend: &fc_db::Backend<Block>,
 	header: &Block::Header,
-) -> Result<(), String> {
+) -> Result<(), String>
+where
+	Block: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: StorageProvider<Block, BE> + Send + Sync + 'static,
+	BE: Backend<Block> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+{
 	match fp_consensus::find_log(header.digest()) {
 		Ok(log) => {
 			let post_hashes = log.into_hashes();
 
+			// The raw transactions are archived before the block is marked as synced, so that
+			// a failed archive is retried.
+			archive_raw_transactions::<Block, C, BE>(
+				client,
+				overrides,
+				backend,
+				header,
+				!post_hashes.transaction_hashes.is_empty(),
+			)?;
+
 			let mapping_commitment = fc_db::MappingCommitment {
 				block_hash: header.hash(),
 				ethereum_block_hash: post_hashes.block_hash,
@@ -50,6 +74,7 @@ pub fn sync_block<Block: BlockT>(
 			Ok(())
 		}
 		Err(FindLogError::NotFound) => {
+			archive_raw_transactions::<Block, C, BE>(client, overrides, backend, header, false)?;
 			backend.mapping().write_none(header.hash())?;
 
 			Ok(())
@@ -58,6 +83,53 @@ pub fn sync_block<Block: BlockT>(
 	}
 }
 
+/// Archives the raw payloads of the Ethereum transactions of a block, if the backend archives
+/// them and they aren't archived yet. A block without transactions is marked as archived.
+///
+/// Decoding only accepts canonical encodings, so the envelope encoding of an included
+/// transaction is the exact payload signed by its sender.
+fn archive_raw_transactions<Block, C, BE>(
+	client: &C,
+	overrides: &OverrideHandle<Block>,
+	backend: &fc_db::Backend<Block>,
+	header: &Block::Header,
+	has_transactions: bool,
+) -> Result<(), String>
+where
+	Block: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: StorageProvider<Block, BE> + Send + Sync + 'static,
+	BE: Backend<Block> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+{
+	let mapping = backend.mapping();
+	if mapping.is_archived(&header.hash())? {
+		return Ok(());
+	}
+
+	let mut transactions = Vec::new();
+	if has_transactions {
+		let id = BlockId::Hash(header.hash());
+		let schema = frontier_backend_client::onchain_storage_schema::<Block, C, BE>(client, id);
+		let block = overrides
+			.schemas
+			.get(&schema)
+			.unwrap_or(&overrides.fallback)
+			.current_block(&id)
+			.ok_or_else(|| "Ethereum block not found".to_string())?;
+		transactions = block
+			.transactions
+			.iter()
+			.map(|transaction| {
+				(
+					transaction.hash(),
+					EnvelopedEncodable::encode(transaction).to_vec(),
+				)
+			})
+			.collect();
+	}
+	mapping.write_raw_transactions(header.hash(), transactions)
+}
+
 pub fn sync_genesis_block<Block: BlockT, C>(
 	client: &C,
 	backend: &fc_db::Backend<Block>,
@@ -69,6 +141,11 @@ where
 {
 	let id = BlockId::Hash(header.hash());
 
+	// The genesis block has no transactions to archive.
+	backend
+		.mapping()
+		.write_raw_transactions(header.hash(), Vec::new())?;
+
 	let has_api = client
 		.runtime_api()
 		.has_api::<dyn EthereumRuntimeRPCApi<Block>>(&id)
@@ -96,17 +173,22 @@ where
 	Ok(())
 }
 
-pub fn sync_one_block<Block: BlockT, C, B>(
+pub fn sync_one_block<Block, C, B, BE>(
 	client: &C,
+	overrides: &OverrideHandle<Block>,
 	substrate_backend: &B,
 	frontier_backend: &fc_db::Backend<Block>,
 	sync_from: <Block::Header as HeaderT>::Number,
 	strategy: SyncStrategy,
 ) -> Result<bool, String>
 where
+	Block: BlockT<Hash = H256> + Send + Sync + 'static,
 	C: ProvideRuntimeApi<Block> + Send + Sync + HeaderBackend<Block> + BlockOf,
+	C: StorageProvider<Block, BE> + 'static,
 	C::Api: EthereumRuntimeRPCApi<Block>,
 	B: sp_blockchain::HeaderBackend<Block> + sp_blockchain::Backend<Block>,
+	BE: Backend<Block> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
 {
 	let mut current_syncing_tips = frontier_backend.meta().current_syncing_tips()?;
 
@@ -150,7 +232,7 @@ where
 		{
 			return Ok(false);
 		}
-		sync_block(frontier_backend, &operating_header)?;
+		sync_block::<Block, C, BE>(client, overrides, frontier_backend, &operating_header)?;
 
 		current_syncing_tips.push(*operating_header.parent_hash());
 		frontier_backend
@@ -160,8 +242,9 @@ where
 	}
 }
 
-pub fn sync_blocks<Block: BlockT, C, B>(
+pub fn sync_blocks<Block, C, B, BE>(
 	client: &C,
+	overrides: &OverrideHandle<Block>,
 	substrate_backend: &B,
 	frontier_backend: &fc_db::Backend<Block>,
 	limit: usize,
@@ -169,16 +252,21 @@ pub fn sync_blocks<Block: BlockT, C, B>(
 	strategy: SyncStrategy,
 ) -> Result<bool, String>
 where
+	Block: BlockT<Hash = H256> + Send + Sync + 'static,
 	C: ProvideRuntimeApi<Block> + Send + Sync + HeaderBackend<Block> + BlockOf,
+	C: StorageProvider<Block, BE> + 'static,
 	C::Api: EthereumRuntimeRPCApi<Block>,
 	B: sp_blockchain::HeaderBackend<Block> + sp_blockchain::Backend<Block>,
+	BE: Backend<Block> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
 {
 	let mut synced_any = false;
 
 	for _ in 0..limit {
 		synced_any = synced_any
-			|| sync_one_block(
+			|| sync_one_block::<Block, C, B, BE>(
 				client,
+				overrides,
 				substrate_backend,
 				frontier_backend,
 				sync_from,
@@ -198,7 +286,10 @@ pub fn fetch_header<Block: BlockT, B>(
 where
 	B: sp_blockchain::HeaderBackend<Block> + sp_blockchain::Backend<Block>,
 {
-	if frontier_backend.mapping().is_synced(&checking_tip)? {
+	// The blocks mapped on import still have their raw transactions to archive.
+	if frontier_backend.mapping().is_synced(&checking_tip)?
+		&& frontier_backend.mapping().is_archived(&checking_tip)?
+	{
 		return Ok(None);
 	}
 
@@ -16,6 +16,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use fc_rpc::OverrideHandle;
 use fp_rpc::EthereumRuntimeRPCApi;
 use futures::{
 	prelude::*,
@@ -23,10 +24,14 @@ use futures::{
 };
 use futures_timer::Delay;
 use log::debug;
-use sc_client_api::{BlockOf, ImportNotifications};
+use sc_client_api::{
+	backend::{StateBackend, StorageProvider},
+	BlockOf, ImportNotifications,
+};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
-use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, Block as BlockT, Header as HeaderT};
 use std::{pin::Pin, sync::Arc, time::Duration};
 
 #[derive(PartialEq, Copy, Clone)]
@@ -43,6 +48,7 @@ pub struct MappingSyncWorker<Block: BlockT, C, B> {
 	client: Arc<C>,
 	substrate_backend: Arc<B>,
 	frontier_backend: Arc<fc_db::Backend<Block>>,
+	overrides: Arc<OverrideHandle<Block>>,
 
 	have_next: bool,
 	retry_times: usize,
@@ -59,6 +65,7 @@ impl<Block: BlockT, C, B> MappingSyncWorker<Block, C, B> {
 		client: Arc<C>,
 		substrate_backend: Arc<B>,
 		frontier_backend: Arc<fc_db::Backend<Block>>,
+		overrides: Arc<OverrideHandle<Block>>,
 		retry_times: usize,
 		sync_from: <Block::Header as HeaderT>::Number,
 		strategy: SyncStrategy,
@@ -71,6 +78,7 @@ impl<Block: BlockT, C, B> MappingSyncWorker<Block, C, B> {
 			client,
 			substrate_backend,
 			frontier_backend,
+			overrides,
 
 			have_next: true,
 			retry_times,
@@ -80,11 +88,14 @@ impl<Block: BlockT, C, B> MappingSyncWorker<Block, C, B> {
 	}
 }
 
-impl<Block: BlockT, C, B> Stream for MappingSyncWorker<Block, C, B>
+impl<Block, C, B> Stream for MappingSyncWorker<Block, C, B>
 where
+	Block: BlockT<Hash = H256> + Send + Sync + 'static,
 	C: ProvideRuntimeApi<Block> + Send + Sync + HeaderBackend<Block> + BlockOf,
+	C: StorageProvider<Block, B> + 'static,
 	C::Api: EthereumRuntimeRPCApi<Block>,
-	B: sc_client_api::Backend<Block>,
+	B: sc_client_api::Backend<Block> + 'static,
+	B::State: StateBackend<BlakeTwo256>,
 {
 	type Item = ();
 
@@ -118,8 +129,9 @@ where
 		if fire {
 			self.inner_delay = None;
 
-			match crate::sync_blocks(
+			match crate::sync_blocks::<Block, C, _, B>(
 				self.client.as_ref(),
+				self.overrides.as_ref(),
 				self.substrate_backend.blockchain(),
 				self.frontier_backend.as_ref(),
 				self.retry_times,
@@ -0,0 +1,54 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+
+//! Bundler rpc interface.
+
+use ethereum_types::{H160, H256};
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_derive::rpc;
+
+use crate::types::{UserOperation, UserOperationGasEstimate, UserOperationReceipt};
+
+pub use rpc_impl_BundlerApi::gen_server::BundlerApi as BundlerApiServer;
+
+/// Bundler rpc interface.
+///
+/// The ERC-4337 methods of a bundler of the user operations of a single EntryPoint, which
+/// the node includes in its own `handleOps` transactions.
+#[rpc(server)]
+pub trait BundlerApi {
+	/// Returns the EntryPoint addresses supported by the node.
+	#[rpc(name = "eth_supportedEntryPoints")]
+	fn supported_entry_points(&self) -> Result<Vec<H160>>;
+
+	/// Validates a user operation and submits it to the pool in a `handleOps` transaction,
+	/// returning the hash of the operation.
+	#[rpc(name = "eth_sendUserOperation")]
+	fn send_user_operation(&self, _: UserOperation, _: H160) -> BoxFuture<Result<H256>>;
+
+	/// Estimates the gas limits of a user operation.
+	#[rpc(name = "eth_estimateUserOperationGas")]
+	fn estimate_user_operation_gas(
+		&self,
+		_: UserOperation,
+		_: H160,
+	) -> BoxFuture<Result<UserOperationGasEstimate>>;
+
+	/// Returns the receipt of a user operation included in the latest blocks.
+	#[rpc(name = "eth_getUserOperationReceipt")]
+	fn user_operation_receipt(&self, _: H256) -> BoxFuture<Result<Option<UserOperationReceipt>>>;
+}
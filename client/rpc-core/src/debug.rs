@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Debug rpc interface.
+
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+
+use crate::types::{BlockNumber, Bytes};
+
+pub use rpc_impl_DebugApi::gen_server::DebugApi as DebugApiServer;
+
+/// Debug rpc interface, returning the RLP encodings of the Ethereum blocks, for cross-client
+/// verification tools and bridge provers.
+#[rpc(server)]
+pub trait DebugApi {
+	/// Returns the RLP encoded header of a block.
+	#[rpc(name = "debug_getRawHeader")]
+	fn raw_header(&self, number: BlockNumber) -> Result<Option<Bytes>>;
+
+	/// Returns the RLP encoded block, with its transactions and ommers.
+	#[rpc(name = "debug_getRawBlock")]
+	fn raw_block(&self, number: BlockNumber) -> Result<Option<Bytes>>;
+
+	/// Returns the EIP-2718 encoded receipts of the transactions of a block.
+	#[rpc(name = "debug_getRawReceipts")]
+	fn raw_receipts(&self, number: BlockNumber) -> Result<Option<Vec<Bytes>>>;
+}
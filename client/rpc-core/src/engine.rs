@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Engine rpc interface.
+
+use ethereum_types::H256;
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_derive::rpc;
+
+use crate::types::{
+	ExecutionPayload, ForkchoiceState, ForkchoiceUpdatedResult, PayloadAttributes, PayloadBody,
+	PayloadStatus,
+};
+
+pub use rpc_impl_EngineApi::gen_server::EngineApi as EngineApiServer;
+
+/// Engine rpc interface.
+///
+/// A subset of the Ethereum Engine API. Fork choice and finality stay driven by the
+/// Substrate consensus, so these methods report how the given Ethereum blocks relate to
+/// the Substrate chain rather than altering it.
+#[rpc(server)]
+pub trait EngineApi {
+	/// Returns the Engine API methods supported by the node.
+	#[rpc(name = "engine_exchangeCapabilities")]
+	fn exchange_capabilities(&self, _: Vec<String>) -> Result<Vec<String>>;
+
+	/// Checks the given fork choice state against the Substrate chain.
+	#[rpc(name = "engine_forkchoiceUpdatedV1")]
+	fn fork_choice_updated(
+		&self,
+		_: ForkchoiceState,
+		_: Option<PayloadAttributes>,
+	) -> BoxFuture<Result<ForkchoiceUpdatedResult>>;
+
+	/// Checks the given payload against the imported blocks.
+	#[rpc(name = "engine_newPayloadV1")]
+	fn new_payload(&self, _: ExecutionPayload) -> BoxFuture<Result<PayloadStatus>>;
+
+	/// Returns the bodies of the given blocks.
+	#[rpc(name = "engine_getPayloadBodiesByHashV1")]
+	fn payload_bodies_by_hash(&self, _: Vec<H256>) -> BoxFuture<Result<Vec<Option<PayloadBody>>>>;
+}
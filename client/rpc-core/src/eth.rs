@@ -83,11 +83,11 @@ pub trait EthApi {
 
 	/// Returns the number of uncles in a block with given hash.
 	#[rpc(name = "eth_getUncleCountByBlockHash")]
-	fn block_uncles_count_by_hash(&self, hash: H256) -> Result<U256>;
+	fn block_uncles_count_by_hash(&self, hash: H256) -> Result<Option<U256>>;
 
 	/// Returns the number of uncles in a block with given block number.
 	#[rpc(name = "eth_getUncleCountByBlockNumber")]
-	fn block_uncles_count_by_number(&self, number: BlockNumber) -> Result<U256>;
+	fn block_uncles_count_by_number(&self, number: BlockNumber) -> Result<Option<U256>>;
 
 	/// Returns an uncles at given block and index.
 	#[rpc(name = "eth_getUncleByBlockHashAndIndex")]
@@ -109,6 +109,10 @@ pub trait EthApi {
 	#[rpc(name = "eth_getTransactionByHash")]
 	fn transaction_by_hash(&self, hash: H256) -> BoxFuture<Result<Option<Transaction>>>;
 
+	/// Get the raw payload of a transaction by its hash, if archived by the node.
+	#[rpc(name = "eth_getRawTransactionByHash")]
+	fn raw_transaction_by_hash(&self, hash: H256) -> Result<Option<Bytes>>;
+
 	/// Returns transaction at given block hash and index.
 	#[rpc(name = "eth_getTransactionByBlockHashAndIndex")]
 	fn transaction_by_block_hash_and_index(
@@ -155,7 +159,7 @@ pub trait EthApi {
 
 	/// Call contract, returning the output data.
 	#[rpc(name = "eth_call")]
-	fn call(&self, request: CallRequest, number: Option<BlockNumber>) -> Result<Bytes>;
+	fn call(&self, request: CallRequest, number: Option<BlockNumber>) -> BoxFuture<Result<Bytes>>;
 
 	/// Estimate gas needed for execution of given contract.
 	#[rpc(name = "eth_estimateGas")]
@@ -165,6 +169,38 @@ pub trait EthApi {
 		number: Option<BlockNumber>,
 	) -> BoxFuture<Result<U256>>;
 
+	/// Creates the access list of a call, listing the accounts and storage keys it accesses,
+	/// except the ones of the sender, of the called or created contract and of the
+	/// precompiles. Returns it with the gas used by the call with the access list.
+	#[rpc(name = "eth_createAccessList")]
+	fn create_access_list(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+	) -> BoxFuture<Result<AccessListResult>>;
+
+	/// Simulate calls, executed in order on top of each other, with optional state and block
+	/// overrides. Returns the output data, logs and gas used of each call.
+	#[rpc(name = "eth_simulateV1")]
+	fn simulate_v1(
+		&self,
+		payload: SimulatePayload,
+		number: Option<BlockNumber>,
+	) -> BoxFuture<Result<Vec<SimulatedBlock>>>;
+
+	/// Traces a call at the given block, with optional state and block overrides, without
+	/// sending a transaction.
+	///
+	/// Only the `callTracer` with `onlyTopCall` is supported, as the runtime doesn't report the
+	/// internal calls, so the trace is the frame of the call itself.
+	#[rpc(name = "debug_traceCall")]
+	fn trace_call(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+		config: Option<TraceCallConfig>,
+	) -> BoxFuture<Result<CallFrame>>;
+
 	// ########################################################################
 	// Fee
 	// ########################################################################
@@ -223,6 +259,10 @@ pub trait EthApi {
 	/// Sends signed transaction, returning its hash.
 	#[rpc(name = "eth_sendRawTransaction")]
 	fn send_raw_transaction(&self, bytes: Bytes) -> BoxFuture<Result<H256>>;
+
+	/// Signs `data` with the key of `address`, as an EIP-191 message.
+	#[rpc(name = "eth_sign")]
+	fn sign(&self, address: H160, data: Bytes) -> Result<Bytes>;
 }
 
 /// Eth filters rpc api (polling).
@@ -244,7 +284,8 @@ pub trait EthFilterApi {
 	#[rpc(name = "eth_getFilterChanges")]
 	fn filter_changes(&self, _: Index) -> BoxFuture<Result<FilterChanges>>;
 
-	/// Returns all logs matching given filter (in a range 'from' - 'to').
+	/// Returns all logs matching given filter (in a range 'from' - 'to'), regardless of the
+	/// polls of `eth_getFilterChanges`, with the limits of `eth_getLogs`.
 	#[rpc(name = "eth_getFilterLogs")]
 	fn filter_logs(&self, _: Index) -> BoxFuture<Result<Vec<Log>>>;
 
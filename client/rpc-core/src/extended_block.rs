@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Extended block rpc interface.
+
+use ethereum_types::H256;
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_derive::rpc;
+
+use crate::types::{BlockNumber, ExtendedBlock};
+
+pub use rpc_impl_ExtendedBlockApi::gen_server::ExtendedBlockApi as ExtendedBlockApiServer;
+
+/// Extended block rpc interface, returning a block along with the receipts of its
+/// transactions in a single response, for indexers.
+///
+/// The traces of the transactions aren't included, as Frontier doesn't trace the EVM
+/// execution: indexers still trace the transactions through a tracing node.
+#[rpc(server)]
+pub trait ExtendedBlockApi {
+	/// Returns the block with the given hash, with its full transactions and their receipts.
+	#[rpc(name = "frontier_getExtendedBlockByHash")]
+	fn extended_block_by_hash(&self, hash: H256) -> BoxFuture<Result<Option<ExtendedBlock>>>;
+
+	/// Returns the block with the given number, with its full transactions and their receipts.
+	/// The pending block has no receipts, so it is never returned, nor are the blocks after the
+	/// best one.
+	#[rpc(name = "frontier_getExtendedBlockByNumber")]
+	fn extended_block_by_number(
+		&self,
+		number: BlockNumber,
+	) -> BoxFuture<Result<Option<ExtendedBlock>>>;
+}
@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Frontier rpc interface.
+
+use ethereum_types::{H160, H256, U256};
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+
+use crate::types::{
+	BlockNumber, Bytes, DroppedTransactionStatus, GasBreakdown, Precompile, ReceiptProof,
+	TransactionValidation,
+};
+
+pub use rpc_impl_FrontierApi::gen_server::FrontierApi as FrontierApiServer;
+
+/// Frontier rpc interface, exposing helpers computed by the runtime.
+#[rpc(server)]
+pub trait FrontierApi {
+	/// Returns the address a contract deployed with CREATE2 by `deployer` would get, given
+	/// the salt and the keccak hash of the init code.
+	#[rpc(name = "frontier_computeCreate2Address")]
+	fn compute_create2_address(
+		&self,
+		deployer: H160,
+		salt: H256,
+		init_code_hash: H256,
+	) -> Result<H160>;
+
+	/// Runs the checks of the pool validation on a signed transaction, in its raw network
+	/// encoding, and returns the exact failure cause if any.
+	#[rpc(name = "frontier_validateTransaction")]
+	fn validate_transaction(&self, bytes: Bytes) -> Result<TransactionValidation>;
+
+	/// Returns why a transaction left the pool without being included, if it was recently
+	/// replaced, invalidated, dropped or retracted by a re-org.
+	#[rpc(name = "frontier_transactionStatus")]
+	fn transaction_status(&self, hash: H256) -> Result<Option<DroppedTransactionStatus>>;
+
+	/// Returns the Merkle Patricia proof of inclusion of the receipt of a transaction in the
+	/// receipts root of its block.
+	#[rpc(name = "frontier_getTransactionReceiptProof")]
+	fn transaction_receipt_proof(&self, hash: H256) -> Result<Option<ReceiptProof>>;
+
+	/// Returns how the gas used by a transaction splits between its intrinsic gas and its
+	/// execution, with the gas refunded to it. The refund is found by replaying the block up to
+	/// the transaction.
+	#[rpc(name = "frontier_gasBreakdown")]
+	fn gas_breakdown(&self, hash: H256) -> Result<Option<GasBreakdown>>;
+
+	/// Returns the active precompiles of the runtime at the best block.
+	#[rpc(name = "frontier_listPrecompiles")]
+	fn list_precompiles(&self) -> Result<Vec<Precompile>>;
+
+	/// Returns the balances of many accounts at a block, in order, as `eth_getBalance`. The
+	/// `pending` block is the best block, and an unknown block is an error. At most 10000
+	/// accounts are queried at once.
+	#[rpc(name = "frontier_getBalances")]
+	fn balances(&self, addresses: Vec<H160>, number: Option<BlockNumber>) -> Result<Vec<U256>>;
+
+	/// Returns the codes of many accounts at a block, in order, as `eth_getCode`. The
+	/// `pending` block is the best block, and an unknown block is an error. At most 128
+	/// accounts are queried at once.
+	#[rpc(name = "frontier_getCodes")]
+	fn codes(&self, addresses: Vec<H160>, number: Option<BlockNumber>) -> Result<Vec<Bytes>>;
+}
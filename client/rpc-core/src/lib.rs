@@ -18,14 +18,32 @@
 
 pub mod types;
 
+mod bundler;
+mod debug;
+mod engine;
 mod eth;
 mod eth_pubsub;
+mod extended_block;
+mod frontier;
+mod metadata;
 mod net;
+mod state_export;
+mod stats;
+mod trace;
 mod web3;
 
 pub use self::{
+	bundler::BundlerApi,
+	debug::DebugApi,
+	engine::EngineApi,
 	eth::{EthApi, EthFilterApi},
 	eth_pubsub::EthPubSubApi,
+	extended_block::ExtendedBlockApi,
+	frontier::FrontierApi,
+	metadata::MetadataApi,
 	net::NetApi,
+	state_export::StateExportApi,
+	stats::StatsApi,
+	trace::TraceApi,
 	web3::Web3Api,
 };
@@ -0,0 +1,39 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Contract metadata rpc interface.
+
+use ethereum_types::H160;
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+
+use crate::types::{BlockNumber, ContractMetadata};
+
+pub use rpc_impl_MetadataApi::gen_server::MetadataApi as MetadataApiServer;
+
+/// Contract metadata rpc interface, for runtimes including the contract metadata registry.
+#[rpc(server)]
+pub trait MetadataApi {
+	/// Returns the metadata registered for a contract by its deployer, if any.
+	#[rpc(name = "frontier_contractMetadata")]
+	fn contract_metadata(
+		&self,
+		address: H160,
+		number: Option<BlockNumber>,
+	) -> Result<Option<ContractMetadata>>;
+}
@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! EVM state export rpc interface.
+
+use ethereum_types::H160;
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+
+use crate::types::{BlockNumber, Bytes, EvmAccountRange, EvmStorageRange};
+
+pub use rpc_impl_StateExportApi::gen_server::StateExportApi as StateExportApiServer;
+
+/// EVM state export rpc interface, serving the EVM state by ranges with their storage proofs,
+/// so that it can be pulled without an archive node.
+///
+/// The ranges follow the order of the storage keys, and each range is read after the storage
+/// key returned as `next` by the previous one.
+#[rpc(server)]
+pub trait StateExportApi {
+	/// Returns up to `limit` EVM contracts with their code, after the `after` storage key.
+	#[rpc(name = "frontier_getEvmAccountRange")]
+	fn evm_account_range(
+		&self,
+		number: Option<BlockNumber>,
+		after: Option<Bytes>,
+		limit: u32,
+	) -> Result<EvmAccountRange>;
+
+	/// Returns up to `limit` storage slots of the EVM contract at `address`, after the `after`
+	/// storage key.
+	#[rpc(name = "frontier_getEvmStorageRange")]
+	fn evm_storage_range(
+		&self,
+		number: Option<BlockNumber>,
+		address: H160,
+		after: Option<Bytes>,
+		limit: u32,
+	) -> Result<EvmStorageRange>;
+}
@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Frontier statistics rpc interface.
+
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_derive::rpc;
+
+use crate::types::FrontierStats;
+
+pub use rpc_impl_StatsApi::gen_server::StatsApi as StatsApiServer;
+
+/// Frontier statistics rpc interface, for node operators.
+#[rpc(server)]
+pub trait StatsApi {
+	/// Returns the mapping sync progress, the database size, the block data cache usage, the
+	/// filter pool size and the number of pending Ethereum transactions.
+	#[rpc(name = "frontier_stats")]
+	fn stats(&self) -> BoxFuture<Result<FrontierStats>>;
+}
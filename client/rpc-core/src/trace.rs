@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Trace rpc interface.
+
+use ethereum_types::H256;
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+
+use crate::types::{PrestateTrace, TraceConfig, TraceResults};
+
+pub use rpc_impl_TraceApi::gen_server::TraceApi as TraceApiServer;
+
+/// Trace rpc interface, following the OpenEthereum `trace` namespace.
+#[rpc(server)]
+pub trait TraceApi {
+	/// Replays a transaction and returns the requested traces of it.
+	///
+	/// Only the `stateDiff` trace type is supported.
+	#[rpc(name = "trace_replayTransaction")]
+	fn replay_transaction(&self, hash: H256, trace_types: Vec<String>) -> Result<TraceResults>;
+
+	/// Replays a transaction and returns the trace of the given go-ethereum tracer.
+	///
+	/// Only the `prestateTracer` is supported, in its default and diff modes.
+	#[rpc(name = "debug_traceTransaction")]
+	fn trace_transaction(&self, hash: H256, config: Option<TraceConfig>) -> Result<PrestateTrace>;
+}
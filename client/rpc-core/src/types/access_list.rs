@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use ethereum::AccessListItem;
+use ethereum_types::U256;
+use serde::Serialize;
+
+/// Access list created for a call, with the gas it uses along with the access list.
+#[derive(Debug, Default, PartialEq, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListResult {
+	/// Accounts and storage keys accessed by the call.
+	pub access_list: Vec<AccessListItem>,
+	/// Gas used by the call with the access list.
+	pub gas_used: U256,
+	/// Error of the call, if it failed.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
@@ -18,8 +18,8 @@
 
 use std::{collections::BTreeMap, ops::Deref};
 
-use crate::types::{Bytes, Transaction};
-use ethereum_types::{Bloom as H2048, H160, H256, U256};
+use crate::types::{Bytes, Receipt, Transaction};
+use ethereum_types::{Bloom as H2048, H160, H256, H64, U256};
 use serde::{ser::Error, Serialize, Serializer};
 
 /// Block Transactions
@@ -98,9 +98,13 @@ pub struct Header {
 	pub timestamp: U256,
 	/// Difficulty
 	pub difficulty: U256,
+	/// Mix hash
+	pub mix_hash: H256,
+	/// Nonce
+	pub nonce: H64,
 	/// Seal fields
 	pub seal_fields: Vec<Bytes>,
-	/// Size in bytes
+	/// Size in bytes of the whole block
 	pub size: Option<U256>,
 }
 
@@ -110,6 +114,15 @@ pub type RichBlock = Rich<Block>;
 /// Header representation with additional info.
 pub type RichHeader = Rich<Header>;
 
+/// Block with its full transactions and their receipts, in the transactions order.
+#[derive(Debug, Serialize)]
+pub struct ExtendedBlock {
+	/// Block, with its full transactions
+	pub block: RichBlock,
+	/// Receipts of the transactions
+	pub receipts: Vec<Receipt>,
+}
+
 /// Value representation with additional info
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Rich<T> {
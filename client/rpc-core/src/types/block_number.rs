@@ -41,6 +41,10 @@ pub enum BlockNumber {
 	Earliest,
 	/// Pending block (being mined)
 	Pending,
+	/// Most recent finalized block
+	Finalized,
+	/// Most recent safe block, which is the finalized block, as blocks are finalized by GRANDPA
+	Safe,
 }
 
 impl Default for BlockNumber {
@@ -85,6 +89,8 @@ impl Serialize for BlockNumber {
 			BlockNumber::Latest => serializer.serialize_str("latest"),
 			BlockNumber::Earliest => serializer.serialize_str("earliest"),
 			BlockNumber::Pending => serializer.serialize_str("pending"),
+			BlockNumber::Finalized => serializer.serialize_str("finalized"),
+			BlockNumber::Safe => serializer.serialize_str("safe"),
 		}
 	}
 }
@@ -97,7 +103,7 @@ impl<'a> Visitor<'a> for BlockNumberVisitor {
 	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
 		write!(
 			formatter,
-			"a block number or 'latest', 'earliest' or 'pending'"
+			"a block number or 'latest', 'earliest', 'pending', 'finalized' or 'safe'"
 		)
 	}
 
@@ -162,6 +168,8 @@ impl<'a> Visitor<'a> for BlockNumberVisitor {
 			"latest" => Ok(BlockNumber::Latest),
 			"earliest" => Ok(BlockNumber::Earliest),
 			"pending" => Ok(BlockNumber::Pending),
+			"finalized" => Ok(BlockNumber::Finalized),
+			"safe" => Ok(BlockNumber::Safe),
 			_ if value.starts_with("0x") => u64::from_str_radix(&value[2..], 16)
 				.map(BlockNumber::Num)
 				.map_err(|e| Error::custom(format!("Invalid block number: {}", e))),
@@ -207,4 +215,21 @@ mod tests {
 		assert_eq!(match_block_number(bn_hex).unwrap(), 69);
 		assert_eq!(match_block_number(bn_u64).unwrap(), 420);
 	}
+
+	#[test]
+	fn block_tag_serde() {
+		for (tag, block_number) in [
+			(r#""latest""#, BlockNumber::Latest),
+			(r#""earliest""#, BlockNumber::Earliest),
+			(r#""pending""#, BlockNumber::Pending),
+			(r#""finalized""#, BlockNumber::Finalized),
+			(r#""safe""#, BlockNumber::Safe),
+		] {
+			assert_eq!(
+				serde_json::from_str::<BlockNumber>(tag).unwrap(),
+				block_number
+			);
+			assert_eq!(serde_json::to_string(&block_number).unwrap(), tag);
+		}
+	}
 }
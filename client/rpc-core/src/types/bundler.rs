@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+
+use ethereum_types::{H160, H256, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Bytes, Log, Receipt};
+
+/// ERC-4337 user operation, as handled by the EntryPoint v0.6.
+#[derive(Debug, Default, PartialEq, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperation {
+	/// Account making the operation.
+	pub sender: H160,
+	/// Anti-replay nonce of the account.
+	pub nonce: U256,
+	/// Factory and calldata deploying the account, empty once it is deployed.
+	pub init_code: Bytes,
+	/// Calldata of the call to the account.
+	pub call_data: Bytes,
+	/// Gas of the call to the account.
+	pub call_gas_limit: U256,
+	/// Gas of the validation of the operation.
+	pub verification_gas_limit: U256,
+	/// Gas paid for the calldata and the overhead of the bundle.
+	pub pre_verification_gas: U256,
+	/// Maximum fee per gas, as in EIP-1559.
+	pub max_fee_per_gas: U256,
+	/// Maximum priority fee per gas, as in EIP-1559.
+	pub max_priority_fee_per_gas: U256,
+	/// Paymaster and its data, empty when the account pays its fees.
+	pub paymaster_and_data: Bytes,
+	/// Signature checked by the account.
+	pub signature: Bytes,
+}
+
+/// Gas limits of a user operation estimated by `eth_estimateUserOperationGas`.
+#[derive(Debug, PartialEq, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperationGasEstimate {
+	/// Gas paid for the calldata and the overhead of the bundle.
+	pub pre_verification_gas: U256,
+	/// Gas of the validation of the operation.
+	pub verification_gas_limit: U256,
+	/// Gas of the call to the account.
+	pub call_gas_limit: U256,
+}
+
+/// Receipt of an included user operation.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperationReceipt {
+	/// Hash of the operation.
+	pub user_op_hash: H256,
+	/// EntryPoint which handled the operation.
+	pub entry_point: H160,
+	/// Account which made the operation.
+	pub sender: H160,
+	/// Nonce of the operation.
+	pub nonce: U256,
+	/// Paymaster of the operation, zero when the account paid its fees.
+	pub paymaster: H160,
+	/// Fees paid for the operation.
+	pub actual_gas_cost: U256,
+	/// Gas used by the operation.
+	pub actual_gas_used: U256,
+	/// Whether the call to the account succeeded.
+	pub success: bool,
+	/// Logs emitted by the operation.
+	pub logs: Vec<Log>,
+	/// Receipt of the `handleOps` transaction including the operation.
+	pub receipt: Receipt,
+}
@@ -0,0 +1,145 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use std::{
+	collections::VecDeque,
+	sync::{Arc, Mutex},
+};
+
+use ethereum_types::H256;
+use serde::Serialize;
+
+/// Reason a transaction left the pool without being included in the best chain.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum DroppedTransactionStatus {
+	/// Transaction was replaced by another one from the same sender with the same nonce.
+	#[serde(rename_all = "camelCase")]
+	Replaced {
+		/// Hash of the replacing transaction, if it is known.
+		replaced_by: Option<H256>,
+	},
+	/// Transaction became invalid, e.g. its nonce was used by another transaction.
+	Invalid,
+	/// Transaction was dropped because the pool reached its limits.
+	Dropped,
+	/// Block including the transaction was retracted by a re-org.
+	#[serde(rename_all = "camelCase")]
+	Retracted {
+		/// Hash of the retracted block.
+		block_hash: H256,
+	},
+}
+
+/// Bounded buffer of the most recent transactions dropped from the pool, the oldest entry
+/// being evicted first once `limit` is reached.
+#[derive(Debug)]
+pub struct DroppedTransactions {
+	entries: VecDeque<(H256, DroppedTransactionStatus)>,
+	limit: usize,
+}
+
+impl DroppedTransactions {
+	pub fn new(limit: usize) -> Self {
+		Self {
+			entries: VecDeque::with_capacity(limit),
+			limit,
+		}
+	}
+
+	/// Records the status of a transaction, replacing any previous status of it.
+	pub fn insert(&mut self, hash: H256, status: DroppedTransactionStatus) {
+		if self.limit == 0 {
+			return;
+		}
+		self.remove(&hash);
+		if self.entries.len() == self.limit {
+			self.entries.pop_front();
+		}
+		self.entries.push_back((hash, status));
+	}
+
+	/// Forgets a transaction, e.g. once it is included again after a re-org.
+	pub fn remove(&mut self, hash: &H256) {
+		self.entries.retain(|(entry_hash, _)| entry_hash != hash);
+	}
+
+	pub fn get(&self, hash: &H256) -> Option<&DroppedTransactionStatus> {
+		self.entries
+			.iter()
+			.find(|(entry_hash, _)| entry_hash == hash)
+			.map(|(_, status)| status)
+	}
+}
+
+/// On-memory stored statuses queried through the `frontier_transactionStatus` RPC.
+pub type DroppedTransactionCache = Arc<Mutex<DroppedTransactions>>;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn evicts_oldest_entries_first() {
+		let mut dropped = DroppedTransactions::new(2);
+		dropped.insert(H256::repeat_byte(1), DroppedTransactionStatus::Dropped);
+		dropped.insert(H256::repeat_byte(2), DroppedTransactionStatus::Invalid);
+		dropped.insert(H256::repeat_byte(3), DroppedTransactionStatus::Dropped);
+
+		assert_eq!(dropped.get(&H256::repeat_byte(1)), None);
+		assert_eq!(
+			dropped.get(&H256::repeat_byte(2)),
+			Some(&DroppedTransactionStatus::Invalid)
+		);
+		assert_eq!(
+			dropped.get(&H256::repeat_byte(3)),
+			Some(&DroppedTransactionStatus::Dropped)
+		);
+	}
+
+	#[test]
+	fn keeps_latest_status_of_a_transaction() {
+		let mut dropped = DroppedTransactions::new(2);
+		let retracted = DroppedTransactionStatus::Retracted {
+			block_hash: H256::repeat_byte(9),
+		};
+		dropped.insert(H256::repeat_byte(1), retracted);
+		dropped.insert(H256::repeat_byte(2), DroppedTransactionStatus::Dropped);
+		dropped.insert(H256::repeat_byte(1), DroppedTransactionStatus::Invalid);
+		dropped.insert(H256::repeat_byte(3), DroppedTransactionStatus::Dropped);
+
+		assert_eq!(dropped.get(&H256::repeat_byte(2)), None);
+		assert_eq!(
+			dropped.get(&H256::repeat_byte(1)),
+			Some(&DroppedTransactionStatus::Invalid)
+		);
+	}
+
+	#[test]
+	fn serializes_status_with_tag() {
+		let status = DroppedTransactionStatus::Replaced {
+			replaced_by: Some(H256::repeat_byte(1)),
+		};
+		assert_eq!(
+			serde_json::to_string(&status).unwrap(),
+			format!(
+				r#"{{"status":"replaced","replacedBy":"{:?}"}}"#,
+				H256::repeat_byte(1)
+			)
+		);
+	}
+}
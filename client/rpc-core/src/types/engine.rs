@@ -0,0 +1,144 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use ethereum_types::{Bloom as H2048, H160, H256, H64, U256, U64};
+use serde::{Deserialize, Serialize};
+
+use crate::types::Bytes;
+
+/// Fork choice state as known by the consensus client.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkchoiceState {
+	/// Hash of the head block.
+	pub head_block_hash: H256,
+	/// Hash of the safe block.
+	pub safe_block_hash: H256,
+	/// Hash of the finalized block.
+	pub finalized_block_hash: H256,
+}
+
+/// Attributes used to initiate a payload build process.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PayloadAttributes {
+	/// Timestamp of the payload.
+	pub timestamp: U64,
+	/// Random value of the payload.
+	pub prev_randao: H256,
+	/// Recipient of the payload fees.
+	pub suggested_fee_recipient: H160,
+}
+
+/// Execution payload, an Ethereum block in the Engine API format.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionPayload {
+	/// Hash of the parent block.
+	pub parent_hash: H256,
+	/// Beneficiary of the block.
+	pub fee_recipient: H160,
+	/// State root hash.
+	pub state_root: H256,
+	/// Transactions receipts root hash.
+	pub receipts_root: H256,
+	/// Logs bloom.
+	pub logs_bloom: H2048,
+	/// Random value of the block.
+	pub prev_randao: H256,
+	/// Block number.
+	pub block_number: U64,
+	/// Gas limit.
+	pub gas_limit: U64,
+	/// Gas used.
+	pub gas_used: U64,
+	/// Timestamp.
+	pub timestamp: U64,
+	/// Extra data.
+	pub extra_data: Bytes,
+	/// Base fee per gas.
+	pub base_fee_per_gas: U256,
+	/// Hash of the block.
+	pub block_hash: H256,
+	/// Enveloped encoded transactions.
+	pub transactions: Vec<Bytes>,
+}
+
+/// Body of an execution payload.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PayloadBody {
+	/// Enveloped encoded transactions.
+	pub transactions: Vec<Bytes>,
+}
+
+/// Validity status of a payload.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PayloadStatusKind {
+	/// The payload is known and valid.
+	Valid,
+	/// The payload is known and invalid.
+	Invalid,
+	/// The payload is not known yet.
+	Syncing,
+	/// The payload is not known yet, but its parent is.
+	Accepted,
+	/// The payload block hash does not match its content.
+	InvalidBlockHash,
+}
+
+/// Validity of a payload.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PayloadStatus {
+	/// Validity status.
+	pub status: PayloadStatusKind,
+	/// Hash of the most recent valid block in the branch defined by the payload.
+	pub latest_valid_hash: Option<H256>,
+	/// Reason of an invalid status.
+	pub validation_error: Option<String>,
+}
+
+impl PayloadStatus {
+	pub fn new(status: PayloadStatusKind, latest_valid_hash: Option<H256>) -> Self {
+		Self {
+			status,
+			latest_valid_hash,
+			validation_error: None,
+		}
+	}
+
+	pub fn invalid(latest_valid_hash: Option<H256>, validation_error: String) -> Self {
+		Self {
+			status: PayloadStatusKind::Invalid,
+			latest_valid_hash,
+			validation_error: Some(validation_error),
+		}
+	}
+}
+
+/// `engine_forkchoiceUpdated` response.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkchoiceUpdatedResult {
+	/// Status of the head block.
+	pub payload_status: PayloadStatus,
+	/// Identifier of the payload build process, if any.
+	pub payload_id: Option<H64>,
+}
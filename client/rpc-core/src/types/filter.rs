@@ -153,6 +153,8 @@ pub struct Filter {
 pub struct FilteredParams {
 	pub filter: Option<Filter>,
 	pub flat_topics: Vec<FlatTopic>,
+	/// The topics matched at each position, `None` matching any topic.
+	pub topic_positions: Vec<Option<Vec<H256>>>,
 }
 
 impl FilteredParams {
@@ -161,8 +163,15 @@ impl FilteredParams {
 			return FilteredParams {
 				filter: Some(f.clone()),
 				flat_topics: {
-					if let Some(t) = f.topics {
-						Self::flatten(&t)
+					if let Some(t) = &f.topics {
+						Self::flatten(t)
+					} else {
+						Vec::new()
+					}
+				},
+				topic_positions: {
+					if let Some(t) = &f.topics {
+						Self::topic_positions(t)
 					} else {
 						Vec::new()
 					}
@@ -300,28 +309,29 @@ impl FilteredParams {
 		out
 	}
 
-	/// Replace None values - aka wildcards - for the log input value in that position.
-	pub fn replace(&self, log: &Log, topic: FlatTopic) -> Option<Vec<H256>> {
-		let mut out: Vec<H256> = Vec::new();
-		match topic {
-			VariadicValue::Single(Some(value)) => {
-				out.push(value);
-			}
-			VariadicValue::Multiple(value) => {
-				for (k, v) in value.into_iter().enumerate() {
-					if let Some(v) = v {
-						out.push(v);
-					} else {
-						out.push(log.topics[k]);
-					}
+	/// The topics matched at each position, `None` matching any topic.
+	/// i.e. `[A,null,[B,C]]` to `[Some([A]),None,Some([B,C])]`.
+	fn topic_positions(topic: &Topic) -> Vec<Option<Vec<H256>>> {
+		fn alternatives(value: &Option<VariadicValue<Option<H256>>>) -> Option<Vec<H256>> {
+			match value {
+				Some(VariadicValue::Single(Some(topic))) => Some(vec![*topic]),
+				// An empty list, or a list containing `null`, matches any topic.
+				Some(VariadicValue::Multiple(topics)) if !topics.is_empty() => {
+					topics.iter().copied().collect()
 				}
+				_ => None,
 			}
-			_ => {}
-		};
-		if out.is_empty() {
-			return None;
 		}
-		Some(out)
+		match topic {
+			// A list of plain topics, i.e. `[A,null,B]`, is deserialized as a single value.
+			VariadicValue::Single(Some(VariadicValue::Multiple(topics))) => topics
+				.iter()
+				.map(|topic| topic.map(|topic| vec![topic]))
+				.collect(),
+			VariadicValue::Single(single) => vec![alternatives(single)],
+			VariadicValue::Multiple(multi) => multi.iter().map(alternatives).collect(),
+			VariadicValue::Null => Vec::new(),
+		}
 	}
 
 	pub fn filter_block_range(&self, block_number: u64) -> bool {
@@ -366,7 +376,8 @@ impl FilteredParams {
 					}
 				}
 				VariadicValue::Multiple(x) => {
-					if !x.contains(&log.address) {
+					// An empty list matches any address.
+					if !x.is_empty() && !x.contains(&log.address) {
 						return false;
 					}
 				}
@@ -378,49 +389,22 @@ impl FilteredParams {
 		true
 	}
 
+	/// Evaluates if the topics of a log match the filter: at each position, the topic of the
+	/// log must be one of the topics of the filter, or the filter must match any topic.
+	///
+	/// As in go-ethereum, a log with fewer topics than the filter positions doesn't match,
+	/// even if the last positions match any topic.
 	pub fn filter_topics(&self, log: &Log) -> bool {
-		let mut out: bool = true;
-		for topic in self.flat_topics.clone() {
-			match topic {
-				VariadicValue::Single(single) => {
-					if let Some(single) = single {
-						if !log.topics.starts_with(&[single]) {
-							out = false;
-						}
-					}
-				}
-				VariadicValue::Multiple(multi) => {
-					// Shrink the topics until the last item is Some.
-					let mut new_multi = multi;
-					while new_multi
-						.iter()
-						.last()
-						.unwrap_or(&Some(H256::default()))
-						.is_none()
-					{
-						new_multi.pop();
-					}
-					// We can discard right away any logs with lesser topics than the filter.
-					if new_multi.len() > log.topics.len() {
-						out = false;
-						break;
-					}
-					let replaced: Option<Vec<H256>> =
-						self.replace(log, VariadicValue::Multiple(new_multi));
-					if let Some(replaced) = replaced {
-						out = false;
-						if log.topics.starts_with(&replaced[..]) {
-							out = true;
-							break;
-						}
-					}
-				}
-				_ => {
-					out = true;
-				}
-			}
-		}
-		out
+		self.topic_positions.len() <= log.topics.len()
+			&& self
+				.topic_positions
+				.iter()
+				.zip(&log.topics)
+				.all(|(topics, topic)| {
+					topics
+						.as_ref()
+						.map_or(true, |topics| topics.contains(topic))
+				})
 	}
 }
 
@@ -468,6 +452,7 @@ pub type FilterPool = Arc<Mutex<BTreeMap<U256, FilterPoolItem>>>;
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use serde_json::json;
 	use std::str::FromStr;
 
 	fn block_bloom() -> Bloom {
@@ -734,4 +719,119 @@ mod tests {
 			&topics_bloom
 		));
 	}
+
+	fn log(address: H160, topics: Vec<H256>) -> Log {
+		Log {
+			address,
+			topics,
+			data: Default::default(),
+			block_hash: None,
+			block_number: None,
+			transaction_hash: None,
+			transaction_index: None,
+			log_index: None,
+			transaction_log_index: None,
+			removed: false,
+		}
+	}
+
+	fn matches(filter: Value, address: H160, topics: Vec<H256>) -> bool {
+		let params = FilteredParams::new(Some(from_value(filter).unwrap()));
+		let log = log(address, topics);
+		params.filter_address(&log) && params.filter_topics(&log)
+	}
+
+	#[test]
+	fn filter_should_match_topics_by_position() {
+		let (a, b, c, d) = (
+			H256::repeat_byte(0xa),
+			H256::repeat_byte(0xb),
+			H256::repeat_byte(0xc),
+			H256::repeat_byte(0xd),
+		);
+		let address = H160::repeat_byte(1);
+		let cases = vec![
+			// No topics, or an empty list, match any log.
+			(json!({}), vec![], true),
+			(json!({ "topics": null }), vec![a], true),
+			(json!({ "topics": [] }), vec![a, b], true),
+			// A topic in first position.
+			(json!({ "topics": [a] }), vec![a], true),
+			(json!({ "topics": [a] }), vec![a, b], true),
+			(json!({ "topics": [a] }), vec![b, a], false),
+			(json!({ "topics": [a] }), vec![], false),
+			// Topics in first and second positions.
+			(json!({ "topics": [a, b] }), vec![a, b, c], true),
+			(json!({ "topics": [a, b] }), vec![a, c], false),
+			(json!({ "topics": [a, b] }), vec![a], false),
+			// `null` matches any topic, but the log must have a topic at its position.
+			(json!({ "topics": [null, b] }), vec![a, b], true),
+			(json!({ "topics": [null, b] }), vec![c, b], true),
+			(json!({ "topics": [null, b] }), vec![b], false),
+			(json!({ "topics": [a, null] }), vec![a, c], true),
+			(json!({ "topics": [a, null] }), vec![a], false),
+			(json!({ "topics": [null, null] }), vec![c, d], true),
+			// A nested list matches any of its topics.
+			(json!({ "topics": [[a, b]] }), vec![a], true),
+			(json!({ "topics": [[a, b]] }), vec![b, c], true),
+			(json!({ "topics": [[a, b]] }), vec![c, a], false),
+			(json!({ "topics": [[a, b], [c, d]] }), vec![b, c], true),
+			(json!({ "topics": [[a, b], [c, d]] }), vec![a, d], true),
+			(json!({ "topics": [[a, b], [c, d]] }), vec![c, a], false),
+			(json!({ "topics": [a, [c, d]] }), vec![a, d], true),
+			(json!({ "topics": [a, [c, d]] }), vec![b, d], false),
+			(json!({ "topics": [null, [c, d]] }), vec![b, c], true),
+			(json!({ "topics": [null, [c, d]] }), vec![b, a], false),
+			// A nested list containing `null`, or empty, matches any topic.
+			(json!({ "topics": [a, [c, null]] }), vec![a, b], true),
+			(json!({ "topics": [a, []] }), vec![a, b], true),
+			(json!({ "topics": [a, []] }), vec![a], false),
+			(
+				json!({ "topics": [[a, b], null, [d]] }),
+				vec![b, c, d],
+				true,
+			),
+			(
+				json!({ "topics": [[a, b], null, [d]] }),
+				vec![b, c, c],
+				false,
+			),
+		];
+		for (filter, topics, expected) in cases {
+			assert_eq!(
+				matches(filter.clone(), address, topics.clone()),
+				expected,
+				"filter {} on topics {:?}",
+				filter,
+				topics
+			);
+		}
+	}
+
+	#[test]
+	fn filter_should_match_addresses() {
+		let (a, b, c) = (
+			H160::repeat_byte(0xa),
+			H160::repeat_byte(0xb),
+			H160::repeat_byte(0xc),
+		);
+		let topic = H256::repeat_byte(1);
+		assert!(matches(json!({ "address": a }), a, vec![]));
+		assert!(!matches(json!({ "address": a }), b, vec![]));
+		assert!(matches(json!({ "address": [a, b] }), b, vec![]));
+		assert!(!matches(json!({ "address": [a, b] }), c, vec![]));
+		// An empty list matches any address.
+		assert!(matches(json!({ "address": [] }), c, vec![]));
+		// Addresses and topics must both match.
+		assert!(matches(
+			json!({ "address": [a, b], "topics": [topic] }),
+			a,
+			vec![topic]
+		));
+		assert!(!matches(
+			json!({ "address": [a, b], "topics": [topic] }),
+			c,
+			vec![topic]
+		));
+	}
 }
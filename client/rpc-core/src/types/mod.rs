@@ -18,29 +18,47 @@
 
 //! RPC types
 
+mod access_list;
 mod account_info;
 mod block;
 mod block_number;
+mod bundler;
 mod bytes;
 mod call_request;
+mod dropped;
+mod engine;
 mod fee;
 mod filter;
 mod index;
 mod log;
+mod metadata;
+mod precompile;
 mod receipt;
+mod simulate;
+mod state_export;
+mod stats;
 mod sync;
+mod trace;
 mod transaction;
 mod transaction_request;
+mod validation;
 mod work;
 
 pub mod pubsub;
 
 pub use self::{
+	access_list::AccessListResult,
 	account_info::{AccountInfo, EthAccount, ExtAccountInfo, RecoveredAccount, StorageProof},
-	block::{Block, BlockTransactions, Header, Rich, RichBlock, RichHeader},
+	block::{Block, BlockTransactions, ExtendedBlock, Header, Rich, RichBlock, RichHeader},
 	block_number::BlockNumber,
+	bundler::{UserOperation, UserOperationGasEstimate, UserOperationReceipt},
 	bytes::Bytes,
 	call_request::CallRequest,
+	dropped::{DroppedTransactionCache, DroppedTransactionStatus, DroppedTransactions},
+	engine::{
+		ExecutionPayload, ForkchoiceState, ForkchoiceUpdatedResult, PayloadAttributes, PayloadBody,
+		PayloadStatus, PayloadStatusKind,
+	},
 	fee::{FeeHistory, FeeHistoryCache, FeeHistoryCacheItem, FeeHistoryCacheLimit},
 	filter::{
 		Filter, FilterAddress, FilterChanges, FilterPool, FilterPoolItem, FilterType,
@@ -48,12 +66,25 @@ pub use self::{
 	},
 	index::Index,
 	log::Log,
-	receipt::Receipt,
+	metadata::ContractMetadata,
+	precompile::Precompile,
+	receipt::{GasBreakdown, Receipt, ReceiptProof},
+	simulate::{
+		AccountOverride, BlockOverrides, SimulateBlock, SimulateCallError, SimulatePayload,
+		SimulatedBlock, SimulatedCall,
+	},
+	state_export::{EvmAccount, EvmAccountRange, EvmStorageEntry, EvmStorageRange},
+	stats::{CacheStats, FrontierStats, MappingSyncStats, PendingTransactionsStats},
 	sync::{
 		ChainStatus, EthProtocolInfo, PeerCount, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
 		Peers, PipProtocolInfo, SyncInfo, SyncStatus, TransactionStats,
 	},
+	trace::{
+		AccountDiff, CallFrame, ChangedType, Diff, PrestateAccount, PrestateTrace, StateDiff,
+		TraceCallConfig, TraceConfig, TraceResults, TracerConfig,
+	},
 	transaction::{LocalTransactionStatus, RichRawTransaction, Transaction},
 	transaction_request::{TransactionMessage, TransactionRequest},
+	validation::{InvalidTransactionReason, TransactionValidation},
 	work::Work,
 };
@@ -16,7 +16,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::types::Log;
+use crate::types::{Bytes, Log};
 use ethereum_types::{Bloom as H2048, H160, H256, U256, U64};
 use serde::Serialize;
 
@@ -57,3 +57,41 @@ pub struct Receipt {
 	/// Effective gas price. Pre-eip1559 this is just the gasprice. Post-eip1559 this is base fee + priority fee.
 	pub effective_gas_price: U256,
 }
+
+/// Proof of inclusion of a receipt in the receipts root of its block.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptProof {
+	/// Transaction Hash
+	pub transaction_hash: H256,
+	/// Transaction index, whose RLP encoding is the key of the receipt in the trie
+	pub transaction_index: U256,
+	/// Block hash
+	pub block_hash: H256,
+	/// Block number
+	pub block_number: U256,
+	/// Receipts root
+	pub receipts_root: H256,
+	/// RLP encoded trie nodes, from the root to the leaf holding the receipt
+	pub proof: Vec<Bytes>,
+}
+
+/// Breakdown of the gas used by a transaction.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasBreakdown {
+	/// Transaction Hash
+	pub transaction_hash: H256,
+	/// Gas limit of the transaction
+	pub gas_limit: U256,
+	/// Gas charged before the execution, for the transaction itself, its input data, its
+	/// contract creation and its access list
+	pub intrinsic_gas: U256,
+	/// Gas used by the execution, net of the refunds
+	pub execution_gas: U256,
+	/// Gas refunded at the end of the execution, already deducted from the execution gas.
+	/// Unknown for the runtimes before version 13 of `EthereumRuntimeRPCApi`
+	pub refunded_gas: Option<U256>,
+	/// Gas used by the transaction, which is paid for
+	pub gas_used: U256,
+}
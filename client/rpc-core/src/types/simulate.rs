@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::{H160, H256, U256, U64};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Bytes, CallRequest, Log};
+
+/// Changes applied to an account before simulating calls.
+#[derive(Debug, Default, PartialEq, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountOverride {
+	/// Balance to set.
+	pub balance: Option<U256>,
+	/// Nonce to set.
+	pub nonce: Option<U256>,
+	/// Code to set.
+	pub code: Option<Bytes>,
+	/// Storage replacing the whole account storage.
+	pub state: Option<BTreeMap<H256, H256>>,
+	/// Storage slots to set, keeping the rest of the account storage.
+	pub state_diff: Option<BTreeMap<H256, H256>>,
+}
+
+/// Changes applied to the block context before simulating calls.
+#[derive(Debug, Default, PartialEq, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockOverrides {
+	/// Block number.
+	pub number: Option<U256>,
+	/// Block timestamp, in seconds.
+	pub time: Option<U64>,
+}
+
+/// Calls simulated in the context of one block.
+#[derive(Debug, Default, PartialEq, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateBlock {
+	/// Block context overrides.
+	pub block_overrides: Option<BlockOverrides>,
+	/// Account overrides, applied before the calls.
+	pub state_overrides: Option<BTreeMap<H160, AccountOverride>>,
+	/// Calls, executed in order on top of each other.
+	#[serde(default)]
+	pub calls: Vec<CallRequest>,
+}
+
+/// `eth_simulateV1` payload.
+#[derive(Debug, Default, PartialEq, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatePayload {
+	/// Blocks to simulate, executed in order on top of each other.
+	pub block_state_calls: Vec<SimulateBlock>,
+}
+
+/// Result of a simulated block.
+#[derive(Debug, PartialEq, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlock {
+	/// Block number the calls were executed with.
+	pub number: U256,
+	/// Gas used by all the calls.
+	pub gas_used: U256,
+	/// Results of the calls.
+	pub calls: Vec<SimulatedCall>,
+}
+
+/// Result of a simulated call.
+#[derive(Debug, PartialEq, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedCall {
+	/// Returned data, or the deployed code for a create.
+	pub return_data: Bytes,
+	/// Logs emitted by the call.
+	pub logs: Vec<Log>,
+	/// Gas used.
+	pub gas_used: U256,
+	/// 1 on success, 0 on failure.
+	pub status: U64,
+	/// Failure cause.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<SimulateCallError>,
+}
+
+/// Failure cause of a simulated call.
+#[derive(Debug, PartialEq, Serialize, Clone)]
+pub struct SimulateCallError {
+	/// Error code, as returned by `eth_call`.
+	pub code: i64,
+	/// Error message, as returned by `eth_call`.
+	pub message: String,
+}
@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use ethereum_types::{H160, H256};
+use serde::Serialize;
+
+use crate::types::Bytes;
+
+/// A range of the EVM contracts, in the order of their storage keys.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvmAccountRange {
+	/// Hash of the Substrate block the range is read at.
+	pub block_hash: H256,
+	/// State root of the Substrate block the range is read at.
+	pub state_root: H256,
+	/// The contracts of the range.
+	pub accounts: Vec<EvmAccount>,
+	/// Storage key to read the next range after, none after the last contract.
+	pub next: Option<Bytes>,
+	/// Proof of the storage entries of the range against the state root.
+	pub proof: Vec<Bytes>,
+}
+
+/// An EVM contract.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvmAccount {
+	/// Address of the contract.
+	pub address: H160,
+	/// Code of the contract.
+	pub code: Bytes,
+}
+
+/// A range of the storage of an EVM contract, in the order of its storage keys.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvmStorageRange {
+	/// Hash of the Substrate block the range is read at.
+	pub block_hash: H256,
+	/// State root of the Substrate block the range is read at.
+	pub state_root: H256,
+	/// The storage slots of the range.
+	pub storage: Vec<EvmStorageEntry>,
+	/// Storage key to read the next range after, none after the last slot.
+	pub next: Option<Bytes>,
+	/// Proof of the storage entries of the range against the state root.
+	pub proof: Vec<Bytes>,
+}
+
+/// A storage slot of an EVM contract.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvmStorageEntry {
+	/// Index of the slot.
+	pub key: H256,
+	/// Value of the slot.
+	pub value: H256,
+}
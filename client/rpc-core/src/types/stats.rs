@@ -0,0 +1,72 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use ethereum_types::U256;
+use serde::Serialize;
+
+/// Frontier statistics, for node operators.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrontierStats {
+	/// Progress of the mapping sync.
+	pub mapping_sync: MappingSyncStats,
+	/// Size of the Frontier database on disk, in bytes, if known.
+	pub database_size: Option<U256>,
+	/// Usage of the block data caches.
+	pub caches: Vec<CacheStats>,
+	/// Number of installed filters, if filters are enabled.
+	pub filter_pool_size: Option<U256>,
+	/// Ethereum transactions in the pool.
+	pub pending_transactions: PendingTransactionsStats,
+}
+
+/// Progress of the mapping sync.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MappingSyncStats {
+	/// Number of the best block.
+	pub best_block: U256,
+	/// Whether the best block is mapped.
+	pub best_block_synced: bool,
+	/// Numbers of the next blocks to map on each branch being synced, none once the sync is
+	/// complete.
+	pub syncing_tips: Vec<U256>,
+}
+
+/// Usage of a block data cache.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+	/// Name of the cache.
+	pub name: String,
+	/// Number of requests served from the cache.
+	pub hits: U256,
+	/// Number of requests fetching their data.
+	pub misses: U256,
+	/// Size of the cached data, in bytes.
+	pub size: U256,
+}
+
+/// Ethereum transactions in the pool.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTransactionsStats {
+	/// Transactions ready to be included.
+	pub ready: U256,
+	/// Transactions waiting for a previous nonce.
+	pub future: U256,
+}
@@ -0,0 +1,249 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::{H160, H256, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{AccountOverride, BlockOverrides, Bytes};
+
+/// Traces of a replayed transaction.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceResults {
+	/// Changes of the state by the transaction, if requested.
+	pub state_diff: Option<StateDiff>,
+	/// Whether changes are missing, as the trace reached the limits of the node.
+	#[serde(skip_serializing_if = "std::ops::Not::not")]
+	pub truncated: bool,
+}
+
+/// Changes of the accounts by a transaction.
+pub type StateDiff = BTreeMap<H160, AccountDiff>;
+
+/// Changes of an account by a transaction.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct AccountDiff {
+	pub balance: Diff<U256>,
+	pub nonce: Diff<U256>,
+	pub code: Diff<Bytes>,
+	pub storage: BTreeMap<H256, Diff<H256>>,
+}
+
+/// Change of a value, serialized as `"="`, `{"+": value}`, `{"-": value}` or
+/// `{"*": {"from": value, "to": value}}`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum Diff<T> {
+	/// The value is unchanged.
+	#[serde(rename = "=")]
+	Same,
+	/// The value is created, along with its account.
+	#[serde(rename = "+")]
+	Born(T),
+	/// The value is removed, along with its account.
+	#[serde(rename = "-")]
+	Died(T),
+	/// The value is changed.
+	#[serde(rename = "*")]
+	Changed(ChangedType<T>),
+}
+
+impl<T: PartialEq> Diff<T> {
+	/// Change between two values, which are `None` when their account doesn't exist.
+	pub fn new(from: Option<T>, to: Option<T>) -> Self {
+		match (from, to) {
+			(None, None) => Diff::Same,
+			(None, Some(to)) => Diff::Born(to),
+			(Some(from), None) => Diff::Died(from),
+			(Some(from), Some(to)) if from == to => Diff::Same,
+			(Some(from), Some(to)) => Diff::Changed(ChangedType { from, to }),
+		}
+	}
+
+	pub fn is_same(&self) -> bool {
+		matches!(self, Diff::Same)
+	}
+}
+
+/// The values before and after a change.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ChangedType<T> {
+	pub from: T,
+	pub to: T,
+}
+
+/// Options of `debug_traceCall`.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceCallConfig {
+	/// Name of the tracer, only `callTracer` is supported.
+	pub tracer: Option<String>,
+	/// Options of the tracer, `onlyTopCall` is required.
+	pub tracer_config: Option<TracerConfig>,
+	/// Account overrides, applied before the call.
+	pub state_overrides: Option<BTreeMap<H160, AccountOverride>>,
+	/// Block context overrides.
+	pub block_overrides: Option<BlockOverrides>,
+}
+
+/// A call frame, in the format of the go-ethereum `callTracer`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+	/// `CALL` or `CREATE`.
+	#[serde(rename = "type")]
+	pub call_type: String,
+	pub from: H160,
+	/// Callee, or address of the created contract.
+	pub to: Option<H160>,
+	pub value: U256,
+	pub gas: U256,
+	pub gas_used: U256,
+	pub input: Bytes,
+	/// Returned data, or the deployed code for a create.
+	pub output: Bytes,
+	/// Failure cause.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	/// Sub-calls of the frame.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub calls: Vec<CallFrame>,
+}
+
+/// Options of `debug_traceTransaction`.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceConfig {
+	/// Name of the tracer, only `prestateTracer` is supported.
+	pub tracer: Option<String>,
+	/// Options of the tracer.
+	pub tracer_config: Option<TracerConfig>,
+}
+
+/// Options of the tracers.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TracerConfig {
+	/// Whether the `prestateTracer` returns the states before and after the transaction.
+	#[serde(default)]
+	pub diff_mode: bool,
+	/// Whether the `callTracer` only returns the frame of the top-level call.
+	#[serde(default)]
+	pub only_top_call: bool,
+}
+
+/// Trace of the go-ethereum `prestateTracer`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum PrestateTrace {
+	/// States of the accounts touched by the transaction, before it.
+	Prestate {
+		#[serde(flatten)]
+		accounts: BTreeMap<H160, PrestateAccount>,
+		/// Whether accounts are missing, as the trace reached the limits of the node.
+		#[serde(skip_serializing_if = "std::ops::Not::not")]
+		truncated: bool,
+	},
+	/// In diff mode, the states before the transaction of the accounts it changes, and their
+	/// changed fields after it.
+	Diff {
+		pre: BTreeMap<H160, PrestateAccount>,
+		post: BTreeMap<H160, PrestateAccount>,
+		/// Whether accounts are missing, as the trace reached the limits of the node.
+		#[serde(skip_serializing_if = "std::ops::Not::not")]
+		truncated: bool,
+	},
+}
+
+/// State of an account in a `prestateTracer` trace, without the unknown or unchanged fields.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct PrestateAccount {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub balance: Option<U256>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub nonce: Option<u64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub code: Option<Bytes>,
+	#[serde(skip_serializing_if = "BTreeMap::is_empty")]
+	pub storage: BTreeMap<H256, H256>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn diff_serialization() {
+		assert_eq!(
+			serde_json::to_value(&Diff::new(Some(U256::one()), Some(U256::one()))).unwrap(),
+			json!("=")
+		);
+		assert_eq!(
+			serde_json::to_value(&Diff::new(None, Some(U256::one()))).unwrap(),
+			json!({ "+": "0x1" })
+		);
+		assert_eq!(
+			serde_json::to_value(&Diff::new(Some(U256::one()), None)).unwrap(),
+			json!({ "-": "0x1" })
+		);
+		assert_eq!(
+			serde_json::to_value(&Diff::new(Some(U256::zero()), Some(U256::one()))).unwrap(),
+			json!({ "*": { "from": "0x0", "to": "0x1" } })
+		);
+	}
+
+	#[test]
+	fn prestate_trace_serialization() {
+		let account = PrestateAccount {
+			balance: Some(U256::one()),
+			nonce: Some(1),
+			..Default::default()
+		};
+		let mut accounts = BTreeMap::new();
+		accounts.insert(H160::zero(), account);
+
+		let address = "0x0000000000000000000000000000000000000000";
+		assert_eq!(
+			serde_json::to_value(&PrestateTrace::Prestate {
+				accounts: accounts.clone(),
+				truncated: false,
+			})
+			.unwrap(),
+			json!({ address: { "balance": "0x1", "nonce": 1 } })
+		);
+		assert_eq!(
+			serde_json::to_value(&PrestateTrace::Prestate {
+				accounts: accounts.clone(),
+				truncated: true,
+			})
+			.unwrap(),
+			json!({ address: { "balance": "0x1", "nonce": 1 }, "truncated": true })
+		);
+		assert_eq!(
+			serde_json::to_value(&PrestateTrace::Diff {
+				pre: accounts,
+				post: BTreeMap::new(),
+				truncated: false,
+			})
+			.unwrap(),
+			json!({ "pre": { address: { "balance": "0x1", "nonce": 1 } }, "post": {} })
+		);
+	}
+}
@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use serde::Serialize;
+
+/// Reason a transaction fails the pool validation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InvalidTransactionReason {
+	GasLimitTooLow,
+	GasLimitTooHigh,
+	InvalidChainId,
+	InvalidSignature,
+	InvalidPaymentInput,
+	PriorityFeeTooHigh,
+	GasPriceTooLow,
+	BalanceTooLow,
+	NonceTooLow,
+	NonceTooHigh,
+	TooManyFutureTransactions,
+}
+
+impl InvalidTransactionReason {
+	/// Error message, as reported by geth when possible.
+	pub fn message(&self) -> &'static str {
+		match self {
+			Self::GasLimitTooLow => "intrinsic gas too low",
+			Self::GasLimitTooHigh => "exceeds block gas limit",
+			Self::InvalidChainId => "invalid chain id",
+			Self::InvalidSignature => "invalid sender",
+			Self::InvalidPaymentInput => "invalid combination of fee fields",
+			Self::PriorityFeeTooHigh => "max priority fee per gas higher than max fee per gas",
+			Self::GasPriceTooLow => "max fee per gas less than block base fee",
+			Self::BalanceTooLow => "insufficient funds for gas * price + value",
+			Self::NonceTooLow => "nonce too low",
+			Self::NonceTooHigh => "nonce too high",
+			Self::TooManyFutureTransactions => "too many future transactions from sender",
+		}
+	}
+}
+
+/// Result of the pool validation of a transaction.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionValidation {
+	/// Whether the transaction would be accepted by the pool.
+	pub valid: bool,
+	/// Reason the transaction is invalid.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub reason: Option<InvalidTransactionReason>,
+	/// Description of the reason.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub message: Option<String>,
+}
+
+impl TransactionValidation {
+	pub fn valid() -> Self {
+		Self {
+			valid: true,
+			reason: None,
+			message: None,
+		}
+	}
+
+	pub fn invalid(reason: InvalidTransactionReason) -> Self {
+		Self {
+			valid: false,
+			reason: Some(reason),
+			message: Some(reason.message().to_string()),
+		}
+	}
+}
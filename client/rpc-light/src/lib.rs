@@ -0,0 +1,722 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Ethereum RPC served from a Substrate light client.
+//!
+//! Dapps can talk to a Frontier chain through a light client running in the browser, such as
+//! smoldot with substrate-connect, instead of a centralized RPC node. [`LightEth`] answers the
+//! Ethereum methods with the Substrate JSON-RPC methods of the light client, reading the
+//! Ethereum state with runtime calls proven by the full nodes it connects to.
+//!
+//! Only the methods which don't need the mapping database of a full Frontier node are served:
+//!
+//! * `eth_blockNumber` and `eth_chainId`,
+//! * `eth_getBalance` and `eth_getTransactionCount`,
+//! * `eth_gasPrice`,
+//! * `eth_getLogs`,
+//! * `eth_sendRawTransaction`.
+//!
+//! Blocks can only be given by their Ethereum hash with a [`BlockMapping`]. The `pending` block
+//! is the best block of the light client, which has no transaction pool. Submitting
+//! transactions requires version 2 of the `ConvertTransactionRuntimeApi`.
+
+use std::time::{Duration, Instant};
+
+use codec::Decode;
+use ethereum::{BlockV2 as EthereumBlock, TransactionV2 as EthereumTransaction};
+use ethereum_types::{Bloom, H160, H256, U256, U64};
+use futures::future::LocalBoxFuture;
+use jsonrpc_core::{Error, ErrorCode, Result, Value};
+use serde::de::DeserializeOwned;
+
+use fc_rpc_core::types::{BlockNumber, Bytes, Filter, FilteredParams, Log};
+
+/// Methods answered by [`LightEth::handle`].
+pub const METHODS: &[&str] = &[
+	"eth_blockNumber",
+	"eth_chainId",
+	"eth_getBalance",
+	"eth_getTransactionCount",
+	"eth_gasPrice",
+	"eth_getLogs",
+	"eth_sendRawTransaction",
+];
+
+/// The Substrate JSON-RPC interface of a light client.
+pub trait LightClient {
+	/// Sends the request `method` with `params` to the light client, returning its result.
+	fn request(&self, method: &str, params: Vec<Value>) -> LocalBoxFuture<'static, Result<Value>>;
+}
+
+/// Mapping of the Ethereum blocks to their Substrate blocks, as kept by a Frontier backend.
+pub trait BlockMapping {
+	/// Hash of the Substrate block of the Ethereum block `ethereum_block_hash`, if it is mapped.
+	fn block_hash(&self, ethereum_block_hash: H256) -> Result<Option<H256>>;
+}
+
+/// Account of the `EthereumRuntimeRPCApi`, decoded as `fp_evm::Account`.
+#[derive(Decode)]
+struct Account {
+	nonce: U256,
+	balance: U256,
+}
+
+/// Status of a transaction of the `EthereumRuntimeRPCApi`, decoded as
+/// `fp_rpc::TransactionStatus`.
+#[derive(Decode)]
+struct TransactionStatus {
+	transaction_hash: H256,
+	transaction_index: u32,
+	_from: H160,
+	_to: Option<H160>,
+	_contract_address: Option<H160>,
+	logs: Vec<ethereum::Log>,
+	_logs_bloom: Bloom,
+}
+
+/// Ethereum RPC served from a light client.
+pub struct LightEth<L> {
+	client: L,
+	mapping: Option<Box<dyn BlockMapping>>,
+	max_past_logs: u32,
+	max_logs_block_range: u64,
+	logs_timeout: Option<Duration>,
+}
+
+impl<L: LightClient> LightEth<L> {
+	pub fn new(client: L) -> Self {
+		Self {
+			client,
+			mapping: None,
+			max_past_logs: 10_000,
+			max_logs_block_range: 1_024,
+			logs_timeout: None,
+		}
+	}
+
+	/// Resolves the blocks given by their Ethereum hash with `mapping`.
+	pub fn with_mapping(mut self, mapping: impl BlockMapping + 'static) -> Self {
+		self.mapping = Some(Box::new(mapping));
+		self
+	}
+
+	/// Sets the maximum number of logs returned by `eth_getLogs`, 10000 by default.
+	pub fn with_max_past_logs(mut self, max_past_logs: u32) -> Self {
+		self.max_past_logs = max_past_logs;
+		self
+	}
+
+	/// Sets the maximum number of blocks of a range queried by `eth_getLogs`, 1024 by default.
+	/// Each block of the range costs requests to the client, even without matching logs.
+	pub fn with_max_logs_block_range(mut self, max_logs_block_range: u64) -> Self {
+		self.max_logs_block_range = max_logs_block_range;
+		self
+	}
+
+	/// Sets the duration after which `eth_getLogs` fails, unlimited by default. Time isn't
+	/// available to the Wasm light clients of the browsers, so it must not be set for them.
+	pub fn with_logs_timeout(mut self, logs_timeout: Duration) -> Self {
+		self.logs_timeout = Some(logs_timeout);
+		self
+	}
+
+	/// Handles the Ethereum JSON-RPC request `method` with `params`, returning its result.
+	pub async fn handle(&self, method: &str, params: Vec<Value>) -> Result<Value> {
+		match method {
+			"eth_blockNumber" => to_value(self.block_number().await?),
+			"eth_chainId" => to_value(self.chain_id().await?),
+			"eth_getBalance" => {
+				to_value(self.balance(param(&params, 0)?, param(&params, 1)?).await?)
+			}
+			"eth_getTransactionCount" => to_value(
+				self.transaction_count(param(&params, 0)?, param(&params, 1)?)
+					.await?,
+			),
+			"eth_gasPrice" => to_value(self.gas_price().await?),
+			"eth_getLogs" => to_value(self.logs(param(&params, 0)?).await?),
+			"eth_sendRawTransaction" => {
+				to_value(self.send_raw_transaction(param(&params, 0)?).await?)
+			}
+			_ => Err(Error::method_not_found()),
+		}
+	}
+
+	pub async fn block_number(&self) -> Result<U256> {
+		let header = self.client.request("chain_getHeader", Vec::new()).await?;
+		header
+			.get("number")
+			.cloned()
+			.map(serde_json::from_value::<U256>)
+			.and_then(|number| number.ok())
+			.ok_or_else(|| internal_err("invalid header"))
+	}
+
+	pub async fn chain_id(&self) -> Result<Option<U64>> {
+		let chain_id: u64 = self.runtime_call("chain_id", Vec::new(), None).await?;
+		Ok(Some(chain_id.into()))
+	}
+
+	pub async fn balance(&self, address: H160, number: Option<BlockNumber>) -> Result<U256> {
+		Ok(self.account(address, number).await?.balance)
+	}
+
+	pub async fn transaction_count(
+		&self,
+		address: H160,
+		number: Option<BlockNumber>,
+	) -> Result<U256> {
+		Ok(self.account(address, number).await?.nonce)
+	}
+
+	pub async fn gas_price(&self) -> Result<U256> {
+		self.runtime_call("gas_price", Vec::new(), None).await
+	}
+
+	pub async fn send_raw_transaction(&self, bytes: Bytes) -> Result<H256> {
+		let transaction = decode_raw_transaction(&bytes.0)?;
+		let transaction_hash = transaction.hash();
+
+		// The extrinsic is passed through still encoded, as expected by the submission.
+		let extrinsic = self
+			.state_call(
+				"ConvertTransactionRuntimeApi_convert_transaction",
+				codec::Encode::encode(&transaction),
+				None,
+			)
+			.await?;
+		self.client
+			.request(
+				"author_submitExtrinsic",
+				vec![Value::String(format!("0x{}", hex::encode(extrinsic)))],
+			)
+			.await?;
+
+		Ok(transaction_hash)
+	}
+
+	pub async fn logs(&self, filter: Filter) -> Result<Vec<Log>> {
+		let mut logs = Vec::new();
+		if let Some(hash) = filter.block_hash {
+			let at = match self.mapped_block_hash(hash)? {
+				Some(at) => at,
+				None => return Ok(logs),
+			};
+			self.block_logs(&mut logs, &filter, at).await?;
+			return Ok(logs);
+		}
+
+		let best_number = self.block_number().await?.low_u64();
+		let to_number = match filter.to_block {
+			Some(number) => self.filter_block_number(number).await?.min(best_number),
+			None => best_number,
+		};
+		let from_number = match filter.from_block {
+			Some(number) => self.filter_block_number(number).await?,
+			None => best_number,
+		};
+
+		if to_number >= from_number && to_number - from_number >= self.max_logs_block_range {
+			return Err(Error::invalid_params(format!(
+				"block range greater than {}",
+				self.max_logs_block_range
+			)));
+		}
+
+		let begin = self.logs_timeout.map(|_| Instant::now());
+		for number in from_number..=to_number {
+			let at = self
+				.block_hash(Some(BlockNumber::Num(number)))
+				.await?
+				.ok_or_else(|| internal_err("header not found"))?;
+			self.block_logs(&mut logs, &filter, at).await?;
+			if logs.len() as u32 > self.max_past_logs {
+				return Err(internal_err(format!(
+					"query returned more than {} results",
+					self.max_past_logs
+				)));
+			}
+			if let (Some(begin), Some(timeout)) = (begin, self.logs_timeout) {
+				if begin.elapsed() > timeout {
+					return Err(internal_err(format!(
+						"query timeout of {} seconds exceeded",
+						timeout.as_secs()
+					)));
+				}
+			}
+		}
+		Ok(logs)
+	}
+
+	/// Adds the logs of the block `at` matching `filter` to `logs`.
+	async fn block_logs(&self, logs: &mut Vec<Log>, filter: &Filter, at: Value) -> Result<()> {
+		let block: Option<EthereumBlock> = self
+			.runtime_call("current_block", Vec::new(), Some(at.clone()))
+			.await?;
+		let block = match block {
+			Some(block) => block,
+			None => return Ok(()),
+		};
+
+		let params = FilteredParams::new(Some(filter.clone()));
+		let address_bloom_filter = FilteredParams::adresses_bloom_filter(&filter.address);
+		let topics_bloom_filter =
+			FilteredParams::topics_bloom_filter(&Some(params.flat_topics.clone()));
+		if !FilteredParams::address_in_bloom(block.header.logs_bloom, &address_bloom_filter)
+			|| !FilteredParams::topics_in_bloom(block.header.logs_bloom, &topics_bloom_filter)
+		{
+			return Ok(());
+		}
+
+		let statuses: Option<Vec<TransactionStatus>> = self
+			.runtime_call("current_transaction_statuses", Vec::new(), Some(at))
+			.await?;
+		let block_hash = block.header.hash();
+		let mut block_log_index = 0u32;
+		for status in statuses.unwrap_or_default() {
+			for (transaction_log_index, ethereum_log) in status.logs.into_iter().enumerate() {
+				let log = Log {
+					address: ethereum_log.address,
+					topics: ethereum_log.topics,
+					data: Bytes(ethereum_log.data),
+					block_hash: Some(block_hash),
+					block_number: Some(block.header.number),
+					transaction_hash: Some(status.transaction_hash),
+					transaction_index: Some(status.transaction_index.into()),
+					log_index: Some(block_log_index.into()),
+					transaction_log_index: Some(transaction_log_index.into()),
+					removed: false,
+				};
+				if params.filter_address(&log) && params.filter_topics(&log) {
+					logs.push(log);
+				}
+				block_log_index += 1;
+			}
+		}
+		Ok(())
+	}
+
+	/// Number of the block `number` of a filter.
+	async fn filter_block_number(&self, number: BlockNumber) -> Result<u64> {
+		match number {
+			BlockNumber::Num(number) => Ok(number),
+			BlockNumber::Earliest => Ok(0),
+			BlockNumber::Latest | BlockNumber::Pending => Ok(self.block_number().await?.low_u64()),
+			number => {
+				let at = self
+					.block_hash(Some(number))
+					.await?
+					.ok_or_else(|| internal_err("header not found"))?;
+				let header = self.client.request("chain_getHeader", vec![at]).await?;
+				header
+					.get("number")
+					.cloned()
+					.and_then(|number| serde_json::from_value::<U256>(number).ok())
+					.map(|number| number.low_u64())
+					.ok_or_else(|| internal_err("invalid header"))
+			}
+		}
+	}
+
+	/// Hash of the Substrate block of the Ethereum block `hash`, as given to the light client.
+	fn mapped_block_hash(&self, hash: H256) -> Result<Option<Value>> {
+		let mapping = self.mapping.as_ref().ok_or_else(|| {
+			Error::invalid_params("block hashes are not supported by the light client")
+		})?;
+		Ok(mapping
+			.block_hash(hash)?
+			.map(|hash| Value::String(format!("{:?}", hash))))
+	}
+
+	async fn account(&self, address: H160, number: Option<BlockNumber>) -> Result<Account> {
+		let at = self.block_hash(number).await?;
+		self.runtime_call("account_basic", codec::Encode::encode(&address), at)
+			.await
+	}
+
+	/// Hash of the Substrate block of `number`, or `None` for the best block.
+	async fn block_hash(&self, number: Option<BlockNumber>) -> Result<Option<Value>> {
+		let number = match number.unwrap_or_default() {
+			BlockNumber::Latest | BlockNumber::Pending => return Ok(None),
+			BlockNumber::Finalized | BlockNumber::Safe => {
+				return self
+					.client
+					.request("chain_getFinalizedHead", Vec::new())
+					.await
+					.map(Some);
+			}
+			BlockNumber::Earliest => 0,
+			BlockNumber::Num(number) => number,
+			BlockNumber::Hash { hash, .. } => {
+				return match self.mapped_block_hash(hash)? {
+					Some(hash) => Ok(Some(hash)),
+					None => Err(internal_err("header not found")),
+				};
+			}
+		};
+		match self
+			.client
+			.request("chain_getBlockHash", vec![number.into()])
+			.await?
+		{
+			Value::Null => Err(internal_err("header not found")),
+			hash => Ok(Some(hash)),
+		}
+	}
+
+	/// Calls `function` of the `EthereumRuntimeRPCApi`, decoding its result.
+	async fn runtime_call<R: Decode>(
+		&self,
+		function: &str,
+		args: Vec<u8>,
+		at: Option<Value>,
+	) -> Result<R> {
+		let result = self
+			.state_call(&format!("EthereumRuntimeRPCApi_{}", function), args, at)
+			.await?;
+		R::decode(&mut &result[..])
+			.map_err(|err| internal_err(format!("runtime result decoding failed: {:?}", err)))
+	}
+
+	async fn state_call(&self, name: &str, args: Vec<u8>, at: Option<Value>) -> Result<Vec<u8>> {
+		let mut params = vec![
+			Value::String(name.into()),
+			Value::String(format!("0x{}", hex::encode(args))),
+		];
+		params.extend(at);
+		let result = self.client.request("state_call", params).await?;
+		result
+			.as_str()
+			.and_then(|result| hex::decode(result.trim_start_matches("0x")).ok())
+			.ok_or_else(|| internal_err("invalid runtime call result"))
+	}
+}
+
+fn param<T: DeserializeOwned>(params: &[Value], index: usize) -> Result<T> {
+	serde_json::from_value(params.get(index).cloned().unwrap_or(Value::Null))
+		.map_err(|err| Error::invalid_params(format!("invalid parameter {}: {}", index, err)))
+}
+
+fn to_value<T: serde::Serialize>(value: T) -> Result<Value> {
+	serde_json::to_value(value).map_err(|err| internal_err(err.to_string()))
+}
+
+fn internal_err<T: ToString>(message: T) -> Error {
+	Error {
+		code: ErrorCode::InternalError,
+		message: message.to_string(),
+		data: None,
+	}
+}
+
+/// Decode a signed transaction, in its raw network encoding.
+fn decode_raw_transaction(bytes: &[u8]) -> Result<EthereumTransaction> {
+	let first = match bytes.get(0) {
+		Some(first) => first,
+		None => return Err(internal_err("transaction data is empty")),
+	};
+	if first > &0x7f {
+		// Legacy transaction. Decode and wrap in envelope.
+		rlp::decode::<ethereum::TransactionV0>(bytes)
+			.map(EthereumTransaction::Legacy)
+			.map_err(|_| internal_err("decode transaction failed"))
+	} else {
+		// Typed transaction, re-encoded as a valid rlp for the `ethereum` crate.
+		let extend = rlp::encode(&bytes);
+		rlp::decode::<EthereumTransaction>(&extend[..])
+			.map_err(|_| internal_err("decode transaction failed"))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use futures::{executor::block_on, FutureExt};
+	use std::{cell::RefCell, rc::Rc};
+
+	/// Light client answering with `respond`, and recording the requests.
+	#[derive(Clone)]
+	struct MockClient {
+		requests: Rc<RefCell<Vec<(String, Vec<Value>)>>>,
+		respond: fn(&str, &[Value]) -> Value,
+	}
+
+	impl MockClient {
+		fn new(respond: fn(&str, &[Value]) -> Value) -> Self {
+			Self {
+				requests: Default::default(),
+				respond,
+			}
+		}
+	}
+
+	impl LightClient for MockClient {
+		fn request(
+			&self,
+			method: &str,
+			params: Vec<Value>,
+		) -> LocalBoxFuture<'static, Result<Value>> {
+			let result = (self.respond)(method, &params);
+			self.requests.borrow_mut().push((method.into(), params));
+			futures::future::ready(Ok(result)).boxed_local()
+		}
+	}
+
+	fn hex_value(bytes: Vec<u8>) -> Value {
+		Value::String(format!("0x{}", hex::encode(bytes)))
+	}
+
+	#[test]
+	fn reads_the_balance_at_the_requested_block() {
+		let client = MockClient::new(|method, _| match method {
+			"chain_getBlockHash" => Value::String(format!("{:?}", H256::repeat_byte(0x22))),
+			"state_call" => hex_value((U256::from(3), U256::from(1_000)).encode()),
+			_ => Value::Null,
+		});
+		let eth = LightEth::new(client.clone());
+
+		let balance = block_on(eth.handle(
+			"eth_getBalance",
+			vec![
+				Value::String(format!("{:?}", H160::repeat_byte(0x11))),
+				Value::String("0x2a".into()),
+			],
+		));
+		assert_eq!(balance, Ok(Value::String("0x3e8".into())));
+
+		let requests = client.requests.borrow();
+		assert_eq!(requests[0], ("chain_getBlockHash".into(), vec![42.into()]));
+		assert_eq!(
+			requests[1],
+			(
+				"state_call".into(),
+				vec![
+					Value::String("EthereumRuntimeRPCApi_account_basic".into()),
+					hex_value(H160::repeat_byte(0x11).encode()),
+					Value::String(format!("{:?}", H256::repeat_byte(0x22))),
+				]
+			)
+		);
+	}
+
+	#[test]
+	fn submits_the_converted_transaction() {
+		let client = MockClient::new(|method, _| match method {
+			"state_call" => hex_value(vec![0x08, 0xaa, 0xbb]),
+			"author_submitExtrinsic" => Value::String(format!("{:?}", H256::zero())),
+			_ => Value::Null,
+		});
+		let eth = LightEth::new(client.clone());
+
+		let transaction = ethereum::LegacyTransaction {
+			nonce: U256::zero(),
+			gas_price: U256::from(1),
+			gas_limit: U256::from(21_000),
+			action: ethereum::TransactionAction::Call(H160::repeat_byte(0x11)),
+			value: U256::from(1),
+			input: Vec::new(),
+			signature: ethereum::TransactionSignature::new(
+				27,
+				H256::repeat_byte(0x01),
+				H256::repeat_byte(0x01),
+			)
+			.unwrap(),
+		};
+		let raw = rlp::encode(&transaction).to_vec();
+
+		let hash = block_on(eth.send_raw_transaction(Bytes(raw)));
+		assert_eq!(
+			hash,
+			Ok(EthereumTransaction::Legacy(transaction.clone()).hash())
+		);
+
+		let requests = client.requests.borrow();
+		assert_eq!(
+			requests[0].1[1],
+			hex_value(EthereumTransaction::Legacy(transaction).encode())
+		);
+		assert_eq!(
+			requests[1],
+			(
+				"author_submitExtrinsic".into(),
+				vec![Value::String("0x08aabb".into())]
+			)
+		);
+	}
+
+	/// Mapping of every Ethereum block to the Substrate block `0x33..33`.
+	struct MockMapping;
+
+	impl BlockMapping for MockMapping {
+		fn block_hash(&self, _ethereum_block_hash: H256) -> Result<Option<H256>> {
+			Ok(Some(H256::repeat_byte(0x33)))
+		}
+	}
+
+	fn log(address: H160) -> ethereum::Log {
+		ethereum::Log {
+			address,
+			topics: vec![H256::repeat_byte(0x44)],
+			data: vec![0x01],
+		}
+	}
+
+	#[test]
+	fn filters_the_logs_of_a_mapped_block() {
+		let client = MockClient::new(|method, params| match (method, params[0].as_str()) {
+			("state_call", Some("EthereumRuntimeRPCApi_current_block")) => {
+				let mut logs_bloom = Bloom::default();
+				logs_bloom.accrue(ethereum_types::BloomInput::Raw(
+					H160::repeat_byte(0x11).as_bytes(),
+				));
+				let block = EthereumBlock::new(
+					ethereum::PartialHeader {
+						parent_hash: H256::zero(),
+						beneficiary: H160::zero(),
+						state_root: H256::zero(),
+						receipts_root: H256::zero(),
+						logs_bloom,
+						difficulty: U256::zero(),
+						number: U256::from(7),
+						gas_limit: U256::zero(),
+						gas_used: U256::zero(),
+						timestamp: 0,
+						extra_data: Vec::new(),
+						mix_hash: H256::zero(),
+						nonce: ethereum_types::H64::zero(),
+					},
+					Vec::new(),
+					Vec::new(),
+				);
+				hex_value(Some(block).encode())
+			}
+			("state_call", Some("EthereumRuntimeRPCApi_current_transaction_statuses")) => {
+				// Encoded as `fp_rpc::TransactionStatus`.
+				let status = (
+					H256::repeat_byte(0x55),
+					1u32,
+					H160::zero(),
+					Option::<H160>::None,
+					Option::<H160>::None,
+					vec![log(H160::repeat_byte(0x22)), log(H160::repeat_byte(0x11))],
+					Bloom::default(),
+				);
+				hex_value(Some(vec![status]).encode())
+			}
+			_ => Value::Null,
+		});
+		let eth = LightEth::new(client.clone()).with_mapping(MockMapping);
+
+		let logs = block_on(eth.handle(
+			"eth_getLogs",
+			vec![serde_json::json!({
+				"blockHash": format!("{:?}", H256::repeat_byte(0x66)),
+				"address": format!("{:?}", H160::repeat_byte(0x11)),
+			})],
+		))
+		.unwrap();
+		let logs = logs.as_array().unwrap();
+		assert_eq!(logs.len(), 1);
+		assert_eq!(logs[0]["address"], format!("{:?}", H160::repeat_byte(0x11)));
+		assert_eq!(logs[0]["blockNumber"], "0x7");
+		assert_eq!(logs[0]["logIndex"], "0x1");
+		assert_eq!(logs[0]["transactionIndex"], "0x1");
+
+		// The runtime calls are made at the mapped Substrate block.
+		let requests = client.requests.borrow();
+		assert_eq!(
+			requests[0].1[2],
+			Value::String(format!("{:?}", H256::repeat_byte(0x33)))
+		);
+	}
+
+	#[test]
+	fn rejects_block_hashes_without_mapping() {
+		let eth = LightEth::new(MockClient::new(|_, _| Value::Null));
+		let result = block_on(eth.handle(
+			"eth_getBalance",
+			vec![
+				Value::String(format!("{:?}", H160::repeat_byte(0x11))),
+				serde_json::json!({ "blockHash": format!("{:?}", H256::repeat_byte(0x22)) }),
+			],
+		));
+		assert_eq!(
+			result.unwrap_err().message,
+			"block hashes are not supported by the light client"
+		);
+	}
+
+	#[test]
+	fn queries_the_logs_of_each_block_of_a_range() {
+		let client = MockClient::new(|method, _| match method {
+			"chain_getHeader" => serde_json::json!({ "number": "0xa" }),
+			"chain_getBlockHash" => Value::String(format!("{:?}", H256::repeat_byte(0x22))),
+			// No Ethereum block.
+			"state_call" => hex_value(Option::<EthereumBlock>::None.encode()),
+			_ => Value::Null,
+		});
+		let eth = LightEth::new(client.clone());
+
+		let logs = block_on(eth.handle(
+			"eth_getLogs",
+			vec![serde_json::json!({ "fromBlock": "0x8", "toBlock": "latest" })],
+		));
+		assert_eq!(logs, Ok(Value::Array(Vec::new())));
+
+		let requests = client.requests.borrow();
+		let block_numbers: Vec<_> = requests
+			.iter()
+			.filter(|(method, _)| method == "chain_getBlockHash")
+			.map(|(_, params)| params[0].clone())
+			.collect();
+		assert_eq!(block_numbers, vec![8.into(), 9.into(), 10.into()]);
+	}
+
+	#[test]
+	fn rejects_the_logs_of_too_wide_ranges() {
+		let client = MockClient::new(|method, _| match method {
+			"chain_getHeader" => serde_json::json!({ "number": "0x3e8" }),
+			_ => Value::Null,
+		});
+		let eth = LightEth::new(client.clone()).with_max_logs_block_range(100);
+
+		let logs = block_on(eth.handle(
+			"eth_getLogs",
+			vec![serde_json::json!({ "fromBlock": "0x0", "toBlock": "0x64" })],
+		));
+		assert_eq!(
+			logs,
+			Err(Error::invalid_params("block range greater than 100"))
+		);
+		// No block of the range is queried.
+		assert!(client
+			.requests
+			.borrow()
+			.iter()
+			.all(|(method, _)| method != "chain_getBlockHash"));
+	}
+
+	#[test]
+	fn rejects_unsupported_methods() {
+		let eth = LightEth::new(MockClient::new(|_, _| Value::Null));
+		assert_eq!(
+			block_on(eth.handle("eth_call", Vec::new())),
+			Err(Error::method_not_found())
+		);
+	}
+}
@@ -0,0 +1,195 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Ethereum JSON-RPC server of a Frontier node, run out of the process of the node.
+//!
+//! `frontier-rpc` serves the methods of `fc-rpc-light` from the Substrate JSON-RPC HTTP server
+//! of a running node, so that the log queries and the account reads are isolated from the
+//! process of a validator. The state of past blocks is only served by an archive node.
+//!
+//! Only these methods are served: `eth_blockNumber`, `eth_chainId`, `eth_getBalance`,
+//! `eth_getTransactionCount`, `eth_gasPrice`, `eth_getLogs` and `eth_sendRawTransaction`. The
+//! calls, blocks, receipts and traces are still served by the Ethereum RPC of a Frontier node.
+//! The log queries are bounded by `--max-past-logs`, `--max-logs-block-range` and
+//! `--logs-timeout`.
+//!
+//! The blocks given by their Ethereum hash are found in the Frontier backend of
+//! `--frontier-db-path`, which the server maps from the headers of the node. As the server
+//! writes it, it can't be the database of a running node, but a copy of it saves the first
+//! mapping.
+
+mod node;
+mod sync;
+
+use std::{net::SocketAddr, path::PathBuf, rc::Rc, sync::Arc, time::Duration};
+
+use clap::Parser;
+use futures::{
+	channel::{mpsc, oneshot},
+	StreamExt,
+};
+use jsonrpc_core::{Error, IoHandler, Params, Value};
+use sp_core::H256;
+use sp_runtime::{generic, traits::BlakeTwo256, OpaqueExtrinsic};
+
+use fc_rpc_light::{BlockMapping, LightEth};
+
+use crate::node::{internal_err, NodeClient};
+
+/// Blocks of the node, of which only the headers are read.
+pub type Block = generic::Block<generic::Header<u32, BlakeTwo256>, OpaqueExtrinsic>;
+
+/// Request to the Ethereum RPC, with the sender of its result.
+type Request = (
+	&'static str,
+	Vec<Value>,
+	oneshot::Sender<jsonrpc_core::Result<Value>>,
+);
+
+#[derive(Debug, Parser)]
+#[clap(
+	name = "frontier-rpc",
+	about = "Ethereum JSON-RPC server of a Frontier node"
+)]
+struct Cli {
+	/// URL of the Substrate JSON-RPC HTTP server of the node.
+	#[clap(long, default_value = "http://127.0.0.1:9933")]
+	node_url: String,
+
+	/// Directory of the Frontier backend mapping the Ethereum blocks of the node.
+	#[clap(long)]
+	frontier_db_path: PathBuf,
+
+	/// Address of the Ethereum JSON-RPC HTTP server.
+	#[clap(long, default_value = "127.0.0.1:8545")]
+	rpc_addr: SocketAddr,
+
+	/// Number of threads of the Ethereum JSON-RPC HTTP server.
+	#[clap(long, default_value = "4")]
+	rpc_threads: usize,
+
+	/// Maximum number of logs in a query.
+	#[clap(long, default_value = "10000")]
+	max_past_logs: u32,
+
+	/// Maximum number of blocks of the range of a log query.
+	#[clap(long, default_value = "1024")]
+	max_logs_block_range: u64,
+
+	/// Timeout in seconds of a log query.
+	#[clap(long, default_value = "10")]
+	logs_timeout: u64,
+
+	/// Interval between two mappings of the new blocks of the node, in milliseconds.
+	#[clap(long, default_value = "1000")]
+	sync_interval: u64,
+}
+
+/// Finds the blocks given by their Ethereum hash in the Frontier backend.
+struct BackendMapping(Arc<fc_db::Backend<Block>>);
+
+impl BlockMapping for BackendMapping {
+	fn block_hash(&self, ethereum_block_hash: H256) -> jsonrpc_core::Result<Option<H256>> {
+		self.0
+			.mapping()
+			.block_hash(&ethereum_block_hash)
+			.map_err(internal_err)
+	}
+}
+
+fn main() -> Result<(), String> {
+	env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+	let cli = Cli::parse();
+
+	let backend = Arc::new(fc_db::Backend::<Block>::new(&fc_db::DatabaseSettings {
+		source: fc_db::DatabaseSource::Auto {
+			rocksdb_path: cli.frontier_db_path.join("db"),
+			paritydb_path: cli.frontier_db_path.join("paritydb"),
+			cache_size: 0,
+		},
+		// Transactions aren't looked up by their hash.
+		transaction_indexing: fc_db::TransactionIndexing::Off,
+		archive_raw_transactions: false,
+		allow_large_migrations: true,
+		replica_path: None,
+	})?);
+
+	let (sender, mut requests) = mpsc::unbounded::<Request>();
+	let _server = jsonrpc_http_server::ServerBuilder::new(io_handler(sender))
+		.threads(cli.rpc_threads)
+		.start_http(&cli.rpc_addr)
+		.map_err(|err| {
+			format!(
+				"failed to start the RPC server on {}: {}",
+				cli.rpc_addr, err
+			)
+		})?;
+	log::info!(target: "frontier-rpc", "Ethereum RPC served on {}", cli.rpc_addr);
+
+	let runtime = tokio::runtime::Runtime::new().map_err(|err| err.to_string())?;
+	// The requests to the node can't be sent across threads, so all the requests are handled on
+	// this thread.
+	let local = tokio::task::LocalSet::new();
+	local.block_on(&runtime, async move {
+		let client = NodeClient::connect(&cli.node_url).await?;
+		tokio::task::spawn_local(sync::run(
+			client.clone(),
+			backend.clone(),
+			Duration::from_millis(cli.sync_interval),
+		));
+
+		let eth = Rc::new(
+			LightEth::new(client)
+				.with_mapping(BackendMapping(backend))
+				.with_max_past_logs(cli.max_past_logs)
+				.with_max_logs_block_range(cli.max_logs_block_range)
+				.with_logs_timeout(Duration::from_secs(cli.logs_timeout)),
+		);
+		while let Some((method, params, result)) = requests.next().await {
+			let eth = eth.clone();
+			tokio::task::spawn_local(async move {
+				let _ = result.send(eth.handle(method, params).await);
+			});
+		}
+		Ok(())
+	})
+}
+
+/// Forwards the Ethereum RPC requests of the HTTP server to `sender`.
+fn io_handler(sender: mpsc::UnboundedSender<Request>) -> IoHandler {
+	let mut io = IoHandler::new();
+	for &method in fc_rpc_light::METHODS {
+		let sender = sender.clone();
+		io.add_method(method, move |params: Params| {
+			let sender = sender.clone();
+			async move {
+				let params = match params {
+					Params::Array(params) => params,
+					Params::None => Vec::new(),
+					Params::Map(_) => return Err(Error::invalid_params("expected an array")),
+				};
+				let (result_sender, result) = oneshot::channel();
+				sender
+					.unbounded_send((method, params, result_sender))
+					.map_err(|_| internal_err("server stopped"))?;
+				result.await.map_err(|_| internal_err("request dropped"))?
+			}
+		});
+	}
+	io
+}
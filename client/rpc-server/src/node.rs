@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use futures::{future::LocalBoxFuture, FutureExt};
+use jsonrpc_core::{Error, ErrorCode, Params, Result, Value};
+use jsonrpc_core_client::{transports::http, RawClient, RpcError};
+
+use fc_rpc_light::LightClient;
+
+/// Client of the Substrate JSON-RPC HTTP server of a node.
+#[derive(Clone)]
+pub struct NodeClient(RawClient);
+
+impl NodeClient {
+	/// Connects to the node serving its RPC at `url`.
+	///
+	/// Must be called within a Tokio runtime, which runs the requests.
+	pub async fn connect(url: &str) -> std::result::Result<Self, String> {
+		http::connect::<RawClient>(url)
+			.await
+			.map(Self)
+			.map_err(|err| format!("failed to connect to {}: {}", url, err))
+	}
+}
+
+impl LightClient for NodeClient {
+	fn request(&self, method: &str, params: Vec<Value>) -> LocalBoxFuture<'static, Result<Value>> {
+		let request = self.0.call_method(method, Params::Array(params));
+		async move {
+			request.await.map_err(|err| match err {
+				// The errors of the node are forwarded as is.
+				RpcError::JsonRpcError(err) => err,
+				err => internal_err(format!("node request failed: {}", err)),
+			})
+		}
+		.boxed_local()
+	}
+}
+
+pub fn internal_err<T: ToString>(message: T) -> Error {
+	Error {
+		code: ErrorCode::InternalError,
+		message: message.to_string(),
+		data: None,
+	}
+}
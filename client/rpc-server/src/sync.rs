@@ -0,0 +1,207 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{sync::Arc, time::Duration};
+
+use codec::Decode;
+use fp_consensus::FindLogError;
+use jsonrpc_core::Value;
+use serde::de::DeserializeOwned;
+use sp_core::H256;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, Zero};
+
+use fc_rpc_light::LightClient;
+
+use crate::{node::NodeClient, Block};
+
+type Header = <Block as BlockT>::Header;
+
+/// Number of blocks mapped before the sync tip is persisted.
+const SYNC_CHUNK: u64 = 256;
+
+/// Maps the blocks of the node in `backend` every `interval`.
+///
+/// The blocks of the best chain of the node are mapped forward, from the oldest block not
+/// mapped yet, in chunks after each of which the last mapped block is persisted as the sync
+/// tip. An interrupted sync resumes from the tip, and a new best fork is mapped from the
+/// block it forks from.
+pub async fn run(client: NodeClient, backend: Arc<fc_db::Backend<Block>>, interval: Duration) {
+	loop {
+		if let Err(err) = sync(&client, &backend).await {
+			log::warn!(target: "frontier-rpc", "Failed to map the blocks of the node: {}", err);
+		}
+		tokio::time::sleep(interval).await;
+	}
+}
+
+async fn sync(client: &NodeClient, backend: &fc_db::Backend<Block>) -> Result<(), String> {
+	let best: Header = request(client, "chain_getHeader", Vec::new()).await?;
+	let best_number = u64::from(*best.number());
+
+	let mut next = first_unmapped(client, backend, best_number).await?;
+	let mut tip: Option<(u64, H256)> = None;
+	'chunks: while next <= best_number {
+		let last = best_number.min(next + SYNC_CHUNK - 1);
+		for number in next..=last {
+			let hash = match canonical_hash(client, number).await? {
+				Some(hash) => hash,
+				// The best chain was reorganized, the next sync resumes from its new blocks.
+				None => break 'chunks,
+			};
+			let header = header(client, hash).await?;
+			if tip.map_or(false, |(_, tip_hash)| tip_hash != *header.parent_hash()) {
+				break 'chunks;
+			}
+			sync_block(client, backend, hash, &header).await?;
+			tip = Some((number, hash));
+		}
+		if let Some((number, hash)) = tip {
+			backend.meta().write_forward_sync_tip(number, hash)?;
+		}
+		next = last + 1;
+	}
+	if let Some((number, hash)) = tip {
+		backend.meta().write_forward_sync_tip(number, hash)?;
+	}
+	Ok(())
+}
+
+/// Number of the oldest block of the best chain which isn't mapped, or the one after
+/// `best_number` when they all are.
+///
+/// The sync tip is used when it is still on the best chain. Otherwise, as the mapped blocks of
+/// the best chain are the ones before a given block, it is found by a binary search, which
+/// also resumes the sync of a database copied from a node.
+async fn first_unmapped(
+	client: &NodeClient,
+	backend: &fc_db::Backend<Block>,
+	best_number: u64,
+) -> Result<u64, String> {
+	if let Some((number, hash)) = backend.meta().forward_sync_tip()? {
+		if number <= best_number && canonical_hash(client, number).await? == Some(hash) {
+			return Ok(number + 1);
+		}
+	}
+
+	let (mut low, mut high) = (0, best_number + 1);
+	while low < high {
+		let middle = low + (high - low) / 2;
+		let synced = match canonical_hash(client, middle).await? {
+			Some(hash) => backend.mapping().is_synced(&hash)?,
+			None => false,
+		};
+		if synced {
+			low = middle + 1;
+		} else {
+			high = middle;
+		}
+	}
+	Ok(low)
+}
+
+/// Hash of the block `number` of the best chain of the node.
+async fn canonical_hash(client: &NodeClient, number: u64) -> Result<Option<H256>, String> {
+	request(client, "chain_getBlockHash", vec![number.into()]).await
+}
+
+async fn header(client: &NodeClient, hash: H256) -> Result<Header, String> {
+	request(client, "chain_getHeader", vec![hash_value(hash)]).await
+}
+
+/// Maps the block `hash` from the Frontier digest of its header, as `fc_mapping_sync`.
+async fn sync_block(
+	client: &NodeClient,
+	backend: &fc_db::Backend<Block>,
+	hash: H256,
+	header: &Header,
+) -> Result<(), String> {
+	match fp_consensus::find_log(header.digest()) {
+		Ok(log) => {
+			let post_hashes = log.into_hashes();
+			backend.mapping().write_hashes(fc_db::MappingCommitment {
+				block_hash: hash,
+				ethereum_block_hash: post_hashes.block_hash,
+				ethereum_transaction_hashes: post_hashes.transaction_hashes,
+			})
+		}
+		// The genesis block has no digest, its Ethereum block is read from the runtime.
+		Err(FindLogError::NotFound) if header.number().is_zero() => {
+			match genesis_block_hash(client, hash).await {
+				Ok(ethereum_block_hash) => {
+					backend.mapping().write_hashes(fc_db::MappingCommitment {
+						block_hash: hash,
+						ethereum_block_hash,
+						ethereum_transaction_hashes: Vec::new(),
+					})
+				}
+				Err(err) => {
+					log::debug!(
+						target: "frontier-rpc",
+						"No Ethereum genesis block: {}",
+						err
+					);
+					backend.mapping().write_none(hash)
+				}
+			}
+		}
+		Err(FindLogError::NotFound) => backend.mapping().write_none(hash),
+		// The block can't be mapped, but must not stop the sync of the next ones.
+		Err(FindLogError::MultipleLogs) => {
+			log::warn!(
+				target: "frontier-rpc",
+				"Multiple Frontier logs in block {:?}, mapped without an Ethereum block",
+				hash
+			);
+			backend.mapping().write_none(hash)
+		}
+	}
+}
+
+async fn genesis_block_hash(client: &NodeClient, hash: H256) -> Result<H256, String> {
+	let result: String = request(
+		client,
+		"state_call",
+		vec![
+			Value::String("EthereumRuntimeRPCApi_current_block".into()),
+			Value::String("0x".into()),
+			hash_value(hash),
+		],
+	)
+	.await?;
+	let result = hex::decode(result.trim_start_matches("0x")).map_err(|err| err.to_string())?;
+	Option::<ethereum::BlockV2>::decode(&mut &result[..])
+		.map_err(|err| format!("{:?}", err))?
+		.map(|block| block.header.hash())
+		.ok_or_else(|| "Ethereum genesis block not found".to_string())
+}
+
+async fn request<T: DeserializeOwned>(
+	client: &NodeClient,
+	method: &str,
+	params: Vec<Value>,
+) -> Result<T, String> {
+	let result = client
+		.request(method, params)
+		.await
+		.map_err(|err| format!("{}: {}", method, err.message))?;
+	serde_json::from_value(result).map_err(|err| format!("{}: {}", method, err))
+}
+
+fn hash_value(hash: H256) -> Value {
+	Value::String(format!("{:?}", hash))
+}
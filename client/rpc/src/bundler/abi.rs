@@ -0,0 +1,293 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+
+//! Encoding of the calls to the EntryPoint v0.6, and decoding of its revert data and events.
+
+use ethereum_types::{H160, H256, U256};
+use sp_core::hashing::keccak_256;
+
+use fc_rpc_core::types::UserOperation;
+
+/// Solidity type of a user operation.
+const USER_OPERATION: &str =
+	"(address,uint256,bytes,bytes,uint256,uint256,uint256,uint256,uint256,bytes,bytes)";
+
+/// Outcome of `simulateValidation`, which always reverts.
+#[derive(Debug, PartialEq)]
+pub enum Validation {
+	/// The operation is valid, with the gas it uses before its call, including its
+	/// `preVerificationGas`, and whether its signature failed.
+	Valid { pre_op_gas: U256, sig_failed: bool },
+	/// The operation is rejected, for a reason.
+	Failed(String),
+}
+
+/// Selector of a function or of an error, from its signature.
+pub fn selector(signature: &str) -> [u8; 4] {
+	let mut selector = [0u8; 4];
+	selector.copy_from_slice(&keccak_256(signature.as_bytes())[..4]);
+	selector
+}
+
+fn uint(value: U256) -> [u8; 32] {
+	let mut word = [0u8; 32];
+	value.to_big_endian(&mut word);
+	word
+}
+
+fn address(value: H160) -> [u8; 32] {
+	H256::from(value).0
+}
+
+/// Encodes a user operation as a tuple.
+fn user_operation(op: &UserOperation) -> Vec<u8> {
+	const HEAD_WORDS: usize = 11;
+
+	let mut tails = Vec::new();
+	// Appends `bytes` to the tails, returning their offset.
+	let mut tail = |bytes: &[u8]| {
+		let offset = HEAD_WORDS * 32 + tails.len();
+		tails.extend_from_slice(&uint(U256::from(bytes.len())));
+		tails.extend_from_slice(bytes);
+		tails.resize((tails.len() + 31) / 32 * 32, 0);
+		uint(U256::from(offset))
+	};
+	let init_code = tail(&op.init_code.0);
+	let call_data = tail(&op.call_data.0);
+	let paymaster_and_data = tail(&op.paymaster_and_data.0);
+	let signature = tail(&op.signature.0);
+
+	[
+		address(op.sender),
+		uint(op.nonce),
+		init_code,
+		call_data,
+		uint(op.call_gas_limit),
+		uint(op.verification_gas_limit),
+		uint(op.pre_verification_gas),
+		uint(op.max_fee_per_gas),
+		uint(op.max_priority_fee_per_gas),
+		paymaster_and_data,
+		signature,
+	]
+	.concat()
+	.into_iter()
+	.chain(tails)
+	.collect()
+}
+
+/// Input of `handleOps`, bundling `op` alone and paying its fees to `beneficiary`.
+pub fn handle_ops(op: &UserOperation, beneficiary: H160) -> Vec<u8> {
+	[
+		&selector(&format!("handleOps({}[],address)", USER_OPERATION))[..],
+		// Offset of the operations, their length, and the offset of the operation.
+		&uint(U256::from(64)),
+		&address(beneficiary),
+		&uint(U256::one()),
+		&uint(U256::from(32)),
+		&user_operation(op),
+	]
+	.concat()
+}
+
+/// Input of `simulateValidation` of `op`.
+pub fn simulate_validation(op: &UserOperation) -> Vec<u8> {
+	[
+		&selector(&format!("simulateValidation({})", USER_OPERATION))[..],
+		&uint(U256::from(32)),
+		&user_operation(op),
+	]
+	.concat()
+}
+
+/// Hash of `op`, as returned by `getUserOpHash` of the EntryPoint at `entry_point`.
+pub fn user_operation_hash(op: &UserOperation, entry_point: H160, chain_id: u64) -> H256 {
+	let packed = [
+		address(op.sender),
+		uint(op.nonce),
+		keccak_256(&op.init_code.0),
+		keccak_256(&op.call_data.0),
+		uint(op.call_gas_limit),
+		uint(op.verification_gas_limit),
+		uint(op.pre_verification_gas),
+		uint(op.max_fee_per_gas),
+		uint(op.max_priority_fee_per_gas),
+		keccak_256(&op.paymaster_and_data.0),
+	]
+	.concat();
+	H256(keccak_256(
+		&[
+			keccak_256(&packed),
+			address(entry_point),
+			uint(U256::from(chain_id)),
+		]
+		.concat(),
+	))
+}
+
+fn word(data: &[u8], index: usize) -> Option<U256> {
+	data.get(index * 32..(index + 1) * 32)
+		.map(U256::from_big_endian)
+}
+
+fn offset(data: &[u8], index: usize) -> Option<usize> {
+	let offset = word(data, index)?;
+	(offset <= U256::from(data.len())).then(|| offset.as_usize())
+}
+
+/// Arguments of the error with `signature` in revert `data`.
+fn error_arguments<'a>(data: &'a [u8], signature: &str) -> Option<&'a [u8]> {
+	data.strip_prefix(&selector(signature)[..])
+}
+
+/// Reason of the `FailedOp` error in revert `data`.
+pub fn failed_op(data: &[u8]) -> Option<String> {
+	let arguments = error_arguments(data, "FailedOp(uint256,string)")?;
+	let reason = &arguments[offset(arguments, 1)?..];
+	let length = word(reason, 0)?;
+	if length > U256::from(reason.len()) {
+		return None;
+	}
+	let reason = reason.get(32..32 + length.as_usize())?;
+	Some(String::from_utf8_lossy(reason).into_owned())
+}
+
+/// Outcome of `simulateValidation` from its revert `data`.
+pub fn validation(data: &[u8]) -> Option<Validation> {
+	if let Some(reason) = failed_op(data) {
+		return Some(Validation::Failed(reason));
+	}
+	// Both results start with the same return info.
+	let arguments = error_arguments(
+		data,
+		"ValidationResult((uint256,uint256,bool,uint48,uint48,bytes),(uint256,uint256),(uint256,uint256),(uint256,uint256))",
+	)
+	.or_else(|| {
+		error_arguments(
+			data,
+			"ValidationResultWithAggregation((uint256,uint256,bool,uint48,uint48,bytes),(uint256,uint256),(uint256,uint256),(uint256,uint256),(address,(uint256,uint256)))",
+		)
+	})?;
+	let return_info = &arguments[offset(arguments, 0)?..];
+	Some(Validation::Valid {
+		pre_op_gas: word(return_info, 0)?,
+		sig_failed: !word(return_info, 2)?.is_zero(),
+	})
+}
+
+/// Topic of the `UserOperationEvent` event, emitted for each handled operation.
+pub fn user_operation_event() -> H256 {
+	H256(keccak_256(
+		b"UserOperationEvent(bytes32,address,address,uint256,bool,uint256,uint256)",
+	))
+}
+
+/// Topic of the `BeforeExecution` event, emitted before the calls of the handled operations.
+pub fn before_execution_event() -> H256 {
+	H256(keccak_256(b"BeforeExecution()"))
+}
+
+/// Nonce, success, actual gas cost and actual gas used of the `UserOperationEvent` with `data`.
+pub fn user_operation_event_data(data: &[u8]) -> Option<(U256, bool, U256, U256)> {
+	Some((
+		word(data, 0)?,
+		!word(data, 1)?.is_zero(),
+		word(data, 2)?,
+		word(data, 3)?,
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use fc_rpc_core::types::Bytes;
+
+	fn error(signature: &str, arguments: &[[u8; 32]]) -> Vec<u8> {
+		[&selector(signature)[..], &arguments.concat()].concat()
+	}
+
+	#[test]
+	fn handle_ops_selector() {
+		assert_eq!(
+			&handle_ops(&UserOperation::default(), H160::zero())[..4],
+			&[0x1f, 0xad, 0x94, 0x8c]
+		);
+	}
+
+	#[test]
+	fn encodes_the_bytes_of_the_operation_after_its_head() {
+		let op = UserOperation {
+			call_data: Bytes(vec![0xab; 33]),
+			signature: Bytes(vec![0xcd]),
+			..Default::default()
+		};
+		let encoded = user_operation(&op);
+
+		// The empty init code, the call data over two words, the empty paymaster data and
+		// the signature.
+		assert_eq!(encoded.len(), 11 * 32 + 32 + 3 * 32 + 32 + 2 * 32);
+		assert_eq!(word(&encoded, 2), Some(U256::from(11 * 32)));
+		assert_eq!(word(&encoded, 3), Some(U256::from(12 * 32)));
+		assert_eq!(word(&encoded, 12), Some(U256::from(33)));
+		assert_eq!(word(&encoded, 9), Some(U256::from(15 * 32)));
+		assert_eq!(word(&encoded, 10), Some(U256::from(16 * 32)));
+		assert_eq!(encoded[17 * 32], 0xcd);
+	}
+
+	#[test]
+	fn decodes_failed_operations() {
+		let mut reason = uint(U256::zero());
+		reason[..8].copy_from_slice(b"AA21 fee");
+		let data = error(
+			"FailedOp(uint256,string)",
+			&[
+				uint(U256::zero()),
+				uint(U256::from(64)),
+				uint(U256::from(8)),
+				reason,
+			],
+		);
+
+		assert_eq!(failed_op(&data), Some("AA21 fee".to_string()));
+		assert_eq!(
+			validation(&data),
+			Some(Validation::Failed("AA21 fee".to_string()))
+		);
+		assert_eq!(failed_op(&data[..data.len() - 32]), None);
+	}
+
+	#[test]
+	fn decodes_validation_results() {
+		let signature = "ValidationResult((uint256,uint256,bool,uint48,uint48,bytes),(uint256,uint256),(uint256,uint256),(uint256,uint256))";
+		let mut arguments = vec![uint(U256::from(7 * 32))];
+		arguments.resize(7, uint(U256::zero()));
+		arguments.extend([
+			uint(U256::from(50_000)),
+			uint(U256::zero()),
+			uint(U256::one()),
+		]);
+
+		assert_eq!(
+			validation(&error(signature, &arguments)),
+			Some(Validation::Valid {
+				pre_op_gas: U256::from(50_000),
+				sig_failed: true,
+			})
+		);
+		assert_eq!(validation(&[0x08, 0xc3, 0x79, 0xa0]), None);
+	}
+}
@@ -0,0 +1,864 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+
+mod abi;
+
+use std::{
+	collections::BTreeSet,
+	marker::PhantomData,
+	sync::{Arc, Mutex},
+};
+
+use ethereum::{EIP1559TransactionMessage, TransactionAction};
+use ethereum_types::{BloomInput, H160, H256, U256};
+use evm::ExitReason;
+use futures::future::{FutureExt, TryFutureExt};
+use jsonrpc_core::{futures::future, BoxFuture, Error, Result};
+
+use sc_client_api::backend::{Backend, StateBackend, StorageProvider};
+use sc_transaction_pool::{ChainApi, Pool};
+use sc_transaction_pool_api::TransactionPool;
+use sp_api::ProvideRuntimeApi;
+use sp_block_builder::BlockBuilder as BlockBuilderApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{BlakeTwo256, Block as BlockT, NumberFor, One, Saturating, UniqueSaturatedInto},
+	transaction_validity::TransactionSource,
+};
+
+use fc_rpc_core::{
+	types::{
+		BlockNumber, Bytes, CallRequest, Log, Receipt, TransactionMessage, UserOperation,
+		UserOperationGasEstimate, UserOperationReceipt,
+	},
+	BundlerApi,
+};
+use fp_rpc::{ConvertTransaction, ConvertTransactionRuntimeApi, EthereumRuntimeRPCApi};
+
+use self::abi::Validation;
+use crate::{
+	eth::{
+		check_transaction, error_on_execution_failure, fee_details, receipt_build, submit_error,
+		transaction_extrinsic, EthBlockDataCacheTask, ExecuteInBlock, ExecutionPool,
+	},
+	frontier_backend_client, internal_err,
+	overrides::OverrideHandle,
+	signer::EthSigner,
+};
+
+/// Error code of the user operations rejected by their validation, as in ERC-4337.
+const REJECTED_BY_ACCOUNT: i64 = -32500;
+/// Error code of the user operations rejected by their paymaster.
+const REJECTED_BY_PAYMASTER: i64 = -32501;
+/// Error code of the user operations whose signature failed.
+const INVALID_SIGNATURE: i64 = -32507;
+/// Error code of the user operations with invalid fields, or for another EntryPoint.
+const INVALID_FIELDS: i64 = -32602;
+
+/// Gas of the transaction of a bundle, besides its calldata.
+const TRANSACTION_GAS: u64 = 21_000;
+/// Gas used by the EntryPoint for each operation of a bundle, besides its validation and its
+/// call, as charged by the reference bundler.
+const USER_OPERATION_OVERHEAD: u64 = 18_300;
+
+/// Configuration of the bundler.
+#[derive(Clone, Copy, Debug)]
+pub struct BundlerConfig {
+	/// EntryPoint handling the user operations. It must implement the EntryPoint v0.6 interface.
+	pub entry_point: H160,
+	/// Account signing the `handleOps` transactions, and receiving the fees of the operations.
+	pub account: H160,
+	/// Number of latest blocks in which the receipts of the operations are looked up.
+	pub receipt_lookup_depth: u32,
+}
+
+/// Allocates the nonces of the transactions of the bundler account, so that the operations
+/// sent concurrently are submitted with distinct nonces.
+#[derive(Default)]
+struct NonceTracker {
+	state: Mutex<NonceState>,
+}
+
+#[derive(Default)]
+struct NonceState {
+	/// Nonce after the last allocated one.
+	next: Option<U256>,
+	/// Nonces before `next` whose transactions weren't submitted.
+	released: BTreeSet<U256>,
+	/// Nonces allocated to transactions not submitted yet.
+	allocated: BTreeSet<U256>,
+	/// Number of transactions submitted to the pool.
+	submitted: u64,
+}
+
+impl NonceTracker {
+	/// Reserves a nonce for an operation, before its pending nonce is read.
+	fn reserve(self: &Arc<Self>) -> NonceReservation {
+		let state = self.state.lock().expect("nonce tracker poisoned");
+		NonceReservation {
+			tracker: Arc::clone(self),
+			submitted: state.submitted,
+			nonce: None,
+		}
+	}
+}
+
+/// Nonce of an operation being sent, released when dropped before its transaction is submitted.
+struct NonceReservation {
+	tracker: Arc<NonceTracker>,
+	/// Number of transactions submitted when the reservation was made.
+	submitted: u64,
+	nonce: Option<U256>,
+}
+
+impl NonceReservation {
+	/// Allocates the nonce of the operation, given the nonce of the account with its pending
+	/// transactions, read after the reservation.
+	fn allocate(&mut self, pending_nonce: U256) -> U256 {
+		let mut state = self.tracker.state.lock().expect("nonce tracker poisoned");
+		// Without a transaction being submitted, nor submitted since `pending_nonce` was read,
+		// the nonces after `pending_nonce` belong to transactions dropped from the pool.
+		if state.allocated.is_empty()
+			&& state.submitted == self.submitted
+			&& state.next.map_or(false, |next| pending_nonce < next)
+		{
+			state.next = Some(pending_nonce);
+			state.released.clear();
+		}
+		// The released nonces before `pending_nonce` were used by other transactions.
+		state.released = state.released.split_off(&pending_nonce);
+
+		let nonce = match state.released.iter().next().copied() {
+			Some(nonce) => {
+				state.released.remove(&nonce);
+				nonce
+			}
+			None => {
+				let nonce = state
+					.next
+					.map_or(pending_nonce, |next| next.max(pending_nonce));
+				state.next = Some(nonce.saturating_add(U256::one()));
+				nonce
+			}
+		};
+		state.allocated.insert(nonce);
+		self.nonce = Some(nonce);
+		nonce
+	}
+
+	/// Records that the transaction of the allocated nonce was submitted to the pool.
+	fn submitted(mut self) {
+		if let Some(nonce) = self.nonce.take() {
+			let mut state = self.tracker.state.lock().expect("nonce tracker poisoned");
+			state.allocated.remove(&nonce);
+			state.submitted += 1;
+		}
+	}
+}
+
+impl Drop for NonceReservation {
+	fn drop(&mut self) {
+		let nonce = match self.nonce.take() {
+			Some(nonce) => nonce,
+			None => return,
+		};
+		let mut state = self.tracker.state.lock().expect("nonce tracker poisoned");
+		state.allocated.remove(&nonce);
+		state.released.insert(nonce);
+		// Released nonces at the end are allocated again from `next`.
+		while let Some(last) = state.next.and_then(|next| next.checked_sub(U256::one())) {
+			if !state.released.remove(&last) {
+				break;
+			}
+			state.next = Some(last);
+		}
+	}
+}
+
+/// Bundler API implementation.
+///
+/// Each user operation is validated with the `simulateValidation` of the EntryPoint, executed
+/// with the runtime api like `eth_call`, and submitted alone in a `handleOps` transaction of
+/// the bundler account. Operations are not aggregated, and the ERC-4337 restrictions on the
+/// storage and opcodes used by their validation are not enforced, so the bundler account
+/// pays for the operations whose validation succeeds in the simulation but not on chain.
+pub struct Bundler<B: BlockT, C, P, CT, BE, A: ChainApi> {
+	client: Arc<C>,
+	pool: Arc<P>,
+	graph: Arc<Pool<A>>,
+	convert_transaction: Option<Arc<CT>>,
+	signers: Arc<Vec<Box<dyn EthSigner>>>,
+	backend: Arc<dyn fc_api::Backend<B>>,
+	overrides: Arc<OverrideHandle<B>>,
+	block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+	execution_pool: ExecutionPool,
+	config: BundlerConfig,
+	nonces: Arc<NonceTracker>,
+	_marker: PhantomData<BE>,
+}
+
+impl<B: BlockT, C, P, CT, BE, A: ChainApi> Bundler<B, C, P, CT, BE, A> {
+	pub fn new(
+		client: Arc<C>,
+		pool: Arc<P>,
+		graph: Arc<Pool<A>>,
+		convert_transaction: Option<CT>,
+		signers: Vec<Box<dyn EthSigner>>,
+		backend: Arc<dyn fc_api::Backend<B>>,
+		overrides: Arc<OverrideHandle<B>>,
+		block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+		execution_pool: ExecutionPool,
+		config: BundlerConfig,
+	) -> Self {
+		Self {
+			client,
+			pool,
+			graph,
+			convert_transaction: convert_transaction.map(Arc::new),
+			signers: Arc::new(signers),
+			backend,
+			overrides,
+			block_data_cache,
+			execution_pool,
+			config,
+			nonces: Arc::new(NonceTracker::default()),
+			_marker: PhantomData,
+		}
+	}
+
+	fn check_entry_point(&self, entry_point: H160) -> Result<()> {
+		if entry_point != self.config.entry_point {
+			return Err(Error {
+				code: INVALID_FIELDS.into(),
+				message: format!("unsupported entry point {:?}", entry_point),
+				data: None,
+			});
+		}
+		Ok(())
+	}
+}
+
+/// Error of an operation rejected by the EntryPoint, for a reason starting with its code.
+fn rejected_err(reason: String) -> Error {
+	// The `AA3x` codes are the failures of the paymaster.
+	let code = if reason.starts_with("AA3") {
+		REJECTED_BY_PAYMASTER
+	} else {
+		REJECTED_BY_ACCOUNT
+	};
+	Error {
+		code: code.into(),
+		message: reason,
+		data: None,
+	}
+}
+
+/// Gas paid by `op` for the calldata of its bundle, and the overheads of the transaction of
+/// the bundle and of the operation.
+fn required_pre_verification_gas(op: &UserOperation, beneficiary: H160) -> U256 {
+	let calldata_gas: u64 = abi::handle_ops(op, beneficiary)
+		.iter()
+		.map(|byte| if *byte == 0 { 4 } else { 16 })
+		.sum();
+	U256::from(TRANSACTION_GAS + USER_OPERATION_OVERHEAD + calldata_gas)
+}
+
+/// Runs the `simulateValidation` of `op`, returning the gas it uses before its call and
+/// whether its signature failed.
+fn simulate_validation<B, C>(
+	execute_in_block: &ExecuteInBlock<B, C>,
+	op: &UserOperation,
+	entry_point: H160,
+	gas_limit: U256,
+) -> Result<(U256, bool)>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync + 'static,
+	C::Api: BlockBuilderApi<B> + EthereumRuntimeRPCApi<B>,
+{
+	let request = CallRequest {
+		to: Some(entry_point),
+		data: Some(Bytes(abi::simulate_validation(op))),
+		..Default::default()
+	};
+	let info =
+		execute_in_block.execute(request, gas_limit, &fee_details(None, None, None)?, false)?;
+	if let ExitReason::Revert(_) = info.exit_reason {
+		match abi::validation(&info.value) {
+			Some(Validation::Valid {
+				pre_op_gas,
+				sig_failed,
+			}) => return Ok((pre_op_gas, sig_failed)),
+			Some(Validation::Failed(reason)) => return Err(rejected_err(reason)),
+			None => {}
+		}
+	}
+	error_on_execution_failure(&info.exit_reason, &info.value)?;
+	Err(internal_err(
+		"simulateValidation didn't return a validation result",
+	))
+}
+
+/// Receipt of the operation with `user_op_hash` in the block `number`, if it was handled by
+/// `entry_point` in this block.
+async fn block_user_operation_receipt<B, C, BE>(
+	client: &C,
+	overrides: &OverrideHandle<B>,
+	block_data_cache: &EthBlockDataCacheTask<B>,
+	number: NumberFor<B>,
+	entry_point: H160,
+	user_op_hash: H256,
+) -> Result<Option<UserOperationReceipt>>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: StorageProvider<B, BE> + HeaderBackend<B> + Send + Sync + 'static,
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+{
+	let id = BlockId::Number(number);
+	let substrate_hash = client
+		.expect_block_hash_from_id(&id)
+		.map_err(|_| internal_err(format!("Expect block number from id: {}", id)))?;
+	let schema = frontier_backend_client::onchain_storage_schema::<B, C, BE>(client, id);
+
+	let block = match block_data_cache.current_block(schema, substrate_hash).await {
+		Some(block) => block,
+		None => return Ok(None),
+	};
+	let bloom = block.header.logs_bloom;
+	if !bloom.contains_input(BloomInput::Raw(entry_point.as_bytes()))
+		|| !bloom.contains_input(BloomInput::Raw(user_op_hash.as_bytes()))
+	{
+		return Ok(None);
+	}
+	let statuses = match block_data_cache
+		.current_transaction_statuses(schema, substrate_hash)
+		.await
+	{
+		Some(statuses) => statuses,
+		None => return Ok(None),
+	};
+
+	let event = abi::user_operation_event();
+	let is_event = |log: &ethereum::Log| {
+		log.address == entry_point
+			&& log.topics.len() == 4
+			&& log.topics[0] == event
+			&& log.topics[1] == user_op_hash
+	};
+	let (index, position) = match statuses.iter().enumerate().find_map(|(index, status)| {
+		status
+			.logs
+			.iter()
+			.position(is_event)
+			.map(|position| (index, position))
+	}) {
+		Some(found) => found,
+		None => return Ok(None),
+	};
+
+	let handler = overrides
+		.schemas
+		.get(&schema)
+		.unwrap_or(&overrides.fallback);
+	let receipts = handler
+		.current_receipts(&id)
+		.ok_or_else(|| internal_err(format!("receipts unavailable for block {}", id)))?;
+	let receipt = receipt_build(
+		&block,
+		&statuses,
+		&receipts,
+		index,
+		handler.is_eip1559(&id),
+		handler.base_fee(&id),
+	)?;
+	user_operation_receipt(receipt, position, entry_point, user_op_hash).map(Some)
+}
+
+/// Receipt of the operation whose `UserOperationEvent` is the log at `position` of `receipt`.
+fn user_operation_receipt(
+	receipt: Receipt,
+	position: usize,
+	entry_point: H160,
+	user_op_hash: H256,
+) -> Result<UserOperationReceipt> {
+	let event = &receipt.logs[position];
+	let (nonce, success, actual_gas_cost, actual_gas_used) =
+		abi::user_operation_event_data(&event.data.0)
+			.ok_or_else(|| internal_err("invalid UserOperationEvent data"))?;
+	let sender = H160::from(event.topics[2]);
+	let paymaster = H160::from(event.topics[3]);
+
+	// The logs of an operation follow the event of the previous operation, or the
+	// `BeforeExecution` event for the first one.
+	let delimiters = [abi::user_operation_event(), abi::before_execution_event()];
+	let is_delimiter = |log: &Log| {
+		log.address == entry_point
+			&& log
+				.topics
+				.first()
+				.map_or(false, |topic| delimiters.contains(topic))
+	};
+	let start = receipt.logs[..position]
+		.iter()
+		.rposition(is_delimiter)
+		.map_or(0, |delimiter| delimiter + 1);
+	let logs = receipt.logs[start..position].to_vec();
+
+	Ok(UserOperationReceipt {
+		user_op_hash,
+		entry_point,
+		sender,
+		nonce,
+		paymaster,
+		actual_gas_cost,
+		actual_gas_used,
+		success,
+		logs,
+		receipt,
+	})
+}
+
+impl<B, C, P, CT, BE, A> BundlerApi for Bundler<B, C, P, CT, BE, A>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + StorageProvider<B, BE>,
+	C: HeaderBackend<B> + Send + Sync + 'static,
+	C::Api: BlockBuilderApi<B> + ConvertTransactionRuntimeApi<B> + EthereumRuntimeRPCApi<B>,
+	P: TransactionPool<Block = B> + Send + Sync + 'static,
+	CT: ConvertTransaction<<B as BlockT>::Extrinsic> + Send + Sync + 'static,
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+	A: ChainApi<Block = B> + 'static,
+{
+	fn supported_entry_points(&self) -> Result<Vec<H160>> {
+		Ok(vec![self.config.entry_point])
+	}
+
+	fn send_user_operation(&self, op: UserOperation, entry_point: H160) -> BoxFuture<Result<H256>> {
+		if let Err(err) = self.check_entry_point(entry_point) {
+			return future::err(err).boxed();
+		}
+
+		let client = Arc::clone(&self.client);
+		let backend = Arc::clone(&self.backend);
+		let graph = Arc::clone(&self.graph);
+		let convert_transaction = self.convert_transaction.clone();
+		let signers = Arc::clone(&self.signers);
+		let nonces = Arc::clone(&self.nonces);
+		let account = self.config.account;
+
+		let bundle = self.execution_pool.spawn(move |_| {
+			let mut reservation = nonces.reserve();
+			let required = required_pre_verification_gas(&op, account);
+			if op.pre_verification_gas < required {
+				return Err(Error {
+					code: INVALID_FIELDS.into(),
+					message: format!("preVerificationGas too low, expected at least {}", required),
+					data: None,
+				});
+			}
+
+			// The operation is validated on top of the pending bundles of the node.
+			let execute_in_block = ExecuteInBlock::new(
+				client.as_ref(),
+				backend.as_ref(),
+				graph.as_ref(),
+				Some(BlockNumber::Pending),
+				Some(account),
+			)?;
+			let id = execute_in_block.id;
+			// The nonce is read before the simulations, which change the state of the runtime api.
+			let pending_nonce = execute_in_block
+				.api
+				.account_basic(&id, account)
+				.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+				.nonce;
+			let chain_id = execute_in_block
+				.api
+				.chain_id(&id)
+				.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+			let block_gas_limit = execute_in_block.gas_limit()?;
+
+			let (_, sig_failed) =
+				simulate_validation(&execute_in_block, &op, entry_point, block_gas_limit)?;
+			if sig_failed {
+				return Err(Error {
+					code: INVALID_SIGNATURE.into(),
+					message: String::from("invalid user operation signature"),
+					data: None,
+				});
+			}
+
+			let input = abi::handle_ops(&op, account);
+			let request = CallRequest {
+				from: Some(account),
+				to: Some(entry_point),
+				data: Some(Bytes(input.clone())),
+				..Default::default()
+			};
+			let info = execute_in_block.execute(
+				request,
+				block_gas_limit,
+				&fee_details(None, None, None)?,
+				false,
+			)?;
+			if let ExitReason::Revert(_) = info.exit_reason {
+				if let Some(reason) = abi::failed_op(&info.value) {
+					return Err(rejected_err(reason));
+				}
+			}
+			error_on_execution_failure(&info.exit_reason, &info.value)?;
+
+			// The EntryPoint requires the gas limits of the operation to be available, and
+			// all but one 64th of the gas is available to the calls.
+			let verification_gas = if op.paymaster_and_data.0.is_empty() {
+				op.verification_gas_limit
+			} else {
+				op.verification_gas_limit.saturating_mul(U256::from(3))
+			};
+			let gas_limit = (info.used_gas.saturating_mul(U256::from(64)) / 63)
+				.max(
+					op.pre_verification_gas
+						.saturating_add(verification_gas)
+						.saturating_add(op.call_gas_limit),
+				)
+				.min(block_gas_limit);
+
+			let nonce = reservation.allocate(pending_nonce);
+			let message = TransactionMessage::EIP1559(EIP1559TransactionMessage {
+				chain_id,
+				nonce,
+				max_priority_fee_per_gas: op.max_priority_fee_per_gas,
+				max_fee_per_gas: op.max_fee_per_gas,
+				gas_limit,
+				action: TransactionAction::Call(entry_point),
+				value: U256::zero(),
+				input,
+				access_list: Vec::new(),
+			});
+			let signed = signers
+				.iter()
+				.find(|signer| signer.accounts().contains(&account))
+				.ok_or_else(|| internal_err("no signer available"))
+				.and_then(|signer| signer.sign(message, &account))
+				.and_then(|transaction| {
+					transaction_extrinsic(
+						client.as_ref(),
+						convert_transaction.as_deref(),
+						&id,
+						transaction.clone(),
+					)
+					.map(|extrinsic| (extrinsic, transaction))
+				});
+			let (extrinsic, transaction) = signed?;
+
+			let user_op_hash = abi::user_operation_hash(&op, entry_point, chain_id);
+			Ok((id, extrinsic, transaction, reservation, user_op_hash))
+		});
+
+		let client = Arc::clone(&self.client);
+		let pool = Arc::clone(&self.pool);
+		Box::pin(bundle.and_then(
+			move |(id, extrinsic, transaction, reservation, user_op_hash)| {
+				pool.submit_one(&id, TransactionSource::Local, extrinsic)
+					.map(move |result| match result {
+						Ok(_) => {
+							reservation.submitted();
+							Ok(user_op_hash)
+						}
+						// The nonce is released with the reservation.
+						Err(err) => Err(submit_error(err, || {
+							check_transaction(client.as_ref(), &id, transaction)
+						})),
+					})
+			},
+		))
+	}
+
+	fn estimate_user_operation_gas(
+		&self,
+		op: UserOperation,
+		entry_point: H160,
+	) -> BoxFuture<Result<UserOperationGasEstimate>> {
+		if let Err(err) = self.check_entry_point(entry_point) {
+			return future::err(err).boxed();
+		}
+
+		let client = Arc::clone(&self.client);
+		let backend = Arc::clone(&self.backend);
+		let graph = Arc::clone(&self.graph);
+		let account = self.config.account;
+
+		self.execution_pool.spawn(move |_| {
+			let execute_in_block = ExecuteInBlock::new(
+				client.as_ref(),
+				backend.as_ref(),
+				graph.as_ref(),
+				None,
+				None,
+			)?;
+			let block_gas_limit = execute_in_block.gas_limit()?;
+
+			// The validation is given the whole block gas, and no fees so that it doesn't need
+			// a prefund. The signature, which doesn't sign these limits, may fail.
+			let pre_verification_gas = required_pre_verification_gas(&op, account);
+			let validated = UserOperation {
+				pre_verification_gas,
+				verification_gas_limit: block_gas_limit,
+				call_gas_limit: U256::zero(),
+				max_fee_per_gas: U256::zero(),
+				max_priority_fee_per_gas: U256::zero(),
+				..op.clone()
+			};
+			let (pre_op_gas, _) =
+				simulate_validation(&execute_in_block, &validated, entry_point, block_gas_limit)?;
+
+			// The call is estimated as a call from the EntryPoint to the deployed account.
+			let code = execute_in_block
+				.api
+				.account_code_at(&execute_in_block.id, op.sender)
+				.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+			if code.is_empty() {
+				return Err(Error {
+					code: INVALID_FIELDS.into(),
+					message: String::from("cannot estimate the call of an undeployed account"),
+					data: None,
+				});
+			}
+			let request = CallRequest {
+				from: Some(entry_point),
+				to: Some(op.sender),
+				data: Some(op.call_data),
+				..Default::default()
+			};
+			let info = execute_in_block.execute(
+				request,
+				block_gas_limit,
+				&fee_details(None, None, None)?,
+				true,
+			)?;
+			error_on_execution_failure(&info.exit_reason, &info.value)?;
+
+			Ok(UserOperationGasEstimate {
+				pre_verification_gas,
+				verification_gas_limit: pre_op_gas.saturating_sub(pre_verification_gas),
+				call_gas_limit: info.used_gas,
+			})
+		})
+	}
+
+	fn user_operation_receipt(
+		&self,
+		user_op_hash: H256,
+	) -> BoxFuture<Result<Option<UserOperationReceipt>>> {
+		let client = Arc::clone(&self.client);
+		let overrides = Arc::clone(&self.overrides);
+		let block_data_cache = Arc::clone(&self.block_data_cache);
+		let BundlerConfig {
+			entry_point,
+			receipt_lookup_depth,
+			..
+		} = self.config;
+
+		Box::pin(async move {
+			let mut number = client.info().best_number;
+			let depth: NumberFor<B> = receipt_lookup_depth.unique_saturated_into();
+			let oldest = number.saturating_sub(depth);
+			loop {
+				let receipt = block_user_operation_receipt::<B, C, BE>(
+					client.as_ref(),
+					overrides.as_ref(),
+					block_data_cache.as_ref(),
+					number,
+					entry_point,
+					user_op_hash,
+				)
+				.await?;
+				if receipt.is_some() || number <= oldest {
+					return Ok(receipt);
+				}
+				number = number.saturating_sub(One::one());
+			}
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethereum_types::{H2048, U64};
+
+	fn log(topics: Vec<H256>, data: Vec<u8>) -> Log {
+		Log {
+			address: H160::repeat_byte(0xee),
+			topics,
+			data: Bytes(data),
+			block_hash: None,
+			block_number: None,
+			transaction_hash: None,
+			transaction_index: None,
+			log_index: None,
+			transaction_log_index: None,
+			removed: false,
+		}
+	}
+
+	fn user_operation_event(user_op_hash: H256, nonce: u64) -> Log {
+		let data = [nonce, 1, 3_000, 1_000]
+			.iter()
+			.flat_map(|word| H256::from_low_u64_be(*word).0)
+			.collect();
+		log(
+			vec![
+				abi::user_operation_event(),
+				user_op_hash,
+				H256::from(H160::repeat_byte(0x11)),
+				H256::zero(),
+			],
+			data,
+		)
+	}
+
+	#[test]
+	fn allocates_consecutive_nonces() {
+		let nonces = Arc::new(NonceTracker::default());
+		let mut first = nonces.reserve();
+		assert_eq!(first.allocate(U256::from(3)), U256::from(3));
+		// The first transaction isn't pending yet.
+		let mut second = nonces.reserve();
+		assert_eq!(second.allocate(U256::from(3)), U256::from(4));
+		// Transactions of the account were submitted by another node.
+		let mut third = nonces.reserve();
+		assert_eq!(third.allocate(U256::from(7)), U256::from(7));
+	}
+
+	#[test]
+	fn reuses_the_released_nonces() {
+		let nonces = Arc::new(NonceTracker::default());
+		let mut first = nonces.reserve();
+		let mut second = nonces.reserve();
+		let mut third = nonces.reserve();
+		assert_eq!(first.allocate(U256::zero()), U256::zero());
+		assert_eq!(second.allocate(U256::zero()), U256::one());
+		assert_eq!(third.allocate(U256::zero()), U256::from(2));
+
+		// The transaction of a nonce in the middle failed to be submitted.
+		drop(second);
+		let mut fourth = nonces.reserve();
+		assert_eq!(fourth.allocate(U256::zero()), U256::one());
+
+		// The last nonce is released.
+		drop(third);
+		let mut fifth = nonces.reserve();
+		assert_eq!(fifth.allocate(U256::zero()), U256::from(2));
+	}
+
+	#[test]
+	fn resets_the_nonces_of_dropped_transactions() {
+		let nonces = Arc::new(NonceTracker::default());
+		let mut first = nonces.reserve();
+		assert_eq!(first.allocate(U256::from(3)), U256::from(3));
+		first.submitted();
+
+		// The transaction was dropped from the pool.
+		let mut second = nonces.reserve();
+		assert_eq!(second.allocate(U256::from(3)), U256::from(3));
+		second.submitted();
+
+		// The pending nonce of the third operation is read before the fourth is submitted.
+		let mut third = nonces.reserve();
+		let mut fourth = nonces.reserve();
+		assert_eq!(fourth.allocate(U256::from(4)), U256::from(4));
+		fourth.submitted();
+		assert_eq!(third.allocate(U256::from(4)), U256::from(5));
+	}
+
+	#[test]
+	fn charges_the_calldata_of_the_bundle() {
+		let zeros = UserOperation {
+			signature: Bytes(vec![0; 64]),
+			..Default::default()
+		};
+		let ones = UserOperation {
+			signature: Bytes(vec![1; 64]),
+			..Default::default()
+		};
+		let beneficiary = H160::repeat_byte(0x22);
+
+		let required = required_pre_verification_gas(&zeros, beneficiary);
+		assert!(required > U256::from(TRANSACTION_GAS + USER_OPERATION_OVERHEAD));
+		assert_eq!(
+			required_pre_verification_gas(&ones, beneficiary) - required,
+			U256::from(64 * (16 - 4))
+		);
+	}
+
+	#[test]
+	fn rejects_the_failures_of_the_paymaster_with_their_code() {
+		assert_eq!(
+			rejected_err(String::from("AA31 paymaster deposit too low")).code,
+			REJECTED_BY_PAYMASTER.into()
+		);
+		assert_eq!(
+			rejected_err(String::from("AA21 didn't pay prefund")).code,
+			REJECTED_BY_ACCOUNT.into()
+		);
+	}
+
+	#[test]
+	fn receipt_has_the_logs_of_its_operation() {
+		let entry_point = H160::repeat_byte(0xee);
+		let (first, second) = (H256::repeat_byte(1), H256::repeat_byte(2));
+		let logs = vec![
+			log(vec![abi::before_execution_event()], Vec::new()),
+			log(vec![H256::repeat_byte(0xa)], Vec::new()),
+			user_operation_event(first, 5),
+			log(vec![H256::repeat_byte(0xb)], Vec::new()),
+			log(vec![H256::repeat_byte(0xc)], Vec::new()),
+			user_operation_event(second, 6),
+		];
+		let receipt = || Receipt {
+			transaction_hash: None,
+			transaction_index: None,
+			block_hash: None,
+			from: None,
+			to: Some(entry_point),
+			block_number: None,
+			cumulative_gas_used: U256::zero(),
+			gas_used: None,
+			contract_address: None,
+			logs: logs.clone(),
+			state_root: None,
+			logs_bloom: H2048::default(),
+			status_code: Some(U64::one()),
+			effective_gas_price: U256::zero(),
+		};
+
+		let first_receipt = user_operation_receipt(receipt(), 2, entry_point, first).unwrap();
+		assert_eq!(first_receipt.logs, logs[1..2].to_vec());
+		assert_eq!(first_receipt.nonce, U256::from(5));
+		assert_eq!(first_receipt.sender, H160::repeat_byte(0x11));
+		assert!(first_receipt.success);
+
+		let second_receipt = user_operation_receipt(receipt(), 5, entry_point, second).unwrap();
+		assert_eq!(second_receipt.logs, logs[3..5].to_vec());
+		assert_eq!(second_receipt.actual_gas_cost, U256::from(3_000));
+		assert_eq!(second_receipt.actual_gas_used, U256::from(1_000));
+	}
+}
@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use ethereum::EnvelopedEncodable;
+use ethereum_types::H256;
+use jsonrpc_core::Result;
+
+use sc_client_api::backend::{Backend, StateBackend, StorageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{BlakeTwo256, Block as BlockT},
+};
+
+use fc_rpc_core::{
+	types::{BlockNumber, Bytes},
+	DebugApi,
+};
+
+use crate::{
+	frontier_backend_client,
+	overrides::{OverrideHandle, StorageOverride},
+};
+
+/// Debug API implementation.
+///
+/// The encodings are rebuilt from the blocks and receipts stored by the runtime.
+pub struct Debug<B: BlockT, C, BE> {
+	client: Arc<C>,
+	backend: Arc<dyn fc_api::Backend<B>>,
+	overrides: Arc<OverrideHandle<B>>,
+	_marker: PhantomData<BE>,
+}
+
+impl<B: BlockT, C, BE> Debug<B, C, BE> {
+	pub fn new(
+		client: Arc<C>,
+		backend: Arc<dyn fc_api::Backend<B>>,
+		overrides: Arc<OverrideHandle<B>>,
+	) -> Self {
+		Self {
+			client,
+			backend,
+			overrides,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<B, C, BE> Debug<B, C, BE>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: StorageProvider<B, BE> + HeaderBackend<B> + Send + Sync + 'static,
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+{
+	/// Substrate block of `number`, if any. The pending block is never returned.
+	fn block_id(&self, number: BlockNumber) -> Result<Option<BlockId<B>>> {
+		if number == BlockNumber::Pending {
+			return Ok(None);
+		}
+		frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			Some(number),
+		)
+	}
+
+	/// Storage override of the schema of a block.
+	fn handler(&self, id: BlockId<B>) -> &(dyn StorageOverride<B> + Send + Sync) {
+		let schema =
+			frontier_backend_client::onchain_storage_schema::<B, C, BE>(self.client.as_ref(), id);
+		self.overrides
+			.schemas
+			.get(&schema)
+			.unwrap_or(&self.overrides.fallback)
+			.as_ref()
+	}
+
+	fn block(&self, number: BlockNumber) -> Result<Option<ethereum::BlockV2>> {
+		Ok(self
+			.block_id(number)?
+			.and_then(|id| self.handler(id).current_block(&id)))
+	}
+}
+
+impl<B, C, BE> DebugApi for Debug<B, C, BE>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: StorageProvider<B, BE> + HeaderBackend<B> + Send + Sync + 'static,
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+{
+	fn raw_header(&self, number: BlockNumber) -> Result<Option<Bytes>> {
+		Ok(self
+			.block(number)?
+			.map(|block| Bytes(rlp::encode(&block.header).to_vec())))
+	}
+
+	fn raw_block(&self, number: BlockNumber) -> Result<Option<Bytes>> {
+		Ok(self
+			.block(number)?
+			.map(|block| Bytes(rlp::encode(&block).to_vec())))
+	}
+
+	fn raw_receipts(&self, number: BlockNumber) -> Result<Option<Vec<Bytes>>> {
+		let id = match self.block_id(number)? {
+			Some(id) => id,
+			None => return Ok(None),
+		};
+		Ok(self.handler(id).current_receipts(&id).map(|receipts| {
+			receipts
+				.iter()
+				.map(|receipt| Bytes(EnvelopedEncodable::encode(receipt).to_vec()))
+				.collect()
+		}))
+	}
+}
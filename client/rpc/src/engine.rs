@@ -0,0 +1,300 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use ethereum::{BlockV2 as EthereumBlock, EnvelopedEncodable};
+use ethereum_types::H256;
+use jsonrpc_core::{BoxFuture, Result};
+
+use sc_client_api::backend::{Backend, StateBackend, StorageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{BlakeTwo256, Block as BlockT},
+};
+
+use fc_rpc_core::{
+	types::{
+		Bytes, ExecutionPayload, ForkchoiceState, ForkchoiceUpdatedResult, PayloadAttributes,
+		PayloadBody, PayloadStatus, PayloadStatusKind,
+	},
+	EngineApi as EngineApiT,
+};
+
+use crate::{frontier_backend_client, internal_err, EthBlockDataCacheTask};
+
+/// Maximum number of payload bodies returned by a single request.
+const MAX_PAYLOAD_BODIES_REQUEST: usize = 32;
+
+/// Engine API implementation.
+pub struct Engine<B: BlockT, C, BE> {
+	client: Arc<C>,
+	backend: Arc<dyn fc_api::Backend<B>>,
+	block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+	_marker: PhantomData<BE>,
+}
+
+impl<B: BlockT, C, BE> Engine<B, C, BE> {
+	pub fn new(
+		client: Arc<C>,
+		backend: Arc<dyn fc_api::Backend<B>>,
+		block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+	) -> Self {
+		Self {
+			client,
+			backend,
+			block_data_cache,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<B, C, BE> EngineApiT for Engine<B, C, BE>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: StorageProvider<B, BE> + HeaderBackend<B> + Send + Sync + 'static,
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+{
+	fn exchange_capabilities(&self, _: Vec<String>) -> Result<Vec<String>> {
+		Ok(vec![
+			"engine_forkchoiceUpdatedV1".to_string(),
+			"engine_newPayloadV1".to_string(),
+			"engine_getPayloadBodiesByHashV1".to_string(),
+		])
+	}
+
+	fn fork_choice_updated(
+		&self,
+		state: ForkchoiceState,
+		attributes: Option<PayloadAttributes>,
+	) -> BoxFuture<Result<ForkchoiceUpdatedResult>> {
+		let client = Arc::clone(&self.client);
+		let backend = Arc::clone(&self.backend);
+		let block_data_cache = Arc::clone(&self.block_data_cache);
+
+		Box::pin(async move {
+			if attributes.is_some() {
+				return Err(internal_err("payload building is not supported"));
+			}
+
+			let payload_status = |payload_status| -> Result<ForkchoiceUpdatedResult> {
+				Ok(ForkchoiceUpdatedResult {
+					payload_status,
+					payload_id: None,
+				})
+			};
+
+			let head = match load_substrate_hash(backend.as_ref(), state.head_block_hash)? {
+				Some(head) => head,
+				None => {
+					return payload_status(PayloadStatus::new(PayloadStatusKind::Syncing, None))
+				}
+			};
+
+			// The fork choice belongs to the Substrate consensus, the head can only be
+			// reported valid when it is already part of the canonical chain.
+			if !frontier_backend_client::is_canon::<B, C>(client.as_ref(), head) {
+				let best_hash = client.info().best_hash;
+				let latest_valid_hash = ethereum_block::<B, C, BE>(
+					client.as_ref(),
+					block_data_cache.as_ref(),
+					best_hash,
+				)
+				.await
+				.map(|block| block.header.hash());
+				return payload_status(PayloadStatus::invalid(
+					latest_valid_hash,
+					"head block is not part of the canonical chain".to_string(),
+				));
+			}
+
+			for (hash, finalized) in [
+				(state.safe_block_hash, false),
+				(state.finalized_block_hash, true),
+			] {
+				// A zero hash means the block is not known by the consensus client yet.
+				if hash.is_zero() {
+					continue;
+				}
+
+				let substrate_hash = match load_substrate_hash(backend.as_ref(), hash)? {
+					Some(substrate_hash) => substrate_hash,
+					None => {
+						return payload_status(PayloadStatus::new(PayloadStatusKind::Syncing, None))
+					}
+				};
+
+				let is_canon =
+					frontier_backend_client::is_canon::<B, C>(client.as_ref(), substrate_hash);
+				let is_finalized = client
+					.number(substrate_hash)
+					.map_err(|err| internal_err(format!("{:?}", err)))?
+					.map_or(false, |number| number <= client.info().finalized_number);
+				if !is_canon || (finalized && !is_finalized) {
+					return payload_status(PayloadStatus::invalid(
+						Some(state.head_block_hash),
+						format!("block {:?} is not finalized by the chain", hash),
+					));
+				}
+			}
+
+			payload_status(PayloadStatus::new(
+				PayloadStatusKind::Valid,
+				Some(state.head_block_hash),
+			))
+		})
+	}
+
+	fn new_payload(&self, payload: ExecutionPayload) -> BoxFuture<Result<PayloadStatus>> {
+		let client = Arc::clone(&self.client);
+		let backend = Arc::clone(&self.backend);
+		let block_data_cache = Arc::clone(&self.block_data_cache);
+
+		Box::pin(async move {
+			let substrate_hash = match load_substrate_hash(backend.as_ref(), payload.block_hash)? {
+				Some(substrate_hash) => substrate_hash,
+				None => {
+					// Blocks can only be imported through the Substrate network.
+					let status =
+						if load_substrate_hash(backend.as_ref(), payload.parent_hash)?.is_some() {
+							PayloadStatusKind::Accepted
+						} else {
+							PayloadStatusKind::Syncing
+						};
+					return Ok(PayloadStatus::new(status, None));
+				}
+			};
+
+			let block = match ethereum_block::<B, C, BE>(
+				client.as_ref(),
+				block_data_cache.as_ref(),
+				substrate_hash,
+			)
+			.await
+			{
+				Some(block) => block,
+				None => return Ok(PayloadStatus::new(PayloadStatusKind::Syncing, None)),
+			};
+
+			let header = &block.header;
+			let transactions: Vec<Bytes> = block
+				.transactions
+				.iter()
+				.map(|transaction| Bytes(EnvelopedEncodable::encode(transaction).to_vec()))
+				.collect();
+			let matches = header.parent_hash == payload.parent_hash
+				&& header.beneficiary == payload.fee_recipient
+				&& header.state_root == payload.state_root
+				&& header.receipts_root == payload.receipts_root
+				&& header.logs_bloom == payload.logs_bloom
+				&& header.number == payload.block_number.as_u64().into()
+				&& header.gas_limit == payload.gas_limit.as_u64().into()
+				&& header.gas_used == payload.gas_used.as_u64().into()
+				&& header.extra_data == payload.extra_data.0
+				&& transactions == payload.transactions;
+
+			if matches {
+				Ok(PayloadStatus::new(
+					PayloadStatusKind::Valid,
+					Some(payload.block_hash),
+				))
+			} else {
+				Ok(PayloadStatus {
+					status: PayloadStatusKind::InvalidBlockHash,
+					latest_valid_hash: None,
+					validation_error: Some("payload does not match the imported block".to_string()),
+				})
+			}
+		})
+	}
+
+	fn payload_bodies_by_hash(
+		&self,
+		hashes: Vec<H256>,
+	) -> BoxFuture<Result<Vec<Option<PayloadBody>>>> {
+		let client = Arc::clone(&self.client);
+		let backend = Arc::clone(&self.backend);
+		let block_data_cache = Arc::clone(&self.block_data_cache);
+
+		Box::pin(async move {
+			if hashes.len() > MAX_PAYLOAD_BODIES_REQUEST {
+				return Err(internal_err(format!(
+					"too many requested payload bodies, maximum is {}",
+					MAX_PAYLOAD_BODIES_REQUEST
+				)));
+			}
+
+			let mut bodies = Vec::with_capacity(hashes.len());
+			for hash in hashes {
+				let block = match load_substrate_hash(backend.as_ref(), hash)? {
+					Some(substrate_hash) => {
+						ethereum_block::<B, C, BE>(
+							client.as_ref(),
+							block_data_cache.as_ref(),
+							substrate_hash,
+						)
+						.await
+					}
+					None => None,
+				};
+				bodies.push(block.map(|block| {
+					PayloadBody {
+						transactions: block
+							.transactions
+							.iter()
+							.map(|transaction| {
+								Bytes(EnvelopedEncodable::encode(transaction).to_vec())
+							})
+							.collect(),
+					}
+				}));
+			}
+
+			Ok(bodies)
+		})
+	}
+}
+
+fn load_substrate_hash<B>(backend: &dyn fc_api::Backend<B>, hash: H256) -> Result<Option<H256>>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+{
+	backend
+		.block_hash(&hash)
+		.map_err(|err| internal_err(format!("fetch aux store failed: {:?}", err)))
+}
+
+async fn ethereum_block<B, C, BE>(
+	client: &C,
+	block_data_cache: &EthBlockDataCacheTask<B>,
+	substrate_hash: H256,
+) -> Option<EthereumBlock>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: StorageProvider<B, BE> + Send + Sync + 'static,
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+{
+	let schema = frontier_backend_client::onchain_storage_schema::<B, C, BE>(
+		client,
+		BlockId::Hash(substrate_hash),
+	);
+	block_data_cache.current_block(schema, substrate_hash).await
+}
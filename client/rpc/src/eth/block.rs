@@ -24,23 +24,33 @@ use jsonrpc_core::{BoxFuture, Result};
 use sc_client_api::backend::{Backend, StateBackend, StorageProvider};
 use sc_network::ExHashT;
 use sc_transaction_pool::ChainApi;
+use sc_transaction_pool_api::InPoolTransaction;
+use sp_api::{Core, ProvideRuntimeApi};
+use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::HeaderBackend;
 use sp_core::hashing::keccak_256;
-use sp_runtime::traits::{BlakeTwo256, Block as BlockT};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{BlakeTwo256, Block as BlockT, Header as HeaderT, One},
+};
 
 use fc_rpc_core::types::*;
+use fp_rpc::EthereumRuntimeRPCApi;
 
 use crate::{
 	eth::{rich_block_build, Eth},
-	frontier_backend_client, internal_err,
+	ethereum_api_version, frontier_backend_client, internal_err,
 };
 
-impl<B, C, P, CT, BE, H: ExHashT, A: ChainApi> Eth<B, C, P, CT, BE, H, A>
+impl<B, C, P, CT, BE, H: ExHashT, A> Eth<B, C, P, CT, BE, H, A>
 where
 	B: BlockT<Hash = H256> + Send + Sync + 'static,
-	C: StorageProvider<B, BE> + HeaderBackend<B> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + StorageProvider<B, BE>,
+	C: HeaderBackend<B> + Send + Sync + 'static,
+	C::Api: BlockBuilderApi<B> + EthereumRuntimeRPCApi<B>,
 	BE: Backend<B> + 'static,
 	BE::State: StateBackend<BlakeTwo256>,
+	A: ChainApi<Block = B> + 'static,
 {
 	pub fn block_by_hash(&self, hash: H256, full: bool) -> BoxFuture<Result<Option<RichBlock>>> {
 		let client = Arc::clone(&self.client);
@@ -91,6 +101,11 @@ where
 		number: BlockNumber,
 		full: bool,
 	) -> BoxFuture<Result<Option<RichBlock>>> {
+		if number == BlockNumber::Pending {
+			let pending = self.pending_block(full);
+			return Box::pin(async move { pending });
+		}
+
 		let client = Arc::clone(&self.client);
 		let overrides = Arc::clone(&self.overrides);
 		let block_data_cache = Arc::clone(&self.block_data_cache);
@@ -140,6 +155,70 @@ where
 		})
 	}
 
+	/// Build the Ethereum block the ready transactions of the pool would produce on top
+	/// of the best block.
+	pub(crate) fn pending_block(&self, full: bool) -> Result<Option<RichBlock>> {
+		let best_hash = self.client.info().best_hash;
+		let best = BlockId::Hash(best_hash);
+		let api = self.client.runtime_api();
+
+		// Building the pending block is not supported by older runtimes.
+		if ethereum_api_version(self.client.as_ref(), &best, 5).is_err() {
+			return Ok(None);
+		}
+
+		let best_header = self
+			.client
+			.header(best)
+			.map_err(|err| internal_err(format!("{:?}", err)))?
+			.ok_or_else(|| internal_err("best header not found"))?;
+		let header = <B::Header as HeaderT>::new(
+			*best_header.number() + One::one(),
+			Default::default(),
+			Default::default(),
+			best_hash,
+			Default::default(),
+		);
+		api.initialize_block(&best, &header)
+			.map_err(|err| internal_err(format!("Runtime api access error: {:?}", err)))?;
+
+		// Get all transactions in the ready queue, in the order the proposer takes them.
+		let xts: Vec<<B as BlockT>::Extrinsic> = self
+			.graph
+			.validated_pool()
+			.ready()
+			.map(|in_pool_tx| in_pool_tx.data().clone())
+			.collect();
+		let (block, _, statuses) = api
+			.pending_block(&best, xts)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+
+		let schema =
+			frontier_backend_client::onchain_storage_schema::<B, C, BE>(self.client.as_ref(), best);
+		let base_fee = self
+			.overrides
+			.schemas
+			.get(&schema)
+			.unwrap_or(&self.overrides.fallback)
+			.base_fee(&best);
+
+		match (block, statuses) {
+			(Some(block), Some(statuses)) => {
+				let mut rich_block = rich_block_build(
+					block,
+					statuses.into_iter().map(Option::Some).collect(),
+					None,
+					full,
+					base_fee,
+				);
+				// The pending block is not sealed yet.
+				rich_block.inner.header.hash = None;
+				Ok(Some(rich_block))
+			}
+			_ => Ok(None),
+		}
+	}
+
 	pub fn block_transaction_count_by_hash(&self, hash: H256) -> Result<Option<U256>> {
 		let id = match frontier_backend_client::load_hash::<B>(self.backend.as_ref(), hash)
 			.map_err(|err| internal_err(format!("{:?}", err)))?
@@ -193,12 +272,31 @@ where
 		}
 	}
 
-	pub fn block_uncles_count_by_hash(&self, _: H256) -> Result<U256> {
-		Ok(U256::zero())
+	pub fn block_uncles_count_by_hash(&self, hash: H256) -> Result<Option<U256>> {
+		// Blocks have no uncles, but unknown blocks have no count.
+		let id = frontier_backend_client::load_hash::<B>(self.backend.as_ref(), hash)
+			.map_err(|err| internal_err(format!("{:?}", err)))?;
+		Ok(id.map(|_| U256::zero()))
 	}
 
-	pub fn block_uncles_count_by_number(&self, _: BlockNumber) -> Result<U256> {
-		Ok(U256::zero())
+	pub fn block_uncles_count_by_number(&self, number: BlockNumber) -> Result<Option<U256>> {
+		if let BlockNumber::Pending = number {
+			return Ok(Some(U256::zero()));
+		}
+
+		let id = match frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			Some(number),
+		)? {
+			Some(id) => id,
+			None => return Ok(None),
+		};
+		let header = self
+			.client
+			.header(id)
+			.map_err(|err| internal_err(format!("{:?}", err)))?;
+		Ok(header.map(|_| U256::zero()))
 	}
 
 	pub fn uncle_by_block_hash_and_index(&self, _: H256, _: Index) -> Result<Option<RichBlock>> {
@@ -17,13 +17,19 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use codec::Encode;
+use ethereum_types::U256;
 use lru::LruCache;
 
+use fc_rpc_core::types::CacheStats;
+
 pub struct LRUCacheByteLimited<K, V> {
 	cache: LruCache<K, V>,
+	name: &'static str,
 	max_size: u64,
 	metrics: Option<LRUCacheByteLimitedMetrics>,
 	size: u64,
+	hits: u64,
+	misses: u64,
 }
 
 impl<K: Eq + core::hash::Hash, V: Encode> LRUCacheByteLimited<K, V> {
@@ -45,19 +51,24 @@ impl<K: Eq + core::hash::Hash, V: Encode> LRUCacheByteLimited<K, V> {
 
 		Self {
 			cache: LruCache::unbounded(),
+			name: cache_name,
 			max_size,
 			metrics,
 			size: 0,
+			hits: 0,
+			misses: 0,
 		}
 	}
 	pub fn get(&mut self, k: &K) -> Option<&V> {
 		if let Some(v) = self.cache.get(k) {
+			self.hits += 1;
 			// Update metrics
 			if let Some(metrics) = &self.metrics {
 				metrics.hits.inc();
 			}
 			Some(v)
 		} else {
+			self.misses += 1;
 			// Update metrics
 			if let Some(metrics) = &self.metrics {
 				metrics.miss.inc();
@@ -85,6 +96,14 @@ impl<K: Eq + core::hash::Hash, V: Encode> LRUCacheByteLimited<K, V> {
 			metrics.size.set(self.size);
 		}
 	}
+	pub fn stats(&self) -> CacheStats {
+		CacheStats {
+			name: self.name.to_string(),
+			hits: U256::from(self.hits),
+			misses: U256::from(self.misses),
+			size: U256::from(self.size),
+		}
+	}
 }
 
 struct LRUCacheByteLimitedMetrics {
@@ -28,7 +28,8 @@ use std::{
 use self::lru_cache::LRUCacheByteLimited;
 use ethereum::BlockV2 as EthereumBlock;
 use ethereum_types::{H256, U256};
-use futures::StreamExt;
+use futures::{stream::SelectAll, StreamExt};
+use lru::LruCache;
 use tokio::sync::{mpsc, oneshot};
 
 use sc_client_api::{
@@ -36,6 +37,10 @@ use sc_client_api::{
 	client::BlockchainEvents,
 };
 use sc_service::SpawnTaskHandle;
+use sc_transaction_pool::{ChainApi, Pool};
+use sc_transaction_pool_api::{
+	InPoolTransaction, TransactionPool, TransactionStatus as PoolTransactionStatus,
+};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_runtime::{
@@ -54,6 +59,10 @@ use crate::{
 
 type WaitList<Hash, T> = HashMap<Hash, Vec<oneshot::Sender<Option<T>>>>;
 
+/// Maximum number of pool transactions watched by `EthTask::dropped_transactions_task`, the
+/// least recently imported ones being forgotten first.
+const MAX_WATCHED_TRANSACTIONS: usize = 16_384;
+
 enum EthBlockDataCacheMessage<B: BlockT> {
 	RequestCurrentBlock {
 		block_hash: B::Hash,
@@ -74,6 +83,10 @@ enum EthBlockDataCacheMessage<B: BlockT> {
 		block_hash: B::Hash,
 		statuses: Option<Vec<TransactionStatus>>,
 	},
+
+	RequestStats {
+		response_tx: oneshot::Sender<Vec<CacheStats>>,
+	},
 }
 
 /// Manage LRU caches for block data and their transaction statuses.
@@ -181,6 +194,11 @@ impl<B: BlockT> EthBlockDataCacheTask<B> {
 							statuses_cache.put(block_hash, statuses);
 						}
 					}
+
+					RequestStats { response_tx } => {
+						let _ =
+							response_tx.send(vec![blocks_cache.stats(), statuses_cache.stats()]);
+					}
 				}
 			}
 		});
@@ -275,6 +293,22 @@ impl<B: BlockT> EthBlockDataCacheTask<B> {
 
 		response_rx.await.ok()?
 	}
+
+	/// Usage of the caches.
+	pub async fn stats(&self) -> Vec<CacheStats> {
+		let (response_tx, response_rx) = oneshot::channel();
+
+		if self
+			.0
+			.send(EthBlockDataCacheMessage::RequestStats { response_tx })
+			.await
+			.is_err()
+		{
+			return Vec::new();
+		}
+
+		response_rx.await.unwrap_or_default()
+	}
 }
 
 pub struct EthTask<B, C, BE>(PhantomData<(B, C, BE)>);
@@ -289,7 +323,7 @@ where
 	BE::State: StateBackend<BlakeTwo256>,
 {
 	/// Task that caches at which substrate hash a new EthereumStorageSchema was inserted in the Runtime Storage.
-	pub async fn ethereum_schema_cache_task(client: Arc<C>, backend: Arc<fc_db::Backend<B>>) {
+	pub async fn ethereum_schema_cache_task(client: Arc<C>, backend: Arc<dyn fc_api::Backend<B>>) {
 		if let Ok(None) = frontier_backend_client::load_cached_schema::<B>(backend.as_ref()) {
 			// Initialize the schema cache at genesis.
 			let mut cache: Vec<(EthereumStorageSchema, H256)> = Vec::new();
@@ -420,14 +454,187 @@ where
 		}
 	}
 
+	/// Task that records why Ethereum transactions left the pool without being included.
+	///
+	/// Every transaction imported in the pool is watched until it reaches a final state, up to
+	/// the `MAX_WATCHED_TRANSACTIONS` most recently imported ones, keeping the last notable
+	/// status of the Ethereum ones in `dropped_transactions`. The Ethereum transaction wrapped
+	/// by an extrinsic is only extracted, through the runtime, once it has such a status.
+	pub async fn dropped_transactions_task<P, A>(
+		client: Arc<C>,
+		pool: Arc<P>,
+		graph: Arc<Pool<A>>,
+		dropped_transactions: DroppedTransactionCache,
+	) where
+		P: TransactionPool<Block = B, Hash = H256>,
+		A: ChainApi<Block = B>,
+	{
+		// An extrinsic of the pool, with the hash of the Ethereum transaction it wraps once
+		// extracted.
+		struct Watched<Extrinsic> {
+			xt: Extrinsic,
+			hash: Option<Option<H256>>,
+		}
+
+		let pool_extrinsic = |pool_hash: &H256| -> Option<B::Extrinsic> {
+			match pool.ready_transaction(pool_hash) {
+				Some(in_pool_tx) => Some(in_pool_tx.data().clone()),
+				None => graph
+					.validated_pool()
+					.futures()
+					.into_iter()
+					.find(|(hash, _)| hash == pool_hash)
+					.map(|(_, xt)| xt),
+			}
+		};
+
+		// Extracts the hash of the Ethereum transaction wrapped by an extrinsic, if any.
+		let ethereum_hash = |xt: B::Extrinsic| -> Option<H256> {
+			let best_block: BlockId<B> = BlockId::Hash(client.info().best_hash);
+			let api = client.runtime_api();
+			let api_version = api
+				.api_version::<dyn EthereumRuntimeRPCApi<B>>(&best_block)
+				.ok()??;
+
+			let txs: Vec<ethereum::TransactionV2> = if api_version > 1 {
+				api.extrinsic_filter(&best_block, vec![xt]).ok()?
+			} else {
+				#[allow(deprecated)]
+				let legacy = api.extrinsic_filter_before_version_2(&best_block, vec![xt])
+					.ok()?;
+				legacy.into_iter().map(|tx| tx.into()).collect()
+			};
+			match &txs[..] {
+				[tx] => Some(tx.hash()),
+				_ => None,
+			}
+		};
+		let watched_hash = |watched: &mut Watched<B::Extrinsic>| -> Option<H256> {
+			if watched.hash.is_none() {
+				watched.hash = Some(ethereum_hash(watched.xt.clone()));
+			}
+			watched.hash.flatten()
+		};
+
+		let record = |hash: H256, status: Option<DroppedTransactionStatus>| {
+			if let Ok(dropped_transactions) = &mut dropped_transactions.lock() {
+				match status {
+					Some(status) => dropped_transactions.insert(hash, status),
+					None => dropped_transactions.remove(&hash),
+				}
+			}
+		};
+
+		let mut import_st = pool.import_notification_stream().fuse();
+		let mut status_st = SelectAll::new();
+		let mut watched = LruCache::<H256, Watched<B::Extrinsic>>::new(MAX_WATCHED_TRANSACTIONS);
+
+		loop {
+			futures::select! {
+				pool_hash = import_st.next() => {
+					let pool_hash = match pool_hash {
+						Some(pool_hash) => pool_hash,
+						None => break,
+					};
+					if let Some(xt) = pool_extrinsic(&pool_hash) {
+						watched.put(pool_hash, Watched { xt, hash: None });
+						status_st.push(
+							graph
+								.validated_pool()
+								.watch(pool_hash)
+								.into_stream()
+								.map(move |status| (pool_hash, status)),
+						);
+					}
+				},
+				(pool_hash, status) = status_st.select_next_some() => {
+					let entry = match watched.get_mut(&pool_hash) {
+						Some(entry) => entry,
+						None => continue,
+					};
+					match status {
+						PoolTransactionStatus::Usurped(by) => {
+							if let Some(hash) = watched_hash(entry) {
+								let replaced_by = match watched.get_mut(&by) {
+									Some(by) => watched_hash(by),
+									None => pool_extrinsic(&by).and_then(ethereum_hash),
+								};
+								record(hash, Some(DroppedTransactionStatus::Replaced { replaced_by }));
+							}
+							watched.pop(&pool_hash);
+						}
+						PoolTransactionStatus::Invalid => {
+							if let Some(hash) = watched_hash(entry) {
+								record(hash, Some(DroppedTransactionStatus::Invalid));
+							}
+							watched.pop(&pool_hash);
+						}
+						PoolTransactionStatus::Dropped => {
+							if let Some(hash) = watched_hash(entry) {
+								record(hash, Some(DroppedTransactionStatus::Dropped));
+							}
+							watched.pop(&pool_hash);
+						}
+						PoolTransactionStatus::Retracted(block_hash) => {
+							if let Some(hash) = watched_hash(entry) {
+								record(hash, Some(DroppedTransactionStatus::Retracted { block_hash }));
+							}
+						}
+						// Included again after a re-org, only recorded once retracted.
+						PoolTransactionStatus::InBlock(_) => {
+							if let Some(Some(hash)) = entry.hash {
+								record(hash, None);
+							}
+						}
+						PoolTransactionStatus::Finalized(_)
+						| PoolTransactionStatus::FinalityTimeout(_) => {
+							watched.pop(&pool_hash);
+						}
+						_ => {}
+					}
+				},
+			}
+		}
+	}
+
+	/// Task caching the fee history of the new best blocks, persisted in `backend` so that the
+	/// cache is filled again on restart.
 	pub async fn fee_history_task(
 		client: Arc<C>,
 		overrides: Arc<OverrideHandle<B>>,
+		backend: Arc<dyn fc_api::Backend<B>>,
 		fee_history_cache: FeeHistoryCache,
 		block_limit: u64,
 	) {
 		use sp_runtime::Permill;
 
+		match backend.fee_history() {
+			Ok(fee_history) => {
+				if let Ok(fee_history_cache) = &mut fee_history_cache.lock() {
+					let latest = fee_history
+						.last()
+						.map(|(block_number, _)| *block_number)
+						.unwrap_or_default();
+					fee_history_cache.extend(
+						fee_history
+							.into_iter()
+							.filter(|(block_number, _)| block_number + block_limit > latest)
+							.map(|(block_number, entry)| {
+								(
+									block_number,
+									FeeHistoryCacheItem {
+										base_fee: entry.base_fee,
+										gas_used_ratio: f64::from_bits(entry.gas_used_ratio),
+										rewards: entry.rewards,
+									},
+								)
+							}),
+					);
+				}
+			}
+			Err(err) => log::warn!("Error loading the persisted fee history: {:?}", err),
+		}
+
 		struct TransactionHelper {
 			gas_used: u64,
 			effective_reward: u64,
@@ -547,6 +754,14 @@ where
 			if let (Some(block_number), Ok(fee_history_cache)) =
 				(key, &mut fee_history_cache.lock())
 			{
+				let entry = fc_api::FeeHistoryEntry {
+					base_fee: item.base_fee,
+					gas_used_ratio: item.gas_used_ratio.to_bits(),
+					rewards: item.rewards.clone(),
+				};
+				if let Err(err) = backend.write_fee_history(block_number, &entry, block_limit) {
+					log::warn!("Error persisting the fee history: {:?}", err);
+				}
 				fee_history_cache.insert(block_number, item);
 				// We want to remain within the configured cache bounds.
 				// The first key out of bounds.
@@ -21,7 +21,7 @@ mod tests {
 	use crate::{frontier_backend_client, EthTask};
 
 	use codec::Encode;
-	use std::{path::PathBuf, sync::Arc, thread, time};
+	use std::{sync::Arc, thread, time};
 
 	use fp_storage::{EthereumStorageSchema, PALLET_ETHEREUM_SCHEMA};
 	use frontier_template_runtime::RuntimeApi;
@@ -36,27 +36,19 @@ mod tests {
 	use substrate_test_runtime_client::{
 		prelude::*, DefaultTestClientBuilderExt, TestClientBuilder, TestClientBuilderExt,
 	};
-	use tempfile::tempdir;
 
 	type OpaqueBlock =
 		Block<Header<u64, BlakeTwo256>, substrate_test_runtime_client::runtime::Extrinsic>;
 
-	pub fn open_frontier_backend(
-		path: PathBuf,
-	) -> Result<Arc<fc_db::Backend<OpaqueBlock>>, String> {
-		Ok(Arc::new(fc_db::Backend::<OpaqueBlock>::new(
-			&fc_db::DatabaseSettings {
-				source: sc_client_db::DatabaseSource::RocksDb {
-					path,
-					cache_size: 0,
-				},
-			},
-		)?))
+	fn open_frontier_backend() -> Arc<dyn fc_api::Backend<OpaqueBlock>> {
+		Arc::new(fc_db::Backend::<OpaqueBlock>::new_in_memory(
+			fc_db::TransactionIndexing::Full,
+			false,
+		))
 	}
 
 	#[test]
 	fn should_cache_pallet_ethereum_schema() {
-		let tmp = tempdir().expect("create a temporary directory");
 		// Initialize storage with schema V1.
 		let builder = TestClientBuilder::new().add_extra_storage(
 			PALLET_ETHEREUM_SCHEMA.to_vec(),
@@ -65,8 +57,8 @@ mod tests {
 		let (client, _) = builder.build_with_native_executor::<RuntimeApi, _>(None);
 		let mut client = Arc::new(client);
 
-		// Create a temporary frontier secondary DB.
-		let frontier_backend = open_frontier_backend(tmp.into_path()).unwrap();
+		// Create an in-memory frontier secondary DB.
+		let frontier_backend = open_frontier_backend();
 
 		// Spawn `frontier-schema-cache-task` background task.
 		let spawner = sp_core::testing::TaskExecutor::new();
@@ -123,7 +115,6 @@ mod tests {
 
 	#[test]
 	fn should_handle_cache_on_multiple_forks() {
-		let tmp = tempdir().expect("create a temporary directory");
 		// Initialize storage with schema V1.
 		let builder = TestClientBuilder::new().add_extra_storage(
 			PALLET_ETHEREUM_SCHEMA.to_vec(),
@@ -132,8 +123,8 @@ mod tests {
 		let (client, _) = builder.build_with_native_executor::<RuntimeApi, _>(None);
 		let mut client = Arc::new(client);
 
-		// Create a temporary frontier secondary DB.
-		let frontier_backend = open_frontier_backend(tmp.into_path()).unwrap();
+		// Create an in-memory frontier secondary DB.
+		let frontier_backend = open_frontier_backend();
 
 		// Spawn `frontier-schema-cache-task` background task.
 		let spawner = sp_core::testing::TaskExecutor::new();
@@ -16,83 +16,100 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
-use ethereum_types::{H256, U256};
+use ethereum::AccessListItem;
+use ethereum_types::{H160, H256, U256, U64};
 use evm::{ExitError, ExitReason};
 use jsonrpc_core::{BoxFuture, Error, ErrorCode, Result, Value};
 
 use sc_client_api::backend::{Backend, StateBackend, StorageProvider};
 use sc_network::ExHashT;
-use sc_transaction_pool::ChainApi;
-use sp_api::{ApiExt, ProvideRuntimeApi};
+use sc_transaction_pool::{ChainApi, Pool};
+use sp_api::{ApiExt, ApiRef, ProvideRuntimeApi, TransactionOutcome};
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::{BlockStatus, HeaderBackend};
 use sp_runtime::{
 	generic::BlockId,
-	traits::{BlakeTwo256, Block as BlockT},
+	traits::{BlakeTwo256, Block as BlockT, UniqueSaturatedInto},
 };
 
 use fc_rpc_core::types::*;
 use fp_rpc::EthereumRuntimeRPCApi;
 
 use crate::{
-	eth::{pending_runtime_api, Eth},
-	frontier_backend_client, internal_err,
+	eth::{pending_runtime_api, sender_pending_runtime_api, Eth},
+	ethereum_api_version, frontier_backend_client, internal_err,
+	overrides::legacy,
+	runtime_upgrade_err,
 };
 
 /// Default JSONRPC error code return by geth
 pub const JSON_RPC_ERROR_DEFAULT: i64 = -32000;
 
-impl<B, C, P, CT, BE, H: ExHashT, A: ChainApi> Eth<B, C, P, CT, BE, H, A>
+/// Maximum number of blocks of an `eth_simulateV1` request.
+const MAX_SIMULATE_BLOCKS: usize = 256;
+
+/// Maximum number of calls, over all its blocks, of an `eth_simulateV1` request.
+const MAX_SIMULATE_CALLS: usize = 1_000;
+
+/// Runtime api set up to execute calls in the context of a given block.
+///
+/// It resolves the requested block, its runtime api version and, for the pending block,
+/// the overlay of the ready transactions, so every executing method queries historical
+/// blocks and runtime upgrades in the same way.
+pub(crate) struct ExecuteInBlock<'a, B: BlockT, C: ProvideRuntimeApi<B>> {
+	pub id: BlockId<B>,
+	pub api: ApiRef<'a, C::Api>,
+	pub api_version: u32,
+}
+
+/// Outcome of a call or a create executed by [`ExecuteInBlock`].
+pub(crate) struct ExecutionResult {
+	pub exit_reason: ExitReason,
+	/// Returned data of a call, empty for a create.
+	pub value: Vec<u8>,
+	pub used_gas: U256,
+	/// Address of the created contract, for a create.
+	pub contract_address: Option<H160>,
+	pub logs: Vec<ethereum::Log>,
+}
+
+impl<'a, B, C> ExecuteInBlock<'a, B, C>
 where
 	B: BlockT<Hash = H256> + Send + Sync + 'static,
-	C: ProvideRuntimeApi<B> + StorageProvider<B, BE>,
-	C: HeaderBackend<B> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync + 'static,
 	C::Api: BlockBuilderApi<B> + EthereumRuntimeRPCApi<B>,
-	BE: Backend<B> + 'static,
-	BE::State: StateBackend<BlakeTwo256>,
-	A: ChainApi<Block = B> + 'static,
 {
-	pub fn call(&self, request: CallRequest, number: Option<BlockNumber>) -> Result<Bytes> {
-		let CallRequest {
-			from,
-			to,
-			gas_price,
-			max_fee_per_gas,
-			max_priority_fee_per_gas,
-			gas,
-			value,
-			data,
-			nonce,
-			access_list,
-			..
-		} = request;
-
-		let (gas_price, max_fee_per_gas, max_priority_fee_per_gas) = {
-			let details = fee_details(gas_price, max_fee_per_gas, max_priority_fee_per_gas)?;
-			(
-				details.gas_price,
-				details.max_fee_per_gas,
-				details.max_priority_fee_per_gas,
-			)
-		};
-
-		let (id, api) = match frontier_backend_client::native_block_id::<B, C>(
-			self.client.as_ref(),
-			self.backend.as_ref(),
-			number,
-		)? {
-			Some(id) => (id, self.client.runtime_api()),
-			None => {
-				// Not mapped in the db, assume pending.
-				let id = BlockId::Hash(self.client.info().best_hash);
-				let api = pending_runtime_api(self.client.as_ref(), self.graph.as_ref())?;
-				(id, api)
-			}
-		};
+	pub fn new<BE, A>(
+		client: &'a C,
+		backend: &dyn fc_api::Backend<B>,
+		graph: &'a Pool<A>,
+		number: Option<BlockNumber>,
+		sender: Option<H160>,
+	) -> Result<Self>
+	where
+		C: StorageProvider<B, BE>,
+		BE: Backend<B> + 'static,
+		BE::State: StateBackend<BlakeTwo256>,
+		A: ChainApi<Block = B> + 'static,
+	{
+		let (id, api) =
+			match frontier_backend_client::native_block_id::<B, C>(client, backend, number)? {
+				Some(id) => (id, client.runtime_api()),
+				None => {
+					// Not mapped in the db, assume pending. Only the ready transactions of the
+					// sender are applied if it is given.
+					let id = BlockId::Hash(client.info().best_hash);
+					let api = match sender {
+						Some(sender) => sender_pending_runtime_api(client, graph, sender)?,
+						None => pending_runtime_api(client, graph)?,
+					};
+					(id, api)
+				}
+			};
 
-		if let Ok(BlockStatus::Unknown) = self.client.status(id) {
+		if let Ok(BlockStatus::Unknown) = client.status(id) {
 			return Err(Error {
 				code: JSON_RPC_ERROR_DEFAULT.into(),
 				message: String::from("header not found"),
@@ -100,476 +117,745 @@ where
 			});
 		}
 
-		let api_version =
-			if let Ok(Some(api_version)) = api.api_version::<dyn EthereumRuntimeRPCApi<B>>(&id) {
-				api_version
-			} else {
-				return Err(internal_err("failed to retrieve Runtime Api version"));
+		let api_version = ethereum_api_version(client, &id, 1)?;
+
+		Ok(Self {
+			id,
+			api,
+			api_version,
+		})
+	}
+
+	/// Gas limit of the Ethereum block.
+	pub fn gas_limit(&self) -> Result<U256> {
+		let block = legacy::current_block(&*self.api, &self.id, self.api_version)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+
+		if let Some(block) = block {
+			Ok(block.header.gas_limit)
+		} else {
+			Err(internal_err("block unavailable, cannot query gas limit"))
+		}
+	}
+
+	/// Applies block and state overrides to the runtime api instance, on top of which the
+	/// subsequent executions run.
+	fn apply_overrides(
+		&self,
+		block_overrides: Option<&BlockOverrides>,
+		state_overrides: Option<BTreeMap<H160, AccountOverride>>,
+	) -> Result<()> {
+		if block_overrides.is_none() && state_overrides.is_none() {
+			return Ok(());
+		}
+		if self.api_version < 7 {
+			return Err(runtime_upgrade_err(7, Some(self.api_version)));
+		}
+
+		if let Some(overrides) = block_overrides {
+			// The runtime timestamp is in milliseconds.
+			let timestamp = overrides
+				.time
+				.map(|time| time.as_u64().saturating_mul(1000));
+			self.api
+				.override_block(&self.id, overrides.number, timestamp)
+				.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+				.map_err(|err| {
+					Error::invalid_params(format!("invalid block overrides: {:?}", err))
+				})?;
+		}
+		for (address, account) in state_overrides.unwrap_or_default() {
+			let account = fp_rpc::AccountOverride {
+				balance: account.balance,
+				nonce: account.nonce,
+				code: account.code.map(|code| code.into_vec()),
+				state: account.state.map(|state| state.into_iter().collect()),
+				state_diff: account
+					.state_diff
+					.map(|state_diff| state_diff.into_iter().collect())
+					.unwrap_or_default(),
 			};
-		// use given gas limit or query current block's limit
-		let gas_limit = match gas {
-			Some(amount) => amount,
-			None => {
-				let block = if api_version > 1 {
-					api.current_block(&id)
-						.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-				} else {
-					#[allow(deprecated)]
-					let legacy_block = api.current_block_before_version_2(&id)
-						.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
-					legacy_block.map(|block| block.into())
-				};
+			self.api
+				.override_account(&self.id, address, account)
+				.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+				.map_err(|err| {
+					Error::invalid_params(format!(
+						"invalid state override of {:?}: {:?}",
+						address, err
+					))
+				})?;
+		}
+		Ok(())
+	}
 
-				if let Some(block) = block {
-					block.header.gas_limit
-				} else {
-					return Err(internal_err("block unavailable, cannot query gas limit"));
-				}
-			}
-		};
+	/// Execute the given request like [`Self::execute`], reverting its changes, so that the
+	/// next executions of the same runtime api instance run on the same state.
+	pub fn execute_reverted(
+		&self,
+		request: CallRequest,
+		gas_limit: U256,
+		fee_details: &FeeDetails,
+		estimate: bool,
+	) -> Result<ExecutionResult> {
+		self.api.execute_in_transaction(|_| {
+			TransactionOutcome::Rollback(self.execute(request, gas_limit, fee_details, estimate))
+		})
+	}
+
+	/// Execute the given request with the given gas limit, using the runtime api matching
+	/// the block runtime version.
+	pub fn execute(
+		&self,
+		request: CallRequest,
+		gas_limit: U256,
+		fee_details: &FeeDetails,
+		estimate: bool,
+	) -> Result<ExecutionResult> {
+		let CallRequest {
+			from,
+			to,
+			value,
+			data,
+			nonce,
+			access_list,
+			..
+		} = request;
+		let FeeDetails {
+			gas_price,
+			max_fee_per_gas,
+			max_priority_fee_per_gas,
+		} = *fee_details;
+
+		let from = from.unwrap_or_default();
+		let value = value.unwrap_or_default();
 		let data = data.map(|d| d.0).unwrap_or_default();
+		let access_list = access_list.map(|access_list| {
+			access_list
+				.into_iter()
+				.map(|item| (item.address, item.storage_keys))
+				.collect()
+		});
+
+		// The signatures of `call` and `create` last changed in version 4: the later versions of
+		// the runtime api only add methods, and are dispatched as version 4.
 		match to {
 			Some(to) => {
-				if api_version == 1 {
+				let info = if self.api_version == 1 {
 					// Legacy pre-london
 					#[allow(deprecated)]
-					let info = api.call_before_version_2(
-						&id,
-						from.unwrap_or_default(),
+					self.api.call_before_version_2(
+						&self.id, from, to, data, value, gas_limit, gas_price, nonce, estimate,
+					)
+				} else if self.api_version < 4 {
+					// Post-london
+					#[allow(deprecated)]
+					self.api.call_before_version_4(
+						&self.id,
+						from,
 						to,
 						data,
-						value.unwrap_or_default(),
+						value,
 						gas_limit,
-						gas_price,
+						max_fee_per_gas,
+						max_priority_fee_per_gas,
 						nonce,
-						false,
+						estimate,
 					)
-					.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-					.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
-
-					error_on_execution_failure(&info.exit_reason, &info.value)?;
-					Ok(Bytes(info.value))
-				} else if api_version >= 2 && api_version < 4 {
-					// Post-london
-					#[allow(deprecated)]
-					let info = api.call_before_version_4(
-						&id,
-						from.unwrap_or_default(),
+				} else {
+					// Post-london + access list support
+					self.api.call(
+						&self.id,
+						from,
 						to,
 						data,
-						value.unwrap_or_default(),
+						value,
 						gas_limit,
 						max_fee_per_gas,
 						max_priority_fee_per_gas,
 						nonce,
-						false,
+						estimate,
+						Some(access_list.unwrap_or_default()),
 					)
-					.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-					.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
-
-					error_on_execution_failure(&info.exit_reason, &info.value)?;
-					Ok(Bytes(info.value))
-				} else if api_version == 4 {
-					// Post-london + access list support
-					let access_list = access_list.unwrap_or_default();
-					let info = api
-						.call(
-							&id,
-							from.unwrap_or_default(),
-							to,
-							data,
-							value.unwrap_or_default(),
-							gas_limit,
-							max_fee_per_gas,
-							max_priority_fee_per_gas,
-							nonce,
-							false,
-							Some(
-								access_list
-									.into_iter()
-									.map(|item| (item.address, item.storage_keys))
-									.collect(),
-							),
-						)
-						.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-						.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
-
-					error_on_execution_failure(&info.exit_reason, &info.value)?;
-					Ok(Bytes(info.value))
-				} else {
-					Err(internal_err("failed to retrieve Runtime Api version"))
 				}
+				.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+				.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+
+				Ok(ExecutionResult {
+					exit_reason: info.exit_reason,
+					value: info.value,
+					used_gas: info.used_gas,
+					contract_address: None,
+					logs: info.logs,
+				})
 			}
 			None => {
-				if api_version == 1 {
+				let info = if self.api_version == 1 {
 					// Legacy pre-london
 					#[allow(deprecated)]
-					let info = api.create_before_version_2(
-						&id,
-						from.unwrap_or_default(),
-						data,
-						value.unwrap_or_default(),
-						gas_limit,
-						gas_price,
-						nonce,
-						false,
+					self.api.create_before_version_2(
+						&self.id, from, data, value, gas_limit, gas_price, nonce, estimate,
 					)
-					.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-					.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
-
-					error_on_execution_failure(&info.exit_reason, &[])?;
-
-					let code = api
-						.account_code_at(&id, info.value)
-						.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
-					Ok(Bytes(code))
-				} else if api_version >= 2 && api_version < 4 {
+				} else if self.api_version < 4 {
 					// Post-london
 					#[allow(deprecated)]
-					let info = api.create_before_version_4(
-						&id,
-						from.unwrap_or_default(),
+					self.api.create_before_version_4(
+						&self.id,
+						from,
 						data,
-						value.unwrap_or_default(),
+						value,
 						gas_limit,
 						max_fee_per_gas,
 						max_priority_fee_per_gas,
 						nonce,
-						false,
+						estimate,
 					)
-					.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-					.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
-
-					error_on_execution_failure(&info.exit_reason, &[])?;
-
-					let code = api
-						.account_code_at(&id, info.value)
-						.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
-					Ok(Bytes(code))
-				} else if api_version == 4 {
-					// Post-london + access list support
-					let access_list = access_list.unwrap_or_default();
-					let info = api
-						.create(
-							&id,
-							from.unwrap_or_default(),
-							data,
-							value.unwrap_or_default(),
-							gas_limit,
-							max_fee_per_gas,
-							max_priority_fee_per_gas,
-							nonce,
-							false,
-							Some(
-								access_list
-									.into_iter()
-									.map(|item| (item.address, item.storage_keys))
-									.collect(),
-							),
-						)
-						.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-						.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
-
-					error_on_execution_failure(&info.exit_reason, &[])?;
-
-					let code = api
-						.account_code_at(&id, info.value)
-						.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
-					Ok(Bytes(code))
 				} else {
-					Err(internal_err("failed to retrieve Runtime Api version"))
+					// Post-london + access list support
+					self.api.create(
+						&self.id,
+						from,
+						data,
+						value,
+						gas_limit,
+						max_fee_per_gas,
+						max_priority_fee_per_gas,
+						nonce,
+						estimate,
+						Some(access_list.unwrap_or_default()),
+					)
 				}
+				.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+				.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+
+				Ok(ExecutionResult {
+					exit_reason: info.exit_reason,
+					value: Vec::new(),
+					used_gas: info.used_gas,
+					contract_address: Some(info.value),
+					logs: info.logs,
+				})
 			}
 		}
 	}
 
-	pub fn estimate_gas(
+	/// Dry runs the given request with the given gas limit, returning it with the storage it
+	/// accessed.
+	fn dry_run(
 		&self,
 		request: CallRequest,
-		_: Option<BlockNumber>,
-	) -> BoxFuture<Result<U256>> {
+		gas_limit: U256,
+		fee_details: &FeeDetails,
+	) -> Result<(ExecutionResult, Vec<(H160, Vec<H256>)>)> {
+		if self.api_version < 12 {
+			return Err(runtime_upgrade_err(12, Some(self.api_version)));
+		}
+
+		let CallRequest {
+			from,
+			to,
+			value,
+			data,
+			nonce,
+			access_list,
+			..
+		} = request;
+		let access_list = access_list.map(|access_list| {
+			access_list
+				.into_iter()
+				.map(|item| (item.address, item.storage_keys))
+				.collect()
+		});
+
+		let fp_evm::DryRunInfo {
+			info, access_list, ..
+		} = self
+			.api
+			.dry_run(
+				&self.id,
+				from.unwrap_or_default(),
+				to,
+				data.map(|d| d.0).unwrap_or_default(),
+				value.unwrap_or_default(),
+				gas_limit,
+				fee_details.max_fee_per_gas,
+				fee_details.max_priority_fee_per_gas,
+				nonce,
+				false,
+				access_list,
+			)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+			.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+
+		let result = match info {
+			fp_evm::CallOrCreateInfo::Call(info) => ExecutionResult {
+				exit_reason: info.exit_reason,
+				value: info.value,
+				used_gas: info.used_gas,
+				contract_address: None,
+				logs: info.logs,
+			},
+			fp_evm::CallOrCreateInfo::Create(info) => ExecutionResult {
+				exit_reason: info.exit_reason,
+				value: Vec::new(),
+				used_gas: info.used_gas,
+				contract_address: Some(info.value),
+				logs: info.logs,
+			},
+		};
+		Ok((result, access_list))
+	}
+}
+
+impl<B, C, P, CT, BE, H: ExHashT, A: ChainApi> Eth<B, C, P, CT, BE, H, A>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + StorageProvider<B, BE>,
+	C: HeaderBackend<B> + Send + Sync + 'static,
+	C::Api: BlockBuilderApi<B> + EthereumRuntimeRPCApi<B>,
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+	A: ChainApi<Block = B> + 'static,
+{
+	pub fn call(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+	) -> BoxFuture<Result<Bytes>> {
 		let client = Arc::clone(&self.client);
-		let block_data_cache = Arc::clone(&self.block_data_cache);
+		let backend = Arc::clone(&self.backend);
+		let graph = Arc::clone(&self.graph);
+		let gas_cap = self.gas_cap;
 
-		Box::pin(async move {
-			// Define the lower bound of estimate
-			const MIN_GAS_PER_TX: U256 = U256([21_000, 0, 0, 0]);
+		self.execution_pool.spawn(move |_| {
+			let fee_details = fee_details(
+				request.gas_price,
+				request.max_fee_per_gas,
+				request.max_priority_fee_per_gas,
+			)?;
 
-			// Get best hash (TODO missing support for estimating gas historically)
-			let best_hash = client.info().best_hash;
+			let execute_in_block = ExecuteInBlock::new(
+				client.as_ref(),
+				backend.as_ref(),
+				graph.as_ref(),
+				number,
+				None,
+			)?;
 
-			// For simple transfer to simple account, return MIN_GAS_PER_TX directly
-			let is_simple_transfer = match &request.data {
-				None => true,
-				Some(vec) => vec.0.is_empty(),
+			// use given gas limit or query current block's limit
+			let gas_limit = match request.gas {
+				Some(amount) => amount,
+				None => execute_in_block.gas_limit()?,
+			};
+			let gas_limit = cap_gas_limit(gas_limit, gas_cap);
+
+			// Calls run through the dry run of the runtime, as `eth_createAccessList`. Creations
+			// are executed, as their deployed code is read back from the state they changed.
+			let info = if request.to.is_some() && execute_in_block.api_version >= 12 {
+				execute_in_block
+					.dry_run(request, gas_limit, &fee_details)?
+					.0
+			} else {
+				execute_in_block.execute(request, gas_limit, &fee_details, false)?
 			};
-			if is_simple_transfer {
-				if let Some(to) = request.to {
-					let to_code = client
-						.runtime_api()
-						.account_code_at(&BlockId::Hash(best_hash), to)
+			error_on_execution_failure(&info.exit_reason, &info.value)?;
+
+			match info.contract_address {
+				Some(contract_address) => {
+					let code = execute_in_block
+						.api
+						.account_code_at(&execute_in_block.id, contract_address)
 						.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
-					if to_code.is_empty() {
-						return Ok(MIN_GAS_PER_TX);
-					}
+					Ok(Bytes(code))
 				}
+				None => Ok(Bytes(info.value)),
 			}
+		})
+	}
 
-			let (gas_price, max_fee_per_gas, max_priority_fee_per_gas) = {
-				let details = fee_details(
-					request.gas_price,
-					request.max_fee_per_gas,
-					request.max_priority_fee_per_gas,
-				)?;
-				(
-					details.gas_price,
-					details.max_fee_per_gas,
-					details.max_priority_fee_per_gas,
-				)
-			};
+	pub fn create_access_list(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+	) -> BoxFuture<Result<AccessListResult>> {
+		let client = Arc::clone(&self.client);
+		let backend = Arc::clone(&self.backend);
+		let graph = Arc::clone(&self.graph);
+		let gas_cap = self.gas_cap;
 
-			let get_current_block_gas_limit = || async {
-				let substrate_hash = client.info().best_hash;
-				let id = BlockId::Hash(substrate_hash);
-				let schema =
-					frontier_backend_client::onchain_storage_schema::<B, C, BE>(&client, id);
-				let block = block_data_cache.current_block(schema, substrate_hash).await;
-				if let Some(block) = block {
-					Ok(block.header.gas_limit)
-				} else {
-					Err(internal_err("block unavailable, cannot query gas limit"))
-				}
+		self.execution_pool.spawn(move |cancellation| {
+			let fee_details = fee_details(
+				request.gas_price,
+				request.max_fee_per_gas,
+				request.max_priority_fee_per_gas,
+			)?;
+
+			let execute_in_block = ExecuteInBlock::new(
+				client.as_ref(),
+				backend.as_ref(),
+				graph.as_ref(),
+				number,
+				None,
+			)?;
+
+			let gas_limit = match request.gas {
+				Some(amount) => amount,
+				None => execute_in_block.gas_limit()?,
 			};
+			let gas_limit = cap_gas_limit(gas_limit, gas_cap);
 
-			// Determine the highest possible gas limits
-			let mut highest = match request.gas {
-				Some(gas) => gas,
-				None => {
-					// query current block's gas limit
-					get_current_block_gas_limit().await?
+			// The access list changes the gas costs, and so possibly the accesses of the call,
+			// so the call is executed again with the access list it accessed until it is
+			// stable.
+			let mut request = request;
+			loop {
+				cancellation.check()?;
+				let (info, accessed) =
+					execute_in_block.dry_run(request.clone(), gas_limit, &fee_details)?;
+				let access_list = accessed
+					.into_iter()
+					.map(|(address, storage_keys)| AccessListItem {
+						address,
+						storage_keys,
+					})
+					.collect::<Vec<_>>();
+
+				if request.access_list.as_ref() == Some(&access_list) {
+					let error = error_on_execution_failure(&info.exit_reason, &info.value)
+						.err()
+						.map(|err| err.message);
+					return Ok(AccessListResult {
+						access_list,
+						gas_used: info.used_gas,
+						error,
+					});
 				}
-			};
+				request.access_list = Some(access_list);
+			}
+		})
+	}
 
-			let api = client.runtime_api();
-
-			// Recap the highest gas allowance with account's balance.
-			if let Some(from) = request.from {
-				let gas_price = gas_price.unwrap_or_default();
-				if gas_price > U256::zero() {
-					let balance = api
-						.account_basic(&BlockId::Hash(best_hash), from)
-						.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-						.balance;
-					let mut available = balance;
-					if let Some(value) = request.value {
-						if value > available {
-							return Err(internal_err("insufficient funds for transfer"));
-						}
-						available -= value;
-					}
-					let allowance = available / gas_price;
-					if highest > allowance {
-						log::warn!(
-							"Gas estimation capped by limited funds original {} balance {} sent {} feecap {} fundable {}",
-							highest,
-							balance,
-							request.value.unwrap_or_default(),
-							gas_price,
-							allowance
-						);
-						highest = allowance;
-					}
+	pub fn simulate_v1(
+		&self,
+		payload: SimulatePayload,
+		number: Option<BlockNumber>,
+	) -> BoxFuture<Result<Vec<SimulatedBlock>>> {
+		let client = Arc::clone(&self.client);
+		let backend = Arc::clone(&self.backend);
+		let graph = Arc::clone(&self.graph);
+		let gas_cap = self.gas_cap;
+
+		self.execution_pool.spawn(move |cancellation| {
+			if payload.block_state_calls.len() > MAX_SIMULATE_BLOCKS {
+				return Err(Error::invalid_params(format!(
+					"more than {} blocks",
+					MAX_SIMULATE_BLOCKS
+				)));
+			}
+			let calls: usize = payload
+				.block_state_calls
+				.iter()
+				.map(|block| block.calls.len())
+				.sum();
+			if calls > MAX_SIMULATE_CALLS {
+				return Err(Error::invalid_params(format!(
+					"more than {} calls",
+					MAX_SIMULATE_CALLS
+				)));
+			}
+
+			// Calls are executed with the same runtime api instance, so each of them sees the
+			// changes of the previous ones.
+			let execute_in_block = ExecuteInBlock::new(
+				client.as_ref(),
+				backend.as_ref(),
+				graph.as_ref(),
+				number,
+				None,
+			)?;
+			let id = &execute_in_block.id;
+			let api = &execute_in_block.api;
+
+			let block_gas_limit = cap_gas_limit(execute_in_block.gas_limit()?, gas_cap);
+			let base_number = client
+				.block_number_from_id(id)
+				.map_err(|err| internal_err(format!("{:?}", err)))?
+				.map(|number| {
+					U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(number))
+				})
+				.ok_or_else(|| internal_err("header not found"))?;
+
+			let mut blocks = Vec::with_capacity(payload.block_state_calls.len());
+			for block in payload.block_state_calls {
+				let number = block
+					.block_overrides
+					.as_ref()
+					.and_then(|overrides| overrides.number)
+					.unwrap_or(base_number);
+				execute_in_block
+					.apply_overrides(block.block_overrides.as_ref(), block.state_overrides)?;
+
+				let mut gas_used = U256::zero();
+				let mut calls = Vec::with_capacity(block.calls.len());
+				for request in block.calls {
+					cancellation.check()?;
+					let fee_details = fee_details(
+						request.gas_price,
+						request.max_fee_per_gas,
+						request.max_priority_fee_per_gas,
+					)?;
+					let gas_limit = cap_gas_limit(request.gas.unwrap_or(block_gas_limit), gas_cap);
+					let info = execute_in_block.execute(request, gas_limit, &fee_details, false)?;
+					gas_used = gas_used.saturating_add(info.used_gas);
+
+					let error = error_on_execution_failure(&info.exit_reason, &info.value)
+						.err()
+						.map(|err| SimulateCallError {
+							code: err.code.code(),
+							message: err.message,
+						});
+					let return_data = match info.contract_address {
+						Some(contract_address) if error.is_none() => api
+							.account_code_at(id, contract_address)
+							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?,
+						_ => info.value,
+					};
+					let logs = info
+						.logs
+						.into_iter()
+						.enumerate()
+						.map(|(index, log)| Log {
+							address: log.address,
+							topics: log.topics,
+							data: Bytes(log.data),
+							block_hash: None,
+							block_number: Some(number),
+							transaction_hash: None,
+							transaction_index: None,
+							log_index: None,
+							transaction_log_index: Some(U256::from(index)),
+							removed: false,
+						})
+						.collect();
+
+					calls.push(SimulatedCall {
+						return_data: Bytes(return_data),
+						logs,
+						gas_used: info.used_gas,
+						status: if error.is_none() {
+							U64::one()
+						} else {
+							U64::zero()
+						},
+						error,
+					});
 				}
+
+				blocks.push(SimulatedBlock {
+					number,
+					gas_used,
+					calls,
+				});
 			}
 
-			struct ExecutableResult {
-				data: Vec<u8>,
-				exit_reason: ExitReason,
-				used_gas: U256,
+			Ok(blocks)
+		})
+	}
+
+	pub fn trace_call(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+		config: Option<TraceCallConfig>,
+	) -> BoxFuture<Result<CallFrame>> {
+		let client = Arc::clone(&self.client);
+		let backend = Arc::clone(&self.backend);
+		let graph = Arc::clone(&self.graph);
+		let gas_cap = self.gas_cap;
+
+		self.execution_pool.spawn(move |_| {
+			let config = config.unwrap_or_default();
+			match config.tracer.as_deref() {
+				Some("callTracer") => {}
+				_ => {
+					return Err(internal_err(
+						"unsupported tracer, only callTracer is supported",
+					))
+				}
 			}
+			// The runtime doesn't trace the EVM, so the inner calls are unknown.
+			let only_top_call = config
+				.tracer_config
+				.as_ref()
+				.map_or(false, |tracer_config| tracer_config.only_top_call);
+			if !only_top_call {
+				return Err(internal_err(
+					"unsupported tracer config, callTracer requires onlyTopCall",
+				));
+			}
+			let fee_details = fee_details(
+				request.gas_price,
+				request.max_fee_per_gas,
+				request.max_priority_fee_per_gas,
+			)?;
 
-			// Create a helper to check if a gas allowance results in an executable transaction.
-			//
-			// A new ApiRef instance needs to be used per execution to avoid the overlayed state to affect
-			// the estimation result of subsequent calls.
-			//
-			// Note that this would have a performance penalty if we introduce gas estimation for past
-			// blocks - and thus, past runtime versions. Substrate has a default `runtime_cache_size` of
-			// 2 slots LRU-style, meaning if users were to access multiple runtime versions in a short period
-			// of time, the RPC response time would degrade a lot, as the VersionedRuntime needs to be compiled.
+			let execute_in_block = ExecuteInBlock::new(
+				client.as_ref(),
+				backend.as_ref(),
+				graph.as_ref(),
+				number,
+				None,
+			)?;
+			execute_in_block
+				.apply_overrides(config.block_overrides.as_ref(), config.state_overrides)?;
+
+			let gas_limit = match request.gas {
+				Some(amount) => amount,
+				None => execute_in_block.gas_limit()?,
+			};
+			let gas_limit = cap_gas_limit(gas_limit, gas_cap);
+
+			let from = request.from.unwrap_or_default();
+			let to = request.to;
+			let value = request.value.unwrap_or_default();
+			let input = request.data.clone().unwrap_or_default();
+			let info = execute_in_block.execute(request, gas_limit, &fee_details, false)?;
+
+			let error = error_on_execution_failure(&info.exit_reason, &info.value)
+				.err()
+				.map(|err| err.message);
+			let output = match info.contract_address {
+				Some(contract_address) if error.is_none() => execute_in_block
+					.api
+					.account_code_at(&execute_in_block.id, contract_address)
+					.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?,
+				_ => info.value,
+			};
+
+			Ok(CallFrame {
+				call_type: if to.is_some() {
+					"CALL".into()
+				} else {
+					"CREATE".into()
+				},
+				from,
+				to: to.or(info.contract_address),
+				value,
+				gas: gas_limit,
+				gas_used: info.used_gas,
+				input,
+				output: Bytes(output),
+				error,
+				calls: Vec::new(),
+			})
+		})
+	}
+
+	pub fn estimate_gas(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+	) -> BoxFuture<Result<U256>> {
+		let client = Arc::clone(&self.client);
+		let backend = Arc::clone(&self.backend);
+		let graph = Arc::clone(&self.graph);
+		let gas_cap = self.gas_cap;
+
+		self.execution_pool.spawn(move |cancellation| {
+			// Define the lower bound of estimate
+			const MIN_GAS_PER_TX: U256 = U256([21_000, 0, 0, 0]);
+
+			// The executions share the same runtime api instance, each of them reverting its
+			// changes so that they don't affect the estimation result of subsequent calls.
 			//
-			// To solve that, and if we introduce historical gas estimation, we'd need to increase that default.
-			#[rustfmt::skip]
-			let executable = move |
-				request, gas_limit, api_version, api: sp_api::ApiRef<'_, C::Api>, estimate_mode
-			| -> Result<ExecutableResult> {
-				let CallRequest {
-					from,
-					to,
-					gas,
-					value,
-					data,
-					nonce,
-					access_list,
-					..
-				} = request;
+			// Estimations at the pending block are done on top of the sender's ready
+			// transactions, applied once, so that those of rapid transaction sequences match
+			// their nonce and state.
+			let execute_in_block = ExecuteInBlock::new(
+				client.as_ref(),
+				backend.as_ref(),
+				graph.as_ref(),
+				number,
+				request.from,
+			)?;
 
-				// Use request gas limit only if it less than gas_limit parameter
-				let gas_limit = core::cmp::min(gas.unwrap_or(gas_limit), gas_limit);
-
-				let data = data.map(|d| d.0).unwrap_or_default();
-
-				let (exit_reason, data, used_gas) = match to {
-					Some(to) => {
-						let info = if api_version == 1 {
-							// Legacy pre-london
-							#[allow(deprecated)]
-							api.call_before_version_2(
-								&BlockId::Hash(best_hash),
-								from.unwrap_or_default(),
-								to,
-								data,
-								value.unwrap_or_default(),
-								gas_limit,
-								gas_price,
-								nonce,
-								estimate_mode,
-							)
-							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-							.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?
-						} else if api_version < 4 {
-							// Post-london
-							#[allow(deprecated)]
-							api.call_before_version_4(
-								&BlockId::Hash(best_hash),
-								from.unwrap_or_default(),
-								to,
-								data,
-								value.unwrap_or_default(),
-								gas_limit,
-								max_fee_per_gas,
-								max_priority_fee_per_gas,
-								nonce,
-								estimate_mode,
-							)
-							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-							.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?
-						} else {
-							// Post-london + access list support
-							let access_list = access_list.unwrap_or_default();
-							api.call(
-								&BlockId::Hash(best_hash),
-								from.unwrap_or_default(),
-								to,
-								data,
-								value.unwrap_or_default(),
-								gas_limit,
-								max_fee_per_gas,
-								max_priority_fee_per_gas,
-								nonce,
-								estimate_mode,
-								Some(
-									access_list
-										.into_iter()
-										.map(|item| (item.address, item.storage_keys))
-										.collect(),
-								),
-							)
-							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-							.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?
-						};
+			let fee_details = fee_details(
+				request.gas_price,
+				request.max_fee_per_gas,
+				request.max_priority_fee_per_gas,
+			)?;
 
-						(info.exit_reason, info.value, info.used_gas)
+			let (block_gas_limit, mut highest) = {
+				// For simple transfer to simple account, return MIN_GAS_PER_TX directly
+				let is_simple_transfer = match &request.data {
+					None => true,
+					Some(vec) => vec.0.is_empty(),
+				};
+				if is_simple_transfer {
+					if let Some(to) = request.to {
+						let to_code = execute_in_block
+							.api
+							.account_code_at(&execute_in_block.id, to)
+							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+						if to_code.is_empty() {
+							return Ok(MIN_GAS_PER_TX);
+						}
 					}
-					None => {
-						let info = if api_version == 1 {
-							// Legacy pre-london
-							#[allow(deprecated)]
-							api.create_before_version_2(
-								&BlockId::Hash(best_hash),
-								from.unwrap_or_default(),
-								data,
-								value.unwrap_or_default(),
-								gas_limit,
-								gas_price,
-								nonce,
-								estimate_mode,
-							)
-							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-							.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?
-						} else if api_version < 4 {
-							// Post-london
-							#[allow(deprecated)]
-							api.create_before_version_4(
-								&BlockId::Hash(best_hash),
-								from.unwrap_or_default(),
-								data,
-								value.unwrap_or_default(),
-								gas_limit,
-								max_fee_per_gas,
-								max_priority_fee_per_gas,
-								nonce,
-								estimate_mode,
-							)
-							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-							.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?
-						} else {
-							// Post-london + access list support
-							let access_list = access_list.unwrap_or_default();
-							api.create(
-								&BlockId::Hash(best_hash),
-								from.unwrap_or_default(),
-								data,
-								value.unwrap_or_default(),
-								gas_limit,
-								max_fee_per_gas,
-								max_priority_fee_per_gas,
-								nonce,
-								estimate_mode,
-								Some(
-									access_list
-										.into_iter()
-										.map(|item| (item.address, item.storage_keys))
-										.collect(),
-								),
-							)
-							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-							.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?
-						};
+				}
+
+				// Determine the highest possible gas limits
+				let block_gas_limit = cap_gas_limit(execute_in_block.gas_limit()?, gas_cap);
+				let mut highest = cap_gas_limit(request.gas.unwrap_or(block_gas_limit), gas_cap);
 
-						(info.exit_reason, Vec::new(), info.used_gas)
+				// Recap the highest gas allowance with account's balance.
+				if let Some(from) = request.from {
+					let gas_price = fee_details.gas_price.unwrap_or_default();
+					if gas_price > U256::zero() {
+						let balance = execute_in_block
+							.api
+							.account_basic(&execute_in_block.id, from)
+							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+							.balance;
+						let mut available = balance;
+						if let Some(value) = request.value {
+							if value > available {
+								return Err(internal_err("insufficient funds for transfer"));
+							}
+							available -= value;
+						}
+						let allowance = available / gas_price;
+						if highest > allowance {
+							log::warn!(
+								"Gas estimation capped by limited funds original {} balance {} sent {} feecap {} fundable {}",
+								highest,
+								balance,
+								request.value.unwrap_or_default(),
+								gas_price,
+								allowance
+							);
+							highest = allowance;
+						}
 					}
-				};
-				Ok(ExecutableResult {
-					exit_reason,
-					data,
-					used_gas,
-				})
+				}
+
+				(block_gas_limit, highest)
 			};
-			let api_version = if let Ok(Some(api_version)) =
-				client
-					.runtime_api()
-					.api_version::<dyn EthereumRuntimeRPCApi<B>>(&BlockId::Hash(best_hash))
-			{
-				api_version
-			} else {
-				return Err(internal_err("failed to retrieve Runtime Api version"));
+
+			let executable = |request: CallRequest, gas_limit: U256, estimate_mode: bool| {
+				// Use request gas limit only if it less than gas_limit parameter
+				let gas_limit = core::cmp::min(request.gas.unwrap_or(gas_limit), gas_limit);
+				cancellation.check()?;
+				execute_in_block.execute_reverted(request, gas_limit, &fee_details, estimate_mode)
 			};
 
 			// Verify that the transaction succeed with highest capacity
 			let cap = highest;
 			let estimate_mode = !cfg!(feature = "rpc_binary_search_estimate");
-			let ExecutableResult {
-				data,
+			let ExecutionResult {
+				value: data,
 				exit_reason,
 				used_gas,
-			} = executable(
-				request.clone(),
-				highest,
-				api_version,
-				client.runtime_api(),
-				estimate_mode,
-			)?;
+				..
+			} = executable(request.clone(), highest, estimate_mode)?;
 			match exit_reason {
 				ExitReason::Succeed(_) => (),
 				ExitReason::Error(ExitError::OutOfGas) => {
@@ -586,17 +872,11 @@ where
 						// If the user has provided a gas limit or a gas price, then we have executed
 						// with less block gas limit, so we must reexecute with block gas limit to
 						// know if the revert is due to a lack of gas or not.
-						let ExecutableResult {
-							data,
+						let ExecutionResult {
+							value: data,
 							exit_reason,
-							used_gas: _,
-						} = executable(
-							request.clone(),
-							get_current_block_gas_limit().await?,
-							api_version,
-							client.runtime_api(),
-							estimate_mode,
-						)?;
+							..
+						} = executable(request.clone(), block_gas_limit, estimate_mode)?;
 						match exit_reason {
 							ExitReason::Succeed(_) => {
 								return Err(internal_err(format!(
@@ -632,17 +912,11 @@ where
 				// Execute the binary search and hone in on an executable gas limit.
 				let mut previous_highest = highest;
 				while (highest - lowest) > U256::one() {
-					let ExecutableResult {
-						data,
+					let ExecutionResult {
+						value: data,
 						exit_reason,
-						used_gas: _,
-					} = executable(
-						request.clone(),
-						mid,
-						api_version,
-						client.runtime_api(),
-						estimate_mode,
-					)?;
+						..
+					} = executable(request.clone(), mid, estimate_mode)?;
 					match exit_reason {
 						ExitReason::Succeed(_) => {
 							highest = mid;
@@ -712,13 +986,30 @@ pub fn error_on_execution_failure(reason: &ExitReason, data: &[u8]) -> Result<()
 	}
 }
 
-struct FeeDetails {
+/// Caps the gas limit of a read-only execution to the gas cap of the node.
+fn cap_gas_limit(gas_limit: U256, gas_cap: Option<u64>) -> U256 {
+	match gas_cap {
+		Some(gas_cap) if gas_limit > U256::from(gas_cap) => {
+			log::debug!(
+				target: "rpc",
+				"Caller gas above allowance, capping requested {} cap {}",
+				gas_limit,
+				gas_cap
+			);
+			U256::from(gas_cap)
+		}
+		_ => gas_limit,
+	}
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct FeeDetails {
 	gas_price: Option<U256>,
 	max_fee_per_gas: Option<U256>,
 	max_priority_fee_per_gas: Option<U256>,
 }
 
-fn fee_details(
+pub(crate) fn fee_details(
 	request_gas_price: Option<U256>,
 	request_max_fee: Option<U256>,
 	request_priority: Option<U256>,
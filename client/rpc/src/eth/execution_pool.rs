@@ -0,0 +1,191 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+	panic::{self, AssertUnwindSafe},
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		mpsc, Arc, Mutex,
+	},
+	thread,
+	time::Duration,
+};
+
+use futures::{
+	channel::oneshot,
+	future::{self, BoxFuture, Either},
+	FutureExt,
+};
+use jsonrpc_core::{Error, Result};
+
+use crate::{eth::execute::JSON_RPC_ERROR_DEFAULT, internal_err};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Flag set when the execution of an RPC method timed out.
+///
+/// Runtime calls can't be interrupted, so executions check it between their runtime calls
+/// to give up early.
+#[derive(Clone, Default)]
+pub struct Cancellation(Arc<AtomicBool>);
+
+impl Cancellation {
+	/// Fails with the execution timeout error if the execution timed out.
+	pub fn check(&self) -> Result<()> {
+		if self.0.load(Ordering::Relaxed) {
+			return Err(timeout_err());
+		}
+		Ok(())
+	}
+
+	fn cancel(&self) {
+		self.0.store(true, Ordering::Relaxed);
+	}
+}
+
+fn timeout_err() -> Error {
+	Error {
+		code: JSON_RPC_ERROR_DEFAULT.into(),
+		message: String::from("execution timeout"),
+		data: None,
+	}
+}
+
+/// Dedicated threads running the runtime calls of `eth_call`, `eth_estimateGas` and
+/// `eth_simulateV1`, so that they don't block the RPC server, within an optional timeout.
+///
+/// When an execution times out, the method fails with an `execution timeout` error and its
+/// [`Cancellation`] is set, but its thread is only released when its current runtime call
+/// completes.
+#[derive(Clone)]
+pub struct ExecutionPool {
+	sender: Arc<Mutex<mpsc::Sender<Job>>>,
+	timeout: Option<Duration>,
+}
+
+impl ExecutionPool {
+	/// Starts a pool of `threads` threads, at least one.
+	pub fn new(threads: usize, timeout: Option<Duration>) -> Self {
+		let (sender, receiver) = mpsc::channel::<Job>();
+		let receiver = Arc::new(Mutex::new(receiver));
+
+		for index in 0..threads.max(1) {
+			let receiver = Arc::clone(&receiver);
+			thread::Builder::new()
+				.name(format!("eth-execution-{}", index))
+				.spawn(move || loop {
+					let job = match receiver.lock().expect("not poisoned; qed").recv() {
+						Ok(job) => job,
+						// The pool has been dropped.
+						Err(_) => break,
+					};
+					// The panic is reported to the caller by the dropped result sender.
+					let _ = panic::catch_unwind(AssertUnwindSafe(job));
+				})
+				.expect("failed to spawn an execution thread");
+		}
+
+		Self {
+			sender: Arc::new(Mutex::new(sender)),
+			timeout,
+		}
+	}
+
+	/// Runs `execute` on the pool, failing with an `execution timeout` error if it doesn't
+	/// complete within the timeout.
+	pub fn spawn<T, F>(&self, execute: F) -> BoxFuture<'static, Result<T>>
+	where
+		T: Send + 'static,
+		F: FnOnce(&Cancellation) -> Result<T> + Send + 'static,
+	{
+		let cancellation = Cancellation::default();
+		let (result_sender, result) = oneshot::channel();
+		let job: Job = {
+			let cancellation = cancellation.clone();
+			Box::new(move || {
+				let result = cancellation.check().and_then(|_| execute(&cancellation));
+				let _ = result_sender.send(result);
+			})
+		};
+		if self
+			.sender
+			.lock()
+			.expect("not poisoned; qed")
+			.send(job)
+			.is_err()
+		{
+			return future::ready(Err(internal_err("execution pool stopped"))).boxed();
+		}
+
+		let result =
+			result.map(|result| result.unwrap_or_else(|_| Err(internal_err("execution panicked"))));
+		match self.timeout {
+			Some(timeout) => async move {
+				match future::select(result, futures_timer::Delay::new(timeout)).await {
+					Either::Left((result, _)) => result,
+					Either::Right(_) => {
+						cancellation.cancel();
+						Err(timeout_err())
+					}
+				}
+			}
+			.boxed(),
+			None => result.boxed(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn returns_the_result_of_the_execution() {
+		let pool = ExecutionPool::new(1, Some(Duration::from_secs(10)));
+		let result = futures::executor::block_on(pool.spawn(|_| Ok(42)));
+		assert_eq!(result, Ok(42));
+	}
+
+	#[test]
+	fn cancels_executions_out_of_time() {
+		let pool = ExecutionPool::new(1, Some(Duration::from_millis(50)));
+		let (sender, receiver) = mpsc::channel();
+
+		let result = futures::executor::block_on(pool.spawn(move |cancellation| {
+			while cancellation.check().is_ok() {
+				thread::sleep(Duration::from_millis(10));
+			}
+			sender.send(()).unwrap();
+			cancellation.check()
+		}));
+		assert_eq!(result.unwrap_err().message, "execution timeout");
+		receiver
+			.recv_timeout(Duration::from_secs(10))
+			.expect("the execution has been cancelled");
+	}
+
+	#[test]
+	fn survives_panicking_executions() {
+		let pool = ExecutionPool::new(1, None);
+		let result = futures::executor::block_on(pool.spawn(|_| -> Result<()> { panic!() }));
+		assert_eq!(result.unwrap_err().message, "execution panicked");
+
+		let result = futures::executor::block_on(pool.spawn(|_| Ok(42)));
+		assert_eq!(result, Ok(42));
+	}
+}
@@ -42,7 +42,7 @@ use crate::{eth::cache::EthBlockDataCacheTask, frontier_backend_client, internal
 
 pub struct EthFilter<B: BlockT, C, BE> {
 	client: Arc<C>,
-	backend: Arc<fc_db::Backend<B>>,
+	backend: Arc<dyn fc_api::Backend<B>>,
 	filter_pool: FilterPool,
 	max_stored_filters: usize,
 	max_past_logs: u32,
@@ -53,7 +53,7 @@ pub struct EthFilter<B: BlockT, C, BE> {
 impl<B: BlockT, C, BE> EthFilter<B, C, BE> {
 	pub fn new(
 		client: Arc<C>,
-		backend: Arc<fc_db::Backend<B>>,
+		backend: Arc<dyn fc_api::Backend<B>>,
 		filter_pool: FilterPool,
 		max_stored_filters: usize,
 		max_past_logs: u32,
@@ -193,7 +193,7 @@ where
 						let best_number = self.client.info().best_number;
 						let mut current_number = filter
 							.to_block
-							.and_then(|v| v.to_min_block_num())
+							.and_then(|v| filter_block_number::<B, C>(self.client.as_ref(), v))
 							.map(|s| s.unique_saturated_into())
 							.unwrap_or(best_number);
 
@@ -210,7 +210,7 @@ where
 
 						let filter_from = filter
 							.from_block
-							.and_then(|v| v.to_min_block_num())
+							.and_then(|v| filter_block_number::<B, C>(self.client.as_ref(), v))
 							.map(|s| s.unique_saturated_into())
 							.unwrap_or(last_poll);
 
@@ -317,41 +317,16 @@ where
 
 		Box::pin(async move {
 			let filter = filter_result?;
-
-			let best_number = client.info().best_number;
-			let mut current_number = filter
-				.to_block
-				.and_then(|v| v.to_min_block_num())
-				.map(|s| s.unique_saturated_into())
-				.unwrap_or(best_number);
-
-			if current_number > best_number {
-				current_number = best_number;
-			}
-
-			if current_number > client.info().best_number {
-				current_number = client.info().best_number;
-			}
-
-			let from_number = filter
-				.from_block
-				.and_then(|v| v.to_min_block_num())
-				.map(|s| s.unique_saturated_into())
-				.unwrap_or(client.info().best_number);
-
-			let mut ret: Vec<Log> = Vec::new();
-			let _ = filter_range_logs(
+			// The whole range of the filter, as queried by `eth_getLogs`, regardless of the
+			// polls of `eth_getFilterChanges`.
+			query_logs(
 				client.as_ref(),
 				backend.as_ref(),
 				&block_data_cache,
-				&mut ret,
 				max_past_logs,
 				&filter,
-				from_number,
-				current_number,
 			)
-			.await?;
-			Ok(ret)
+			.await
 		})
 	}
 
@@ -378,68 +353,105 @@ where
 		let max_past_logs = self.max_past_logs;
 
 		Box::pin(async move {
-			let mut ret: Vec<Log> = Vec::new();
-			if let Some(hash) = filter.block_hash {
-				let id = match frontier_backend_client::load_hash::<B>(backend.as_ref(), hash)
-					.map_err(|err| internal_err(format!("{:?}", err)))?
-				{
-					Some(hash) => hash,
-					_ => return Ok(Vec::new()),
-				};
-				let substrate_hash = client
-					.expect_block_hash_from_id(&id)
-					.map_err(|_| internal_err(format!("Expect block number from id: {}", id)))?;
-
-				let schema = frontier_backend_client::onchain_storage_schema::<B, C, BE>(
-					client.as_ref(),
-					id,
-				);
-
-				let block = block_data_cache.current_block(schema, substrate_hash).await;
-				let statuses = block_data_cache
-					.current_transaction_statuses(schema, substrate_hash)
-					.await;
-				if let (Some(block), Some(statuses)) = (block, statuses) {
-					filter_block_logs(&mut ret, &filter, block, statuses);
-				}
-			} else {
-				let best_number = client.info().best_number;
-				let mut current_number = filter
-					.to_block
-					.and_then(|v| v.to_min_block_num())
-					.map(|s| s.unique_saturated_into())
-					.unwrap_or(best_number);
-
-				if current_number > best_number {
-					current_number = best_number;
-				}
-
-				let from_number = filter
-					.from_block
-					.and_then(|v| v.to_min_block_num())
-					.map(|s| s.unique_saturated_into())
-					.unwrap_or(client.info().best_number);
-
-				let _ = filter_range_logs(
-					client.as_ref(),
-					backend.as_ref(),
-					&block_data_cache,
-					&mut ret,
-					max_past_logs,
-					&filter,
-					from_number,
-					current_number,
-				)
-				.await?;
-			}
-			Ok(ret)
+			query_logs(
+				client.as_ref(),
+				backend.as_ref(),
+				&block_data_cache,
+				max_past_logs,
+				&filter,
+			)
+			.await
 		})
 	}
 }
 
+/// Number of the block bounding a filter range, resolving the finalized and safe tags.
+fn filter_block_number<B, C>(client: &C, number: BlockNumber) -> Option<u64>
+where
+	B: BlockT,
+	C: HeaderBackend<B>,
+{
+	match number {
+		BlockNumber::Finalized | BlockNumber::Safe => Some(
+			UniqueSaturatedInto::<u64>::unique_saturated_into(client.info().finalized_number),
+		),
+		number => number.to_min_block_num(),
+	}
+}
+
+/// Logs matching `filter`, in its block or its block range, as returned by `eth_getLogs`.
+async fn query_logs<B: BlockT, C, BE>(
+	client: &C,
+	backend: &dyn fc_api::Backend<B>,
+	block_data_cache: &EthBlockDataCacheTask<B>,
+	max_past_logs: u32,
+	filter: &Filter,
+) -> Result<Vec<Log>>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + StorageProvider<B, BE>,
+	C: HeaderBackend<B> + Send + Sync + 'static,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+{
+	let mut ret: Vec<Log> = Vec::new();
+	if let Some(hash) = filter.block_hash {
+		let id = match frontier_backend_client::load_hash::<B>(backend, hash)
+			.map_err(|err| internal_err(format!("{:?}", err)))?
+		{
+			Some(hash) => hash,
+			_ => return Ok(Vec::new()),
+		};
+		let substrate_hash = client
+			.expect_block_hash_from_id(&id)
+			.map_err(|_| internal_err(format!("Expect block number from id: {}", id)))?;
+
+		let schema = frontier_backend_client::onchain_storage_schema::<B, C, BE>(client, id);
+
+		let block = block_data_cache.current_block(schema, substrate_hash).await;
+		let statuses = block_data_cache
+			.current_transaction_statuses(schema, substrate_hash)
+			.await;
+		if let (Some(block), Some(statuses)) = (block, statuses) {
+			filter_block_logs(&mut ret, filter, block, statuses);
+		}
+	} else {
+		let best_number = client.info().best_number;
+		let mut current_number = filter
+			.to_block
+			.and_then(|v| filter_block_number::<B, C>(client, v))
+			.map(|s| s.unique_saturated_into())
+			.unwrap_or(best_number);
+
+		if current_number > best_number {
+			current_number = best_number;
+		}
+
+		let from_number = filter
+			.from_block
+			.and_then(|v| filter_block_number::<B, C>(client, v))
+			.map(|s| s.unique_saturated_into())
+			.unwrap_or(client.info().best_number);
+
+		let _ = filter_range_logs(
+			client,
+			backend,
+			block_data_cache,
+			&mut ret,
+			max_past_logs,
+			filter,
+			from_number,
+			current_number,
+		)
+		.await?;
+	}
+	Ok(ret)
+}
+
 async fn filter_range_logs<B: BlockT, C, BE>(
 	client: &C,
-	backend: &fc_db::Backend<B>,
+	backend: &dyn fc_api::Backend<B>,
 	block_data_cache: &EthBlockDataCacheTask<B>,
 	ret: &mut Vec<Log>,
 	max_past_logs: u32,
@@ -20,6 +20,7 @@ mod block;
 mod cache;
 mod client;
 mod execute;
+mod execution_pool;
 mod fee;
 mod filter;
 mod mining;
@@ -54,8 +55,13 @@ use crate::{internal_err, overrides::OverrideHandle, public_key, signer::EthSign
 
 pub use self::{
 	cache::{EthBlockDataCacheTask, EthTask},
+	execution_pool::ExecutionPool,
 	filter::EthFilter,
 };
+pub(crate) use self::{
+	execute::{error_on_execution_failure, fee_details, ExecuteInBlock},
+	submit::{check_transaction, submit_error, transaction_extrinsic},
+};
 
 /// Eth API implementation.
 pub struct Eth<B: BlockT, C, P, CT, BE, H: ExHashT, A: ChainApi> {
@@ -67,10 +73,12 @@ pub struct Eth<B: BlockT, C, P, CT, BE, H: ExHashT, A: ChainApi> {
 	is_authority: bool,
 	signers: Vec<Box<dyn EthSigner>>,
 	overrides: Arc<OverrideHandle<B>>,
-	backend: Arc<fc_db::Backend<B>>,
+	backend: Arc<dyn fc_api::Backend<B>>,
 	block_data_cache: Arc<EthBlockDataCacheTask<B>>,
 	fee_history_cache: FeeHistoryCache,
 	fee_history_cache_limit: FeeHistoryCacheLimit,
+	execution_pool: ExecutionPool,
+	gas_cap: Option<u64>,
 	_marker: PhantomData<(B, BE)>,
 }
 
@@ -83,11 +91,13 @@ impl<B: BlockT, C, P, CT, BE, H: ExHashT, A: ChainApi> Eth<B, C, P, CT, BE, H, A
 		network: Arc<NetworkService<B, H>>,
 		signers: Vec<Box<dyn EthSigner>>,
 		overrides: Arc<OverrideHandle<B>>,
-		backend: Arc<fc_db::Backend<B>>,
+		backend: Arc<dyn fc_api::Backend<B>>,
 		is_authority: bool,
 		block_data_cache: Arc<EthBlockDataCacheTask<B>>,
 		fee_history_cache: FeeHistoryCache,
 		fee_history_cache_limit: FeeHistoryCacheLimit,
+		execution_pool: ExecutionPool,
+		gas_cap: Option<u64>,
 	) -> Self {
 		Self {
 			client,
@@ -102,6 +112,8 @@ impl<B: BlockT, C, P, CT, BE, H: ExHashT, A: ChainApi> Eth<B, C, P, CT, BE, H, A
 			block_data_cache,
 			fee_history_cache,
 			fee_history_cache_limit,
+			execution_pool,
+			gas_cap,
 			_marker: PhantomData,
 		}
 	}
@@ -175,11 +187,11 @@ where
 		self.block_transaction_count_by_number(number)
 	}
 
-	fn block_uncles_count_by_hash(&self, hash: H256) -> Result<U256> {
+	fn block_uncles_count_by_hash(&self, hash: H256) -> Result<Option<U256>> {
 		self.block_uncles_count_by_hash(hash)
 	}
 
-	fn block_uncles_count_by_number(&self, number: BlockNumber) -> Result<U256> {
+	fn block_uncles_count_by_number(&self, number: BlockNumber) -> Result<Option<U256>> {
 		self.block_uncles_count_by_number(number)
 	}
 
@@ -203,6 +215,10 @@ where
 		self.transaction_by_hash(hash)
 	}
 
+	fn raw_transaction_by_hash(&self, hash: H256) -> Result<Option<Bytes>> {
+		self.raw_transaction_by_hash(hash)
+	}
+
 	fn transaction_by_block_hash_and_index(
 		&self,
 		hash: H256,
@@ -247,7 +263,11 @@ where
 	// Execute
 	// ########################################################################
 
-	fn call(&self, request: CallRequest, number: Option<BlockNumber>) -> Result<Bytes> {
+	fn call(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+	) -> BoxFuture<'static, Result<Bytes>> {
 		self.call(request, number)
 	}
 
@@ -259,6 +279,31 @@ where
 		self.estimate_gas(request, number)
 	}
 
+	fn create_access_list(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+	) -> BoxFuture<'static, Result<AccessListResult>> {
+		self.create_access_list(request, number)
+	}
+
+	fn simulate_v1(
+		&self,
+		payload: SimulatePayload,
+		number: Option<BlockNumber>,
+	) -> BoxFuture<'static, Result<Vec<SimulatedBlock>>> {
+		self.simulate_v1(payload, number)
+	}
+
+	fn trace_call(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+		config: Option<TraceCallConfig>,
+	) -> BoxFuture<'static, Result<CallFrame>> {
+		self.trace_call(request, number, config)
+	}
+
 	// ########################################################################
 	// Fee
 	// ########################################################################
@@ -315,41 +360,24 @@ where
 	fn send_raw_transaction(&self, bytes: Bytes) -> BoxFuture<'static, Result<H256>> {
 		self.send_raw_transaction(bytes)
 	}
+
+	fn sign(&self, address: H160, data: Bytes) -> Result<Bytes> {
+		self.sign(address, data)
+	}
 }
 
-fn rich_block_build(
+pub(crate) fn rich_block_build(
 	block: EthereumBlock,
 	statuses: Vec<Option<TransactionStatus>>,
 	hash: Option<H256>,
 	full_transactions: bool,
 	base_fee: Option<U256>,
 ) -> RichBlock {
+	let size = Some(U256::from(rlp::encode(&block).len() as u32));
 	Rich {
 		inner: Block {
-			header: Header {
-				hash: Some(
-					hash.unwrap_or_else(|| H256::from(keccak_256(&rlp::encode(&block.header)))),
-				),
-				parent_hash: block.header.parent_hash,
-				uncles_hash: block.header.ommers_hash,
-				author: block.header.beneficiary,
-				miner: block.header.beneficiary,
-				state_root: block.header.state_root,
-				transactions_root: block.header.transactions_root,
-				receipts_root: block.header.receipts_root,
-				number: Some(block.header.number),
-				gas_used: block.header.gas_used,
-				gas_limit: block.header.gas_limit,
-				extra_data: Bytes(block.header.extra_data.clone()),
-				logs_bloom: block.header.logs_bloom,
-				timestamp: U256::from(block.header.timestamp / 1000),
-				difficulty: block.header.difficulty,
-				seal_fields: vec![
-					Bytes(block.header.mix_hash.as_bytes().to_vec()),
-					Bytes(block.header.nonce.as_bytes().to_vec()),
-				],
-				size: Some(U256::from(rlp::encode(&block.header).len() as u32)),
-			},
+			header: header_build(&block, hash),
+			// Frontier blocks carry no difficulty, so the total difficulty is always zero.
 			total_difficulty: U256::zero(),
 			uncles: vec![],
 			transactions: {
@@ -379,13 +407,179 @@ fn rich_block_build(
 					)
 				}
 			},
-			size: Some(U256::from(rlp::encode(&block).len() as u32)),
+			size,
 			base_fee_per_gas: base_fee,
 		},
 		extra_info: BTreeMap::new(),
 	}
 }
 
+/// Build the RPC header of an Ethereum block.
+///
+/// Shared by `eth_getBlockBy*` and the `newHeads` subscription so both expose the same fields.
+pub(crate) fn header_build(block: &EthereumBlock, hash: Option<H256>) -> Header {
+	Header {
+		hash: Some(hash.unwrap_or_else(|| H256::from(keccak_256(&rlp::encode(&block.header))))),
+		parent_hash: block.header.parent_hash,
+		uncles_hash: block.header.ommers_hash,
+		author: block.header.beneficiary,
+		miner: block.header.beneficiary,
+		state_root: block.header.state_root,
+		transactions_root: block.header.transactions_root,
+		receipts_root: block.header.receipts_root,
+		number: Some(block.header.number),
+		gas_used: block.header.gas_used,
+		gas_limit: block.header.gas_limit,
+		extra_data: Bytes(block.header.extra_data.clone()),
+		logs_bloom: block.header.logs_bloom,
+		// The runtime stores the timestamp in milliseconds.
+		timestamp: U256::from(block.header.timestamp / 1000),
+		difficulty: block.header.difficulty,
+		mix_hash: block.header.mix_hash,
+		nonce: block.header.nonce,
+		seal_fields: vec![
+			Bytes(block.header.mix_hash.as_bytes().to_vec()),
+			Bytes(block.header.nonce.as_bytes().to_vec()),
+		],
+		size: Some(U256::from(rlp::encode(block).len() as u32)),
+	}
+}
+
+/// Build the RPC receipt of the transaction at `index` in `block`.
+///
+/// Shared by `eth_getTransactionReceipt` and the extended blocks so both expose the same fields.
+pub(crate) fn receipt_build(
+	block: &EthereumBlock,
+	statuses: &[TransactionStatus],
+	receipts: &[ethereum::ReceiptV3],
+	index: usize,
+	is_eip1559: bool,
+	base_fee: Option<U256>,
+) -> Result<Receipt> {
+	let block_hash = H256::from(keccak_256(&rlp::encode(&block.header)));
+	let receipt = receipts[index].clone();
+
+	let (logs, logs_bloom, status_code, cumulative_gas_used, gas_used) = if !is_eip1559 {
+		// Pre-london frontier update stored receipts require cumulative gas calculation.
+		match receipt {
+			ethereum::ReceiptV3::Legacy(d) => {
+				let index = core::cmp::min(receipts.len(), index + 1);
+				let cumulative_gas: u32 = receipts[..index]
+					.iter()
+					.map(|r| match r {
+						ethereum::ReceiptV3::Legacy(d) => Ok(d.used_gas.as_u32()),
+						_ => Err(internal_err(format!(
+							"Unknown receipt in block {}",
+							block_hash
+						))),
+					})
+					.sum::<Result<u32>>()?;
+				(
+					d.logs,
+					d.logs_bloom,
+					d.status_code,
+					U256::from(cumulative_gas),
+					d.used_gas,
+				)
+			}
+			_ => {
+				return Err(internal_err(format!(
+					"Unknown receipt in block {}",
+					block_hash
+				)))
+			}
+		}
+	} else {
+		match receipt {
+			ethereum::ReceiptV3::Legacy(d)
+			| ethereum::ReceiptV3::EIP2930(d)
+			| ethereum::ReceiptV3::EIP1559(d) => {
+				let cumulative_gas = d.used_gas;
+				let gas_used = if index > 0 {
+					let previous_receipt = receipts[index - 1].clone();
+					let previous_gas_used = match previous_receipt {
+						ethereum::ReceiptV3::Legacy(d)
+						| ethereum::ReceiptV3::EIP2930(d)
+						| ethereum::ReceiptV3::EIP1559(d) => d.used_gas,
+					};
+					cumulative_gas.saturating_sub(previous_gas_used)
+				} else {
+					cumulative_gas
+				};
+				(
+					d.logs,
+					d.logs_bloom,
+					d.status_code,
+					cumulative_gas,
+					gas_used,
+				)
+			}
+		}
+	};
+
+	let status = statuses[index].clone();
+	let mut cumulative_receipts = receipts.to_vec();
+	cumulative_receipts.truncate((status.transaction_index + 1) as usize);
+
+	let transaction = block.transactions[index].clone();
+	let effective_gas_price = match transaction {
+		EthereumTransaction::Legacy(t) => t.gas_price,
+		EthereumTransaction::EIP2930(t) => t.gas_price,
+		EthereumTransaction::EIP1559(t) => base_fee
+			.unwrap_or_default()
+			.checked_add(t.max_priority_fee_per_gas)
+			.unwrap_or_else(U256::max_value)
+			.min(t.max_fee_per_gas),
+	};
+
+	Ok(Receipt {
+		transaction_hash: Some(status.transaction_hash),
+		transaction_index: Some(status.transaction_index.into()),
+		block_hash: Some(block_hash),
+		from: Some(status.from),
+		to: status.to,
+		block_number: Some(block.header.number),
+		cumulative_gas_used,
+		gas_used: Some(gas_used),
+		contract_address: status.contract_address,
+		logs: {
+			let mut pre_receipts_log_index = None;
+			if cumulative_receipts.len() > 0 {
+				cumulative_receipts.truncate(cumulative_receipts.len() - 1);
+				pre_receipts_log_index = Some(
+					cumulative_receipts
+						.iter()
+						.map(|r| match r {
+							ethereum::ReceiptV3::Legacy(d)
+							| ethereum::ReceiptV3::EIP2930(d)
+							| ethereum::ReceiptV3::EIP1559(d) => d.logs.len() as u32,
+						})
+						.sum::<u32>(),
+				);
+			}
+			logs.iter()
+				.enumerate()
+				.map(|(i, log)| Log {
+					address: log.address,
+					topics: log.topics.clone(),
+					data: Bytes(log.data.clone()),
+					block_hash: Some(block_hash),
+					block_number: Some(block.header.number),
+					transaction_hash: Some(status.transaction_hash),
+					transaction_index: Some(status.transaction_index.into()),
+					log_index: Some(U256::from((pre_receipts_log_index.unwrap_or(0)) + i as u32)),
+					transaction_log_index: Some(U256::from(i)),
+					removed: false,
+				})
+				.collect()
+		},
+		status_code: Some(U64::from(status_code)),
+		logs_bloom,
+		state_root: None,
+		effective_gas_price,
+	})
+}
+
 fn transaction_build(
 	ethereum_transaction: EthereumTransaction,
 	block: Option<EthereumBlock>,
@@ -475,20 +669,67 @@ where
 	BE::State: StateBackend<BlakeTwo256>,
 	A: ChainApi<Block = B> + 'static,
 {
-	// In case of Pending, we need an overlayed state to query over.
-	let api = client.runtime_api();
-	let best = BlockId::Hash(client.info().best_hash);
 	// Get all transactions in the ready queue.
 	let xts: Vec<<B as BlockT>::Extrinsic> = graph
 		.validated_pool()
 		.ready()
 		.map(|in_pool_tx| in_pool_tx.data().clone())
 		.collect::<Vec<<B as BlockT>::Extrinsic>>();
+	overlayed_runtime_api(client, xts)
+}
+
+/// Runtime api on top of the best block, with the ready transactions of `sender` applied.
+///
+/// Executions depending on the previous transactions of their sender, such as gas
+/// estimations, see their nonce and state changes, without applying the whole ready queue.
+fn sender_pending_runtime_api<'a, B: BlockT, C, BE, A: ChainApi>(
+	client: &'a C,
+	graph: &'a Pool<A>,
+	sender: H160,
+) -> Result<sp_api::ApiRef<'a, C::Api>>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + StorageProvider<B, BE>,
+	C: HeaderBackend<B> + Send + Sync + 'static,
+	C::Api: BlockBuilderApi<B> + EthereumRuntimeRPCApi<B>,
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+	A: ChainApi<Block = B> + 'static,
+{
+	// Ethereum transactions provide the `(sender, nonce)` tag, so those of the sender are
+	// found without decoding the whole ready queue.
+	let is_from_sender =
+		|tag: &Vec<u8>| tag.len() == H160::len_bytes() + 32 && tag.starts_with(sender.as_bytes());
+	let xts: Vec<<B as BlockT>::Extrinsic> = graph
+		.validated_pool()
+		.ready()
+		.filter(|in_pool_tx| in_pool_tx.provides().iter().any(is_from_sender))
+		.map(|in_pool_tx| in_pool_tx.data().clone())
+		.collect::<Vec<<B as BlockT>::Extrinsic>>();
+	if xts.is_empty() {
+		return Ok(client.runtime_api());
+	}
+	overlayed_runtime_api(client, xts)
+}
+
+/// Runtime api with `xts` applied to the best block's state.
+fn overlayed_runtime_api<'a, B: BlockT, C>(
+	client: &'a C,
+	xts: Vec<<B as BlockT>::Extrinsic>,
+) -> Result<sp_api::ApiRef<'a, C::Api>>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync + 'static,
+	C::Api: BlockBuilderApi<B>,
+{
+	// In case of Pending, we need an overlayed state to query over.
+	let api = client.runtime_api();
+	let best = BlockId::Hash(client.info().best_hash);
 	// Manually initialize the overlay.
 	let header = client.header(best).unwrap().unwrap();
 	api.initialize_block(&best, &header)
 		.map_err(|e| internal_err(format!("Runtime api access error: {:?}", e)))?;
-	// Apply the ready queue to the best block's state.
+	// Apply the transactions to the best block's state.
 	for xt in xts {
 		let _ = api.apply_extrinsic(&best, xt);
 	}
@@ -37,7 +37,7 @@ use fp_rpc::EthereumRuntimeRPCApi;
 
 use crate::{
 	eth::{pending_runtime_api, Eth},
-	frontier_backend_client, internal_err,
+	ethereum_api_version, frontier_backend_client, internal_err,
 };
 
 impl<B, C, P, CT, BE, H: ExHashT, A: ChainApi> Eth<B, C, P, CT, BE, H, A>
@@ -64,6 +64,7 @@ where
 			self.backend.as_ref(),
 			Some(number),
 		) {
+			ethereum_api_version(self.client.as_ref(), &id, 1)?;
 			Ok(self
 				.client
 				.runtime_api()
@@ -143,6 +144,7 @@ where
 			Some(id) => id,
 			None => return Ok(U256::zero()),
 		};
+		ethereum_api_version(self.client.as_ref(), &id, 1)?;
 
 		Ok(self
 			.client
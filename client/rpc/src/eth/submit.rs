@@ -16,27 +16,33 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use ethereum_types::H256;
+use ethereum_types::{H160, H256};
 use futures::future::{FutureExt, TryFutureExt};
 use jsonrpc_core::{futures::future, BoxFuture, Result};
 
 use sc_client_api::backend::{Backend, StateBackend, StorageProvider};
 use sc_network::ExHashT;
 use sc_transaction_pool::ChainApi;
-use sc_transaction_pool_api::TransactionPool;
-use sp_api::{ApiExt, ProvideRuntimeApi};
+use sc_transaction_pool_api::{
+	error::{Error as PoolError, IntoPoolError},
+	TransactionPool,
+};
+use sp_api::{ApiExt, Core, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::HeaderBackend;
 use sp_runtime::{
 	generic::BlockId,
 	traits::{BlakeTwo256, Block as BlockT},
-	transaction_validity::TransactionSource,
+	transaction_validity::{InvalidTransaction, TransactionSource},
 };
 
 use fc_rpc_core::types::*;
 use fp_rpc::{ConvertTransaction, ConvertTransactionRuntimeApi, EthereumRuntimeRPCApi};
 
-use crate::{eth::Eth, internal_err};
+use crate::{
+	decode_raw_transaction, eth::Eth, ethereum_api_version, frontier::invalid_transaction_reason,
+	internal_err, overrides::legacy, EthereumTransaction,
+};
 
 impl<B, C, P, CT, BE, H: ExHashT, A: ChainApi> Eth<B, C, P, CT, BE, H, A>
 where
@@ -86,10 +92,13 @@ where
 		let gas_limit = match request.gas {
 			Some(gas_limit) => gas_limit,
 			None => {
-				let block = self
-					.client
-					.runtime_api()
-					.current_block(&BlockId::Hash(hash));
+				let id = BlockId::Hash(hash);
+				let block = match ethereum_api_version(self.client.as_ref(), &id, 1) {
+					Ok(api_version) => {
+						legacy::current_block(&*self.client.runtime_api(), &id, api_version)
+					}
+					Err(e) => return future::err(e).boxed(),
+				};
 				if let Ok(Some(block)) = block {
 					block.header.gas_limit
 				} else {
@@ -150,163 +159,186 @@ where
 		let transaction_hash = transaction.hash();
 
 		let block_hash = BlockId::hash(self.client.info().best_hash);
-		let api_version = match self
-			.client
-			.runtime_api()
-			.api_version::<dyn ConvertTransactionRuntimeApi<B>>(&block_hash)
-		{
-			Ok(api_version) => api_version,
-			_ => return future::err(internal_err("cannot access runtime api")).boxed(),
-		};
-
-		let extrinsic = match api_version {
-			Some(2) => match self
-				.client
-				.runtime_api()
-				.convert_transaction(&block_hash, transaction)
-			{
-				Ok(extrinsic) => extrinsic,
-				Err(_) => return future::err(internal_err("cannot access runtime api")).boxed(),
-			},
-			Some(1) => {
-				if let ethereum::TransactionV2::Legacy(legacy_transaction) = transaction {
-					// To be compatible with runtimes that do not support transactions v2
-					#[allow(deprecated)]
-					match self
-						.client
-						.runtime_api()
-						.convert_transaction_before_version_2(&block_hash, legacy_transaction)
-					{
-						Ok(extrinsic) => extrinsic,
-						Err(_) => {
-							return future::err(internal_err("cannot access runtime api")).boxed()
-						}
-					}
-				} else {
-					return future::err(internal_err(
-						"This runtime not support eth transactions v2",
-					))
-					.boxed();
-				}
-			}
-			None => {
-				if let Some(ref convert_transaction) = self.convert_transaction {
-					convert_transaction.convert_transaction(transaction.clone())
-				} else {
-					return future::err(internal_err(
-						"No TransactionConverter is provided and the runtime api ConvertTransactionRuntimeApi is not found"
-					)).boxed();
-				}
-			}
-			_ => {
-				return future::err(internal_err(
-					"ConvertTransactionRuntimeApi version not supported",
-				))
-				.boxed()
-			}
+		let extrinsic = match transaction_extrinsic(
+			self.client.as_ref(),
+			self.convert_transaction.as_ref(),
+			&block_hash,
+			transaction.clone(),
+		) {
+			Ok(extrinsic) => extrinsic,
+			Err(err) => return future::err(err).boxed(),
 		};
 
+		let client = self.client.clone();
 		Box::pin(
 			self.pool
 				.submit_one(&block_hash, TransactionSource::Local, extrinsic)
 				.map_ok(move |_| transaction_hash)
-				.map_err(|err| {
-					internal_err(format!("submit transaction to pool failed: {:?}", err))
+				.map_err(move |err| {
+					submit_error(err, || {
+						check_transaction(client.as_ref(), &block_hash, transaction)
+					})
 				}),
 		)
 	}
 
 	pub fn send_raw_transaction(&self, bytes: Bytes) -> BoxFuture<Result<H256>> {
-		let slice = &bytes.0[..];
-		if slice.is_empty() {
-			return future::err(internal_err("transaction data is empty")).boxed();
-		}
-		let first = slice.get(0).unwrap();
-		let transaction = if first > &0x7f {
-			// Legacy transaction. Decode and wrap in envelope.
-			match rlp::decode::<ethereum::TransactionV0>(slice) {
-				Ok(transaction) => ethereum::TransactionV2::Legacy(transaction),
-				Err(_) => return future::err(internal_err("decode transaction failed")).boxed(),
-			}
-		} else {
-			// Typed Transaction.
-			// `ethereum` crate decode implementation for `TransactionV2` expects a valid rlp input,
-			// and EIP-1559 breaks that assumption by prepending a version byte.
-			// We re-encode the payload input to get a valid rlp, and the decode implementation will strip
-			// them to check the transaction version byte.
-			let extend = rlp::encode(&slice);
-			match rlp::decode::<ethereum::TransactionV2>(&extend[..]) {
-				Ok(transaction) => transaction,
-				Err(_) => return future::err(internal_err("decode transaction failed")).boxed(),
-			}
+		let transaction = match decode_raw_transaction(&bytes.0[..]) {
+			Ok(transaction) => transaction,
+			Err(err) => return future::err(err).boxed(),
 		};
 
 		let transaction_hash = transaction.hash();
 
 		let block_hash = BlockId::hash(self.client.info().best_hash);
-		let api_version = match self
-			.client
-			.runtime_api()
-			.api_version::<dyn ConvertTransactionRuntimeApi<B>>(&block_hash)
-		{
-			Ok(api_version) => api_version,
-			_ => return future::err(internal_err("cannot access runtime api")).boxed(),
-		};
-
-		let extrinsic = match api_version {
-			Some(2) => match self
-				.client
-				.runtime_api()
-				.convert_transaction(&block_hash, transaction)
-			{
-				Ok(extrinsic) => extrinsic,
-				Err(_) => return future::err(internal_err("cannot access runtime api")).boxed(),
-			},
-			Some(1) => {
-				if let ethereum::TransactionV2::Legacy(legacy_transaction) = transaction {
-					// To be compatible with runtimes that do not support transactions v2
-					#[allow(deprecated)]
-					match self
-						.client
-						.runtime_api()
-						.convert_transaction_before_version_2(&block_hash, legacy_transaction)
-					{
-						Ok(extrinsic) => extrinsic,
-						Err(_) => {
-							return future::err(internal_err("cannot access runtime api")).boxed();
-						}
-					}
-				} else {
-					return future::err(internal_err(
-						"This runtime not support eth transactions v2",
-					))
-					.boxed();
-				}
-			}
-			None => {
-				if let Some(ref convert_transaction) = self.convert_transaction {
-					convert_transaction.convert_transaction(transaction.clone())
-				} else {
-					return future::err(internal_err(
-						"No TransactionConverter is provided and the runtime api ConvertTransactionRuntimeApi is not found"
-					)).boxed();
-				}
-			}
-			_ => {
-				return future::err(internal_err(
-					"ConvertTransactionRuntimeApi version not supported",
-				))
-				.boxed()
-			}
+		let extrinsic = match transaction_extrinsic(
+			self.client.as_ref(),
+			self.convert_transaction.as_ref(),
+			&block_hash,
+			transaction.clone(),
+		) {
+			Ok(extrinsic) => extrinsic,
+			Err(err) => return future::err(err).boxed(),
 		};
 
+		let client = self.client.clone();
 		Box::pin(
 			self.pool
 				.submit_one(&block_hash, TransactionSource::Local, extrinsic)
 				.map_ok(move |_| transaction_hash)
-				.map_err(|err| {
-					internal_err(format!("submit transaction to pool failed: {:?}", err))
+				.map_err(move |err| {
+					submit_error(err, || {
+						check_transaction(client.as_ref(), &block_hash, transaction)
+					})
 				}),
 		)
 	}
+
+	pub fn sign(&self, address: H160, data: Bytes) -> Result<Bytes> {
+		for signer in &self.signers {
+			if signer.accounts().contains(&address) {
+				return signer
+					.sign_message(&data.0[..], &address)
+					.map(|signature| Bytes(signature.to_vec()));
+			}
+		}
+		Err(internal_err("no signer available"))
+	}
+}
+
+/// Converts `transaction` to an extrinsic of the runtime at `block_hash`, with its
+/// `ConvertTransactionRuntimeApi` or else with the transaction converter of its spec version.
+pub(crate) fn transaction_extrinsic<B, C, CT>(
+	client: &C,
+	convert_transaction: Option<&CT>,
+	block_hash: &BlockId<B>,
+	transaction: EthereumTransaction,
+) -> Result<B::Extrinsic>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: ConvertTransactionRuntimeApi<B>,
+	CT: ConvertTransaction<<B as BlockT>::Extrinsic>,
+{
+	let api_version = client
+		.runtime_api()
+		.api_version::<dyn ConvertTransactionRuntimeApi<B>>(block_hash)
+		.map_err(|_| internal_err("cannot access runtime api"))?;
+
+	match api_version {
+		Some(2) => client
+			.runtime_api()
+			.convert_transaction(block_hash, transaction)
+			.map_err(|_| internal_err("cannot access runtime api")),
+		Some(1) => {
+			if let ethereum::TransactionV2::Legacy(legacy_transaction) = transaction {
+				// To be compatible with runtimes that do not support transactions v2
+				#[allow(deprecated)]
+				client
+					.runtime_api()
+					.convert_transaction_before_version_2(block_hash, legacy_transaction)
+					.map_err(|_| internal_err("cannot access runtime api"))
+			} else {
+				Err(internal_err("This runtime not support eth transactions v2"))
+			}
+		}
+		None => {
+			let convert_transaction = convert_transaction.ok_or_else(|| {
+				internal_err(
+					"No TransactionConverter is provided and the runtime api ConvertTransactionRuntimeApi is not found",
+				)
+			})?;
+			let spec_version = client
+				.runtime_api()
+				.version(block_hash)
+				.map_err(|_| internal_err("cannot access runtime api"))?
+				.spec_version;
+			convert_transaction
+				.convert_transaction_for(spec_version, transaction)
+				.ok_or_else(|| {
+					internal_err(format!(
+						"no transaction converter for the runtime spec version {}",
+						spec_version
+					))
+				})
+		}
+		_ => Err(internal_err(
+			"ConvertTransactionRuntimeApi version not supported",
+		)),
+	}
+}
+
+/// Checks a transaction rejected by the pool with the runtime, to find the precise reason the
+/// validity error it reported stands for.
+pub(crate) fn check_transaction<B, C>(
+	client: &C,
+	id: &BlockId<B>,
+	transaction: EthereumTransaction,
+) -> Option<InvalidTransactionReason>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeRPCApi<B>,
+{
+	let api = client.runtime_api();
+	match api.api_version::<dyn EthereumRuntimeRPCApi<B>>(id) {
+		Ok(Some(api_version)) if api_version >= 8 => {}
+		_ => return None,
+	}
+	match api.check_transaction(id, transaction) {
+		Ok(Err(err)) => Some(invalid_transaction_reason(err)),
+		_ => None,
+	}
+}
+
+/// Converts a pool submission failure to the error reported by geth in the same case.
+pub(crate) fn submit_error<E, F>(err: E, reason: F) -> jsonrpc_core::Error
+where
+	E: IntoPoolError + std::fmt::Debug,
+	F: FnOnce() -> Option<InvalidTransactionReason>,
+{
+	let err = match err.into_pool_error() {
+		Ok(err) => err,
+		Err(err) => return internal_err(format!("submit transaction to pool failed: {:?}", err)),
+	};
+	let message = match &err {
+		PoolError::AlreadyImported(_) => Some("already known"),
+		PoolError::TooLowPriority { .. } => Some("replacement transaction underpriced"),
+		PoolError::ImmediatelyDropped => Some("txpool is full"),
+		PoolError::InvalidTransaction(invalid) => {
+			reason().map(|reason| reason.message()).or(match invalid {
+				InvalidTransaction::Stale => Some("nonce too low"),
+				InvalidTransaction::Future => Some("nonce too high"),
+				InvalidTransaction::Payment => Some("insufficient funds for gas * price + value"),
+				InvalidTransaction::ExhaustsResources => Some("exceeds block gas limit"),
+				InvalidTransaction::BadProof => Some("invalid sender"),
+				_ => None,
+			})
+		}
+		_ => None,
+	};
+	match message {
+		Some(message) => internal_err(message),
+		None => internal_err(format!("submit transaction to pool failed: {:?}", err)),
+	}
 }
@@ -19,16 +19,16 @@
 use std::sync::Arc;
 
 use ethereum::TransactionV2 as EthereumTransaction;
-use ethereum_types::{H256, U256, U64};
+use ethereum_types::H256;
 use jsonrpc_core::{BoxFuture, Result};
 
 use sc_client_api::backend::{Backend, StateBackend, StorageProvider};
 use sc_network::ExHashT;
-use sc_transaction_pool::ChainApi;
+use sc_transaction_pool::{ChainApi, Pool};
 use sc_transaction_pool_api::InPoolTransaction;
-use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_api::ProvideRuntimeApi;
+use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::HeaderBackend;
-use sp_core::hashing::keccak_256;
 use sp_runtime::{
 	generic::BlockId,
 	traits::{BlakeTwo256, Block as BlockT},
@@ -38,15 +38,15 @@ use fc_rpc_core::types::*;
 use fp_rpc::EthereumRuntimeRPCApi;
 
 use crate::{
-	eth::{transaction_build, Eth},
-	frontier_backend_client, internal_err,
+	eth::{receipt_build, transaction_build, Eth},
+	ethereum_api_version, frontier_backend_client, internal_err,
 };
 
 impl<B, C, P, CT, BE, H: ExHashT, A: ChainApi> Eth<B, C, P, CT, BE, H, A>
 where
 	B: BlockT<Hash = H256> + Send + Sync + 'static,
 	C: ProvideRuntimeApi<B> + StorageProvider<B, BE> + HeaderBackend<B> + Send + Sync + 'static,
-	C::Api: EthereumRuntimeRPCApi<B>,
+	C::Api: BlockBuilderApi<B> + EthereumRuntimeRPCApi<B>,
 	BE: Backend<B> + 'static,
 	BE::State: StateBackend<BlakeTwo256>,
 	A: ChainApi<Block = B> + 'static,
@@ -68,67 +68,9 @@ where
 			.map_err(|err| internal_err(format!("{:?}", err)))?
 			{
 				Some((hash, index)) => (hash, index as usize),
-				None => {
-					let api = client.runtime_api();
-					let best_block: BlockId<B> = BlockId::Hash(client.info().best_hash);
-
-					let api_version = if let Ok(Some(api_version)) =
-						api.api_version::<dyn EthereumRuntimeRPCApi<B>>(&best_block)
-					{
-						api_version
-					} else {
-						return Err(internal_err("failed to retrieve Runtime Api version"));
-					};
-					// If the transaction is not yet mapped in the frontier db,
-					// check for it in the transaction pool.
-					let mut xts: Vec<<B as BlockT>::Extrinsic> = Vec::new();
-					// Collect transactions in the ready validated pool.
-					xts.extend(
-						graph
-							.validated_pool()
-							.ready()
-							.map(|in_pool_tx| in_pool_tx.data().clone())
-							.collect::<Vec<<B as BlockT>::Extrinsic>>(),
-					);
-
-					// Collect transactions in the future validated pool.
-					xts.extend(
-						graph
-							.validated_pool()
-							.futures()
-							.iter()
-							.map(|(_hash, extrinsic)| extrinsic.clone())
-							.collect::<Vec<<B as BlockT>::Extrinsic>>(),
-					);
-
-					let ethereum_transactions: Vec<EthereumTransaction> = if api_version > 1 {
-						api.extrinsic_filter(&best_block, xts).map_err(|err| {
-							internal_err(format!(
-								"fetch runtime extrinsic filter failed: {:?}",
-								err
-							))
-						})?
-					} else {
-						#[allow(deprecated)]
-						let legacy = api.extrinsic_filter_before_version_2(&best_block, xts)
-							.map_err(|err| {
-								internal_err(format!(
-									"fetch runtime extrinsic filter failed: {:?}",
-									err
-								))
-							})?;
-						legacy.into_iter().map(|tx| tx.into()).collect()
-					};
-
-					for txn in ethereum_transactions {
-						let inner_hash = txn.hash();
-						if hash == inner_hash {
-							return Ok(Some(transaction_build(txn, None, None, None)));
-						}
-					}
-					// Unknown transaction.
-					return Ok(None);
-				}
+				// If the transaction is not yet mapped in the frontier db,
+				// check for it in the transaction pool.
+				None => return pending_transaction(client.as_ref(), graph.as_ref(), hash),
 			};
 
 			let id = match frontier_backend_client::load_hash::<B>(backend.as_ref(), hash)
@@ -167,6 +109,19 @@ where
 		})
 	}
 
+	pub fn raw_transaction_by_hash(&self, hash: H256) -> Result<Option<Bytes>> {
+		if !self.backend.archives_raw_transactions() {
+			return Err(internal_err(
+				"raw transactions are not archived by this node",
+			));
+		}
+		Ok(self
+			.backend
+			.raw_transaction(&hash)
+			.map_err(|err| internal_err(format!("{:?}", err)))?
+			.map(Bytes))
+	}
+
 	pub fn transaction_by_block_hash_and_index(
 		&self,
 		hash: H256,
@@ -184,9 +139,13 @@ where
 				Some(hash) => hash,
 				_ => return Ok(None),
 			};
-			let substrate_hash = client
-				.expect_block_hash_from_id(&id)
-				.map_err(|_| internal_err(format!("Expect block number from id: {}", id)))?;
+			let substrate_hash = match client
+				.block_hash_from_id(&id)
+				.map_err(|err| internal_err(format!("{:?}", err)))?
+			{
+				Some(hash) => hash,
+				_ => return Ok(None),
+			};
 
 			let index = index.value();
 
@@ -216,7 +175,7 @@ where
 							base_fee,
 						)))
 					} else {
-						Err(internal_err(format!("{:?} is out of bounds", index)))
+						Ok(None)
 					}
 				}
 				_ => Ok(None),
@@ -229,6 +188,18 @@ where
 		number: BlockNumber,
 		index: Index,
 	) -> BoxFuture<Result<Option<Transaction>>> {
+		if number == BlockNumber::Pending {
+			let transaction = self.pending_block(true).map(|block| {
+				block.and_then(|block| match block.inner.transactions {
+					BlockTransactions::Full(transactions) => {
+						transactions.into_iter().nth(index.value())
+					}
+					BlockTransactions::Hashes(_) => None,
+				})
+			});
+			return Box::pin(async move { transaction });
+		}
+
 		let client = Arc::clone(&self.client);
 		let overrides = Arc::clone(&self.overrides);
 		let block_data_cache = Arc::clone(&self.block_data_cache);
@@ -243,9 +214,13 @@ where
 				Some(id) => id,
 				None => return Ok(None),
 			};
-			let substrate_hash = client
-				.expect_block_hash_from_id(&id)
-				.map_err(|_| internal_err(format!("Expect block number from id: {}", id)))?;
+			let substrate_hash = match client
+				.block_hash_from_id(&id)
+				.map_err(|err| internal_err(format!("{:?}", err)))?
+			{
+				Some(hash) => hash,
+				_ => return Ok(None),
+			};
 
 			let index = index.value();
 			let schema =
@@ -274,7 +249,7 @@ where
 							base_fee,
 						)))
 					} else {
-						Err(internal_err(format!("{:?} is out of bounds", index)))
+						Ok(None)
 					}
 				}
 				_ => Ok(None),
@@ -326,138 +301,74 @@ where
 			let is_eip1559 = handler.is_eip1559(&id);
 
 			match (block, statuses, receipts) {
-				(Some(block), Some(statuses), Some(receipts)) => {
-					let block_hash = H256::from(keccak_256(&rlp::encode(&block.header)));
-					let receipt = receipts[index].clone();
-
-					let (logs, logs_bloom, status_code, cumulative_gas_used, gas_used) =
-						if !is_eip1559 {
-							// Pre-london frontier update stored receipts require cumulative gas calculation.
-							match receipt {
-								ethereum::ReceiptV3::Legacy(d) => {
-									let index = core::cmp::min(receipts.len(), index + 1);
-									let cumulative_gas: u32 = receipts[..index]
-										.iter()
-										.map(|r| match r {
-											ethereum::ReceiptV3::Legacy(d) => {
-												Ok(d.used_gas.as_u32())
-											}
-											_ => Err(internal_err(format!(
-												"Unknown receipt for request {}",
-												hash
-											))),
-										})
-										.sum::<Result<u32>>()?;
-									(
-										d.logs,
-										d.logs_bloom,
-										d.status_code,
-										U256::from(cumulative_gas),
-										d.used_gas,
-									)
-								}
-								_ => {
-									return Err(internal_err(format!(
-										"Unknown receipt for request {}",
-										hash
-									)))
-								}
-							}
-						} else {
-							match receipt {
-								ethereum::ReceiptV3::Legacy(d)
-								| ethereum::ReceiptV3::EIP2930(d)
-								| ethereum::ReceiptV3::EIP1559(d) => {
-									let cumulative_gas = d.used_gas;
-									let gas_used = if index > 0 {
-										let previous_receipt = receipts[index - 1].clone();
-										let previous_gas_used = match previous_receipt {
-											ethereum::ReceiptV3::Legacy(d)
-											| ethereum::ReceiptV3::EIP2930(d)
-											| ethereum::ReceiptV3::EIP1559(d) => d.used_gas,
-										};
-										cumulative_gas.saturating_sub(previous_gas_used)
-									} else {
-										cumulative_gas
-									};
-									(
-										d.logs,
-										d.logs_bloom,
-										d.status_code,
-										cumulative_gas,
-										gas_used,
-									)
-								}
-							}
-						};
-
-					let status = statuses[index].clone();
-					let mut cumulative_receipts = receipts;
-					cumulative_receipts.truncate((status.transaction_index + 1) as usize);
-
-					let transaction = block.transactions[index].clone();
-					let effective_gas_price = match transaction {
-						EthereumTransaction::Legacy(t) => t.gas_price,
-						EthereumTransaction::EIP2930(t) => t.gas_price,
-						EthereumTransaction::EIP1559(t) => handler
-							.base_fee(&id)
-							.unwrap_or_default()
-							.checked_add(t.max_priority_fee_per_gas)
-							.unwrap_or_else(U256::max_value)
-							.min(t.max_fee_per_gas),
-					};
-
-					return Ok(Some(Receipt {
-						transaction_hash: Some(status.transaction_hash),
-						transaction_index: Some(status.transaction_index.into()),
-						block_hash: Some(block_hash),
-						from: Some(status.from),
-						to: status.to,
-						block_number: Some(block.header.number),
-						cumulative_gas_used,
-						gas_used: Some(gas_used),
-						contract_address: status.contract_address,
-						logs: {
-							let mut pre_receipts_log_index = None;
-							if cumulative_receipts.len() > 0 {
-								cumulative_receipts.truncate(cumulative_receipts.len() - 1);
-								pre_receipts_log_index = Some(
-									cumulative_receipts
-										.iter()
-										.map(|r| match r {
-											ethereum::ReceiptV3::Legacy(d)
-											| ethereum::ReceiptV3::EIP2930(d)
-											| ethereum::ReceiptV3::EIP1559(d) => d.logs.len() as u32,
-										})
-										.sum::<u32>(),
-								);
-							}
-							logs.iter()
-								.enumerate()
-								.map(|(i, log)| Log {
-									address: log.address,
-									topics: log.topics.clone(),
-									data: Bytes(log.data.clone()),
-									block_hash: Some(block_hash),
-									block_number: Some(block.header.number),
-									transaction_hash: Some(status.transaction_hash),
-									transaction_index: Some(status.transaction_index.into()),
-									log_index: Some(U256::from(
-										(pre_receipts_log_index.unwrap_or(0)) + i as u32,
-									)),
-									transaction_log_index: Some(U256::from(i)),
-									removed: false,
-								})
-								.collect()
-						},
-						status_code: Some(U64::from(status_code)),
-						logs_bloom,
-						state_root: None,
-						effective_gas_price,
-					}));
-				}
+				(Some(block), Some(statuses), Some(receipts)) => Ok(Some(receipt_build(
+					&block,
+					&statuses,
+					&receipts,
+					index,
+					is_eip1559,
+					handler.base_fee(&id),
+				)?)),
 				_ => Ok(None),
 			}
 		})
 	}
 }
+
+/// Look for a transaction in the transaction pool, ready or future. A pending transaction
+/// has no block hash, block number and transaction index, as in Geth.
+fn pending_transaction<B, C, A>(
+	client: &C,
+	graph: &Pool<A>,
+	hash: H256,
+) -> Result<Option<Transaction>>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync + 'static,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	A: ChainApi<Block = B> + 'static,
+{
+	let api = client.runtime_api();
+	let best_block: BlockId<B> = BlockId::Hash(client.info().best_hash);
+
+	let api_version = ethereum_api_version(client, &best_block, 1)?;
+
+	let mut xts: Vec<<B as BlockT>::Extrinsic> = Vec::new();
+	// Collect transactions in the ready validated pool.
+	xts.extend(
+		graph
+			.validated_pool()
+			.ready()
+			.map(|in_pool_tx| in_pool_tx.data().clone())
+			.collect::<Vec<<B as BlockT>::Extrinsic>>(),
+	);
+
+	// Collect transactions in the future validated pool.
+	xts.extend(
+		graph
+			.validated_pool()
+			.futures()
+			.iter()
+			.map(|(_hash, extrinsic)| extrinsic.clone())
+			.collect::<Vec<<B as BlockT>::Extrinsic>>(),
+	);
+
+	let ethereum_transactions: Vec<EthereumTransaction> = if api_version > 1 {
+		api.extrinsic_filter(&best_block, xts).map_err(|err| {
+			internal_err(format!("fetch runtime extrinsic filter failed: {:?}", err))
+		})?
+	} else {
+		#[allow(deprecated)]
+		let legacy = api
+			.extrinsic_filter_before_version_2(&best_block, xts)
+			.map_err(|err| {
+				internal_err(format!("fetch runtime extrinsic filter failed: {:?}", err))
+			})?;
+		legacy.into_iter().map(|tx| tx.into()).collect()
+	};
+
+	Ok(ethereum_transactions
+		.into_iter()
+		.find(|transaction| transaction.hash() == hash)
+		.map(|transaction| transaction_build(transaction, None, None, None)))
+}
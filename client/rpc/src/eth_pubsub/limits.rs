@@ -0,0 +1,289 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+	collections::HashMap,
+	future::Future,
+	sync::{Arc, Mutex},
+};
+
+use futures::{
+	channel::mpsc,
+	future::{self, Either},
+	Sink, SinkExt as _, Stream, StreamExt as _,
+};
+use jsonrpc_core::{Error, ErrorCode};
+use jsonrpc_pubsub::PubSubMetadata;
+
+/// Limits of the Ethereum subscriptions, shared by all the connections of the RPC servers.
+///
+/// A connection can't have more than `max_subscriptions_per_connection` active subscriptions.
+/// Each subscription buffers up to `notification_buffer_size` notifications for a slow
+/// subscriber. When its buffer is full, further notifications are dropped, and the subscriber
+/// is then notified of the number of notifications it missed with an error notification.
+#[derive(Clone)]
+pub struct EthPubSubLimits {
+	max_subscriptions_per_connection: usize,
+	notification_buffer_size: usize,
+	/// Number of active subscriptions per connection.
+	connections: Arc<Mutex<HashMap<usize, usize>>>,
+	metrics: Option<Arc<EthPubSubMetrics>>,
+}
+
+impl EthPubSubLimits {
+	pub fn new(
+		max_subscriptions_per_connection: usize,
+		notification_buffer_size: usize,
+		prometheus_registry: Option<prometheus_endpoint::Registry>,
+	) -> Self {
+		let metrics = match prometheus_registry {
+			Some(registry) => match EthPubSubMetrics::register(&registry) {
+				Ok(metrics) => Some(Arc::new(metrics)),
+				Err(e) => {
+					log::error!(target: "eth-pubsub", "Failed to register metrics: {:?}", e);
+					None
+				}
+			},
+			None => None,
+		};
+
+		Self {
+			max_subscriptions_per_connection,
+			notification_buffer_size: notification_buffer_size.max(1),
+			connections: Default::default(),
+			metrics,
+		}
+	}
+
+	/// Registers a new subscription of the connection of `metadata`, or returns `None` if the
+	/// connection already has too many subscriptions.
+	///
+	/// The subscription is active until the returned guard is dropped.
+	pub(crate) fn subscribe<M: PubSubMetadata>(&self, metadata: &M) -> Option<SubscriptionGuard> {
+		// The session of a connection lives as long as the connection, so its address
+		// identifies it.
+		let connection = metadata
+			.session()
+			.map(|session| Arc::as_ptr(&session) as usize)
+			.unwrap_or_default();
+		self.subscribe_connection(connection)
+	}
+
+	fn subscribe_connection(&self, connection: usize) -> Option<SubscriptionGuard> {
+		let mut connections = self.connections.lock().expect("not poisoned; qed");
+		let subscriptions = connections.entry(connection).or_default();
+		if *subscriptions >= self.max_subscriptions_per_connection {
+			if let Some(metrics) = &self.metrics {
+				metrics.rejected_subscriptions.inc();
+			}
+			return None;
+		}
+		*subscriptions += 1;
+
+		if let Some(metrics) = &self.metrics {
+			metrics.subscriptions.inc();
+		}
+		Some(SubscriptionGuard {
+			limits: self.clone(),
+			connection,
+		})
+	}
+
+	/// Sends the items of `stream` to `sink` through the notification buffer of a subscription.
+	pub(crate) fn forward<T, S, K>(
+		&self,
+		guard: SubscriptionGuard,
+		stream: S,
+		sink: K,
+	) -> impl Future<Output = ()>
+	where
+		S: Stream<Item = T>,
+		K: Sink<Result<T, Error>>,
+	{
+		// Each buffered notification comes with the number of notifications dropped before it.
+		let (mut sender, mut receiver) = mpsc::channel::<(usize, Option<T>)>(
+			// The channel has an additional slot for its sender.
+			self.notification_buffer_size - 1,
+		);
+		let metrics = self.metrics.clone();
+
+		async move {
+			let _guard = guard;
+			futures::pin_mut!(stream, sink);
+
+			let produce = async move {
+				let mut dropped = 0;
+				while let Some(item) = stream.next().await {
+					match sender.try_send((dropped, Some(item))) {
+						Ok(()) => dropped = 0,
+						Err(err) if err.is_full() => {
+							dropped += 1;
+							if let Some(metrics) = &metrics {
+								metrics.dropped_notifications.inc();
+							}
+						}
+						// The subscriber is gone.
+						Err(_) => return,
+					}
+				}
+				if dropped > 0 {
+					let _ = sender.send((dropped, None)).await;
+				}
+				// Ends the buffer.
+				drop(sender);
+			};
+			let consume = async move {
+				while let Some((dropped, item)) = receiver.next().await {
+					if dropped > 0 && sink.send(Err(dropped_err(dropped))).await.is_err() {
+						return;
+					}
+					if let Some(item) = item {
+						if sink.send(Ok(item)).await.is_err() {
+							return;
+						}
+					}
+				}
+			};
+			futures::pin_mut!(produce, consume);
+
+			// Once the stream ends, the buffered notifications are still sent.
+			if let Either::Left((_, consume)) = future::select(produce, consume).await {
+				consume.await;
+			}
+		}
+	}
+}
+
+/// Error sent to a subscriber which missed notifications.
+fn dropped_err(dropped: usize) -> Error {
+	Error {
+		code: ErrorCode::ServerError(-32000),
+		message: format!(
+			"{} notifications dropped, the subscriber is too slow",
+			dropped
+		),
+		data: None,
+	}
+}
+
+/// Error rejecting a subscription over the limit of its connection.
+pub(crate) fn too_many_subscriptions_err() -> Error {
+	Error {
+		code: ErrorCode::ServerError(-32000),
+		message: String::from("too many subscriptions on this connection"),
+		data: None,
+	}
+}
+
+/// An active subscription, released on drop.
+pub(crate) struct SubscriptionGuard {
+	limits: EthPubSubLimits,
+	connection: usize,
+}
+
+impl Drop for SubscriptionGuard {
+	fn drop(&mut self) {
+		let mut connections = self.limits.connections.lock().expect("not poisoned; qed");
+		if let Some(subscriptions) = connections.get_mut(&self.connection) {
+			*subscriptions -= 1;
+			if *subscriptions == 0 {
+				connections.remove(&self.connection);
+			}
+		}
+
+		if let Some(metrics) = &self.limits.metrics {
+			metrics.subscriptions.dec();
+		}
+	}
+}
+
+struct EthPubSubMetrics {
+	subscriptions: prometheus::IntGauge,
+	rejected_subscriptions: prometheus::IntCounter,
+	dropped_notifications: prometheus::IntCounter,
+}
+
+impl EthPubSubMetrics {
+	fn register(
+		registry: &prometheus_endpoint::Registry,
+	) -> std::result::Result<Self, prometheus_endpoint::PrometheusError> {
+		Ok(Self {
+			subscriptions: prometheus_endpoint::register(
+				prometheus::IntGauge::new(
+					"frontier_eth_pubsub_subscriptions",
+					"Active eth subscriptions.",
+				)?,
+				registry,
+			)?,
+			rejected_subscriptions: prometheus_endpoint::register(
+				prometheus::IntCounter::new(
+					"frontier_eth_pubsub_rejected_subscriptions",
+					"Eth subscriptions rejected by the per connection limit.",
+				)?,
+				registry,
+			)?,
+			dropped_notifications: prometheus_endpoint::register(
+				prometheus::IntCounter::new(
+					"frontier_eth_pubsub_dropped_notifications",
+					"Eth subscription notifications dropped for slow subscribers.",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn limits_the_subscriptions_of_a_connection() {
+		let limits = EthPubSubLimits::new(2, 16, None);
+
+		let first = limits.subscribe_connection(1).expect("under the limit");
+		let _second = limits.subscribe_connection(1).expect("under the limit");
+		assert!(limits.subscribe_connection(1).is_none());
+		// Other connections have their own limit.
+		assert!(limits.subscribe_connection(2).is_some());
+
+		drop(first);
+		assert!(limits.subscribe_connection(1).is_some());
+	}
+
+	#[test]
+	fn drops_and_reports_notifications_over_the_buffer_size() {
+		let limits = EthPubSubLimits::new(1, 2, None);
+		let guard = limits.subscribe_connection(1).expect("under the limit");
+		let (sink, received) = mpsc::unbounded::<std::result::Result<u32, Error>>();
+
+		// The whole stream is ready before the subscriber receives anything.
+		futures::executor::block_on(limits.forward(guard, futures::stream::iter(0..5), sink));
+
+		let received = futures::executor::block_on(received.collect::<Vec<_>>());
+		assert_eq!(received.len(), 3);
+		assert_eq!(received[0], Ok(0));
+		assert_eq!(received[1], Ok(1));
+		assert_eq!(
+			received[2].as_ref().unwrap_err().message,
+			"3 notifications dropped, the subscriber is too slow"
+		);
+		// The subscription has been released.
+		assert!(limits.subscribe_connection(1).is_some());
+	}
+}
@@ -16,6 +16,8 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+mod limits;
+
 use std::{collections::BTreeMap, iter, marker::PhantomData, sync::Arc};
 
 use ethereum::{BlockV2 as EthereumBlock, TransactionV2 as EthereumTransaction};
@@ -28,6 +30,7 @@ use jsonrpc_pubsub::{
 	SubscriptionId,
 };
 use log::warn;
+use lru::LruCache;
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
 
 use sc_client_api::{
@@ -45,13 +48,19 @@ use sp_runtime::traits::{BlakeTwo256, Block as BlockT, UniqueSaturatedInto};
 use fc_rpc_core::{
 	types::{
 		pubsub::{Kind, Params, PubSubSyncStatus, Result as PubSubResult, SyncStatusMetadata},
-		Bytes, FilteredParams, Header, Log, Rich,
+		Bytes, FilteredParams, Log, Rich,
 	},
 	EthPubSubApi,
 };
 use fp_rpc::EthereumRuntimeRPCApi;
 
-use crate::{frontier_backend_client, overrides::OverrideHandle};
+use crate::{eth::header_build, frontier_backend_client, overrides::OverrideHandle};
+
+pub use self::limits::EthPubSubLimits;
+
+/// Number of recent blocks whose notified logs are kept by a logs subscription, to re-send them
+/// as removed if their block is retracted.
+const NOTIFIED_LOGS_BLOCKS: usize = 64;
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct HexEncodedIdProvider {
@@ -85,6 +94,7 @@ pub struct EthPubSub<B: BlockT, P, C, BE, H: ExHashT> {
 	network: Arc<NetworkService<B, H>>,
 	subscriptions: SubscriptionManager<HexEncodedIdProvider>,
 	overrides: Arc<OverrideHandle<B>>,
+	limits: EthPubSubLimits,
 	starting_block: u64,
 	_marker: PhantomData<BE>,
 }
@@ -99,6 +109,7 @@ where
 		network: Arc<NetworkService<B, H>>,
 		subscriptions: SubscriptionManager<HexEncodedIdProvider>,
 		overrides: Arc<OverrideHandle<B>>,
+		limits: EthPubSubLimits,
 	) -> Self {
 		// Capture the best block as seen on initialization. Used for syncing subscriptions.
 		let starting_block =
@@ -109,6 +120,7 @@ where
 			network,
 			subscriptions,
 			overrides,
+			limits,
 			starting_block,
 			_marker: PhantomData,
 		}
@@ -122,28 +134,7 @@ impl SubscriptionResult {
 	}
 	pub fn new_heads(&self, block: EthereumBlock) -> PubSubResult {
 		PubSubResult::Header(Box::new(Rich {
-			inner: Header {
-				hash: Some(H256::from(keccak_256(&rlp::encode(&block.header)))),
-				parent_hash: block.header.parent_hash,
-				uncles_hash: block.header.ommers_hash,
-				author: block.header.beneficiary,
-				miner: block.header.beneficiary,
-				state_root: block.header.state_root,
-				transactions_root: block.header.transactions_root,
-				receipts_root: block.header.receipts_root,
-				number: Some(block.header.number),
-				gas_used: block.header.gas_used,
-				gas_limit: block.header.gas_limit,
-				extra_data: Bytes(block.header.extra_data.clone()),
-				logs_bloom: block.header.logs_bloom,
-				timestamp: U256::from(block.header.timestamp),
-				difficulty: block.header.difficulty,
-				seal_fields: vec![
-					Bytes(block.header.mix_hash.as_bytes().to_vec()),
-					Bytes(block.header.nonce.as_bytes().to_vec()),
-				],
-				size: Some(U256::from(rlp::encode(&block.header).len() as u32)),
-			},
+			inner: header_build(&block, None),
 			extra_info: BTreeMap::new(),
 		}))
 	}
@@ -236,11 +227,18 @@ where
 	type Metadata = Metadata;
 	fn subscribe(
 		&self,
-		_metadata: Self::Metadata,
+		metadata: Self::Metadata,
 		subscriber: Subscriber<PubSubResult>,
 		kind: Kind,
 		params: Option<Params>,
 	) {
+		let guard = match self.limits.subscribe(&metadata) {
+			Some(guard) => guard,
+			None => {
+				let _ = subscriber.reject(limits::too_many_subscriptions_err());
+				return;
+			}
+		};
 		let filtered_params = match params {
 			Some(Params::Logs(filter)) => FilteredParams::new(Some(filter)),
 			_ => FilteredParams::default(),
@@ -250,56 +248,77 @@ where
 		let pool = self.pool.clone();
 		let network = self.network.clone();
 		let overrides = self.overrides.clone();
+		let limits = self.limits.clone();
 		let starting_block = self.starting_block;
 		match kind {
 			Kind::Logs => {
 				self.subscriptions.add(subscriber, |sink| {
-					let stream = client
-						.import_notification_stream()
-						.filter_map(move |notification| {
-							if notification.is_new_best {
-								let id = BlockId::Hash(notification.hash);
+					let notifications = client.import_notification_stream();
+					// Logs of the block, matching the filter of the subscription.
+					let block_logs = move |hash: B::Hash| {
+						let id = BlockId::Hash(hash);
 
-								let schema = frontier_backend_client::onchain_storage_schema::<
-									B,
-									C,
-									BE,
-								>(client.as_ref(), id);
-								let handler = overrides
-									.schemas
-									.get(&schema)
-									.unwrap_or(&overrides.fallback);
+						let schema = frontier_backend_client::onchain_storage_schema::<B, C, BE>(
+							client.as_ref(),
+							id,
+						);
+						let handler = overrides
+							.schemas
+							.get(&schema)
+							.unwrap_or(&overrides.fallback);
 
-								let block = handler.current_block(&id);
-								let receipts = handler.current_receipts(&id);
+						let block = handler.current_block(&id);
+						let receipts = handler.current_receipts(&id);
+
+						match (receipts, block) {
+							(Some(receipts), Some(block)) => {
+								SubscriptionResult::new().logs(block, receipts, &filtered_params)
+							}
+							_ => Vec::new(),
+						}
+					};
+					// Logs notified for the recent blocks, re-sent as removed when their block
+					// is retracted.
+					let mut notified_logs =
+						LruCache::<B::Hash, Vec<Log>>::new(NOTIFIED_LOGS_BLOCKS);
+
+					let stream = notifications
+						.filter_map(move |notification| {
+							if !notification.is_new_best {
+								return futures::future::ready(None);
+							}
 
-								match (receipts, block) {
-									(Some(receipts), Some(block)) => {
-										futures::future::ready(Some((block, receipts)))
+							let mut logs = Vec::new();
+							let mut enacted = Vec::new();
+							if let Some(tree_route) = &notification.tree_route {
+								// Retracted blocks come from the previous best block backwards.
+								for retracted in tree_route.retracted() {
+									if let Some(removed) = notified_logs.pop(&retracted.hash) {
+										logs.extend(removed.into_iter().rev().map(|log| Log {
+											removed: true,
+											..log
+										}));
 									}
-									_ => futures::future::ready(None),
 								}
-							} else {
-								futures::future::ready(None)
+								// The tree route ends at the parent of the new best block.
+								enacted.extend(tree_route.enacted().iter().map(|block| block.hash));
 							}
+							enacted.push(notification.hash);
+
+							for hash in enacted {
+								let block_logs = block_logs(hash);
+								logs.extend(block_logs.iter().cloned());
+								notified_logs.put(hash, block_logs);
+							}
+							futures::future::ready(Some(logs))
 						})
-						.flat_map(move |(block, receipts)| {
-							futures::stream::iter(SubscriptionResult::new().logs(
-								block,
-								receipts,
-								&filtered_params,
-							))
-						})
-						.map(|x| {
-							Ok::<Result<PubSubResult, jsonrpc_core::types::error::Error>, ()>(Ok(
-								PubSubResult::Log(Box::new(x)),
-							))
-						});
-					stream
-						.forward(
-							sink.sink_map_err(|e| warn!("Error sending notifications: {:?}", e)),
-						)
-						.map(|_| ())
+						.flat_map(futures::stream::iter)
+						.map(|x| PubSubResult::Log(Box::new(x)));
+					limits.forward(
+						guard,
+						stream,
+						sink.sink_map_err(|e| warn!("Error sending notifications: {:?}", e)),
+					)
 				});
 			}
 			Kind::NewHeads => {
@@ -326,12 +345,12 @@ where
 								futures::future::ready(None)
 							}
 						})
-						.map(|block| Ok::<_, ()>(Ok(SubscriptionResult::new().new_heads(block))));
-					stream
-						.forward(
-							sink.sink_map_err(|e| warn!("Error sending notifications: {:?}", e)),
-						)
-						.map(|_| ())
+						.map(|block| SubscriptionResult::new().new_heads(block));
+					limits.forward(
+						guard,
+						stream,
+						sink.sink_map_err(|e| warn!("Error sending notifications: {:?}", e)),
+					)
 				});
 			}
 			Kind::NewPendingTransactions => {
@@ -384,16 +403,12 @@ where
 								futures::future::ready(None)
 							}
 						})
-						.map(|transaction| {
-							Ok::<Result<PubSubResult, jsonrpc_core::types::error::Error>, ()>(Ok(
-								PubSubResult::TransactionHash(transaction.hash()),
-							))
-						});
-					stream
-						.forward(
-							sink.sink_map_err(|e| warn!("Error sending notifications: {:?}", e)),
-						)
-						.map(|_| ())
+						.map(|transaction| PubSubResult::TransactionHash(transaction.hash()));
+					limits.forward(
+						guard,
+						stream,
+						sink.sink_map_err(|e| warn!("Error sending notifications: {:?}", e)),
+					)
 				});
 			}
 			Kind::Syncing => {
@@ -404,6 +419,9 @@ where
 					let client = Arc::clone(&client);
 					let network = Arc::clone(&network);
 					async move {
+						// Syncing notifications are rare, so they aren't buffered.
+						let _guard = guard;
+
 						// Gets the node syncing status.
 						// The response is expected to be serialized either as a plain boolean
 						// if the node is not syncing, or a structure containing syncing metadata
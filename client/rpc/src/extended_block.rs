@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use ethereum_types::H256;
+use jsonrpc_core::{BoxFuture, Result};
+
+use sc_client_api::backend::{Backend, StateBackend, StorageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_core::hashing::keccak_256;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{BlakeTwo256, Block as BlockT},
+};
+
+use fc_rpc_core::{
+	types::{BlockNumber, ExtendedBlock},
+	ExtendedBlockApi,
+};
+
+use crate::{
+	eth::{receipt_build, rich_block_build},
+	frontier_backend_client, internal_err,
+	overrides::OverrideHandle,
+	EthBlockDataCacheTask,
+};
+
+/// Extended block API implementation.
+pub struct ExtendedBlocks<B: BlockT, C, BE> {
+	client: Arc<C>,
+	backend: Arc<dyn fc_api::Backend<B>>,
+	overrides: Arc<OverrideHandle<B>>,
+	block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+	_marker: PhantomData<BE>,
+}
+
+impl<B: BlockT, C, BE> ExtendedBlocks<B, C, BE> {
+	pub fn new(
+		client: Arc<C>,
+		backend: Arc<dyn fc_api::Backend<B>>,
+		overrides: Arc<OverrideHandle<B>>,
+		block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+	) -> Self {
+		Self {
+			client,
+			backend,
+			overrides,
+			block_data_cache,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<B, C, BE> ExtendedBlocks<B, C, BE>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: StorageProvider<B, BE> + HeaderBackend<B> + Send + Sync + 'static,
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+{
+	fn extended_block(&self, id: BlockId<B>) -> BoxFuture<Result<Option<ExtendedBlock>>> {
+		let client = Arc::clone(&self.client);
+		let overrides = Arc::clone(&self.overrides);
+		let block_data_cache = Arc::clone(&self.block_data_cache);
+
+		Box::pin(async move {
+			// Block numbers over the best block aren't known yet.
+			let substrate_hash = match client
+				.block_hash_from_id(&id)
+				.map_err(|_| internal_err(format!("Expect block number from id: {}", id)))?
+			{
+				Some(hash) => hash,
+				None => return Ok(None),
+			};
+
+			let schema =
+				frontier_backend_client::onchain_storage_schema::<B, C, BE>(client.as_ref(), id);
+			let handler = overrides
+				.schemas
+				.get(&schema)
+				.unwrap_or(&overrides.fallback);
+
+			let block = block_data_cache.current_block(schema, substrate_hash).await;
+			let statuses = block_data_cache
+				.current_transaction_statuses(schema, substrate_hash)
+				.await;
+			let receipts = handler.current_receipts(&id);
+			let is_eip1559 = handler.is_eip1559(&id);
+			let base_fee = handler.base_fee(&id);
+
+			match (block, statuses, receipts) {
+				(Some(block), Some(statuses), Some(receipts)) => {
+					let receipts = (0..statuses.len())
+						.map(|index| {
+							receipt_build(&block, &statuses, &receipts, index, is_eip1559, base_fee)
+						})
+						.collect::<Result<Vec<_>>>()?;
+					let hash = H256::from(keccak_256(&rlp::encode(&block.header)));
+
+					Ok(Some(ExtendedBlock {
+						block: rich_block_build(
+							block,
+							statuses.into_iter().map(Option::Some).collect(),
+							Some(hash),
+							true,
+							base_fee,
+						),
+						receipts,
+					}))
+				}
+				_ => Ok(None),
+			}
+		})
+	}
+}
+
+impl<B, C, BE> ExtendedBlockApi for ExtendedBlocks<B, C, BE>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: StorageProvider<B, BE> + HeaderBackend<B> + Send + Sync + 'static,
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+{
+	fn extended_block_by_hash(&self, hash: H256) -> BoxFuture<Result<Option<ExtendedBlock>>> {
+		match frontier_backend_client::load_hash::<B>(self.backend.as_ref(), hash) {
+			Ok(Some(id)) => self.extended_block(id),
+			Ok(None) => Box::pin(async { Ok(None) }),
+			Err(err) => Box::pin(async move { Err(err) }),
+		}
+	}
+
+	fn extended_block_by_number(
+		&self,
+		number: BlockNumber,
+	) -> BoxFuture<Result<Option<ExtendedBlock>>> {
+		if number == BlockNumber::Pending {
+			return Box::pin(async { Ok(None) });
+		}
+		match frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			Some(number),
+		) {
+			Ok(Some(id)) => self.extended_block(id),
+			Ok(None) => Box::pin(async { Ok(None) }),
+			Err(err) => Box::pin(async move { Err(err) }),
+		}
+	}
+}
@@ -0,0 +1,473 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use ethereum_types::{H160, H256, U256};
+use jsonrpc_core::Result;
+use sc_client_api::BlockBackend;
+use sp_api::{Core, ProvideRuntimeApi};
+use sp_block_builder::BlockBuilder as BlockBuilderApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT},
+};
+
+use fc_rpc_core::{
+	types::{
+		BlockNumber, Bytes, DroppedTransactionCache, DroppedTransactionStatus, GasBreakdown,
+		InvalidTransactionReason, Precompile, ReceiptProof, TransactionValidation,
+	},
+	FrontierApi,
+};
+use fp_evm::InvalidEvmTransaction;
+use fp_rpc::EthereumRuntimeRPCApi;
+
+use crate::{
+	decode_raw_transaction, eth::receipt_build, ethereum_api_version, frontier_backend_client,
+	internal_err, overrides::legacy, EthereumTransaction,
+};
+
+/// Maximum number of accounts of `frontier_getBalances`.
+const MAX_BATCH_ACCOUNTS: usize = 10_000;
+/// Maximum number of accounts of `frontier_getCodes`, as a code takes up to 24KiB.
+const MAX_BATCH_CODES: usize = 128;
+
+/// Frontier API implementation.
+pub struct Frontier<B: BlockT, C> {
+	client: Arc<C>,
+	backend: Arc<dyn fc_api::Backend<B>>,
+	dropped_transactions: DroppedTransactionCache,
+	_marker: PhantomData<B>,
+}
+
+impl<B: BlockT, C> Frontier<B, C> {
+	pub fn new(
+		client: Arc<C>,
+		backend: Arc<dyn fc_api::Backend<B>>,
+		dropped_transactions: DroppedTransactionCache,
+	) -> Self {
+		Self {
+			client,
+			backend,
+			dropped_transactions,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<B, C> FrontierApi for Frontier<B, C>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: HeaderBackend<B> + BlockBackend<B> + ProvideRuntimeApi<B> + Send + Sync + 'static,
+	C::Api: BlockBuilderApi<B> + EthereumRuntimeRPCApi<B>,
+{
+	fn compute_create2_address(
+		&self,
+		deployer: H160,
+		salt: H256,
+		init_code_hash: H256,
+	) -> Result<H160> {
+		let id = BlockId::Hash(self.client.info().best_hash);
+		let api = self.client.runtime_api();
+
+		ethereum_api_version(self.client.as_ref(), &id, 6)?;
+
+		api.create2_address(&id, deployer, salt, init_code_hash)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))
+	}
+
+	fn validate_transaction(&self, bytes: Bytes) -> Result<TransactionValidation> {
+		let transaction = decode_raw_transaction(&bytes.0[..])?;
+
+		let id = BlockId::Hash(self.client.info().best_hash);
+		let api = self.client.runtime_api();
+
+		ethereum_api_version(self.client.as_ref(), &id, 8)?;
+
+		let result = api
+			.check_transaction(&id, transaction)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+		Ok(match result {
+			Ok(()) => TransactionValidation::valid(),
+			Err(err) => TransactionValidation::invalid(invalid_transaction_reason(err)),
+		})
+	}
+
+	fn transaction_status(&self, hash: H256) -> Result<Option<DroppedTransactionStatus>> {
+		let dropped_transactions = self
+			.dropped_transactions
+			.lock()
+			.map_err(|_| internal_err("dropped transactions lock is poisoned"))?;
+		Ok(dropped_transactions.get(&hash).cloned())
+	}
+
+	fn transaction_receipt_proof(&self, hash: H256) -> Result<Option<ReceiptProof>> {
+		let (ethereum_block_hash, index) = match frontier_backend_client::load_transactions::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			hash,
+			true,
+		)? {
+			Some((ethereum_block_hash, index)) => (ethereum_block_hash, index),
+			None => return Ok(None),
+		};
+		let id = match frontier_backend_client::load_hash::<B>(
+			self.backend.as_ref(),
+			ethereum_block_hash,
+		)? {
+			Some(id) => id,
+			None => return Ok(None),
+		};
+		let api = self.client.runtime_api();
+
+		ethereum_api_version(self.client.as_ref(), &id, 9)?;
+
+		let block = api
+			.current_block(&id)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+		let proof = api
+			.receipt_proof(&id, index)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+
+		Ok(match (block, proof) {
+			(Some(block), Some(proof)) => Some(ReceiptProof {
+				transaction_hash: hash,
+				transaction_index: U256::from(index),
+				block_hash: block.header.hash(),
+				block_number: block.header.number,
+				receipts_root: block.header.receipts_root,
+				proof: proof.into_iter().map(Bytes).collect(),
+			}),
+			_ => None,
+		})
+	}
+
+	fn gas_breakdown(&self, hash: H256) -> Result<Option<GasBreakdown>> {
+		let (ethereum_block_hash, index) = match frontier_backend_client::load_transactions::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			hash,
+			true,
+		)? {
+			Some((ethereum_block_hash, index)) => (ethereum_block_hash, index as usize),
+			None => return Ok(None),
+		};
+		let id = match frontier_backend_client::load_hash::<B>(
+			self.backend.as_ref(),
+			ethereum_block_hash,
+		)? {
+			Some(id) => id,
+			None => return Ok(None),
+		};
+		let api = self.client.runtime_api();
+
+		let api_version = ethereum_api_version(self.client.as_ref(), &id, 1)?;
+
+		let (block, receipts, statuses) = match legacy::current_all(&*api, &id, api_version)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+		{
+			(Some(block), Some(receipts), Some(statuses)) => (block, receipts, statuses),
+			_ => return Ok(None),
+		};
+		let transaction = match block.transactions.get(index) {
+			Some(transaction) => transaction,
+			None => return Ok(None),
+		};
+		let is_eip1559 = api_version >= 2;
+		let base_fee = if is_eip1559 {
+			Some(
+				api.gas_price(&id)
+					.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?,
+			)
+		} else {
+			None
+		};
+		let gas_used = receipt_build(&block, &statuses, &receipts, index, is_eip1559, base_fee)?
+			.gas_used
+			.unwrap_or_default();
+
+		// Older runtimes don't expose their EVM configuration, nor the refunds of a replay.
+		let (intrinsic_gas, refunded_gas) = if api_version >= 13 {
+			let intrinsic_gas = api
+				.intrinsic_gas(&id, transaction.clone())
+				.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+			let from = match statuses.get(index) {
+				Some(status) => status.from,
+				None => return Ok(None),
+			};
+			(
+				intrinsic_gas,
+				Some(self.refunded_gas(&id, index, transaction, from)?),
+			)
+		} else {
+			(london_intrinsic_gas(transaction)?, None)
+		};
+		let intrinsic_gas = U256::from(intrinsic_gas);
+
+		Ok(Some(GasBreakdown {
+			transaction_hash: hash,
+			gas_limit: match transaction {
+				EthereumTransaction::Legacy(t) => t.gas_limit,
+				EthereumTransaction::EIP2930(t) => t.gas_limit,
+				EthereumTransaction::EIP1559(t) => t.gas_limit,
+			},
+			intrinsic_gas,
+			execution_gas: gas_used.saturating_sub(intrinsic_gas),
+			refunded_gas,
+			gas_used,
+		}))
+	}
+
+	fn list_precompiles(&self) -> Result<Vec<Precompile>> {
+		let id = BlockId::Hash(self.client.info().best_hash);
+		let api = self.client.runtime_api();
+
+		ethereum_api_version(self.client.as_ref(), &id, 10)?;
+
+		let precompiles = api
+			.precompiles(&id)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+		Ok(precompiles
+			.into_iter()
+			.map(|precompile| Precompile {
+				address: precompile.address,
+				name: String::from_utf8_lossy(&precompile.name).into_owned(),
+				interface_hash: precompile.interface_hash,
+			})
+			.collect())
+	}
+
+	fn balances(&self, addresses: Vec<H160>, number: Option<BlockNumber>) -> Result<Vec<U256>> {
+		check_batch_size(&addresses, MAX_BATCH_ACCOUNTS)?;
+		let id = self.batch_block_id(number)?;
+		let api = self.client.runtime_api();
+
+		// Runtimes without the batched call are called once per account.
+		let accounts = if ethereum_api_version(self.client.as_ref(), &id, 1)? >= 13 {
+			api.accounts_basic(&id, addresses)
+		} else {
+			addresses
+				.into_iter()
+				.map(|address| api.account_basic(&id, address))
+				.collect()
+		}
+		.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+		Ok(accounts
+			.into_iter()
+			.map(|account| account.balance)
+			.collect())
+	}
+
+	fn codes(&self, addresses: Vec<H160>, number: Option<BlockNumber>) -> Result<Vec<Bytes>> {
+		check_batch_size(&addresses, MAX_BATCH_CODES)?;
+		let id = self.batch_block_id(number)?;
+		let api = self.client.runtime_api();
+
+		// Runtimes without the batched call are called once per account.
+		let codes = if ethereum_api_version(self.client.as_ref(), &id, 1)? >= 13 {
+			api.accounts_code_at(&id, addresses)
+		} else {
+			addresses
+				.into_iter()
+				.map(|address| api.account_code_at(&id, address))
+				.collect()
+		}
+		.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+		Ok(codes.into_iter().map(Bytes).collect())
+	}
+}
+
+impl<B, C> Frontier<B, C>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: HeaderBackend<B> + Send + Sync + 'static,
+{
+	/// Block of a batched account query, the best block for `pending`. Unknown blocks are an
+	/// error, rather than reporting empty accounts.
+	fn batch_block_id(&self, number: Option<BlockNumber>) -> Result<BlockId<B>> {
+		let number = match number {
+			Some(BlockNumber::Pending) => Some(BlockNumber::Latest),
+			number => number,
+		};
+		frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			number,
+		)?
+		.ok_or_else(|| internal_err("header not found"))
+	}
+}
+
+impl<B, C> Frontier<B, C>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: HeaderBackend<B> + BlockBackend<B> + ProvideRuntimeApi<B> + Send + Sync + 'static,
+	C::Api: BlockBuilderApi<B> + EthereumRuntimeRPCApi<B>,
+{
+	/// Gas refunded to `transaction` of `from`, the Ethereum transaction at `index` in the block
+	/// `id`, dry run on top of the extrinsics of the block before the one applying it. The
+	/// transactions before it in the same extrinsic, eg. of a batch, aren't replayed.
+	fn refunded_gas(
+		&self,
+		id: &BlockId<B>,
+		index: usize,
+		transaction: &EthereumTransaction,
+		from: H160,
+	) -> Result<U256> {
+		let access_err = |err| internal_err(format!("runtime error: {:?}", err));
+		let header = self
+			.client
+			.header(*id)
+			.map_err(|err| internal_err(format!("{:?}", err)))?
+			.ok_or_else(|| internal_err("block not found"))?;
+		let extrinsics = self
+			.client
+			.block_body(id)
+			.map_err(|err| internal_err(format!("{:?}", err)))?
+			.ok_or_else(|| internal_err("block body not found"))?;
+		let parent = BlockId::Hash(*header.parent_hash());
+
+		let api = self.client.runtime_api();
+		api.initialize_block(&parent, &header).map_err(access_err)?;
+		let mut transactions = 0;
+		for xt in extrinsics {
+			transactions += api
+				.extrinsic_filter(&parent, vec![xt.clone()])
+				.map_err(access_err)?
+				.len();
+			if transactions > index {
+				break;
+			}
+			api.apply_extrinsic(&parent, xt)
+				.map_err(access_err)?
+				.map_err(|err| internal_err(format!("failed to replay extrinsic: {:?}", err)))?;
+		}
+
+		let (to, input, value, gas_limit, max_fee_per_gas, max_priority_fee_per_gas, access_list) =
+			transaction_call(transaction);
+		let info = api
+			.dry_run(
+				&parent,
+				from,
+				to,
+				input,
+				value,
+				gas_limit,
+				max_fee_per_gas,
+				max_priority_fee_per_gas,
+				None,
+				false,
+				Some(access_list),
+			)
+			.map_err(access_err)?
+			.map_err(|err| internal_err(format!("failed to replay transaction: {:?}", err)))?;
+		Ok(info.refunded_gas)
+	}
+}
+
+fn check_batch_size(addresses: &[H160], max: usize) -> Result<()> {
+	if addresses.len() > max {
+		return Err(jsonrpc_core::Error::invalid_params(format!(
+			"more than {} accounts",
+			max
+		)));
+	}
+	Ok(())
+}
+
+/// Fields of a transaction executed by `dry_run`: its target, input, value, gas limit, fees
+/// and access list.
+type TransactionCall = (
+	Option<H160>,
+	Vec<u8>,
+	U256,
+	U256,
+	Option<U256>,
+	Option<U256>,
+	Vec<(H160, Vec<H256>)>,
+);
+
+fn transaction_call(transaction: &EthereumTransaction) -> TransactionCall {
+	let access_list = |access_list: &[ethereum::AccessListItem]| {
+		access_list
+			.iter()
+			.map(|item| (item.address, item.storage_keys.clone()))
+			.collect()
+	};
+	let to = |action: ethereum::TransactionAction| match action {
+		ethereum::TransactionAction::Call(to) => Some(to),
+		ethereum::TransactionAction::Create => None,
+	};
+	match transaction {
+		EthereumTransaction::Legacy(t) => (
+			to(t.action),
+			t.input.clone(),
+			t.value,
+			t.gas_limit,
+			Some(t.gas_price),
+			None,
+			Vec::new(),
+		),
+		EthereumTransaction::EIP2930(t) => (
+			to(t.action),
+			t.input.clone(),
+			t.value,
+			t.gas_limit,
+			Some(t.gas_price),
+			None,
+			access_list(&t.access_list),
+		),
+		EthereumTransaction::EIP1559(t) => (
+			to(t.action),
+			t.input.clone(),
+			t.value,
+			t.gas_limit,
+			Some(t.max_fee_per_gas),
+			Some(t.max_priority_fee_per_gas),
+			access_list(&t.access_list),
+		),
+	}
+}
+
+/// Intrinsic gas of a transaction, with the London configuration of the EVM, for the runtimes
+/// which don't expose theirs.
+fn london_intrinsic_gas(transaction: &EthereumTransaction) -> Result<u64> {
+	let (to, input, _, _, _, _, access_list) = transaction_call(transaction);
+	fp_evm::intrinsic_gas(&evm::Config::london(), to.is_none(), &input, &access_list)
+		.ok_or_else(|| internal_err("intrinsic gas overflow"))
+}
+
+pub(crate) fn invalid_transaction_reason(error: InvalidEvmTransaction) -> InvalidTransactionReason {
+	match error {
+		InvalidEvmTransaction::GasLimitTooLow => InvalidTransactionReason::GasLimitTooLow,
+		InvalidEvmTransaction::GasLimitTooHigh => InvalidTransactionReason::GasLimitTooHigh,
+		InvalidEvmTransaction::InvalidChainId => InvalidTransactionReason::InvalidChainId,
+		InvalidEvmTransaction::InvalidSignature => InvalidTransactionReason::InvalidSignature,
+		InvalidEvmTransaction::InvalidPaymentInput => InvalidTransactionReason::InvalidPaymentInput,
+		InvalidEvmTransaction::PriorityFeeTooHigh => InvalidTransactionReason::PriorityFeeTooHigh,
+		InvalidEvmTransaction::GasPriceTooLow => InvalidTransactionReason::GasPriceTooLow,
+		InvalidEvmTransaction::BalanceTooLow => InvalidTransactionReason::BalanceTooLow,
+		InvalidEvmTransaction::TxNonceTooLow => InvalidTransactionReason::NonceTooLow,
+		InvalidEvmTransaction::TxNonceTooHigh => InvalidTransactionReason::NonceTooHigh,
+		InvalidEvmTransaction::TooManyFutureTransactions => {
+			InvalidTransactionReason::TooManyFutureTransactions
+		}
+	}
+}
@@ -25,26 +25,49 @@
 	clippy::new_without_default
 )]
 
+mod bundler;
+mod debug;
+mod engine;
 mod eth;
 mod eth_pubsub;
+mod extended_block;
+mod frontier;
+mod metadata;
 mod net;
 mod overrides;
 mod signer;
+mod state_export;
+mod stats;
+mod trace;
+mod transaction_converter;
 mod web3;
 
 pub use self::{
-	eth::{Eth, EthBlockDataCacheTask, EthFilter, EthTask},
-	eth_pubsub::{EthPubSub, HexEncodedIdProvider},
+	bundler::{Bundler, BundlerConfig},
+	debug::Debug,
+	engine::Engine,
+	eth::{Eth, EthBlockDataCacheTask, EthFilter, EthTask, ExecutionPool},
+	eth_pubsub::{EthPubSub, EthPubSubLimits, HexEncodedIdProvider},
+	extended_block::ExtendedBlocks,
+	frontier::Frontier,
+	metadata::Metadata,
 	net::Net,
 	overrides::{
 		OverrideHandle, RuntimeApiStorageOverride, SchemaV1Override, SchemaV2Override,
 		SchemaV3Override, StorageOverride,
 	},
-	signer::{EthDevSigner, EthSigner},
+	signer::{EthDevSigner, EthKeystoreSigner, EthSigner, ETH_KEY_TYPE},
+	state_export::StateExport,
+	stats::Stats,
+	trace::{Trace, TraceLimits},
+	transaction_converter::TransactionConverters,
 	web3::Web3,
 };
 pub use ethereum::TransactionV2 as EthereumTransaction;
-pub use fc_rpc_core::{EthApi, EthFilterApi, EthPubSubApi, NetApi, Web3Api};
+pub use fc_rpc_core::{
+	BundlerApi, DebugApi, EngineApi, EthApi, EthFilterApi, EthPubSubApi, ExtendedBlockApi,
+	FrontierApi, MetadataApi, NetApi, StateExportApi, StatsApi, TraceApi, Web3Api,
+};
 
 pub mod frontier_backend_client {
 	use super::internal_err;
@@ -66,7 +89,7 @@ pub mod frontier_backend_client {
 
 	pub fn native_block_id<B: BlockT, C>(
 		client: &C,
-		backend: &fc_db::Backend<B>,
+		backend: &dyn fc_api::Backend<B>,
 		number: Option<BlockNumber>,
 	) -> RpcResult<Option<BlockId<B>>>
 	where
@@ -79,18 +102,20 @@ pub mod frontier_backend_client {
 			BlockNumber::Latest => Some(BlockId::Hash(client.info().best_hash)),
 			BlockNumber::Earliest => Some(BlockId::Number(Zero::zero())),
 			BlockNumber::Pending => None,
+			BlockNumber::Finalized | BlockNumber::Safe => {
+				Some(BlockId::Hash(client.info().finalized_hash))
+			}
 		})
 	}
 
 	pub fn load_hash<B: BlockT>(
-		backend: &fc_db::Backend<B>,
+		backend: &dyn fc_api::Backend<B>,
 		hash: H256,
 	) -> RpcResult<Option<BlockId<B>>>
 	where
 		B: BlockT<Hash = H256> + Send + Sync + 'static,
 	{
 		let substrate_hash = backend
-			.mapping()
 			.block_hash(&hash)
 			.map_err(|err| internal_err(format!("fetch aux store failed: {:?}", err)))?;
 
@@ -101,27 +126,25 @@ pub mod frontier_backend_client {
 	}
 
 	pub fn load_cached_schema<B: BlockT>(
-		backend: &fc_db::Backend<B>,
+		backend: &dyn fc_api::Backend<B>,
 	) -> RpcResult<Option<Vec<(EthereumStorageSchema, H256)>>>
 	where
 		B: BlockT<Hash = H256> + Send + Sync + 'static,
 	{
 		let cache = backend
-			.meta()
 			.ethereum_schema()
 			.map_err(|err| internal_err(format!("fetch backend failed: {:?}", err)))?;
 		Ok(cache)
 	}
 
 	pub fn write_cached_schema<B: BlockT>(
-		backend: &fc_db::Backend<B>,
+		backend: &dyn fc_api::Backend<B>,
 		new_cache: Vec<(EthereumStorageSchema, H256)>,
 	) -> RpcResult<()>
 	where
 		B: BlockT<Hash = H256> + Send + Sync + 'static,
 	{
 		backend
-			.meta()
 			.write_ethereum_schema(new_cache)
 			.map_err(|err| internal_err(format!("write backend failed: {:?}", err)))?;
 		Ok(())
@@ -160,7 +183,7 @@ pub mod frontier_backend_client {
 
 	pub fn load_transactions<B: BlockT, C>(
 		client: &C,
-		backend: &fc_db::Backend<B>,
+		backend: &dyn fc_api::Backend<B>,
 		transaction_hash: H256,
 		only_canonical: bool,
 	) -> RpcResult<Option<(H256, u32)>>
@@ -169,26 +192,70 @@ pub mod frontier_backend_client {
 		C: HeaderBackend<B> + Send + Sync + 'static,
 	{
 		let transaction_metadata = backend
-			.mapping()
 			.transaction_metadata(&transaction_hash)
 			.map_err(|err| internal_err(format!("fetch aux store failed: {:?}", err)))?;
 
-		transaction_metadata
+		let transaction = transaction_metadata
 			.iter()
 			.find(|meta| is_canon::<B, C>(client, meta.block_hash))
 			.map_or_else(
 				|| {
 					if !only_canonical && transaction_metadata.len() > 0 {
-						Ok(Some((
+						Some((
 							transaction_metadata[0].ethereum_block_hash,
 							transaction_metadata[0].ethereum_index,
-						)))
+						))
 					} else {
-						Ok(None)
+						None
 					}
 				},
-				|meta| Ok(Some((meta.ethereum_block_hash, meta.ethereum_index))),
-			)
+				|meta| Some((meta.ethereum_block_hash, meta.ethereum_index)),
+			);
+
+		match (transaction, backend.transaction_indexing()) {
+			(None, fc_api::TransactionIndexing::Lazy { depth }) => {
+				scan_transactions::<B, C>(client, transaction_hash, depth)
+			}
+			(transaction, _) => Ok(transaction),
+		}
+	}
+
+	/// Looks for a transaction in the `depth` latest canonical blocks, using the Ethereum
+	/// hashes of their digests.
+	fn scan_transactions<B: BlockT, C>(
+		client: &C,
+		transaction_hash: H256,
+		depth: u32,
+	) -> RpcResult<Option<(H256, u32)>>
+	where
+		B: BlockT<Hash = H256> + Send + Sync + 'static,
+		C: HeaderBackend<B> + Send + Sync + 'static,
+	{
+		let mut hash = client.info().best_hash;
+		for _ in 0..depth {
+			let header = match client
+				.header(BlockId::Hash(hash))
+				.map_err(|err| internal_err(format!("fetch header failed: {:?}", err)))?
+			{
+				Some(header) => header,
+				None => break,
+			};
+			if let Ok(log) = fp_consensus::find_log(header.digest()) {
+				let hashes = log.into_hashes();
+				if let Some(index) = hashes
+					.transaction_hashes
+					.iter()
+					.position(|hash| hash == &transaction_hash)
+				{
+					return Ok(Some((hashes.block_hash, index as u32)));
+				}
+			}
+			if header.number().is_zero() {
+				break;
+			}
+			hash = *header.parent_hash();
+		}
+		Ok(None)
 	}
 }
 
@@ -200,6 +267,77 @@ pub fn internal_err<T: ToString>(message: T) -> jsonrpc_core::Error {
 	}
 }
 
+/// Error of a method needing a version of `EthereumRuntimeRPCApi` which the runtime of the
+/// queried block doesn't implement, e.g. for blocks older than the runtime upgrade adding it.
+pub fn runtime_upgrade_err(required: u32, version: Option<u32>) -> jsonrpc_core::Error {
+	jsonrpc_core::Error {
+		code: jsonrpc_core::ErrorCode::MethodNotFound,
+		message: String::from("method unavailable before runtime upgrade"),
+		data: Some(serde_json::json!({
+			"requiredVersion": required,
+			"runtimeVersion": version,
+		})),
+	}
+}
+
+/// Returns the version of `EthereumRuntimeRPCApi` implemented by the runtime at `id`, failing
+/// with a [`runtime_upgrade_err`] if it is older than `required`.
+///
+/// Runtime api calls must select the signature of this version, as calls of a signature the
+/// runtime doesn't implement fail to decode.
+pub fn ethereum_api_version<B, C>(
+	client: &C,
+	id: &sp_runtime::generic::BlockId<B>,
+	required: u32,
+) -> Result<u32, jsonrpc_core::Error>
+where
+	B: sp_runtime::traits::Block,
+	C: sp_api::ProvideRuntimeApi<B>,
+	C::Api: fp_rpc::EthereumRuntimeRPCApi<B>,
+{
+	use sp_api::ApiExt;
+
+	match client
+		.runtime_api()
+		.api_version::<dyn fp_rpc::EthereumRuntimeRPCApi<B>>(id)
+	{
+		Ok(Some(version)) if version >= required => Ok(version),
+		Ok(version) => Err(runtime_upgrade_err(required, version)),
+		Err(err) => Err(internal_err(format!(
+			"failed to retrieve Runtime Api version: {:?}",
+			err
+		))),
+	}
+}
+
+/// Decode a signed transaction, in its raw network encoding.
+pub fn decode_raw_transaction(bytes: &[u8]) -> Result<EthereumTransaction, jsonrpc_core::Error> {
+	let first = match bytes.get(0) {
+		Some(first) => first,
+		None => return Err(internal_err("transaction data is empty")),
+	};
+	if first > &0x7f {
+		// Legacy transaction. Decode and wrap in envelope.
+		rlp::decode::<ethereum::TransactionV0>(bytes)
+			.map(EthereumTransaction::Legacy)
+			.map_err(|_| internal_err("decode transaction failed"))
+	} else if first == &fp_rpc::DEADLINE_TRANSACTION_TYPE {
+		// Frontier deadline transaction, submitted as the EIP-1559 transaction it wraps.
+		fp_rpc::DeadlineTransaction::decode_envelope(bytes)
+			.map(|transaction| EthereumTransaction::EIP1559(transaction.transaction))
+			.map_err(|_| internal_err("decode transaction failed"))
+	} else {
+		// Typed Transaction.
+		// `ethereum` crate decode implementation for `TransactionV2` expects a valid rlp input,
+		// and EIP-1559 breaks that assumption by prepending a version byte.
+		// We re-encode the payload input to get a valid rlp, and the decode implementation will strip
+		// them to check the transaction version byte.
+		let extend = rlp::encode(&bytes);
+		rlp::decode::<EthereumTransaction>(&extend[..])
+			.map_err(|_| internal_err("decode transaction failed"))
+	}
+}
+
 pub fn public_key(transaction: &EthereumTransaction) -> Result<[u8; 64], sp_io::EcdsaVerifyError> {
 	let mut sig = [0u8; 65];
 	let mut msg = [0u8; 32];
@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use ethereum_types::{H160, H256};
+use jsonrpc_core::Result;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+
+use fc_rpc_core::{
+	types::{BlockNumber, Bytes, ContractMetadata},
+	MetadataApi,
+};
+use fp_rpc::ContractMetadataRuntimeApi;
+
+use crate::{frontier_backend_client, internal_err};
+
+/// Contract metadata API implementation.
+pub struct Metadata<B: BlockT, C> {
+	client: Arc<C>,
+	backend: Arc<dyn fc_api::Backend<B>>,
+	_marker: PhantomData<B>,
+}
+
+impl<B: BlockT, C> Metadata<B, C> {
+	pub fn new(client: Arc<C>, backend: Arc<dyn fc_api::Backend<B>>) -> Self {
+		Self {
+			client,
+			backend,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<B, C> MetadataApi for Metadata<B, C>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: HeaderBackend<B> + ProvideRuntimeApi<B> + Send + Sync + 'static,
+	C::Api: ContractMetadataRuntimeApi<B>,
+{
+	fn contract_metadata(
+		&self,
+		address: H160,
+		number: Option<BlockNumber>,
+	) -> Result<Option<ContractMetadata>> {
+		let id = match frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			number,
+		)? {
+			Some(id) => id,
+			None => return Ok(None),
+		};
+
+		let metadata = self
+			.client
+			.runtime_api()
+			.contract_metadata(&id, address)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+		Ok(metadata.map(|metadata| ContractMetadata {
+			deployer: metadata.deployer,
+			metadata: Bytes(metadata.metadata),
+		}))
+	}
+}
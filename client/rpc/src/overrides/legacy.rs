@@ -0,0 +1,147 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2017-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Converters of the Ethereum data of older runtimes to the current types.
+//!
+//! Blocks produced before a Frontier upgrade keep the encodings of their runtime, so their
+//! data is read with the signatures of the `EthereumRuntimeRPCApi` version their runtime
+//! implements, or from the storage with the types of their storage schema, and converted.
+
+use ethereum::{BlockV2 as EthereumBlock, ReceiptV0, ReceiptV3};
+use fp_rpc::{EthereumRuntimeRPCApi, TransactionStatus};
+use sp_api::{ApiError, BlockId};
+use sp_runtime::traits::Block as BlockT;
+
+/// Converts receipts predating typed receipts, of runtimes before version 4 of
+/// `EthereumRuntimeRPCApi` and of the storage schemas before V3.
+pub(crate) fn receipts_from_v0(receipts: Vec<ReceiptV0>) -> Vec<ReceiptV3> {
+	receipts
+		.into_iter()
+		.map(|receipt| {
+			ReceiptV3::Legacy(ethereum::EIP658ReceiptData {
+				// The EIP-658 status code is stored as the state root.
+				status_code: receipt.state_root.to_low_u64_be() as u8,
+				used_gas: receipt.used_gas,
+				logs_bloom: receipt.logs_bloom,
+				logs: receipt.logs,
+			})
+		})
+		.collect()
+}
+
+/// Returns the current block, with the signature of the given runtime api version.
+pub(crate) fn current_block<B, A>(
+	api: &A,
+	id: &BlockId<B>,
+	api_version: u32,
+) -> Result<Option<EthereumBlock>, ApiError>
+where
+	B: BlockT,
+	A: EthereumRuntimeRPCApi<B> + ?Sized,
+{
+	if api_version < 2 {
+		#[allow(deprecated)]
+		let block = api.current_block_before_version_2(id)?;
+		Ok(block.map(Into::into))
+	} else {
+		api.current_block(id)
+	}
+}
+
+/// Returns the current receipts, with the signature of the given runtime api version.
+pub(crate) fn current_receipts<B, A>(
+	api: &A,
+	id: &BlockId<B>,
+	api_version: u32,
+) -> Result<Option<Vec<ReceiptV3>>, ApiError>
+where
+	B: BlockT,
+	A: EthereumRuntimeRPCApi<B> + ?Sized,
+{
+	if api_version < 4 {
+		#[allow(deprecated)]
+		let receipts = api.current_receipts_before_version_4(id)?;
+		Ok(receipts.map(receipts_from_v0))
+	} else {
+		api.current_receipts(id)
+	}
+}
+
+/// Returns the current block, receipts and transaction statuses, with the signature of the
+/// given runtime api version.
+pub(crate) fn current_all<B, A>(
+	api: &A,
+	id: &BlockId<B>,
+	api_version: u32,
+) -> Result<
+	(
+		Option<EthereumBlock>,
+		Option<Vec<ReceiptV3>>,
+		Option<Vec<TransactionStatus>>,
+	),
+	ApiError,
+>
+where
+	B: BlockT,
+	A: EthereumRuntimeRPCApi<B> + ?Sized,
+{
+	if api_version < 2 {
+		#[allow(deprecated)]
+		let (block, receipts, statuses) = api.current_all_before_version_2(id)?;
+		Ok((
+			block.map(Into::into),
+			receipts.map(receipts_from_v0),
+			statuses,
+		))
+	} else if api_version < 4 {
+		#[allow(deprecated)]
+		let (block, receipts, statuses) = api.current_all_before_version_4(id)?;
+		Ok((block, receipts.map(receipts_from_v0), statuses))
+	} else {
+		api.current_all(id)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethereum_types::{Bloom, H256, U256};
+
+	#[test]
+	fn converts_the_status_code_of_receipts_v0() {
+		let receipt = |state_root| ReceiptV0 {
+			state_root,
+			used_gas: U256::from(21_000),
+			logs_bloom: Bloom::default(),
+			logs: vec![],
+		};
+		let receipts = receipts_from_v0(vec![
+			receipt(H256::from_low_u64_be(1)),
+			receipt(H256::zero()),
+		]);
+
+		match &receipts[..] {
+			[ReceiptV3::Legacy(success), ReceiptV3::Legacy(failure)] => {
+				assert_eq!(success.status_code, 1);
+				assert_eq!(failure.status_code, 0);
+				assert_eq!(success.used_gas, U256::from(21_000));
+			}
+			_ => panic!("receipts should be legacy receipts"),
+		}
+	}
+}
@@ -18,15 +18,22 @@
 
 use std::collections::BTreeMap;
 
+use codec::Decode;
 use ethereum::BlockV2 as EthereumBlock;
 use ethereum_types::{H160, H256, U256};
 use fp_rpc::{EthereumRuntimeRPCApi, TransactionStatus};
-use fp_storage::EthereumStorageSchema;
+use fp_storage::{EthereumStorageSchema, EVM_ACCOUNT_STORAGE_CHILD_PREFIX};
+use sc_client_api::backend::{Backend, StateBackend, StorageProvider};
 use sp_api::{ApiExt, BlockId, ProvideRuntimeApi};
 use sp_io::hashing::{blake2_128, twox_128};
-use sp_runtime::{traits::Block as BlockT, Permill};
+use sp_runtime::{
+	traits::{BlakeTwo256, Block as BlockT},
+	Permill,
+};
+use sp_storage::{ChildInfo, StorageKey};
 use std::{marker::PhantomData, sync::Arc};
 
+pub(crate) mod legacy;
 mod schema_v1_override;
 mod schema_v2_override;
 mod schema_v3_override;
@@ -48,7 +55,7 @@ pub struct OverrideHandle<Block: BlockT> {
 pub trait StorageOverride<Block: BlockT> {
 	/// For a given account address, returns pallet_evm::AccountCodes.
 	fn account_code_at(&self, block: &BlockId<Block>, address: H160) -> Option<Vec<u8>>;
-	/// For a given account address and index, returns pallet_evm::AccountStorages.
+	/// For a given account address and index, returns the storage slot of the account.
 	fn storage_at(&self, block: &BlockId<Block>, address: H160, index: U256) -> Option<H256>;
 	/// Return the current block.
 	fn current_block(&self, block: &BlockId<Block>) -> Option<EthereumBlock>;
@@ -67,16 +74,52 @@ pub trait StorageOverride<Block: BlockT> {
 	fn is_eip1559(&self, block: &BlockId<Block>) -> bool;
 }
 
-fn storage_prefix_build(module: &[u8], storage: &[u8]) -> Vec<u8> {
+pub(crate) fn storage_prefix_build(module: &[u8], storage: &[u8]) -> Vec<u8> {
 	[twox_128(module), twox_128(storage)].concat().to_vec()
 }
 
-fn blake2_128_extend(bytes: &[u8]) -> Vec<u8> {
+pub(crate) fn blake2_128_extend(bytes: &[u8]) -> Vec<u8> {
 	let mut ext: Vec<u8> = blake2_128(bytes).to_vec();
 	ext.extend_from_slice(bytes);
 	ext
 }
 
+/// Child trie holding the storage of an EVM account, keyed by the storage index.
+pub(crate) fn account_storage_child_info(address: &H160) -> ChildInfo {
+	ChildInfo::new_default(&[EVM_ACCOUNT_STORAGE_CHILD_PREFIX, address.as_bytes()].concat())
+}
+
+/// For a given account address and index, returns the storage slot from the child trie of the
+/// account, falling back to the legacy pallet_evm::AccountStorages of a chain migrating to the
+/// child tries.
+pub(crate) fn account_storage_at<B, C, BE>(
+	client: &C,
+	block: &BlockId<B>,
+	address: H160,
+	index: U256,
+) -> Option<H256>
+where
+	B: BlockT,
+	C: StorageProvider<B, BE>,
+	BE: Backend<B>,
+	BE::State: StateBackend<BlakeTwo256>,
+{
+	let tmp: &mut [u8; 32] = &mut [0; 32];
+	index.to_big_endian(tmp);
+
+	let child_info = account_storage_child_info(&address);
+	let data = match client.child_storage(block, &child_info, &StorageKey(tmp.to_vec())) {
+		Ok(Some(data)) => Some(data),
+		_ => {
+			let mut key: Vec<u8> = storage_prefix_build(b"EVM", b"AccountStorages");
+			key.extend(blake2_128_extend(address.as_bytes()));
+			key.extend(blake2_128_extend(tmp));
+			client.storage(block, &StorageKey(key)).ok().flatten()
+		}
+	};
+	data.and_then(|data| Decode::decode(&mut &data.0[..]).ok())
+}
+
 /// A wrapper type for the Runtime API. This type implements `StorageOverride`, so it can be used
 /// when calling the runtime API is desired but a `dyn StorageOverride` is required.
 pub struct RuntimeApiStorageOverride<B: BlockT, C> {
@@ -107,7 +150,7 @@ where
 			.ok()
 	}
 
-	/// For a given account address and index, returns pallet_evm::AccountStorages.
+	/// For a given account address and index, returns the storage slot of the account.
 	fn storage_at(&self, block: &BlockId<Block>, address: H160, index: U256) -> Option<H256> {
 		self.client
 			.runtime_api()
@@ -119,52 +162,20 @@ where
 	fn current_block(&self, block: &BlockId<Block>) -> Option<ethereum::BlockV2> {
 		let api = self.client.runtime_api();
 
-		let api_version = if let Ok(Some(api_version)) =
-			api.api_version::<dyn EthereumRuntimeRPCApi<Block>>(block)
-		{
-			api_version
-		} else {
-			return None;
-		};
-		if api_version == 1 {
-			#[allow(deprecated)]
-			let old_block = api.current_block_before_version_2(block).ok()?;
-			old_block.map(|block| block.into())
-		} else {
-			api.current_block(block).ok()?
-		}
+		let api_version = api
+			.api_version::<dyn EthereumRuntimeRPCApi<Block>>(block)
+			.ok()??;
+		legacy::current_block(&*api, block, api_version).ok()?
 	}
 
 	/// Return the current receipt.
 	fn current_receipts(&self, block: &BlockId<Block>) -> Option<Vec<ethereum::ReceiptV3>> {
 		let api = self.client.runtime_api();
 
-		let api_version = if let Ok(Some(api_version)) =
-			api.api_version::<dyn EthereumRuntimeRPCApi<Block>>(block)
-		{
-			api_version
-		} else {
-			return None;
-		};
-		if api_version < 4 {
-			#[allow(deprecated)]
-			let old_receipts = api.current_receipts_before_version_4(block).ok()?;
-			old_receipts.map(|receipts| {
-				receipts
-					.into_iter()
-					.map(|r| {
-						ethereum::ReceiptV3::Legacy(ethereum::EIP658ReceiptData {
-							status_code: r.state_root.to_low_u64_be() as u8,
-							used_gas: r.used_gas,
-							logs_bloom: r.logs_bloom,
-							logs: r.logs,
-						})
-					})
-					.collect()
-			})
-		} else {
-			self.client.runtime_api().current_receipts(block).ok()?
-		}
+		let api_version = api
+			.api_version::<dyn EthereumRuntimeRPCApi<Block>>(block)
+			.ok()??;
+		legacy::current_receipts(&*api, block, api_version).ok()?
 	}
 
 	/// Return the current transaction status.
@@ -31,7 +31,7 @@ use sp_storage::StorageKey;
 
 use fp_rpc::TransactionStatus;
 
-use super::{blake2_128_extend, storage_prefix_build, StorageOverride};
+use super::{account_storage_at, blake2_128_extend, legacy, storage_prefix_build, StorageOverride};
 
 /// An override for runtimes that use Schema V2
 pub struct SchemaV2Override<B: BlockT, C, BE> {
@@ -79,16 +79,9 @@ where
 		self.query_storage::<Vec<u8>>(block, &StorageKey(key))
 	}
 
-	/// For a given account address and index, returns pallet_evm::AccountStorages.
+	/// For a given account address and index, returns the storage slot of the account.
 	fn storage_at(&self, block: &BlockId<B>, address: H160, index: U256) -> Option<H256> {
-		let tmp: &mut [u8; 32] = &mut [0; 32];
-		index.to_big_endian(tmp);
-
-		let mut key: Vec<u8> = storage_prefix_build(b"EVM", b"AccountStorages");
-		key.extend(blake2_128_extend(address.as_bytes()));
-		key.extend(blake2_128_extend(tmp));
-
-		self.query_storage::<H256>(block, &StorageKey(key))
+		account_storage_at(self.client.as_ref(), block, address, index)
 	}
 
 	/// Return the current block.
@@ -105,19 +98,7 @@ where
 			block,
 			&StorageKey(storage_prefix_build(b"Ethereum", b"CurrentReceipts")),
 		)
-		.map(|receipts| {
-			receipts
-				.into_iter()
-				.map(|r| {
-					ethereum::ReceiptV3::Legacy(ethereum::EIP658ReceiptData {
-						status_code: r.state_root.to_low_u64_be() as u8,
-						used_gas: r.used_gas,
-						logs_bloom: r.logs_bloom,
-						logs: r.logs,
-					})
-				})
-				.collect()
-		})
+		.map(legacy::receipts_from_v0)
 	}
 
 	/// Return the current transaction status.
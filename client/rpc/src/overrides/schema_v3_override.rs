@@ -31,7 +31,7 @@ use sp_storage::StorageKey;
 
 use fp_rpc::TransactionStatus;
 
-use super::{blake2_128_extend, storage_prefix_build, StorageOverride};
+use super::{account_storage_at, blake2_128_extend, storage_prefix_build, StorageOverride};
 
 /// An override for runtimes that use Schema V3
 pub struct SchemaV3Override<B: BlockT, C, BE> {
@@ -72,23 +72,25 @@ where
 	BE: Backend<B> + 'static,
 	BE::State: StateBackend<BlakeTwo256>,
 {
-	/// For a given account address, returns pallet_evm::AccountCodes.
+	/// For a given account address, returns the code from pallet_evm::AccountCodeByHash,
+	/// falling back to the legacy pallet_evm::AccountCodes.
 	fn account_code_at(&self, block: &BlockId<B>, address: H160) -> Option<Vec<u8>> {
+		let mut key: Vec<u8> = storage_prefix_build(b"EVM", b"AccountCodeHash");
+		key.extend(blake2_128_extend(address.as_bytes()));
+		if let Some(code_hash) = self.query_storage::<H256>(block, &StorageKey(key)) {
+			let mut key: Vec<u8> = storage_prefix_build(b"EVM", b"AccountCodeByHash");
+			key.extend(code_hash.as_bytes());
+			return self.query_storage::<Vec<u8>>(block, &StorageKey(key));
+		}
+
 		let mut key: Vec<u8> = storage_prefix_build(b"EVM", b"AccountCodes");
 		key.extend(blake2_128_extend(address.as_bytes()));
 		self.query_storage::<Vec<u8>>(block, &StorageKey(key))
 	}
 
-	/// For a given account address and index, returns pallet_evm::AccountStorages.
+	/// For a given account address and index, returns the storage slot of the account.
 	fn storage_at(&self, block: &BlockId<B>, address: H160, index: U256) -> Option<H256> {
-		let tmp: &mut [u8; 32] = &mut [0; 32];
-		index.to_big_endian(tmp);
-
-		let mut key: Vec<u8> = storage_prefix_build(b"EVM", b"AccountStorages");
-		key.extend(blake2_128_extend(address.as_bytes()));
-		key.extend(blake2_128_extend(tmp));
-
-		self.query_storage::<H256>(block, &StorageKey(key))
+		account_storage_at(self.client.as_ref(), block, address, index)
 	}
 
 	/// Return the current block.
@@ -16,15 +16,21 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use std::sync::Arc;
+
 use ethereum::TransactionV2 as EthereumTransaction;
 use ethereum_types::{H160, H256};
 use jsonrpc_core::Error;
 
 use fc_rpc_core::types::TransactionMessage;
-use sp_core::hashing::keccak_256;
+use sp_core::{crypto::KeyTypeId, ecdsa, hashing::keccak_256};
+use sp_keystore::{SyncCryptoStore, SyncCryptoStorePtr};
 
 use crate::internal_err;
 
+/// Key type of the ECDSA keys of the node keystore used as Ethereum accounts.
+pub const ETH_KEY_TYPE: KeyTypeId = KeyTypeId(*b"ethk");
+
 /// A generic Ethereum signer.
 pub trait EthSigner: Send + Sync {
 	/// Available accounts from this signer.
@@ -35,8 +41,16 @@ pub trait EthSigner: Send + Sync {
 		message: TransactionMessage,
 		address: &H160,
 	) -> Result<EthereumTransaction, Error>;
+	/// Sign an `eth_sign` message using the given account, returning `r`, `s` and `v`. Signers
+	/// which don't sign messages keep the default, rejecting them.
+	fn sign_message(&self, _message: &[u8], _address: &H160) -> Result<[u8; 65], Error> {
+		Err(internal_err("signer does not support signing messages"))
+	}
 }
 
+/// Signature of a message hash, as `r`, `s` and the recovery id.
+type RecoverableSignature = (H256, H256, u8);
+
 pub struct EthDevSigner {
 	keys: Vec<libsecp256k1::SecretKey>,
 }
@@ -52,6 +66,18 @@ impl EthDevSigner {
 			.expect("Test key is valid; qed")],
 		}
 	}
+
+	/// Signer holding the given keys, e.g. the pre-funded accounts of a development chain.
+	pub fn with_keys(keys: Vec<libsecp256k1::SecretKey>) -> Self {
+		Self { keys }
+	}
+
+	fn secret(&self, address: &H160) -> Result<&libsecp256k1::SecretKey, Error> {
+		self.keys
+			.iter()
+			.find(|secret| &secret_key_address(secret) == address)
+			.ok_or_else(|| internal_err("signer not available"))
+	}
 }
 
 fn secret_key_address(secret: &libsecp256k1::SecretKey) -> H160 {
@@ -65,6 +91,98 @@ fn public_key_address(public: &libsecp256k1::PublicKey) -> H160 {
 	H160::from(H256::from(keccak_256(&res)))
 }
 
+fn sign_with_secret(
+	secret: &libsecp256k1::SecretKey,
+	hash: &H256,
+) -> Result<RecoverableSignature, Error> {
+	let signing_message = libsecp256k1::Message::parse_slice(&hash[..])
+		.map_err(|_| internal_err("invalid signing message"))?;
+	let (signature, recid) = libsecp256k1::sign(&signing_message, secret);
+	let rs = signature.serialize();
+	Ok((
+		H256::from_slice(&rs[0..32]),
+		H256::from_slice(&rs[32..64]),
+		recid.serialize(),
+	))
+}
+
+/// Hash of an `eth_sign` message, prefixed as of EIP-191.
+fn message_hash(message: &[u8]) -> H256 {
+	let mut prefixed = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+	prefixed.extend_from_slice(message);
+	H256::from(keccak_256(&prefixed))
+}
+
+fn message_signature(
+	message: &[u8],
+	sign: impl FnOnce(&H256) -> Result<RecoverableSignature, Error>,
+) -> Result<[u8; 65], Error> {
+	let (r, s, recid) = sign(&message_hash(message))?;
+	let mut signature = [0u8; 65];
+	signature[0..32].copy_from_slice(r.as_bytes());
+	signature[32..64].copy_from_slice(s.as_bytes());
+	signature[64] = 27 + recid;
+	Ok(signature)
+}
+
+fn signed_transaction(
+	message: TransactionMessage,
+	sign: impl FnOnce(&H256) -> Result<RecoverableSignature, Error>,
+) -> Result<EthereumTransaction, Error> {
+	Ok(match message {
+		TransactionMessage::Legacy(m) => {
+			let (r, s, recid) = sign(&m.hash())?;
+			let v = match m.chain_id {
+				None => 27 + recid as u64,
+				Some(chain_id) => 2 * chain_id + 35 + recid as u64,
+			};
+			EthereumTransaction::Legacy(ethereum::LegacyTransaction {
+				nonce: m.nonce,
+				gas_price: m.gas_price,
+				gas_limit: m.gas_limit,
+				action: m.action,
+				value: m.value,
+				input: m.input,
+				signature: ethereum::TransactionSignature::new(v, r, s)
+					.ok_or_else(|| internal_err("signer generated invalid signature"))?,
+			})
+		}
+		TransactionMessage::EIP2930(m) => {
+			let (r, s, recid) = sign(&m.hash())?;
+			EthereumTransaction::EIP2930(ethereum::EIP2930Transaction {
+				chain_id: m.chain_id,
+				nonce: m.nonce,
+				gas_price: m.gas_price,
+				gas_limit: m.gas_limit,
+				action: m.action,
+				value: m.value,
+				input: m.input.clone(),
+				access_list: m.access_list,
+				odd_y_parity: recid != 0,
+				r,
+				s,
+			})
+		}
+		TransactionMessage::EIP1559(m) => {
+			let (r, s, recid) = sign(&m.hash())?;
+			EthereumTransaction::EIP1559(ethereum::EIP1559Transaction {
+				chain_id: m.chain_id,
+				nonce: m.nonce,
+				max_priority_fee_per_gas: m.max_priority_fee_per_gas,
+				max_fee_per_gas: m.max_fee_per_gas,
+				gas_limit: m.gas_limit,
+				action: m.action,
+				value: m.value,
+				input: m.input.clone(),
+				access_list: m.access_list,
+				odd_y_parity: recid != 0,
+				r,
+				s,
+			})
+		}
+	})
+}
+
 impl EthSigner for EthDevSigner {
 	fn accounts(&self) -> Vec<H160> {
 		self.keys.iter().map(secret_key_address).collect()
@@ -75,88 +193,150 @@ impl EthSigner for EthDevSigner {
 		message: TransactionMessage,
 		address: &H160,
 	) -> Result<EthereumTransaction, Error> {
-		let mut transaction = None;
-
-		for secret in &self.keys {
-			let key_address = secret_key_address(secret);
-
-			if &key_address == address {
-				match message {
-					TransactionMessage::Legacy(m) => {
-						let signing_message = libsecp256k1::Message::parse_slice(&m.hash()[..])
-							.map_err(|_| internal_err("invalid signing message"))?;
-						let (signature, recid) = libsecp256k1::sign(&signing_message, secret);
-						let v = match m.chain_id {
-							None => 27 + recid.serialize() as u64,
-							Some(chain_id) => 2 * chain_id + 35 + recid.serialize() as u64,
-						};
-						let rs = signature.serialize();
-						let r = H256::from_slice(&rs[0..32]);
-						let s = H256::from_slice(&rs[32..64]);
-						transaction =
-							Some(EthereumTransaction::Legacy(ethereum::LegacyTransaction {
-								nonce: m.nonce,
-								gas_price: m.gas_price,
-								gas_limit: m.gas_limit,
-								action: m.action,
-								value: m.value,
-								input: m.input,
-								signature: ethereum::TransactionSignature::new(v, r, s)
-									.ok_or_else(|| {
-										internal_err("signer generated invalid signature")
-									})?,
-							}));
-					}
-					TransactionMessage::EIP2930(m) => {
-						let signing_message = libsecp256k1::Message::parse_slice(&m.hash()[..])
-							.map_err(|_| internal_err("invalid signing message"))?;
-						let (signature, recid) = libsecp256k1::sign(&signing_message, secret);
-						let rs = signature.serialize();
-						let r = H256::from_slice(&rs[0..32]);
-						let s = H256::from_slice(&rs[32..64]);
-						transaction =
-							Some(EthereumTransaction::EIP2930(ethereum::EIP2930Transaction {
-								chain_id: m.chain_id,
-								nonce: m.nonce,
-								gas_price: m.gas_price,
-								gas_limit: m.gas_limit,
-								action: m.action,
-								value: m.value,
-								input: m.input.clone(),
-								access_list: m.access_list,
-								odd_y_parity: recid.serialize() != 0,
-								r,
-								s,
-							}));
-					}
-					TransactionMessage::EIP1559(m) => {
-						let signing_message = libsecp256k1::Message::parse_slice(&m.hash()[..])
-							.map_err(|_| internal_err("invalid signing message"))?;
-						let (signature, recid) = libsecp256k1::sign(&signing_message, secret);
-						let rs = signature.serialize();
-						let r = H256::from_slice(&rs[0..32]);
-						let s = H256::from_slice(&rs[32..64]);
-						transaction =
-							Some(EthereumTransaction::EIP1559(ethereum::EIP1559Transaction {
-								chain_id: m.chain_id,
-								nonce: m.nonce,
-								max_priority_fee_per_gas: m.max_priority_fee_per_gas,
-								max_fee_per_gas: m.max_fee_per_gas,
-								gas_limit: m.gas_limit,
-								action: m.action,
-								value: m.value,
-								input: m.input.clone(),
-								access_list: m.access_list,
-								odd_y_parity: recid.serialize() != 0,
-								r,
-								s,
-							}));
-					}
-				}
-				break;
-			}
+		let secret = self.secret(address)?;
+		signed_transaction(message, |hash| sign_with_secret(secret, hash))
+	}
+
+	fn sign_message(&self, message: &[u8], address: &H160) -> Result<[u8; 65], Error> {
+		let secret = self.secret(address)?;
+		message_signature(message, |hash| sign_with_secret(secret, hash))
+	}
+}
+
+/// Signer of the ECDSA keys of the node keystore with the [`ETH_KEY_TYPE`] key type, so that
+/// operators can sign transactions without exporting their keys.
+///
+/// When an allowlist is given, only its accounts are available.
+#[derive(Clone)]
+pub struct EthKeystoreSigner {
+	keystore: SyncCryptoStorePtr,
+	allowlist: Option<Arc<Vec<H160>>>,
+}
+
+impl EthKeystoreSigner {
+	pub fn new(keystore: SyncCryptoStorePtr, allowlist: Option<Vec<H160>>) -> Self {
+		Self {
+			keystore,
+			allowlist: allowlist.map(Arc::new),
 		}
+	}
+
+	/// The available accounts, along with their public keys.
+	fn keys(&self) -> Vec<(H160, ecdsa::Public)> {
+		SyncCryptoStore::ecdsa_public_keys(&*self.keystore, ETH_KEY_TYPE)
+			.into_iter()
+			.filter_map(|public| {
+				let address =
+					public_key_address(&libsecp256k1::PublicKey::parse_compressed(&public.0).ok()?);
+				match &self.allowlist {
+					Some(allowlist) if !allowlist.contains(&address) => None,
+					_ => Some((address, public)),
+				}
+			})
+			.collect()
+	}
+
+	fn public(&self, address: &H160) -> Result<ecdsa::Public, Error> {
+		self.keys()
+			.into_iter()
+			.find(|(key_address, _)| key_address == address)
+			.map(|(_, public)| public)
+			.ok_or_else(|| internal_err("signer not available"))
+	}
+
+	fn sign_with_keystore(
+		&self,
+		public: &ecdsa::Public,
+		hash: &H256,
+	) -> Result<RecoverableSignature, Error> {
+		let signature = SyncCryptoStore::ecdsa_sign_prehashed(
+			&*self.keystore,
+			ETH_KEY_TYPE,
+			public,
+			hash.as_fixed_bytes(),
+		)
+		.map_err(|err| internal_err(format!("keystore signing failed: {:?}", err)))?
+		.ok_or_else(|| internal_err("signer not available"))?;
+		Ok((
+			H256::from_slice(&signature.0[0..32]),
+			H256::from_slice(&signature.0[32..64]),
+			signature.0[64],
+		))
+	}
+}
+
+impl EthSigner for EthKeystoreSigner {
+	fn accounts(&self) -> Vec<H160> {
+		self.keys()
+			.into_iter()
+			.map(|(address, _)| address)
+			.collect()
+	}
+
+	fn sign(
+		&self,
+		message: TransactionMessage,
+		address: &H160,
+	) -> Result<EthereumTransaction, Error> {
+		let public = self.public(address)?;
+		signed_transaction(message, |hash| self.sign_with_keystore(&public, hash))
+	}
+
+	fn sign_message(&self, message: &[u8], address: &H160) -> Result<[u8; 65], Error> {
+		let public = self.public(address)?;
+		message_signature(message, |hash| self.sign_with_keystore(&public, hash))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_keystore::testing::KeyStore;
+
+	fn recover(message: &[u8], signature: &[u8; 65]) -> H160 {
+		let message = libsecp256k1::Message::parse(message_hash(message).as_fixed_bytes());
+		let rs = libsecp256k1::Signature::parse_standard_slice(&signature[0..64]).unwrap();
+		let recid = libsecp256k1::RecoveryId::parse(signature[64] - 27).unwrap();
+		public_key_address(&libsecp256k1::recover(&message, &rs, &recid).unwrap())
+	}
+
+	#[test]
+	fn keystore_signer_signs_with_the_keystore_keys() {
+		let keystore: SyncCryptoStorePtr = Arc::new(KeyStore::new());
+		let public = SyncCryptoStore::ecdsa_generate_new(&*keystore, ETH_KEY_TYPE, None).unwrap();
+		// Keys of other key types are not accounts.
+		SyncCryptoStore::ecdsa_generate_new(&*keystore, KeyTypeId(*b"beef"), None).unwrap();
+		let address =
+			public_key_address(&libsecp256k1::PublicKey::parse_compressed(&public.0).unwrap());
+
+		let signer = EthKeystoreSigner::new(keystore, None);
+		assert_eq!(signer.accounts(), vec![address]);
+
+		let signature = signer.sign_message(b"hello", &address).unwrap();
+		assert_eq!(recover(b"hello", &signature), address);
+		assert!(signer
+			.sign_message(b"hello", &H160::repeat_byte(1))
+			.is_err());
+	}
+
+	#[test]
+	fn keystore_signer_only_signs_with_the_allowlisted_accounts() {
+		let keystore: SyncCryptoStorePtr = Arc::new(KeyStore::new());
+		let public = SyncCryptoStore::ecdsa_generate_new(&*keystore, ETH_KEY_TYPE, None).unwrap();
+		SyncCryptoStore::ecdsa_generate_new(&*keystore, ETH_KEY_TYPE, None).unwrap();
+		let address =
+			public_key_address(&libsecp256k1::PublicKey::parse_compressed(&public.0).unwrap());
+
+		let signer = EthKeystoreSigner::new(keystore, Some(vec![address]));
+		assert_eq!(signer.accounts(), vec![address]);
+	}
+
+	#[test]
+	fn dev_signer_signs_messages() {
+		let signer = EthDevSigner::new();
+		let address = signer.accounts()[0];
 
-		transaction.ok_or_else(|| internal_err("signer not available"))
+		let signature = signer.sign_message(b"hello", &address).unwrap();
+		assert_eq!(recover(b"hello", &signature), address);
 	}
 }
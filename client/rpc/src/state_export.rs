@@ -0,0 +1,360 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Decode;
+use ethereum_types::{H160, H256};
+use jsonrpc_core::Result;
+
+use sc_client_api::{
+	backend::{Backend, StateBackend, StorageProvider},
+	ProofProvider,
+};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{BlakeTwo256, Block as BlockT, Header as HeaderT},
+};
+use sp_storage::{ChildInfo, StorageKey};
+
+use fc_rpc_core::{
+	types::{BlockNumber, Bytes, EvmAccount, EvmAccountRange, EvmStorageEntry, EvmStorageRange},
+	StateExportApi,
+};
+
+use crate::{
+	frontier_backend_client, internal_err,
+	overrides::{account_storage_child_info, blake2_128_extend, storage_prefix_build},
+};
+
+/// Maximum number of entries returned by a single request.
+const MAX_RANGE_LIMIT: u32 = 1024;
+
+/// EVM state export API implementation.
+///
+/// The state is read from the `pallet_evm` storage of the blocks, so it must be available:
+/// old blocks can only be read from an archive node.
+pub struct StateExport<B: BlockT, C, BE> {
+	client: Arc<C>,
+	backend: Arc<dyn fc_api::Backend<B>>,
+	_marker: PhantomData<BE>,
+}
+
+impl<B: BlockT, C, BE> StateExport<B, C, BE> {
+	pub fn new(client: Arc<C>, backend: Arc<dyn fc_api::Backend<B>>) -> Self {
+		Self {
+			client,
+			backend,
+			_marker: PhantomData,
+		}
+	}
+}
+
+/// A range of storage entries, with the proof of their keys.
+struct StorageRange {
+	block_hash: H256,
+	state_root: H256,
+	entries: Vec<(StorageKey, Vec<u8>)>,
+	next: Option<Bytes>,
+	proof: Vec<Bytes>,
+}
+
+impl<B, C, BE> StateExport<B, C, BE>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: StorageProvider<B, BE> + ProofProvider<B> + HeaderBackend<B> + Send + Sync + 'static,
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+{
+	/// Header of the block of a range request.
+	fn range_header(&self, number: Option<BlockNumber>, limit: u32) -> Result<B::Header> {
+		if limit > MAX_RANGE_LIMIT {
+			return Err(internal_err(format!(
+				"range limit must be at most {}",
+				MAX_RANGE_LIMIT
+			)));
+		}
+
+		let id = frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			number,
+		)?
+		.ok_or_else(|| internal_err("block not found"))?;
+		self.client
+			.header(id)
+			.map_err(|err| internal_err(format!("{:?}", err)))?
+			.ok_or_else(|| internal_err("block not found"))
+	}
+
+	/// Reads up to `limit` storage entries under `prefix`, after the `after` storage key, from
+	/// the main trie or from the child trie `child_info`.
+	fn storage_range(
+		&self,
+		header: &B::Header,
+		child_info: Option<&ChildInfo>,
+		prefix: Vec<u8>,
+		after: Option<Bytes>,
+		limit: u32,
+	) -> Result<StorageRange> {
+		let id = BlockId::Hash(header.hash());
+
+		let prefix = StorageKey(prefix);
+		let after = after.map(|after| StorageKey(after.0));
+		let keys = match child_info {
+			Some(child_info) => self.client.child_storage_keys_iter(
+				&id,
+				child_info.clone(),
+				Some(&prefix),
+				after.as_ref(),
+			),
+			None => self
+				.client
+				.storage_keys_iter(&id, Some(&prefix), after.as_ref()),
+		}
+		.map_err(|err| internal_err(format!("state not available: {:?}", err)))?
+		.take(limit as usize)
+		.collect::<Vec<_>>();
+
+		let mut entries = Vec::with_capacity(keys.len());
+		for key in keys {
+			let value = match child_info {
+				Some(child_info) => self.client.child_storage(&id, child_info, &key),
+				None => self.client.storage(&id, &key),
+			}
+			.map_err(|err| internal_err(format!("{:?}", err)))?
+			.ok_or_else(|| internal_err("storage entry not found"))?;
+			entries.push((key, value.0));
+		}
+		let mut keys = entries.iter().map(|(key, _)| &key.0[..]);
+		let proof = match child_info {
+			Some(child_info) => self.client.read_child_proof(&id, child_info, &mut keys),
+			None => self.client.read_proof(&id, &mut keys),
+		}
+		.map_err(|err| internal_err(format!("{:?}", err)))?;
+
+		Ok(StorageRange {
+			block_hash: header.hash(),
+			state_root: *header.state_root(),
+			// A full range may not be the last one.
+			next: if entries.len() == limit as usize {
+				entries.last().map(|(key, _)| Bytes(key.0.clone()))
+			} else {
+				None
+			},
+			entries,
+			proof: proof.into_iter_nodes().map(Bytes).collect(),
+		})
+	}
+}
+
+impl<B, C, BE> StateExport<B, C, BE>
+where
+	B: BlockT<Hash = H256>,
+{
+	/// Accounts of a range of the legacy `AccountCodes` map, which stored the code of each
+	/// account.
+	fn legacy_accounts_of(entries: &[(StorageKey, Vec<u8>)]) -> Result<Vec<EvmAccount>> {
+		entries
+			.iter()
+			.map(|(key, value)| {
+				// The keys end with the address, after the `Blake2_128Concat` hash.
+				let address = H160::from_slice(&key.0[key.0.len() - 20..]);
+				let code = Vec::<u8>::decode(&mut &value[..])
+					.map_err(|_| internal_err("invalid contract code in storage"))?;
+				Ok(EvmAccount {
+					address,
+					code: Bytes(code),
+				})
+			})
+			.collect()
+	}
+
+	fn account_range_of(range: StorageRange, accounts: Vec<EvmAccount>) -> EvmAccountRange {
+		EvmAccountRange {
+			block_hash: range.block_hash,
+			state_root: range.state_root,
+			accounts,
+			next: range.next,
+			proof: range.proof,
+		}
+	}
+
+	/// Storage slots of `range`, keyed by their index, after the `legacy` ones.
+	fn storage_range_of(
+		range: StorageRange,
+		legacy: Vec<(StorageKey, Vec<u8>)>,
+	) -> Result<EvmStorageRange> {
+		let storage = legacy
+			.into_iter()
+			.chain(range.entries)
+			.map(|(key, value)| {
+				// The keys end with the slot index, after the `Blake2_128Concat` hash for the
+				// legacy keys.
+				let key = H256::from_slice(&key.0[key.0.len() - 32..]);
+				let value = H256::decode(&mut &value[..])
+					.map_err(|_| internal_err("invalid storage slot in storage"))?;
+				Ok(EvmStorageEntry { key, value })
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		Ok(EvmStorageRange {
+			block_hash: range.block_hash,
+			state_root: range.state_root,
+			storage,
+			next: range.next,
+			proof: range.proof,
+		})
+	}
+}
+
+impl<B, C, BE> StateExportApi for StateExport<B, C, BE>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: StorageProvider<B, BE> + ProofProvider<B> + HeaderBackend<B> + Send + Sync + 'static,
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+{
+	fn evm_account_range(
+		&self,
+		number: Option<BlockNumber>,
+		after: Option<Bytes>,
+		limit: u32,
+	) -> Result<EvmAccountRange> {
+		let header = self.range_header(number, limit)?;
+
+		// The contracts not yet migrated from the legacy `AccountCodes` map come first. Their
+		// keys have another prefix than the ones of `AccountCodeHash`.
+		let prefix = storage_prefix_build(b"EVM", b"AccountCodeHash");
+		let (legacy, after) = match after {
+			Some(after) if after.0.starts_with(&prefix) => (None, Some(after)),
+			after => {
+				let legacy = self.storage_range(
+					&header,
+					None,
+					storage_prefix_build(b"EVM", b"AccountCodes"),
+					after,
+					limit,
+				)?;
+				let accounts = Self::legacy_accounts_of(&legacy.entries)?;
+				if legacy.next.is_some() {
+					return Ok(Self::account_range_of(legacy, accounts));
+				}
+				(Some((legacy, accounts)), None)
+			}
+		};
+
+		let limit = limit
+			- legacy
+				.as_ref()
+				.map_or(0, |(legacy, _)| legacy.entries.len() as u32);
+		let mut range = self.storage_range(&header, None, prefix, after, limit)?;
+
+		// The codes are resolved from their hash, with the proof of their preimage.
+		let id = BlockId::Hash(header.hash());
+		let mut code_keys = Vec::with_capacity(range.entries.len());
+		let mut accounts = Vec::with_capacity(range.entries.len());
+		for (key, value) in &range.entries {
+			// The keys end with the address, after the `Blake2_128Concat` hash.
+			let address = H160::from_slice(&key.0[key.0.len() - 20..]);
+			let code_hash = H256::decode(&mut &value[..])
+				.map_err(|_| internal_err("invalid contract code hash in storage"))?;
+			let mut code_key = storage_prefix_build(b"EVM", b"AccountCodeByHash");
+			code_key.extend(code_hash.as_bytes());
+			let code = self
+				.client
+				.storage(&id, &StorageKey(code_key.clone()))
+				.map_err(|err| internal_err(format!("{:?}", err)))?
+				.ok_or_else(|| internal_err("contract code not found"))?;
+			let code = Vec::<u8>::decode(&mut &code.0[..])
+				.map_err(|_| internal_err("invalid contract code in storage"))?;
+			code_keys.push(code_key);
+			accounts.push(EvmAccount {
+				address,
+				code: Bytes(code),
+			});
+		}
+		let code_proof = self
+			.client
+			.read_proof(&id, &mut code_keys.iter().map(|key| &key[..]))
+			.map_err(|err| internal_err(format!("{:?}", err)))?;
+		let legacy_proof = legacy
+			.as_ref()
+			.map(|(legacy, _)| legacy.proof.clone())
+			.unwrap_or_default();
+		for node in code_proof.into_iter_nodes().map(Bytes).chain(legacy_proof) {
+			if !range.proof.contains(&node) {
+				range.proof.push(node);
+			}
+		}
+
+		let accounts = match legacy {
+			Some((_, legacy_accounts)) => legacy_accounts.into_iter().chain(accounts).collect(),
+			None => accounts,
+		};
+		Ok(Self::account_range_of(range, accounts))
+	}
+
+	fn evm_storage_range(
+		&self,
+		number: Option<BlockNumber>,
+		address: H160,
+		after: Option<Bytes>,
+		limit: u32,
+	) -> Result<EvmStorageRange> {
+		let header = self.range_header(number, limit)?;
+
+		// The slots not yet migrated from the legacy `AccountStorages` map come first. Their
+		// keys are longer than the slot indexes of the child trie of the account.
+		let mut prefix = storage_prefix_build(b"EVM", b"AccountStorages");
+		prefix.extend(blake2_128_extend(address.as_bytes()));
+		let (legacy, after) = match after {
+			Some(after) if after.0.len() == 32 => (None, Some(after)),
+			after => {
+				let legacy = self.storage_range(&header, None, prefix, after, limit)?;
+				if legacy.next.is_some() {
+					return Ok(Self::storage_range_of(legacy, Vec::new()));
+				}
+				(Some(legacy), None)
+			}
+		};
+
+		let limit = limit
+			- legacy
+				.as_ref()
+				.map_or(0, |legacy| legacy.entries.len() as u32);
+		let mut range = self.storage_range(
+			&header,
+			Some(&account_storage_child_info(&address)),
+			Vec::new(),
+			after,
+			limit,
+		)?;
+		let mut legacy_entries = Vec::new();
+		if let Some(legacy) = legacy {
+			legacy_entries = legacy.entries;
+			for node in legacy.proof {
+				if !range.proof.contains(&node) {
+					range.proof.push(node);
+				}
+			}
+		}
+		Ok(Self::storage_range_of(range, legacy_entries))
+	}
+}
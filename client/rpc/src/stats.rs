@@ -0,0 +1,181 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use std::sync::Arc;
+
+use ethereum_types::{H256, U256};
+use jsonrpc_core::{BoxFuture, Result};
+use sc_transaction_pool::{ChainApi, Pool};
+use sc_transaction_pool_api::InPoolTransaction;
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, UniqueSaturatedInto},
+};
+
+use fc_rpc_core::{
+	types::{FilterPool, FrontierStats, MappingSyncStats, PendingTransactionsStats},
+	StatsApi,
+};
+use fp_rpc::EthereumRuntimeRPCApi;
+
+use crate::{eth::EthBlockDataCacheTask, internal_err};
+
+/// Frontier statistics API implementation.
+pub struct Stats<B: BlockT, C, A: ChainApi> {
+	client: Arc<C>,
+	backend: Arc<dyn fc_api::Backend<B>>,
+	graph: Arc<Pool<A>>,
+	block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+	filter_pool: Option<FilterPool>,
+}
+
+impl<B: BlockT, C, A: ChainApi> Stats<B, C, A> {
+	pub fn new(
+		client: Arc<C>,
+		backend: Arc<dyn fc_api::Backend<B>>,
+		graph: Arc<Pool<A>>,
+		block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+		filter_pool: Option<FilterPool>,
+	) -> Self {
+		Self {
+			client,
+			backend,
+			graph,
+			block_data_cache,
+			filter_pool,
+		}
+	}
+}
+
+impl<B, C, A> Stats<B, C, A>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: HeaderBackend<B> + ProvideRuntimeApi<B> + Send + Sync + 'static,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	A: ChainApi<Block = B> + 'static,
+{
+	fn mapping_sync(&self) -> Result<MappingSyncStats> {
+		let info = self.client.info();
+		let best_block_synced = self
+			.backend
+			.is_synced(&info.best_hash)
+			.map_err(|err| internal_err(format!("fetch aux store failed: {:?}", err)))?;
+		let syncing_tips = self
+			.backend
+			.current_syncing_tips()
+			.map_err(|err| internal_err(format!("fetch aux store failed: {:?}", err)))?
+			.into_iter()
+			.filter_map(|hash| self.client.number(hash).ok().flatten())
+			.map(|number| U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(number)))
+			.collect();
+
+		Ok(MappingSyncStats {
+			best_block: U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(
+				info.best_number,
+			)),
+			best_block_synced,
+			syncing_tips,
+		})
+	}
+
+	/// Counts the Ethereum transactions of the ready and future pools.
+	fn pending_transactions(&self) -> Result<PendingTransactionsStats> {
+		let best_block = BlockId::Hash(self.client.info().best_hash);
+		let api = self.client.runtime_api();
+		let api_version = if let Ok(Some(api_version)) =
+			api.api_version::<dyn EthereumRuntimeRPCApi<B>>(&best_block)
+		{
+			api_version
+		} else {
+			return Err(internal_err("failed to retrieve Runtime Api version"));
+		};
+		let count = |xts: Vec<<B as BlockT>::Extrinsic>| -> Result<U256> {
+			let count = if api_version > 1 {
+				api.extrinsic_filter(&best_block, xts)
+					.map_err(|err| {
+						internal_err(format!("fetch runtime extrinsic filter failed: {:?}", err))
+					})?
+					.len()
+			} else {
+				#[allow(deprecated)]
+				let legacy = api.extrinsic_filter_before_version_2(&best_block, xts)
+					.map_err(|err| {
+						internal_err(format!("fetch runtime extrinsic filter failed: {:?}", err))
+					})?;
+				legacy.len()
+			};
+			Ok(U256::from(count))
+		};
+
+		let ready = self
+			.graph
+			.validated_pool()
+			.ready()
+			.map(|in_pool_tx| in_pool_tx.data().clone())
+			.collect();
+		let future = self
+			.graph
+			.validated_pool()
+			.futures()
+			.into_iter()
+			.map(|(_hash, extrinsic)| extrinsic)
+			.collect();
+
+		Ok(PendingTransactionsStats {
+			ready: count(ready)?,
+			future: count(future)?,
+		})
+	}
+}
+
+impl<B, C, A> StatsApi for Stats<B, C, A>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: HeaderBackend<B> + ProvideRuntimeApi<B> + Send + Sync + 'static,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	A: ChainApi<Block = B> + 'static,
+{
+	fn stats(&self) -> BoxFuture<Result<FrontierStats>> {
+		let stats = self.mapping_sync().and_then(|mapping_sync| {
+			let filter_pool_size = match &self.filter_pool {
+				Some(filter_pool) => Some(U256::from(
+					filter_pool
+						.lock()
+						.map_err(|_| internal_err("filter pool lock is poisoned"))?
+						.len(),
+				)),
+				None => None,
+			};
+			Ok(FrontierStats {
+				mapping_sync,
+				database_size: self.backend.size_on_disk().map(U256::from),
+				caches: Vec::new(),
+				filter_pool_size,
+				pending_transactions: self.pending_transactions()?,
+			})
+		});
+		let block_data_cache = Arc::clone(&self.block_data_cache);
+
+		Box::pin(async move {
+			let mut stats = stats?;
+			stats.caches = block_data_cache.stats().await;
+			Ok(stats)
+		})
+	}
+}
@@ -0,0 +1,634 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+use ethereum::{TransactionAction, TransactionV2};
+use ethereum_types::{H160, H256, U256};
+use jsonrpc_core::Result;
+
+use sc_client_api::{
+	backend::{Backend, StateBackend},
+	BlockBackend,
+};
+use sp_api::{ApiExt, ApiRef, Core, ProvideRuntimeApi};
+use sp_block_builder::BlockBuilder as BlockBuilderApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{BlakeTwo256, Block as BlockT, Header as HeaderT},
+};
+
+use fc_rpc_core::{
+	types::{
+		AccountDiff, Bytes, Diff, PrestateAccount, PrestateTrace, StateDiff, TraceConfig,
+		TraceResults,
+	},
+	TraceApi,
+};
+use fp_rpc::EthereumRuntimeRPCApi;
+use fp_storage::EVM_ACCOUNT_STORAGE_CHILD_PREFIX;
+
+use crate::{frontier_backend_client, internal_err, overrides::storage_prefix_build};
+
+/// Limits of a trace, so that tracing a pathological transaction can't exhaust the node.
+///
+/// A trace over its limits is truncated: it only has the accounts traced within them.
+#[derive(Clone, Copy, Debug)]
+pub struct TraceLimits {
+	/// Maximum number of accounts and storage slots in a trace.
+	pub max_entries: usize,
+	/// Maximum time spent tracing a transaction. A trace fails if its transaction can't be
+	/// replayed in time.
+	pub timeout: Option<Duration>,
+}
+
+impl Default for TraceLimits {
+	fn default() -> Self {
+		Self {
+			max_entries: 10_000,
+			timeout: Some(Duration::from_secs(30)),
+		}
+	}
+}
+
+/// Trace API implementation.
+///
+/// Transactions are replayed on top of the state of the parent of their block, so it must be
+/// available: old transactions can only be replayed by an archive node.
+pub struct Trace<B: BlockT, C, BE> {
+	client: Arc<C>,
+	substrate_backend: Arc<BE>,
+	backend: Arc<dyn fc_api::Backend<B>>,
+	limits: TraceLimits,
+}
+
+impl<B: BlockT, C, BE> Trace<B, C, BE> {
+	pub fn new(
+		client: Arc<C>,
+		substrate_backend: Arc<BE>,
+		backend: Arc<dyn fc_api::Backend<B>>,
+		limits: TraceLimits,
+	) -> Self {
+		Self {
+			client,
+			substrate_backend,
+			backend,
+			limits,
+		}
+	}
+}
+
+/// Fails with the trace timeout error if `deadline` has passed.
+fn check_deadline(deadline: Option<Instant>) -> Result<()> {
+	match deadline {
+		Some(deadline) if Instant::now() > deadline => Err(internal_err("trace timeout")),
+		_ => Ok(()),
+	}
+}
+
+/// States of the accounts of a trace, before and after its transaction.
+struct ReplayedStates {
+	states: BTreeMap<H160, (AccountState, AccountState)>,
+	/// Whether accounts or storage slots are missing, as the trace reached its limits.
+	truncated: bool,
+}
+
+/// State of an EVM account, with the storage slots of interest.
+struct AccountState {
+	balance: U256,
+	nonce: U256,
+	code: Vec<u8>,
+	storage: BTreeMap<H256, H256>,
+}
+
+impl AccountState {
+	/// An account exists as soon as it has a nonce, a balance or code.
+	fn exists(&self) -> bool {
+		!self.nonce.is_zero() || !self.balance.is_zero() || !self.code.is_empty()
+	}
+}
+
+impl<B, C, BE> Trace<B, C, BE>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + BlockBackend<B> + HeaderBackend<B> + Send + Sync + 'static,
+	C::Api: BlockBuilderApi<B> + EthereumRuntimeRPCApi<B>,
+	C::Api: ApiExt<B, StateBackend = BE::State>,
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+{
+	/// Applies `extrinsics` on top of the state of `parent`, in a new block with `header`.
+	fn replay(
+		&self,
+		parent: &BlockId<B>,
+		header: &B::Header,
+		extrinsics: &[B::Extrinsic],
+		deadline: Option<Instant>,
+	) -> Result<ApiRef<'_, C::Api>> {
+		let api = self.client.runtime_api();
+		api.initialize_block(parent, header)
+			.map_err(|err| internal_err(format!("Runtime api access error: {:?}", err)))?;
+		for xt in extrinsics {
+			check_deadline(deadline)?;
+			api.apply_extrinsic(parent, xt.clone())
+				.map_err(|err| internal_err(format!("Runtime api access error: {:?}", err)))?
+				.map_err(|err| internal_err(format!("failed to replay extrinsic: {:?}", err)))?;
+		}
+		Ok(api)
+	}
+
+	/// Reads the accounts and storage slots of `accounts` in the overlayed state of `api`, up
+	/// to `deadline`.
+	fn read_accounts(
+		api: &ApiRef<'_, C::Api>,
+		parent: &BlockId<B>,
+		accounts: &BTreeMap<H160, BTreeSet<H256>>,
+		deadline: Option<Instant>,
+	) -> Result<BTreeMap<H160, AccountState>> {
+		let access_err = |err| internal_err(format!("Runtime api access error: {:?}", err));
+		accounts
+			.iter()
+			.take_while(|_| check_deadline(deadline).is_ok())
+			.map(|(address, slots)| {
+				let basic = api.account_basic(parent, *address).map_err(access_err)?;
+				let code = api.account_code_at(parent, *address).map_err(access_err)?;
+				let storage = slots
+					.iter()
+					.map(|slot| {
+						let value = api
+							.storage_at(parent, *address, U256::from_big_endian(slot.as_bytes()))
+							.map_err(access_err)?;
+						Ok((*slot, value))
+					})
+					.collect::<Result<_>>()?;
+				Ok((
+					*address,
+					AccountState {
+						balance: basic.balance,
+						nonce: basic.nonce,
+						code,
+						storage,
+					},
+				))
+			})
+			.collect()
+	}
+
+	/// Accounts and storage slots accessed by the transaction at `index` of the block `id`, from
+	/// a dry run of the transaction in the state of `api`, before it. They are unknown to the
+	/// runtimes without the `dry_run` runtime api.
+	fn accessed_accounts(
+		&self,
+		api: &ApiRef<'_, C::Api>,
+		parent: &BlockId<B>,
+		id: &BlockId<B>,
+		index: usize,
+		from: H160,
+	) -> Result<Vec<(H160, Vec<H256>)>> {
+		let access_err = |err| internal_err(format!("Runtime api access error: {:?}", err));
+		let api_version = api
+			.api_version::<dyn EthereumRuntimeRPCApi<B>>(parent)
+			.map_err(access_err)?;
+		if api_version.map_or(true, |api_version| api_version < 12) {
+			return Ok(Vec::new());
+		}
+
+		let transaction = self
+			.client
+			.runtime_api()
+			.current_block(id)
+			.map_err(access_err)?
+			.and_then(|block| block.transactions.into_iter().nth(index))
+			.ok_or_else(|| internal_err("transaction not found"))?;
+		// The fees of the legacy and EIP-2930 transactions are their gas price.
+		let (input, value, gas_limit, max_fee_per_gas, max_priority_fee_per_gas, nonce, action) =
+			match &transaction {
+				TransactionV2::Legacy(t) => (
+					t.input.clone(),
+					t.value,
+					t.gas_limit,
+					t.gas_price,
+					t.gas_price,
+					t.nonce,
+					t.action,
+				),
+				TransactionV2::EIP2930(t) => (
+					t.input.clone(),
+					t.value,
+					t.gas_limit,
+					t.gas_price,
+					t.gas_price,
+					t.nonce,
+					t.action,
+				),
+				TransactionV2::EIP1559(t) => (
+					t.input.clone(),
+					t.value,
+					t.gas_limit,
+					t.max_fee_per_gas,
+					t.max_priority_fee_per_gas,
+					t.nonce,
+					t.action,
+				),
+			};
+		let access_list = match &transaction {
+			TransactionV2::Legacy(_) => Vec::new(),
+			TransactionV2::EIP2930(ethereum::EIP2930Transaction { access_list, .. })
+			| TransactionV2::EIP1559(ethereum::EIP1559Transaction { access_list, .. }) => access_list
+				.iter()
+				.map(|item| (item.address, item.storage_keys.clone()))
+				.collect(),
+		};
+		let to = match action {
+			TransactionAction::Call(to) => Some(to),
+			TransactionAction::Create => None,
+		};
+
+		let dry_run = api
+			.dry_run(
+				parent,
+				from,
+				to,
+				input,
+				value,
+				gas_limit,
+				Some(max_fee_per_gas),
+				Some(max_priority_fee_per_gas),
+				Some(nonce),
+				false,
+				Some(access_list),
+			)
+			.map_err(access_err)?
+			.map_err(|err| internal_err(format!("failed to dry run transaction: {:?}", err)))?;
+		Ok(dry_run
+			.access_list
+			.into_iter()
+			.chain(dry_run.excluded_storage)
+			.collect())
+	}
+
+	/// Replays a transaction, and returns the states of the accounts it may touch before and
+	/// after it.
+	///
+	/// The accounts are the ones of the transaction status, and the ones changed in the block up
+	/// to the transaction, with their changed storage slots. With `read_only`, the accounts and
+	/// slots the transaction only reads are added from a dry run of the transaction.
+	///
+	/// Past the limits of the trace, the remaining accounts and slots are left out.
+	fn replay_states(&self, hash: H256, read_only: bool) -> Result<ReplayedStates> {
+		let deadline = self.limits.timeout.map(|timeout| Instant::now() + timeout);
+		let (ethereum_block_hash, index) = frontier_backend_client::load_transactions::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			hash,
+			true,
+		)
+		.map_err(|err| internal_err(format!("{:?}", err)))?
+		.ok_or_else(|| internal_err("transaction not found"))?;
+		let index = index as usize;
+		let id =
+			frontier_backend_client::load_hash::<B>(self.backend.as_ref(), ethereum_block_hash)
+				.map_err(|err| internal_err(format!("{:?}", err)))?
+				.ok_or_else(|| internal_err("block not found"))?;
+		let header = self
+			.client
+			.header(id)
+			.map_err(|err| internal_err(format!("{:?}", err)))?
+			.ok_or_else(|| internal_err("block not found"))?;
+		let extrinsics = self
+			.client
+			.block_body(&id)
+			.map_err(|err| internal_err(format!("{:?}", err)))?
+			.ok_or_else(|| internal_err("block body not found"))?;
+		let parent = BlockId::Hash(*header.parent_hash());
+
+		let status = self
+			.client
+			.runtime_api()
+			.current_transaction_statuses(&id)
+			.map_err(|err| internal_err(format!("Runtime api access error: {:?}", err)))?
+			.and_then(|statuses| statuses.into_iter().nth(index))
+			.ok_or_else(|| internal_err("transaction status not found"))?;
+
+		// The extrinsic of the transaction is the one where the count of the Ethereum
+		// transactions of the block goes over its index.
+		let mut position = None;
+		let mut transactions = 0;
+		for (i, xt) in extrinsics.iter().enumerate() {
+			check_deadline(deadline)?;
+			transactions += self
+				.client
+				.runtime_api()
+				.extrinsic_filter(&parent, vec![xt.clone()])
+				.map_err(|err| {
+					internal_err(format!("fetch runtime extrinsic filter failed: {:?}", err))
+				})?
+				.len();
+			if transactions > index {
+				position = Some(i);
+				break;
+			}
+		}
+		let position = position.ok_or_else(|| internal_err("transaction extrinsic not found"))?;
+
+		// The accounts the transaction may change: the ones of its status, and the EVM accounts
+		// changed in the block up to the transaction.
+		let mut accounts = BTreeMap::<H160, BTreeSet<H256>>::new();
+		accounts.entry(status.from).or_default();
+		for address in status
+			.to
+			.into_iter()
+			.chain(status.contract_address)
+			.chain(status.logs.iter().map(|log| log.address))
+		{
+			accounts.entry(address).or_default();
+		}
+
+		let state = self
+			.substrate_backend
+			.state_at(parent)
+			.map_err(|err| internal_err(format!("state not available: {:?}", err)))?;
+		let changes = self
+			.replay(&parent, &header, &extrinsics[..=position], deadline)?
+			.into_storage_changes(&state, *header.parent_hash())
+			.map_err(internal_err)?;
+		let codes_prefix = storage_prefix_build(b"EVM", b"AccountCodeHash");
+		let storages_prefix = storage_prefix_build(b"EVM", b"AccountStorages");
+		for (key, _) in changes.main_storage_changes {
+			// The keys end with the address, and then with the slot index for the legacy
+			// storage slots, each after its `Blake2_128Concat` hash. Contracts are created and
+			// deleted by setting and removing their code hash.
+			if key.starts_with(&codes_prefix) && key.len() == codes_prefix.len() + 16 + 20 {
+				accounts
+					.entry(H160::from_slice(&key[key.len() - 20..]))
+					.or_default();
+			} else if key.starts_with(&storages_prefix)
+				&& key.len() == storages_prefix.len() + 16 + 20 + 16 + 32
+			{
+				let address = H160::from_slice(&key[storages_prefix.len() + 16..][..20]);
+				let slot = H256::from_slice(&key[key.len() - 32..]);
+				accounts.entry(address).or_default().insert(slot);
+			}
+		}
+		// The storage of the accounts is in a child trie per account, keyed by the slot index.
+		for (storage_key, child_changes) in changes.child_storage_changes {
+			if storage_key.starts_with(EVM_ACCOUNT_STORAGE_CHILD_PREFIX)
+				&& storage_key.len() == EVM_ACCOUNT_STORAGE_CHILD_PREFIX.len() + 20
+			{
+				let address =
+					H160::from_slice(&storage_key[EVM_ACCOUNT_STORAGE_CHILD_PREFIX.len()..]);
+				let slots = accounts.entry(address).or_default();
+				for (key, _) in child_changes {
+					if key.len() == 32 {
+						slots.insert(H256::from_slice(&key));
+					}
+				}
+			}
+		}
+
+		let api = self.replay(&parent, &header, &extrinsics[..position], deadline)?;
+		if read_only {
+			for (address, slots) in
+				self.accessed_accounts(&api, &parent, &id, index, status.from)?
+			{
+				accounts.entry(address).or_default().extend(slots);
+			}
+		}
+
+		// Each account and each slot is an entry of the trace.
+		let mut truncated = false;
+		let mut entries = 0;
+		accounts.retain(|_, slots| {
+			if entries >= self.limits.max_entries {
+				truncated = true;
+				return false;
+			}
+			entries += 1;
+			if entries + slots.len() > self.limits.max_entries {
+				truncated = true;
+				*slots = slots
+					.iter()
+					.take(self.limits.max_entries - entries)
+					.copied()
+					.collect();
+			}
+			entries += slots.len();
+			true
+		});
+
+		let before = Self::read_accounts(&api, &parent, &accounts, deadline)?;
+		if before.len() < accounts.len() {
+			truncated = true;
+			accounts.retain(|address, _| before.contains_key(address));
+		}
+		api.apply_extrinsic(&parent, extrinsics[position].clone())
+			.map_err(|err| internal_err(format!("Runtime api access error: {:?}", err)))?
+			.map_err(|err| internal_err(format!("failed to replay extrinsic: {:?}", err)))?;
+		// The same accounts are read after the transaction, within the time they took before.
+		let after = Self::read_accounts(&api, &parent, &accounts, None)?;
+
+		Ok(ReplayedStates {
+			states: before
+				.into_iter()
+				.zip(after.into_iter())
+				.map(|((address, before), (_, after))| (address, (before, after)))
+				.collect(),
+			truncated,
+		})
+	}
+
+	fn state_diff(&self, hash: H256) -> Result<(StateDiff, bool)> {
+		let ReplayedStates { states, truncated } = self.replay_states(hash, false)?;
+		let state_diff = states
+			.into_iter()
+			.filter_map(|(address, (before, after))| {
+				account_diff(before, after).map(|diff| (address, diff))
+			})
+			.collect();
+		Ok((state_diff, truncated))
+	}
+
+	fn prestate(&self, hash: H256, diff_mode: bool) -> Result<PrestateTrace> {
+		// Only the changed accounts are part of the diff.
+		let ReplayedStates { states, truncated } = self.replay_states(hash, !diff_mode)?;
+		if !diff_mode {
+			return Ok(PrestateTrace::Prestate {
+				accounts: states
+					.into_iter()
+					.filter(|(_, (before, _))| before.exists())
+					.map(|(address, (before, _))| (address, prestate_account(before)))
+					.collect(),
+				truncated,
+			});
+		}
+
+		let mut pre = BTreeMap::new();
+		let mut post = BTreeMap::new();
+		for (address, (mut before, mut after)) in states {
+			// Only the changed slots are part of the diff.
+			before
+				.storage
+				.retain(|slot, value| after.storage.get(slot) != Some(value));
+			after
+				.storage
+				.retain(|slot, value| !value.is_zero() && before.storage.contains_key(slot));
+			let changed = before.balance != after.balance
+				|| before.nonce != after.nonce
+				|| before.code != after.code
+				|| !before.storage.is_empty();
+			if !changed {
+				continue;
+			}
+
+			if after.exists() {
+				post.insert(
+					address,
+					PrestateAccount {
+						balance: (before.balance != after.balance).then(|| after.balance),
+						nonce: (before.nonce != after.nonce).then(|| after.nonce.low_u64()),
+						code: (before.code != after.code).then(|| Bytes(after.code.clone())),
+						storage: after.storage,
+					},
+				);
+			}
+			if before.exists() {
+				pre.insert(address, prestate_account(before));
+			}
+		}
+		Ok(PrestateTrace::Diff {
+			pre,
+			post,
+			truncated,
+		})
+	}
+}
+
+/// The `prestateTracer` state of an existing account.
+fn prestate_account(state: AccountState) -> PrestateAccount {
+	PrestateAccount {
+		balance: Some(state.balance),
+		nonce: Some(state.nonce.low_u64()),
+		code: (!state.code.is_empty()).then(|| Bytes(state.code)),
+		storage: state.storage,
+	}
+}
+
+/// Changes between two states of an account, if any.
+fn account_diff(before: AccountState, after: AccountState) -> Option<AccountDiff> {
+	let before = before.exists().then(|| before);
+	let after = after.exists().then(|| after);
+	let slots = before
+		.iter()
+		.chain(after.iter())
+		.flat_map(|state| state.storage.keys().copied())
+		.collect::<BTreeSet<_>>();
+
+	let diff = AccountDiff {
+		balance: Diff::new(
+			before.as_ref().map(|state| state.balance),
+			after.as_ref().map(|state| state.balance),
+		),
+		nonce: Diff::new(
+			before.as_ref().map(|state| state.nonce),
+			after.as_ref().map(|state| state.nonce),
+		),
+		code: Diff::new(
+			before.as_ref().map(|state| Bytes(state.code.clone())),
+			after.as_ref().map(|state| Bytes(state.code.clone())),
+		),
+		storage: slots
+			.into_iter()
+			.map(|slot| {
+				let value =
+					|state: &AccountState| state.storage.get(&slot).copied().unwrap_or_default();
+				(
+					slot,
+					Diff::new(before.as_ref().map(value), after.as_ref().map(value)),
+				)
+			})
+			.filter(|(_, diff)| !diff.is_same())
+			.collect(),
+	};
+
+	if diff.balance.is_same()
+		&& diff.nonce.is_same()
+		&& diff.code.is_same()
+		&& diff.storage.is_empty()
+	{
+		None
+	} else {
+		Some(diff)
+	}
+}
+
+impl<B, C, BE> TraceApi for Trace<B, C, BE>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + BlockBackend<B> + HeaderBackend<B> + Send + Sync + 'static,
+	C::Api: BlockBuilderApi<B> + EthereumRuntimeRPCApi<B>,
+	C::Api: ApiExt<B, StateBackend = BE::State>,
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+{
+	fn replay_transaction(&self, hash: H256, trace_types: Vec<String>) -> Result<TraceResults> {
+		if let Some(trace_type) = trace_types
+			.iter()
+			.find(|trace_type| trace_type.as_str() != "stateDiff")
+		{
+			return Err(internal_err(format!(
+				"unsupported trace type: {}",
+				trace_type
+			)));
+		}
+
+		if trace_types.is_empty() {
+			return Ok(TraceResults {
+				state_diff: None,
+				truncated: false,
+			});
+		}
+		let (state_diff, truncated) = self.state_diff(hash)?;
+		Ok(TraceResults {
+			state_diff: Some(state_diff),
+			truncated,
+		})
+	}
+
+	fn trace_transaction(&self, hash: H256, config: Option<TraceConfig>) -> Result<PrestateTrace> {
+		let config = config.unwrap_or_default();
+		match config.tracer.as_deref() {
+			Some("prestateTracer") => {}
+			_ => {
+				return Err(internal_err(
+					"unsupported tracer, only prestateTracer is supported",
+				))
+			}
+		}
+		let diff_mode = config
+			.tracer_config
+			.map(|tracer_config| tracer_config.diff_mode)
+			.unwrap_or_default();
+
+		self.prestate(hash, diff_mode)
+	}
+}
@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+use fp_rpc::ConvertTransaction;
+
+/// Transaction converters of successive runtimes, for chains whose runtimes don't provide the
+/// `ConvertTransactionRuntimeApi`, so that Ethereum transactions are still converted after
+/// runtime upgrades changing the encoding of the extrinsics.
+///
+/// Each converter is registered with the first spec version it supports, and converts the
+/// transactions of the runtimes up to the next registered spec version.
+pub struct TransactionConverters<E> {
+	converters: BTreeMap<u32, Box<dyn ConvertTransaction<E> + Send + Sync>>,
+}
+
+impl<E> TransactionConverters<E> {
+	/// Creates a registry with the converter of the runtimes from `spec_version` on.
+	pub fn new<C>(spec_version: u32, converter: C) -> Self
+	where
+		C: ConvertTransaction<E> + Send + Sync + 'static,
+	{
+		Self {
+			converters: BTreeMap::new(),
+		}
+		.with(spec_version, converter)
+	}
+
+	/// Registers the converter of the runtimes from `spec_version` on.
+	pub fn with<C>(mut self, spec_version: u32, converter: C) -> Self
+	where
+		C: ConvertTransaction<E> + Send + Sync + 'static,
+	{
+		self.converters.insert(spec_version, Box::new(converter));
+		self
+	}
+
+	fn converter_for(
+		&self,
+		spec_version: u32,
+	) -> Option<&(dyn ConvertTransaction<E> + Send + Sync)> {
+		self.converters
+			.range(..=spec_version)
+			.next_back()
+			.map(|(_, converter)| converter.as_ref())
+	}
+}
+
+impl<E> ConvertTransaction<E> for TransactionConverters<E> {
+	/// Converts `transaction` with the converter of the latest runtime.
+	fn convert_transaction(&self, transaction: ethereum::TransactionV2) -> E {
+		self.converters
+			.values()
+			.next_back()
+			.expect("a registry has at least one converter; qed")
+			.convert_transaction(transaction)
+	}
+
+	fn convert_transaction_for(
+		&self,
+		spec_version: u32,
+		transaction: ethereum::TransactionV2,
+	) -> Option<E> {
+		self.converter_for(spec_version)?
+			.convert_transaction_for(spec_version, transaction)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Converts every transaction to the same extrinsic.
+	struct Converter(&'static str);
+	impl ConvertTransaction<&'static str> for Converter {
+		fn convert_transaction(&self, _transaction: ethereum::TransactionV2) -> &'static str {
+			self.0
+		}
+	}
+
+	fn transaction() -> ethereum::TransactionV2 {
+		ethereum::TransactionV2::Legacy(ethereum::LegacyTransaction {
+			nonce: Default::default(),
+			gas_price: Default::default(),
+			gas_limit: Default::default(),
+			action: ethereum::TransactionAction::Create,
+			value: Default::default(),
+			input: Vec::new(),
+			signature: ethereum::TransactionSignature::new(
+				27,
+				ethereum_types::H256::repeat_byte(1),
+				ethereum_types::H256::repeat_byte(1),
+			)
+			.expect("valid signature"),
+		})
+	}
+
+	#[test]
+	fn converts_with_the_converter_of_the_spec_version() {
+		let converters = TransactionConverters::new(100, Converter("v100"))
+			.with(200, Converter("v200"))
+			.with(300, Converter("v300"));
+
+		assert_eq!(converters.convert_transaction_for(99, transaction()), None);
+		assert_eq!(
+			converters.convert_transaction_for(100, transaction()),
+			Some("v100")
+		);
+		assert_eq!(
+			converters.convert_transaction_for(250, transaction()),
+			Some("v200")
+		);
+		assert_eq!(
+			converters.convert_transaction_for(400, transaction()),
+			Some("v300")
+		);
+		assert_eq!(converters.convert_transaction(transaction()), "v300");
+	}
+}
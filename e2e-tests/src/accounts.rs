@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Accounts of the template node development chain.
+
+use ethers::{signers::LocalWallet, types::Address};
+
+/// Account endowed at genesis.
+pub const GENESIS_ACCOUNT: &str = "0x6be02d1d3665660d22ff9624b7be0551ee1ac91b";
+/// Private key of [`GENESIS_ACCOUNT`].
+pub const GENESIS_ACCOUNT_PRIVATE_KEY: &str =
+	"99b3c12287537e38c90a9219d4cb074a89a16e9cdb20bf85728ebd97c343e342";
+
+pub fn genesis_account() -> Address {
+	GENESIS_ACCOUNT.parse().expect("valid address; qed")
+}
+
+pub fn genesis_wallet() -> LocalWallet {
+	GENESIS_ACCOUNT_PRIVATE_KEY
+		.parse()
+		.expect("valid private key; qed")
+}
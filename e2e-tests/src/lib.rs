@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! End to end test framework for Frontier nodes.
+//!
+//! A [`Node`] spawns a node binary sealing its blocks manually, and is driven through its
+//! Ethereum RPC with [`ethers`]. The template node is used by default, other chains can run
+//! the same harness on their own node with a [`NodeConfig`].
+
+pub mod accounts;
+mod node;
+
+use std::{fmt, io, path::PathBuf};
+
+pub use ethers;
+use ethers::{providers::ProviderError, types::H256};
+
+pub use self::node::{Client, Node, NodeConfig};
+
+#[derive(Debug)]
+pub enum Error {
+	/// The node binary couldn't be started.
+	Spawn(PathBuf, io::Error),
+	/// The node exited or didn't get ready in time.
+	NotReady,
+	/// The node didn't import a sealed block in time.
+	BlockNotImported,
+	Io(io::Error),
+	Provider(ProviderError),
+	/// A transaction couldn't be signed or sent.
+	Middleware(String),
+	MissingReceipt(H256),
+	/// The receipt of a contract creation has no contract address.
+	MissingContractAddress(H256),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::Spawn(binary, err) => write!(f, "cannot start {}: {}", binary.display(), err),
+			Error::NotReady => write!(f, "the node didn't get ready"),
+			Error::BlockNotImported => write!(f, "the sealed block wasn't imported"),
+			Error::Io(err) => write!(f, "{}", err),
+			Error::Provider(err) => write!(f, "{}", err),
+			Error::Middleware(err) => write!(f, "{}", err),
+			Error::MissingReceipt(hash) => write!(f, "no receipt for transaction {:?}", hash),
+			Error::MissingContractAddress(hash) => {
+				write!(f, "transaction {:?} didn't create a contract", hash)
+			}
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+	fn from(err: io::Error) -> Self {
+		Error::Io(err)
+	}
+}
+
+impl From<ProviderError> for Error {
+	fn from(err: ProviderError) -> Self {
+		Error::Provider(err)
+	}
+}
@@ -0,0 +1,271 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+	io::{BufRead, BufReader},
+	net::TcpListener,
+	path::PathBuf,
+	process::{Child, Command, Stdio},
+	thread,
+	time::Duration,
+};
+
+use ethers::{
+	middleware::SignerMiddleware,
+	providers::{Http, Middleware, Provider},
+	signers::{LocalWallet, Signer},
+	types::{
+		transaction::eip2718::TypedTransaction, Address, Bytes, TransactionReceipt,
+		TransactionRequest, H256,
+	},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::oneshot;
+
+use crate::Error;
+
+/// Provider signing the transactions it sends with a local wallet.
+pub type Client = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+/// How a [`Node`] is started.
+///
+/// The node must seal its blocks on `engine_createBlock` requests, as the template node does
+/// when built with the `manual-seal` feature. The network and RPC ports are picked by the
+/// harness, so that nodes can run in parallel.
+#[derive(Clone, Debug)]
+pub struct NodeConfig {
+	binary: PathBuf,
+	args: Vec<String>,
+	ready_message: String,
+	timeout: Duration,
+}
+
+impl Default for NodeConfig {
+	/// The template node, from the `FRONTIER_NODE_BINARY` environment variable or else the
+	/// release build of the workspace.
+	fn default() -> Self {
+		let binary = std::env::var_os("FRONTIER_NODE_BINARY")
+			.map(PathBuf::from)
+			.unwrap_or_else(|| {
+				PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+					.join("../target/release/frontier-template-node")
+			});
+		NodeConfig {
+			binary,
+			args: [
+				"--chain=dev",
+				"--validator",
+				"--execution=Native",
+				"--no-telemetry",
+				"--no-prometheus",
+				"--sealing=Manual",
+				"--no-grandpa",
+				"--force-authoring",
+				"--tmp",
+			]
+			.into_iter()
+			.map(String::from)
+			.collect(),
+			ready_message: "Manual Seal Ready".into(),
+			timeout: Duration::from_secs(60),
+		}
+	}
+}
+
+impl NodeConfig {
+	pub fn binary(mut self, binary: impl Into<PathBuf>) -> Self {
+		self.binary = binary.into();
+		self
+	}
+
+	/// Replaces the arguments the node is started with, except its ports.
+	pub fn args<I, S>(mut self, args: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: Into<String>,
+	{
+		self.args = args.into_iter().map(Into::into).collect();
+		self
+	}
+
+	pub fn arg(mut self, arg: impl Into<String>) -> Self {
+		self.args.push(arg.into());
+		self
+	}
+
+	/// Log line telling the node is ready to seal blocks.
+	pub fn ready_message(mut self, ready_message: impl Into<String>) -> Self {
+		self.ready_message = ready_message.into();
+		self
+	}
+
+	/// How long to wait for the node to get ready.
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = timeout;
+		self
+	}
+}
+
+/// A running node, killed when dropped.
+///
+/// Its logs are printed when the `FRONTIER_LOG` environment variable is set.
+pub struct Node {
+	process: Child,
+	rpc_url: String,
+	provider: Provider<Http>,
+}
+
+impl Node {
+	/// Spawns the template node.
+	pub async fn spawn() -> Result<Self, Error> {
+		Self::spawn_with(NodeConfig::default()).await
+	}
+
+	pub async fn spawn_with(config: NodeConfig) -> Result<Self, Error> {
+		let rpc_port = free_port()?;
+		let mut process = Command::new(&config.binary)
+			.args(&config.args)
+			.arg(format!("--port={}", free_port()?))
+			.arg(format!("--rpc-port={}", rpc_port))
+			.arg(format!("--ws-port={}", free_port()?))
+			.stdout(Stdio::null())
+			.stderr(Stdio::piped())
+			.spawn()
+			.map_err(|err| Error::Spawn(config.binary.clone(), err))?;
+
+		// The logs keep being read once the node is ready, so that it never blocks on a full
+		// pipe.
+		let stderr = process.stderr.take().expect("stderr is piped; qed");
+		let (ready_sender, ready) = oneshot::channel();
+		let ready_message = config.ready_message.clone();
+		let display_logs = std::env::var_os("FRONTIER_LOG").is_some();
+		thread::spawn(move || {
+			let mut ready_sender = Some(ready_sender);
+			for line in BufReader::new(stderr).lines() {
+				let line = match line {
+					Ok(line) => line,
+					Err(_) => break,
+				};
+				if display_logs {
+					eprintln!("{}", line);
+				}
+				if line.contains(&ready_message) {
+					if let Some(ready_sender) = ready_sender.take() {
+						let _ = ready_sender.send(());
+					}
+				}
+			}
+		});
+
+		let rpc_url = format!("http://127.0.0.1:{}", rpc_port);
+		let provider = Provider::<Http>::try_from(rpc_url.as_str())
+			.expect("valid url; qed")
+			.interval(Duration::from_millis(100));
+		let node = Node {
+			process,
+			rpc_url,
+			provider,
+		};
+		match tokio::time::timeout(config.timeout, ready).await {
+			Ok(Ok(())) => Ok(node),
+			_ => Err(Error::NotReady),
+		}
+	}
+
+	pub fn rpc_url(&self) -> &str {
+		&self.rpc_url
+	}
+
+	pub fn provider(&self) -> &Provider<Http> {
+		&self.provider
+	}
+
+	/// Sends a raw RPC request.
+	pub async fn request<P, R>(&self, method: &str, params: P) -> Result<R, Error>
+	where
+		P: Serialize + Send + Sync + std::fmt::Debug,
+		R: DeserializeOwned + Send,
+	{
+		Ok(self.provider.request(method, params).await?)
+	}
+
+	/// Seals a block with the transactions of the pool, finalizes it and waits for its
+	/// Ethereum block to be served by the RPC.
+	pub async fn create_block(&self) -> Result<(), Error> {
+		let number = self.provider.get_block_number().await?;
+		let _: serde_json::Value = self
+			.request("engine_createBlock", (true, true, Option::<H256>::None))
+			.await?;
+		for _ in 0..50 {
+			if self.provider.get_block_number().await? > number {
+				return Ok(());
+			}
+			tokio::time::sleep(Duration::from_millis(100)).await;
+		}
+		Err(Error::BlockNotImported)
+	}
+
+	/// A client signing with the given wallet for the chain of the node.
+	pub async fn client(&self, wallet: LocalWallet) -> Result<Client, Error> {
+		let chain_id = self.provider.get_chainid().await?;
+		Ok(SignerMiddleware::new(
+			self.provider.clone(),
+			wallet.with_chain_id(chain_id.as_u64()),
+		))
+	}
+
+	/// Sends a transaction, seals a block with it and returns its receipt.
+	pub async fn send_and_seal<T>(
+		&self,
+		client: &Client,
+		tx: T,
+	) -> Result<TransactionReceipt, Error>
+	where
+		T: Into<TypedTransaction> + Send + Sync,
+	{
+		let hash = *client
+			.send_transaction(tx, None)
+			.await
+			.map_err(|err| Error::Middleware(err.to_string()))?;
+		self.create_block().await?;
+		self.provider
+			.get_transaction_receipt(hash)
+			.await?
+			.ok_or(Error::MissingReceipt(hash))
+	}
+
+	/// Deploys a contract from its creation bytecode and returns its address.
+	pub async fn deploy(&self, client: &Client, bytecode: Bytes) -> Result<Address, Error> {
+		let receipt = self
+			.send_and_seal(client, TransactionRequest::new().data(bytecode))
+			.await?;
+		receipt
+			.contract_address
+			.ok_or(Error::MissingContractAddress(receipt.transaction_hash))
+	}
+}
+
+impl Drop for Node {
+	fn drop(&mut self) {
+		let _ = self.process.kill();
+		let _ = self.process.wait();
+	}
+}
+
+fn free_port() -> Result<u16, Error> {
+	Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+}
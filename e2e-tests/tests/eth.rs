@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use frontier_e2e_tests::{
+	accounts::{genesis_account, genesis_wallet},
+	ethers::{
+		providers::Middleware,
+		types::{Address, Bytes, TransactionRequest, U256, U64},
+	},
+	Node,
+};
+
+// Creation code of a contract whose calls all return 42 as a word.
+const RETURN_42: &str = "0x600a600c600039600a6000f3602a60005260206000f3";
+
+#[tokio::test]
+#[ignore = "requires a built template node, see e2e-tests/README.md"]
+async fn sealing_a_block_increases_the_block_number() {
+	let node = Node::spawn().await.unwrap();
+	assert_eq!(
+		node.provider().get_block_number().await.unwrap(),
+		U64::zero()
+	);
+
+	node.create_block().await.unwrap();
+
+	assert_eq!(
+		node.provider().get_block_number().await.unwrap(),
+		U64::one()
+	);
+}
+
+#[tokio::test]
+#[ignore = "requires a built template node, see e2e-tests/README.md"]
+async fn transfer_is_credited_to_the_recipient() {
+	let node = Node::spawn().await.unwrap();
+	let client = node.client(genesis_wallet()).await.unwrap();
+	let recipient = Address::repeat_byte(0x11);
+
+	let receipt = node
+		.send_and_seal(
+			&client,
+			TransactionRequest::new()
+				.from(genesis_account())
+				.to(recipient)
+				.value(512),
+		)
+		.await
+		.unwrap();
+
+	assert_eq!(receipt.status, Some(U64::one()));
+	assert_eq!(
+		node.provider().get_balance(recipient, None).await.unwrap(),
+		U256::from(512)
+	);
+}
+
+#[tokio::test]
+#[ignore = "requires a built template node, see e2e-tests/README.md"]
+async fn deployed_contract_can_be_called() {
+	let node = Node::spawn().await.unwrap();
+	let client = node.client(genesis_wallet()).await.unwrap();
+
+	let contract = node
+		.deploy(&client, RETURN_42.parse::<Bytes>().unwrap())
+		.await
+		.unwrap();
+
+	assert_eq!(
+		node.provider().get_code(contract, None).await.unwrap(),
+		"0x602a60005260206000f3".parse::<Bytes>().unwrap()
+	);
+	let result = node
+		.provider()
+		.call(&TransactionRequest::new().to(contract).into(), None)
+		.await
+		.unwrap();
+	assert_eq!(U256::from_big_endian(&result), U256::from(42));
+}
@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use frame_benchmarking::{benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+
+use super::*;
+
+/// Registers a new session key on behalf of `who`, funded for the deposit.
+fn register<T: Config>(who: &T::AccountId) -> T::AuthorityId {
+	T::Currency::make_free_balance_be(who, BalanceOf::<T>::max_value() / 2u32.into());
+	let author = T::AuthorityId::generate_pair(None);
+	let account = H160::repeat_byte(0x11);
+	let signature = author
+		.sign(&Pallet::<T>::registration_payload(&account, who))
+		.expect("the session key is in the keystore");
+	Pallet::<T>::register(
+		RawOrigin::Signed(who.clone()).into(),
+		author.clone(),
+		account,
+		signature,
+	)
+	.expect("the session key isn't registered");
+	author
+}
+
+benchmarks! {
+	register {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+		let author = T::AuthorityId::generate_pair(None);
+		let account = H160::repeat_byte(0x11);
+		let signature = author
+			.sign(&Pallet::<T>::registration_payload(&account, &caller))
+			.expect("the session key is in the keystore");
+	}: _(RawOrigin::Signed(caller), author.clone(), account, signature)
+	verify {
+		assert_eq!(Pallet::<T>::author_account(&author), Some(account));
+	}
+
+	set_account {
+		let caller: T::AccountId = whitelisted_caller();
+		let author = register::<T>(&caller);
+		let account = H160::repeat_byte(0x22);
+	}: _(RawOrigin::Signed(caller), author.clone(), account)
+	verify {
+		assert_eq!(Pallet::<T>::author_account(&author), Some(account));
+	}
+
+	unregister {
+		let caller: T::AccountId = whitelisted_caller();
+		let author = register::<T>(&caller);
+	}: _(RawOrigin::Signed(caller), author.clone())
+	verify {
+		assert_eq!(Pallet::<T>::author_account(&author), None);
+	}
+}
+
+impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
@@ -0,0 +1,224 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Author mapping pallet
+//!
+//! A registry binding the session keys of block authors, such as their Aura or Babe key, to
+//! an H160 author account. The account is used as the `author`, or coinbase, of the Ethereum
+//! blocks they author, and receives the priority fees, instead of an address derived from the
+//! session key.
+//!
+//! A session key is registered by any signed account, with a signature of the session key
+//! proving its ownership. The signed message includes the genesis hash of the chain, so that
+//! it can't be replayed on another chain. The registering account reserves a deposit, released when it
+//! unregisters the key, and is the only one allowed to change the author account.
+//!
+//! The runtime uses the registry by setting the `FindAuthor` of the EVM pallet to
+//! [`FindMappedAuthor`], which needs a `FindAuthor` returning the session key of the author.
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+use codec::{Decode, Encode};
+use frame_support::{
+	traits::{Currency, FindAuthor, ReservableCurrency},
+	ConsensusEngineId,
+};
+use scale_info::TypeInfo;
+use sp_core::H160;
+use sp_runtime::{traits::Zero, RuntimeAppPublic, RuntimeDebug};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+pub use self::{pallet::*, weights::WeightInfo};
+
+/// Context of the message signed by a session key to register it.
+pub const REGISTRATION_CONTEXT: &[u8] = b"frontier-author-mapping";
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Author account of a session key, along with the account which registered it.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo)]
+pub struct Registration<AccountId, Balance> {
+	/// Author account of the session key.
+	pub account: H160,
+	/// Account which registered the session key.
+	pub owner: AccountId,
+	/// Deposit reserved from the owner.
+	pub deposit: Balance,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// Session key of the block authors.
+		type AuthorityId: Member + Parameter + RuntimeAppPublic;
+		/// Currency of the registration deposits.
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// Deposit reserved for the registration of a session key.
+		#[pallet::constant]
+		type Deposit: Get<BalanceOf<Self>>;
+		/// Weights of the calls.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Register the author account of a session key, with a signature by the session key
+		/// of [`Pallet::registration_payload`].
+		#[pallet::weight(T::WeightInfo::register())]
+		pub fn register(
+			origin: OriginFor<T>,
+			author: T::AuthorityId,
+			account: H160,
+			signature: <T::AuthorityId as RuntimeAppPublic>::Signature,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				!<Mappings<T>>::contains_key(&author),
+				Error::<T>::AlreadyRegistered
+			);
+			ensure!(
+				author.verify(&Self::registration_payload(&account, &who), &signature),
+				Error::<T>::BadSignature
+			);
+
+			let deposit = T::Deposit::get();
+			T::Currency::reserve(&who, deposit)?;
+			<Mappings<T>>::insert(
+				&author,
+				Registration {
+					account,
+					owner: who,
+					deposit,
+				},
+			);
+			Self::deposit_event(Event::AuthorRegistered(author, account));
+			Ok(())
+		}
+
+		/// Change the author account of a session key. Only its owner can change it.
+		#[pallet::weight(T::WeightInfo::set_account())]
+		pub fn set_account(
+			origin: OriginFor<T>,
+			author: T::AuthorityId,
+			account: H160,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			<Mappings<T>>::try_mutate(&author, |registration| {
+				let registration = registration.as_mut().ok_or(Error::<T>::NotRegistered)?;
+				ensure!(registration.owner == who, Error::<T>::NotOwner);
+				registration.account = account;
+				Ok::<_, DispatchError>(())
+			})?;
+			Self::deposit_event(Event::AccountSet(author, account));
+			Ok(())
+		}
+
+		/// Unregister a session key, releasing its deposit. Only its owner can unregister it.
+		#[pallet::weight(T::WeightInfo::unregister())]
+		pub fn unregister(origin: OriginFor<T>, author: T::AuthorityId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let registration = <Mappings<T>>::get(&author).ok_or(Error::<T>::NotRegistered)?;
+			ensure!(registration.owner == who, Error::<T>::NotOwner);
+
+			T::Currency::unreserve(&who, registration.deposit);
+			<Mappings<T>>::remove(&author);
+			Self::deposit_event(Event::AuthorUnregistered(author));
+			Ok(())
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A session key \[author\] has been registered with an author \[account\].
+		AuthorRegistered(T::AuthorityId, H160),
+		/// The author \[account\] of a session key \[author\] has been changed.
+		AccountSet(T::AuthorityId, H160),
+		/// A session key \[author\] has been unregistered.
+		AuthorUnregistered(T::AuthorityId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The session key is already registered.
+		AlreadyRegistered,
+		/// The signature isn't a signature of the registration by the session key.
+		BadSignature,
+		/// The session key isn't registered.
+		NotRegistered,
+		/// The session key was registered by another account.
+		NotOwner,
+	}
+
+	/// Registration of each session key.
+	#[pallet::storage]
+	#[pallet::getter(fn mapping)]
+	pub type Mappings<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AuthorityId,
+		Registration<T::AccountId, BalanceOf<T>>,
+		OptionQuery,
+	>;
+}
+
+impl<T: Config> Pallet<T> {
+	/// Message signed by a session key to register `account` as its author account, on behalf
+	/// of `who`, on the chain of the genesis hash.
+	pub fn registration_payload(account: &H160, who: &T::AccountId) -> Vec<u8> {
+		let genesis_hash = frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero());
+		(REGISTRATION_CONTEXT, genesis_hash, account, who).encode()
+	}
+
+	/// Author account of a session key, if it is registered.
+	pub fn author_account(author: &T::AuthorityId) -> Option<H160> {
+		<Mappings<T>>::get(author).map(|registration| registration.account)
+	}
+}
+
+/// Finds the author account of the session key found by `F`, if it is registered.
+pub struct FindMappedAuthor<T, F>(PhantomData<(T, F)>);
+
+impl<T: Config, F: FindAuthor<T::AuthorityId>> FindAuthor<H160> for FindMappedAuthor<T, F> {
+	fn find_author<'a, I>(digests: I) -> Option<H160>
+	where
+		I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+	{
+		Pallet::<T>::author_account(&F::find_author(digests)?)
+	}
+}
@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use super::*;
+use crate::mock::*;
+
+use frame_support::{assert_noop, assert_ok};
+use sp_core::H256;
+use sp_runtime::testing::UintAuthorityId;
+use std::str::FromStr;
+
+fn account() -> H160 {
+	H160::from_str("6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0").unwrap()
+}
+
+fn signature(
+	author: &UintAuthorityId,
+	account: &H160,
+	who: u64,
+) -> <UintAuthorityId as RuntimeAppPublic>::Signature {
+	author
+		.sign(&AuthorMapping::registration_payload(account, &who))
+		.unwrap()
+}
+
+/// Finds the session key 1 as the author of every block.
+struct FindFirstAuthor;
+impl FindAuthor<UintAuthorityId> for FindFirstAuthor {
+	fn find_author<'a, I>(_digests: I) -> Option<UintAuthorityId>
+	where
+		I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+	{
+		Some(UintAuthorityId(1))
+	}
+}
+
+#[test]
+fn registers_the_author_account_of_a_session_key() {
+	new_test_ext().execute_with(|| {
+		let author = UintAuthorityId(1);
+
+		assert_ok!(AuthorMapping::register(
+			Origin::signed(ALICE),
+			author.clone(),
+			account(),
+			signature(&author, &account(), ALICE),
+		));
+		assert_eq!(AuthorMapping::author_account(&author), Some(account()));
+		assert_eq!(Balances::reserved_balance(ALICE), 100);
+		assert_eq!(
+			FindMappedAuthor::<Test, FindFirstAuthor>::find_author(Vec::new()),
+			Some(account())
+		);
+
+		assert_noop!(
+			AuthorMapping::register(
+				Origin::signed(BOB),
+				author.clone(),
+				account(),
+				signature(&author, &account(), BOB),
+			),
+			Error::<Test>::AlreadyRegistered
+		);
+	});
+}
+
+#[test]
+fn rejects_signatures_of_other_registrations() {
+	new_test_ext().execute_with(|| {
+		let author = UintAuthorityId(1);
+
+		// Signed for another account.
+		assert_noop!(
+			AuthorMapping::register(
+				Origin::signed(BOB),
+				author.clone(),
+				account(),
+				signature(&author, &account(), ALICE),
+			),
+			Error::<Test>::BadSignature
+		);
+		// Signed by another session key.
+		assert_noop!(
+			AuthorMapping::register(
+				Origin::signed(ALICE),
+				author,
+				account(),
+				signature(&UintAuthorityId(2), &account(), ALICE),
+			),
+			Error::<Test>::BadSignature
+		);
+	});
+}
+
+#[test]
+fn rejects_signatures_for_other_chains() {
+	new_test_ext().execute_with(|| {
+		let author = UintAuthorityId(1);
+		let signature = signature(&author, &account(), ALICE);
+
+		// Another genesis hash.
+		frame_system::BlockHash::<Test>::insert(0, H256::repeat_byte(0x01));
+		assert_noop!(
+			AuthorMapping::register(Origin::signed(ALICE), author, account(), signature),
+			Error::<Test>::BadSignature
+		);
+	});
+}
+
+#[test]
+fn only_the_owner_changes_and_unregisters_a_session_key() {
+	new_test_ext().execute_with(|| {
+		let author = UintAuthorityId(1);
+		assert_ok!(AuthorMapping::register(
+			Origin::signed(ALICE),
+			author.clone(),
+			account(),
+			signature(&author, &account(), ALICE),
+		));
+
+		assert_noop!(
+			AuthorMapping::set_account(Origin::signed(BOB), author.clone(), H160::zero()),
+			Error::<Test>::NotOwner
+		);
+		assert_ok!(AuthorMapping::set_account(
+			Origin::signed(ALICE),
+			author.clone(),
+			H160::zero()
+		));
+		assert_eq!(AuthorMapping::author_account(&author), Some(H160::zero()));
+
+		assert_noop!(
+			AuthorMapping::unregister(Origin::signed(BOB), author.clone()),
+			Error::<Test>::NotOwner
+		);
+		assert_ok!(AuthorMapping::unregister(
+			Origin::signed(ALICE),
+			author.clone()
+		));
+		assert_eq!(AuthorMapping::author_account(&author), None);
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		assert_eq!(
+			FindMappedAuthor::<Test, FindFirstAuthor>::find_author(Vec::new()),
+			None
+		);
+	});
+}
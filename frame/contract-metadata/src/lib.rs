@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Contract metadata pallet
+//!
+//! A registry mapping EVM contracts to metadata, such as the hash or the IPFS CID of their
+//! verified sources, so that explorers can verify sources without a centralized service.
+//!
+//! The metadata of a contract is set by its deployer, who proves it deployed the contract by
+//! giving the nonce of its CREATE, or the salt and init code hash of its CREATE2. The registry
+//! is exposed to the RPC through the `ContractMetadataRuntimeApi`.
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use codec::{Decode, Encode};
+use rlp::RlpStream;
+use scale_info::TypeInfo;
+use sp_core::{H160, H256, U256};
+use sp_runtime::RuntimeDebug;
+use sp_std::vec::Vec;
+
+pub use fp_rpc::ContractMetadata;
+use pallet_evm::EnsureAddressOrigin;
+
+pub use self::pallet::*;
+
+/// How a contract was deployed by its deployer.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo)]
+pub enum Deployment {
+	/// With CREATE, at the given nonce of the deployer.
+	Create { nonce: U256 },
+	/// With CREATE2.
+	Create2 { salt: H256, init_code_hash: H256 },
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_evm::Config {
+		/// The overarching event type.
+		type Event: From<Event> + IsType<<Self as frame_system::Config>::Event>;
+		/// Allow the origin to act on behalf of the given deployer address.
+		type DeployerOrigin: EnsureAddressOrigin<Self::Origin>;
+		/// Maximum length of the metadata of a contract.
+		type MaxMetadataLength: Get<u32>;
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the metadata of a contract deployed by `deployer`, replacing the previous one.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1))]
+		pub fn set_metadata(
+			origin: OriginFor<T>,
+			contract: H160,
+			deployer: H160,
+			deployment: Deployment,
+			metadata: Vec<u8>,
+		) -> DispatchResult {
+			T::DeployerOrigin::ensure_address_origin(&deployer, origin)?;
+			ensure!(
+				metadata.len() <= T::MaxMetadataLength::get() as usize,
+				Error::<T>::MetadataTooLong
+			);
+			ensure!(
+				Self::deployed_address(deployer, &deployment) == contract,
+				Error::<T>::NotDeployer
+			);
+			ensure!(
+				pallet_evm::Pallet::<T>::has_code(&contract),
+				Error::<T>::NotAContract
+			);
+
+			<Metadata<T>>::insert(contract, ContractMetadata { deployer, metadata });
+			Self::deposit_event(Event::MetadataSet(contract));
+			Ok(())
+		}
+
+		/// Remove the metadata of a contract. Only its deployer can remove it.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn clear_metadata(origin: OriginFor<T>, contract: H160) -> DispatchResult {
+			let entry = <Metadata<T>>::get(contract).ok_or(Error::<T>::NoMetadata)?;
+			T::DeployerOrigin::ensure_address_origin(&entry.deployer, origin)?;
+
+			<Metadata<T>>::remove(contract);
+			Self::deposit_event(Event::MetadataCleared(contract));
+			Ok(())
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event {
+		/// The metadata of a \[contract\] has been set.
+		MetadataSet(H160),
+		/// The metadata of a \[contract\] has been removed.
+		MetadataCleared(H160),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The metadata is longer than `MaxMetadataLength`.
+		MetadataTooLong,
+		/// The deployment doesn't give the address of the contract.
+		NotDeployer,
+		/// There is no code at the address of the contract.
+		NotAContract,
+		/// The contract has no metadata.
+		NoMetadata,
+	}
+
+	/// Metadata of each contract, along with its deployer.
+	#[pallet::storage]
+	#[pallet::getter(fn metadata)]
+	pub type Metadata<T: Config> =
+		StorageMap<_, Blake2_128Concat, H160, ContractMetadata, OptionQuery>;
+}
+
+impl<T: Config> Pallet<T> {
+	/// Address of a contract deployed by `deployer`, as computed by the EVM.
+	pub fn deployed_address(deployer: H160, deployment: &Deployment) -> H160 {
+		match deployment {
+			Deployment::Create { nonce } => {
+				let mut stream = RlpStream::new_list(2);
+				stream.append(&deployer);
+				stream.append(nonce);
+				H256::from(sp_io::hashing::keccak_256(&stream.out())).into()
+			}
+			Deployment::Create2 {
+				salt,
+				init_code_hash,
+			} => pallet_evm::Pallet::<T>::create2_address(deployer, *salt, *init_code_hash),
+		}
+	}
+}
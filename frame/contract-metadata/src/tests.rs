@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::mock::*;
+
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::DispatchError;
+use std::str::FromStr;
+
+fn deployer() -> H160 {
+	H160::from_str("6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0").unwrap()
+}
+
+/// Address of the contract created by `deployer` at nonce 1, with code.
+fn contract() -> H160 {
+	let contract = H160::from_str("343c43a37d37dff08ae8c4a11544c718abb4fcf8").unwrap();
+	EVM::create_account(contract, vec![0x00]);
+	contract
+}
+
+const DEPLOYMENT: Deployment = Deployment::Create {
+	nonce: U256([1, 0, 0, 0]),
+};
+
+#[test]
+fn deployed_address_follows_create() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			Registry::deployed_address(
+				deployer(),
+				&Deployment::Create {
+					nonce: U256::zero()
+				}
+			),
+			H160::from_str("cd234a471b72ba2f1ccf0a70fcaba648a5eecd8d").unwrap()
+		);
+	});
+}
+
+#[test]
+fn deployer_can_set_and_clear_metadata() {
+	new_test_ext().execute_with(|| {
+		let contract = contract();
+
+		assert_ok!(Registry::set_metadata(
+			Origin::signed(deployer()),
+			contract,
+			deployer(),
+			DEPLOYMENT,
+			b"ipfs-cid".to_vec()
+		));
+		assert_eq!(
+			Registry::metadata(contract),
+			Some(fp_rpc::ContractMetadata {
+				deployer: deployer(),
+				metadata: b"ipfs-cid".to_vec(),
+			})
+		);
+
+		assert_ok!(Registry::clear_metadata(
+			Origin::signed(deployer()),
+			contract
+		));
+		assert_eq!(Registry::metadata(contract), None);
+	});
+}
+
+#[test]
+fn only_the_deployer_can_set_metadata() {
+	new_test_ext().execute_with(|| {
+		let contract = contract();
+		let other = H160::repeat_byte(0x11);
+
+		assert_noop!(
+			Registry::set_metadata(
+				Origin::signed(other),
+				contract,
+				deployer(),
+				DEPLOYMENT,
+				vec![]
+			),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Registry::set_metadata(Origin::signed(other), contract, other, DEPLOYMENT, vec![]),
+			Error::<Test>::NotDeployer
+		);
+	});
+}
+
+#[test]
+fn only_the_deployer_can_clear_metadata() {
+	new_test_ext().execute_with(|| {
+		let contract = contract();
+		assert_ok!(Registry::set_metadata(
+			Origin::signed(deployer()),
+			contract,
+			deployer(),
+			DEPLOYMENT,
+			vec![1]
+		));
+
+		assert_noop!(
+			Registry::clear_metadata(Origin::signed(H160::repeat_byte(0x11)), contract),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn metadata_requires_a_contract() {
+	new_test_ext().execute_with(|| {
+		let contract = H160::from_str("343c43a37d37dff08ae8c4a11544c718abb4fcf8").unwrap();
+
+		assert_noop!(
+			Registry::set_metadata(
+				Origin::signed(deployer()),
+				contract,
+				deployer(),
+				DEPLOYMENT,
+				vec![]
+			),
+			Error::<Test>::NotAContract
+		);
+	});
+}
+
+#[test]
+fn metadata_length_is_bounded() {
+	new_test_ext().execute_with(|| {
+		let contract = contract();
+
+		assert_noop!(
+			Registry::set_metadata(
+				Origin::signed(deployer()),
+				contract,
+				deployer(),
+				DEPLOYMENT,
+				vec![0; MaxMetadataLength::get() as usize + 1]
+			),
+			Error::<Test>::MetadataTooLong
+		);
+	});
+}
@@ -26,13 +26,18 @@
 
 #[cfg(all(feature = "std", test))]
 mod mock;
+mod proof;
 #[cfg(all(feature = "std", test))]
 mod tests;
 
+use ethereum::EnvelopedEncodable;
 use ethereum_types::{Bloom, BloomInput, H160, H256, H64, U256};
 use evm::ExitReason;
 use fp_consensus::{PostLog, PreLog, FRONTIER_ENGINE_ID};
-use fp_evm::CallOrCreateInfo;
+use fp_evm::{
+	Account, CallOrCreateInfo, CheckEvmTransaction, CheckEvmTransactionConfig,
+	CheckEvmTransactionInput, InvalidEvmTransaction,
+};
 use fp_storage::{EthereumStorageSchema, PALLET_ETHEREUM_SCHEMA};
 #[cfg(feature = "try-runtime")]
 use frame_support::traits::OnRuntimeUpgradeHelpersExt;
@@ -40,27 +45,38 @@ use frame_support::{
 	codec::{Decode, Encode},
 	dispatch::DispatchResultWithPostInfo,
 	scale_info::TypeInfo,
-	traits::{EnsureOrigin, Get, PalletInfoAccess},
+	traits::{EnsureOrigin, Get, IsSubType, PalletInfoAccess},
+	transactional,
 	weights::{DispatchInfo, Pays, PostDispatchInfo, Weight},
+	BoundedVec,
 };
 use frame_system::{pallet_prelude::OriginFor, CheckWeight, WeightInfo};
-use pallet_evm::{BlockHashMapping, FeeCalculator, GasWeightMapping, Runner};
+use pallet_evm::{BlockHashMapping, GasWeightMapping, OnChargeEVMTransaction, Runner};
 use sha3::{Digest, Keccak256};
 use sp_runtime::{
 	generic::DigestItem,
-	traits::{DispatchInfoOf, Dispatchable, One, Saturating, UniqueSaturatedInto, Zero},
+	traits::{
+		DispatchInfoOf, Dispatchable, One, Saturating, SignedExtension, UniqueSaturatedInto, Zero,
+	},
 	transaction_validity::{
-		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransactionBuilder,
+		InvalidTransaction, TransactionLongevity, TransactionPriority, TransactionValidity,
+		TransactionValidityError, ValidTransaction, ValidTransactionBuilder,
 	},
-	DispatchErrorWithPostInfo, RuntimeDebug,
+	DispatchError, DispatchErrorWithPostInfo, RuntimeDebug,
 };
-use sp_std::{marker::PhantomData, prelude::*};
+use sp_std::{collections::btree_map::BTreeMap, marker::PhantomData, prelude::*};
 
 pub use ethereum::{
 	AccessListItem, BlockV2 as Block, LegacyTransactionMessage, Log, ReceiptV3 as Receipt,
 	TransactionAction, TransactionV2 as Transaction,
 };
-pub use fp_rpc::TransactionStatus;
+pub use fp_rpc::{
+	DeadlineTransaction, TransactionStatus, DEADLINE_ADDRESS, DEADLINE_TRANSACTION_TYPE,
+};
+
+/// Gas cost of the `ecrecover` precompile, the weight of recovering the sender of a
+/// transaction of a `transact_batch` being the weight of this gas.
+pub const ECRECOVER_GAS: u64 = 3_000;
 
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub enum RawOrigin {
@@ -91,6 +107,26 @@ struct TransactionData {
 	access_list: Vec<(H160, Vec<H256>)>,
 }
 
+impl From<&TransactionData> for CheckEvmTransactionInput {
+	fn from(t: &TransactionData) -> Self {
+		CheckEvmTransactionInput {
+			chain_id: t.chain_id,
+			to: match t.action {
+				TransactionAction::Call(to) => Some(to),
+				TransactionAction::Create => None,
+			},
+			input: t.input.clone(),
+			nonce: t.nonce,
+			gas_limit: t.gas_limit,
+			gas_price: t.gas_price,
+			max_fee_per_gas: t.max_fee_per_gas,
+			max_priority_fee_per_gas: t.max_priority_fee_per_gas,
+			value: t.value,
+			access_list: t.access_list.clone(),
+		}
+	}
+}
+
 pub struct EnsureEthereumTransaction;
 impl<O: Into<Result<RawOrigin, O>> + From<RawOrigin>> EnsureOrigin<O>
 	for EnsureEthereumTransaction
@@ -137,8 +173,14 @@ where
 	pub fn pre_dispatch_self_contained(
 		&self,
 		origin: &H160,
+		dispatch_info: &DispatchInfoOf<T::Call>,
+		len: usize,
 	) -> Option<Result<(), TransactionValidityError>> {
 		if let Call::transact { transaction } = self {
+			if let Err(e) = CheckWeight::<T>::do_pre_dispatch(dispatch_info, len) {
+				return Some(Err(e));
+			}
+
 			Some(Pallet::<T>::validate_transaction_in_block(
 				*origin,
 				transaction,
@@ -183,6 +225,56 @@ pub mod pallet {
 		type Event: From<Event> + IsType<<Self as frame_system::Config>::Event>;
 		/// How Ethereum state root is calculated.
 		type StateRoot: Get<H256>;
+		/// Maximum number of Ethereum transactions in a `transact_batch` call.
+		type MaxBatchTransactions: Get<u32>;
+		/// Ordering of the Ethereum transactions in the blocks.
+		type TransactionOrdering: TransactionOrdering;
+
+		/// Number of blocks an Ethereum transaction stays valid in the pool.
+		fn transaction_longevity() -> TransactionLongevity {
+			TransactionLongevity::MAX
+		}
+
+		/// Whether Ethereum transactions are gossiped to the other nodes.
+		fn propagate_transactions() -> bool {
+			true
+		}
+
+		/// Maximum number of Ethereum transactions a sender can have in the pool with a
+		/// nonce higher than its account nonce. Unbounded when `None`.
+		fn max_future_transactions_per_sender() -> Option<u32> {
+			None
+		}
+
+		/// Extra data of the Ethereum block headers. Ethereum tooling usually expects it to
+		/// be at most 32 bytes long.
+		fn block_extra_data() -> Vec<u8> {
+			Vec::new()
+		}
+
+		/// Difficulty of the Ethereum block headers.
+		fn block_difficulty() -> U256 {
+			U256::zero()
+		}
+
+		/// Nonce of the Ethereum block headers.
+		fn block_nonce() -> H64 {
+			H64::default()
+		}
+
+		/// Whether legacy transactions signed without chain id (pre EIP-155) are accepted.
+		/// Such transactions can be replayed on any chain, but allow deploying contracts at
+		/// the same address on every chain, as the deterministic deployment proxy does.
+		fn allow_unprotected_transactions() -> bool {
+			false
+		}
+
+		/// Whether the deadlines of the Frontier specific [`DeadlineTransaction`]s, carried by
+		/// their access list, are enforced. Otherwise the deadline item is a plain access list
+		/// item.
+		fn deadline_transactions() -> bool {
+			false
+		}
 	}
 
 	#[pallet::pallet]
@@ -262,6 +354,9 @@ pub mod pallet {
 		OriginFor<T>: Into<Result<RawOrigin, OriginFor<T>>>,
 	{
 		/// Transact an Ethereum transaction.
+		///
+		/// The declared weight is derived from the transaction gas limit. On completion the
+		/// actual weight is derived from the gas used, so the difference is refunded to the block.
 		#[pallet::weight(<T as pallet_evm::Config>::GasWeightMapping::gas_to_weight(
 			Pallet::<T>::transaction_data(transaction).gas_limit.unique_saturated_into()
 		))]
@@ -278,6 +373,41 @@ pub mod pallet {
 
 			Self::apply_validated_transaction(source, transaction)
 		}
+
+		/// Transact a batch of Ethereum transactions, in order and atomically.
+		///
+		/// All the transactions are validated before the first one is applied, and the whole
+		/// batch fails if one of them can't be applied. The declared weight is derived from the
+		/// sum of the transaction gas limits, and the actual weight from the gas used, both
+		/// with the recovery of the senders. The caller pays the fees of the weight, and each
+		/// sender the gas of its transaction. The batch is validated in the pool by
+		/// [`CheckBatchTransactions`].
+		#[pallet::weight(Pallet::<T>::batch_weight(transactions))]
+		#[transactional]
+		pub fn transact_batch(
+			origin: OriginFor<T>,
+			transactions: BoundedVec<Transaction, T::MaxBatchTransactions>,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			ensure!(
+				fp_consensus::find_pre_log(&frame_system::Pallet::<T>::digest()).is_err(),
+				Error::<T>::PreLogExists,
+			);
+
+			let sources = Self::validate_batch(&transactions)?;
+			let mut weight = Self::recovery_weight(transactions.len());
+			for (source, transaction) in sources.into_iter().zip(transactions) {
+				// A failed batch is charged its declared weight.
+				let info =
+					Self::apply_validated_transaction(source, transaction).map_err(|e| e.error)?;
+				weight = weight.saturating_add(info.actual_weight.unwrap_or(0));
+			}
+
+			Ok(PostDispatchInfo {
+				actual_weight: Some(weight),
+				pays_fee: Pays::Yes,
+			})
+		}
 	}
 
 	#[pallet::event]
@@ -293,6 +423,8 @@ pub mod pallet {
 		InvalidSignature,
 		/// Pre-log is present, therefore transact is not allowed.
 		PreLogExists,
+		/// A transaction of the batch is invalid.
+		InvalidBatchTransaction,
 	}
 
 	/// Current building block's transactions and receipts.
@@ -333,6 +465,19 @@ pub mod pallet {
 }
 
 impl<T: Config> Pallet<T> {
+	/// Gas charged before the execution of `transaction`, with the EVM configuration of the
+	/// runtime. The gas overrides don't change it.
+	pub fn intrinsic_gas(transaction: &Transaction) -> u64 {
+		let transaction_data = Self::transaction_data(transaction);
+		fp_evm::intrinsic_gas(
+			<T as pallet_evm::Config>::config(),
+			matches!(transaction_data.action, TransactionAction::Create),
+			&transaction_data.input,
+			&transaction_data.access_list,
+		)
+		.unwrap_or(u64::MAX)
+	}
+
 	fn transaction_data(transaction: &Transaction) -> TransactionData {
 		match transaction {
 			Transaction::Legacy(t) => TransactionData {
@@ -437,7 +582,7 @@ impl<T: Config> Pallet<T> {
 		}
 
 		let ommers = Vec::<ethereum::Header>::new();
-		let receipts_root = ethereum::util::ordered_trie_root(receipts.iter().map(rlp::encode));
+		let receipts_root = Self::receipts_root(&receipts);
 		let partial_header = ethereum::PartialHeader {
 			parent_hash: if block_number > U256::zero() {
 				BlockHash::<T>::get(block_number - 1)
@@ -448,16 +593,16 @@ impl<T: Config> Pallet<T> {
 			state_root: T::StateRoot::get(),
 			receipts_root,
 			logs_bloom,
-			difficulty: U256::zero(),
+			difficulty: T::block_difficulty(),
 			number: block_number,
 			gas_limit: T::BlockGasLimit::get(),
 			gas_used: cumulative_gas_used,
 			timestamp: UniqueSaturatedInto::<u64>::unique_saturated_into(
 				pallet_timestamp::Pallet::<T>::get(),
 			),
-			extra_data: Vec::new(),
+			extra_data: T::block_extra_data(),
 			mix_hash: H256::default(),
-			nonce: H64::default(),
+			nonce: T::block_nonce(),
 		};
 		let block = ethereum::Block::new(partial_header, transactions.clone(), ommers);
 
@@ -475,6 +620,12 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
+	/// Root of the receipts trie, keyed by the RLP encoded index of each receipt and valued by
+	/// its EIP-2718 envelope, as in Ethereum.
+	fn receipts_root(receipts: &[Receipt]) -> H256 {
+		ethereum::util::ordered_trie_root(receipts.iter().map(EnvelopedEncodable::encode))
+	}
+
 	fn logs_bloom(logs: Vec<Log>, bloom: &mut Bloom) {
 		for log in logs {
 			bloom.accrue(BloomInput::Raw(&log.address[..]));
@@ -484,127 +635,235 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
-	// Common controls to be performed in the same way by the pool and the
-	// State Transition Function (STF).
-	// This is the case for all controls except those concerning the nonce.
-	fn validate_transaction_common(
-		origin: H160,
+	// Controls to be performed in the same way by the pool and the
+	// State Transition Function (STF), except those concerning the nonce.
+	// See `fp_evm::CheckEvmTransaction`.
+	fn check_evm_transaction<E: From<InvalidEvmTransaction>>(
+		origin: &H160,
 		transaction_data: &TransactionData,
-	) -> Result<(U256, u64), TransactionValidityError> {
-		let gas_limit = transaction_data.gas_limit;
-
-		// We must ensure a transaction can pay the cost of its data bytes.
-		// If it can't it should not be included in a block.
-		let mut gasometer = evm::gasometer::Gasometer::new(
-			gas_limit.low_u64(),
-			<T as pallet_evm::Config>::config(),
+	) -> CheckEvmTransaction<'static, E> {
+		let (base_fee, _) = pallet_evm::Pallet::<T>::base_fee_for(origin);
+		let check = CheckEvmTransaction::new(
+			CheckEvmTransactionConfig {
+				// The gas overrides don't change the intrinsic gas of the transactions.
+				evm_config: <T as pallet_evm::Config>::config(),
+				block_gas_limit: T::BlockGasLimit::get(),
+				base_fee,
+				chain_id: T::ChainId::get(),
+				allow_unprotected_txs: T::allow_unprotected_transactions(),
+				max_future_transactions: T::max_future_transactions_per_sender(),
+			},
+			transaction_data.into(),
 		);
-		let transaction_cost = match transaction_data.action {
-			TransactionAction::Call(_) => evm::gasometer::call_transaction_cost(
-				&transaction_data.input,
-				&transaction_data.access_list,
-			),
-			TransactionAction::Create => evm::gasometer::create_transaction_cost(
-				&transaction_data.input,
-				&transaction_data.access_list,
-			),
-		};
-		if gasometer.record_transaction(transaction_cost).is_err() {
-			return Err(InvalidTransaction::Custom(
-				TransactionValidationError::InvalidGasLimit as u8,
-			)
-			.into());
-		}
-
-		if let Some(chain_id) = transaction_data.chain_id {
-			if chain_id != T::ChainId::get() {
-				return Err(InvalidTransaction::Custom(
-					TransactionValidationError::InvalidChainId as u8,
-				)
-				.into());
+		// A sponsor of the target may pay a share of the base fee, as in the runner.
+		let check = match transaction_data.action {
+			TransactionAction::Call(target) => {
+				let (sponsor, _) = pallet_evm::Pallet::<T>::fee_sponsor_for(
+					origin,
+					&target,
+					base_fee,
+					transaction_data.gas_limit.unique_saturated_into(),
+				);
+				match sponsor {
+					Some((_, _, sponsored_fee)) => check.with_sponsored_fee(sponsored_fee),
+					None => check,
+				}
 			}
+			TransactionAction::Create => check,
+		};
+		if <T as pallet_evm::Config>::OnChargeTransaction::pays_from_balance() {
+			check
+		} else {
+			check.with_fee_payment(|fee| {
+				<T as pallet_evm::Config>::OnChargeTransaction::can_pay_fee(origin, fee)
+			})
 		}
+	}
 
-		if gas_limit >= T::BlockGasLimit::get() {
-			return Err(InvalidTransaction::Custom(
-				TransactionValidationError::InvalidGasLimit as u8,
-			)
-			.into());
-		}
-
-		let (base_fee, _) = T::FeeCalculator::min_gas_price();
-		let mut priority = 0;
-
-		let max_fee_per_gas = match (
+	fn transaction_priority(transaction_data: &TransactionData, base_fee: U256) -> u64 {
+		match (
 			transaction_data.gas_price,
 			transaction_data.max_fee_per_gas,
 			transaction_data.max_priority_fee_per_gas,
 		) {
 			// Legacy or EIP-2930 transaction.
-			// Handle priority here. On legacy transaction everything in gas_price except
-			// the current base_fee is considered a tip to the miner and thus the priority.
+			// On legacy transaction everything in gas_price except the current base_fee
+			// is considered a tip to the miner and thus the priority.
 			(Some(gas_price), None, None) => {
-				priority = gas_price.saturating_sub(base_fee).unique_saturated_into();
-				gas_price
+				gas_price.saturating_sub(base_fee).unique_saturated_into()
 			}
-			// EIP-1559 transaction without tip.
-			(None, Some(max_fee_per_gas), None) => max_fee_per_gas,
 			// EIP-1559 transaction with tip.
-			(None, Some(max_fee_per_gas), Some(max_priority_fee_per_gas)) => {
-				if max_priority_fee_per_gas > max_fee_per_gas {
-					return Err(InvalidTransaction::Custom(
-						TransactionValidationError::MaxFeePerGasTooLow as u8,
-					)
-					.into());
-				}
-				priority = max_fee_per_gas
-					.saturating_sub(base_fee)
-					.min(max_priority_fee_per_gas)
-					.unique_saturated_into();
-				max_fee_per_gas
+			(None, Some(max_fee_per_gas), Some(max_priority_fee_per_gas)) => max_fee_per_gas
+				.saturating_sub(base_fee)
+				.min(max_priority_fee_per_gas)
+				.unique_saturated_into(),
+			// EIP-1559 transaction without tip.
+			_ => 0,
+		}
+	}
+
+	fn batch_weight(transactions: &[Transaction]) -> Weight {
+		transactions.iter().fold(
+			Self::recovery_weight(transactions.len()),
+			|weight: Weight, transaction| {
+				weight.saturating_add(T::GasWeightMapping::gas_to_weight(
+					Self::transaction_data(transaction)
+						.gas_limit
+						.unique_saturated_into(),
+				))
+			},
+		)
+	}
+
+	// Weight of recovering the senders of `count` transactions.
+	fn recovery_weight(count: usize) -> Weight {
+		T::GasWeightMapping::gas_to_weight(ECRECOVER_GAS).saturating_mul(count as Weight)
+	}
+
+	// Validates the transactions of a batch as if they were in the block one after the other,
+	// and returns their senders. Their balances are only checked against the balances before
+	// the batch, the batch fails on application if a sender runs out of balance.
+	fn validate_batch(transactions: &[Transaction]) -> Result<Vec<H160>, DispatchError> {
+		let mut accounts = BTreeMap::<H160, Account>::new();
+		transactions
+			.iter()
+			.map(|transaction| -> Result<H160, DispatchError> {
+				let source =
+					Self::recover_signer(transaction).ok_or(Error::<T>::InvalidSignature)?;
+				Self::check_address_migrated(&source)
+					.map_err(|_| Error::<T>::InvalidBatchTransaction)?;
+				Self::check_deadline(transaction)
+					.map_err(|_| Error::<T>::InvalidBatchTransaction)?;
+				let who = accounts
+					.entry(source)
+					.or_insert_with(|| pallet_evm::Pallet::<T>::account_basic(&source).0);
+
+				Self::check_evm_transaction::<InvalidEvmTransaction>(
+					&source,
+					&Self::transaction_data(transaction),
+				)
+				.validate_in_block_for(who)
+				.map_err(|_| Error::<T>::InvalidBatchTransaction)?;
+				who.nonce = who.nonce.saturating_add(U256::one());
+
+				Ok(source)
+			})
+			.collect()
+	}
+
+	// Controls that must be performed by the pool for a batch. The batch provides the tags of
+	// its transactions, so that it conflicts with the same transactions submitted alone.
+	fn validate_batch_in_pool(transactions: &[Transaction]) -> TransactionValidity {
+		let sources = Self::validate_batch(transactions).map_err(|_| {
+			InvalidTransaction::Custom(TransactionValidationError::InvalidBatchTransaction as u8)
+		})?;
+
+		let mut longevity = T::transaction_longevity();
+		let mut builder = ValidTransactionBuilder::default().propagate(T::propagate_transactions());
+		for (source, transaction) in sources.into_iter().zip(transactions) {
+			// A batch is dropped from the pool once the deadline of one of its transactions
+			// passed.
+			if let Some(remaining_blocks) = Self::check_deadline(transaction)? {
+				longevity = longevity.min(remaining_blocks);
 			}
-			_ => return Err(InvalidTransaction::Payment.into()),
-		};
+			builder = builder.and_provides((source, Self::transaction_data(transaction).nonce));
+		}
+		builder.longevity(longevity).build()
+	}
+
+	/// Validate an Ethereum transaction as the pool does, returning the exact failure cause.
+	pub fn check_transaction(transaction: &Transaction) -> Result<(), InvalidEvmTransaction> {
+		let origin =
+			Self::recover_signer(transaction).ok_or(InvalidEvmTransaction::InvalidSignature)?;
+		let transaction_data = Pallet::<T>::transaction_data(transaction);
+		let (who, _) = pallet_evm::Pallet::<T>::account_basic(&origin);
+
+		Self::check_evm_transaction::<InvalidEvmTransaction>(&origin, &transaction_data)
+			.validate_in_pool_for(&who)
+			.map(|_| ())
+	}
 
-		if max_fee_per_gas < base_fee {
-			return Err(InvalidTransaction::Payment.into());
+	// Checks the deadline carried by the access list of `transaction`, if any, see
+	// `DeadlineTransaction`, when the deadlines are enforced: it must not be passed. Returns
+	// the number of blocks the transaction can still be included in.
+	fn check_deadline(
+		transaction: &Transaction,
+	) -> Result<Option<TransactionLongevity>, TransactionValidityError> {
+		if !T::deadline_transactions() {
+			return Ok(None);
 		}
 
-		let fee = max_fee_per_gas.saturating_mul(gas_limit);
-		let (account_data, _) = pallet_evm::Pallet::<T>::account_basic(&origin);
-		let total_payment = transaction_data.value.saturating_add(fee);
-		if account_data.balance < total_payment {
-			return Err(InvalidTransaction::Payment.into());
+		let access_list = match transaction {
+			Transaction::Legacy(_) => return Ok(None),
+			Transaction::EIP2930(t) => &t.access_list,
+			Transaction::EIP1559(t) => &t.access_list,
+		};
+		let valid_until_block = match fp_rpc::deadline_of(access_list) {
+			Ok(Some(valid_until_block)) => valid_until_block,
+			Ok(None) => return Ok(None),
+			Err(()) => return Err(InvalidTransaction::Call.into()),
+		};
+
+		let block_number: u64 = UniqueSaturatedInto::<u64>::unique_saturated_into(
+			frame_system::Pallet::<T>::block_number(),
+		);
+		if block_number > valid_until_block {
+			return Err(InvalidTransaction::Custom(
+				TransactionValidationError::DeadlinePassed as u8,
+			)
+			.into());
 		}
+		Ok(Some(valid_until_block - block_number + 1))
+	}
 
-		Ok((account_data.nonce, priority))
+	// Checks that the account of `origin` has been migrated to the current address mapping,
+	// see `pallet_evm::Config::is_address_migrated`.
+	fn check_address_migrated(origin: &H160) -> Result<(), TransactionValidityError> {
+		if !<T as pallet_evm::Config>::is_address_migrated(origin) {
+			return Err(InvalidTransaction::Custom(
+				TransactionValidationError::AccountNotMigrated as u8,
+			)
+			.into());
+		}
+		Ok(())
 	}
 
 	// Controls that must be performed by the pool.
-	// The controls common with the State Transition Function (STF) are in
-	// the function `validate_transaction_common`.
 	fn validate_transaction_in_pool(
 		origin: H160,
 		transaction: &Transaction,
 	) -> TransactionValidity {
+		Self::check_address_migrated(&origin)?;
+		let remaining_blocks = Self::check_deadline(transaction)?;
 		let transaction_data = Pallet::<T>::transaction_data(transaction);
 		let transaction_nonce = transaction_data.nonce;
+		let (who, _) = pallet_evm::Pallet::<T>::account_basic(&origin);
 
-		let (account_nonce, priority) =
-			Self::validate_transaction_common(origin, &transaction_data)?;
+		let check =
+			Self::check_evm_transaction::<InvalidTransactionWrapper>(&origin, &transaction_data);
+		check.validate_in_pool_for(&who).map_err(|e| e.0)?;
 
-		if transaction_nonce < account_nonce {
-			return Err(InvalidTransaction::Stale.into());
-		}
+		let priority = T::TransactionOrdering::priority(
+			&origin,
+			transaction_nonce,
+			transaction,
+			Self::transaction_priority(&transaction_data, check.config.base_fee),
+		);
 
-		// The tag provides and requires must be filled correctly according to the nonce.
+		// The tag provides and requires must be filled correctly according to the nonce,
+		// so that the transactions of a sender are included in the nonce order.
 		let mut builder = ValidTransactionBuilder::default()
 			.and_provides((origin, transaction_nonce))
-			.priority(priority);
+			.priority(priority)
+			// A deadline transaction is dropped from the pool once its deadline passed.
+			.longevity(
+				remaining_blocks.map_or(T::transaction_longevity(), |remaining_blocks| {
+					remaining_blocks.min(T::transaction_longevity())
+				}),
+			)
+			.propagate(T::propagate_transactions());
 
-		// In the context of the pool, a transaction with
-		// too high a nonce is still considered valid
-		if transaction_nonce > account_nonce {
+		if transaction_nonce > who.nonce {
 			if let Some(prev_nonce) = transaction_nonce.checked_sub(1.into()) {
 				builder = builder.and_requires((origin, prev_nonce))
 			}
@@ -717,6 +976,59 @@ impl<T: Config> Pallet<T> {
 		})
 	}
 
+	/// Append to the current block a pseudo transaction from and to `address`, carrying
+	/// `logs`, so that they can be followed through the Ethereum RPC as contract logs.
+	///
+	/// The transaction is never executed: it is a legacy call using no gas, with a placeholder
+	/// signature, the block number as nonce and its index in the block as input. It must be
+	/// appended before the `on_finalize` of this pallet.
+	pub fn deposit_pseudo_transaction(address: H160, logs: Vec<Log>) {
+		let pending = Pending::<T>::get();
+		let transaction_index = pending.len() as u32;
+		let transaction = Transaction::Legacy(ethereum::LegacyTransaction {
+			nonce: U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(
+				frame_system::Pallet::<T>::block_number(),
+			)),
+			gas_price: U256::zero(),
+			gas_limit: U256::zero(),
+			action: TransactionAction::Call(address),
+			value: U256::zero(),
+			input: transaction_index.to_be_bytes().to_vec(),
+			signature: ethereum::TransactionSignature::new(
+				27,
+				H256::from_low_u64_be(1),
+				H256::from_low_u64_be(1),
+			)
+			.expect("the placeholder signature is in range; qed"),
+		});
+
+		let mut logs_bloom = Bloom::default();
+		Self::logs_bloom(logs.clone(), &mut logs_bloom);
+		let status = TransactionStatus {
+			transaction_hash: transaction.hash(),
+			transaction_index,
+			from: address,
+			to: Some(address),
+			contract_address: None,
+			logs: logs.clone(),
+			logs_bloom,
+		};
+		let cumulative_gas_used = match pending.last() {
+			Some((_, _, Receipt::Legacy(d) | Receipt::EIP2930(d) | Receipt::EIP1559(d))) => {
+				d.used_gas
+			}
+			None => U256::zero(),
+		};
+		let receipt = Receipt::Legacy(ethereum::EIP658ReceiptData {
+			status_code: 1,
+			used_gas: cumulative_gas_used,
+			logs_bloom,
+			logs,
+		});
+
+		Pending::<T>::append((transaction, status, receipt));
+	}
+
 	/// Get the transaction status with given index.
 	pub fn current_transaction_statuses() -> Option<Vec<TransactionStatus>> {
 		CurrentTransactionStatuses::<T>::get()
@@ -737,6 +1049,16 @@ impl<T: Config> Pallet<T> {
 		CurrentReceipts::<T>::get()
 	}
 
+	/// Get the proof of inclusion of the receipt at the given index in the receipts root of
+	/// the current block.
+	pub fn current_receipt_proof(index: u32) -> Option<Vec<Vec<u8>>> {
+		let receipts = CurrentReceipts::<T>::get()?
+			.iter()
+			.map(EnvelopedEncodable::encode)
+			.collect::<Vec<_>>();
+		proof::ordered_trie_proof(&receipts, index as usize)
+	}
+
 	/// Execute an Ethereum transaction.
 	pub fn execute(
 		from: H160,
@@ -806,6 +1128,7 @@ impl<T: Config> Pallet<T> {
 			}
 		};
 
+		let config = config.unwrap_or_else(pallet_evm::Pallet::<T>::evm_config);
 		let is_transactional = true;
 		match action {
 			ethereum::TransactionAction::Call(target) => {
@@ -820,7 +1143,7 @@ impl<T: Config> Pallet<T> {
 					nonce,
 					access_list,
 					is_transactional,
-					config.as_ref().unwrap_or_else(|| T::config()),
+					&config,
 				) {
 					Ok(res) => res,
 					Err(e) => {
@@ -847,7 +1170,7 @@ impl<T: Config> Pallet<T> {
 					nonce,
 					access_list,
 					is_transactional,
-					config.as_ref().unwrap_or_else(|| T::config()),
+					&config,
 				) {
 					Ok(res) => res,
 					Err(e) => {
@@ -874,21 +1197,18 @@ impl<T: Config> Pallet<T> {
 		origin: H160,
 		transaction: &Transaction,
 	) -> Result<(), TransactionValidityError> {
+		Self::check_address_migrated(&origin)?;
+		Self::check_deadline(transaction)?;
 		let transaction_data = Pallet::<T>::transaction_data(transaction);
-		let transaction_nonce = transaction_data.nonce;
-		let (account_nonce, _) = Self::validate_transaction_common(origin, &transaction_data)?;
+		let (who, _) = pallet_evm::Pallet::<T>::account_basic(&origin);
 
 		// In the context of the block, a transaction with a nonce that is
 		// too high should be considered invalid and make the whole block invalid.
-		if transaction_nonce > account_nonce {
-			Err(TransactionValidityError::Invalid(
-				InvalidTransaction::Future,
-			))
-		} else if transaction_nonce < account_nonce {
-			Err(TransactionValidityError::Invalid(InvalidTransaction::Stale))
-		} else {
-			Ok(())
-		}
+		Self::check_evm_transaction::<InvalidTransactionWrapper>(&origin, &transaction_data)
+			.validate_in_block_for(&who)
+			.map_err(|e| e.0)?;
+
+		Ok(())
 	}
 
 	pub fn migrate_block_v0_to_v2() -> Weight {
@@ -978,6 +1298,177 @@ impl<T: Config> BlockHashMapping for EthereumBlockHashMapping<T> {
 	}
 }
 
+/// Ordering of the Ethereum transactions in the blocks, through their priority in the pool.
+///
+/// The transactions of a sender are always included in the nonce order, whatever their
+/// priority. The pool only replaces a transaction by another one of the same sender and nonce
+/// with a higher priority, so the priority must grow with the tip for a given sender and nonce.
+pub trait TransactionOrdering {
+	/// The priority of the transaction of `source` with `nonce`, given the priority derived from
+	/// its tip.
+	fn priority(
+		source: &H160,
+		nonce: U256,
+		transaction: &Transaction,
+		tip_priority: TransactionPriority,
+	) -> TransactionPriority;
+}
+
+/// Orders the transactions by tip, as Ethereum does.
+impl TransactionOrdering for () {
+	fn priority(
+		_: &H160,
+		_: U256,
+		_: &Transaction,
+		tip_priority: TransactionPriority,
+	) -> TransactionPriority {
+		tip_priority
+	}
+}
+
+/// Orders the transactions by arrival in the pool among the ones whose tip priorities have the
+/// same bit length.
+///
+/// The priority is the bit length of the tip priority, so replacing a transaction takes twice
+/// its tip, as does running before a transaction of another sender.
+pub struct FifoOrdering;
+impl TransactionOrdering for FifoOrdering {
+	fn priority(
+		_: &H160,
+		_: U256,
+		_: &Transaction,
+		tip_priority: TransactionPriority,
+	) -> TransactionPriority {
+		(TransactionPriority::BITS - tip_priority.leading_zeros()).into()
+	}
+}
+
+/// Orders the transactions by a hash of their sender, of their nonce and of the best block
+/// hash, so that neither the tip nor the arrival order lets a transaction run before the ones
+/// of other senders.
+///
+/// The hash is the high half of the priority and the saturated tip priority the low half, which
+/// only orders the transactions of a sender with the same nonce, for replacements. A sender
+/// can't change the hash by changing the content of its transaction, only by sending it from
+/// another account.
+///
+/// The priority is computed when the transaction is validated by the pool, the order of the
+/// transactions staying in the pool over several blocks only changes when they are revalidated.
+pub struct ShuffledOrdering<T>(PhantomData<T>);
+impl<T: frame_system::Config> TransactionOrdering for ShuffledOrdering<T> {
+	fn priority(
+		source: &H160,
+		nonce: U256,
+		_: &Transaction,
+		tip_priority: TransactionPriority,
+	) -> TransactionPriority {
+		let seed = frame_system::Pallet::<T>::parent_hash();
+		let mut nonce_bytes = [0u8; 32];
+		nonce.to_big_endian(&mut nonce_bytes);
+		let key = Keccak256::digest(&[seed.as_ref(), source.as_bytes(), &nonce_bytes].concat());
+		let mut shuffle = [0u8; 4];
+		shuffle.copy_from_slice(&key[..4]);
+		TransactionPriority::from(u32::from_be_bytes(shuffle)) << 32
+			| tip_priority.min(u32::MAX.into())
+	}
+}
+
+/// Validates the `transact_batch` calls in the pool, checking their Ethereum transactions as
+/// their dispatch does. Other calls are left to the other signed extensions.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct CheckBatchTransactions<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> CheckBatchTransactions<T> {
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config + Send + Sync> Default for CheckBatchTransactions<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Config + Send + Sync> sp_std::fmt::Debug for CheckBatchTransactions<T> {
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "CheckBatchTransactions")
+	}
+}
+
+impl<T: Config + Send + Sync> SignedExtension for CheckBatchTransactions<T>
+where
+	<T as frame_system::Config>::Call: IsSubType<Call<T>>,
+{
+	const IDENTIFIER: &'static str = "CheckBatchTransactions";
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::Call;
+	type AdditionalSigned = ();
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		_who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		match call.is_sub_type() {
+			Some(Call::transact_batch { transactions }) => {
+				Pallet::<T>::validate_batch_in_pool(transactions)
+			}
+			_ => Ok(ValidTransaction::default()),
+		}
+	}
+
+	fn pre_dispatch(
+		self,
+		_who: &Self::AccountId,
+		_call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> Result<(), TransactionValidityError> {
+		// The dispatch of the batch validates it again, failing as a whole.
+		Ok(())
+	}
+}
+
+/// The transaction validity error reported to the pool and the block builder for a
+/// validation failure.
+pub struct InvalidTransactionWrapper(InvalidTransaction);
+
+impl From<InvalidEvmTransaction> for InvalidTransactionWrapper {
+	fn from(validation_error: InvalidEvmTransaction) -> Self {
+		InvalidTransactionWrapper(match validation_error {
+			InvalidEvmTransaction::GasLimitTooLow | InvalidEvmTransaction::GasLimitTooHigh => {
+				InvalidTransaction::Custom(TransactionValidationError::InvalidGasLimit as u8)
+			}
+			InvalidEvmTransaction::InvalidChainId => {
+				InvalidTransaction::Custom(TransactionValidationError::InvalidChainId as u8)
+			}
+			InvalidEvmTransaction::InvalidSignature => {
+				InvalidTransaction::Custom(TransactionValidationError::InvalidSignature as u8)
+			}
+			InvalidEvmTransaction::PriorityFeeTooHigh => {
+				InvalidTransaction::Custom(TransactionValidationError::MaxFeePerGasTooLow as u8)
+			}
+			InvalidEvmTransaction::InvalidPaymentInput
+			| InvalidEvmTransaction::GasPriceTooLow
+			| InvalidEvmTransaction::BalanceTooLow => InvalidTransaction::Payment,
+			InvalidEvmTransaction::TxNonceTooLow => InvalidTransaction::Stale,
+			InvalidEvmTransaction::TxNonceTooHigh => InvalidTransaction::Future,
+			InvalidEvmTransaction::TooManyFutureTransactions => InvalidTransaction::Custom(
+				TransactionValidationError::TooManyFutureTransactions as u8,
+			),
+		})
+	}
+}
+
 #[repr(u8)]
 enum TransactionValidationError {
 	#[allow(dead_code)]
@@ -986,4 +1477,8 @@ enum TransactionValidationError {
 	InvalidSignature,
 	InvalidGasLimit,
 	MaxFeePerGasTooLow,
+	TooManyFutureTransactions,
+	DeadlinePassed,
+	InvalidBatchTransaction,
+	AccountNotMigrated,
 }
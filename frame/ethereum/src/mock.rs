@@ -31,13 +31,16 @@ use sp_core::{H160, H256, U256};
 use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, IdentityLookup},
-	AccountId32,
+	AccountId32, Permill,
 };
 
 use super::*;
 use crate::IntermediateStateRoot;
 
-pub type SignedExtra = (frame_system::CheckSpecVersion<Test>,);
+pub type SignedExtra = (
+	frame_system::CheckSpecVersion<Test>,
+	frame_system::CheckWeight<Test>,
+);
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test, (), SignedExtra>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -169,15 +172,126 @@ impl pallet_evm::Config for Test {
 	type OnChargeTransaction = ();
 	type FindAuthor = FindAuthorTruncated;
 	type BlockHashMapping = crate::EthereumBlockHashMapping<Self>;
+
+	fn fee_sponsor(_source: &H160, _target: &H160) -> Option<(H160, Permill)> {
+		FeeSponsor::get()
+	}
+
+	fn is_address_migrated(address: &H160) -> bool {
+		!UnmigratedAddresses::get().contains(address)
+	}
+}
+
+parameter_types! {
+	pub static EthereumTransactionLongevity: TransactionLongevity = TransactionLongevity::MAX;
+	pub static PropagateEthereumTransactions: bool = true;
+	pub static MaxFutureTransactionsPerSender: Option<u32> = None;
+	pub static BlockExtraData: Vec<u8> = Vec::new();
+	pub static BlockDifficulty: U256 = U256::zero();
+	pub static BlockNonce: H64 = H64::default();
+	pub static AllowUnprotectedTransactions: bool = false;
+	pub static DeadlineTransactions: bool = false;
+	pub static PausedSender: Option<AccountId32> = None;
+	pub static FeeSponsor: Option<(H160, Permill)> = None;
+	pub static UnmigratedAddresses: Vec<H160> = Vec::new();
 }
 
 impl crate::Config for Test {
 	type Event = Event;
 	type StateRoot = IntermediateStateRoot<Self>;
+	type MaxBatchTransactions = ConstU32<16>;
+	type TransactionOrdering = ();
+
+	fn transaction_longevity() -> TransactionLongevity {
+		EthereumTransactionLongevity::get()
+	}
+
+	fn propagate_transactions() -> bool {
+		PropagateEthereumTransactions::get()
+	}
+
+	fn max_future_transactions_per_sender() -> Option<u32> {
+		MaxFutureTransactionsPerSender::get()
+	}
+
+	fn block_extra_data() -> Vec<u8> {
+		BlockExtraData::get()
+	}
+
+	fn block_difficulty() -> U256 {
+		BlockDifficulty::get()
+	}
+
+	fn block_nonce() -> H64 {
+		BlockNonce::get()
+	}
+
+	fn allow_unprotected_transactions() -> bool {
+		AllowUnprotectedTransactions::get()
+	}
+
+	fn deadline_transactions() -> bool {
+		DeadlineTransactions::get()
+	}
+}
+
+/// Rejects the extrinsics of `PausedSender`.
+#[derive(Clone, Default, Eq, PartialEq, Encode, Decode, TypeInfo, sp_runtime::RuntimeDebug)]
+pub struct CheckNotPaused;
+
+impl CheckNotPaused {
+	fn check(who: &AccountId32) -> TransactionValidity {
+		if PausedSender::get().as_ref() == Some(who) {
+			return Err(InvalidTransaction::Call.into());
+		}
+		Ok(Default::default())
+	}
+}
+
+impl sp_runtime::traits::SignedExtension for CheckNotPaused {
+	const IDENTIFIER: &'static str = "CheckNotPaused";
+	type AccountId = AccountId32;
+	type Call = Call;
+	type AdditionalSigned = ();
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		_call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		Self::check(who)
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		_call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> Result<(), TransactionValidityError> {
+		Self::check(who).map(|_| ())
+	}
+}
+
+/// The account of the sender of a self-contained call.
+pub struct AccountOf;
+
+impl sp_runtime::traits::Convert<H160, AccountId32> for AccountOf {
+	fn convert(address: H160) -> AccountId32 {
+		HashedAddressMapping::into_account_id(address)
+	}
 }
 
 impl fp_self_contained::SelfContainedCall for Call {
 	type SignedInfo = H160;
+	type Extensions = fp_self_contained::SenderExtensions<CheckNotPaused, AccountOf>;
 
 	fn is_self_contained(&self) -> bool {
 		match self {
@@ -208,9 +322,11 @@ impl fp_self_contained::SelfContainedCall for Call {
 	fn pre_dispatch_self_contained(
 		&self,
 		info: &Self::SignedInfo,
+		dispatch_info: &DispatchInfoOf<Call>,
+		len: usize,
 	) -> Option<Result<(), TransactionValidityError>> {
 		match self {
-			Call::Ethereum(call) => call.pre_dispatch_self_contained(info),
+			Call::Ethereum(call) => call.pre_dispatch_self_contained(info, dispatch_info, len),
 			_ => None,
 		}
 	}
@@ -298,22 +414,24 @@ pub struct LegacyUnsignedTransaction {
 }
 
 impl LegacyUnsignedTransaction {
-	fn signing_rlp_append(&self, s: &mut RlpStream) {
-		s.begin_list(9);
+	fn signing_rlp_append(&self, s: &mut RlpStream, chain_id: Option<u64>) {
+		s.begin_list(if chain_id.is_some() { 9 } else { 6 });
 		s.append(&self.nonce);
 		s.append(&self.gas_price);
 		s.append(&self.gas_limit);
 		s.append(&self.action);
 		s.append(&self.value);
 		s.append(&self.input);
-		s.append(&ChainId::get());
-		s.append(&0u8);
-		s.append(&0u8);
+		if let Some(chain_id) = chain_id {
+			s.append(&chain_id);
+			s.append(&0u8);
+			s.append(&0u8);
+		}
 	}
 
-	fn signing_hash(&self) -> H256 {
+	fn signing_hash(&self, chain_id: Option<u64>) -> H256 {
 		let mut stream = RlpStream::new();
-		self.signing_rlp_append(&mut stream);
+		self.signing_rlp_append(&mut stream, chain_id);
 		H256::from_slice(&Keccak256::digest(&stream.out()).as_slice())
 	}
 
@@ -322,7 +440,16 @@ impl LegacyUnsignedTransaction {
 	}
 
 	pub fn sign_with_chain_id(&self, key: &H256, chain_id: u64) -> Transaction {
-		let hash = self.signing_hash();
+		self.sign_with_optional_chain_id(key, Some(chain_id))
+	}
+
+	/// Signs the transaction as before EIP-155, without replay protection.
+	pub fn sign_without_chain_id(&self, key: &H256) -> Transaction {
+		self.sign_with_optional_chain_id(key, None)
+	}
+
+	fn sign_with_optional_chain_id(&self, key: &H256, chain_id: Option<u64>) -> Transaction {
+		let hash = self.signing_hash(chain_id);
 		let msg = libsecp256k1::Message::parse(hash.as_fixed_bytes());
 		let s = libsecp256k1::sign(
 			&msg,
@@ -331,7 +458,10 @@ impl LegacyUnsignedTransaction {
 		let sig = s.0.serialize();
 
 		let sig = TransactionSignature::new(
-			s.1.serialize() as u64 % 2 + chain_id * 2 + 35,
+			match chain_id {
+				Some(chain_id) => s.1.serialize() as u64 % 2 + chain_id * 2 + 35,
+				None => s.1.serialize() as u64 % 2 + 27,
+			},
 			H256::from_slice(&sig[0..32]),
 			H256::from_slice(&sig[32..64]),
 		)
@@ -410,6 +540,15 @@ pub struct EIP1559UnsignedTransaction {
 
 impl EIP1559UnsignedTransaction {
 	pub fn sign(&self, secret: &H256, chain_id: Option<u64>) -> Transaction {
+		self.sign_with_access_list(secret, chain_id, vec![])
+	}
+
+	pub fn sign_with_access_list(
+		&self,
+		secret: &H256,
+		chain_id: Option<u64>,
+		access_list: Vec<ethereum::AccessListItem>,
+	) -> Transaction {
 		let secret = {
 			let mut sk: [u8; 32] = [0u8; 32];
 			sk.copy_from_slice(&secret[0..]);
@@ -425,7 +564,7 @@ impl EIP1559UnsignedTransaction {
 			action: self.action,
 			value: self.value,
 			input: self.input.clone(),
-			access_list: vec![],
+			access_list,
 		};
 		let signing_message = libsecp256k1::Message::parse_slice(&msg.hash()[..]).unwrap();
 
@@ -448,4 +587,15 @@ impl EIP1559UnsignedTransaction {
 			s,
 		})
 	}
+
+	pub fn sign_until(&self, secret: &H256, valid_until_block: u64) -> crate::DeadlineTransaction {
+		match self.sign_with_access_list(
+			secret,
+			None,
+			vec![fp_rpc::deadline_item(valid_until_block)],
+		) {
+			Transaction::EIP1559(transaction) => crate::DeadlineTransaction { transaction },
+			_ => unreachable!(),
+		}
+	}
 }
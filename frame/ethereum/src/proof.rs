@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Merkle Patricia trie inclusion proofs of the Ethereum block data.
+
+use rlp::RlpStream;
+use sha3::{Digest, Keccak256};
+use sp_std::prelude::*;
+
+/// Returns the RLP encoded nodes, from the root, proving the inclusion of the value at
+/// `index` in the trie computed by `ethereum::util::ordered_trie_root`, i.e. keyed by the RLP
+/// encoded index of each value.
+///
+/// As in `eth_getProof`, nodes embedded in their parent are not part of the proof.
+pub fn ordered_trie_proof<V: AsRef<[u8]>>(values: &[V], index: usize) -> Option<Vec<Vec<u8>>> {
+	if index >= values.len() {
+		return None;
+	}
+
+	let mut items = values
+		.iter()
+		.enumerate()
+		.map(|(i, value)| (nibbles(&rlp::encode(&i)), value.as_ref()))
+		.collect::<Vec<_>>();
+	items.sort_by(|a, b| a.0.cmp(&b.0));
+
+	let key = nibbles(&rlp::encode(&index));
+	let mut proof = Vec::new();
+	encode_node(&items, 0, Some(&key[..]), &mut proof);
+	Some(proof)
+}
+
+fn nibbles(bytes: &[u8]) -> Vec<u8> {
+	bytes.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect()
+}
+
+/// Hex-prefix encoding of a path.
+fn hex_prefix(path: &[u8], is_leaf: bool) -> Vec<u8> {
+	let flag = if is_leaf { 2 } else { 0 };
+	let mut encoded = Vec::with_capacity(path.len() / 2 + 1);
+	let rest = if path.len() % 2 == 1 {
+		encoded.push(((flag + 1) << 4) | path[0]);
+		&path[1..]
+	} else {
+		encoded.push(flag << 4);
+		path
+	};
+	encoded.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+	encoded
+}
+
+/// Appends the reference to a child node: the node itself if its encoding is shorter than a
+/// hash, its hash otherwise.
+fn append_child(stream: &mut RlpStream, encoded: &[u8]) {
+	if encoded.len() < 32 {
+		stream.append_raw(encoded, 1);
+	} else {
+		stream.append(&Keccak256::digest(encoded).to_vec());
+	}
+}
+
+/// RLP encodes the node holding `items`, whose keys share their first `depth` nibbles.
+/// Nodes are added to `proof` when `path`, the rest of the proven key, goes through them.
+fn encode_node(
+	items: &[(Vec<u8>, &[u8])],
+	depth: usize,
+	path: Option<&[u8]>,
+	proof: &mut Vec<Vec<u8>>,
+) -> Vec<u8> {
+	let slot = proof.len();
+
+	let encoded = if let [(key, value)] = items {
+		let mut stream = RlpStream::new_list(2);
+		stream.append(&hex_prefix(&key[depth..], true));
+		stream.append(&value.to_vec());
+		stream.out().to_vec()
+	} else {
+		let first = &items[0].0;
+		let shared = (depth..first.len())
+			.take_while(|&i| items.iter().all(|(key, _)| key.get(i) == Some(&first[i])))
+			.count();
+
+		if shared > 0 {
+			let prefix = &first[depth..depth + shared];
+			let child_path = path.and_then(|path| path.strip_prefix(prefix));
+			let child = encode_node(items, depth + shared, child_path, proof);
+
+			let mut stream = RlpStream::new_list(2);
+			stream.append(&hex_prefix(prefix, false));
+			append_child(&mut stream, &child);
+			stream.out().to_vec()
+		} else {
+			let mut stream = RlpStream::new_list(17);
+			for nibble in 0..16u8 {
+				let start = items.partition_point(|(key, _)| key.get(depth) < Some(&nibble));
+				let end = items.partition_point(|(key, _)| key.get(depth) <= Some(&nibble));
+				if start == end {
+					stream.append_empty_data();
+					continue;
+				}
+				let child_path = path
+					.filter(|path| path.first() == Some(&nibble))
+					.map(|path| &path[1..]);
+				let child = encode_node(&items[start..end], depth + 1, child_path, proof);
+				append_child(&mut stream, &child);
+			}
+			match items.iter().find(|(key, _)| key.len() == depth) {
+				Some((_, value)) => stream.append(&value.to_vec()),
+				None => stream.append_empty_data(),
+			};
+			stream.out().to_vec()
+		}
+	};
+
+	if path.is_some() && (depth == 0 || encoded.len() >= 32) {
+		proof.insert(slot, encoded.clone());
+	}
+	encoded
+}
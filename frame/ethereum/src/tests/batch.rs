@@ -0,0 +1,216 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Batched Ethereum transactions tests.
+
+use super::*;
+use crate::{CheckBatchTransactions, Pending, ECRECOVER_GAS};
+use frame_support::{codec::Encode, weights::Pays};
+use sp_runtime::traits::SignedExtension;
+
+fn transfer_transaction(account: &AccountInfo, nonce: u64) -> Transaction {
+	LegacyUnsignedTransaction {
+		nonce: U256::from(nonce),
+		gas_price: U256::from(1),
+		gas_limit: U256::from(0x100000),
+		action: TransactionAction::Call(H160::repeat_byte(0x11)),
+		value: U256::from(1),
+		input: Vec::new(),
+	}
+	.sign(&account.private_key)
+}
+
+#[test]
+fn batch_should_apply_transactions_in_order() {
+	let (pairs, mut ext) = new_test_ext(2);
+	let alice = &pairs[0];
+	let bob = &pairs[1];
+
+	ext.execute_with(|| {
+		let transactions = vec![
+			transfer_transaction(alice, 0),
+			transfer_transaction(bob, 0),
+			transfer_transaction(alice, 1),
+		];
+		assert_ok!(Ethereum::transact_batch(
+			Origin::signed(alice.account_id.clone()),
+			transactions.clone().try_into().unwrap(),
+		));
+
+		assert_eq!(EVM::account_basic(&alice.address).0.nonce, U256::from(2));
+		assert_eq!(EVM::account_basic(&bob.address).0.nonce, U256::from(1));
+		let pending = Pending::<Test>::get();
+		assert_eq!(
+			pending.into_iter().map(|(t, _, _)| t).collect::<Vec<_>>(),
+			transactions
+		);
+	});
+}
+
+#[test]
+fn batch_with_an_invalid_transaction_should_apply_none() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		// The second transaction has a nonce too high.
+		let transactions = vec![
+			transfer_transaction(alice, 0),
+			transfer_transaction(alice, 2),
+		];
+		assert!(Ethereum::transact_batch(
+			Origin::signed(alice.account_id.clone()),
+			transactions.try_into().unwrap(),
+		)
+		.is_err());
+
+		assert_eq!(EVM::account_basic(&alice.address).0.nonce, U256::zero());
+		assert!(Pending::<Test>::get().is_empty());
+	});
+}
+
+#[test]
+fn batch_with_an_unmigrated_sender_should_be_rejected() {
+	let (pairs, mut ext) = new_test_ext(2);
+	let alice = &pairs[0];
+	let bob = &pairs[1];
+
+	ext.execute_with(|| {
+		UnmigratedAddresses::set(vec![bob.address]);
+		let transactions = vec![transfer_transaction(alice, 0), transfer_transaction(bob, 0)];
+		assert_err!(
+			Ethereum::validate_batch(&transactions),
+			crate::Error::<Test>::InvalidBatchTransaction
+		);
+		assert!(Ethereum::transact_batch(
+			Origin::signed(alice.account_id.clone()),
+			transactions.try_into().unwrap(),
+		)
+		.is_err());
+
+		assert_eq!(EVM::account_basic(&alice.address).0.nonce, U256::zero());
+		assert!(Pending::<Test>::get().is_empty());
+	});
+}
+
+#[test]
+fn batch_should_be_rolled_back_when_a_transaction_fails() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		// Both transactions are valid against the balance before the batch, but the second
+		// one can't pay once the first one is applied.
+		let balance = EVM::account_basic(&alice.address).0.balance;
+		let mut unsigned = LegacyUnsignedTransaction {
+			nonce: U256::zero(),
+			gas_price: U256::from(1),
+			gas_limit: U256::from(21_000),
+			action: TransactionAction::Call(H160::repeat_byte(0x11)),
+			value: balance / 2,
+			input: Vec::new(),
+		};
+		let first = unsigned.sign(&alice.private_key);
+		unsigned.nonce = U256::one();
+		let second = unsigned.sign(&alice.private_key);
+
+		assert!(Ethereum::transact_batch(
+			Origin::signed(alice.account_id.clone()),
+			vec![first, second].try_into().unwrap(),
+		)
+		.is_err());
+
+		assert_eq!(EVM::account_basic(&alice.address).0.nonce, U256::zero());
+		assert_eq!(EVM::account_basic(&alice.address).0.balance, balance);
+		assert!(Pending::<Test>::get().is_empty());
+	});
+}
+
+#[test]
+fn batch_should_be_charged_to_the_caller() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let transactions = vec![
+			transfer_transaction(alice, 0),
+			transfer_transaction(alice, 1),
+		];
+		let call = crate::Call::<Test>::transact_batch {
+			transactions: transactions.clone().try_into().unwrap(),
+		};
+		// The gas limits of the transactions, and the recovery of their senders.
+		assert_eq!(
+			call.get_dispatch_info().weight,
+			2 * 0x100000 + 2 * ECRECOVER_GAS
+		);
+
+		let post_info = Ethereum::transact_batch(
+			Origin::signed(alice.account_id.clone()),
+			transactions.try_into().unwrap(),
+		)
+		.unwrap();
+		assert_eq!(post_info.pays_fee, Pays::Yes);
+		assert_eq!(
+			post_info.actual_weight,
+			Some(2 * 21_000 + 2 * ECRECOVER_GAS)
+		);
+	});
+}
+
+#[test]
+fn batch_should_be_validated_in_the_pool() {
+	let (pairs, mut ext) = new_test_ext(2);
+	let alice = &pairs[0];
+	let bob = &pairs[1];
+
+	ext.execute_with(|| {
+		let validate = |transactions: Vec<Transaction>| {
+			let call: Call = crate::Call::<Test>::transact_batch {
+				transactions: transactions.try_into().unwrap(),
+			}
+			.into();
+			CheckBatchTransactions::<Test>::new().validate(
+				&alice.account_id,
+				&call,
+				&call.get_dispatch_info(),
+				0,
+			)
+		};
+
+		// The batch conflicts with its transactions submitted alone.
+		let valid = validate(vec![
+			transfer_transaction(alice, 0),
+			transfer_transaction(bob, 0),
+		])
+		.unwrap();
+		assert_eq!(
+			valid.provides,
+			vec![
+				(alice.address, U256::zero()).encode(),
+				(bob.address, U256::zero()).encode(),
+			]
+		);
+
+		// The second transaction has a nonce too high.
+		assert!(validate(vec![
+			transfer_transaction(alice, 0),
+			transfer_transaction(alice, 2)
+		])
+		.is_err());
+	});
+}
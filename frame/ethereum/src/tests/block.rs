@@ -0,0 +1,253 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ethereum block header and proofs tests.
+//!
+//! Expected values follow go-ethereum's `types.DeriveSha` and `types.CreateBloom`: trie keys
+//! are the RLP encoded indexes and trie values the EIP-2718 envelopes.
+
+use super::*;
+use crate::{Pending, Receipt, TransactionStatus, H64};
+use ethereum::{
+	EIP1559Transaction, EIP2930Transaction, EIP658ReceiptData, EnvelopedEncodable,
+	LegacyTransaction, Log, TransactionSignature,
+};
+use ethereum_types::Bloom;
+use sha3::{Digest, Keccak256};
+
+const EMPTY_TRIE_ROOT: &str = "56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421";
+
+fn h256(hex: &str) -> H256 {
+	H256::from_str(hex).unwrap()
+}
+
+fn transactions() -> Vec<Transaction> {
+	let to = H160::repeat_byte(0x33);
+	let r = H256::repeat_byte(0x44);
+	let s = H256::repeat_byte(0x55);
+	vec![
+		Transaction::Legacy(LegacyTransaction {
+			nonce: U256::zero(),
+			gas_price: U256::from(1_000_000_000),
+			gas_limit: U256::from(21_000),
+			action: TransactionAction::Call(to),
+			value: U256::one(),
+			input: vec![],
+			signature: TransactionSignature::new(37, r, s).unwrap(),
+		}),
+		Transaction::EIP2930(EIP2930Transaction {
+			chain_id: 1,
+			nonce: U256::one(),
+			gas_price: U256::from(1_000_000_000),
+			gas_limit: U256::from(50_000),
+			action: TransactionAction::Call(to),
+			value: U256::zero(),
+			input: vec![0xde, 0xad],
+			access_list: vec![],
+			odd_y_parity: false,
+			r,
+			s,
+		}),
+		Transaction::EIP1559(EIP1559Transaction {
+			chain_id: 1,
+			nonce: U256::from(2),
+			max_priority_fee_per_gas: U256::from(1_000_000_000),
+			max_fee_per_gas: U256::from(2_000_000_000),
+			gas_limit: U256::from(100_000),
+			action: TransactionAction::Create,
+			value: U256::zero(),
+			input: vec![0x60, 0x00],
+			access_list: vec![],
+			odd_y_parity: true,
+			r,
+			s,
+		}),
+	]
+}
+
+fn receipts() -> Vec<Receipt> {
+	let logs = vec![Log {
+		address: H160::repeat_byte(0x11),
+		topics: vec![H256::repeat_byte(0x22)],
+		data: vec![0x01, 0x02],
+	}];
+	let mut logs_bloom = Bloom::default();
+	Ethereum::logs_bloom(logs.clone(), &mut logs_bloom);
+	vec![
+		Receipt::Legacy(EIP658ReceiptData {
+			status_code: 1,
+			used_gas: U256::from(21_000),
+			logs_bloom: Bloom::default(),
+			logs: vec![],
+		}),
+		Receipt::EIP2930(EIP658ReceiptData {
+			status_code: 1,
+			used_gas: U256::from(45_000),
+			logs_bloom,
+			logs,
+		}),
+		Receipt::EIP1559(EIP658ReceiptData {
+			status_code: 0,
+			used_gas: U256::from(90_000),
+			logs_bloom: Bloom::default(),
+			logs: vec![],
+		}),
+	]
+}
+
+#[test]
+fn empty_block_has_empty_roots() {
+	let (_, mut ext) = new_test_ext(0);
+
+	ext.execute_with(|| {
+		Ethereum::store_block(false, U256::one());
+
+		let block = Ethereum::current_block().unwrap();
+		assert_eq!(block.header.transactions_root, h256(EMPTY_TRIE_ROOT));
+		assert_eq!(block.header.receipts_root, h256(EMPTY_TRIE_ROOT));
+		assert_eq!(block.header.logs_bloom, Bloom::default());
+		assert_eq!(block.header.gas_used, U256::zero());
+	});
+}
+
+#[test]
+fn block_roots_and_bloom_match_ethereum() {
+	let (_, mut ext) = new_test_ext(0);
+
+	ext.execute_with(|| {
+		for (transaction, receipt) in transactions().into_iter().zip(receipts()) {
+			Pending::<Test>::append((transaction, TransactionStatus::default(), receipt));
+		}
+		Ethereum::store_block(false, U256::one());
+
+		let block = Ethereum::current_block().unwrap();
+		assert_eq!(
+			block.header.transactions_root,
+			h256("3cf580c75e3ef2e1d557d8880dc1dde6dba24e1535f489baf29bdd6bd869b13d")
+		);
+		assert_eq!(
+			block.header.receipts_root,
+			h256("fe7b071b6ecb4ee7a83d32fa57ca0b1af9f97018ffa441ecd9899e075062a283")
+		);
+		assert_eq!(
+			block.header.logs_bloom,
+			Bloom::from_str(concat!(
+				"0000000000000000000000000000000000000000000000000000000000000000",
+				"0000000000000000000000000000000000000000000000000000000000000000",
+				"0000000000000000000000000000000000000000000000000000000000000000",
+				"0000000000000000200000000000000000000000000000000000000000000010",
+				"0000000000000000000000000000000000000000000000000000000000000000",
+				"0000000000000001000000000000000000000000000000000000000000000000",
+				"0000000200000000000000000000000000000000000000800000000000000000",
+				"0000000000000002000000000000000000000000000000000000000000000000",
+			))
+			.unwrap()
+		);
+		assert_eq!(block.header.gas_used, U256::from(90_000));
+	});
+}
+
+#[test]
+fn receipt_proof_leads_from_receipts_root_to_receipt() {
+	let (_, mut ext) = new_test_ext(0);
+
+	ext.execute_with(|| {
+		for (transaction, receipt) in transactions().into_iter().zip(receipts()) {
+			Pending::<Test>::append((transaction, TransactionStatus::default(), receipt));
+		}
+		Ethereum::store_block(false, U256::one());
+
+		let proof = Ethereum::current_receipt_proof(1).unwrap();
+		let node_hashes = proof
+			.iter()
+			.map(|node| H256::from_slice(&Keccak256::digest(node)))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			node_hashes,
+			vec![
+				Ethereum::current_block().unwrap().header.receipts_root,
+				h256("ceb35583a5a25afca56fdc32c599fa8a42338578338b7e18efbdec9a276742ba"),
+				h256("57d2ff9adbabfcf233bc02047e70950befdbb31944608d889da56bb62831f52a"),
+			]
+		);
+
+		let leaf = rlp::Rlp::new(proof.last().unwrap());
+		assert_eq!(
+			leaf.val_at::<Vec<u8>>(1).unwrap(),
+			EnvelopedEncodable::encode(&receipts()[1]).to_vec()
+		);
+
+		assert_eq!(Ethereum::current_receipt_proof(3), None);
+	});
+}
+
+#[test]
+fn header_fields_are_customizable() {
+	let (_, mut ext) = new_test_ext(0);
+
+	ext.execute_with(|| {
+		BlockExtraData::set(b"frontier".to_vec());
+		BlockDifficulty::set(U256::from(131_072));
+		BlockNonce::set(H64::from_low_u64_be(42));
+		Ethereum::store_block(false, U256::one());
+
+		let header = Ethereum::current_block().unwrap().header;
+		assert_eq!(header.extra_data, b"frontier".to_vec());
+		assert_eq!(header.difficulty, U256::from(131_072));
+		assert_eq!(header.nonce, H64::from_low_u64_be(42));
+	});
+}
+
+#[test]
+fn pseudo_transactions_carry_logs_into_the_block() {
+	let (_, mut ext) = new_test_ext(0);
+
+	ext.execute_with(|| {
+		let address = H160::repeat_byte(0x11);
+		let logs = vec![Log {
+			address,
+			topics: vec![H256::repeat_byte(0x22)],
+			data: vec![0x01, 0x02],
+		}];
+		for (transaction, receipt) in transactions().into_iter().zip(receipts()).take(2) {
+			Pending::<Test>::append((transaction, TransactionStatus::default(), receipt));
+		}
+		Ethereum::deposit_pseudo_transaction(address, logs.clone());
+		Ethereum::deposit_pseudo_transaction(address, logs.clone());
+		Ethereum::store_block(false, U256::one());
+
+		let block = Ethereum::current_block().unwrap();
+		assert_eq!(block.transactions.len(), 4);
+		assert_ne!(block.transactions[2].hash(), block.transactions[3].hash());
+		assert_eq!(block.header.gas_used, U256::from(45_000));
+
+		let statuses = Ethereum::current_transaction_statuses().unwrap();
+		assert_eq!(statuses[2].transaction_index, 2);
+		assert_eq!(statuses[2].transaction_hash, block.transactions[2].hash());
+		assert_eq!(statuses[2].from, address);
+		assert_eq!(statuses[2].logs, logs);
+
+		match &Ethereum::current_receipts().unwrap()[3] {
+			Receipt::Legacy(d) => {
+				assert_eq!(d.status_code, 1);
+				assert_eq!(d.used_gas, U256::from(45_000));
+				assert_eq!(d.logs, logs);
+			}
+			_ => panic!("pseudo transactions have legacy receipts"),
+		}
+	});
+}
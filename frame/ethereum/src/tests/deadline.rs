@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deadline transactions tests.
+
+use super::*;
+use crate::{AccessListItem, DeadlineTransaction};
+
+fn transfer() -> EIP1559UnsignedTransaction {
+	EIP1559UnsignedTransaction {
+		nonce: U256::zero(),
+		max_priority_fee_per_gas: U256::from(1),
+		max_fee_per_gas: U256::from(1),
+		gas_limit: U256::from(0x100000),
+		action: TransactionAction::Call(H160::repeat_byte(0x11)),
+		value: U256::from(1),
+		input: Vec::new(),
+	}
+}
+
+fn transfer_transaction(account: &AccountInfo, valid_until_block: u64) -> DeadlineTransaction {
+	transfer().sign_until(&account.private_key, valid_until_block)
+}
+
+// Access list with the deadline item before another item.
+fn misplaced_deadline() -> Vec<AccessListItem> {
+	vec![
+		fp_rpc::deadline_item(10),
+		AccessListItem {
+			address: H160::repeat_byte(0x11),
+			storage_keys: Vec::new(),
+		},
+	]
+}
+
+fn validate(transaction: Transaction) -> sp_runtime::transaction_validity::TransactionValidity {
+	let call = crate::Call::<Test>::transact { transaction };
+	let source = call.check_self_contained().unwrap().unwrap();
+	let extrinsic = CheckedExtrinsic::<u64, crate::mock::Call, SignedExtra, H160> {
+		signed: fp_self_contained::CheckedSignature::SelfContained(source),
+		function: Call::Ethereum(call.clone()),
+	};
+	let dispatch_info = extrinsic.get_dispatch_info();
+	call.validate_self_contained(&source, &dispatch_info, 0)
+		.unwrap()
+}
+
+#[test]
+fn deadline_transaction_envelope_should_round_trip() {
+	let (pairs, _) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	let transaction = transfer_transaction(alice, 10);
+	assert_eq!(transaction.valid_until_block(), Some(10));
+	let envelope = transaction.encode_envelope().unwrap();
+	assert_eq!(envelope[0], crate::DEADLINE_TRANSACTION_TYPE);
+	assert_eq!(
+		DeadlineTransaction::decode_envelope(&envelope),
+		Ok(transaction)
+	);
+	assert!(DeadlineTransaction::decode_envelope(&envelope[1..]).is_err());
+}
+
+#[test]
+fn deadline_should_be_covered_by_the_signature() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		// The signature is the EIP-1559 signature of the transaction.
+		let mut transaction = transfer_transaction(alice, 10);
+		assert_eq!(
+			Ethereum::recover_signer(&Transaction::EIP1559(transaction.transaction.clone())),
+			Some(alice.address)
+		);
+
+		transaction.transaction.access_list = vec![fp_rpc::deadline_item(20)];
+		assert_ne!(
+			Ethereum::recover_signer(&Transaction::EIP1559(transaction.transaction)),
+			Some(alice.address)
+		);
+	});
+}
+
+#[test]
+fn deadline_should_be_a_plain_access_list_item_when_disabled() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		System::set_block_number(11);
+		assert_ok!(validate(Transaction::EIP1559(
+			transfer_transaction(alice, 10).transaction
+		)));
+
+		// Wherever it is in the access list.
+		assert_ok!(validate(transfer().sign_with_access_list(
+			&alice.private_key,
+			None,
+			misplaced_deadline()
+		)));
+	});
+}
+
+#[test]
+fn deadline_transaction_should_be_valid_until_its_deadline() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		DeadlineTransactions::set(true);
+		System::set_block_number(8);
+
+		// The transaction can be included in the blocks 8, 9 and 10.
+		assert_eq!(
+			validate(Transaction::EIP1559(
+				transfer_transaction(alice, 10).transaction
+			)),
+			ValidTransactionBuilder::default()
+				.and_provides((alice.address, U256::zero()))
+				.priority(0u64)
+				.longevity(3)
+				.build()
+		);
+
+		System::set_block_number(11);
+		assert_err!(
+			validate(Transaction::EIP1559(
+				transfer_transaction(alice, 10).transaction
+			)),
+			InvalidTransaction::Custom(crate::TransactionValidationError::DeadlinePassed as u8)
+		);
+	});
+}
+
+#[test]
+fn deadline_transaction_should_be_applied_as_the_transaction_it_wraps() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		DeadlineTransactions::set(true);
+		let transaction = transfer_transaction(alice, 10);
+		assert_ok!(Ethereum::transact(
+			RawOrigin::EthereumTransaction(alice.address).into(),
+			Transaction::EIP1559(transaction.transaction.clone()),
+		));
+
+		assert_eq!(EVM::account_basic(&alice.address).0.nonce, U256::one());
+		assert_eq!(
+			crate::Pending::<Test>::get()
+				.into_iter()
+				.map(|(t, _, _)| t)
+				.collect::<Vec<_>>(),
+			vec![Transaction::EIP1559(transaction.transaction.clone())]
+		);
+		assert_eq!(
+			Ethereum::recover_signer(&Transaction::EIP1559(transaction.transaction)),
+			Some(alice.address)
+		);
+	});
+}
+
+#[test]
+fn misplaced_deadline_should_be_rejected() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		DeadlineTransactions::set(true);
+
+		let transaction =
+			transfer().sign_with_access_list(&alice.private_key, None, misplaced_deadline());
+		assert_err!(validate(transaction), InvalidTransaction::Call);
+	});
+}
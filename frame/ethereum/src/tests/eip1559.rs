@@ -74,6 +74,53 @@ fn transaction_without_enough_gas_should_not_work() {
 	});
 }
 
+#[test]
+fn transaction_with_a_fee_sponsor_should_only_need_the_sender_share() {
+	let (pairs, mut ext) = new_test_ext(2);
+	let alice = &pairs[0];
+	let bob = &pairs[1];
+
+	ext.execute_with(|| {
+		// More than the balance of alice.
+		let transaction = EIP1559UnsignedTransaction {
+			nonce: U256::zero(),
+			max_priority_fee_per_gas: U256::zero(),
+			max_fee_per_gas: U256::from(1),
+			gas_limit: U256::from(12_000_000),
+			action: ethereum::TransactionAction::Call(H160::repeat_byte(0x11)),
+			value: U256::zero(),
+			input: Vec::new(),
+		}
+		.sign(&alice.private_key, None);
+
+		let call = crate::Call::<Test>::transact {
+			transaction: transaction.clone(),
+		};
+		let source = call.check_self_contained().unwrap().unwrap();
+		let extrinsic = CheckedExtrinsic::<u64, crate::mock::Call, SignedExtra, _> {
+			signed: fp_self_contained::CheckedSignature::SelfContained(source),
+			function: Call::Ethereum(call.clone()),
+		};
+		let dispatch_info = extrinsic.get_dispatch_info();
+		assert_err!(
+			call.validate_self_contained(&source, &dispatch_info, 0)
+				.unwrap(),
+			InvalidTransaction::Payment
+		);
+
+		// Bob pays half of the fees.
+		FeeSponsor::set(Some((bob.address, sp_runtime::Permill::from_percent(50))));
+		assert_ok!(call
+			.validate_self_contained(&source, &dispatch_info, 0)
+			.unwrap());
+		assert_ok!(Ethereum::validate_transaction_in_block(
+			source,
+			&transaction
+		));
+		FeeSponsor::set(None);
+	});
+}
+
 #[test]
 fn transaction_with_to_low_nonce_should_not_work() {
 	let (pairs, mut ext) = new_test_ext(1);
@@ -74,6 +74,31 @@ fn transaction_without_enough_gas_should_not_work() {
 	});
 }
 
+#[test]
+fn check_transaction_should_report_the_failure_cause() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let transaction = legacy_erc20_creation_transaction(alice);
+		assert_ok!(Ethereum::check_transaction(&transaction));
+
+		let mut unsigned = legacy_erc20_creation_unsigned_transaction();
+		unsigned.gas_limit = U256::from(1);
+		assert_eq!(
+			Ethereum::check_transaction(&unsigned.sign(&alice.private_key)),
+			Err(fp_evm::InvalidEvmTransaction::GasLimitTooLow)
+		);
+
+		let mut unsigned = legacy_erc20_creation_unsigned_transaction();
+		unsigned.value = U256::MAX;
+		assert_eq!(
+			Ethereum::check_transaction(&unsigned.sign(&alice.private_key)),
+			Err(fp_evm::InvalidEvmTransaction::BalanceTooLow)
+		);
+	});
+}
+
 #[test]
 fn transaction_with_to_low_nonce_should_not_work() {
 	let (pairs, mut ext) = new_test_ext(1);
@@ -131,6 +156,75 @@ fn transaction_with_to_low_nonce_should_not_work() {
 	});
 }
 
+#[test]
+fn transaction_validity_should_follow_the_configured_longevity_and_propagation() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		EthereumTransactionLongevity::set(64);
+		PropagateEthereumTransactions::set(false);
+
+		let mut transaction = legacy_erc20_creation_unsigned_transaction();
+		transaction.nonce = U256::from(2);
+		let call = crate::Call::<Test>::transact {
+			transaction: transaction.sign(&alice.private_key),
+		};
+		let source = call.check_self_contained().unwrap().unwrap();
+		let extrinsic = CheckedExtrinsic::<u64, crate::mock::Call, SignedExtra, H160> {
+			signed: fp_self_contained::CheckedSignature::SelfContained(source),
+			function: Call::Ethereum(call.clone()),
+		};
+		let dispatch_info = extrinsic.get_dispatch_info();
+
+		assert_eq!(
+			call.validate_self_contained(&source, &dispatch_info, 0)
+				.unwrap(),
+			ValidTransactionBuilder::default()
+				.and_provides((alice.address, U256::from(2)))
+				.priority(0u64)
+				.longevity(64)
+				.propagate(false)
+				.and_requires((alice.address, U256::from(1)))
+				.build()
+		);
+	});
+}
+
+#[test]
+fn transaction_too_far_ahead_of_the_sender_nonce_should_not_enter_the_pool() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		MaxFutureTransactionsPerSender::set(Some(2));
+
+		let validate = |nonce: u64| {
+			let mut transaction = legacy_erc20_creation_unsigned_transaction();
+			transaction.nonce = U256::from(nonce);
+			let call = crate::Call::<Test>::transact {
+				transaction: transaction.sign(&alice.private_key),
+			};
+			let source = call.check_self_contained().unwrap().unwrap();
+			let extrinsic = CheckedExtrinsic::<u64, crate::mock::Call, SignedExtra, H160> {
+				signed: fp_self_contained::CheckedSignature::SelfContained(source),
+				function: Call::Ethereum(call.clone()),
+			};
+			let dispatch_info = extrinsic.get_dispatch_info();
+			call.validate_self_contained(&source, &dispatch_info, 0)
+				.unwrap()
+		};
+
+		assert_ok!(validate(2));
+		assert_err!(
+			validate(3),
+			InvalidTransaction::Custom(
+				crate::TransactionValidationError::TooManyFutureTransactions as u8
+			)
+		);
+	});
+}
+
 #[test]
 fn transaction_with_to_hight_nonce_should_fail_in_block() {
 	let (pairs, mut ext) = new_test_ext(1);
@@ -184,6 +278,105 @@ fn transaction_with_invalid_chain_id_should_fail_in_block() {
 	});
 }
 
+#[test]
+fn self_contained_extensions_run_for_the_sender_of_ethereum_transactions() {
+	let (pairs, mut ext) = new_test_ext(2);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let call = crate::Call::<Test>::transact {
+			transaction: legacy_erc20_creation_unsigned_transaction().sign(&alice.private_key),
+		};
+		let source = call.check_self_contained().unwrap().unwrap();
+		let extrinsic = fp_self_contained::CheckedExtrinsic::<_, _, SignedExtra, _> {
+			signed: fp_self_contained::CheckedSignature::SelfContained(source),
+			function: Call::Ethereum(call),
+		};
+		let dispatch_info = extrinsic.get_dispatch_info();
+
+		PausedSender::set(Some(alice.account_id.clone()));
+		assert_err!(
+			extrinsic.validate::<Test>(
+				sp_runtime::transaction_validity::TransactionSource::External,
+				&dispatch_info,
+				0
+			),
+			TransactionValidityError::Invalid(InvalidTransaction::Call)
+		);
+		assert_err!(
+			extrinsic.clone().apply::<Test>(&dispatch_info, 0),
+			TransactionValidityError::Invalid(InvalidTransaction::Call)
+		);
+
+		PausedSender::set(Some(pairs[1].account_id.clone()));
+		assert_ok!(extrinsic.apply::<Test>(&dispatch_info, 0));
+	});
+}
+
+#[test]
+fn transaction_without_chain_id_should_follow_the_configuration() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let call = crate::Call::<Test>::transact {
+			transaction: legacy_erc20_creation_unsigned_transaction()
+				.sign_without_chain_id(&alice.private_key),
+		};
+		let source = call.check_self_contained().unwrap().unwrap();
+		assert_eq!(source, alice.address);
+		let extrinsic = CheckedExtrinsic::<u64, crate::mock::Call, SignedExtra, H160> {
+			signed: fp_self_contained::CheckedSignature::SelfContained(source),
+			function: Call::Ethereum(call.clone()),
+		};
+		let dispatch_info = extrinsic.get_dispatch_info();
+
+		assert_err!(
+			call.validate_self_contained(&source, &dispatch_info, 0)
+				.unwrap(),
+			InvalidTransaction::Custom(crate::TransactionValidationError::InvalidChainId as u8)
+		);
+
+		AllowUnprotectedTransactions::set(true);
+		assert_ok!(call
+			.validate_self_contained(&source, &dispatch_info, 0)
+			.unwrap());
+		assert_ok!(extrinsic.apply::<Test>(&dispatch_info, 0));
+	});
+}
+
+#[test]
+fn transaction_without_enough_balance_should_fail_in_pool_and_in_block() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let mut transaction = legacy_erc20_creation_unsigned_transaction();
+		transaction.value = U256::MAX;
+
+		let call = crate::Call::<Test>::transact {
+			transaction: transaction.sign(&alice.private_key),
+		};
+		let source = call.check_self_contained().unwrap().unwrap();
+		let extrinsic = fp_self_contained::CheckedExtrinsic::<_, _, SignedExtra, _> {
+			signed: fp_self_contained::CheckedSignature::SelfContained(source),
+			function: Call::Ethereum(call.clone()),
+		};
+		use frame_support::weights::GetDispatchInfo as _;
+		let dispatch_info = extrinsic.get_dispatch_info();
+
+		assert_err!(
+			call.validate_self_contained(&source, &dispatch_info, 0)
+				.unwrap(),
+			InvalidTransaction::Payment
+		);
+		assert_err!(
+			extrinsic.apply::<Test>(&dispatch_info, 0),
+			TransactionValidityError::Invalid(InvalidTransaction::Payment)
+		);
+	});
+}
+
 #[test]
 fn contract_constructor_should_get_executed() {
 	let (pairs, mut ext) = new_test_ext(1);
@@ -261,6 +454,44 @@ fn transaction_should_generate_correct_gas_used() {
 	});
 }
 
+#[test]
+fn transaction_should_refund_unused_weight() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	let expected_gas = 893928;
+
+	ext.execute_with(|| {
+		use frame_support::traits::Get;
+
+		let transaction = legacy_erc20_creation_transaction(alice);
+		let call = crate::Call::<Test>::transact { transaction };
+		let source = call.check_self_contained().unwrap().unwrap();
+		let extrinsic = CheckedExtrinsic::<u64, crate::mock::Call, SignedExtra, H160> {
+			signed: fp_self_contained::CheckedSignature::SelfContained(source),
+			function: Call::Ethereum(call),
+		};
+		let dispatch_info = extrinsic.get_dispatch_info();
+		// Pre-dispatch weight is derived from the gas limit.
+		assert_eq!(dispatch_info.weight, 0x100000);
+
+		let post_info = extrinsic
+			.apply::<Test>(&dispatch_info, 0)
+			.unwrap()
+			.unwrap();
+		assert_eq!(post_info.actual_weight, Some(expected_gas));
+
+		// Only the weight of the gas actually used is registered for the block.
+		let base_extrinsic = <Test as frame_system::Config>::BlockWeights::get()
+			.get(dispatch_info.class)
+			.base_extrinsic;
+		assert_eq!(
+			System::block_weight().total(),
+			base_extrinsic + expected_gas
+		);
+	});
+}
+
 #[test]
 fn call_should_handle_errors() {
 	// 	pragma solidity ^0.6.6;
@@ -331,3 +562,70 @@ fn call_should_handle_errors() {
 		Ethereum::execute(alice.address, &t3, None).ok().unwrap();
 	});
 }
+
+#[test]
+fn fifo_ordering_should_ignore_the_tip_within_a_power_of_two() {
+	use crate::{FifoOrdering, TransactionOrdering};
+
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let transaction = legacy_erc20_creation_transaction(alice);
+		let priority = |tip_priority| {
+			FifoOrdering::priority(&alice.address, U256::zero(), &transaction, tip_priority)
+		};
+
+		assert_eq!(priority(0), 0);
+		assert_eq!(priority(8), priority(15));
+		// A replacement doubling the tip is accepted by the pool.
+		assert!(priority(16) > priority(8));
+	});
+}
+
+#[test]
+fn shuffled_ordering_should_ignore_the_tip() {
+	use crate::{ShuffledOrdering, TransactionOrdering};
+
+	let (pairs, mut ext) = new_test_ext(2);
+	let alice = &pairs[0];
+	let bob = &pairs[1];
+
+	ext.execute_with(|| {
+		let transaction = legacy_erc20_creation_transaction(alice);
+		let priority = |source: &H160, tip_priority| {
+			ShuffledOrdering::<Test>::priority(source, U256::zero(), &transaction, tip_priority)
+		};
+
+		// The tip only orders the transactions of a sender with the same nonce.
+		let (alice_priority, bob_priority) =
+			(priority(&alice.address, 10), priority(&bob.address, 10));
+		assert_ne!(alice_priority >> 32, bob_priority >> 32);
+		if alice_priority > bob_priority {
+			assert!(priority(&alice.address, 0) > priority(&bob.address, 1_000));
+		} else {
+			assert!(priority(&bob.address, 0) > priority(&alice.address, 1_000));
+		}
+		assert!(priority(&alice.address, 11) > alice_priority);
+
+		// The content of the transaction doesn't change the priority.
+		let other_transaction = LegacyUnsignedTransaction {
+			gas_price: U256::from(2),
+			..legacy_erc20_creation_unsigned_transaction()
+		}
+		.sign(&alice.private_key);
+		assert_eq!(
+			ShuffledOrdering::<Test>::priority(
+				&alice.address,
+				U256::zero(),
+				&other_transaction,
+				10
+			),
+			alice_priority
+		);
+
+		// The priority changes with the block.
+		System::set_parent_hash(H256::repeat_byte(1));
+		assert_ne!(priority(&alice.address, 10), alice_priority);
+	});
+}
@@ -30,6 +30,9 @@ use crate::{
 };
 use fp_self_contained::CheckedExtrinsic;
 
+mod batch;
+mod block;
+mod deadline;
 mod eip1559;
 mod eip2930;
 mod legacy;
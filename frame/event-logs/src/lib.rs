@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Event logs pallet
+//!
+//! Bridges selected runtime events to EVM logs, so that EVM indexers can follow the activity
+//! of other pallets, such as governance or staking, without a Substrate indexer.
+//!
+//! At the end of each block, the runtime events selected by `EventToLog` are encoded as logs
+//! of the `LogAddress` pseudo-contract, and carried into the Ethereum block by a pseudo
+//! transaction of the Ethereum pallet. This pallet must therefore come before the Ethereum
+//! pallet in `construct_runtime!`, and after the pallets whose events are bridged.
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_core::{H160, H256};
+use sp_std::vec::Vec;
+
+pub use self::pallet::*;
+
+/// A runtime event encoded as a log, without its address.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct EventLog {
+	pub topics: Vec<H256>,
+	pub data: Vec<u8>,
+}
+
+impl EventLog {
+	/// A log of the Solidity event `signature`, such as `Transfer(bytes32,bytes32,uint256)`.
+	///
+	/// Its first topic is the hash of the signature, followed by the `indexed` arguments.
+	/// `data` holds the ABI encoded arguments not indexed.
+	pub fn new(signature: &[u8], indexed: Vec<H256>, data: Vec<u8>) -> Self {
+		let mut topics = Vec::with_capacity(indexed.len() + 1);
+		topics.push(H256(sp_io::hashing::keccak_256(signature)));
+		topics.extend(indexed);
+		Self { topics, data }
+	}
+}
+
+/// Selects the runtime events bridged to EVM logs, and encodes them.
+pub trait EventToLog<Event> {
+	/// The log of `event`, or `None` if it isn't bridged.
+	fn event_to_log(event: &Event) -> Option<EventLog>;
+}
+
+impl<Event> EventToLog<Event> for () {
+	fn event_to_log(_event: &Event) -> Option<EventLog> {
+		None
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_ethereum::Config {
+		/// Address of the pseudo-contract emitting the logs.
+		type LogAddress: Get<H160>;
+		/// Selects the runtime events to bridge and encodes them.
+		type EventToLog: EventToLog<<Self as frame_system::Config>::Event>;
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_: T::BlockNumber) -> Weight {
+			// Reading the events of the block, and appending the pseudo transaction.
+			T::DbWeight::get().reads_writes(2, 1)
+		}
+
+		fn on_finalize(_: T::BlockNumber) {
+			let address = T::LogAddress::get();
+			let logs = frame_system::Pallet::<T>::read_events_no_consensus()
+				.into_iter()
+				.filter_map(|record| T::EventToLog::event_to_log(&record.event))
+				.map(|log| pallet_ethereum::Log {
+					address,
+					topics: log.topics,
+					data: log.data,
+				})
+				.collect::<Vec<_>>();
+
+			if !logs.is_empty() {
+				pallet_ethereum::Pallet::<T>::deposit_pseudo_transaction(address, logs);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::str::FromStr;
+
+	#[test]
+	fn first_topic_is_the_event_signature() {
+		let log = EventLog::new(
+			b"Transfer(address,address,uint256)",
+			vec![H256::repeat_byte(0x11)],
+			vec![0x01],
+		);
+
+		assert_eq!(
+			log.topics,
+			vec![
+				H256::from_str("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef")
+					.unwrap(),
+				H256::repeat_byte(0x11),
+			]
+		);
+		assert_eq!(log.data, vec![0x01]);
+	}
+}
@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Hashing precompiles not covered by the standard Ethereum precompiles, for contracts
+//! verifying proofs of other chains. Each of them returns the hash of its whole input.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use fp_evm::{ExitSucceed, LinearCostPrecompile, PrecompileFailure};
+
+/// The SHA-512 hash of the input.
+pub struct Sha512;
+
+impl LinearCostPrecompile for Sha512 {
+	const BASE: u64 = 60;
+	const WORD: u64 = 12;
+
+	fn execute(input: &[u8], _: u64) -> Result<(ExitSucceed, Vec<u8>), PrecompileFailure> {
+		use sha2::Digest;
+		Ok((ExitSucceed::Returned, sha2::Sha512::digest(input).to_vec()))
+	}
+}
+
+/// The 40 bytes RIPEMD-320 hash of the input, not padded to a word unlike RIPEMD-160.
+pub struct Ripemd320;
+
+impl LinearCostPrecompile for Ripemd320 {
+	const BASE: u64 = 600;
+	const WORD: u64 = 120;
+
+	fn execute(input: &[u8], _: u64) -> Result<(ExitSucceed, Vec<u8>), PrecompileFailure> {
+		use ripemd::Digest;
+		Ok((
+			ExitSucceed::Returned,
+			ripemd::Ripemd320::digest(input).to_vec(),
+		))
+	}
+}
+
+/// The Keccak-512 hash of the input, with the original Keccak padding of `KECCAK256`.
+pub struct Keccak512;
+
+impl LinearCostPrecompile for Keccak512 {
+	const BASE: u64 = 60;
+	const WORD: u64 = 12;
+
+	fn execute(input: &[u8], _: u64) -> Result<(ExitSucceed, Vec<u8>), PrecompileFailure> {
+		use tiny_keccak::Hasher;
+		let mut output = [0; 64];
+		let mut keccak = tiny_keccak::Keccak::v512();
+		keccak.update(input);
+		keccak.finalize(&mut output);
+		Ok((ExitSucceed::Returned, output.to_vec()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn hello_sha512() {
+		let expected = b"\
+			\x9b\x71\xd2\x24\xbd\x62\xf3\x78\x5d\x96\xd4\x6a\xd3\xea\x3d\x73\
+			\x31\x9b\xfb\xc2\x89\x0c\xaa\xda\xe2\xdf\xf7\x25\x19\x67\x3c\xa7\
+			\x23\x23\xc3\xd9\x9b\xa5\xc1\x1d\x7c\x7a\xcc\x6e\x14\xb8\xc5\xda\
+			\x0c\x46\x63\x47\x5c\x2e\x5c\x3a\xde\xf4\x6f\x73\xbc\xde\xc0\x43\
+		";
+
+		let (_, out) = Sha512::execute(b"hello", 1).expect("sha512 doesn't fail");
+		assert_eq!(out, expected);
+	}
+
+	#[test]
+	fn hello_ripemd320() {
+		let expected = b"\
+			\xeb\x0c\xf4\x51\x14\xc5\x6a\x84\x21\xfb\xcb\x33\x43\x0f\xa2\x2e\
+			\x0c\xd6\x07\x56\x0a\x88\xbb\xe1\x4c\xe7\x0b\xdf\x59\xbf\x55\xb1\
+			\x1a\x39\x06\x98\x7c\x48\x79\x92\
+		";
+
+		let (_, out) = Ripemd320::execute(b"hello", 1).expect("ripemd320 doesn't fail");
+		assert_eq!(out, expected);
+	}
+
+	#[test]
+	fn empty_ripemd320() {
+		let expected = b"\
+			\x22\xd6\x5d\x56\x61\x53\x6c\xdc\x75\xc1\xfd\xf5\xc6\xde\x7b\x41\
+			\xb9\xf2\x73\x25\xeb\xc6\x1e\x85\x57\x17\x7d\x70\x5a\x0e\xc8\x80\
+			\x15\x1c\x3a\x32\xa0\x08\x99\xb8\
+		";
+
+		let (_, out) = Ripemd320::execute(&[], 1).expect("ripemd320 doesn't fail");
+		assert_eq!(out, expected);
+	}
+
+	#[test]
+	fn hello_keccak512() {
+		let expected = b"\
+			\x52\xfa\x80\x66\x2e\x64\xc1\x28\xf8\x38\x9c\x9e\xa6\xc7\x3d\x4c\
+			\x02\x36\x80\x04\xbf\x44\x63\x49\x19\x00\xd1\x1a\xaa\xdc\xa3\x9d\
+			\x47\xde\x1b\x01\x36\x1f\x20\x7c\x51\x2c\xfa\x79\xf0\xf9\x2c\x33\
+			\x95\xc6\x7f\xf7\x92\x8e\x3f\x5c\xe3\xe3\xc8\x52\xb3\x92\xf9\x76\
+		";
+
+		let (_, out) = Keccak512::execute(b"hello", 1).expect("keccak512 doesn't fail");
+		assert_eq!(out, expected);
+	}
+}
@@ -0,0 +1,147 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Solidity ABI encoding of the arguments and results of the precompile.
+
+use sp_core::{H160, U256};
+use sp_std::vec::Vec;
+
+/// Selector of a function, from its signature.
+pub fn selector(signature: &[u8]) -> [u8; 4] {
+	let mut selector = [0u8; 4];
+	selector.copy_from_slice(&sp_io::hashing::keccak_256(signature)[..4]);
+	selector
+}
+
+/// Reads the arguments following the selector.
+pub struct Reader<'a> {
+	arguments: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+	pub fn new(arguments: &'a [u8]) -> Self {
+		Self { arguments }
+	}
+
+	fn word(&self, index: usize) -> Option<&'a [u8]> {
+		self.arguments.get(index * 32..(index + 1) * 32)
+	}
+
+	fn uint32(&self, index: usize) -> Option<u32> {
+		let value = U256::from_big_endian(self.word(index)?);
+		if value > U256::from(u32::MAX) {
+			return None;
+		}
+		Some(value.low_u32())
+	}
+
+	/// The `address` argument at `index`.
+	pub fn address(&self, index: usize) -> Option<H160> {
+		let word = self.word(index)?;
+		if word[..12].iter().any(|byte| *byte != 0) {
+			return None;
+		}
+		Some(H160::from_slice(&word[12..]))
+	}
+
+	/// The `string` or `bytes` argument at `index`.
+	pub fn bytes(&self, index: usize) -> Option<Vec<u8>> {
+		let offset = self.uint32(index)? as usize;
+		let tail = Reader::new(self.arguments.get(offset..)?);
+		let length = tail.uint32(0)? as usize;
+		Some(tail.arguments.get(32..)?.get(..length)?.to_vec())
+	}
+}
+
+/// A value returned by the precompile.
+pub enum Value {
+	Bool(bool),
+	/// A `string` or `bytes` value.
+	Bytes(Vec<u8>),
+}
+
+fn word(value: usize) -> [u8; 32] {
+	let mut word = [0u8; 32];
+	U256::from(value).to_big_endian(&mut word);
+	word
+}
+
+/// Encodes `values` as the tuple returned by the precompile.
+pub fn encode(values: &[Value]) -> Vec<u8> {
+	let head_length = values.len() * 32;
+	let mut head = Vec::with_capacity(head_length);
+	let mut tail = Vec::new();
+
+	for value in values {
+		match value {
+			Value::Bool(value) => head.extend_from_slice(&word(*value as usize)),
+			Value::Bytes(data) => {
+				head.extend_from_slice(&word(head_length + tail.len()));
+				tail.extend_from_slice(&word(data.len()));
+				tail.extend_from_slice(data);
+				tail.resize(tail.len() + (32 - data.len() % 32) % 32, 0);
+			}
+		}
+	}
+
+	head.extend(tail);
+	head
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn encodes_dynamic_values_after_head() {
+		let output = encode(&[
+			Value::Bool(true),
+			Value::Bytes(b"alice".to_vec()),
+			Value::Bytes(Vec::new()),
+		]);
+
+		assert_eq!(output.len(), 32 * 6);
+		assert_eq!(output[..32], word(1));
+		assert_eq!(output[32..64], word(96));
+		assert_eq!(output[64..96], word(160));
+		assert_eq!(output[96..128], word(5));
+		assert_eq!(&output[128..133], b"alice");
+		assert_eq!(output[160..192], word(0));
+	}
+
+	#[test]
+	fn decodes_encoded_strings() {
+		let encoded = encode(&[
+			Value::Bytes(b"alice".to_vec()),
+			Value::Bytes(vec![0xff; 40]),
+		]);
+		let reader = Reader::new(&encoded);
+
+		assert_eq!(reader.bytes(0), Some(b"alice".to_vec()));
+		assert_eq!(reader.bytes(1), Some(vec![0xff; 40]));
+	}
+
+	#[test]
+	fn rejects_dirty_address() {
+		let mut word = [0u8; 32];
+		word[12..].copy_from_slice(&[0x11; 20]);
+		assert_eq!(Reader::new(&word).address(0), Some(H160::repeat_byte(0x11)));
+
+		word[0] = 1;
+		assert_eq!(Reader::new(&word).address(0), None);
+	}
+}
@@ -0,0 +1,211 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod abi;
+
+use core::marker::PhantomData;
+use fp_evm::{
+	Context, ExitError, ExitSucceed, Precompile, PrecompileFailure, PrecompileOutput,
+	PrecompileResult,
+};
+use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	traits::Get,
+};
+use pallet_evm::{AddressMapping, GasWeightMapping};
+use pallet_identity::{Data, IdentityInfo, Judgement};
+use sp_std::{boxed::Box, vec::Vec};
+
+use crate::abi::{Reader, Value};
+
+/// Precompile exposing `pallet-identity` to the EVM.
+///
+/// Only the display name, web and riot fields are supported, as strings of at most 32
+/// bytes. The functions are:
+///
+/// - `setIdentity(string display, string web, string riot)`, replacing the identity of
+///   the caller.
+/// - `clearIdentity()`, removing the identity of the caller.
+/// - `identity(address who) returns (bool exists, bool verified, string display,
+///   string web, string riot)`, where `verified` is true when a registrar judged the
+///   identity `Reasonable` or `KnownGood`. Hashed fields are returned empty.
+pub struct Identity<T> {
+	_marker: PhantomData<T>,
+}
+
+fn error(message: &'static str) -> PrecompileFailure {
+	PrecompileFailure::Error {
+		exit_status: ExitError::Other(message.into()),
+	}
+}
+
+fn to_data(value: Vec<u8>) -> Option<Data> {
+	if value.is_empty() {
+		return Some(Data::None);
+	}
+	Some(Data::Raw(value.try_into().ok()?))
+}
+
+fn from_data(data: &Data) -> Vec<u8> {
+	match data {
+		Data::Raw(value) => value.to_vec(),
+		_ => Vec::new(),
+	}
+}
+
+impl<T> Identity<T>
+where
+	T: pallet_evm::Config + pallet_identity::Config,
+	<T as frame_system::Config>::Call: Dispatchable<PostInfo = PostDispatchInfo>
+		+ GetDispatchInfo
+		+ From<pallet_identity::Call<T>>,
+	<<T as frame_system::Config>::Call as Dispatchable>::Origin: From<Option<T::AccountId>>,
+{
+	fn set_identity(reader: &Reader) -> Option<pallet_identity::Call<T>> {
+		let info = IdentityInfo {
+			additional: Default::default(),
+			display: to_data(reader.bytes(0)?)?,
+			legal: Data::None,
+			web: to_data(reader.bytes(1)?)?,
+			riot: to_data(reader.bytes(2)?)?,
+			email: Data::None,
+			pgp_fingerprint: None,
+			image: Data::None,
+			twitter: Data::None,
+		};
+		Some(pallet_identity::Call::set_identity {
+			info: Box::new(info),
+		})
+	}
+
+	fn dispatch(
+		call: pallet_identity::Call<T>,
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> PrecompileResult {
+		let call: <T as frame_system::Config>::Call = call.into();
+		let info = call.get_dispatch_info();
+
+		if let Some(gas) = target_gas {
+			let valid_weight = info.weight <= T::GasWeightMapping::gas_to_weight(gas);
+			if !valid_weight {
+				return Err(PrecompileFailure::Error {
+					exit_status: ExitError::OutOfGas,
+				});
+			}
+		}
+
+		let origin = T::AddressMapping::into_account_id(context.caller);
+
+		match call.dispatch(Some(origin).into()) {
+			Ok(post_info) => {
+				let cost = T::GasWeightMapping::weight_to_gas(
+					post_info.actual_weight.unwrap_or(info.weight),
+				);
+				Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Stopped,
+					cost,
+					output: Default::default(),
+					logs: Default::default(),
+				})
+			}
+			Err(_) => Err(error("dispatch execution failed")),
+		}
+	}
+
+	fn identity(reader: &Reader, target_gas: Option<u64>) -> PrecompileResult {
+		let cost = T::GasWeightMapping::weight_to_gas(T::DbWeight::get().read);
+		if let Some(gas) = target_gas {
+			if cost > gas {
+				return Err(PrecompileFailure::Error {
+					exit_status: ExitError::OutOfGas,
+				});
+			}
+		}
+
+		let who = reader.address(0).ok_or_else(|| error("decode failed"))?;
+		let registration =
+			pallet_identity::Pallet::<T>::identity(T::AddressMapping::into_account_id(who));
+		let output = match registration {
+			Some(registration) => abi::encode(&[
+				Value::Bool(true),
+				Value::Bool(registration.judgements.iter().any(|(_, judgement)| {
+					matches!(judgement, Judgement::Reasonable | Judgement::KnownGood)
+				})),
+				Value::Bytes(from_data(&registration.info.display)),
+				Value::Bytes(from_data(&registration.info.web)),
+				Value::Bytes(from_data(&registration.info.riot)),
+			]),
+			None => abi::encode(&[
+				Value::Bool(false),
+				Value::Bool(false),
+				Value::Bytes(Vec::new()),
+				Value::Bytes(Vec::new()),
+				Value::Bytes(Vec::new()),
+			]),
+		};
+
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			cost,
+			output,
+			logs: Default::default(),
+		})
+	}
+}
+
+impl<T> Precompile for Identity<T>
+where
+	T: pallet_evm::Config + pallet_identity::Config,
+	<T as frame_system::Config>::Call: Dispatchable<PostInfo = PostDispatchInfo>
+		+ GetDispatchInfo
+		+ From<pallet_identity::Call<T>>,
+	<<T as frame_system::Config>::Call as Dispatchable>::Origin: From<Option<T::AccountId>>,
+{
+	fn execute(
+		input: &[u8],
+		target_gas: Option<u64>,
+		context: &Context,
+		is_static: bool,
+	) -> PrecompileResult {
+		if !context.apparent_value.is_zero() {
+			return Err(error("value not accepted"));
+		}
+		let selector = input.get(..4).ok_or_else(|| error("decode failed"))?;
+		let reader = Reader::new(&input[4..]);
+
+		if selector == abi::selector(b"identity(address)") {
+			return Self::identity(&reader, target_gas);
+		}
+
+		let call = if selector == abi::selector(b"setIdentity(string,string,string)") {
+			Self::set_identity(&reader).ok_or_else(|| error("decode failed"))?
+		} else if selector == abi::selector(b"clearIdentity()") {
+			pallet_identity::Call::clear_identity {}
+		} else {
+			return Err(error("unknown selector"));
+		};
+		if is_static {
+			return Err(error("cannot modify identities in a static context"));
+		}
+		Self::dispatch(call, target_gas, context)
+	}
+}
@@ -21,9 +21,9 @@
 extern crate alloc;
 
 use alloc::vec::Vec;
-use core::{cmp::max, ops::BitAnd};
+use core::cmp::max;
 
-use num::{BigUint, FromPrimitive, One, ToPrimitive, Zero};
+use num::{BigUint, One, Zero};
 
 use fp_evm::{
 	Context, ExitError, ExitSucceed, Precompile, PrecompileFailure, PrecompileOutput,
@@ -34,14 +34,36 @@ pub struct Modexp;
 
 const MIN_GAS_COST: u64 = 200;
 
+/// Bytes of working memory a computation may allocate per unit of gas it is charged.
+///
+/// The EIP 2565 cost grows with the square of the base and modulus lengths, while the memory
+/// of the computation grows linearly with the lengths, so every computation paid for fits
+/// within this cap. It bounds the memory of the precompile by its cost, also when it's called
+/// without a gas limit.
+const ALLOCATION_PER_GAS: u64 = 8;
+
+/// Upper bound of the bytes allocated by a computation: the base, the exponent, the modulus,
+/// the double length products of the modular multiplications and the output.
+fn allocation_bound(base_length: u64, exp_length: u64, mod_length: u64) -> u64 {
+	base_length + exp_length + 4 * mod_length
+}
+
+/// Length in bits of the big endian number `bytes`.
+fn bit_length(bytes: &[u8]) -> u64 {
+	match bytes.iter().position(|byte| *byte != 0) {
+		Some(first) => {
+			(bytes.len() - first - 1) as u64 * 8 + (8 - bytes[first].leading_zeros()) as u64
+		}
+		None => 0,
+	}
+}
+
 // Calculate gas cost according to EIP 2565:
 // https://eips.ethereum.org/EIPS/eip-2565
-fn calculate_gas_cost(
-	base_length: u64,
-	exp_length: u64,
-	mod_length: u64,
-	exponent: &BigUint,
-) -> u64 {
+//
+// The exponent is given as its big endian bytes, so that the cost is known before any number
+// is allocated.
+fn calculate_gas_cost(base_length: u64, exp_length: u64, mod_length: u64, exponent: &[u8]) -> u64 {
 	fn calculate_multiplication_complexity(base_length: u64, mod_length: u64) -> u64 {
 		let max_length = max(base_length, mod_length);
 		let mut words = max_length / 8;
@@ -56,17 +78,15 @@ fn calculate_gas_cost(
 		words * words
 	}
 
-	fn calculate_iteration_count(exp_length: u64, exponent: &BigUint) -> u64 {
+	fn calculate_iteration_count(exp_length: u64, exponent: &[u8]) -> u64 {
 		let mut iteration_count: u64 = 0;
 
-		if exp_length <= 32 && exponent.is_zero() {
-			iteration_count = 0;
-		} else if exp_length <= 32 {
-			iteration_count = exponent.bits() - 1;
+		if exp_length <= 32 {
+			// the bit length of a zero exponent is zero
+			iteration_count = bit_length(exponent).saturating_sub(1);
 		} else if exp_length > 32 {
-			// construct BigUint to represent (2^256) - 1
-			let bytes: [u8; 32] = [0xFF; 32];
-			let max_256_bit_uint = BigUint::from_bytes_be(&bytes);
+			// the low 256 bits of the exponent, i.e. its last 32 bytes
+			let low_bits = &exponent[exponent.len() - 32..];
 
 			// from the EIP spec:
 			// (8 * (exp_length - 32)) + ((exponent & (2**256 - 1)).bit_length() - 1)
@@ -77,8 +97,7 @@ fn calculate_gas_cost(
 			//   must be > 0)
 			// * the addition can't overflow because the terms are both capped at roughly
 			//   8 * max size of exp_length (1024)
-			iteration_count =
-				(8 * (exp_length - 32)) + exponent.bitand(max_256_bit_uint).bits() - 1;
+			iteration_count = (8 * (exp_length - 32)) + bit_length(low_bits) - 1;
 		}
 
 		max(iteration_count, 1)
@@ -101,10 +120,9 @@ fn calculate_gas_cost(
 // 6) modulus, size as described above
 //
 //
-// NOTE: input sizes are bound to 1024 bytes, with the expectation
-//       that gas limits would be applied before actual computation.
-//
-//       maximum stack size will also prevent abuse.
+// NOTE: input sizes are bound to 1024 bytes, and the gas cost is charged before any number
+//       is allocated. The memory of the computation is then capped by its cost, see
+//       `ALLOCATION_PER_GAS`.
 //
 //       see: https://eips.ethereum.org/EIPS/eip-198
 
@@ -122,37 +140,15 @@ impl Precompile for Modexp {
 		};
 
 		// reasonable assumption: this must fit within the Ethereum EVM's max stack size
-		let max_size_big = BigUint::from_u32(1024).expect("can't create BigUint");
-
-		let mut buf = [0; 32];
-		buf.copy_from_slice(&input[0..32]);
-		let base_len_big = BigUint::from_bytes_be(&buf);
-		if base_len_big > max_size_big {
-			return Err(PrecompileFailure::Error {
-				exit_status: ExitError::Other("unreasonably large base length".into()),
-			});
-		}
-
-		buf.copy_from_slice(&input[32..64]);
-		let exp_len_big = BigUint::from_bytes_be(&buf);
-		if exp_len_big > max_size_big {
-			return Err(PrecompileFailure::Error {
-				exit_status: ExitError::Other("unreasonably large exponent length".into()),
-			});
-		}
-
-		buf.copy_from_slice(&input[64..96]);
-		let mod_len_big = BigUint::from_bytes_be(&buf);
-		if mod_len_big > max_size_big {
-			return Err(PrecompileFailure::Error {
-				exit_status: ExitError::Other("unreasonably large modulus length".into()),
-			});
-		}
-
-		// bounds check handled above
-		let base_len = base_len_big.to_usize().expect("base_len out of bounds");
-		let exp_len = exp_len_big.to_usize().expect("exp_len out of bounds");
-		let mod_len = mod_len_big.to_usize().expect("mod_len out of bounds");
+		let base_len = read_length(&input[0..32]).ok_or_else(|| PrecompileFailure::Error {
+			exit_status: ExitError::Other("unreasonably large base length".into()),
+		})?;
+		let exp_len = read_length(&input[32..64]).ok_or_else(|| PrecompileFailure::Error {
+			exit_status: ExitError::Other("unreasonably large exponent length".into()),
+		})?;
+		let mod_len = read_length(&input[64..96]).ok_or_else(|| PrecompileFailure::Error {
+			exit_status: ExitError::Other("unreasonably large modulus length".into()),
+		})?;
 
 		// input length should be at least 96 + user-specified length of base + exp + mod
 		let total_len = base_len + exp_len + mod_len + 96;
@@ -162,20 +158,20 @@ impl Precompile for Modexp {
 			});
 		}
 
+		let base_start = 96; // previous 3 32-byte fields
+		let exp_start = base_start + base_len;
+		let mod_start = exp_start + exp_len;
+		let base = &input[base_start..exp_start];
+		let exponent = &input[exp_start..mod_start];
+		let modulus = &input[mod_start..total_len];
+
 		// Gas formula allows arbitrary large exp_len when base and modulus are empty, so we need to handle empty base first.
 		let (r, gas_cost) = if base_len == 0 && mod_len == 0 {
 			(BigUint::zero(), MIN_GAS_COST)
 		} else {
-			// read the numbers themselves.
-			let base_start = 96; // previous 3 32-byte fields
-			let base = BigUint::from_bytes_be(&input[base_start..base_start + base_len]);
-
-			let exp_start = base_start + base_len;
-			let exponent = BigUint::from_bytes_be(&input[exp_start..exp_start + exp_len]);
-
-			// do our gas accounting
+			// do our gas accounting, before reading the numbers themselves.
 			let gas_cost =
-				calculate_gas_cost(base_len as u64, exp_len as u64, mod_len as u64, &exponent);
+				calculate_gas_cost(base_len as u64, exp_len as u64, mod_len as u64, exponent);
 			if let Some(gas_left) = target_gas {
 				if gas_left < gas_cost {
 					return Err(PrecompileFailure::Error {
@@ -184,12 +180,20 @@ impl Precompile for Modexp {
 				}
 			};
 
-			let mod_start = exp_start + exp_len;
-			let modulus = BigUint::from_bytes_be(&input[mod_start..mod_start + mod_len]);
+			let allocation = allocation_bound(base_len as u64, exp_len as u64, mod_len as u64);
+			if allocation > gas_cost.saturating_mul(ALLOCATION_PER_GAS) {
+				return Err(PrecompileFailure::Error {
+					exit_status: ExitError::Other("unreasonably large allocation".into()),
+				});
+			}
 
+			let modulus = BigUint::from_bytes_be(modulus);
 			if modulus.is_zero() || modulus.is_one() {
+				// the base and the exponent don't need to be read.
 				(BigUint::zero(), gas_cost)
 			} else {
+				let base = BigUint::from_bytes_be(base);
+				let exponent = BigUint::from_bytes_be(exponent);
 				(base.modpow(&exponent, &modulus), gas_cost)
 			}
 		};
@@ -199,21 +203,14 @@ impl Precompile for Modexp {
 
 		// always true except in the case of zero-length modulus, which leads to
 		// output of length and value 1.
-		if bytes.len() == mod_len {
+		if bytes.len() <= mod_len {
+			let mut output = Vec::with_capacity(mod_len);
+			output.resize(mod_len - bytes.len(), 0);
+			output.extend_from_slice(&bytes);
 			Ok(PrecompileOutput {
 				exit_status: ExitSucceed::Returned,
 				cost: gas_cost,
-				output: bytes.to_vec(),
-				logs: Default::default(),
-			})
-		} else if bytes.len() < mod_len {
-			let mut ret = Vec::with_capacity(mod_len);
-			ret.extend(core::iter::repeat(0).take(mod_len - bytes.len()));
-			ret.extend_from_slice(&bytes[..]);
-			Ok(PrecompileOutput {
-				exit_status: ExitSucceed::Returned,
-				cost: gas_cost,
-				output: ret.to_vec(),
+				output,
 				logs: Default::default(),
 			})
 		} else {
@@ -224,6 +221,23 @@ impl Precompile for Modexp {
 	}
 }
 
+/// Reads a 32 bytes big endian length, bounded to 1024.
+fn read_length(bytes: &[u8]) -> Option<usize> {
+	const MAX_LENGTH: usize = 1024;
+
+	let (high, low) = bytes.split_at(bytes.len() - 8);
+	if high.iter().any(|byte| *byte != 0) {
+		return None;
+	}
+	let mut buf = [0; 8];
+	buf.copy_from_slice(low);
+	let length = u64::from_be_bytes(buf);
+	if length > MAX_LENGTH as u64 {
+		return None;
+	}
+	Some(length as usize)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -469,4 +483,35 @@ mod tests {
 		let expected = BigUint::parse_bytes(b"0", 10).unwrap();
 		assert_eq!(result, expected);
 	}
+
+	#[test]
+	fn bit_length_of_big_endian_bytes() {
+		assert_eq!(bit_length(&[]), 0);
+		assert_eq!(bit_length(&[0, 0]), 0);
+		assert_eq!(bit_length(&[0, 1]), 1);
+		assert_eq!(bit_length(&[0, 0x80, 0]), 16);
+		assert_eq!(bit_length(&[0xff; 32]), 256);
+	}
+
+	#[test]
+	fn allocation_of_paid_computations_is_within_the_cap() {
+		// A zero exponent has the lowest cost for its length.
+		let exponent = [0u8; 1024];
+		for base_len in (0..=1024).step_by(13) {
+			for mod_len in (0..=1024).step_by(11) {
+				for exp_len in (0..=64).chain((65..=1024).step_by(31)) {
+					let gas_cost = calculate_gas_cost(
+						base_len,
+						exp_len,
+						mod_len,
+						&exponent[..exp_len as usize],
+					);
+					assert!(
+						allocation_bound(base_len, exp_len, mod_len)
+							<= gas_cost * ALLOCATION_PER_GAS
+					);
+				}
+			}
+		}
+	}
 }
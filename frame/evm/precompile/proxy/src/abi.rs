@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decoding of the Solidity ABI arguments of the precompile.
+
+use sp_core::U256;
+use sp_std::vec::Vec;
+
+/// Selector of a function, from its signature.
+pub fn selector(signature: &[u8]) -> [u8; 4] {
+	let mut selector = [0u8; 4];
+	selector.copy_from_slice(&sp_io::hashing::keccak_256(signature)[..4]);
+	selector
+}
+
+/// Reads the arguments following the selector.
+pub struct Reader<'a> {
+	arguments: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+	pub fn new(arguments: &'a [u8]) -> Self {
+		Self { arguments }
+	}
+
+	fn word(&self, index: usize) -> Option<&'a [u8]> {
+		self.arguments.get(index * 32..(index + 1) * 32)
+	}
+
+	fn uint(&self, index: usize, max: U256) -> Option<U256> {
+		let value = U256::from_big_endian(self.word(index)?);
+		if value > max {
+			return None;
+		}
+		Some(value)
+	}
+
+	/// The `bytes32` argument at `index`.
+	pub fn bytes32(&self, index: usize) -> Option<[u8; 32]> {
+		let mut value = [0u8; 32];
+		value.copy_from_slice(self.word(index)?);
+		Some(value)
+	}
+
+	/// The `uint8` argument at `index`.
+	pub fn uint8(&self, index: usize) -> Option<u8> {
+		Some(self.uint(index, U256::from(u8::MAX))?.low_u32() as u8)
+	}
+
+	/// The `uint32` argument at `index`.
+	pub fn uint32(&self, index: usize) -> Option<u32> {
+		Some(self.uint(index, U256::from(u32::MAX))?.low_u32())
+	}
+
+	/// The `bytes` argument at `index`.
+	pub fn bytes(&self, index: usize) -> Option<Vec<u8>> {
+		let offset = self.uint32(index)? as usize;
+		let tail = Reader::new(self.arguments.get(offset..)?);
+		let length = tail.uint32(0)? as usize;
+		Some(tail.arguments.get(32..)?.get(..length)?.to_vec())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn word(value: u64) -> Vec<u8> {
+		let mut word = [0u8; 32];
+		U256::from(value).to_big_endian(&mut word);
+		word.to_vec()
+	}
+
+	#[test]
+	fn selector_is_keccak_prefix() {
+		assert_eq!(selector(b"removeProxies()"), [0x14, 0xa5, 0xb5, 0xfa]);
+	}
+
+	#[test]
+	fn reads_static_arguments() {
+		let arguments = [vec![0xab; 32], word(3), word(100)].concat();
+		let reader = Reader::new(&arguments);
+
+		assert_eq!(reader.bytes32(0), Some([0xab; 32]));
+		assert_eq!(reader.uint8(1), Some(3));
+		assert_eq!(reader.uint32(2), Some(100));
+		assert_eq!(reader.uint8(2), None);
+		assert_eq!(reader.bytes32(3), None);
+	}
+
+	#[test]
+	fn reads_dynamic_bytes() {
+		let mut arguments = [vec![0xab; 32], word(64), word(3), vec![1, 2, 3]].concat();
+		arguments.resize(128, 0);
+		let reader = Reader::new(&arguments);
+
+		assert_eq!(reader.bytes(1), Some(vec![1, 2, 3]));
+		arguments.truncate(96);
+		assert_eq!(Reader::new(&arguments).bytes(1), None);
+	}
+}
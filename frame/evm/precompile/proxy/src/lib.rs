@@ -0,0 +1,175 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod abi;
+
+use core::marker::PhantomData;
+use fp_evm::{
+	Context, ExitError, ExitSucceed, Precompile, PrecompileFailure, PrecompileOutput,
+	PrecompileResult,
+};
+use frame_support::{
+	codec::Decode,
+	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+};
+use pallet_evm::{AddressMapping, GasWeightMapping};
+use sp_std::boxed::Box;
+
+use crate::abi::Reader;
+
+/// Precompile exposing `pallet-proxy` to the EVM, on behalf of the caller.
+///
+/// Accounts are given as `bytes32`, SCALE encoded, and proxy types as `uint8`, their
+/// SCALE encoding. Proxied calls are SCALE encoded runtime calls. The functions are:
+///
+/// - `addProxy(bytes32 delegate, uint8 proxyType, uint32 delay)`
+/// - `removeProxy(bytes32 delegate, uint8 proxyType, uint32 delay)`
+/// - `removeProxies()`
+/// - `proxy(bytes32 real, bytes call)`
+/// - `announce(bytes32 real, bytes32 callHash)`
+/// - `removeAnnouncement(bytes32 real, bytes32 callHash)`
+/// - `rejectAnnouncement(bytes32 delegate, bytes32 callHash)`
+/// - `proxyAnnounced(bytes32 delegate, bytes32 real, bytes call)`
+pub struct Proxy<T> {
+	_marker: PhantomData<T>,
+}
+
+fn decode<V: Decode>(data: &[u8]) -> Option<V> {
+	V::decode(&mut &*data).ok()
+}
+
+impl<T> Proxy<T>
+where
+	T: pallet_evm::Config + pallet_proxy::Config,
+{
+	fn decode_call(input: &[u8]) -> Option<pallet_proxy::Call<T>> {
+		let selector = input.get(..4)?;
+		let reader = Reader::new(&input[4..]);
+
+		let call = if selector == abi::selector(b"addProxy(bytes32,uint8,uint32)") {
+			pallet_proxy::Call::add_proxy {
+				delegate: decode(&reader.bytes32(0)?)?,
+				proxy_type: decode(&[reader.uint8(1)?])?,
+				delay: reader.uint32(2)?.into(),
+			}
+		} else if selector == abi::selector(b"removeProxy(bytes32,uint8,uint32)") {
+			pallet_proxy::Call::remove_proxy {
+				delegate: decode(&reader.bytes32(0)?)?,
+				proxy_type: decode(&[reader.uint8(1)?])?,
+				delay: reader.uint32(2)?.into(),
+			}
+		} else if selector == abi::selector(b"removeProxies()") {
+			pallet_proxy::Call::remove_proxies {}
+		} else if selector == abi::selector(b"proxy(bytes32,bytes)") {
+			pallet_proxy::Call::proxy {
+				real: decode(&reader.bytes32(0)?)?,
+				force_proxy_type: None,
+				call: Box::new(decode(&reader.bytes(1)?)?),
+			}
+		} else if selector == abi::selector(b"announce(bytes32,bytes32)") {
+			pallet_proxy::Call::announce {
+				real: decode(&reader.bytes32(0)?)?,
+				call_hash: decode(&reader.bytes32(1)?)?,
+			}
+		} else if selector == abi::selector(b"removeAnnouncement(bytes32,bytes32)") {
+			pallet_proxy::Call::remove_announcement {
+				real: decode(&reader.bytes32(0)?)?,
+				call_hash: decode(&reader.bytes32(1)?)?,
+			}
+		} else if selector == abi::selector(b"rejectAnnouncement(bytes32,bytes32)") {
+			pallet_proxy::Call::reject_announcement {
+				delegate: decode(&reader.bytes32(0)?)?,
+				call_hash: decode(&reader.bytes32(1)?)?,
+			}
+		} else if selector == abi::selector(b"proxyAnnounced(bytes32,bytes32,bytes)") {
+			pallet_proxy::Call::proxy_announced {
+				delegate: decode(&reader.bytes32(0)?)?,
+				real: decode(&reader.bytes32(1)?)?,
+				force_proxy_type: None,
+				call: Box::new(decode(&reader.bytes(2)?)?),
+			}
+		} else {
+			return None;
+		};
+		Some(call)
+	}
+}
+
+impl<T> Precompile for Proxy<T>
+where
+	T: pallet_evm::Config + pallet_proxy::Config,
+	<T as frame_system::Config>::Call:
+		Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + From<pallet_proxy::Call<T>>,
+	<<T as frame_system::Config>::Call as Dispatchable>::Origin: From<Option<T::AccountId>>,
+{
+	fn execute(
+		input: &[u8],
+		target_gas: Option<u64>,
+		context: &Context,
+		is_static: bool,
+	) -> PrecompileResult {
+		if is_static {
+			return Err(PrecompileFailure::Error {
+				exit_status: ExitError::Other("cannot modify proxies in a static context".into()),
+			});
+		}
+		if !context.apparent_value.is_zero() {
+			return Err(PrecompileFailure::Error {
+				exit_status: ExitError::Other("value not accepted".into()),
+			});
+		}
+
+		let call: <T as frame_system::Config>::Call = Self::decode_call(input)
+			.ok_or_else(|| PrecompileFailure::Error {
+				exit_status: ExitError::Other("decode failed".into()),
+			})?
+			.into();
+		let info = call.get_dispatch_info();
+
+		if let Some(gas) = target_gas {
+			let valid_weight = info.weight <= T::GasWeightMapping::gas_to_weight(gas);
+			if !valid_weight {
+				return Err(PrecompileFailure::Error {
+					exit_status: ExitError::OutOfGas,
+				});
+			}
+		}
+
+		let origin = T::AddressMapping::into_account_id(context.caller);
+
+		match call.dispatch(Some(origin).into()) {
+			Ok(post_info) => {
+				let cost = T::GasWeightMapping::weight_to_gas(
+					post_info.actual_weight.unwrap_or(info.weight),
+				);
+				Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Stopped,
+					cost,
+					output: Default::default(),
+					logs: Default::default(),
+				})
+			}
+			Err(_) => Err(PrecompileFailure::Error {
+				exit_status: ExitError::Other("dispatch execution failed".into()),
+			}),
+		}
+	}
+}
@@ -19,45 +19,107 @@
 
 extern crate alloc;
 
-use alloc::vec::Vec;
-
-use fp_evm::{ExitSucceed, LinearCostPrecompile, PrecompileFailure};
-
-pub struct Sha3FIPS256;
-
-impl LinearCostPrecompile for Sha3FIPS256 {
-	const BASE: u64 = 60;
-	const WORD: u64 = 12;
+use core::marker::PhantomData;
+
+use fp_evm::{
+	ensure_linear_cost, Context, ExitSucceed, Precompile, PrecompileOutput, PrecompileResult,
+};
+use sp_core::{ConstU64, Get};
+
+fn sha3_precompile<const N: usize>(
+	input: &[u8],
+	target_gas: Option<u64>,
+	base: u64,
+	word: u64,
+	mut sha3: tiny_keccak::Sha3,
+) -> PrecompileResult {
+	use tiny_keccak::Hasher;
+	let cost = ensure_linear_cost(target_gas, input.len() as u64, base, word)?;
+
+	let mut output = [0; N];
+	sha3.update(input);
+	sha3.finalize(&mut output);
+	Ok(PrecompileOutput {
+		exit_status: ExitSucceed::Returned,
+		cost,
+		output: output.to_vec(),
+		logs: Default::default(),
+	})
+}
 
-	fn execute(input: &[u8], _: u64) -> Result<(ExitSucceed, Vec<u8>), PrecompileFailure> {
-		use tiny_keccak::Hasher;
-		let mut output = [0; 32];
-		let mut sha3 = tiny_keccak::Sha3::v256();
-		sha3.update(input);
-		sha3.finalize(&mut output);
-		Ok((ExitSucceed::Returned, output.to_vec()))
+/// The SHA3-256 precompile, costing `Base + Word * ceil(len / 32)` gas.
+///
+/// The default costs are those of the SHA-256 precompile.
+pub struct Sha3FIPS256<Base = ConstU64<60>, Word = ConstU64<12>>(PhantomData<(Base, Word)>);
+
+impl<Base: Get<u64>, Word: Get<u64>> Precompile for Sha3FIPS256<Base, Word> {
+	fn execute(input: &[u8], target_gas: Option<u64>, _: &Context, _: bool) -> PrecompileResult {
+		sha3_precompile::<32>(
+			input,
+			target_gas,
+			Base::get(),
+			Word::get(),
+			tiny_keccak::Sha3::v256(),
+		)
 	}
 }
 
-pub struct Sha3FIPS512;
-
-impl LinearCostPrecompile for Sha3FIPS512 {
-	const BASE: u64 = 60;
-	const WORD: u64 = 12;
-
-	fn execute(input: &[u8], _: u64) -> Result<(ExitSucceed, Vec<u8>), PrecompileFailure> {
-		use tiny_keccak::Hasher;
-		let mut output = [0; 64];
-		let mut sha3 = tiny_keccak::Sha3::v512();
-		sha3.update(input);
-		sha3.finalize(&mut output);
-		Ok((ExitSucceed::Returned, output.to_vec()))
+/// The SHA3-512 precompile, costing `Base + Word * ceil(len / 32)` gas.
+///
+/// The default costs are those of the SHA-256 precompile.
+pub struct Sha3FIPS512<Base = ConstU64<60>, Word = ConstU64<12>>(PhantomData<(Base, Word)>);
+
+impl<Base: Get<u64>, Word: Get<u64>> Precompile for Sha3FIPS512<Base, Word> {
+	fn execute(input: &[u8], target_gas: Option<u64>, _: &Context, _: bool) -> PrecompileResult {
+		sha3_precompile::<64>(
+			input,
+			target_gas,
+			Base::get(),
+			Word::get(),
+			tiny_keccak::Sha3::v512(),
+		)
 	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use fp_evm::PrecompileFailure;
+	use pallet_evm_test_vector_support::test_precompile_test_vectors;
+
+	fn context() -> Context {
+		Context {
+			address: Default::default(),
+			caller: Default::default(),
+			apparent_value: From::from(0),
+		}
+	}
+
+	fn execute<P: Precompile>(input: &[u8]) -> Result<(ExitSucceed, Vec<u8>), PrecompileFailure> {
+		P::execute(input, None, &context(), false).map(|output| (output.exit_status, output.output))
+	}
+
+	#[test]
+	fn process_consensus_tests_for_sha3fips256() -> Result<(), String> {
+		test_precompile_test_vectors::<Sha3FIPS256>("../testdata/sha3fips256.json")?;
+		Ok(())
+	}
+
+	#[test]
+	fn process_consensus_tests_for_sha3fips512() -> Result<(), String> {
+		test_precompile_test_vectors::<Sha3FIPS512>("../testdata/sha3fips512.json")?;
+		Ok(())
+	}
+
+	#[test]
+	fn costs_are_configurable() {
+		type Tuned = Sha3FIPS256<ConstU64<30>, ConstU64<6>>;
+
+		let output = Tuned::execute(&[0; 33], Some(42), &context(), false)
+			.expect("the cost is within the target gas");
+		assert_eq!(output.cost, 42);
+		assert!(Tuned::execute(&[0; 33], Some(41), &context(), false).is_err());
+	}
 
 	#[test]
 	fn test_empty_input() -> Result<(), PrecompileFailure> {
@@ -67,9 +129,7 @@ mod tests {
 			\xf5\x80\xff\x4d\xe4\x3b\x49\xfa\x82\xd8\x0a\x4b\x80\xf8\x43\x4a\
 		";
 
-		let cost: u64 = 1;
-
-		match Sha3FIPS256::execute(&input, cost) {
+		match execute::<Sha3FIPS256>(&input) {
 			Ok((_, out)) => {
 				assert_eq!(out, expected);
 				Ok(())
@@ -88,9 +148,7 @@ mod tests {
 			\x53\xa8\x88\xb8\x4f\x42\x4d\x79\x2a\xf4\xb9\x20\x23\x98\xf3\x92\
 		";
 
-		let cost: u64 = 1;
-
-		match Sha3FIPS256::execute(input, cost) {
+		match execute::<Sha3FIPS256>(input) {
 			Ok((_, out)) => {
 				assert_eq!(out, expected);
 				Ok(())
@@ -109,9 +167,7 @@ mod tests {
 			\xd6\x43\xce\xa8\x5b\x84\xc8\xb8\xbb\x80\x95\x2e\x70\xb6\xea\xe0\
 		";
 
-		let cost: u64 = 1;
-
-		match Sha3FIPS256::execute(input, cost) {
+		match execute::<Sha3FIPS256>(input) {
 			Ok((_, out)) => {
 				assert_eq!(out, expected);
 				Ok(())
@@ -132,9 +188,7 @@ mod tests {
 			\x06\x85\x3b\x97\x97\xef\x9a\xb1\x0c\xbd\xe1\x00\x9c\x7d\x0f\x09\
 		";
 
-		let cost: u64 = 1;
-
-		match Sha3FIPS512::execute(input, cost) {
+		match execute::<Sha3FIPS512>(input) {
 			Ok((_, out)) => {
 				assert_eq!(out, expected);
 				Ok(())
@@ -20,9 +20,13 @@
 extern crate alloc;
 
 use alloc::vec::Vec;
-use core::cmp::min;
+use core::{cmp::min, marker::PhantomData};
 
-use fp_evm::{ExitError, ExitSucceed, LinearCostPrecompile, PrecompileFailure};
+use fp_evm::{
+	ensure_linear_cost, Context, ExitError, ExitSucceed, LinearCostPrecompile, Precompile,
+	PrecompileFailure, PrecompileOutput, PrecompileResult,
+};
+use sp_core::{ConstU64, Get};
 
 /// The identity precompile.
 pub struct Identity;
@@ -44,16 +48,7 @@ impl LinearCostPrecompile for ECRecover {
 	const WORD: u64 = 0;
 
 	fn execute(i: &[u8], _: u64) -> Result<(ExitSucceed, Vec<u8>), PrecompileFailure> {
-		let mut input = [0u8; 128];
-		input[..min(i.len(), 128)].copy_from_slice(&i[..min(i.len(), 128)]);
-
-		let mut msg = [0u8; 32];
-		let mut sig = [0u8; 65];
-
-		msg[0..32].copy_from_slice(&input[0..32]);
-		sig[0..32].copy_from_slice(&input[64..96]);
-		sig[32..64].copy_from_slice(&input[96..128]);
-		sig[64] = input[63];
+		let (sig, msg) = recover_input(i);
 
 		let result = match sp_io::crypto::secp256k1_ecdsa_recover(&sig, &msg) {
 			Ok(pubkey) => {
@@ -68,6 +63,23 @@ impl LinearCostPrecompile for ECRecover {
 	}
 }
 
+/// Reads the `(signature, message)` of an ecrecover input, `hash || v || r || s`, zero
+/// padded to 128 bytes.
+fn recover_input(i: &[u8]) -> ([u8; 65], [u8; 32]) {
+	let mut input = [0u8; 128];
+	input[..min(i.len(), 128)].copy_from_slice(&i[..min(i.len(), 128)]);
+
+	let mut msg = [0u8; 32];
+	let mut sig = [0u8; 65];
+
+	msg[0..32].copy_from_slice(&input[0..32]);
+	sig[0..32].copy_from_slice(&input[64..96]);
+	sig[32..64].copy_from_slice(&input[96..128]);
+	sig[64] = input[63];
+
+	(sig, msg)
+}
+
 /// The ripemd precompile.
 pub struct Ripemd160;
 
@@ -97,25 +109,17 @@ impl LinearCostPrecompile for Sha256 {
 	}
 }
 
-/// The ECRecoverPublicKey precompile.
+/// The ECRecoverPublicKey precompile, costing `Base + Word * ceil(len / 32)` gas.
 /// Similar to ECRecover, but returns the pubkey (not the corresponding Ethereum address)
-pub struct ECRecoverPublicKey;
-
-impl LinearCostPrecompile for ECRecoverPublicKey {
-	const BASE: u64 = 3000;
-	const WORD: u64 = 0;
-
-	fn execute(i: &[u8], _: u64) -> Result<(ExitSucceed, Vec<u8>), PrecompileFailure> {
-		let mut input = [0u8; 128];
-		input[..min(i.len(), 128)].copy_from_slice(&i[..min(i.len(), 128)]);
+///
+/// Only the first 128 bytes of the input are read. The default costs are those of ECRecover.
+pub struct ECRecoverPublicKey<Base = ConstU64<3000>, Word = ConstU64<0>>(PhantomData<(Base, Word)>);
 
-		let mut msg = [0u8; 32];
-		let mut sig = [0u8; 65];
+impl<Base: Get<u64>, Word: Get<u64>> Precompile for ECRecoverPublicKey<Base, Word> {
+	fn execute(i: &[u8], target_gas: Option<u64>, _: &Context, _: bool) -> PrecompileResult {
+		let cost = ensure_linear_cost(target_gas, i.len() as u64, Base::get(), Word::get())?;
 
-		msg[0..32].copy_from_slice(&input[0..32]);
-		sig[0..32].copy_from_slice(&input[64..96]);
-		sig[32..64].copy_from_slice(&input[96..128]);
-		sig[64] = input[63];
+		let (sig, msg) = recover_input(i);
 
 		let pubkey = sp_io::crypto::secp256k1_ecdsa_recover(&sig, &msg).map_err(|_| {
 			PrecompileFailure::Error {
@@ -123,7 +127,12 @@ impl LinearCostPrecompile for ECRecoverPublicKey {
 			}
 		})?;
 
-		Ok((ExitSucceed::Returned, pubkey.to_vec()))
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			cost,
+			output: pubkey.to_vec(),
+			logs: Default::default(),
+		})
 	}
 }
 
@@ -150,4 +159,10 @@ mod tests {
 		test_precompile_test_vectors::<Ripemd160>("../testdata/common_ripemd.json")?;
 		Ok(())
 	}
+
+	#[test]
+	fn process_consensus_tests_for_ecrecover_public_key() -> Result<(), String> {
+		test_precompile_test_vectors::<ECRecoverPublicKey>("../testdata/ecRecoverPublicKey.json")?;
+		Ok(())
+	}
 }
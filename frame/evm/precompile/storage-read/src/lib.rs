@@ -0,0 +1,131 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use core::marker::PhantomData;
+
+use fp_evm::{
+	Context, ExitError, ExitSucceed, Precompile, PrecompileFailure, PrecompileOutput,
+	PrecompileResult,
+};
+
+/// The storage keys contracts are allowed to read, with their gas costs.
+pub trait StorageReadAllowlist {
+	/// The gas cost of reading `key`, or `None` if it can't be read.
+	///
+	/// The cost should cover the largest value stored at the key.
+	fn read_cost(key: &[u8]) -> Option<u64>;
+}
+
+/// Precompile reading the raw Substrate storage.
+///
+/// The input is the whole storage key, for example a `storage_prefix` followed by the
+/// hashed key of a map, and the output is the SCALE encoded value, empty if there is none.
+/// Keys not allowed by `Allowlist` can't be read.
+pub struct StorageRead<Allowlist> {
+	_marker: PhantomData<Allowlist>,
+}
+
+impl<Allowlist: StorageReadAllowlist> Precompile for StorageRead<Allowlist> {
+	fn execute(
+		input: &[u8],
+		target_gas: Option<u64>,
+		_context: &Context,
+		_is_static: bool,
+	) -> PrecompileResult {
+		let cost = Allowlist::read_cost(input).ok_or_else(|| PrecompileFailure::Error {
+			exit_status: ExitError::Other("storage key not allowed".into()),
+		})?;
+		if let Some(target_gas) = target_gas {
+			if cost > target_gas {
+				return Err(PrecompileFailure::Error {
+					exit_status: ExitError::OutOfGas,
+				});
+			}
+		}
+
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			cost,
+			output: sp_io::storage::get(input).unwrap_or_default(),
+			logs: Default::default(),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	struct Allowlist;
+
+	impl StorageReadAllowlist for Allowlist {
+		fn read_cost(key: &[u8]) -> Option<u64> {
+			if key.starts_with(b":allowed") {
+				Some(1_000)
+			} else {
+				None
+			}
+		}
+	}
+
+	fn read(key: &[u8], target_gas: Option<u64>) -> PrecompileResult {
+		let context = Context {
+			address: Default::default(),
+			caller: Default::default(),
+			apparent_value: From::from(0),
+		};
+		sp_io::TestExternalities::default().execute_with(|| {
+			sp_io::storage::set(b":allowed:value", &[1, 2, 3]);
+			sp_io::storage::set(b":denied", &[4]);
+			StorageRead::<Allowlist>::execute(key, target_gas, &context, true)
+		})
+	}
+
+	#[test]
+	fn reads_allowed_keys() {
+		let output = read(b":allowed:value", None).expect("the key is allowed");
+		assert_eq!(output.output, vec![1, 2, 3]);
+		assert_eq!(output.cost, 1_000);
+
+		let output = read(b":allowed:missing", None).expect("the key is allowed");
+		assert!(output.output.is_empty());
+	}
+
+	#[test]
+	fn rejects_denied_keys() {
+		assert!(matches!(
+			read(b":denied", None),
+			Err(PrecompileFailure::Error {
+				exit_status: ExitError::Other(_)
+			})
+		));
+	}
+
+	#[test]
+	fn charges_the_cost_of_the_key() {
+		assert!(matches!(
+			read(b":allowed:value", Some(999)),
+			Err(PrecompileFailure::Error {
+				exit_status: ExitError::OutOfGas
+			})
+		));
+	}
+}
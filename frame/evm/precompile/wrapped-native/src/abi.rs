@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Solidity ABI encoding of the arguments and results of the precompile.
+
+use sp_core::{H160, U256};
+use sp_std::vec::Vec;
+
+/// Selector of a function, from its signature.
+pub fn selector(signature: &[u8]) -> [u8; 4] {
+	let mut selector = [0u8; 4];
+	selector.copy_from_slice(&sp_io::hashing::keccak_256(signature)[..4]);
+	selector
+}
+
+/// Reads the arguments following the selector.
+pub struct Reader<'a> {
+	arguments: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+	pub fn new(arguments: &'a [u8]) -> Self {
+		Self { arguments }
+	}
+
+	fn word(&self, index: usize) -> Option<&'a [u8]> {
+		self.arguments.get(index * 32..(index + 1) * 32)
+	}
+
+	/// The `address` argument at `index`.
+	pub fn address(&self, index: usize) -> Option<H160> {
+		let word = self.word(index)?;
+		if word[..12].iter().any(|byte| *byte != 0) {
+			return None;
+		}
+		Some(H160::from_slice(&word[12..]))
+	}
+
+	/// The `uint256` argument at `index`.
+	pub fn uint256(&self, index: usize) -> Option<U256> {
+		Some(U256::from_big_endian(self.word(index)?))
+	}
+}
+
+/// Encodes a `uint256`, or a `uint8` or `bool`.
+pub fn uint256(value: U256) -> [u8; 32] {
+	let mut word = [0u8; 32];
+	value.to_big_endian(&mut word);
+	word
+}
+
+/// Encodes an `address`.
+pub fn address(value: H160) -> [u8; 32] {
+	let mut word = [0u8; 32];
+	word[12..].copy_from_slice(value.as_bytes());
+	word
+}
+
+/// Encodes a `string`, as the only returned value.
+pub fn string(value: &str) -> Vec<u8> {
+	let mut output = uint256(U256::from(32)).to_vec();
+	output.extend_from_slice(&uint256(U256::from(value.len())));
+	output.extend_from_slice(value.as_bytes());
+	output.resize(output.len() + (32 - value.len() % 32) % 32, 0);
+	output
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn encodes_strings_padded() {
+		let output = string("Wrapped Ether");
+
+		assert_eq!(output.len(), 32 * 3);
+		assert_eq!(output[..32], uint256(U256::from(32)));
+		assert_eq!(output[32..64], uint256(U256::from(13)));
+		assert_eq!(&output[64..77], b"Wrapped Ether");
+		assert!(output[77..].iter().all(|byte| *byte == 0));
+	}
+
+	#[test]
+	fn decodes_encoded_arguments() {
+		let arguments = [address(H160::repeat_byte(0x11)), uint256(U256::from(1_000))].concat();
+		let reader = Reader::new(&arguments);
+
+		assert_eq!(reader.address(0), Some(H160::repeat_byte(0x11)));
+		assert_eq!(reader.uint256(1), Some(U256::from(1_000)));
+		assert_eq!(reader.address(1), None);
+		assert_eq!(reader.uint256(2), None);
+	}
+}
@@ -0,0 +1,335 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod abi;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use core::marker::PhantomData;
+use fp_evm::{
+	Context, ExitError, ExitRevert, ExitSucceed, Log, Precompile, PrecompileFailure,
+	PrecompileOutput, PrecompileResult,
+};
+use frame_support::traits::{Currency, ExistenceRequirement, Get};
+use pallet_evm::{AddressMapping, GasWeightMapping, Pallet as EVM};
+use sp_core::{H160, H256, U256};
+use sp_runtime::traits::UniqueSaturatedInto;
+use sp_std::{vec, vec::Vec};
+
+use crate::abi::Reader;
+
+/// Name, symbol and decimals of the wrapped native currency.
+pub trait WrappedNativeMetadata {
+	fn name() -> &'static str;
+	fn symbol() -> &'static str;
+	fn decimals() -> u8;
+}
+
+/// Slot of the `balanceOf` mapping, as in the WETH9 contract.
+const BALANCES_SLOT: u64 = 3;
+/// Slot of the `allowance` mapping, as in the WETH9 contract.
+const ALLOWANCES_SLOT: u64 = 4;
+
+/// Precompile wrapping the native currency as an ERC-20 token, following the WETH9 contract.
+///
+/// The native currency deposited is held by the account of the precompile address, at which
+/// the runtime places the precompile. The balances and allowances are stored in the EVM
+/// storage of the precompile address, with the layout of WETH9. The functions are:
+///
+/// - `name()`, `symbol()` and `decimals()`, from `M`.
+/// - `totalSupply()`, the native currency held by the precompile.
+/// - `balanceOf(address)` and `allowance(address,address)`.
+/// - `deposit()`, also called with an empty input, wrapping the value of the call.
+/// - `withdraw(uint256)`, unwrapping to the native currency.
+/// - `transfer(address,uint256)`, `transferFrom(address,address,uint256)` and
+///   `approve(address,uint256)`.
+///
+/// The `Deposit`, `Withdrawal`, `Transfer` and `Approval` events are logged as by WETH9.
+pub struct WrappedNative<T, M> {
+	_marker: PhantomData<(T, M)>,
+}
+
+fn error(message: &'static str) -> PrecompileFailure {
+	PrecompileFailure::Error {
+		exit_status: ExitError::Other(message.into()),
+	}
+}
+
+fn revert(cost: u64) -> PrecompileFailure {
+	PrecompileFailure::Revert {
+		exit_status: ExitRevert::Reverted,
+		output: Default::default(),
+		cost,
+	}
+}
+
+fn keccak(data: &[u8]) -> H256 {
+	H256(sp_io::hashing::keccak_256(data))
+}
+
+fn balance_key(owner: H160) -> H256 {
+	keccak(&[abi::address(owner), abi::uint256(BALANCES_SLOT.into())].concat())
+}
+
+fn allowance_key(owner: H160, spender: H160) -> H256 {
+	let owner_key = keccak(&[abi::address(owner), abi::uint256(ALLOWANCES_SLOT.into())].concat());
+	keccak(&[abi::address(spender), owner_key.0].concat())
+}
+
+/// An event of the precompile, with its indexed addresses and its amount.
+fn log(precompile: H160, signature: &[u8], indexed: &[H160], amount: U256) -> Log {
+	let mut topics = vec![keccak(signature)];
+	topics.extend(indexed.iter().map(|address| H256(abi::address(*address))));
+	Log {
+		address: precompile,
+		topics,
+		data: abi::uint256(amount).to_vec(),
+	}
+}
+
+impl<T, M> WrappedNative<T, M>
+where
+	T: pallet_evm::Config,
+	M: WrappedNativeMetadata,
+{
+	/// Gas cost of `reads` and `writes` storage accesses, failing if over `target_gas`.
+	fn cost(target_gas: Option<u64>, reads: u64, writes: u64) -> Result<u64, PrecompileFailure> {
+		let cost =
+			T::GasWeightMapping::weight_to_gas(T::DbWeight::get().reads_writes(reads, writes));
+		if let Some(gas) = target_gas {
+			if cost > gas {
+				return Err(PrecompileFailure::Error {
+					exit_status: ExitError::OutOfGas,
+				});
+			}
+		}
+		Ok(cost)
+	}
+
+	fn read(precompile: H160, key: H256) -> U256 {
+		U256::from_big_endian(EVM::<T>::account_storages(precompile, key).as_bytes())
+	}
+
+	/// Writes a slot, accounted in the storage deposits and limits of the transaction.
+	fn write(precompile: H160, key: H256, value: U256) {
+		EVM::<T>::set_precompile_storage(precompile, key, H256(abi::uint256(value)));
+	}
+
+	/// Moves `amount` from the balance of `from` to the one of `to`, spending the allowance of
+	/// `spender` if it isn't `from`.
+	fn transfer(
+		precompile: H160,
+		spender: H160,
+		from: H160,
+		to: H160,
+		amount: U256,
+		cost: u64,
+	) -> Result<(), PrecompileFailure> {
+		let from_balance = Self::read(precompile, balance_key(from));
+		if from_balance < amount {
+			return Err(revert(cost));
+		}
+		if from != spender {
+			let allowance = Self::read(precompile, allowance_key(from, spender));
+			if allowance != U256::MAX {
+				if allowance < amount {
+					return Err(revert(cost));
+				}
+				Self::write(precompile, allowance_key(from, spender), allowance - amount);
+			}
+		}
+		Self::write(precompile, balance_key(from), from_balance - amount);
+		let to_balance = Self::read(precompile, balance_key(to));
+		Self::write(
+			precompile,
+			balance_key(to),
+			to_balance.saturating_add(amount),
+		);
+		Ok(())
+	}
+
+	fn returned(cost: u64, output: Vec<u8>, logs: Vec<Log>) -> PrecompileResult {
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			cost,
+			output,
+			logs,
+		})
+	}
+}
+
+impl<T, M> Precompile for WrappedNative<T, M>
+where
+	T: pallet_evm::Config,
+	M: WrappedNativeMetadata,
+{
+	fn execute(
+		input: &[u8],
+		target_gas: Option<u64>,
+		context: &Context,
+		is_static: bool,
+	) -> PrecompileResult {
+		let precompile = context.address;
+		let caller = context.caller;
+		let value = context.apparent_value;
+		let true_value = abi::uint256(U256::one()).to_vec();
+
+		// As the fallback function of WETH9, an empty input deposits the value.
+		let selector = if input.is_empty() {
+			abi::selector(b"deposit()")
+		} else {
+			let mut selector = [0u8; 4];
+			selector.copy_from_slice(input.get(..4).ok_or_else(|| error("decode failed"))?);
+			selector
+		};
+		let reader = Reader::new(&input[input.len().min(4)..]);
+
+		if selector == abi::selector(b"deposit()") {
+			if is_static {
+				return Err(error("cannot deposit in a static context"));
+			}
+			let cost = Self::cost(target_gas, 1, 1)?;
+			// The value of the call is already transferred to the precompile.
+			let balance = Self::read(precompile, balance_key(caller));
+			Self::write(
+				precompile,
+				balance_key(caller),
+				balance.saturating_add(value),
+			);
+			return Self::returned(
+				cost,
+				Vec::new(),
+				vec![log(
+					precompile,
+					b"Deposit(address,uint256)",
+					&[caller],
+					value,
+				)],
+			);
+		}
+		if !value.is_zero() {
+			return Err(error("value not accepted"));
+		}
+
+		if selector == abi::selector(b"name()") {
+			Self::returned(0, abi::string(M::name()), Vec::new())
+		} else if selector == abi::selector(b"symbol()") {
+			Self::returned(0, abi::string(M::symbol()), Vec::new())
+		} else if selector == abi::selector(b"decimals()") {
+			Self::returned(0, abi::uint256(M::decimals().into()).to_vec(), Vec::new())
+		} else if selector == abi::selector(b"totalSupply()") {
+			let cost = Self::cost(target_gas, 1, 0)?;
+			let account = T::AddressMapping::into_account_id(precompile);
+			let supply: u128 = T::Currency::free_balance(&account).unique_saturated_into();
+			Self::returned(cost, abi::uint256(supply.into()).to_vec(), Vec::new())
+		} else if selector == abi::selector(b"balanceOf(address)") {
+			let cost = Self::cost(target_gas, 1, 0)?;
+			let owner = reader.address(0).ok_or_else(|| error("decode failed"))?;
+			let balance = Self::read(precompile, balance_key(owner));
+			Self::returned(cost, abi::uint256(balance).to_vec(), Vec::new())
+		} else if selector == abi::selector(b"allowance(address,address)") {
+			let cost = Self::cost(target_gas, 1, 0)?;
+			let owner = reader.address(0).ok_or_else(|| error("decode failed"))?;
+			let spender = reader.address(1).ok_or_else(|| error("decode failed"))?;
+			let allowance = Self::read(precompile, allowance_key(owner, spender));
+			Self::returned(cost, abi::uint256(allowance).to_vec(), Vec::new())
+		} else if selector == abi::selector(b"withdraw(uint256)") {
+			if is_static {
+				return Err(error("cannot withdraw in a static context"));
+			}
+			let cost = Self::cost(target_gas, 3, 3)?;
+			let amount = reader.uint256(0).ok_or_else(|| error("decode failed"))?;
+			let balance = Self::read(precompile, balance_key(caller));
+			if balance < amount || amount > U256::from(u128::MAX) {
+				return Err(revert(cost));
+			}
+			Self::write(precompile, balance_key(caller), balance - amount);
+			T::Currency::transfer(
+				&T::AddressMapping::into_account_id(precompile),
+				&T::AddressMapping::into_account_id(caller),
+				amount.low_u128().unique_saturated_into(),
+				ExistenceRequirement::AllowDeath,
+			)
+			.map_err(|_| revert(cost))?;
+			Self::returned(
+				cost,
+				Vec::new(),
+				vec![log(
+					precompile,
+					b"Withdrawal(address,uint256)",
+					&[caller],
+					amount,
+				)],
+			)
+		} else if selector == abi::selector(b"approve(address,uint256)") {
+			if is_static {
+				return Err(error("cannot approve in a static context"));
+			}
+			let cost = Self::cost(target_gas, 0, 1)?;
+			let spender = reader.address(0).ok_or_else(|| error("decode failed"))?;
+			let amount = reader.uint256(1).ok_or_else(|| error("decode failed"))?;
+			Self::write(precompile, allowance_key(caller, spender), amount);
+			Self::returned(
+				cost,
+				true_value,
+				vec![log(
+					precompile,
+					b"Approval(address,address,uint256)",
+					&[caller, spender],
+					amount,
+				)],
+			)
+		} else if selector == abi::selector(b"transfer(address,uint256)")
+			|| selector == abi::selector(b"transferFrom(address,address,uint256)")
+		{
+			if is_static {
+				return Err(error("cannot transfer in a static context"));
+			}
+			let cost = Self::cost(target_gas, 3, 3)?;
+			let (from, to, amount) = if selector == abi::selector(b"transfer(address,uint256)") {
+				(
+					caller,
+					reader.address(0).ok_or_else(|| error("decode failed"))?,
+					reader.uint256(1).ok_or_else(|| error("decode failed"))?,
+				)
+			} else {
+				(
+					reader.address(0).ok_or_else(|| error("decode failed"))?,
+					reader.address(1).ok_or_else(|| error("decode failed"))?,
+					reader.uint256(2).ok_or_else(|| error("decode failed"))?,
+				)
+			};
+			Self::transfer(precompile, caller, from, to, amount, cost)?;
+			Self::returned(
+				cost,
+				true_value,
+				vec![log(
+					precompile,
+					b"Transfer(address,address,uint256)",
+					&[from, to],
+					amount,
+				)],
+			)
+		} else {
+			Err(error("unknown selector"))
+		}
+	}
+}
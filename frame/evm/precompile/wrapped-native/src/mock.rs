@@ -0,0 +1,192 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test mock for unit tests
+
+use fp_evm::{Context, Precompile, PrecompileResult, PrecompileSet};
+use frame_support::{parameter_types, traits::ConstU32, weights::Weight};
+use pallet_evm::{EnsureAddressNever, EnsureAddressRoot, FeeCalculator, IdentityAddressMapping};
+use sp_core::{H160, H256, U256};
+use sp_runtime::traits::{BlakeTwo256, IdentityLookup};
+
+use crate::{WrappedNative, WrappedNativeMetadata};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime! {
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Timestamp: pallet_timestamp::{Pallet, Call, Storage},
+		EVM: pallet_evm::{Pallet, Call, Storage, Config, Event<T>},
+	}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Call = Call;
+	type Hashing = BlakeTwo256;
+	type AccountId = H160;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = sp_runtime::generic::Header<u64, BlakeTwo256>;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 0;
+}
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = ();
+}
+
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1000;
+}
+impl pallet_timestamp::Config for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+
+/// No fees, so that the balances only move with the value of the calls.
+pub struct FreeGasPrice;
+impl FeeCalculator for FreeGasPrice {
+	fn min_gas_price() -> (U256, Weight) {
+		(U256::zero(), 0)
+	}
+}
+
+pub struct WrappedEther;
+impl WrappedNativeMetadata for WrappedEther {
+	fn name() -> &'static str {
+		"Wrapped Ether"
+	}
+
+	fn symbol() -> &'static str {
+		"WETH"
+	}
+
+	fn decimals() -> u8 {
+		18
+	}
+}
+
+/// The wrapped native currency precompile, at [`MockPrecompiles::wrapped_native`].
+pub struct MockPrecompiles;
+impl MockPrecompiles {
+	pub fn wrapped_native() -> H160 {
+		H160::from_low_u64_be(2048)
+	}
+}
+impl PrecompileSet for MockPrecompiles {
+	fn execute(
+		&self,
+		address: H160,
+		input: &[u8],
+		target_gas: Option<u64>,
+		context: &Context,
+		is_static: bool,
+	) -> Option<PrecompileResult> {
+		if address == Self::wrapped_native() {
+			Some(WrappedNative::<Test, WrappedEther>::execute(
+				input, target_gas, context, is_static,
+			))
+		} else {
+			None
+		}
+	}
+
+	fn is_precompile(&self, address: H160) -> bool {
+		address == Self::wrapped_native()
+	}
+}
+
+parameter_types! {
+	pub static StorageDepositPerByte: u64 = 0;
+	pub static MaxStorageSlotsPerTransaction: Option<u64> = None;
+	pub MockPrecompilesValue: MockPrecompiles = MockPrecompiles;
+}
+
+impl pallet_evm::Config for Test {
+	type FeeCalculator = FreeGasPrice;
+	type GasWeightMapping = ();
+
+	type CallOrigin = EnsureAddressRoot<Self::AccountId>;
+	type WithdrawOrigin = EnsureAddressNever<Self::AccountId>;
+
+	type AddressMapping = IdentityAddressMapping;
+	type Currency = Balances;
+	type Runner = pallet_evm::runner::stack::Runner<Self>;
+
+	type Event = Event;
+	type PrecompilesType = MockPrecompiles;
+	type PrecompilesValue = MockPrecompilesValue;
+	type ChainId = ();
+	type BlockGasLimit = ();
+	type OnChargeTransaction = ();
+	type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
+	type FindAuthor = ();
+
+	fn storage_deposit_per_byte() -> u64 {
+		StorageDepositPerByte::get()
+	}
+
+	fn max_storage_slots_per_transaction() -> Option<u64> {
+		MaxStorageSlotsPerTransaction::get()
+	}
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap()
+		.into()
+}
@@ -0,0 +1,316 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::mock::*;
+
+use fp_evm::{CallInfo, ExitReason};
+use pallet_evm::{Config, Runner};
+
+fn alice() -> H160 {
+	H160::repeat_byte(0x11)
+}
+
+fn bob() -> H160 {
+	H160::repeat_byte(0x22)
+}
+
+fn carol() -> H160 {
+	H160::repeat_byte(0x33)
+}
+
+fn call(source: H160, input: Vec<u8>, value: u64) -> CallInfo {
+	<Test as Config>::Runner::call(
+		source,
+		MockPrecompiles::wrapped_native(),
+		input,
+		U256::from(value),
+		1_000_000,
+		Some(U256::zero()),
+		None,
+		None,
+		Vec::new(),
+		true,
+		<Test as Config>::config(),
+	)
+	.expect("call succeeds")
+}
+
+fn input(signature: &[u8], arguments: &[[u8; 32]]) -> Vec<u8> {
+	[&abi::selector(signature)[..], &arguments.concat()].concat()
+}
+
+fn balance_of(owner: H160) -> U256 {
+	let info = call(
+		alice(),
+		input(b"balanceOf(address)", &[abi::address(owner)]),
+		0,
+	);
+	U256::from_big_endian(&info.value)
+}
+
+fn allowance(owner: H160, spender: H160) -> U256 {
+	let info = call(
+		alice(),
+		input(
+			b"allowance(address,address)",
+			&[abi::address(owner), abi::address(spender)],
+		),
+		0,
+	);
+	U256::from_big_endian(&info.value)
+}
+
+fn deposit(source: H160, amount: u64) -> CallInfo {
+	call(source, input(b"deposit()", &[]), amount)
+}
+
+fn approve(owner: H160, spender: H160, amount: U256) -> CallInfo {
+	call(
+		owner,
+		input(
+			b"approve(address,uint256)",
+			&[abi::address(spender), abi::uint256(amount)],
+		),
+		0,
+	)
+}
+
+fn transfer_from(spender: H160, from: H160, to: H160, amount: u64) -> CallInfo {
+	call(
+		spender,
+		input(
+			b"transferFrom(address,address,uint256)",
+			&[
+				abi::address(from),
+				abi::address(to),
+				abi::uint256(amount.into()),
+			],
+		),
+		0,
+	)
+}
+
+fn native_balance(address: H160) -> u64 {
+	Balances::free_balance(&address)
+}
+
+fn ext() -> sp_io::TestExternalities {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		Balances::make_free_balance_be(&alice(), 1_000_000);
+		Balances::make_free_balance_be(&bob(), 1_000_000);
+	});
+	ext
+}
+
+#[test]
+fn deposit_should_wrap_the_value() {
+	ext().execute_with(|| {
+		let info = deposit(alice(), 1_000);
+
+		assert!(info.exit_reason.is_succeed());
+		assert_eq!(
+			info.logs,
+			vec![log(
+				MockPrecompiles::wrapped_native(),
+				b"Deposit(address,uint256)",
+				&[alice()],
+				U256::from(1_000),
+			)]
+		);
+		assert_eq!(balance_of(alice()), U256::from(1_000));
+		assert_eq!(native_balance(alice()), 999_000);
+		assert_eq!(native_balance(MockPrecompiles::wrapped_native()), 1_000);
+
+		let supply = call(alice(), input(b"totalSupply()", &[]), 0);
+		assert_eq!(U256::from_big_endian(&supply.value), U256::from(1_000));
+	});
+}
+
+#[test]
+fn empty_input_should_deposit() {
+	ext().execute_with(|| {
+		assert!(call(alice(), Vec::new(), 1_000).exit_reason.is_succeed());
+
+		assert_eq!(balance_of(alice()), U256::from(1_000));
+	});
+}
+
+#[test]
+fn withdraw_should_unwrap_to_the_native_currency() {
+	ext().execute_with(|| {
+		deposit(alice(), 1_000);
+
+		let info = call(
+			alice(),
+			input(b"withdraw(uint256)", &[abi::uint256(400.into())]),
+			0,
+		);
+
+		assert!(info.exit_reason.is_succeed());
+		assert_eq!(
+			info.logs,
+			vec![log(
+				MockPrecompiles::wrapped_native(),
+				b"Withdrawal(address,uint256)",
+				&[alice()],
+				U256::from(400),
+			)]
+		);
+		assert_eq!(balance_of(alice()), U256::from(600));
+		assert_eq!(native_balance(alice()), 999_400);
+		assert_eq!(native_balance(MockPrecompiles::wrapped_native()), 600);
+	});
+}
+
+#[test]
+fn withdraw_over_the_balance_should_revert() {
+	ext().execute_with(|| {
+		deposit(alice(), 1_000);
+
+		let info = call(
+			alice(),
+			input(b"withdraw(uint256)", &[abi::uint256(1_001.into())]),
+			0,
+		);
+
+		assert!(matches!(info.exit_reason, ExitReason::Revert(_)));
+		assert_eq!(balance_of(alice()), U256::from(1_000));
+		assert_eq!(native_balance(alice()), 999_000);
+	});
+}
+
+#[test]
+fn approve_should_set_the_allowance() {
+	ext().execute_with(|| {
+		let info = approve(alice(), bob(), U256::from(300));
+
+		assert!(info.exit_reason.is_succeed());
+		assert_eq!(info.value, abi::uint256(U256::one()).to_vec());
+		assert_eq!(
+			info.logs,
+			vec![log(
+				MockPrecompiles::wrapped_native(),
+				b"Approval(address,address,uint256)",
+				&[alice(), bob()],
+				U256::from(300),
+			)]
+		);
+		assert_eq!(allowance(alice(), bob()), U256::from(300));
+	});
+}
+
+#[test]
+fn transfer_from_should_spend_the_allowance() {
+	ext().execute_with(|| {
+		deposit(alice(), 1_000);
+		approve(alice(), bob(), U256::from(300));
+
+		let info = transfer_from(bob(), alice(), carol(), 200);
+
+		assert!(info.exit_reason.is_succeed());
+		assert_eq!(
+			info.logs,
+			vec![log(
+				MockPrecompiles::wrapped_native(),
+				b"Transfer(address,address,uint256)",
+				&[alice(), carol()],
+				U256::from(200),
+			)]
+		);
+		assert_eq!(balance_of(alice()), U256::from(800));
+		assert_eq!(balance_of(carol()), U256::from(200));
+		assert_eq!(allowance(alice(), bob()), U256::from(100));
+
+		// Over the remaining allowance.
+		let info = transfer_from(bob(), alice(), carol(), 200);
+		assert!(matches!(info.exit_reason, ExitReason::Revert(_)));
+		assert_eq!(balance_of(alice()), U256::from(800));
+	});
+}
+
+#[test]
+fn transfer_from_should_not_spend_an_infinite_allowance() {
+	ext().execute_with(|| {
+		deposit(alice(), 1_000);
+		approve(alice(), bob(), U256::MAX);
+
+		assert!(transfer_from(bob(), alice(), carol(), 200)
+			.exit_reason
+			.is_succeed());
+
+		assert_eq!(allowance(alice(), bob()), U256::MAX);
+	});
+}
+
+#[test]
+fn transfer_should_move_the_balance_of_the_caller() {
+	ext().execute_with(|| {
+		deposit(alice(), 1_000);
+
+		let info = call(
+			alice(),
+			input(
+				b"transfer(address,uint256)",
+				&[abi::address(carol()), abi::uint256(300.into())],
+			),
+			0,
+		);
+
+		assert!(info.exit_reason.is_succeed());
+		assert_eq!(balance_of(alice()), U256::from(700));
+		assert_eq!(balance_of(carol()), U256::from(300));
+	});
+}
+
+#[test]
+fn new_balances_should_pay_storage_deposits() {
+	ext().execute_with(|| {
+		StorageDepositPerByte::set(1);
+
+		assert!(deposit(alice(), 1_000).exit_reason.is_succeed());
+
+		// The new balance slot of alice.
+		assert_eq!(
+			pallet_evm::Pallet::<Test>::storage_deposits(MockPrecompiles::wrapped_native()),
+			pallet_evm::STORAGE_SLOT_BYTES as u64
+		);
+		assert_eq!(
+			native_balance(alice()),
+			1_000_000 - 1_000 - pallet_evm::STORAGE_SLOT_BYTES as u64
+		);
+	});
+}
+
+#[test]
+fn new_balances_should_count_in_the_storage_slots_limit() {
+	ext().execute_with(|| {
+		MaxStorageSlotsPerTransaction::set(Some(0));
+
+		let info = deposit(alice(), 1_000);
+
+		assert_eq!(
+			info.exit_reason,
+			ExitReason::Error(fp_evm::ExitError::Other(
+				"new storage slots limit exceeded".into()
+			))
+		);
+		assert_eq!(balance_of(alice()), U256::zero());
+		assert_eq!(native_balance(alice()), 1_000_000);
+	});
+}
@@ -87,7 +87,7 @@ benchmarks! {
 			Some(nonce_as_u256),
 			Vec::new(),
 			is_transactional,
-			T::config(),
+			&Pallet::<T>::evm_config(),
 		);
 		assert_eq!(create_runner_results.is_ok(), true, "create() failed");
 
@@ -120,10 +120,45 @@ benchmarks! {
 			Some(nonce_as_u256),
 			Vec::new(),
 			is_transactional,
-			T::config(),
+			&Pallet::<T>::evm_config(),
 		);
 		assert_eq!(call_runner_results.is_ok(), true, "call() failed");
 	}
+
+	force_set_balance {
+		let address = H160::repeat_byte(0x11);
+		let balance: BalanceOf<T> = 1_000_000u32.into();
+	}: _(RawOrigin::Root, address, balance)
+	verify {
+		assert_eq!(Pallet::<T>::account_basic(&address).0.balance, U256::from(1_000_000));
+	}
+
+	force_set_nonce {
+		let address = H160::repeat_byte(0x11);
+		let nonce: T::Index = 42u32.into();
+	}: _(RawOrigin::Root, address, nonce)
+	verify {
+		assert_eq!(Pallet::<T>::account_basic(&address).0.nonce, U256::from(42));
+	}
+
+	force_set_code {
+		let c in 1..24_576;
+
+		let address = H160::repeat_byte(0x11);
+		let code = sp_std::vec![0x00; c as usize];
+	}: _(RawOrigin::Root, address, code.clone())
+	verify {
+		assert_eq!(Pallet::<T>::account_codes(address), code);
+	}
+
+	force_set_storage {
+		let address = H160::repeat_byte(0x11);
+		let index = H256::repeat_byte(0x01);
+		let value = H256::repeat_byte(0x02);
+	}: _(RawOrigin::Root, address, index, value)
+	verify {
+		assert_eq!(Pallet::<T>::account_storages(address, index), value);
+	}
 }
 
 impl_benchmark_test_suite!(Pallet, crate::tests::new_test_ext(), crate::tests::Test);
@@ -56,6 +56,7 @@
 
 pub mod benchmarking;
 
+pub mod migration;
 #[cfg(test)]
 mod mock;
 pub mod runner;
@@ -63,20 +64,27 @@ pub mod runner;
 mod tests;
 
 use frame_support::{
-	dispatch::DispatchResultWithPostInfo,
+	dispatch::{DispatchResult, DispatchResultWithPostInfo},
+	storage::child,
 	traits::{
-		tokens::fungible::Inspect, Currency, ExistenceRequirement, FindAuthor, Get, Imbalance,
-		OnUnbalanced, SignedImbalance, WithdrawReasons,
+		tokens::{fungible::Inspect, fungibles, BalanceConversion, WithdrawConsequence},
+		Currency, ExistenceRequirement, FindAuthor, Get, Imbalance, OnUnbalanced,
+		ReservableCurrency, SignedImbalance, WithdrawReasons,
 	},
 	weights::{Pays, PostDispatchInfo, Weight},
 };
 use frame_system::RawOrigin;
-use sp_core::{Hasher, H160, H256, U256};
+use scale_info::TypeInfo;
+use sha3::{Digest, Keccak256};
+use sp_core::{storage::ChildInfo, Hasher, H160, H256, U256};
 use sp_runtime::{
-	traits::{BadOrigin, Saturating, UniqueSaturatedInto, Zero},
-	AccountId32, DispatchErrorWithPostInfo,
+	traits::{BadOrigin, One, Saturating, UniqueSaturatedFrom, UniqueSaturatedInto, Zero},
+	AccountId32, DispatchErrorWithPostInfo, PerThing, Permill, RuntimeDebug,
+};
+use sp_std::{
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+	vec::Vec,
 };
-use sp_std::vec::Vec;
 
 pub use evm::{
 	Config as EvmConfig, Context, ExitError, ExitFatal, ExitReason, ExitRevert, ExitSucceed,
@@ -84,23 +92,55 @@ pub use evm::{
 #[cfg(feature = "std")]
 use fp_evm::GenesisAccount;
 pub use fp_evm::{
-	Account, CallInfo, CreateInfo, ExecutionInfo, FeeCalculator, LinearCostPrecompile, Log,
-	Precompile, PrecompileFailure, PrecompileOutput, PrecompileResult, PrecompileSet, Vicinity,
+	Account, CallInfo, CallOrCreateInfo, CreateInfo, DryRunInfo, ExecutionInfo, FeeCalculator,
+	GasParameter, InvalidEvmTransaction, LinearCostPrecompile, Log, Precompile, PrecompileFailure,
+	PrecompileOutput, PrecompileResult, PrecompileSet, Vicinity,
 };
+use fp_storage::EVM_ACCOUNT_STORAGE_CHILD_PREFIX;
 
 pub use self::{
 	pallet::*,
 	runner::{Runner, RunnerError},
 };
 
+/// Kind of a precompile failure, as reported by [`Event::PrecompileFailed`].
+#[derive(Clone, Copy, Eq, PartialEq, codec::Encode, codec::Decode, RuntimeDebug, TypeInfo)]
+pub enum PrecompileFailureKind {
+	/// The precompile ran out of gas.
+	OutOfGas,
+	/// The precompile failed with another error.
+	Error,
+	/// The precompile reverted.
+	Revert,
+	/// The precompile failed with a fatal error, aborting the whole execution.
+	Fatal,
+}
+
+impl PrecompileFailureKind {
+	pub fn of(failure: &PrecompileFailure) -> Self {
+		match failure {
+			PrecompileFailure::Error {
+				exit_status: ExitError::OutOfGas,
+			} => Self::OutOfGas,
+			PrecompileFailure::Error { .. } => Self::Error,
+			PrecompileFailure::Revert { .. } => Self::Revert,
+			PrecompileFailure::Fatal { .. } => Self::Fatal,
+		}
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
 	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
@@ -123,7 +163,7 @@ pub mod pallet {
 		/// Mapping from address to account id.
 		type AddressMapping: AddressMapping<Self::AccountId>;
 		/// Currency type for withdraw and balance storage.
-		type Currency: Currency<Self::AccountId> + Inspect<Self::AccountId>;
+		type Currency: ReservableCurrency<Self::AccountId> + Inspect<Self::AccountId>;
 
 		/// The overarching event type.
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
@@ -146,9 +186,99 @@ pub mod pallet {
 		type FindAuthor: FindAuthor<H160>;
 
 		/// EVM config used in the module.
+		///
+		/// Hardfork dependent gas accounting, such as the EIP-2929 warm and cold accesses
+		/// seeded from the transaction access list, follows this configuration.
 		fn config() -> &'static EvmConfig {
 			&LONDON_CONFIG
 		}
+
+		/// Gas costs overriding the ones of [`Config::config`], e.g. to make `SSTORE` more
+		/// expensive on chains with a tight storage budget. The executions use
+		/// [`Pallet::evm_config`], with the overrides applied.
+		fn gas_overrides() -> Vec<(GasParameter, u64)> {
+			Vec::new()
+		}
+
+		/// Addresses of the precompiles. A revert bytecode stub is stored at each of them, so
+		/// that EXTCODESIZE checks see them as contracts.
+		fn precompile_addresses() -> Vec<H160> {
+			Vec::new()
+		}
+
+		/// Whether SELFDESTRUCT follows EIP-6780 (Cancun), only deleting accounts created in
+		/// the same transaction. Otherwise, SELFDESTRUCT always deletes the account.
+		fn eip6780_selfdestruct() -> bool {
+			false
+		}
+
+		/// Deposit per byte of contract code and storage, reserved on the contract account
+		/// when a transaction grows its state and refunded to the sender of the transaction
+		/// which shrinks it. Zero disables the storage deposits.
+		///
+		/// A storage slot counts for [`STORAGE_SLOT_BYTES`] bytes.
+		fn storage_deposit_per_byte() -> BalanceOf<Self> {
+			Zero::zero()
+		}
+
+		/// Maximum number of storage slots a transaction can set from zero. Executions over
+		/// the limit are reverted with an `ExitError::Other` error.
+		fn max_storage_slots_per_transaction() -> Option<u64> {
+			None
+		}
+
+		/// Maximum number of contracts a transaction can create. Executions over the limit
+		/// are reverted with an `ExitError::Other` error.
+		fn max_contracts_per_transaction() -> Option<u64> {
+			None
+		}
+
+		/// Whether a [`Event::PrecompileFailed`] event is deposited when a precompile fails,
+		/// including the failures handled by the calling contract.
+		fn precompile_failure_events() -> bool {
+			false
+		}
+
+		/// Whether the transactions of `address` are exempt from the base fee, so that bridge
+		/// relayers or system accounts can transact at a zero gas price. Their transactions are
+		/// checked and charged against a base fee of zero.
+		///
+		/// A runtime can keep the exempt addresses in a storage under governance control.
+		fn is_fee_exempt(_address: &H160) -> bool {
+			false
+		}
+
+		/// Address sponsoring the calls of `source` to the contract `target`, eg. with a gas
+		/// subsidy of the contract, and the share of their fees it pays. The sponsor only pays
+		/// when it can pay its whole share, and `source` pays the rest of the fees.
+		fn fee_sponsor(_source: &H160, _target: &H160) -> Option<(H160, Permill)> {
+			None
+		}
+
+		/// Called once `sponsor` paid `fee` of a call of `source` to `target`.
+		fn on_fee_sponsored(_sponsor: &H160, _source: &H160, _target: &H160, _fee: U256) {}
+
+		/// Whether the account of `address` has been migrated to [`Config::AddressMapping`],
+		/// eg. with [`migration::AddressMappingMigration::is_migrated`] while the runtime
+		/// migrates its address mapping. The transactions of an address which isn't are
+		/// rejected with [`Error::AccountNotMigrated`].
+		fn is_address_migrated(_address: &H160) -> bool {
+			true
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let mut weight = T::DbWeight::get().read;
+			if Self::on_chain_storage_version() < 1 {
+				// The codes are moved by `migration::AccountCodesMigration`.
+				STORAGE_VERSION.put::<Self>();
+				weight = weight.saturating_add(T::DbWeight::get().write);
+			}
+			// Precompiles may have been added by the upgrade.
+			weight.saturating_add(Self::register_precompiles())
+		}
 	}
 
 	#[pallet::call]
@@ -201,7 +331,7 @@ pub mod pallet {
 				nonce,
 				access_list,
 				is_transactional,
-				T::config(),
+				&Self::evm_config(),
 			) {
 				Ok(info) => info,
 				Err(e) => {
@@ -259,7 +389,7 @@ pub mod pallet {
 				nonce,
 				access_list,
 				is_transactional,
-				T::config(),
+				&Self::evm_config(),
 			) {
 				Ok(info) => info,
 				Err(e) => {
@@ -326,7 +456,7 @@ pub mod pallet {
 				nonce,
 				access_list,
 				is_transactional,
-				T::config(),
+				&Self::evm_config(),
 			) {
 				Ok(info) => info,
 				Err(e) => {
@@ -364,6 +494,71 @@ pub mod pallet {
 				pays_fee: Pays::No,
 			})
 		}
+
+		/// Set the balance of an account, e.g. to recover from an incident or to migrate
+		/// tokens. Root only.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn force_set_balance(
+			origin: OriginFor<T>,
+			address: H160,
+			balance: BalanceOf<T>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			let account_id = T::AddressMapping::into_account_id(address);
+			T::Currency::make_free_balance_be(&account_id, balance);
+			Self::deposit_event(Event::BalanceSet(address, balance));
+			Ok(())
+		}
+
+		/// Set the nonce of an account. Root only.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn force_set_nonce(
+			origin: OriginFor<T>,
+			address: H160,
+			nonce: T::Index,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			let account_id = T::AddressMapping::into_account_id(address);
+			frame_system::Account::<T>::mutate(&account_id, |account| account.nonce = nonce);
+			Self::deposit_event(Event::NonceSet(address, nonce));
+			Ok(())
+		}
+
+		/// Set the code of an account, removing it when empty. The storage of the account is
+		/// kept. Root only.
+		#[pallet::weight(
+			10_000
+				+ T::DbWeight::get().reads_writes(3, 4)
+				+ (code.len() as Weight).saturating_mul(10)
+		)]
+		pub fn force_set_code(
+			origin: OriginFor<T>,
+			address: H160,
+			code: Vec<u8>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			if code.is_empty() {
+				Self::remove_account_code(&address);
+			} else {
+				Self::create_account(address, code);
+			}
+			Self::deposit_event(Event::CodeSet(address));
+			Ok(())
+		}
+
+		/// Set a storage slot of an account, removing it when zero. Root only.
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		pub fn force_set_storage(
+			origin: OriginFor<T>,
+			address: H160,
+			index: H256,
+			value: H256,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::set_account_storage(address, index, value);
+			Self::deposit_event(Event::StorageSet(address, index, value));
+			Ok(())
+		}
 	}
 
 	#[pallet::event]
@@ -383,6 +578,16 @@ pub mod pallet {
 		BalanceDeposit(T::AccountId, H160, U256),
 		/// A withdrawal has been made from a given address. \[sender, address, value\]
 		BalanceWithdraw(T::AccountId, H160, U256),
+		/// The balance of an account has been set by root. \[address, balance\]
+		BalanceSet(H160, BalanceOf<T>),
+		/// The nonce of an account has been set by root. \[address, nonce\]
+		NonceSet(H160, T::Index),
+		/// The code of an \[address\] has been set by root.
+		CodeSet(H160),
+		/// A storage slot of an account has been set by root. \[address, index, value\]
+		StorageSet(H160, H256, H256),
+		/// A precompile has failed. \[address, selector, kind\]
+		PrecompileFailed(H160, Option<[u8; 4]>, PrecompileFailureKind),
 	}
 
 	#[pallet::error]
@@ -399,6 +604,8 @@ pub mod pallet {
 		GasPriceTooLow,
 		/// Nonce is invalid
 		InvalidNonce,
+		/// The account of the source hasn't been migrated to the current address mapping
+		AccountNotMigrated,
 	}
 
 	#[pallet::genesis_config]
@@ -427,20 +634,66 @@ pub mod pallet {
 				Pallet::<T>::create_account(*address, account.code.clone());
 
 				for (index, value) in &account.storage {
-					<AccountStorages<T>>::insert(address, index, value);
+					Pallet::<T>::set_account_storage(*address, *index, *value);
 				}
 			}
+
+			<AccountCodesMigrated<T>>::put(true);
+			<AccountStoragesMigrated<T>>::put(true);
+			Pallet::<T>::register_precompiles();
 		}
 	}
 
+	/// Keccak hash of the code of each contract account.
+	#[pallet::storage]
+	#[pallet::getter(fn account_code_hash)]
+	pub type AccountCodeHash<T: Config> = StorageMap<_, Blake2_128Concat, H160, H256, OptionQuery>;
+
+	/// Code preimages by keccak hash, shared by every account deployed with the same code.
 	#[pallet::storage]
-	#[pallet::getter(fn account_codes)]
-	pub type AccountCodes<T: Config> = StorageMap<_, Blake2_128Concat, H160, Vec<u8>, ValueQuery>;
+	pub type AccountCodeByHash<T: Config> = StorageMap<_, Identity, H256, Vec<u8>, ValueQuery>;
 
+	/// Number of accounts referencing each code in `AccountCodeByHash`.
+	#[pallet::storage]
+	pub type AccountCodeRefCount<T: Config> = StorageMap<_, Identity, H256, u32, ValueQuery>;
+
+	/// Legacy code of the accounts, a copy per account, moved to `AccountCodeHash` by
+	/// [`migration::AccountCodesMigration`]. Use [`Pallet::account_codes`] to read the code of
+	/// an account.
+	#[pallet::storage]
+	pub type AccountCodes<T: Config> = StorageMap<_, Blake2_128Concat, H160, Vec<u8>, OptionQuery>;
+
+	/// Whether `AccountCodes` is empty, so that code reads don't fall back to it.
+	#[pallet::storage]
+	pub type AccountCodesMigrated<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// Legacy storage of the accounts, moved to a child trie per account by
+	/// [`migration::AccountStoragesMigration`]. Use [`Pallet::account_storages`] to read the
+	/// storage of an account.
 	#[pallet::storage]
-	#[pallet::getter(fn account_storages)]
 	pub type AccountStorages<T: Config> =
 		StorageDoubleMap<_, Blake2_128Concat, H160, Blake2_128Concat, H256, H256, ValueQuery>;
+
+	/// Whether `AccountStorages` is empty, so that storage reads don't fall back to it.
+	#[pallet::storage]
+	pub type AccountStoragesMigrated<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// Storage deposit reserved on the account of a contract.
+	#[pallet::storage]
+	#[pallet::getter(fn storage_deposits)]
+	pub type StorageDeposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, H160, BalanceOf<T>, ValueQuery>;
+
+	/// Last contract migrated by [`migration::AddressMappingMigration::migrate_contracts`].
+	#[pallet::storage]
+	pub type AddressMappingMigrationCursor<T: Config> = StorageValue<_, H160, OptionQuery>;
+
+	/// Storage slots set by the precompiles during the current execution, with whether they
+	/// were empty before it, see [`Pallet::set_precompile_storage`]. The runner drains them at
+	/// the end of the execution.
+	#[pallet::storage]
+	pub type PrecompileStorageSlots<T: Config> =
+		StorageMap<_, Blake2_128Concat, (H160, H256), bool, OptionQuery>;
 }
 
 /// Type alias for currency balance.
@@ -594,13 +847,29 @@ impl GasWeightMapping for () {
 
 static LONDON_CONFIG: EvmConfig = EvmConfig::london();
 
+/// Bytes of a storage slot, its key and its value, for the storage deposits.
+pub const STORAGE_SLOT_BYTES: i64 = 64;
+
+/// Bytecode stored at precompile addresses: `PUSH1 0x00 PUSH1 0x00 REVERT`.
+pub const PRECOMPILE_REVERT_BYTECODE: [u8; 5] = [0x60, 0x00, 0x60, 0x00, 0xfd];
+
 impl<T: Config> Pallet<T> {
+	/// EVM config of the executions: the one of [`Config::config`], with the costs of
+	/// [`Config::gas_overrides`].
+	pub fn evm_config() -> EvmConfig {
+		fp_evm::apply_gas_overrides(T::config(), &T::gas_overrides())
+	}
+
+	/// Costs of all the overridable gas parameters in [`Pallet::evm_config`].
+	pub fn gas_table() -> Vec<(GasParameter, u64)> {
+		fp_evm::gas_table(&Self::evm_config())
+	}
+
 	/// Check whether an account is empty.
 	pub fn is_account_empty(address: &H160) -> bool {
 		let (account, _) = Self::account_basic(address);
-		let code_len = <AccountCodes<T>>::decode_len(address).unwrap_or(0);
 
-		account.nonce == U256::zero() && account.balance == U256::zero() && code_len == 0
+		account.nonce == U256::zero() && account.balance == U256::zero() && !Self::has_code(address)
 	}
 
 	/// Remove an account if its empty.
@@ -610,15 +879,100 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
+	/// Settles the storage deposits of the contracts whose state a transaction of `source`
+	/// changed by `growth` bytes, or deleted.
+	///
+	/// The deposits of grown contracts are transferred from `source` and reserved on the
+	/// contracts, and the ones of shrunk or deleted contracts are unreserved and refunded to
+	/// `source`. Fails if `source` can't pay.
+	///
+	/// A contract account holds at least the existential deposit: the first deposit of a
+	/// contract without balance is raised to it, and the refunds of a shrunk contract keep it
+	/// reserved until the contract is deleted.
+	pub(crate) fn settle_storage_deposits(
+		source: &H160,
+		growth: &BTreeMap<H160, i64>,
+		deletes: &BTreeSet<H160>,
+	) -> DispatchResult {
+		let per_byte = T::storage_deposit_per_byte();
+		if per_byte.is_zero() {
+			return Ok(());
+		}
+		let source_account = T::AddressMapping::into_account_id(*source);
+
+		for (address, bytes) in growth {
+			if deletes.contains(address) {
+				continue;
+			}
+			let contract_account = T::AddressMapping::into_account_id(*address);
+			let amount = per_byte
+				.saturating_mul(BalanceOf::<T>::unique_saturated_from(bytes.unsigned_abs()));
+			let minimum_balance = <T::Currency as Currency<_>>::minimum_balance();
+			let total_balance = <T::Currency as Currency<_>>::total_balance(&contract_account);
+			if *bytes > 0 {
+				let amount = amount.max(minimum_balance.saturating_sub(total_balance));
+				T::Currency::transfer(
+					&source_account,
+					&contract_account,
+					amount,
+					ExistenceRequirement::KeepAlive,
+				)?;
+				T::Currency::reserve(&contract_account, amount)?;
+				<StorageDeposits<T>>::mutate(address, |deposit| {
+					*deposit = deposit.saturating_add(amount)
+				});
+			} else {
+				let amount = amount
+					.min(<StorageDeposits<T>>::get(address))
+					.min(total_balance.saturating_sub(minimum_balance));
+				Self::refund_storage_deposit(address, &source_account, amount)?;
+			}
+		}
+		for address in deletes {
+			let amount = <StorageDeposits<T>>::get(address);
+			Self::refund_storage_deposit(address, &source_account, amount)?;
+		}
+		Ok(())
+	}
+
+	fn refund_storage_deposit(
+		address: &H160,
+		beneficiary: &T::AccountId,
+		amount: BalanceOf<T>,
+	) -> DispatchResult {
+		if amount.is_zero() {
+			return Ok(());
+		}
+		let contract_account = T::AddressMapping::into_account_id(*address);
+		T::Currency::unreserve(&contract_account, amount);
+		T::Currency::transfer(
+			&contract_account,
+			beneficiary,
+			amount,
+			ExistenceRequirement::AllowDeath,
+		)?;
+		<StorageDeposits<T>>::mutate_exists(address, |deposit| {
+			*deposit = deposit
+				.map(|deposit| deposit.saturating_sub(amount))
+				.filter(|deposit| !deposit.is_zero())
+		});
+		Ok(())
+	}
+
 	/// Remove an account.
 	pub fn remove_account(address: &H160) {
-		if <AccountCodes<T>>::contains_key(address) {
+		Self::remove_account_code(address);
+		Self::remove_account_storage(address);
+	}
+
+	/// Remove the code of an account, keeping its storage.
+	pub fn remove_account_code(address: &H160) {
+		Self::migrate_account_code(address);
+		if let Some(code_hash) = <AccountCodeHash<T>>::take(address) {
 			let account_id = T::AddressMapping::into_account_id(*address);
 			let _ = frame_system::Pallet::<T>::dec_sufficients(&account_id);
+			Self::release_code(code_hash);
 		}
-
-		<AccountCodes<T>>::remove(address);
-		<AccountStorages<T>>::remove_prefix(address, None);
 	}
 
 	/// Create an account.
@@ -627,12 +981,167 @@ impl<T: Config> Pallet<T> {
 			return;
 		}
 
-		if !<AccountCodes<T>>::contains_key(&address) {
-			let account_id = T::AddressMapping::into_account_id(address);
-			let _ = frame_system::Pallet::<T>::inc_sufficients(&account_id);
+		Self::migrate_account_code(&address);
+		let code_hash = H256::from_slice(Keccak256::digest(&code).as_slice());
+		match <AccountCodeHash<T>>::get(address) {
+			Some(previous) if previous == code_hash => return,
+			Some(previous) => Self::release_code(previous),
+			None => {
+				let account_id = T::AddressMapping::into_account_id(address);
+				let _ = frame_system::Pallet::<T>::inc_sufficients(&account_id);
+			}
+		}
+
+		Self::retain_code(code_hash, code);
+		<AccountCodeHash<T>>::insert(address, code_hash);
+	}
+
+	/// Store the revert bytecode stub at every precompile address without code.
+	pub fn register_precompiles() -> Weight {
+		let db_weights = T::DbWeight::get();
+		let mut weight: Weight = 0;
+		for address in T::precompile_addresses() {
+			weight = weight.saturating_add(db_weights.reads(2));
+			if !Self::has_code(&address) {
+				// - inc_sufficients: System::Account
+				// - retain_code: AccountCodeRefCount, AccountCodeByHash
+				// - write: AccountCodeHash
+				weight = weight.saturating_add(db_weights.reads_writes(2, 4));
+				Self::create_account(address, PRECOMPILE_REVERT_BYTECODE.to_vec());
+			}
+		}
+		weight
+	}
+
+	/// Compute the address of a contract deployed with CREATE2, as the EVM does.
+	pub fn create2_address(deployer: H160, salt: H256, init_code_hash: H256) -> H160 {
+		let mut hasher = Keccak256::new();
+		hasher.update(&[0xff]);
+		hasher.update(&deployer[..]);
+		hasher.update(&salt[..]);
+		hasher.update(&init_code_hash[..]);
+		H256::from_slice(hasher.finalize().as_slice()).into()
+	}
+
+	/// Get the code of an account.
+	pub fn account_codes(address: H160) -> Vec<u8> {
+		match <AccountCodeHash<T>>::get(address) {
+			Some(code_hash) => <AccountCodeByHash<T>>::get(code_hash),
+			None if !<AccountCodesMigrated<T>>::get() => {
+				<AccountCodes<T>>::get(address).unwrap_or_default()
+			}
+			None => Vec::new(),
+		}
+	}
+
+	/// Whether an account has code.
+	pub fn has_code(address: &H160) -> bool {
+		<AccountCodeHash<T>>::contains_key(address)
+			|| (!<AccountCodesMigrated<T>>::get() && <AccountCodes<T>>::contains_key(address))
+	}
+
+	/// Child trie holding the storage of an account, keyed by the storage index.
+	pub fn account_storage_child_info(address: &H160) -> ChildInfo {
+		let mut storage_key = EVM_ACCOUNT_STORAGE_CHILD_PREFIX.to_vec();
+		storage_key.extend_from_slice(address.as_bytes());
+		ChildInfo::new_default(&storage_key)
+	}
+
+	/// Get a storage slot of an account.
+	pub fn account_storages(address: H160, index: H256) -> H256 {
+		let child_info = Self::account_storage_child_info(&address);
+		match child::get::<H256>(&child_info, index.as_bytes()) {
+			Some(value) => value,
+			None if !<AccountStoragesMigrated<T>>::get() => {
+				<AccountStorages<T>>::get(address, index)
+			}
+			None => H256::default(),
+		}
+	}
+
+	/// Whether a storage slot of an account is set.
+	pub fn account_storage_exists(address: H160, index: H256) -> bool {
+		let child_info = Self::account_storage_child_info(&address);
+		child::exists(&child_info, index.as_bytes())
+			|| (!<AccountStoragesMigrated<T>>::get()
+				&& <AccountStorages<T>>::contains_key(address, index))
+	}
+
+	/// Set a storage slot of an account, removing it when zero.
+	pub fn set_account_storage(address: H160, index: H256, value: H256) {
+		let child_info = Self::account_storage_child_info(&address);
+		if value.is_zero() {
+			child::kill(&child_info, index.as_bytes());
+		} else {
+			child::put(&child_info, index.as_bytes(), &value);
+		}
+		if !<AccountStoragesMigrated<T>>::get() {
+			<AccountStorages<T>>::remove(address, index);
 		}
+	}
 
-		<AccountCodes<T>>::insert(address, code);
+	/// Set a storage slot of a precompile, which the storage deposits and the limit of new
+	/// storage slots of the transaction account as a slot set by a contract.
+	pub fn set_precompile_storage(address: H160, index: H256, value: H256) {
+		if (!T::storage_deposit_per_byte().is_zero()
+			|| T::max_storage_slots_per_transaction().is_some())
+			&& !<PrecompileStorageSlots<T>>::contains_key((address, index))
+		{
+			<PrecompileStorageSlots<T>>::insert(
+				(address, index),
+				!Self::account_storage_exists(address, index),
+			);
+		}
+		Self::set_account_storage(address, index, value);
+	}
+
+	/// Remove the whole storage of an account.
+	pub fn remove_account_storage(address: &H160) {
+		let _ = child::kill_storage(&Self::account_storage_child_info(address), None);
+		if !<AccountStoragesMigrated<T>>::get() {
+			<AccountStorages<T>>::remove_prefix(address, None);
+		}
+	}
+
+	/// Add a reference to a code, storing its preimage if it is the first one.
+	fn retain_code(code_hash: H256, code: Vec<u8>) {
+		<AccountCodeRefCount<T>>::mutate(code_hash, |count| {
+			if *count == 0 {
+				<AccountCodeByHash<T>>::insert(code_hash, code);
+			}
+			*count = count.saturating_add(1);
+		});
+	}
+
+	/// Remove a reference to a code, removing its preimage if it was the last one.
+	fn release_code(code_hash: H256) {
+		<AccountCodeRefCount<T>>::mutate_exists(code_hash, |count| {
+			let remaining = count.unwrap_or_default().saturating_sub(1);
+			if remaining == 0 {
+				<AccountCodeByHash<T>>::remove(code_hash);
+				*count = None;
+			} else {
+				*count = Some(remaining);
+			}
+		});
+	}
+
+	/// Move the code of an account from the legacy `AccountCodes` map, if it is there, so that
+	/// it can be changed. The sufficient reference of the account is left untouched.
+	fn migrate_account_code(address: &H160) {
+		if <AccountCodesMigrated<T>>::get() {
+			return;
+		}
+		if let Some(code) = <AccountCodes<T>>::take(address) {
+			Self::insert_legacy_code(*address, code);
+		}
+	}
+
+	/// Store a code taken from the legacy `AccountCodes` map by its hash.
+	fn insert_legacy_code(address: H160, code: Vec<u8>) {
+		let code_hash = H256::from_slice(Keccak256::digest(&code).as_slice());
+		Self::retain_code(code_hash, code);
+		<AccountCodeHash<T>>::insert(address, code_hash);
 	}
 
 	/// Get the account basic in EVM format.
@@ -652,6 +1161,49 @@ impl<T: Config> Pallet<T> {
 		)
 	}
 
+	/// The base fee charged to the transactions of `source`, zero if it is fee exempt.
+	pub fn base_fee_for(source: &H160) -> (U256, Weight) {
+		let (base_fee, weight) = T::FeeCalculator::min_gas_price();
+		if T::is_fee_exempt(source) {
+			(U256::zero(), weight)
+		} else {
+			(base_fee, weight)
+		}
+	}
+
+	/// The sponsor of a call of `source` to `target`, the share of the fees it pays and its
+	/// part of the highest fee of the call, if it can pay that part.
+	///
+	/// A sponsor only pays its share of the base fee, so that a caller can't spend the sponsor
+	/// funds on priority fees.
+	pub fn fee_sponsor_for(
+		source: &H160,
+		target: &H160,
+		base_fee: U256,
+		gas_limit: u64,
+	) -> (Option<(H160, Permill, U256)>, Weight) {
+		let mut weight = 0;
+		let sponsor = T::fee_sponsor(source, target)
+			.filter(|(sponsor, _)| sponsor != source)
+			.map(|(sponsor, share)| {
+				let sponsored_fee =
+					permill_of(share, base_fee.saturating_mul(U256::from(gas_limit)));
+				(sponsor, share, sponsored_fee)
+			})
+			.filter(|(sponsor, _, sponsored_fee)| {
+				if sponsored_fee.is_zero() {
+					false
+				} else if T::OnChargeTransaction::pays_from_balance() {
+					let (sponsor_account, inner_weight) = Self::account_basic(sponsor);
+					weight = inner_weight;
+					sponsor_account.balance >= *sponsored_fee
+				} else {
+					T::OnChargeTransaction::can_pay_fee(sponsor, *sponsored_fee)
+				}
+			});
+		(sponsor, weight)
+	}
+
 	/// Get the author using the FindAuthor trait.
 	pub fn find_author() -> H160 {
 		let digest = <frame_system::Pallet<T>>::digest();
@@ -661,6 +1213,13 @@ impl<T: Config> Pallet<T> {
 	}
 }
 
+/// The `share` of `fee`, rounded down.
+pub(crate) fn permill_of(share: Permill, fee: U256) -> U256 {
+	let parts = U256::from(share.deconstruct());
+	let accuracy = U256::from(Permill::ACCURACY);
+	fee / accuracy * parts + fee % accuracy * parts / accuracy
+}
+
 /// Handle withdrawing, refunding and depositing of transaction fees.
 /// Similar to `OnChargeTransaction` of `pallet_transaction_payment`
 pub trait OnChargeEVMTransaction<T: Config> {
@@ -681,6 +1240,17 @@ pub trait OnChargeEVMTransaction<T: Config> {
 
 	/// Introduced in EIP1559 to handle the priority tip payment to the block Author.
 	fn pay_priority_fee(tip: U256);
+
+	/// Whether the fees are paid from the balance of the sender, which must then cover both
+	/// the fees and the value of its transactions.
+	fn pays_from_balance() -> bool {
+		true
+	}
+
+	/// Whether `who` can pay `fee`, for fees which aren't paid from its balance.
+	fn can_pay_fee(_who: &H160, _fee: U256) -> bool {
+		true
+	}
 }
 
 /// Implements the transaction payment for a pallet implementing the `Currency`
@@ -772,6 +1342,102 @@ where
 	}
 }
 
+/// Implements the transaction payment in an asset of a pallet implementing the `fungibles`
+/// traits (eg. the pallet_assets), while the EVM still accounts the fees in the native currency.
+///
+/// The fees are converted to the asset `AssetId` with the conversion rate `CON` when they are
+/// charged, and the final fee is handled by `OU` (dropping it burns it). The priority fee is
+/// paid to the block author in the same asset.
+pub struct EVMFungiblesAdapter<F, AssetId, CON, OU>(
+	sp_std::marker::PhantomData<(F, AssetId, CON, OU)>,
+);
+
+impl<F, AssetId, CON, OU> EVMFungiblesAdapter<F, AssetId, CON, OU> {
+	/// Converts `fee`, in the native currency, to the asset.
+	fn to_asset_balance<T>(fee: U256) -> Option<F::Balance>
+	where
+		T: Config,
+		F: fungibles::Inspect<<T as frame_system::Config>::AccountId>,
+		AssetId: Get<F::AssetId>,
+		CON: BalanceConversion<BalanceOf<T>, F::AssetId, F::Balance>,
+	{
+		if fee.is_zero() {
+			return Some(Zero::zero());
+		}
+		// Fees which don't fit the native balance can't be paid.
+		let fee = BalanceOf::<T>::try_from(u128::try_from(fee).ok()?).ok()?;
+		CON::to_asset_balance(fee, AssetId::get())
+			.ok()
+			// Non zero fees aren't rounded down to nothing.
+			.map(|fee| fee.max(One::one()))
+	}
+}
+
+impl<T, F, AssetId, CON, OU> OnChargeEVMTransaction<T> for EVMFungiblesAdapter<F, AssetId, CON, OU>
+where
+	T: Config,
+	F: fungibles::Balanced<<T as frame_system::Config>::AccountId>,
+	AssetId: Get<F::AssetId>,
+	CON: BalanceConversion<BalanceOf<T>, F::AssetId, F::Balance>,
+	OU: OnUnbalanced<fungibles::CreditOf<<T as frame_system::Config>::AccountId, F>>,
+{
+	// Kept type as Option to satisfy bound of Default
+	type LiquidityInfo = Option<fungibles::CreditOf<<T as frame_system::Config>::AccountId, F>>;
+
+	fn withdraw_fee(who: &H160, fee: U256) -> Result<Self::LiquidityInfo, Error<T>> {
+		if fee.is_zero() {
+			return Ok(None);
+		}
+		let account_id = T::AddressMapping::into_account_id(*who);
+		let fee = Self::to_asset_balance::<T>(fee).ok_or(Error::<T>::BalanceLow)?;
+		let credit =
+			F::withdraw(AssetId::get(), &account_id, fee).map_err(|_| Error::<T>::BalanceLow)?;
+		Ok(Some(credit))
+	}
+
+	fn correct_and_deposit_fee(
+		who: &H160,
+		corrected_fee: U256,
+		already_withdrawn: Self::LiquidityInfo,
+	) {
+		if let Some(paid) = already_withdrawn {
+			let account_id = T::AddressMapping::into_account_id(*who);
+
+			// Without a conversion rate, the whole withdrawn fee is kept.
+			let corrected_fee =
+				Self::to_asset_balance::<T>(corrected_fee).unwrap_or_else(|| paid.peek());
+			let (fee, refund) = paid.split(corrected_fee);
+			// If the refund fails, the account might have dropped below the minimum balance of
+			// the asset. In that case the refund is burnt.
+			let _ = F::resolve(&account_id, refund);
+			OU::on_unbalanced(fee);
+		}
+	}
+
+	fn pay_priority_fee(tip: U256) {
+		let account_id = T::AddressMapping::into_account_id(<Pallet<T>>::find_author());
+		if let Some(tip) = Self::to_asset_balance::<T>(tip) {
+			let _ = F::deposit(AssetId::get(), &account_id, tip);
+		}
+	}
+
+	fn pays_from_balance() -> bool {
+		false
+	}
+
+	fn can_pay_fee(who: &H160, fee: U256) -> bool {
+		let account_id = T::AddressMapping::into_account_id(*who);
+		match Self::to_asset_balance::<T>(fee) {
+			// The account may be reaped by the fees, as with `withdraw_fee`.
+			Some(fee) => matches!(
+				F::can_withdraw(AssetId::get(), &account_id, fee),
+				WithdrawConsequence::Success | WithdrawConsequence::ReducedToZero(_)
+			),
+			None => false,
+		}
+	}
+}
+
 /// Implementation for () does not specify what to do with imbalance
 impl<T> OnChargeEVMTransaction<T> for ()
 	where
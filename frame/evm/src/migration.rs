@@ -0,0 +1,257 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Migrations of the EVM state.
+
+use frame_support::{
+	storage::child,
+	traits::{tokens::fungible::Inspect, Currency, ExistenceRequirement, Get, ReservableCurrency},
+	weights::Weight,
+};
+use sp_core::{H160, H256};
+use sp_runtime::traits::Zero;
+use sp_std::{marker::PhantomData, vec::Vec};
+
+use crate::{
+	AccountCodeHash, AccountCodes, AccountCodesMigrated, AccountStorages, AccountStoragesMigrated,
+	AddressMapping, AddressMappingMigrationCursor, Config, Pallet, StorageDeposits,
+};
+
+/// Moves the accounts of the EVM addresses from the account ids of a previous address mapping
+/// `Old` to the ones of `T::AddressMapping`, for chains switching their address mapping after
+/// launch, eg. to unified accounts. The runtime must already use the new mapping.
+///
+/// The EVM state is keyed by address and stays in place. For each address, the migration moves
+/// from the old account to the new one:
+/// - its transferable balance, and the storage deposit reserved on a contract,
+/// - its nonce, the new account keeping the highest of both,
+/// - the sufficient reference of a contract.
+///
+/// Locked balances and the reserves of other pallets are left on the old account. Migrating an
+/// address twice is harmless, as there is nothing left to move the second time.
+///
+/// The contracts are enumerated from the state by [`Self::migrate_contracts`], in bounded
+/// batches. The externally owned accounts can't be enumerated from their account ids, and are
+/// migrated by [`Self::migrate_addresses`] from a list of addresses, eg. an index of the senders
+/// of the chain, also in bounded batches.
+///
+/// Until an address is migrated, its transactions would run against an empty account, replaying
+/// the nonces of its old account. The runtime rejects them during the migration by implementing
+/// [`Config::is_address_migrated`] with [`Self::is_migrated`].
+pub struct AddressMappingMigration<T, Old>(PhantomData<(T, Old)>);
+
+impl<T, Old> AddressMappingMigration<T, Old>
+where
+	T: Config,
+	Old: AddressMapping<T::AccountId>,
+{
+	/// Migrates the accounts of up to `limit` addresses from the start of `addresses`. Returns
+	/// the number of addresses migrated, the runtime resuming with the next ones until all of
+	/// them are migrated, eg. from `on_idle`.
+	pub fn migrate_addresses(addresses: &[H160], limit: u32) -> (u32, Weight) {
+		let addresses = &addresses[..addresses.len().min(limit as usize)];
+		let weight = addresses.iter().fold(0, |weight: Weight, address| {
+			weight.saturating_add(Self::migrate_address(*address))
+		});
+		(addresses.len() as u32, weight)
+	}
+
+	/// Whether the account of `address` has nothing left to migrate: the nonce of its old
+	/// account isn't over the one of its new account, and its transferable balance, if any, is
+	/// below the existential deposit. Contracts don't send transactions and aren't checked.
+	pub fn is_migrated(address: H160) -> bool {
+		let old = Old::into_account_id(address);
+		let new = T::AddressMapping::into_account_id(address);
+		if old == new {
+			return true;
+		}
+		let transferable = <T::Currency as Inspect<T::AccountId>>::reducible_balance(&old, false);
+		frame_system::Pallet::<T>::account_nonce(&old)
+			<= frame_system::Pallet::<T>::account_nonce(&new)
+			&& (transferable.is_zero()
+				|| transferable < <T::Currency as Currency<T::AccountId>>::minimum_balance())
+	}
+
+	/// Migrates the accounts of up to `limit` contracts, resuming after the last contract
+	/// migrated by the previous call. Returns whether all the contracts have been migrated, in
+	/// which case the next call starts over.
+	///
+	/// The runtime calls it until it is done, eg. from `on_idle`.
+	pub fn migrate_contracts(limit: u32) -> (bool, Weight) {
+		let db_weights = T::DbWeight::get();
+		let contracts: Vec<H160> = match <AddressMappingMigrationCursor<T>>::get() {
+			Some(last) => {
+				<AccountCodeHash<T>>::iter_keys_from(<AccountCodeHash<T>>::hashed_key_for(last))
+					.take(limit as usize)
+					.collect()
+			}
+			None => <AccountCodeHash<T>>::iter_keys()
+				.take(limit as usize)
+				.collect(),
+		};
+		// - read: AddressMappingMigrationCursor, AccountCodeHash keys
+		// - write: AddressMappingMigrationCursor
+		let mut weight = db_weights.reads_writes(1 + contracts.len() as Weight, 1);
+
+		for address in &contracts {
+			weight = weight.saturating_add(Self::migrate_address(*address));
+		}
+
+		match contracts.last() {
+			Some(last) if contracts.len() == limit as usize => {
+				<AddressMappingMigrationCursor<T>>::put(last);
+				(false, weight)
+			}
+			_ => {
+				<AddressMappingMigrationCursor<T>>::kill();
+				(true, weight)
+			}
+		}
+	}
+
+	/// Migrates the account of `address`.
+	pub fn migrate_address(address: H160) -> Weight {
+		let db_weights = T::DbWeight::get();
+		let old = Old::into_account_id(address);
+		let new = T::AddressMapping::into_account_id(address);
+		if old == new {
+			return 0;
+		}
+		// - read: System::Account (old, new), AccountCodeHash, StorageDeposits
+		// - write: System::Account (old, new)
+		let weight = db_weights.reads_writes(4, 2);
+
+		// The new account holds the sufficient reference of the contract before the old one
+		// can be reaped.
+		let moves_sufficient =
+			Pallet::<T>::has_code(&address) && frame_system::Pallet::<T>::sufficients(&old) > 0;
+		if moves_sufficient {
+			let _ = frame_system::Pallet::<T>::inc_sufficients(&new);
+		}
+
+		let nonce = frame_system::Pallet::<T>::account_nonce(&old);
+		let deposit = <StorageDeposits<T>>::get(address);
+		let deposit = deposit.saturating_sub(T::Currency::unreserve(&old, deposit));
+
+		let transferable = <T::Currency as Inspect<T::AccountId>>::reducible_balance(&old, false);
+		if !transferable.is_zero() {
+			// Fails if the balance is below the existential deposit of a new account, in which
+			// case the dust stays on the old account.
+			let _ =
+				T::Currency::transfer(&old, &new, transferable, ExistenceRequirement::AllowDeath);
+		}
+		if !deposit.is_zero() && T::Currency::reserve(&new, deposit).is_err() {
+			log::error!(
+				target: "evm",
+				"Failed to reserve the storage deposit of {:?} on its new account",
+				address
+			);
+		}
+
+		if !nonce.is_zero() {
+			frame_system::Account::<T>::mutate(&new, |account| {
+				account.nonce = account.nonce.max(nonce)
+			});
+		}
+
+		if moves_sufficient {
+			let _ = frame_system::Pallet::<T>::dec_sufficients(&old);
+		}
+		weight
+	}
+}
+
+/// Moves the storage of the accounts from the legacy `AccountStorages` map to a child trie per
+/// account, for chains launched before the child tries.
+///
+/// The storage of the accounts is read from their child trie, falling back to the legacy map
+/// until the migration is done, and is always written to their child trie. The chain keeps
+/// working during the migration, which moves the remaining slots in bounded batches.
+pub struct AccountStoragesMigration<T>(PhantomData<T>);
+
+impl<T: Config> AccountStoragesMigration<T> {
+	/// Moves up to `limit` storage slots to the child tries of their account. Returns whether
+	/// the legacy map is empty, after which the storage reads no longer fall back to it.
+	///
+	/// The runtime calls it until it is done, eg. from `on_idle`.
+	pub fn migrate(limit: u32) -> (bool, Weight) {
+		let db_weights = T::DbWeight::get();
+		if <AccountStoragesMigrated<T>>::get() {
+			return (true, db_weights.reads(1));
+		}
+
+		let slots: Vec<(H160, H256, H256)> =
+			<AccountStorages<T>>::drain().take(limit as usize).collect();
+		// - read: AccountStoragesMigrated, AccountStorages
+		// - write: AccountStorages, child trie
+		let mut weight =
+			db_weights.reads_writes(1 + slots.len() as Weight, 2 * slots.len() as Weight);
+		for (address, index, value) in &slots {
+			child::put(
+				&Pallet::<T>::account_storage_child_info(address),
+				index.as_bytes(),
+				value,
+			);
+		}
+
+		if slots.len() < limit as usize {
+			<AccountStoragesMigrated<T>>::put(true);
+			weight = weight.saturating_add(db_weights.writes(1));
+			return (true, weight);
+		}
+		(false, weight)
+	}
+}
+
+/// Moves the code of the accounts from the legacy `AccountCodes` map, which stored a copy of
+/// the code per account, to the storage deduplicated by code hash, for chains launched before
+/// it. The sufficient references of the accounts are left untouched.
+///
+/// The code of the accounts is read from `AccountCodeHash`, falling back to the legacy map
+/// until the migration is done, and a changed code is moved first. The chain keeps working
+/// during the migration, which moves the remaining codes in bounded batches.
+pub struct AccountCodesMigration<T>(PhantomData<T>);
+
+impl<T: Config> AccountCodesMigration<T> {
+	/// Moves the code of up to `limit` accounts. Returns whether the legacy map is empty, after
+	/// which the code reads no longer fall back to it.
+	///
+	/// The runtime calls it until it is done, eg. from `on_idle`.
+	pub fn migrate(limit: u32) -> (bool, Weight) {
+		let db_weights = T::DbWeight::get();
+		if <AccountCodesMigrated<T>>::get() {
+			return (true, db_weights.reads(1));
+		}
+
+		let codes: Vec<(H160, Vec<u8>)> = <AccountCodes<T>>::drain().take(limit as usize).collect();
+		let count = codes.len();
+		// - read: AccountCodesMigrated, AccountCodes
+		// - read + write: AccountCodeRefCount
+		// - write: AccountCodes, AccountCodeByHash, AccountCodeHash
+		let mut weight = db_weights.reads_writes(1 + 2 * count as Weight, 4 * count as Weight);
+		for (address, code) in codes {
+			Pallet::<T>::insert_legacy_code(address, code);
+		}
+
+		if count < limit as usize {
+			<AccountCodesMigrated<T>>::put(true);
+			weight = weight.saturating_add(db_weights.writes(1));
+			return (true, weight);
+		}
+		(false, weight)
+	}
+}
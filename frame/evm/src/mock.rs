@@ -19,7 +19,7 @@
 
 use frame_support::{
 	parameter_types,
-	traits::{ConstU32, FindAuthor},
+	traits::{tokens::BalanceConversion, ConstU32, ConstU64, FindAuthor},
 	weights::Weight,
 	ConsensusEngineId,
 };
@@ -30,7 +30,10 @@ use sp_runtime::{
 };
 use sp_std::{boxed::Box, prelude::*, str::FromStr};
 
-use crate::{EnsureAddressNever, EnsureAddressRoot, FeeCalculator, IdentityAddressMapping};
+use crate::{
+	AddressMapping, Context, EnsureAddressNever, EnsureAddressRoot, ExitRevert, FeeCalculator,
+	IdentityAddressMapping, PrecompileFailure, PrecompileResult, PrecompileSet,
+};
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -44,6 +47,7 @@ frame_support::construct_runtime! {
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
 		EVM: crate::{Pallet, Call, Storage, Config, Event<T>},
 	}
 }
@@ -81,7 +85,7 @@ impl frame_system::Config for Test {
 }
 
 parameter_types! {
-	pub const ExistentialDeposit: u64 = 0;
+	pub static ExistentialDeposit: u64 = 0;
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
@@ -95,6 +99,42 @@ impl pallet_balances::Config for Test {
 	type ReserveIdentifier = ();
 }
 
+impl pallet_assets::Config for Test {
+	type Event = Event;
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<H160>;
+	type AssetDeposit = ConstU64<0>;
+	type AssetAccountDeposit = ConstU64<0>;
+	type MetadataDepositBase = ConstU64<0>;
+	type MetadataDepositPerByte = ConstU64<0>;
+	type ApprovalDeposit = ConstU64<0>;
+	type StringLimit = ConstU32<50>;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const FeeAsset: u32 = 1;
+	pub static FeeAssetRate: Option<u64> = Some(2);
+}
+
+/// Converts the native currency to the fee asset at `FeeAssetRate` assets per native unit.
+pub struct FeeAssetConversion;
+impl BalanceConversion<u64, u32, u64> for FeeAssetConversion {
+	type Error = ();
+
+	fn to_asset_balance(balance: u64, _asset_id: u32) -> Result<u64, ()> {
+		FeeAssetRate::get()
+			.map(|rate| balance.saturating_mul(rate))
+			.ok_or(())
+	}
+}
+
+pub type FungiblesFeePayment = crate::EVMFungiblesAdapter<Assets, FeeAsset, FeeAssetConversion, ()>;
+
 parameter_types! {
 	pub const MinimumPeriod: u64 = 1000;
 }
@@ -105,6 +145,49 @@ impl pallet_timestamp::Config for Test {
 	type WeightInfo = ();
 }
 
+parameter_types! {
+	pub static Eip6780Selfdestruct: bool = false;
+	pub static StorageDepositPerByte: u64 = 0;
+	pub static MaxStorageSlotsPerTransaction: Option<u64> = None;
+	pub static MaxContractsPerTransaction: Option<u64> = None;
+	pub static PrecompileFailureEvents: bool = false;
+	pub static FeeExemptAccounts: Vec<H160> = Vec::new();
+	pub static MigratingFromFlippedAddressMapping: bool = false;
+	pub MockPrecompilesValue: MockPrecompiles = MockPrecompiles;
+}
+
+/// A single precompile, which reverts all its calls.
+pub struct MockPrecompiles;
+impl MockPrecompiles {
+	pub fn reverting() -> H160 {
+		H160::from_low_u64_be(1024)
+	}
+}
+impl PrecompileSet for MockPrecompiles {
+	fn execute(
+		&self,
+		address: H160,
+		_input: &[u8],
+		_target_gas: Option<u64>,
+		_context: &Context,
+		_is_static: bool,
+	) -> Option<PrecompileResult> {
+		if address == Self::reverting() {
+			Some(Err(PrecompileFailure::Revert {
+				exit_status: ExitRevert::Reverted,
+				output: Vec::new(),
+				cost: 0,
+			}))
+		} else {
+			None
+		}
+	}
+
+	fn is_precompile(&self, address: H160) -> bool {
+		address == Self::reverting()
+	}
+}
+
 pub struct FixedGasPrice;
 impl FeeCalculator for FixedGasPrice {
 	fn min_gas_price() -> (U256, Weight) {
@@ -135,11 +218,52 @@ impl crate::Config for Test {
 	type Runner = crate::runner::stack::Runner<Self>;
 
 	type Event = Event;
-	type PrecompilesType = ();
-	type PrecompilesValue = ();
+	type PrecompilesType = MockPrecompiles;
+	type PrecompilesValue = MockPrecompilesValue;
 	type ChainId = ();
 	type BlockGasLimit = ();
 	type OnChargeTransaction = ();
 	type BlockHashMapping = crate::SubstrateBlockHashMapping<Self>;
 	type FindAuthor = FindAuthorTruncated;
+
+	fn eip6780_selfdestruct() -> bool {
+		Eip6780Selfdestruct::get()
+	}
+
+	fn storage_deposit_per_byte() -> u64 {
+		StorageDepositPerByte::get()
+	}
+
+	fn max_storage_slots_per_transaction() -> Option<u64> {
+		MaxStorageSlotsPerTransaction::get()
+	}
+
+	fn max_contracts_per_transaction() -> Option<u64> {
+		MaxContractsPerTransaction::get()
+	}
+
+	fn precompile_failure_events() -> bool {
+		PrecompileFailureEvents::get()
+	}
+
+	fn is_fee_exempt(address: &H160) -> bool {
+		FeeExemptAccounts::get().contains(address)
+	}
+
+	fn is_address_migrated(address: &H160) -> bool {
+		!MigratingFromFlippedAddressMapping::get()
+			|| crate::migration::AddressMappingMigration::<Self, FlippedAddressMapping>::is_migrated(
+				*address,
+			)
+	}
+}
+
+/// Address mapping before `IdentityAddressMapping`, flipping the first byte of the addresses.
+pub struct FlippedAddressMapping;
+impl AddressMapping<H160> for FlippedAddressMapping {
+	fn into_account_id(address: H160) -> H160 {
+		let mut account_id = address;
+		account_id.0[0] ^= 0xff;
+		account_id
+	}
 }
@@ -18,7 +18,7 @@
 pub mod stack;
 
 use crate::Config;
-use fp_evm::{CallInfo, CreateInfo};
+use fp_evm::{CallInfo, CreateInfo, DryRunInfo};
 use sp_core::{H160, H256, U256};
 use sp_std::vec::Vec;
 
@@ -71,4 +71,19 @@ pub trait Runner<T: Config> {
 		is_transactional: bool,
 		config: &evm::Config,
 	) -> Result<CreateInfo, RunnerError<Self::Error>>;
+
+	/// Executes a call to `target`, or a create without it, as a non-transactional execution.
+	/// Nothing is committed, and the execution is returned with the storage it accessed.
+	fn dry_run(
+		source: H160,
+		target: Option<H160>,
+		input: Vec<u8>,
+		value: U256,
+		gas_limit: u64,
+		max_fee_per_gas: Option<U256>,
+		max_priority_fee_per_gas: Option<U256>,
+		nonce: Option<U256>,
+		access_list: Vec<(H160, Vec<H256>)>,
+		config: &evm::Config,
+	) -> Result<DryRunInfo, RunnerError<Self::Error>>;
 }
@@ -18,23 +18,99 @@
 //! EVM stack-based runner.
 
 use crate::{
-	runner::Runner as RunnerT, AccountCodes, AccountStorages, AddressMapping, BlockHashMapping,
-	Config, Error, Event, FeeCalculator, OnChargeEVMTransaction, Pallet, RunnerError,
+	permill_of, runner::Runner as RunnerT, AddressMapping, BlockHashMapping, Config, Error, Event,
+	FeeCalculator, OnChargeEVMTransaction, Pallet, PrecompileFailureKind, PrecompileStorageSlots,
+	RunnerError, STORAGE_SLOT_BYTES,
 };
 use evm::{
 	backend::Backend as BackendT,
 	executor::stack::{Accessed, StackExecutor, StackState as StackStateT, StackSubstateMetadata},
 	ExitError, ExitReason, Transfer,
 };
-use fp_evm::{CallInfo, CreateInfo, ExecutionInfo, Log, Vicinity};
+use fp_evm::{
+	CallInfo, CallOrCreateInfo, Context, CreateInfo, DryRunInfo, ExecutionInfo, Log,
+	PrecompileResult, PrecompileSet, Vicinity,
+};
 use frame_support::{
 	ensure,
 	traits::{Currency, ExistenceRequirement, Get},
 };
 use sha3::{Digest, Keccak256};
 use sp_core::{H160, H256, U256};
-use sp_runtime::traits::UniqueSaturatedInto;
-use sp_std::{boxed::Box, collections::btree_set::BTreeSet, marker::PhantomData, mem, vec::Vec};
+use sp_runtime::traits::{UniqueSaturatedInto, Zero};
+use sp_std::{
+	boxed::Box,
+	cell::RefCell,
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+	marker::PhantomData,
+	mem,
+	vec::Vec,
+};
+
+/// A precompile failure: the address of the precompile, the selector of its input and the
+/// kind of the failure.
+type PrecompileFailureOf = (H160, Option<[u8; 4]>, PrecompileFailureKind);
+
+/// The precompiles of the runtime, recording their failures when
+/// [`Config::precompile_failure_events`] is enabled.
+///
+/// The failures are deposited as [`Event::PrecompileFailed`] events by the runner once the
+/// execution is over, as the events deposited by the precompiles themselves are rolled back
+/// with their failing call.
+pub struct ObservedPrecompiles<T: Config> {
+	precompiles: T::PrecompilesType,
+	enabled: bool,
+	failures: RefCell<Vec<PrecompileFailureOf>>,
+}
+
+impl<T: Config> ObservedPrecompiles<T> {
+	pub fn new(precompiles: T::PrecompilesType) -> Self {
+		Self {
+			precompiles,
+			enabled: T::precompile_failure_events(),
+			failures: RefCell::new(Vec::new()),
+		}
+	}
+
+	/// Deposits the events of the failures recorded so far.
+	fn deposit_failure_events(&self) {
+		for (address, selector, kind) in self.failures.take() {
+			Pallet::<T>::deposit_event(Event::<T>::PrecompileFailed(address, selector, kind));
+		}
+	}
+}
+
+impl<T: Config> PrecompileSet for ObservedPrecompiles<T> {
+	fn execute(
+		&self,
+		address: H160,
+		input: &[u8],
+		target_gas: Option<u64>,
+		context: &Context,
+		is_static: bool,
+	) -> Option<PrecompileResult> {
+		let result = self
+			.precompiles
+			.execute(address, input, target_gas, context, is_static);
+		if let (true, Some(Err(failure))) = (self.enabled, &result) {
+			let selector = input.get(0..4).map(|selector| {
+				let mut buf = [0u8; 4];
+				buf.copy_from_slice(selector);
+				buf
+			});
+			self.failures.borrow_mut().push((
+				address,
+				selector,
+				PrecompileFailureKind::of(failure),
+			));
+		}
+		result
+	}
+
+	fn is_precompile(&self, address: H160) -> bool {
+		self.precompiles.is_precompile(address)
+	}
+}
 
 #[derive(Default)]
 pub struct Runner<T: Config> {
@@ -45,13 +121,14 @@ impl<T: Config> Runner<T> {
 	/// Execute an EVM operation.
 	pub fn execute<'config, 'precompiles, F, R>(
 		source: H160,
+		target: Option<H160>,
 		value: U256,
 		gas_limit: u64,
 		max_fee_per_gas: Option<U256>,
 		max_priority_fee_per_gas: Option<U256>,
 		nonce: Option<U256>,
 		config: &'config evm::Config,
-		precompiles: &'precompiles T::PrecompilesType,
+		precompiles: &'precompiles ObservedPrecompiles<T>,
 		is_transactional: bool,
 		f: F,
 	) -> Result<ExecutionInfo<R>, RunnerError<Error<T>>>
@@ -61,11 +138,19 @@ impl<T: Config> Runner<T> {
 				'config,
 				'precompiles,
 				SubstrateStackState<'_, 'config, T>,
-				T::PrecompilesType,
+				ObservedPrecompiles<T>,
 			>,
 		) -> (ExitReason, R),
 	{
-		let (base_fee, mut weight) = T::FeeCalculator::min_gas_price();
+		ensure!(
+			T::is_address_migrated(&source),
+			RunnerError {
+				error: Error::<T>::AccountNotMigrated,
+				weight: 0,
+			}
+		);
+
+		let (base_fee, mut weight) = Pallet::<T>::base_fee_for(&source);
 		let max_fee_per_gas = match (max_fee_per_gas, is_transactional) {
 			(Some(max_fee_per_gas), _) => {
 				ensure!(
@@ -106,10 +191,31 @@ impl<T: Config> Runner<T> {
 				weight,
 			})?;
 
-		let total_payment = value.checked_add(total_fee).ok_or(RunnerError {
-			error: Error::<T>::PaymentOverflow,
-			weight,
-		})?;
+		// A sponsor of the target may pay a share of the base fee, if it can.
+		let sponsor = match target {
+			Some(target) => {
+				let (sponsor, inner_weight) =
+					Pallet::<T>::fee_sponsor_for(&source, &target, base_fee, gas_limit);
+				weight = weight.saturating_add(inner_weight);
+				sponsor
+			}
+			None => None,
+		};
+		let source_fee = total_fee.saturating_sub(
+			sponsor
+				.map(|(_, _, sponsored_fee)| sponsored_fee)
+				.unwrap_or_default(),
+		);
+
+		// Fees which aren't paid from the balance are checked on their own.
+		let total_payment = if T::OnChargeTransaction::pays_from_balance() {
+			value.checked_add(source_fee).ok_or(RunnerError {
+				error: Error::<T>::PaymentOverflow,
+				weight,
+			})?
+		} else {
+			value
+		};
 		let (source_account, inner_weight) = Pallet::<T>::account_basic(&source);
 		weight = weight.saturating_add(inner_weight);
 		// Account balance check is skipped if fee is Zero.
@@ -117,6 +223,14 @@ impl<T: Config> Runner<T> {
 		// 	- Non-transactional calls.
 		//	- BaseFee is configured to be Zero.
 		if total_fee > U256::zero() {
+			ensure!(
+				T::OnChargeTransaction::pays_from_balance()
+					|| T::OnChargeTransaction::can_pay_fee(&source, source_fee),
+				RunnerError {
+					error: Error::<T>::BalanceLow,
+					weight,
+				}
+			);
 			ensure!(
 				source_account.balance >= total_payment,
 				RunnerError {
@@ -137,8 +251,20 @@ impl<T: Config> Runner<T> {
 		}
 
 		// Deduct fee from the `source` account. Returns `None` if `total_fee` is Zero.
-		let fee = T::OnChargeTransaction::withdraw_fee(&source, total_fee)
+		let fee = T::OnChargeTransaction::withdraw_fee(&source, source_fee)
 			.map_err(|e| RunnerError { error: e, weight })?;
+		let sponsor = match sponsor {
+			Some((sponsor, share, sponsored_fee)) => {
+				match T::OnChargeTransaction::withdraw_fee(&sponsor, sponsored_fee) {
+					Ok(sponsor_fee) => Some((sponsor, share, sponsor_fee)),
+					Err(error) => {
+						T::OnChargeTransaction::correct_and_deposit_fee(&source, U256::zero(), fee);
+						return Err(RunnerError { error, weight });
+					}
+				}
+			}
+			None => None,
+		};
 
 		// Execute the EVM call.
 		let vicinity = Vicinity {
@@ -150,7 +276,10 @@ impl<T: Config> Runner<T> {
 		let state = SubstrateStackState::new(&vicinity, metadata);
 		let mut executor = StackExecutor::new_with_precompiles(state, config, precompiles);
 
-		let (reason, retv) = f(&mut executor);
+		// The execution is reverted if it exceeds the state growth limits, or if its storage
+		// deposits can't be paid.
+		sp_io::storage::start_transaction();
+		let (mut reason, retv) = f(&mut executor);
 
 		// Post execution.
 		let used_gas = U256::from(executor.used_gas());
@@ -199,13 +328,83 @@ impl<T: Config> Runner<T> {
 		// Refunded 200 - 40 = 160.
 		// Tip 5 * 6 = 30.
 		// Burned 200 - (160 + 30) = 10. Which is equivalent to gas_used * base_fee.
-		T::OnChargeTransaction::correct_and_deposit_fee(&source, actual_fee, fee);
+		let mut state = executor.into_state();
+		state.substate.account_precompile_storage::<T>();
+
+		let exceeds =
+			|limit: Option<u64>, count: usize| limit.map_or(false, |limit| count as u64 > limit);
+		let post_execution_error = if !reason.is_succeed() {
+			None
+		} else if exceeds(
+			T::max_storage_slots_per_transaction(),
+			state.substate.new_storage_slots::<T>(),
+		) {
+			Some(ExitError::Other("new storage slots limit exceeded".into()))
+		} else if exceeds(
+			T::max_contracts_per_transaction(),
+			state.substate.creates.len(),
+		) {
+			Some(ExitError::Other("created contracts limit exceeded".into()))
+		} else if is_transactional
+			&& Pallet::<T>::settle_storage_deposits(
+				&source,
+				&state.substate.storage_growth,
+				&state.substate.deletes,
+			)
+			.is_err()
+		{
+			// Non-transactional calls, i.e. simulations, don't pay storage deposits.
+			Some(ExitError::OutOfFund)
+		} else {
+			None
+		};
+		match post_execution_error {
+			None => sp_io::storage::commit_transaction(),
+			Some(error) => {
+				log::debug!(
+					target: "evm",
+					"Reverting execution {:?} [source: {:?}]",
+					error,
+					source
+				);
+				sp_io::storage::rollback_transaction();
+				// The nonce increment of the executor is rolled back as well, but the
+				// transaction is still included and must not be replayable.
+				let account_id = T::AddressMapping::into_account_id(source);
+				frame_system::Pallet::<T>::inc_account_nonce(&account_id);
+				reason = ExitReason::Error(error);
+				state.substate.deletes.clear();
+				state.substate.logs.clear();
+			}
+		}
+		precompiles.deposit_failure_events();
+
+		match (sponsor, target) {
+			(Some((sponsor, share, sponsor_fee)), Some(target)) => {
+				// The sponsor pays its share of the actual base fee, and the source the rest of
+				// the actual fee, including the priority fee.
+				let sponsored_fee = permill_of(
+					share,
+					actual_fee.saturating_sub(actual_priority_fee.unwrap_or_default()),
+				);
+				T::OnChargeTransaction::correct_and_deposit_fee(
+					&source,
+					actual_fee.saturating_sub(sponsored_fee),
+					fee,
+				);
+				T::OnChargeTransaction::correct_and_deposit_fee(
+					&sponsor,
+					sponsored_fee,
+					sponsor_fee,
+				);
+				T::on_fee_sponsored(&sponsor, &source, &target, sponsored_fee);
+			}
+			_ => T::OnChargeTransaction::correct_and_deposit_fee(&source, actual_fee, fee),
+		}
 		if let Some(actual_priority_fee) = actual_priority_fee {
 			T::OnChargeTransaction::pay_priority_fee(actual_priority_fee);
 		}
 
-		let state = executor.into_state();
-
 		for address in state.substate.deletes {
 			log::debug!(
 				target: "evm",
@@ -257,9 +456,10 @@ impl<T: Config> RunnerT<T> for Runner<T> {
 		is_transactional: bool,
 		config: &evm::Config,
 	) -> Result<CallInfo, RunnerError<Self::Error>> {
-		let precompiles = T::PrecompilesValue::get();
+		let precompiles = ObservedPrecompiles::<T>::new(T::PrecompilesValue::get());
 		Self::execute(
 			source,
+			Some(target),
 			value,
 			gas_limit,
 			max_fee_per_gas,
@@ -284,9 +484,10 @@ impl<T: Config> RunnerT<T> for Runner<T> {
 		is_transactional: bool,
 		config: &evm::Config,
 	) -> Result<CreateInfo, RunnerError<Self::Error>> {
-		let precompiles = T::PrecompilesValue::get();
+		let precompiles = ObservedPrecompiles::<T>::new(T::PrecompilesValue::get());
 		Self::execute(
 			source,
+			None,
 			value,
 			gas_limit,
 			max_fee_per_gas,
@@ -317,10 +518,11 @@ impl<T: Config> RunnerT<T> for Runner<T> {
 		is_transactional: bool,
 		config: &evm::Config,
 	) -> Result<CreateInfo, RunnerError<Self::Error>> {
-		let precompiles = T::PrecompilesValue::get();
+		let precompiles = ObservedPrecompiles::<T>::new(T::PrecompilesValue::get());
 		let code_hash = H256::from_slice(Keccak256::digest(&init).as_slice());
 		Self::execute(
 			source,
+			None,
 			value,
 			gas_limit,
 			max_fee_per_gas,
@@ -341,11 +543,141 @@ impl<T: Config> RunnerT<T> for Runner<T> {
 			},
 		)
 	}
+
+	fn dry_run(
+		source: H160,
+		target: Option<H160>,
+		input: Vec<u8>,
+		value: U256,
+		gas_limit: u64,
+		max_fee_per_gas: Option<U256>,
+		max_priority_fee_per_gas: Option<U256>,
+		nonce: Option<U256>,
+		access_list: Vec<(H160, Vec<H256>)>,
+		config: &evm::Config,
+	) -> Result<DryRunInfo, RunnerError<Self::Error>> {
+		let precompiles = ObservedPrecompiles::<T>::new(T::PrecompilesValue::get());
+		// Everything the execution changes, fees included, is rolled back.
+		sp_io::storage::start_transaction();
+		let result = Self::execute(
+			source,
+			target,
+			value,
+			gas_limit,
+			max_fee_per_gas,
+			max_priority_fee_per_gas,
+			nonce,
+			config,
+			&precompiles,
+			false,
+			|executor| {
+				let (reason, output, address) = match target {
+					Some(target) => {
+						let (reason, output) = executor.transact_call(
+							source,
+							target,
+							value,
+							input,
+							gas_limit,
+							access_list,
+						);
+						(reason, output, target)
+					}
+					None => {
+						let address =
+							executor.create_address(evm::CreateScheme::Legacy { caller: source });
+						let (reason, _) =
+							executor.transact_create(source, value, input, gas_limit, access_list);
+						(reason, Vec::new(), address)
+					}
+				};
+				let (accessed, excluded_storage) =
+					accessed_list(executor.state().metadata(), |accessed| {
+						*accessed == source
+							|| *accessed == address
+							|| precompiles.is_precompile(*accessed)
+					});
+				let refunded_gas = executor
+					.state()
+					.metadata()
+					.gasometer()
+					.total_used_gas()
+					.saturating_sub(executor.used_gas());
+				(
+					reason,
+					(output, address, accessed, excluded_storage, refunded_gas),
+				)
+			},
+		);
+		sp_io::storage::rollback_transaction();
+
+		let ExecutionInfo {
+			exit_reason,
+			value: (output, address, access_list, excluded_storage, refunded_gas),
+			used_gas,
+			logs,
+		} = result?;
+		let info = match target {
+			Some(_) => CallOrCreateInfo::Call(CallInfo {
+				exit_reason,
+				value: output,
+				used_gas,
+				logs,
+			}),
+			None => CallOrCreateInfo::Create(CreateInfo {
+				exit_reason,
+				value: address,
+				used_gas,
+				logs,
+			}),
+		};
+		Ok(DryRunInfo {
+			info,
+			access_list,
+			excluded_storage,
+			refunded_gas: refunded_gas.into(),
+		})
+	}
+}
+
+/// Accounts and storage keys accessed by an execution, except the `excluded` accounts, and the
+/// storage keys accessed in the `excluded` accounts.
+fn accessed_list(
+	metadata: &StackSubstateMetadata,
+	excluded: impl Fn(&H160) -> bool,
+) -> (Vec<(H160, Vec<H256>)>, Vec<(H160, Vec<H256>)>) {
+	let mut list = BTreeMap::<H160, Vec<H256>>::new();
+	let mut excluded_storage = BTreeMap::<H160, Vec<H256>>::new();
+	if let Some(accessed) = metadata.accessed() {
+		for address in &accessed.accessed_addresses {
+			if !excluded(address) {
+				list.entry(*address).or_default();
+			}
+		}
+		for (address, key) in &accessed.accessed_storage {
+			if excluded(address) {
+				excluded_storage.entry(*address).or_default().push(*key);
+			} else {
+				list.entry(*address).or_default().push(*key);
+			}
+		}
+	}
+	(
+		list.into_iter().collect(),
+		excluded_storage.into_iter().collect(),
+	)
 }
 
 struct SubstrateStackSubstate<'config> {
 	metadata: StackSubstateMetadata<'config>,
+	creates: BTreeSet<H160>,
 	deletes: BTreeSet<H160>,
+	/// Bytes of code and storage added to each account, negative when removed, for the
+	/// storage deposits.
+	storage_growth: BTreeMap<H160, i64>,
+	/// Storage slots written to, with whether they were originally empty, for the state
+	/// growth limits.
+	storage_slots: BTreeMap<(H160, H256), bool>,
 	logs: Vec<Log>,
 	parent: Option<Box<SubstrateStackSubstate<'config>>>,
 }
@@ -363,7 +695,10 @@ impl<'config> SubstrateStackSubstate<'config> {
 		let mut entering = Self {
 			metadata: self.metadata.spit_child(gas_limit, is_static),
 			parent: None,
+			creates: BTreeSet::new(),
 			deletes: BTreeSet::new(),
+			storage_growth: BTreeMap::new(),
+			storage_slots: BTreeMap::new(),
 			logs: Vec::new(),
 		};
 		mem::swap(&mut entering, self);
@@ -379,7 +714,12 @@ impl<'config> SubstrateStackSubstate<'config> {
 
 		self.metadata.swallow_commit(exited.metadata)?;
 		self.logs.append(&mut exited.logs);
+		self.creates.append(&mut exited.creates);
 		self.deletes.append(&mut exited.deletes);
+		for (address, bytes) in exited.storage_growth {
+			self.grow_storage(address, bytes);
+		}
+		self.storage_slots.append(&mut exited.storage_slots);
 
 		sp_io::storage::commit_transaction();
 		Ok(())
@@ -403,6 +743,22 @@ impl<'config> SubstrateStackSubstate<'config> {
 		Ok(())
 	}
 
+	pub fn created(&self, address: H160) -> bool {
+		if self.creates.contains(&address) {
+			return true;
+		}
+
+		if let Some(parent) = self.parent.as_ref() {
+			return parent.created(address);
+		}
+
+		false
+	}
+
+	pub fn set_created(&mut self, address: H160) {
+		self.creates.insert(address);
+	}
+
 	pub fn deleted(&self, address: H160) -> bool {
 		if self.deletes.contains(&address) {
 			return true;
@@ -419,6 +775,57 @@ impl<'config> SubstrateStackSubstate<'config> {
 		self.deletes.insert(address);
 	}
 
+	/// Whether the storage slot was originally empty, if it was written to.
+	pub fn storage_slot(&self, address: H160, index: H256) -> Option<bool> {
+		self.storage_slots
+			.get(&(address, index))
+			.copied()
+			.or_else(|| {
+				self.parent
+					.as_ref()
+					.and_then(|parent| parent.storage_slot(address, index))
+			})
+	}
+
+	pub fn set_storage_slot(&mut self, address: H160, index: H256, originally_empty: bool) {
+		self.storage_slots
+			.insert((address, index), originally_empty);
+	}
+
+	/// Number of distinct storage slots which were originally empty and are now set.
+	pub fn new_storage_slots<T: Config>(&self) -> usize {
+		self.storage_slots
+			.iter()
+			.filter(|((address, index), originally_empty)| {
+				**originally_empty && Pallet::<T>::account_storage_exists(*address, *index)
+			})
+			.count()
+	}
+
+	/// Accounts the storage slots set by the precompiles in the new storage slots and the
+	/// storage growth, as the ones set by contracts.
+	pub fn account_precompile_storage<T: Config>(&mut self) {
+		for ((address, index), originally_empty) in <PrecompileStorageSlots<T>>::drain() {
+			if self.storage_slot(address, index).is_some() {
+				continue;
+			}
+			self.set_storage_slot(address, index, originally_empty);
+			match (
+				originally_empty,
+				Pallet::<T>::account_storage_exists(address, index),
+			) {
+				(true, true) => self.grow_storage(address, STORAGE_SLOT_BYTES),
+				(false, false) => self.grow_storage(address, -STORAGE_SLOT_BYTES),
+				_ => (),
+			}
+		}
+	}
+
+	pub fn grow_storage(&mut self, address: H160, bytes: i64) {
+		let growth = self.storage_growth.entry(address).or_default();
+		*growth = growth.saturating_add(bytes);
+	}
+
 	pub fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>) {
 		self.logs.push(Log {
 			address,
@@ -454,7 +861,10 @@ impl<'vicinity, 'config, T: Config> SubstrateStackState<'vicinity, 'config, T> {
 			vicinity,
 			substate: SubstrateStackSubstate {
 				metadata,
+				creates: BTreeSet::new(),
 				deletes: BTreeSet::new(),
+				storage_growth: BTreeMap::new(),
+				storage_slots: BTreeMap::new(),
 				logs: Vec::new(),
 				parent: None,
 			},
@@ -519,11 +929,11 @@ impl<'vicinity, 'config, T: Config> BackendT for SubstrateStackState<'vicinity,
 	}
 
 	fn code(&self, address: H160) -> Vec<u8> {
-		<AccountCodes<T>>::get(&address)
+		Pallet::<T>::account_codes(address)
 	}
 
 	fn storage(&self, address: H160, index: H256) -> H256 {
-		<AccountStorages<T>>::get(address, index)
+		Pallet::<T>::account_storages(address, index)
 	}
 
 	fn original_storage(&self, _address: H160, _index: H256) -> Option<H256> {
@@ -577,6 +987,22 @@ impl<'vicinity, 'config, T: Config> StackStateT<'config>
 	}
 
 	fn set_storage(&mut self, address: H160, index: H256, value: H256) {
+		// The previous value is only read when needed, as it adds to the proof size.
+		if !T::storage_deposit_per_byte().is_zero()
+			|| T::max_storage_slots_per_transaction().is_some()
+		{
+			// Zero slots are not stored.
+			let exists = Pallet::<T>::account_storage_exists(address, index);
+			if self.substate.storage_slot(address, index).is_none() {
+				self.substate.set_storage_slot(address, index, !exists);
+			}
+			match (exists, value == H256::default()) {
+				(false, false) => self.substate.grow_storage(address, STORAGE_SLOT_BYTES),
+				(true, true) => self.substate.grow_storage(address, -STORAGE_SLOT_BYTES),
+				_ => (),
+			}
+		}
+
 		if value == H256::default() {
 			log::debug!(
 				target: "evm",
@@ -584,7 +1010,6 @@ impl<'vicinity, 'config, T: Config> StackStateT<'config>
 				address,
 				index,
 			);
-			<AccountStorages<T>>::remove(address, index);
 		} else {
 			log::debug!(
 				target: "evm",
@@ -593,12 +1018,14 @@ impl<'vicinity, 'config, T: Config> StackStateT<'config>
 				index,
 				value,
 			);
-			<AccountStorages<T>>::insert(address, index, value);
 		}
+		Pallet::<T>::set_account_storage(address, index, value);
 	}
 
 	fn reset_storage(&mut self, address: H160) {
-		<AccountStorages<T>>::remove_prefix(address, None);
+		// The executor only resets the storage of an account when creating a contract at it.
+		self.substate.set_created(address);
+		Pallet::<T>::remove_account_storage(&address);
 	}
 
 	fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>) {
@@ -606,6 +1033,11 @@ impl<'vicinity, 'config, T: Config> StackStateT<'config>
 	}
 
 	fn set_deleted(&mut self, address: H160) {
+		// With EIP-6780, only the balance is sent to the beneficiary unless the account was
+		// created in the same transaction.
+		if T::eip6780_selfdestruct() && !self.substate.created(address) {
+			return;
+		}
 		self.substate.set_deleted(address)
 	}
 
@@ -616,6 +1048,7 @@ impl<'vicinity, 'config, T: Config> StackStateT<'config>
 			code.len(),
 			address
 		);
+		self.substate.grow_storage(address, code.len() as i64);
 		Pallet::<T>::create_account(address, code);
 	}
 
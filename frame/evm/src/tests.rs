@@ -21,9 +21,10 @@ use super::*;
 use crate::mock::*;
 
 use frame_support::{
-	assert_ok,
+	assert_noop, assert_ok,
 	traits::{GenesisBuild, LockIdentifier, LockableCurrency, WithdrawReasons},
 };
+use sp_runtime::DispatchError;
 use std::{collections::BTreeMap, str::FromStr};
 
 type Balances = pallet_balances::Pallet<Test>;
@@ -194,6 +195,94 @@ fn ed_0_refund_patch_is_required() {
 	});
 }
 
+#[test]
+fn fee_deduction_in_an_asset() {
+	new_test_ext().execute_with(|| {
+		let evm_addr = H160::from_str("1000000000000000000000000000000000000003").unwrap();
+		let substrate_addr = <Test as Config>::AddressMapping::into_account_id(evm_addr);
+		let author = EVM::find_author();
+		assert_ok!(Assets::force_create(
+			Origin::root(),
+			FeeAsset::get(),
+			H160::default(),
+			true,
+			1
+		));
+		assert_ok!(Assets::mint(
+			Origin::signed(H160::default()),
+			FeeAsset::get(),
+			substrate_addr,
+			100
+		));
+
+		// Without native balance, the fees can be paid in the asset.
+		assert!(!<FungiblesFeePayment as OnChargeEVMTransaction<Test>>::pays_from_balance());
+		assert!(
+			<FungiblesFeePayment as OnChargeEVMTransaction<Test>>::can_pay_fee(
+				&evm_addr,
+				U256::from(50)
+			)
+		);
+		assert!(
+			!<FungiblesFeePayment as OnChargeEVMTransaction<Test>>::can_pay_fee(
+				&evm_addr,
+				U256::from(51)
+			)
+		);
+
+		// Deduct fees as 10 units, worth 20 of the asset.
+		let credit = <FungiblesFeePayment as OnChargeEVMTransaction<Test>>::withdraw_fee(
+			&evm_addr,
+			U256::from(10),
+		)
+		.unwrap();
+		assert_eq!(Assets::balance(FeeAsset::get(), substrate_addr), 80);
+		assert_eq!(Balances::free_balance(&substrate_addr), 0);
+
+		// Refund fees as 5 units, the final fee is burnt.
+		<FungiblesFeePayment as OnChargeEVMTransaction<Test>>::correct_and_deposit_fee(
+			&evm_addr,
+			U256::from(5),
+			credit,
+		);
+		assert_eq!(Assets::balance(FeeAsset::get(), substrate_addr), 90);
+		assert_eq!(Assets::total_supply(FeeAsset::get()), 90);
+
+		// The priority fee is paid to the author in the asset.
+		<FungiblesFeePayment as OnChargeEVMTransaction<Test>>::pay_priority_fee(U256::from(3));
+		assert_eq!(Assets::balance(FeeAsset::get(), author), 6);
+
+		// Fees over the maximum balance aren't truncated.
+		for fee in [U256::from(u64::MAX) + 10, (U256::one() << 128) + 10] {
+			assert!(
+				!<FungiblesFeePayment as OnChargeEVMTransaction<Test>>::can_pay_fee(&evm_addr, fee)
+			);
+			assert!(
+				<FungiblesFeePayment as OnChargeEVMTransaction<Test>>::withdraw_fee(&evm_addr, fee)
+					.is_err()
+			);
+		}
+		assert_eq!(Assets::balance(FeeAsset::get(), substrate_addr), 90);
+
+		// Without conversion rate, the fees can't be paid.
+		FeeAssetRate::set(None);
+		assert!(
+			!<FungiblesFeePayment as OnChargeEVMTransaction<Test>>::can_pay_fee(
+				&evm_addr,
+				U256::from(10)
+			)
+		);
+		assert!(
+			<FungiblesFeePayment as OnChargeEVMTransaction<Test>>::withdraw_fee(
+				&evm_addr,
+				U256::from(10)
+			)
+			.is_err()
+		);
+		FeeAssetRate::set(Some(2));
+	});
+}
+
 #[test]
 fn find_author() {
 	new_test_ext().execute_with(|| {
@@ -389,7 +478,7 @@ fn handle_sufficient_reference() {
 		let substrate_addr_2 = <Test as Config>::AddressMapping::into_account_id(addr_2);
 
 		// Sufficients should increase when creating EVM accounts.
-		let _ = <crate::AccountCodes<Test>>::insert(addr, &vec![0]);
+		let _ = <crate::AccountCodeHash<Test>>::insert(addr, H256::repeat_byte(1));
 		let account = frame_system::Account::<Test>::get(substrate_addr);
 		// Using storage is not correct as it leads to a sufficient reference mismatch.
 		assert_eq!(account.sufficients, 0);
@@ -489,6 +578,42 @@ fn runner_transactional_call_with_zero_gas_price_fails() {
 	});
 }
 
+#[test]
+fn runner_fee_exempt_accounts_transact_at_zero_gas_price() {
+	new_test_ext().execute_with(|| {
+		let relayer = H160::from_str("7700000000000000000000000000000000000001").unwrap();
+		let call = || {
+			<Test as Config>::Runner::call(
+				relayer,
+				H160::from_str("1000000000000000000000000000000000000001").unwrap(),
+				Vec::new(),
+				U256::zero(),
+				1000000,
+				Some(U256::zero()),
+				None,
+				None,
+				Vec::new(),
+				true,
+				&<Test as Config>::config().clone(),
+			)
+		};
+
+		assert!(matches!(
+			call(),
+			Err(RunnerError {
+				error: Error::<Test>::GasPriceTooLow,
+				..
+			})
+		));
+
+		FeeExemptAccounts::set(vec![relayer]);
+		assert_eq!(EVM::base_fee_for(&relayer).0, U256::zero());
+		assert!(call().is_ok());
+		assert_eq!(EVM::account_basic(&relayer).0.nonce, U256::from(1));
+		FeeExemptAccounts::set(Vec::new());
+	});
+}
+
 #[test]
 fn runner_max_fee_per_gas_gte_max_priority_fee_per_gas() {
 	// Transactional and non transactional calls enforce `max_fee_per_gas >= max_priority_fee_per_gas`.
@@ -523,3 +648,848 @@ fn runner_max_fee_per_gas_gte_max_priority_fee_per_gas() {
 		assert!(res.is_err());
 	});
 }
+
+#[test]
+fn identical_codes_are_stored_once() {
+	new_test_ext().execute_with(|| {
+		let addr = H160::from_str("1230000000000000000000000000000000000001").unwrap();
+		let addr_2 = H160::from_str("1234000000000000000000000000000000000001").unwrap();
+		let code = vec![1, 2, 3];
+		let code_hash = H256::from_slice(Keccak256::digest(&code).as_slice());
+
+		EVM::create_account(addr, code.clone());
+		EVM::create_account(addr_2, code.clone());
+		assert_eq!(EVM::account_code_hash(addr), Some(code_hash));
+		assert_eq!(EVM::account_code_hash(addr_2), Some(code_hash));
+		assert_eq!(EVM::account_codes(addr_2), code);
+		assert_eq!(<AccountCodeRefCount<Test>>::get(code_hash), 2);
+
+		// The preimage is kept while an account still references it.
+		EVM::remove_account(&addr);
+		assert_eq!(EVM::account_codes(addr), Vec::<u8>::new());
+		assert_eq!(EVM::account_codes(addr_2), code);
+		assert_eq!(<AccountCodeRefCount<Test>>::get(code_hash), 1);
+
+		EVM::remove_account(&addr_2);
+		assert!(!<AccountCodeRefCount<Test>>::contains_key(code_hash));
+		assert!(!<AccountCodeByHash<Test>>::contains_key(code_hash));
+	});
+}
+
+#[test]
+fn account_codes_migration_moves_codes_in_batches() {
+	new_test_ext().execute_with(|| {
+		// A chain launched before the codes were deduplicated.
+		<AccountCodesMigrated<Test>>::kill();
+		let addr = H160::from_str("1230000000000000000000000000000000000001").unwrap();
+		let addr_2 = H160::from_str("1234000000000000000000000000000000000001").unwrap();
+		let addr_3 = H160::from_str("1234500000000000000000000000000000000001").unwrap();
+		let code = vec![1, 2, 3];
+		let code_hash = H256::from_slice(Keccak256::digest(&code).as_slice());
+		for address in [addr, addr_2, addr_3] {
+			<AccountCodes<Test>>::insert(address, code.clone());
+			let _ = frame_system::Pallet::<Test>::inc_sufficients(&address);
+		}
+
+		// Reads fall back to the legacy map, changes move the code first.
+		assert_eq!(EVM::account_codes(addr), code);
+		assert!(EVM::has_code(&addr));
+		EVM::remove_account_code(&addr_3);
+		assert!(!<AccountCodes<Test>>::contains_key(addr_3));
+		assert!(!EVM::has_code(&addr_3));
+
+		type Migration = migration::AccountCodesMigration<Test>;
+		assert_eq!(Migration::migrate(1).0, false);
+		assert!(!<AccountCodesMigrated<Test>>::get());
+		assert_eq!(Migration::migrate(2).0, true);
+		assert!(<AccountCodesMigrated<Test>>::get());
+
+		assert_eq!(<AccountCodes<Test>>::iter().count(), 0);
+		assert_eq!(EVM::account_codes(addr), code);
+		assert_eq!(EVM::account_codes(addr_2), code);
+		assert_eq!(<AccountCodeRefCount<Test>>::get(code_hash), 2);
+	});
+}
+
+type FlippedMappingMigration = migration::AddressMappingMigration<Test, FlippedAddressMapping>;
+
+#[test]
+fn address_mapping_migration_moves_externally_owned_accounts() {
+	new_test_ext().execute_with(|| {
+		let address = H160::from_str("7700000000000000000000000000000000000001").unwrap();
+		let old = FlippedAddressMapping::into_account_id(address);
+		let _ = Balances::deposit_creating(&old, 100);
+		for _ in 0..3 {
+			frame_system::Pallet::<Test>::inc_account_nonce(&old);
+		}
+
+		FlippedMappingMigration::migrate_addresses(&[address], 1);
+		assert_eq!(Balances::free_balance(&old), 0);
+		assert_eq!(
+			EVM::account_basic(&address).0,
+			Account {
+				nonce: U256::from(3),
+				balance: U256::from(100),
+			}
+		);
+
+		// Migrating again changes nothing.
+		FlippedMappingMigration::migrate_addresses(&[address], 1);
+		assert_eq!(EVM::account_basic(&address).0.balance, U256::from(100));
+		assert_eq!(EVM::account_basic(&address).0.nonce, U256::from(3));
+	});
+}
+
+#[test]
+fn address_mapping_migration_moves_addresses_in_batches() {
+	new_test_ext().execute_with(|| {
+		let addresses: Vec<H160> = (1..=3).map(|i| H160::from_low_u64_be(0x7700 + i)).collect();
+		for address in &addresses {
+			let _ =
+				Balances::deposit_creating(&FlippedAddressMapping::into_account_id(*address), 100);
+		}
+
+		assert_eq!(
+			FlippedMappingMigration::migrate_addresses(&addresses, 2).0,
+			2
+		);
+		assert!(FlippedMappingMigration::is_migrated(addresses[1]));
+		assert!(!FlippedMappingMigration::is_migrated(addresses[2]));
+
+		assert_eq!(
+			FlippedMappingMigration::migrate_addresses(&addresses[2..], 2).0,
+			1
+		);
+		assert!(FlippedMappingMigration::is_migrated(addresses[2]));
+	});
+}
+
+#[test]
+fn address_mapping_migration_blocks_unmigrated_addresses() {
+	new_test_ext().execute_with(|| {
+		let address = H160::from_str("7700000000000000000000000000000000000001").unwrap();
+		let old = FlippedAddressMapping::into_account_id(address);
+		let _ = Balances::deposit_creating(&old, 1_000_000_000);
+		frame_system::Pallet::<Test>::inc_account_nonce(&old);
+		let call = || {
+			<Test as Config>::Runner::call(
+				address,
+				H160::from_str("1000000000000000000000000000000000000001").unwrap(),
+				Vec::new(),
+				U256::zero(),
+				1000000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false,
+				&<Test as Config>::config().clone(),
+			)
+		};
+
+		MigratingFromFlippedAddressMapping::set(true);
+		assert!(matches!(
+			call(),
+			Err(RunnerError {
+				error: Error::<Test>::AccountNotMigrated,
+				..
+			})
+		));
+
+		FlippedMappingMigration::migrate_address(address);
+		assert!(call().is_ok());
+		MigratingFromFlippedAddressMapping::set(false);
+	});
+}
+
+#[test]
+fn address_mapping_migration_moves_contracts_in_batches() {
+	new_test_ext().execute_with(|| {
+		let contract = H160::from_str("7700000000000000000000000000000000000002").unwrap();
+		let old = FlippedAddressMapping::into_account_id(contract);
+		<AccountCodeHash<Test>>::insert(contract, H256::repeat_byte(1));
+		let _ = frame_system::Pallet::<Test>::inc_sufficients(&old);
+		let _ = Balances::deposit_creating(&old, 50);
+		assert_ok!(Balances::reserve(&old, 20));
+		<StorageDeposits<Test>>::insert(contract, 20);
+
+		// Along with the two contracts of the genesis.
+		assert_eq!(FlippedMappingMigration::migrate_contracts(2).0, false);
+		assert!(<AddressMappingMigrationCursor<Test>>::get().is_some());
+		assert_eq!(FlippedMappingMigration::migrate_contracts(2).0, true);
+		assert!(<AddressMappingMigrationCursor<Test>>::get().is_none());
+
+		assert_eq!(Balances::total_balance(&old), 0);
+		assert_eq!(frame_system::Pallet::<Test>::sufficients(&old), 0);
+		assert_eq!(Balances::free_balance(&contract), 30);
+		assert_eq!(Balances::reserved_balance(&contract), 20);
+		assert_eq!(frame_system::Pallet::<Test>::sufficients(&contract), 1);
+	});
+}
+
+#[test]
+fn account_storages_are_stored_in_a_child_trie() {
+	new_test_ext().execute_with(|| {
+		let address = H160::repeat_byte(0x77);
+		let index = H256::repeat_byte(1);
+		EVM::set_account_storage(address, index, H256::repeat_byte(2));
+
+		assert!(!<AccountStorages<Test>>::contains_key(address, index));
+		assert_eq!(
+			child::get::<H256>(&EVM::account_storage_child_info(&address), index.as_bytes()),
+			Some(H256::repeat_byte(2))
+		);
+
+		EVM::remove_account(&address);
+		assert!(!EVM::account_storage_exists(address, index));
+		assert_eq!(EVM::account_storages(address, index), H256::zero());
+	});
+}
+
+#[test]
+fn account_storages_migration_moves_slots_in_batches() {
+	new_test_ext().execute_with(|| {
+		// A chain launched before the child tries.
+		<AccountStoragesMigrated<Test>>::kill();
+		let address = H160::repeat_byte(0x77);
+		for byte in 1..=3 {
+			<AccountStorages<Test>>::insert(
+				address,
+				H256::repeat_byte(byte),
+				H256::repeat_byte(byte),
+			);
+		}
+
+		// Reads fall back to the legacy map, writes move the slot.
+		assert_eq!(
+			EVM::account_storages(address, H256::repeat_byte(1)),
+			H256::repeat_byte(1)
+		);
+		assert!(EVM::account_storage_exists(address, H256::repeat_byte(2)));
+		EVM::set_account_storage(address, H256::repeat_byte(1), H256::repeat_byte(4));
+		assert!(!<AccountStorages<Test>>::contains_key(
+			address,
+			H256::repeat_byte(1)
+		));
+		assert_eq!(
+			EVM::account_storages(address, H256::repeat_byte(1)),
+			H256::repeat_byte(4)
+		);
+
+		type Migration = migration::AccountStoragesMigration<Test>;
+		assert_eq!(Migration::migrate(1).0, false);
+		assert!(!<AccountStoragesMigrated<Test>>::get());
+		assert_eq!(Migration::migrate(2).0, true);
+		assert!(<AccountStoragesMigrated<Test>>::get());
+
+		assert_eq!(<AccountStorages<Test>>::iter().count(), 0);
+		assert_eq!(
+			EVM::account_storages(address, H256::repeat_byte(1)),
+			H256::repeat_byte(4)
+		);
+		assert_eq!(
+			EVM::account_storages(address, H256::repeat_byte(2)),
+			H256::repeat_byte(2)
+		);
+		assert_eq!(
+			EVM::account_storages(address, H256::repeat_byte(3)),
+			H256::repeat_byte(3)
+		);
+	});
+}
+
+#[test]
+fn create2_address_works() {
+	new_test_ext().execute_with(|| {
+		// Example 0 of EIP-1014.
+		let init_code_hash = H256::from_slice(Keccak256::digest(&[0x00]).as_slice());
+		assert_eq!(
+			EVM::create2_address(H160::zero(), H256::zero(), init_code_hash),
+			H160::from_str("4d1a2e2bb4f88f0250f26ffff098b0b30b26bf38").unwrap()
+		);
+	});
+}
+
+fn used_gas_of(code: Vec<u8>, access_list: Vec<(H160, Vec<H256>)>, config: &EvmConfig) -> U256 {
+	let contract = H160::from_str("1230000000000000000000000000000000000001").unwrap();
+	EVM::create_account(contract, code);
+	<Test as Config>::Runner::call(
+		H160::from_str("1000000000000000000000000000000000000001").unwrap(),
+		contract,
+		Vec::new(),
+		U256::zero(),
+		1000000,
+		None,
+		None,
+		None,
+		access_list,
+		false,
+		config,
+	)
+	.expect("call succeeds")
+	.used_gas
+}
+
+#[test]
+fn sload_gas_follows_eip2929() {
+	new_test_ext().execute_with(|| {
+		let contract = H160::from_str("1230000000000000000000000000000000000001").unwrap();
+		// PUSH1 0x00 SLOAD PUSH1 0x00 SLOAD STOP
+		let code = vec![0x60, 0x00, 0x54, 0x60, 0x00, 0x54, 0x00];
+
+		// Cold then warm slot access.
+		assert_eq!(
+			used_gas_of(code.clone(), Vec::new(), <Test as Config>::config()),
+			U256::from(21_000 + 3 + 2_100 + 3 + 100)
+		);
+		// The slot is warmed by the access list, which is charged in the intrinsic cost.
+		assert_eq!(
+			used_gas_of(
+				code.clone(),
+				vec![(contract, vec![H256::zero()])],
+				<Test as Config>::config()
+			),
+			U256::from(21_000 + 2_400 + 1_900 + 3 + 100 + 3 + 100)
+		);
+		// No access accounting before Berlin.
+		assert_eq!(
+			used_gas_of(
+				code,
+				vec![(contract, vec![H256::zero()])],
+				&EvmConfig::istanbul()
+			),
+			U256::from(21_000 + 3 + 800 + 3 + 800)
+		);
+	});
+}
+
+#[test]
+fn dry_run_returns_the_access_list_without_committing() {
+	new_test_ext().execute_with(|| {
+		let source = H160::from_str("1000000000000000000000000000000000000001").unwrap();
+		let contract = H160::from_str("1230000000000000000000000000000000000001").unwrap();
+		let other = H160::from_str("1000000000000000000000000000000000000002").unwrap();
+		// PUSH1 0x01 SLOAD PUSH20 other EXTCODEHASH PUSH1 0x02 PUSH1 0x03 SSTORE STOP
+		let mut code = vec![0x60, 0x01, 0x54, 0x73];
+		code.extend_from_slice(other.as_bytes());
+		code.extend_from_slice(&[0x3f, 0x60, 0x02, 0x60, 0x03, 0x55, 0x00]);
+		EVM::create_account(contract, code);
+
+		let dry_run = <Test as Config>::Runner::dry_run(
+			source,
+			Some(contract),
+			Vec::new(),
+			U256::zero(),
+			1000000,
+			None,
+			None,
+			None,
+			Vec::new(),
+			<Test as Config>::config(),
+		)
+		.expect("dry run succeeds");
+
+		match dry_run.info {
+			CallOrCreateInfo::Call(info) => {
+				assert_eq!(info.exit_reason, ExitReason::Succeed(ExitSucceed::Stopped))
+			}
+			CallOrCreateInfo::Create(_) => panic!("expected call info"),
+		}
+		// The sender and the called contract are excluded, along with their storage keys.
+		assert_eq!(dry_run.access_list, vec![(other, Vec::new())]);
+		assert_eq!(
+			dry_run.excluded_storage,
+			vec![(
+				contract,
+				vec![H256::from_low_u64_be(1), H256::from_low_u64_be(3)]
+			)]
+		);
+		assert_eq!(
+			EVM::account_storages(contract, H256::from_low_u64_be(3)),
+			H256::zero()
+		);
+		assert_eq!(EVM::account_basic(&source).0.nonce, U256::from(1));
+	});
+}
+
+#[test]
+fn dry_run_returns_the_refunded_gas() {
+	new_test_ext().execute_with(|| {
+		let source = H160::from_str("1000000000000000000000000000000000000001").unwrap();
+		let contract = H160::from_str("1230000000000000000000000000000000000001").unwrap();
+		// PUSH1 0x00 PUSH1 0x00 SSTORE STOP
+		EVM::create_account(contract, vec![0x60, 0x00, 0x60, 0x00, 0x55, 0x00]);
+		EVM::set_account_storage(contract, H256::zero(), H256::from_low_u64_be(1));
+
+		let dry_run = <Test as Config>::Runner::dry_run(
+			source,
+			Some(contract),
+			Vec::new(),
+			U256::zero(),
+			1000000,
+			None,
+			None,
+			None,
+			Vec::new(),
+			<Test as Config>::config(),
+		)
+		.expect("dry run succeeds");
+
+		// Clearing the cold slot costs 5000 and refunds 4800, under a fifth of the gas.
+		assert_eq!(dry_run.refunded_gas, U256::from(4_800));
+		match dry_run.info {
+			CallOrCreateInfo::Call(info) => {
+				assert_eq!(info.used_gas, U256::from(21_000 + 3 + 3 + 5_000 - 4_800))
+			}
+			CallOrCreateInfo::Create(_) => panic!("expected call info"),
+		}
+	});
+}
+
+#[test]
+fn extcodehash_gas_follows_eip2929() {
+	new_test_ext().execute_with(|| {
+		let other = H160::from_str("1000000000000000000000000000000000000002").unwrap();
+		// PUSH20 other EXTCODEHASH PUSH20 other EXTCODEHASH STOP
+		let mut code = vec![0x73];
+		code.extend_from_slice(other.as_bytes());
+		code.push(0x3f);
+		code.push(0x73);
+		code.extend_from_slice(other.as_bytes());
+		code.extend_from_slice(&[0x3f, 0x00]);
+
+		// Cold then warm account access.
+		assert_eq!(
+			used_gas_of(code.clone(), Vec::new(), <Test as Config>::config()),
+			U256::from(21_000 + 3 + 2_600 + 3 + 100)
+		);
+		// The account is warmed by the access list.
+		assert_eq!(
+			used_gas_of(code, vec![(other, Vec::new())], <Test as Config>::config()),
+			U256::from(21_000 + 2_400 + 3 + 100 + 3 + 100)
+		);
+	});
+}
+
+#[test]
+fn gas_overrides_are_layered_over_the_hardfork_costs() {
+	new_test_ext().execute_with(|| {
+		// PUSH1 0x00 SLOAD PUSH1 0x00 SLOAD STOP
+		let code = vec![0x60, 0x00, 0x54, 0x60, 0x00, 0x54, 0x00];
+		let config = fp_evm::apply_gas_overrides(
+			<Test as Config>::config(),
+			&[(GasParameter::SloadCold, 5_000)],
+		);
+
+		assert_eq!(
+			used_gas_of(code, Vec::new(), &config),
+			U256::from(21_000 + 3 + 5_000 + 3 + 100)
+		);
+		for (parameter, cost) in fp_evm::gas_table(&config) {
+			if parameter == GasParameter::SloadCold {
+				assert_eq!(cost, 5_000);
+			} else {
+				assert_eq!(cost, parameter.cost(<Test as Config>::config()));
+			}
+		}
+	});
+}
+
+fn call_selfdestruct() -> H160 {
+	let contract = H160::from_str("1230000000000000000000000000000000000001").unwrap();
+	let beneficiary = H160::from_str("1000000000000000000000000000000000000002").unwrap();
+	// PUSH20 beneficiary SELFDESTRUCT
+	let mut code = vec![0x73];
+	code.extend_from_slice(beneficiary.as_bytes());
+	code.push(0xff);
+	EVM::create_account(contract, code);
+	<Test as Config>::Runner::call(
+		H160::from_str("1000000000000000000000000000000000000001").unwrap(),
+		contract,
+		Vec::new(),
+		U256::zero(),
+		1000000,
+		None,
+		None,
+		None,
+		Vec::new(),
+		false,
+		<Test as Config>::config(),
+	)
+	.expect("call succeeds");
+	contract
+}
+
+#[test]
+fn selfdestruct_deletes_account() {
+	new_test_ext().execute_with(|| {
+		let contract = call_selfdestruct();
+		assert!(EVM::account_codes(contract).is_empty());
+	});
+}
+
+#[test]
+fn selfdestruct_keeps_existing_account_with_eip6780() {
+	new_test_ext().execute_with(|| {
+		Eip6780Selfdestruct::set(true);
+		let contract = call_selfdestruct();
+		assert!(!EVM::account_codes(contract).is_empty());
+		Eip6780Selfdestruct::set(false);
+	});
+}
+
+fn transact_call(source: H160, contract: H160) -> ExitReason {
+	<Test as Config>::Runner::call(
+		source,
+		contract,
+		Vec::new(),
+		U256::zero(),
+		100_000,
+		Some(U256::from(1_000_000_000)),
+		None,
+		None,
+		Vec::new(),
+		true,
+		<Test as Config>::config(),
+	)
+	.expect("call succeeds")
+	.exit_reason
+}
+
+#[test]
+fn storage_deposits_follow_the_storage_growth() {
+	new_test_ext().execute_with(|| {
+		StorageDepositPerByte::set(10);
+		let source = H160::default();
+		let contract = H160::from_str("1230000000000000000000000000000000000001").unwrap();
+		let deposit = 10 * STORAGE_SLOT_BYTES as u64;
+
+		// PUSH1 0x01 PUSH1 0x00 SSTORE STOP
+		EVM::create_account(contract, vec![0x60, 0x01, 0x60, 0x00, 0x55, 0x00]);
+		assert!(transact_call(source, contract).is_succeed());
+		assert_eq!(EVM::storage_deposits(contract), deposit);
+		assert_eq!(Balances::reserved_balance(contract), deposit);
+
+		// PUSH1 0x00 PUSH1 0x00 SSTORE STOP
+		EVM::create_account(contract, vec![0x60, 0x00, 0x60, 0x00, 0x55, 0x00]);
+		assert!(transact_call(source, contract).is_succeed());
+		assert_eq!(EVM::storage_deposits(contract), 0);
+		// The deposit is refunded to the source.
+		assert_eq!(Balances::reserved_balance(contract), 0);
+		assert_eq!(Balances::free_balance(contract), 0);
+
+		StorageDepositPerByte::set(0);
+	});
+}
+
+#[test]
+fn storage_deposits_revert_the_execution_if_unpaid() {
+	new_test_ext().execute_with(|| {
+		// More than the balance of the source left after the fee.
+		StorageDepositPerByte::set(1_000_000);
+		let source = H160::default();
+		let contract = H160::from_str("1230000000000000000000000000000000000001").unwrap();
+		Balances::make_free_balance_be(&source, 1_000_000_000 * 100_000 + 1_000);
+
+		// PUSH1 0x01 PUSH1 0x00 SSTORE STOP
+		EVM::create_account(contract, vec![0x60, 0x01, 0x60, 0x00, 0x55, 0x00]);
+		assert_eq!(
+			transact_call(source, contract),
+			ExitReason::Error(ExitError::OutOfFund)
+		);
+		assert_eq!(EVM::account_storages(contract, H256::zero()), H256::zero());
+		assert_eq!(EVM::storage_deposits(contract), 0);
+		// The transaction is included, so its nonce is used.
+		assert_eq!(EVM::account_basic(&source).0.nonce, U256::one());
+
+		StorageDepositPerByte::set(0);
+	});
+}
+
+#[test]
+fn storage_deposits_keep_the_existential_deposit_of_contracts() {
+	new_test_ext().execute_with(|| {
+		StorageDepositPerByte::set(10);
+		ExistentialDeposit::set(500);
+		let source = H160::default();
+		let contract = H160::from_str("1230000000000000000000000000000000000001").unwrap();
+
+		// PUSH1 0x01 PUSH1 0x00 SSTORE STOP
+		EVM::create_account(contract, vec![0x60, 0x01, 0x60, 0x00, 0x55, 0x00]);
+		assert!(transact_call(source, contract).is_succeed());
+		// The deposit of a contract without balance is raised to the existential deposit.
+		assert_eq!(EVM::storage_deposits(contract), 500);
+		assert_eq!(Balances::reserved_balance(contract), 500);
+
+		// PUSH1 0x00 PUSH1 0x00 SSTORE STOP
+		EVM::create_account(contract, vec![0x60, 0x00, 0x60, 0x00, 0x55, 0x00]);
+		assert!(transact_call(source, contract).is_succeed());
+		// The existential deposit stays reserved until the contract is deleted.
+		assert_eq!(EVM::storage_deposits(contract), 500);
+		assert_eq!(Balances::reserved_balance(contract), 500);
+
+		ExistentialDeposit::set(0);
+		StorageDepositPerByte::set(0);
+	});
+}
+
+#[test]
+fn new_storage_slots_over_the_limit_revert_the_execution() {
+	new_test_ext().execute_with(|| {
+		let source = H160::default();
+		let contract = H160::from_str("1230000000000000000000000000000000000001").unwrap();
+		// PUSH1 0x01 PUSH1 0x00 SSTORE PUSH1 0x01 PUSH1 0x01 SSTORE STOP
+		EVM::create_account(
+			contract,
+			vec![
+				0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x01, 0x60, 0x01, 0x55, 0x00,
+			],
+		);
+
+		MaxStorageSlotsPerTransaction::set(Some(1));
+		assert_eq!(
+			transact_call(source, contract),
+			ExitReason::Error(ExitError::Other("new storage slots limit exceeded".into()))
+		);
+		assert_eq!(EVM::account_storages(contract, H256::zero()), H256::zero());
+		// The transaction is included, so its nonce is used.
+		assert_eq!(EVM::account_basic(&source).0.nonce, U256::one());
+
+		MaxStorageSlotsPerTransaction::set(Some(2));
+		assert!(transact_call(source, contract).is_succeed());
+		assert_eq!(
+			EVM::account_storages(contract, H256::zero()),
+			H256::from_low_u64_be(1)
+		);
+
+		MaxStorageSlotsPerTransaction::set(None);
+	});
+}
+
+#[test]
+fn new_storage_slots_are_counted_once() {
+	new_test_ext().execute_with(|| {
+		let source = H160::default();
+		let contract = H160::from_str("1230000000000000000000000000000000000001").unwrap();
+		// PUSH1 0x01 PUSH1 0x00 SSTORE PUSH1 0x00 PUSH1 0x00 SSTORE PUSH1 0x01 PUSH1 0x00 SSTORE
+		// STOP
+		EVM::create_account(
+			contract,
+			vec![
+				0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x00, 0x60, 0x00, 0x55, 0x60, 0x01, 0x60, 0x00,
+				0x55, 0x00,
+			],
+		);
+
+		MaxStorageSlotsPerTransaction::set(Some(1));
+		assert!(transact_call(source, contract).is_succeed());
+		assert_eq!(
+			EVM::account_storages(contract, H256::zero()),
+			H256::from_low_u64_be(1)
+		);
+
+		MaxStorageSlotsPerTransaction::set(None);
+	});
+}
+
+#[test]
+fn created_contracts_over_the_limit_revert_the_execution() {
+	new_test_ext().execute_with(|| {
+		MaxContractsPerTransaction::set(Some(0));
+		// PUSH1 0x00 PUSH1 0x00 RETURN
+		let info = <Test as Config>::Runner::create(
+			H160::default(),
+			vec![0x60, 0x00, 0x60, 0x00, 0xf3],
+			U256::zero(),
+			100_000,
+			Some(U256::from(1_000_000_000)),
+			None,
+			None,
+			Vec::new(),
+			true,
+			<Test as Config>::config(),
+		)
+		.expect("create succeeds");
+		assert_eq!(
+			info.exit_reason,
+			ExitReason::Error(ExitError::Other("created contracts limit exceeded".into()))
+		);
+		assert_eq!(EVM::account_basic(&H160::default()).0.nonce, U256::one());
+
+		MaxContractsPerTransaction::set(None);
+	});
+}
+
+fn call_reverting_precompile(input: Vec<u8>) -> ExitReason {
+	<Test as Config>::Runner::call(
+		H160::default(),
+		MockPrecompiles::reverting(),
+		input,
+		U256::zero(),
+		100_000,
+		Some(U256::from(1_000_000_000)),
+		None,
+		None,
+		Vec::new(),
+		true,
+		<Test as Config>::config(),
+	)
+	.expect("call succeeds")
+	.exit_reason
+}
+
+fn precompile_failed_events() -> Vec<crate::mock::Event> {
+	System::events()
+		.into_iter()
+		.map(|record| record.event)
+		.filter(|event| {
+			matches!(
+				event,
+				crate::mock::Event::EVM(crate::Event::PrecompileFailed(..))
+			)
+		})
+		.collect()
+}
+
+#[test]
+fn precompile_failures_deposit_events_when_enabled() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_eq!(
+			call_reverting_precompile(vec![0x12, 0x34, 0x56, 0x78, 0x9a]),
+			ExitReason::Revert(ExitRevert::Reverted)
+		);
+		assert!(precompile_failed_events().is_empty());
+
+		PrecompileFailureEvents::set(true);
+		call_reverting_precompile(vec![0x12, 0x34, 0x56, 0x78, 0x9a]);
+		call_reverting_precompile(vec![0x12]);
+		assert_eq!(
+			precompile_failed_events(),
+			vec![
+				crate::mock::Event::EVM(crate::Event::PrecompileFailed(
+					MockPrecompiles::reverting(),
+					Some([0x12, 0x34, 0x56, 0x78]),
+					PrecompileFailureKind::Revert,
+				)),
+				crate::mock::Event::EVM(crate::Event::PrecompileFailed(
+					MockPrecompiles::reverting(),
+					None,
+					PrecompileFailureKind::Revert,
+				)),
+			]
+		);
+
+		PrecompileFailureEvents::set(false);
+	});
+}
+
+#[test]
+fn precompile_failure_events_survive_the_failing_call() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		PrecompileFailureEvents::set(true);
+		let contract = H160::from_str("1230000000000000000000000000000000000001").unwrap();
+		// PUSH1 0x00 PUSH1 0x00 PUSH1 0x00 PUSH1 0x00 PUSH1 0x00 PUSH2 0x0400 GAS CALL POP STOP
+		EVM::create_account(
+			contract,
+			vec![
+				0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x61, 0x04, 0x00, 0x5a,
+				0xf1, 0x50, 0x00,
+			],
+		);
+
+		// The contract handles the failure of the precompile, whose call is rolled back.
+		assert!(transact_call(H160::default(), contract).is_succeed());
+		assert_eq!(
+			precompile_failed_events(),
+			vec![crate::mock::Event::EVM(crate::Event::PrecompileFailed(
+				MockPrecompiles::reverting(),
+				None,
+				PrecompileFailureKind::Revert,
+			))]
+		);
+
+		PrecompileFailureEvents::set(false);
+	});
+}
+
+#[test]
+fn force_operations_require_root() {
+	new_test_ext().execute_with(|| {
+		let address = H160::repeat_byte(0x11);
+		let origin = || Origin::signed(H160::default());
+		assert_noop!(
+			EVM::force_set_balance(origin(), address, 1),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			EVM::force_set_nonce(origin(), address, 1),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			EVM::force_set_code(origin(), address, vec![0x00]),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			EVM::force_set_storage(origin(), address, H256::zero(), H256::repeat_byte(1)),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn force_set_balance_and_nonce_work() {
+	new_test_ext().execute_with(|| {
+		let address = H160::from_str("1000000000000000000000000000000000000001").unwrap();
+
+		assert_ok!(EVM::force_set_balance(Origin::root(), address, 500));
+		assert_ok!(EVM::force_set_nonce(Origin::root(), address, 7));
+
+		let (account, _) = EVM::account_basic(&address);
+		assert_eq!(account.balance, U256::from(500));
+		assert_eq!(account.nonce, U256::from(7));
+	});
+}
+
+#[test]
+fn force_set_code_replaces_and_removes_code() {
+	new_test_ext().execute_with(|| {
+		let address = H160::from_str("1000000000000000000000000000000000000001").unwrap();
+		let index = H256::repeat_byte(1);
+		EVM::set_account_storage(address, index, H256::repeat_byte(2));
+
+		assert_ok!(EVM::force_set_code(Origin::root(), address, vec![0xfe]));
+		assert_eq!(EVM::account_codes(address), vec![0xfe]);
+
+		// The storage is kept with the code removed.
+		assert_ok!(EVM::force_set_code(Origin::root(), address, vec![]));
+		assert!(EVM::account_codes(address).is_empty());
+		assert_eq!(EVM::account_code_hash(address), None);
+		assert_eq!(EVM::account_storages(address, index), H256::repeat_byte(2));
+	});
+}
+
+#[test]
+fn force_set_storage_works() {
+	new_test_ext().execute_with(|| {
+		let address = H160::from_str("1000000000000000000000000000000000000001").unwrap();
+		let index = H256::repeat_byte(1);
+
+		assert_ok!(EVM::force_set_storage(
+			Origin::root(),
+			address,
+			index,
+			H256::repeat_byte(2)
+		));
+		assert_eq!(EVM::account_storages(address, index), H256::repeat_byte(2));
+
+		assert_ok!(EVM::force_set_storage(
+			Origin::root(),
+			address,
+			index,
+			H256::zero()
+		));
+		assert!(!EVM::account_storage_exists(address, index));
+	});
+}
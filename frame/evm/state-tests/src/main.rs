@@ -0,0 +1,238 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runs the `GeneralStateTests` of the Ethereum tests repository against the EVM pallet, and
+//! reports how many of them pass for each fork.
+//!
+//! The EVM pallet knowingly diverges from Ethereum on some points, so some tests are expected
+//! to fail. Given a file of known failures, only the failures it doesn't list are reported as
+//! errors, which catches the regressions of the executor and of the gas accounting.
+
+mod mock;
+mod runner;
+mod state;
+mod types;
+
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	fs,
+	path::{Path, PathBuf},
+	process,
+};
+
+use crate::{runner::Fork, types::StateTest};
+
+const USAGE: &str = "\
+Usage: pallet-evm-state-tests [OPTIONS] <PATH>...
+
+Runs the state tests of the given files, or of the JSON files found in the given directories.
+
+Options:
+    --fork <FORK>             Only runs the tests of this fork (Istanbul, Berlin or London).
+                              Can be repeated. Defaults to every supported fork.
+    --known-failures <FILE>   File listing the tests known to fail, one identifier per line.
+    --update-known-failures   Writes the failing tests to the known failures file.
+    -v, --verbose             Prints the reason of each failure.";
+
+struct Options {
+	forks: Vec<Fork>,
+	known_failures: Option<PathBuf>,
+	update_known_failures: bool,
+	verbose: bool,
+	paths: Vec<PathBuf>,
+}
+
+impl Options {
+	fn parse() -> Result<Self, String> {
+		let mut options = Options {
+			forks: Vec::new(),
+			known_failures: None,
+			update_known_failures: false,
+			verbose: false,
+			paths: Vec::new(),
+		};
+		let mut args = std::env::args().skip(1);
+		while let Some(arg) = args.next() {
+			match arg.as_str() {
+				"--fork" => {
+					let name = args.next().ok_or("--fork requires a value")?;
+					let fork = Fork::from_name(&name)
+						.ok_or_else(|| format!("unsupported fork {}", name))?;
+					options.forks.push(fork);
+				}
+				"--known-failures" => {
+					let path = args.next().ok_or("--known-failures requires a value")?;
+					options.known_failures = Some(path.into());
+				}
+				"--update-known-failures" => options.update_known_failures = true,
+				"-v" | "--verbose" => options.verbose = true,
+				"-h" | "--help" => {
+					println!("{}", USAGE);
+					process::exit(0);
+				}
+				arg if arg.starts_with('-') => return Err(format!("unknown option {}", arg)),
+				path => options.paths.push(path.into()),
+			}
+		}
+		if options.paths.is_empty() {
+			return Err("no test path given".into());
+		}
+		if options.update_known_failures && options.known_failures.is_none() {
+			return Err("--update-known-failures requires --known-failures".into());
+		}
+		if options.forks.is_empty() {
+			options.forks = Fork::ALL.to_vec();
+		}
+		Ok(options)
+	}
+}
+
+/// Number of passed and failed tests of a fork.
+#[derive(Default)]
+struct Tally {
+	passed: usize,
+	failed: usize,
+}
+
+fn main() {
+	let options = match Options::parse() {
+		Ok(options) => options,
+		Err(err) => {
+			eprintln!("{}\n\n{}", err, USAGE);
+			process::exit(2);
+		}
+	};
+
+	let known_failures: BTreeSet<String> = match &options.known_failures {
+		Some(path) if path.exists() => fs::read_to_string(path)
+			.unwrap_or_else(|err| panic!("cannot read {}: {}", path.display(), err))
+			.lines()
+			.map(str::trim)
+			.filter(|line| !line.is_empty() && !line.starts_with('#'))
+			.map(String::from)
+			.collect(),
+		_ => BTreeSet::new(),
+	};
+
+	let mut files = Vec::new();
+	for path in &options.paths {
+		collect_files(path, &mut files);
+	}
+	files.sort();
+
+	let mut tallies: BTreeMap<&'static str, Tally> = BTreeMap::new();
+	let mut failures = BTreeSet::new();
+	let mut regressions = Vec::new();
+	for file in &files {
+		let tests: BTreeMap<String, StateTest> = match fs::read_to_string(file)
+			.map_err(|err| err.to_string())
+			.and_then(|data| serde_json::from_str(&data).map_err(|err| err.to_string()))
+		{
+			Ok(tests) => tests,
+			Err(err) => {
+				eprintln!("skipping {}: {}", file.display(), err);
+				continue;
+			}
+		};
+
+		for (name, test) in &tests {
+			for fork in &options.forks {
+				for post in test.post.get(fork.name()).into_iter().flatten() {
+					let id = format!(
+						"{}::{}::d{}g{}v{}",
+						name,
+						fork.name(),
+						post.indexes.data,
+						post.indexes.gas,
+						post.indexes.value
+					);
+					let tally = tallies.entry(fork.name()).or_default();
+					match runner::run(test, *fork, post) {
+						Ok(()) => tally.passed += 1,
+						Err(reason) => {
+							tally.failed += 1;
+							if options.verbose {
+								println!("FAIL {}: {}", id, reason);
+							}
+							if !known_failures.contains(&id) {
+								regressions.push(id.clone());
+							}
+							failures.insert(id);
+						}
+					}
+				}
+			}
+		}
+	}
+
+	for (fork, tally) in &tallies {
+		let total = tally.passed + tally.failed;
+		println!(
+			"{}: {}/{} passed ({:.2}%)",
+			fork,
+			tally.passed,
+			total,
+			tally.passed as f64 * 100.0 / total as f64
+		);
+	}
+
+	if options.update_known_failures {
+		let path = options
+			.known_failures
+			.expect("checked when parsing options; qed");
+		// The comments heading the file are kept.
+		let header: String = fs::read_to_string(&path)
+			.unwrap_or_default()
+			.lines()
+			.take_while(|line| line.starts_with('#') || line.trim().is_empty())
+			.map(|line| format!("{}\n", line))
+			.collect();
+		let content: String = header
+			+ &failures
+				.iter()
+				.map(|id| format!("{}\n", id))
+				.collect::<String>();
+		fs::write(&path, content)
+			.unwrap_or_else(|err| panic!("cannot write {}: {}", path.display(), err));
+		println!(
+			"{} known failures written to {}",
+			failures.len(),
+			path.display()
+		);
+	} else if !regressions.is_empty() {
+		println!("{} tests failed unexpectedly:", regressions.len());
+		for id in &regressions {
+			println!("  {}", id);
+		}
+		process::exit(1);
+	}
+}
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) {
+	if path.is_dir() {
+		let entries = fs::read_dir(path)
+			.unwrap_or_else(|err| panic!("cannot read {}: {}", path.display(), err));
+		for entry in entries.flatten() {
+			collect_files(&entry.path(), files);
+		}
+	} else if path
+		.extension()
+		.map_or(false, |extension| extension == "json")
+	{
+		files.push(path.to_path_buf());
+	}
+}
@@ -0,0 +1,234 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Execution of a state test transaction variant against the EVM pallet.
+
+use frame_support::traits::Currency;
+use pallet_evm::{Log, Pallet as EVM, Runner as _};
+use sha3::{Digest, Keccak256};
+use sp_core::{H160, H256, U256};
+
+use crate::{
+	mock::{new_test_ext, BaseFee, BlockGasLimit, Coinbase, Test},
+	state,
+	types::{self, PostState, StateTest},
+};
+
+/// The forks whose state tests can be run, from the configurations of the EVM.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Fork {
+	Istanbul,
+	Berlin,
+	London,
+}
+
+impl Fork {
+	pub const ALL: [Fork; 3] = [Fork::Istanbul, Fork::Berlin, Fork::London];
+
+	/// Name of the fork in the `post` section of the state tests.
+	pub fn name(&self) -> &'static str {
+		match self {
+			Fork::Istanbul => "Istanbul",
+			Fork::Berlin => "Berlin",
+			Fork::London => "London",
+		}
+	}
+
+	pub fn from_name(name: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|fork| fork.name() == name)
+	}
+
+	fn config(&self) -> evm::Config {
+		match self {
+			Fork::Istanbul => evm::Config::istanbul(),
+			Fork::Berlin => evm::Config::berlin(),
+			Fork::London => evm::Config::london(),
+		}
+	}
+}
+
+/// Executes the variant of the test transaction selected by `post` and checks the resulting
+/// state and logs against it.
+pub fn run(test: &StateTest, fork: Fork, post: &PostState) -> Result<(), String> {
+	let expected_root = types::h256(&post.hash)?;
+	let expected_logs = types::h256(&post.logs)?;
+
+	new_test_ext().execute_with(|| {
+		set_up(test)?;
+		let logs = execute(test, fork, post)?;
+
+		let root = state::state_root();
+		if root != expected_root {
+			return Err(format!(
+				"state root {:?}, expected {:?}",
+				root, expected_root
+			));
+		}
+		let logs_hash = state::logs_hash(&logs);
+		if logs_hash != expected_logs {
+			return Err(format!(
+				"logs hash {:?}, expected {:?}",
+				logs_hash, expected_logs
+			));
+		}
+		Ok(())
+	})
+}
+
+/// Sets the block environment and the pre state of the test.
+fn set_up(test: &StateTest) -> Result<(), String> {
+	let env = &test.env;
+	frame_system::Pallet::<Test>::set_block_number(types::u256(&env.current_number)?.low_u64());
+	pallet_timestamp::Pallet::<Test>::set_timestamp(
+		types::u256(&env.current_timestamp)?
+			.low_u64()
+			.saturating_mul(1000),
+	);
+	BaseFee::set(match &env.current_base_fee {
+		Some(base_fee) => types::u256(base_fee)?,
+		None => U256::zero(),
+	});
+	BlockGasLimit::set(types::u256(&env.current_gas_limit)?);
+	Coinbase::set(types::h160(&env.current_coinbase)?);
+
+	for (address, account) in &test.pre {
+		let address = types::h160(address)?;
+
+		let nonce = types::u256(&account.nonce)?;
+		if nonce > U256::from(u64::MAX) {
+			return Err(format!(
+				"nonce of {:?} overflows the account nonce",
+				address
+			));
+		}
+		frame_system::Account::<Test>::mutate(address, |info| info.nonce = nonce.low_u64());
+
+		let balance = types::u256(&account.balance)?;
+		if balance > U256::from(u128::MAX) {
+			return Err(format!(
+				"balance of {:?} overflows the account balance",
+				address
+			));
+		}
+		if !balance.is_zero() {
+			let _ = pallet_balances::Pallet::<Test>::deposit_creating(&address, balance.low_u128());
+		}
+
+		EVM::<Test>::create_account(address, types::bytes(&account.code)?);
+		for (index, value) in &account.storage {
+			EVM::<Test>::set_account_storage(address, types::h256(index)?, types::h256(value)?);
+		}
+	}
+	Ok(())
+}
+
+/// Executes the transaction variant, returning its logs. A transaction rejected by the runner
+/// leaves the state untouched and has no logs, as an invalid Ethereum transaction.
+fn execute(test: &StateTest, fork: Fork, post: &PostState) -> Result<Vec<Log>, String> {
+	let transaction = &test.transaction;
+	let indexes = post.indexes;
+	let variant = |values: &[String], index: usize| {
+		values
+			.get(index)
+			.cloned()
+			.ok_or_else(|| format!("missing transaction variant {}", index))
+	};
+
+	let source = sender(&transaction.secret_key)?;
+	let input = types::bytes(&variant(&transaction.data, indexes.data)?)?;
+	let value = types::u256(&variant(&transaction.value, indexes.value)?)?;
+	let gas_limit = types::u256(&variant(&transaction.gas_limit, indexes.gas)?)?;
+	if gas_limit > U256::from(u64::MAX) {
+		return Err("gas limit overflows 64 bits".into());
+	}
+	let nonce = types::u256(&transaction.nonce)?;
+
+	// Legacy transactions pay their whole gas price, above the base fee, as a tip.
+	let (max_fee_per_gas, max_priority_fee_per_gas) = match (
+		&transaction.gas_price,
+		&transaction.max_fee_per_gas,
+		&transaction.max_priority_fee_per_gas,
+	) {
+		(Some(gas_price), None, None) => {
+			let gas_price = types::u256(gas_price)?;
+			(gas_price, gas_price)
+		}
+		(None, Some(max_fee_per_gas), Some(max_priority_fee_per_gas)) => (
+			types::u256(max_fee_per_gas)?,
+			types::u256(max_priority_fee_per_gas)?,
+		),
+		_ => return Err("invalid combination of fee fields".into()),
+	};
+
+	let mut access_list = Vec::new();
+	if let Some(Some(items)) = transaction
+		.access_lists
+		.as_ref()
+		.and_then(|access_lists| access_lists.get(indexes.data))
+	{
+		for item in items {
+			let storage_keys = item
+				.storage_keys
+				.iter()
+				.map(|key| types::h256(key))
+				.collect::<Result<Vec<_>, _>>()?;
+			access_list.push((types::h160(&item.address)?, storage_keys));
+		}
+	}
+
+	let config = fork.config();
+	let logs = if transaction.to.is_empty() {
+		<Test as pallet_evm::Config>::Runner::create(
+			source,
+			input,
+			value,
+			gas_limit.low_u64(),
+			Some(max_fee_per_gas),
+			Some(max_priority_fee_per_gas),
+			Some(nonce),
+			access_list,
+			true,
+			&config,
+		)
+		.map(|info| info.logs)
+	} else {
+		<Test as pallet_evm::Config>::Runner::call(
+			source,
+			types::h160(&transaction.to)?,
+			input,
+			value,
+			gas_limit.low_u64(),
+			Some(max_fee_per_gas),
+			Some(max_priority_fee_per_gas),
+			Some(nonce),
+			access_list,
+			true,
+			&config,
+		)
+		.map(|info| info.logs)
+	};
+	Ok(logs.unwrap_or_default())
+}
+
+/// Address of the account of a secret key.
+fn sender(secret_key: &str) -> Result<H160, String> {
+	let secret_key = libsecp256k1::SecretKey::parse_slice(&types::bytes(secret_key)?)
+		.map_err(|err| format!("invalid secret key: {:?}", err))?;
+	let public_key = libsecp256k1::PublicKey::from_secret_key(&secret_key).serialize();
+	let hash = H256::from_slice(Keccak256::digest(&public_key[1..]).as_slice());
+	Ok(H160::from(hash))
+}
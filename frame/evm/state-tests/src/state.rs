@@ -0,0 +1,134 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ethereum commitments to the EVM pallet state, compared to the expected post states.
+
+use std::collections::BTreeSet;
+
+use ethereum::util::sec_trie_root;
+use fp_storage::EVM_ACCOUNT_STORAGE_CHILD_PREFIX;
+use frame_support::storage::ChildTriePrefixIterator;
+use pallet_evm::{AccountCodeHash, Log, Pallet as EVM};
+use rlp::RlpStream;
+use sha3::{Digest, Keccak256};
+use sp_core::{storage::ChildInfo, H160, H256, U256};
+
+use crate::mock::Test;
+
+/// Addresses of the accounts with a storage child trie.
+fn storage_addresses() -> Vec<H160> {
+	let prefix = ChildInfo::new_default(EVM_ACCOUNT_STORAGE_CHILD_PREFIX)
+		.prefixed_storage_key()
+		.into_inner();
+	let mut addresses = Vec::new();
+	let mut key = prefix.clone();
+	while let Some(next) = sp_io::storage::next_key(&key) {
+		if !next.starts_with(&prefix) {
+			break;
+		}
+		addresses.push(H160::from_slice(&next[prefix.len()..]));
+		key = next;
+	}
+	addresses
+}
+
+fn keccak(data: &[u8]) -> H256 {
+	H256::from_slice(Keccak256::digest(data).as_slice())
+}
+
+/// Root of the Ethereum state trie of the accounts of the EVM pallet.
+///
+/// Empty accounts are left out of the trie, as they are removed by Ethereum since EIP-161.
+pub fn state_root() -> H256 {
+	let mut addresses: BTreeSet<H160> = frame_system::Account::<Test>::iter_keys().collect();
+	addresses.extend(AccountCodeHash::<Test>::iter_keys());
+	addresses.extend(storage_addresses());
+
+	let accounts = addresses.into_iter().filter_map(|address| {
+		let (account, _) = EVM::<Test>::account_basic(&address);
+		let code = EVM::<Test>::account_codes(address);
+		let storage: Vec<(H256, H256)> = ChildTriePrefixIterator::<(Vec<u8>, H256)>::with_prefix(
+			&EVM::<Test>::account_storage_child_info(&address),
+			&[],
+		)
+		.map(|(index, value)| (H256::from_slice(&index), value))
+		.filter(|(_, value)| !value.is_zero())
+		.collect();
+		if account.nonce.is_zero()
+			&& account.balance.is_zero()
+			&& code.is_empty()
+			&& storage.is_empty()
+		{
+			return None;
+		}
+
+		let storage_root =
+			sec_trie_root(storage.into_iter().map(|(index, value)| {
+				(index, rlp::encode(&U256::from_big_endian(value.as_bytes())))
+			}));
+		let mut stream = RlpStream::new_list(4);
+		stream.append(&account.nonce);
+		stream.append(&account.balance);
+		stream.append(&storage_root);
+		stream.append(&keccak(&code));
+		Some((address, stream.out()))
+	});
+	sec_trie_root(accounts)
+}
+
+/// Keccak hash of the RLP encoded list of the logs, as committed to by the state tests.
+pub fn logs_hash(logs: &[Log]) -> H256 {
+	let mut stream = RlpStream::new_list(logs.len());
+	for log in logs {
+		stream.begin_list(3);
+		stream.append(&log.address);
+		stream.append_list(&log.topics);
+		stream.append(&log.data);
+	}
+	keccak(&stream.out())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn empty_state_has_the_empty_trie_root() {
+		crate::mock::new_test_ext().execute_with(|| {
+			assert_eq!(
+				state_root(),
+				H256::from_slice(
+					&hex::decode(
+						"56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"
+					)
+					.unwrap()
+				)
+			);
+		});
+	}
+
+	#[test]
+	fn no_logs_hash_is_the_hash_of_the_empty_list() {
+		assert_eq!(
+			logs_hash(&[]),
+			H256::from_slice(
+				&hex::decode("1dcc4de8dec75d7aab85b567ae6cd41ad312451b948a7413f0a142fd40d49347")
+					.unwrap()
+			)
+		);
+	}
+}
@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Format of the filled general state tests of the Ethereum tests repository.
+//!
+//! Values are kept as the hex strings of the fixtures and parsed when a test is run, so that
+//! a malformed value only fails the test using it.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use sp_core::{H160, H256, U256};
+
+/// A general state test: a transaction executed on a pre state, with the expected post
+/// state of each of its variants, by fork.
+#[derive(Debug, Deserialize)]
+pub struct StateTest {
+	pub env: Env,
+	pub pre: BTreeMap<String, Account>,
+	pub transaction: Transaction,
+	pub post: BTreeMap<String, Vec<PostState>>,
+}
+
+/// The block the transaction is executed in.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Env {
+	pub current_coinbase: String,
+	pub current_gas_limit: String,
+	pub current_number: String,
+	pub current_timestamp: String,
+	/// Only set from London.
+	pub current_base_fee: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Account {
+	pub balance: String,
+	pub code: String,
+	pub nonce: String,
+	pub storage: BTreeMap<String, String>,
+}
+
+/// The transaction, with the candidate data, gas limit and value of its variants.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transaction {
+	pub data: Vec<String>,
+	pub gas_limit: Vec<String>,
+	pub value: Vec<String>,
+	pub gas_price: Option<String>,
+	pub max_fee_per_gas: Option<String>,
+	pub max_priority_fee_per_gas: Option<String>,
+	pub nonce: String,
+	pub secret_key: String,
+	/// Empty for a contract creation.
+	pub to: String,
+	/// Access list of each data variant, for EIP-2930 and EIP-1559 transactions.
+	pub access_lists: Option<Vec<Option<Vec<AccessListItem>>>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListItem {
+	pub address: String,
+	pub storage_keys: Vec<String>,
+}
+
+/// Expected result of a transaction variant.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostState {
+	/// State root after the transaction.
+	pub hash: String,
+	/// Keccak hash of the RLP encoded list of the logs of the transaction.
+	pub logs: String,
+	pub indexes: Indexes,
+	/// Set when the transaction is invalid and must not be executed.
+	pub expect_exception: Option<String>,
+}
+
+/// Indexes of the data, gas limit and value of a transaction variant.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct Indexes {
+	pub data: usize,
+	pub gas: usize,
+	pub value: usize,
+}
+
+pub fn bytes(value: &str) -> Result<Vec<u8>, String> {
+	let value = value.trim_start_matches("0x");
+	let padded;
+	let value = if value.len() % 2 == 1 {
+		padded = format!("0{}", value);
+		&padded
+	} else {
+		value
+	};
+	hex::decode(value).map_err(|err| format!("invalid hex {:?}: {}", value, err))
+}
+
+pub fn u256(value: &str) -> Result<U256, String> {
+	let bytes = bytes(value)?;
+	if bytes.len() > 32 {
+		return Err(format!("{} overflows 256 bits", value));
+	}
+	Ok(U256::from_big_endian(&bytes))
+}
+
+pub fn h160(value: &str) -> Result<H160, String> {
+	let bytes = bytes(value)?;
+	if bytes.len() != 20 {
+		return Err(format!("invalid address {}", value));
+	}
+	Ok(H160::from_slice(&bytes))
+}
+
+/// Parses a 32 bytes word, such as a storage key, which the fixtures may write without its
+/// leading zeros.
+pub fn h256(value: &str) -> Result<H256, String> {
+	let mut word = [0u8; 32];
+	u256(value)?.to_big_endian(&mut word);
+	Ok(H256(word))
+}
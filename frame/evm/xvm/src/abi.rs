@@ -0,0 +1,146 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Solidity ABI encoding of the [`WasmCall`](crate::WasmCall) precompile.
+
+use sp_core::U256;
+use sp_std::vec::Vec;
+
+/// Signature of the function of the precompile.
+pub const CALL_SIGNATURE: &[u8] = b"call(bytes32,uint256,uint64,bytes)";
+
+/// Arguments of `call(bytes32 contract, uint256 value, uint64 gasLimit, bytes input)`.
+#[derive(Debug, Eq, PartialEq)]
+pub struct CallArguments {
+	pub contract: [u8; 32],
+	pub value: U256,
+	pub gas_limit: u64,
+	pub input: Vec<u8>,
+}
+
+fn word(data: &[u8], index: usize) -> Option<&[u8]> {
+	data.get(index * 32..(index + 1) * 32)
+}
+
+fn word_as_usize(data: &[u8], index: usize) -> Option<usize> {
+	let word = U256::from_big_endian(word(data, index)?);
+	if word > U256::from(u32::MAX) {
+		return None;
+	}
+	Some(word.low_u32() as usize)
+}
+
+/// Decodes the input of the precompile, selector included.
+pub fn decode_call(input: &[u8]) -> Option<CallArguments> {
+	let selector = &sp_io::hashing::keccak_256(CALL_SIGNATURE)[..4];
+	if input.get(..4)? != selector {
+		return None;
+	}
+	let arguments = &input[4..];
+
+	let mut contract = [0u8; 32];
+	contract.copy_from_slice(word(arguments, 0)?);
+	let value = U256::from_big_endian(word(arguments, 1)?);
+	let gas_limit = U256::from_big_endian(word(arguments, 2)?);
+	if gas_limit > U256::from(u64::MAX) {
+		return None;
+	}
+
+	let offset = word_as_usize(arguments, 3)?;
+	let length = word_as_usize(arguments.get(offset..)?, 0)?;
+	let input = arguments.get(offset + 32..)?.get(..length)?.to_vec();
+
+	Some(CallArguments {
+		contract,
+		value,
+		gas_limit: gas_limit.low_u64(),
+		input,
+	})
+}
+
+/// Encodes `data` as the single `bytes` value returned by the precompile.
+pub fn encode_bytes(data: &[u8]) -> Vec<u8> {
+	let padded_length = (data.len() + 31) / 32 * 32;
+	let mut output = Vec::with_capacity(64 + padded_length);
+
+	let mut word = [0u8; 32];
+	U256::from(32).to_big_endian(&mut word);
+	output.extend_from_slice(&word);
+	U256::from(data.len()).to_big_endian(&mut word);
+	output.extend_from_slice(&word);
+	output.extend_from_slice(data);
+	output.resize(64 + padded_length, 0);
+	output
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn encode_call(arguments: &CallArguments) -> Vec<u8> {
+		let mut input = sp_io::hashing::keccak_256(CALL_SIGNATURE)[..4].to_vec();
+		let mut word = [0u8; 32];
+		input.extend_from_slice(&arguments.contract);
+		arguments.value.to_big_endian(&mut word);
+		input.extend_from_slice(&word);
+		U256::from(arguments.gas_limit).to_big_endian(&mut word);
+		input.extend_from_slice(&word);
+		U256::from(128).to_big_endian(&mut word);
+		input.extend_from_slice(&word);
+		input.extend_from_slice(&encode_bytes(&arguments.input)[32..]);
+		input
+	}
+
+	#[test]
+	fn decodes_encoded_call() {
+		let arguments = CallArguments {
+			contract: [1; 32],
+			value: U256::from(1_000),
+			gas_limit: 50_000,
+			input: vec![2; 40],
+		};
+		assert_eq!(decode_call(&encode_call(&arguments)), Some(arguments));
+	}
+
+	#[test]
+	fn rejects_unknown_selector_and_truncated_input() {
+		let arguments = CallArguments {
+			contract: [1; 32],
+			value: U256::zero(),
+			gas_limit: 50_000,
+			input: vec![2; 40],
+		};
+		let mut input = encode_call(&arguments);
+
+		input[0] ^= 1;
+		assert_eq!(decode_call(&input), None);
+		input[0] ^= 1;
+
+		input.truncate(input.len() - 24);
+		assert_eq!(decode_call(&input), None);
+	}
+
+	#[test]
+	fn encodes_bytes_padded() {
+		let output = encode_bytes(&[0xff; 33]);
+		assert_eq!(output.len(), 32 * 4);
+		assert_eq!(U256::from_big_endian(&output[..32]), U256::from(32));
+		assert_eq!(U256::from_big_endian(&output[32..64]), U256::from(33));
+		assert_eq!(&output[64..97], &[0xff; 33][..]);
+		assert!(output[97..].iter().all(|byte| *byte == 0));
+	}
+}
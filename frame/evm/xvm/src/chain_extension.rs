@@ -0,0 +1,129 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::marker::PhantomData;
+
+use codec::Decode;
+use fp_evm::ExitReason;
+use frame_support::traits::{Currency, ExistenceRequirement};
+use pallet_contracts::chain_extension::{
+	ChainExtension, Environment, Ext, InitState, RetVal, SysConfig, UncheckedFrom,
+};
+use pallet_evm::{AddressMapping, GasWeightMapping, Runner};
+use sp_core::{H160, U256};
+use sp_runtime::DispatchError;
+use sp_std::vec::Vec;
+
+/// Function id of the EVM call in [`EvmCallExtension`].
+pub const EVM_CALL_FUNC_ID: u32 = 0;
+
+/// Input of the EVM call, SCALE encoded by the Wasm contract.
+#[derive(Decode)]
+pub struct EvmCallInput {
+	/// The EVM contract to call.
+	pub target: H160,
+	/// The value to transfer to the EVM contract.
+	pub value: u128,
+	/// The gas limit of the call, charged to the Wasm contract as weight.
+	pub gas_limit: u64,
+	/// The input of the call.
+	pub input: Vec<u8>,
+}
+
+/// Chain extension calling an EVM contract from a Wasm contract.
+///
+/// The EVM contract is called by the first 20 bytes of the account of the Wasm contract.
+/// The value is transferred beforehand to the account this address is mapped to, and
+/// transferred back if the call fails. The output of the call is written to the output
+/// buffer, and the extension returns `0` if the call succeeded, `1` otherwise.
+pub struct EvmCallExtension<T> {
+	_marker: PhantomData<T>,
+}
+
+impl<T> Default for EvmCallExtension<T> {
+	fn default() -> Self {
+		Self {
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<T> ChainExtension<T> for EvmCallExtension<T>
+where
+	T: pallet_evm::Config + pallet_contracts::Config,
+{
+	fn call<E>(func_id: u32, env: Environment<E, InitState>) -> Result<RetVal, DispatchError>
+	where
+		E: Ext<T = T>,
+		<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+	{
+		if func_id != EVM_CALL_FUNC_ID {
+			return Err(DispatchError::Other("unknown chain extension function"));
+		}
+
+		let mut env = env.buf_in_buf_out();
+		let in_len = env.in_len();
+		let call: EvmCallInput = env.read_as_unbounded(in_len)?;
+		let charged = env.charge_weight(T::GasWeightMapping::gas_to_weight(call.gas_limit))?;
+
+		let contract = env.ext().address().clone();
+		let source = H160::from_slice(&contract.as_ref()[..20]);
+		let mapped = <T as pallet_evm::Config>::AddressMapping::into_account_id(source);
+		let value = call
+			.value
+			.try_into()
+			.map_err(|_| DispatchError::Other("value overflow"))?;
+		<T as pallet_contracts::Config>::Currency::transfer(
+			&contract,
+			&mapped,
+			value,
+			ExistenceRequirement::KeepAlive,
+		)?;
+
+		let info = T::Runner::call(
+			source,
+			call.target,
+			call.input,
+			U256::from(call.value),
+			call.gas_limit,
+			None,
+			None,
+			None,
+			Vec::new(),
+			false,
+			&pallet_evm::Pallet::<T>::evm_config(),
+		)
+		.map_err(|e| e.error.into())?;
+		env.adjust_weight(
+			charged,
+			T::GasWeightMapping::gas_to_weight(info.used_gas.low_u64()),
+		);
+
+		let succeeded = matches!(info.exit_reason, ExitReason::Succeed(_));
+		if !succeeded {
+			<T as pallet_contracts::Config>::Currency::transfer(
+				&mapped,
+				&contract,
+				value,
+				ExistenceRequirement::AllowDeath,
+			)?;
+		}
+		env.write(&info.value, false, None)?;
+
+		Ok(RetVal::Converging(if succeeded { 0 } else { 1 }))
+	}
+}
@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Calls between EVM contracts and the Wasm contracts of `pallet-contracts`, for runtimes
+//! running both VMs.
+//!
+//! - [`WasmCall`] is a precompile calling a Wasm contract from the EVM.
+//! - [`EvmCallExtension`] is a chain extension calling an EVM contract from a Wasm contract.
+//!
+//! Both forward at most the gas, or weight, left to the caller, converted with the
+//! `GasWeightMapping` of the EVM pallet, and charge the caller for what the callee used.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod abi;
+mod chain_extension;
+mod precompile;
+
+pub use self::{
+	chain_extension::{EvmCallExtension, EvmCallInput, EVM_CALL_FUNC_ID},
+	precompile::WasmCall,
+};
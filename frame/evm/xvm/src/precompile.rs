@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::marker::PhantomData;
+
+use codec::Decode;
+use fp_evm::{
+	Context, ExitError, ExitRevert, ExitSucceed, Precompile, PrecompileFailure, PrecompileOutput,
+	PrecompileResult,
+};
+use frame_support::traits::Currency;
+use pallet_contracts_primitives::ReturnFlags;
+use pallet_evm::{AddressMapping, GasWeightMapping};
+use sp_core::U256;
+
+use crate::abi;
+
+type ContractsBalanceOf<T> = <<T as pallet_contracts::Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::Balance;
+
+/// Precompile calling a Wasm contract of `pallet-contracts` on behalf of the EVM caller.
+///
+/// Its only function is `call(bytes32 contract, uint256 value, uint64 gasLimit, bytes input)`,
+/// returning the output of the contract as `bytes`. The value is transferred from the
+/// account of the caller, and the gas limit is bounded by the gas left. A reverted Wasm
+/// contract reverts the precompile with its output.
+pub struct WasmCall<T> {
+	_marker: PhantomData<T>,
+}
+
+fn error(message: &'static str) -> PrecompileFailure {
+	PrecompileFailure::Error {
+		exit_status: ExitError::Other(message.into()),
+	}
+}
+
+impl<T> Precompile for WasmCall<T>
+where
+	T: pallet_evm::Config + pallet_contracts::Config,
+{
+	fn execute(
+		input: &[u8],
+		target_gas: Option<u64>,
+		context: &Context,
+		is_static: bool,
+	) -> PrecompileResult {
+		if is_static {
+			return Err(error("cannot call a Wasm contract in a static context"));
+		}
+		// Value sent to the precompile itself would be stuck at its address.
+		if !context.apparent_value.is_zero() {
+			return Err(error("value must be given as an argument"));
+		}
+
+		let arguments = abi::decode_call(input).ok_or_else(|| error("invalid input"))?;
+		let dest = T::AccountId::decode(&mut &arguments.contract[..])
+			.map_err(|_| error("invalid contract account"))?;
+		if arguments.value > U256::from(u128::MAX) {
+			return Err(error("value overflow"));
+		}
+		let value: ContractsBalanceOf<T> = arguments
+			.value
+			.low_u128()
+			.try_into()
+			.map_err(|_| error("value overflow"))?;
+		let gas_limit = match target_gas {
+			Some(target_gas) => arguments.gas_limit.min(target_gas),
+			None => arguments.gas_limit,
+		};
+
+		let origin = <T as pallet_evm::Config>::AddressMapping::into_account_id(context.caller);
+		let result = pallet_contracts::Pallet::<T>::bare_call(
+			origin,
+			dest,
+			value,
+			T::GasWeightMapping::gas_to_weight(gas_limit),
+			None,
+			arguments.input,
+			false,
+		);
+		let cost = T::GasWeightMapping::weight_to_gas(result.gas_consumed).min(gas_limit);
+
+		match result.result {
+			Ok(value) if !value.flags.contains(ReturnFlags::REVERT) => Ok(PrecompileOutput {
+				exit_status: ExitSucceed::Returned,
+				cost,
+				output: abi::encode_bytes(&value.data.0),
+				logs: Default::default(),
+			}),
+			Ok(value) => Err(PrecompileFailure::Revert {
+				exit_status: ExitRevert::Reverted,
+				output: value.data.0,
+				cost,
+			}),
+			Err(_) => Err(PrecompileFailure::Revert {
+				exit_status: ExitRevert::Reverted,
+				output: Default::default(),
+				cost,
+			}),
+		}
+	}
+}
@@ -0,0 +1,275 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Gas subsidy pallet
+//!
+//! A registry of gas subsidies of EVM contracts. A subsidy pays a share of the fees of the
+//! calls to its contract from funds deposited for it, so that a dapp can onboard users who
+//! don't hold the native currency yet, without a paymaster contract.
+//!
+//! The deployer of a contract registers its subsidy, proving it deployed the contract as for
+//! the contract metadata pallet, and is the owner of the subsidy. Anyone can deposit funds for
+//! a subsidy, and only its owner can withdraw them, to its account. The funds are held by the
+//! account of the [`Pallet::subsidy_address`] of the contract, which has no known private key.
+//!
+//! The runtime applies the subsidies to the fees of the EVM pallet by calling
+//! [`Pallet::fee_sponsor`] and [`Pallet::on_fee_sponsored`] from its `Config::fee_sponsor` and
+//! `Config::on_fee_sponsored`. A subsidy which can't pay its whole share of the fees of a call
+//! pays nothing, and the caller pays all of them. A subsidy only pays its share of the base
+//! fee, the priority fee is always paid by the caller.
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use codec::{Decode, Encode};
+use frame_support::traits::{Currency, ExistenceRequirement};
+use scale_info::TypeInfo;
+use sp_core::{H160, H256, U256};
+use sp_runtime::{traits::Zero, DispatchError, Permill, RuntimeDebug};
+
+use pallet_contract_metadata::Deployment;
+use pallet_evm::{AddressMapping, BalanceOf, EnsureAddressOrigin};
+
+pub use self::pallet::*;
+
+/// Prefix of the preimage of the subsidy address of a contract.
+pub const SUBSIDY_ADDRESS_PREFIX: &[u8] = b"frontier-gas-subsidy";
+
+/// Gas subsidy of a contract.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode, TypeInfo)]
+pub struct Subsidy {
+	/// Deployer of the contract, which registered the subsidy.
+	pub owner: H160,
+	/// Share of the fees of the calls to the contract paid by the subsidy.
+	pub share: Permill,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	/// The owners of the subsidies are authenticated with the `DeployerOrigin` of the contract
+	/// metadata pallet.
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_contract_metadata::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Register the subsidy of a contract deployed by `deployer`, paying `share` of the fees
+		/// of its callers. The deployer owns the subsidy.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1))]
+		pub fn register(
+			origin: OriginFor<T>,
+			contract: H160,
+			deployer: H160,
+			deployment: Deployment,
+			share: Permill,
+		) -> DispatchResult {
+			<T as pallet_contract_metadata::Config>::DeployerOrigin::ensure_address_origin(
+				&deployer, origin,
+			)?;
+			ensure!(
+				!<Subsidies<T>>::contains_key(contract),
+				Error::<T>::AlreadyRegistered
+			);
+			ensure!(
+				pallet_contract_metadata::Pallet::<T>::deployed_address(deployer, &deployment)
+					== contract,
+				Error::<T>::NotDeployer
+			);
+			ensure!(
+				pallet_evm::Pallet::<T>::has_code(&contract),
+				Error::<T>::NotAContract
+			);
+
+			<Subsidies<T>>::insert(
+				contract,
+				Subsidy {
+					owner: deployer,
+					share,
+				},
+			);
+			Self::deposit_event(Event::Registered(contract, deployer, share));
+			Ok(())
+		}
+
+		/// Change the share of the fees paid by the subsidy of a contract. Only its owner can
+		/// change it.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+		pub fn set_share(origin: OriginFor<T>, contract: H160, share: Permill) -> DispatchResult {
+			let subsidy = Self::owned_subsidy(origin, contract)?;
+			<Subsidies<T>>::insert(contract, Subsidy { share, ..subsidy });
+			Self::deposit_event(Event::ShareSet(contract, share));
+			Ok(())
+		}
+
+		/// Deposit `amount` to the funds of the subsidy of a contract.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 2))]
+		pub fn deposit(
+			origin: OriginFor<T>,
+			contract: H160,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				<Subsidies<T>>::contains_key(contract),
+				Error::<T>::NotRegistered
+			);
+
+			<T as pallet_evm::Config>::Currency::transfer(
+				&who,
+				&Self::subsidy_account(&contract),
+				amount,
+				ExistenceRequirement::KeepAlive,
+			)?;
+			Self::deposit_event(Event::Deposited(contract, who, amount));
+			Ok(())
+		}
+
+		/// Withdraw `amount` from the funds of the subsidy of a contract, to the account of its
+		/// owner. Only its owner can withdraw them.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 2))]
+		pub fn withdraw(
+			origin: OriginFor<T>,
+			contract: H160,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let subsidy = Self::owned_subsidy(origin, contract)?;
+
+			<T as pallet_evm::Config>::Currency::transfer(
+				&Self::subsidy_account(&contract),
+				&T::AddressMapping::into_account_id(subsidy.owner),
+				amount,
+				ExistenceRequirement::AllowDeath,
+			)?;
+			Self::deposit_event(Event::Withdrawn(contract, amount));
+			Ok(())
+		}
+
+		/// Unregister the subsidy of a contract, withdrawing its remaining funds to the account
+		/// of its owner. Only its owner can unregister it.
+		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 3))]
+		pub fn unregister(origin: OriginFor<T>, contract: H160) -> DispatchResult {
+			let subsidy = Self::owned_subsidy(origin, contract)?;
+
+			let account = Self::subsidy_account(&contract);
+			let funds = <T as pallet_evm::Config>::Currency::free_balance(&account);
+			if !funds.is_zero() {
+				<T as pallet_evm::Config>::Currency::transfer(
+					&account,
+					&T::AddressMapping::into_account_id(subsidy.owner),
+					funds,
+					ExistenceRequirement::AllowDeath,
+				)?;
+				Self::deposit_event(Event::Withdrawn(contract, funds));
+			}
+			<Subsidies<T>>::remove(contract);
+			Self::deposit_event(Event::Unregistered(contract));
+			Ok(())
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The subsidy of a \[contract\] has been registered by its \[owner\], paying a
+		/// \[share\] of the fees.
+		Registered(H160, H160, Permill),
+		/// The subsidy of a \[contract\] now pays another \[share\] of the fees.
+		ShareSet(H160, Permill),
+		/// The subsidy of a \[contract\] received a deposit from an \[account\] of an
+		/// \[amount\].
+		Deposited(H160, T::AccountId, BalanceOf<T>),
+		/// The owner of the subsidy of a \[contract\] withdrew an \[amount\].
+		Withdrawn(H160, BalanceOf<T>),
+		/// The subsidy of a \[contract\] has been unregistered.
+		Unregistered(H160),
+		/// The subsidy of a \[contract\] paid for a \[caller\] a \[fee\] of its call.
+		Subsidized(H160, H160, U256),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The contract already has a subsidy.
+		AlreadyRegistered,
+		/// The deployment doesn't give the address of the contract.
+		NotDeployer,
+		/// There is no code at the address of the contract.
+		NotAContract,
+		/// The contract has no subsidy.
+		NotRegistered,
+	}
+
+	/// Subsidy of each contract.
+	#[pallet::storage]
+	#[pallet::getter(fn subsidy)]
+	pub type Subsidies<T: Config> = StorageMap<_, Blake2_128Concat, H160, Subsidy, OptionQuery>;
+}
+
+impl<T: Config> Pallet<T> {
+	/// Address holding the funds of the subsidy of a contract.
+	pub fn subsidy_address(contract: &H160) -> H160 {
+		H256::from(sp_io::hashing::keccak_256(
+			&[SUBSIDY_ADDRESS_PREFIX, contract.as_bytes()].concat(),
+		))
+		.into()
+	}
+
+	/// Account holding the funds of the subsidy of a contract.
+	pub fn subsidy_account(contract: &H160) -> T::AccountId {
+		T::AddressMapping::into_account_id(Self::subsidy_address(contract))
+	}
+
+	/// Sponsor of the calls to `target`, with the share of their fees it pays, for
+	/// `pallet_evm::Config::fee_sponsor`.
+	pub fn fee_sponsor(_source: &H160, target: &H160) -> Option<(H160, Permill)> {
+		<Subsidies<T>>::get(target)
+			.filter(|subsidy| !subsidy.share.is_zero())
+			.map(|subsidy| (Self::subsidy_address(target), subsidy.share))
+	}
+
+	/// Records the fees paid by the subsidy of `target`, for
+	/// `pallet_evm::Config::on_fee_sponsored`.
+	pub fn on_fee_sponsored(_sponsor: &H160, source: &H160, target: &H160, fee: U256) {
+		Self::deposit_event(Event::Subsidized(*target, *source, fee));
+	}
+
+	/// Subsidy of a contract, if `origin` is its owner.
+	fn owned_subsidy(origin: T::Origin, contract: H160) -> Result<Subsidy, DispatchError> {
+		let subsidy = <Subsidies<T>>::get(contract).ok_or(Error::<T>::NotRegistered)?;
+		<T as pallet_contract_metadata::Config>::DeployerOrigin::ensure_address_origin(
+			&subsidy.owner,
+			origin,
+		)?;
+		Ok(subsidy)
+	}
+}
@@ -0,0 +1,238 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::mock::*;
+
+use frame_support::{assert_noop, assert_ok};
+use std::str::FromStr;
+
+fn deployer() -> H160 {
+	H160::from_str("6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0").unwrap()
+}
+
+fn caller() -> H160 {
+	H160::repeat_byte(0x22)
+}
+
+/// Address of the contract created by `deployer` at nonce 1, with code.
+fn contract() -> H160 {
+	let contract = H160::from_str("343c43a37d37dff08ae8c4a11544c718abb4fcf8").unwrap();
+	EVM::create_account(contract, vec![0x00]);
+	contract
+}
+
+const DEPLOYMENT: Deployment = Deployment::Create {
+	nonce: U256([1, 0, 0, 0]),
+};
+
+/// Fee of a call to the contract, stopping right away.
+const CALL_FEE: u64 = 21_000 * 1_000_000_000;
+
+fn register(share: Permill) -> H160 {
+	let contract = contract();
+	assert_ok!(GasSubsidy::register(
+		Origin::signed(deployer()),
+		contract,
+		deployer(),
+		DEPLOYMENT,
+		share
+	));
+	contract
+}
+
+fn call(contract: H160) -> frame_support::dispatch::DispatchResultWithPostInfo {
+	call_with_tip(contract, 0)
+}
+
+fn call_with_tip(contract: H160, tip: u64) -> frame_support::dispatch::DispatchResultWithPostInfo {
+	EVM::call(
+		Origin::signed(caller()),
+		caller(),
+		contract,
+		Vec::new(),
+		U256::zero(),
+		21_000,
+		U256::from(1_000_000_000 + tip),
+		Some(U256::from(tip)),
+		None,
+		Vec::new(),
+	)
+}
+
+#[test]
+fn subsidy_pays_its_share_of_the_fees() {
+	new_test_ext().execute_with(|| {
+		let contract = register(Permill::from_percent(50));
+		let _ = Balances::deposit_creating(&deployer(), 2 * CALL_FEE);
+		assert_ok!(GasSubsidy::deposit(
+			Origin::signed(deployer()),
+			contract,
+			CALL_FEE
+		));
+		// The caller can only pay its share.
+		let _ = Balances::deposit_creating(&caller(), CALL_FEE / 2);
+
+		assert_ok!(call(contract));
+
+		assert_eq!(Balances::free_balance(&caller()), 0);
+		assert_eq!(
+			Balances::free_balance(&GasSubsidy::subsidy_account(&contract)),
+			CALL_FEE / 2
+		);
+		System::assert_has_event(crate::mock::Event::GasSubsidy(crate::Event::Subsidized(
+			contract,
+			caller(),
+			U256::from(CALL_FEE / 2),
+		)));
+	});
+}
+
+#[test]
+fn subsidy_does_not_pay_the_priority_fee() {
+	new_test_ext().execute_with(|| {
+		let contract = register(Permill::from_percent(50));
+		let _ = Balances::deposit_creating(&deployer(), 2 * CALL_FEE);
+		assert_ok!(GasSubsidy::deposit(
+			Origin::signed(deployer()),
+			contract,
+			CALL_FEE
+		));
+		// The caller pays its share of the base fee and the whole priority fee.
+		let _ = Balances::deposit_creating(&caller(), CALL_FEE / 2 + CALL_FEE);
+
+		assert_ok!(call_with_tip(contract, 1_000_000_000));
+
+		assert_eq!(Balances::free_balance(&caller()), 0);
+		assert_eq!(
+			Balances::free_balance(&GasSubsidy::subsidy_account(&contract)),
+			CALL_FEE / 2
+		);
+	});
+}
+
+#[test]
+fn caller_pays_the_fees_a_subsidy_cannot_pay() {
+	new_test_ext().execute_with(|| {
+		let contract = register(Permill::from_percent(50));
+		let _ = Balances::deposit_creating(&caller(), CALL_FEE);
+
+		assert_ok!(call(contract));
+
+		assert_eq!(Balances::free_balance(&caller()), 0);
+	});
+}
+
+#[test]
+fn owner_can_withdraw_and_unregister() {
+	new_test_ext().execute_with(|| {
+		let contract = register(Permill::from_percent(50));
+		let _ = Balances::deposit_creating(&caller(), 100);
+		assert_ok!(GasSubsidy::deposit(Origin::signed(caller()), contract, 100));
+
+		assert_ok!(GasSubsidy::withdraw(
+			Origin::signed(deployer()),
+			contract,
+			30
+		));
+		assert_eq!(Balances::free_balance(&deployer()), 30);
+
+		assert_ok!(GasSubsidy::set_share(
+			Origin::signed(deployer()),
+			contract,
+			Permill::from_percent(20)
+		));
+		assert_eq!(
+			GasSubsidy::subsidy(contract).map(|subsidy| subsidy.share),
+			Some(Permill::from_percent(20))
+		);
+
+		assert_ok!(GasSubsidy::unregister(Origin::signed(deployer()), contract));
+		assert_eq!(Balances::free_balance(&deployer()), 100);
+		assert_eq!(GasSubsidy::subsidy(contract), None);
+		assert_eq!(GasSubsidy::fee_sponsor(&caller(), &contract), None);
+	});
+}
+
+#[test]
+fn only_the_owner_manages_a_subsidy() {
+	new_test_ext().execute_with(|| {
+		let contract = register(Permill::from_percent(50));
+		let _ = Balances::deposit_creating(&caller(), 100);
+		assert_ok!(GasSubsidy::deposit(Origin::signed(caller()), contract, 100));
+
+		assert_noop!(
+			GasSubsidy::withdraw(Origin::signed(caller()), contract, 100),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			GasSubsidy::set_share(Origin::signed(caller()), contract, Permill::one()),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			GasSubsidy::unregister(Origin::signed(caller()), contract),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn only_the_deployer_registers_a_subsidy() {
+	new_test_ext().execute_with(|| {
+		let contract = contract();
+
+		assert_noop!(
+			GasSubsidy::register(
+				Origin::signed(caller()),
+				contract,
+				caller(),
+				DEPLOYMENT,
+				Permill::one()
+			),
+			Error::<Test>::NotDeployer
+		);
+		assert_ok!(GasSubsidy::register(
+			Origin::signed(deployer()),
+			contract,
+			deployer(),
+			DEPLOYMENT,
+			Permill::one()
+		));
+		assert_noop!(
+			GasSubsidy::register(
+				Origin::signed(deployer()),
+				contract,
+				deployer(),
+				DEPLOYMENT,
+				Permill::one()
+			),
+			Error::<Test>::AlreadyRegistered
+		);
+	});
+}
+
+#[test]
+fn deposits_require_a_subsidy() {
+	new_test_ext().execute_with(|| {
+		let _ = Balances::deposit_creating(&caller(), 100);
+
+		assert_noop!(
+			GasSubsidy::deposit(Origin::signed(caller()), contract(), 100),
+			Error::<Test>::NotRegistered
+		);
+	});
+}
@@ -0,0 +1,188 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use codec::{Decode, Encode};
+use evm::Config;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_core::{H160, H256};
+use sp_std::vec::Vec;
+
+/// Gas cost of the EVM configuration which a runtime can override, layered over the
+/// defaults of its hardfork.
+///
+/// The costs of the other opcodes are fixed by the EVM.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub enum GasParameter {
+	/// `EXTCODESIZE` and `EXTCODECOPY`.
+	ExtCode,
+	/// `EXTCODEHASH`.
+	ExtCodeHash,
+	/// `BALANCE`.
+	Balance,
+	/// `SLOAD`, or a warm `SLOAD` since EIP-2929.
+	Sload,
+	/// Cold `SLOAD`, since EIP-2929.
+	SloadCold,
+	/// `SSTORE` of a zero slot to a non-zero value.
+	SstoreSet,
+	/// Other `SSTORE`s changing the value of a slot.
+	SstoreReset,
+	/// Refund of an `SSTORE` clearing a slot.
+	RefundSstoreClears,
+	/// `SELFDESTRUCT`.
+	Suicide,
+	/// Additional cost of a `SELFDESTRUCT` to a new account.
+	SuicideNewAccount,
+	/// `CALL` and the other calls.
+	Call,
+	/// `EXP`, per byte of the exponent.
+	ExpByte,
+	/// Cold account access, since EIP-2929.
+	AccountAccessCold,
+	/// Warm storage read, since EIP-2929.
+	StorageReadWarm,
+}
+
+impl GasParameter {
+	/// All the parameters.
+	pub const ALL: [GasParameter; 14] = [
+		GasParameter::ExtCode,
+		GasParameter::ExtCodeHash,
+		GasParameter::Balance,
+		GasParameter::Sload,
+		GasParameter::SloadCold,
+		GasParameter::SstoreSet,
+		GasParameter::SstoreReset,
+		GasParameter::RefundSstoreClears,
+		GasParameter::Suicide,
+		GasParameter::SuicideNewAccount,
+		GasParameter::Call,
+		GasParameter::ExpByte,
+		GasParameter::AccountAccessCold,
+		GasParameter::StorageReadWarm,
+	];
+
+	/// Cost of the parameter in `config`.
+	pub fn cost(&self, config: &Config) -> u64 {
+		match self {
+			GasParameter::ExtCode => config.gas_ext_code,
+			GasParameter::ExtCodeHash => config.gas_ext_code_hash,
+			GasParameter::Balance => config.gas_balance,
+			GasParameter::Sload => config.gas_sload,
+			GasParameter::SloadCold => config.gas_sload_cold,
+			GasParameter::SstoreSet => config.gas_sstore_set,
+			GasParameter::SstoreReset => config.gas_sstore_reset,
+			GasParameter::RefundSstoreClears => config.refund_sstore_clears as u64,
+			GasParameter::Suicide => config.gas_suicide,
+			GasParameter::SuicideNewAccount => config.gas_suicide_new_account,
+			GasParameter::Call => config.gas_call,
+			GasParameter::ExpByte => config.gas_expbyte,
+			GasParameter::AccountAccessCold => config.gas_account_access_cold,
+			GasParameter::StorageReadWarm => config.gas_storage_read_warm,
+		}
+	}
+
+	/// Sets the cost of the parameter in `config`.
+	pub fn set_cost(&self, config: &mut Config, cost: u64) {
+		match self {
+			GasParameter::ExtCode => config.gas_ext_code = cost,
+			GasParameter::ExtCodeHash => config.gas_ext_code_hash = cost,
+			GasParameter::Balance => config.gas_balance = cost,
+			GasParameter::Sload => config.gas_sload = cost,
+			GasParameter::SloadCold => config.gas_sload_cold = cost,
+			GasParameter::SstoreSet => config.gas_sstore_set = cost,
+			GasParameter::SstoreReset => config.gas_sstore_reset = cost,
+			GasParameter::RefundSstoreClears => {
+				config.refund_sstore_clears = cost.min(i64::MAX as u64) as i64
+			}
+			GasParameter::Suicide => config.gas_suicide = cost,
+			GasParameter::SuicideNewAccount => config.gas_suicide_new_account = cost,
+			GasParameter::Call => config.gas_call = cost,
+			GasParameter::ExpByte => config.gas_expbyte = cost,
+			GasParameter::AccountAccessCold => config.gas_account_access_cold = cost,
+			GasParameter::StorageReadWarm => config.gas_storage_read_warm = cost,
+		}
+	}
+}
+
+/// Returns `config` with the costs of `overrides` in place of its own.
+pub fn apply_gas_overrides(config: &Config, overrides: &[(GasParameter, u64)]) -> Config {
+	let mut config = config.clone();
+	for (parameter, cost) in overrides {
+		parameter.set_cost(&mut config, *cost);
+	}
+	config
+}
+
+/// The costs of all the parameters in `config`.
+pub fn gas_table(config: &Config) -> Vec<(GasParameter, u64)> {
+	GasParameter::ALL
+		.iter()
+		.map(|parameter| (*parameter, parameter.cost(config)))
+		.collect()
+}
+
+/// Gas charged before the execution of a transaction in `config`, for the transaction itself,
+/// its input data, its contract creation and its access list. `None` if it overflows.
+pub fn intrinsic_gas(
+	config: &Config,
+	is_create: bool,
+	input: &[u8],
+	access_list: &[(H160, Vec<H256>)],
+) -> Option<u64> {
+	let cost = if is_create {
+		evm::gasometer::create_transaction_cost(input, access_list)
+	} else {
+		evm::gasometer::call_transaction_cost(input, access_list)
+	};
+	let mut gasometer = evm::gasometer::Gasometer::new(u64::MAX, config);
+	gasometer.record_transaction(cost).ok()?;
+	Some(gasometer.total_used_gas())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn intrinsic_gas_follows_the_configuration() {
+		let config = Config::london();
+		let input = [0, 0, 0, 0, 1, 2, 3, 4];
+		let access_list = [(
+			H160::repeat_byte(1),
+			vec![H256::zero(), H256::repeat_byte(1)],
+		)];
+
+		assert_eq!(intrinsic_gas(&config, false, &[], &[]), Some(21_000));
+		assert_eq!(intrinsic_gas(&config, true, &[], &[]), Some(53_000));
+		assert_eq!(
+			intrinsic_gas(&config, false, &input, &[]),
+			Some(21_000 + 4 * 4 + 4 * 16)
+		);
+		assert_eq!(
+			intrinsic_gas(&config, false, &[], &access_list),
+			Some(21_000 + 2_400 + 2 * 1_900)
+		);
+		// Before Istanbul, the non zero bytes were more expensive.
+		assert_eq!(
+			intrinsic_gas(&Config::frontier(), false, &input, &[]),
+			Some(21_000 + 4 * 4 + 4 * 68)
+		);
+	}
+}
@@ -17,21 +17,34 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+mod gas;
 mod precompile;
+#[cfg(feature = "std")]
+mod system_contract;
+mod validation;
 
 use codec::{Decode, Encode};
 pub use evm::ExitReason;
 use frame_support::weights::Weight;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
-use sp_core::{H160, U256};
+use sp_core::{H160, H256, U256};
 use sp_std::vec::Vec;
 
 pub use evm::backend::{Basic as Account, Log};
 
+pub use self::gas::{apply_gas_overrides, gas_table, intrinsic_gas, GasParameter};
 pub use self::precompile::{
-	Context, ExitError, ExitRevert, ExitSucceed, LinearCostPrecompile, Precompile,
-	PrecompileFailure, PrecompileOutput, PrecompileResult, PrecompileSet,
+	ensure_linear_cost, Context, ExitError, ExitRevert, ExitSucceed, LinearCostPrecompile,
+	Precompile, PrecompileFailure, PrecompileOutput, PrecompileResult, PrecompileSet,
+};
+#[cfg(feature = "std")]
+pub use self::system_contract::{
+	SystemContract, DETERMINISTIC_DEPLOYMENT_PROXY_ADDRESS, DETERMINISTIC_DEPLOYMENT_PROXY_CODE,
+	DETERMINISTIC_DEPLOYMENT_PROXY_CODE_HASH,
+};
+pub use self::validation::{
+	CheckEvmTransaction, CheckEvmTransactionConfig, CheckEvmTransactionInput, InvalidEvmTransaction,
 };
 
 #[derive(Clone, Eq, PartialEq, Encode, Decode, Default)]
@@ -63,6 +76,20 @@ pub enum CallOrCreateInfo {
 	Create(CreateInfo),
 }
 
+/// Execution of a dry run, which doesn't commit any state.
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub struct DryRunInfo {
+	pub info: CallOrCreateInfo,
+	/// Accounts and storage keys accessed by the execution, except the ones of the sender, of
+	/// the called or created contract and of the precompiles.
+	pub access_list: Vec<(H160, Vec<H256>)>,
+	/// Storage keys accessed by the execution in the accounts left out of `access_list`.
+	pub excluded_storage: Vec<(H160, Vec<H256>)>,
+	/// Gas refunded at the end of the execution, already deducted from the used gas of `info`.
+	pub refunded_gas: U256,
+}
+
 /// Account definition used for genesis block construction.
 #[cfg(feature = "std")]
 #[derive(Clone, Eq, PartialEq, Encode, Decode, Debug, Serialize, Deserialize)]
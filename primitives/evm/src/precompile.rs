@@ -36,10 +36,17 @@ pub trait Precompile {
 	) -> PrecompileResult;
 }
 
+/// A precompile costing `BASE + WORD * ceil(len / 32)` gas for an input of `len` bytes.
+///
+/// Implementors get a [`Precompile`] implementation, which fails with `OutOfGas` when the cost
+/// is above the gas limit, and otherwise charges the cost and executes the precompile.
 pub trait LinearCostPrecompile {
+	/// Base cost of an execution.
 	const BASE: u64;
+	/// Cost of each word of 32 bytes of the input.
 	const WORD: u64;
 
+	/// Executes the precompile, once its `cost` is known to be affordable.
 	fn execute(
 		input: &[u8],
 		cost: u64,
@@ -60,8 +67,9 @@ impl<T: LinearCostPrecompile> Precompile for T {
 	}
 }
 
-/// Linear gas cost
-fn ensure_linear_cost(
+/// Linear gas cost, `base + word * ceil(len / 32)`, failing with `OutOfGas` above
+/// `target_gas`.
+pub fn ensure_linear_cost(
 	target_gas: Option<u64>,
 	len: u64,
 	base: u64,
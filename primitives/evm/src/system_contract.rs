@@ -0,0 +1,92 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::str::FromStr;
+
+use sp_core::{H160, U256};
+
+use crate::GenesisAccount;
+
+/// Canonical address of the deterministic deployment proxy.
+pub const DETERMINISTIC_DEPLOYMENT_PROXY_ADDRESS: &str = "4e59b44847b379578588920ca78fbf26c0b4956c";
+
+/// Runtime code of the deterministic deployment proxy, deploying the init code following a
+/// 32 bytes salt in its input with `CREATE2`, and returning the address of the contract.
+pub const DETERMINISTIC_DEPLOYMENT_PROXY_CODE: &str = "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe03601600081602082378035828234f58015156039578182fd5b8082525050506014600cf3";
+
+/// Code hash of the deterministic deployment proxy at its canonical address on Ethereum.
+pub const DETERMINISTIC_DEPLOYMENT_PROXY_CODE_HASH: &str =
+	"2fa86add0aed31f33a762c9d88e807c475bd51d0f52bd0955754b2608f7e4989";
+
+fn address(address: &str) -> H160 {
+	H160::from_str(address).expect("internal H160 is valid; qed")
+}
+
+/// A well-known contract deployed at genesis at its canonical address, which tooling expects
+/// to find on any chain.
+///
+/// On Ethereum, these contracts are deployed with pre-signed transactions without replay
+/// protection. A chain can instead deploy them at genesis with their exact runtime code.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SystemContract {
+	pub address: H160,
+	/// Runtime code, as deployed at the canonical address.
+	pub code: Vec<u8>,
+}
+
+impl SystemContract {
+	/// The deterministic deployment proxy, used by Foundry and hardhat-deploy for `CREATE2`
+	/// deployments.
+	pub fn deterministic_deployment_proxy() -> Self {
+		Self {
+			address: address(DETERMINISTIC_DEPLOYMENT_PROXY_ADDRESS),
+			code: sp_core::bytes::from_hex(DETERMINISTIC_DEPLOYMENT_PROXY_CODE)
+				.expect("internal code is valid hex; qed"),
+		}
+	}
+
+	/// The genesis account of the contract. As any contract since EIP-161, it has a nonce of
+	/// one.
+	pub fn genesis_account(&self) -> GenesisAccount {
+		GenesisAccount {
+			nonce: U256::one(),
+			balance: U256::zero(),
+			storage: Default::default(),
+			code: self.code.clone(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::H256;
+
+	#[test]
+	fn deterministic_deployment_proxy_has_the_canonical_code() {
+		let proxy = SystemContract::deterministic_deployment_proxy();
+
+		assert_eq!(
+			proxy.address,
+			address(DETERMINISTIC_DEPLOYMENT_PROXY_ADDRESS)
+		);
+		assert_eq!(
+			H256(sp_core::hashing::keccak_256(&proxy.code)),
+			H256::from_str(DETERMINISTIC_DEPLOYMENT_PROXY_CODE_HASH).unwrap()
+		);
+	}
+}
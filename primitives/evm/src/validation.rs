@@ -0,0 +1,250 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::Account;
+use codec::{Decode, Encode};
+use sp_core::{H160, H256, U256};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+/// Reason an Ethereum transaction fails validation.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum InvalidEvmTransaction {
+	/// The gas limit is lower than the intrinsic cost of the transaction.
+	GasLimitTooLow,
+	/// The gas limit is not lower than the block gas limit.
+	GasLimitTooHigh,
+	/// The chain id doesn't match the one of the chain, or is missing while unprotected
+	/// transactions are not allowed.
+	InvalidChainId,
+	/// The signature is invalid.
+	InvalidSignature,
+	/// The fee fields are not a valid combination.
+	InvalidPaymentInput,
+	/// The max priority fee per gas is higher than the max fee per gas.
+	PriorityFeeTooHigh,
+	/// The max fee per gas, or the gas price, is lower than the base fee.
+	GasPriceTooLow,
+	/// The sender can't pay for the value and the gas limit at the max fee per gas.
+	BalanceTooLow,
+	/// The nonce is lower than the sender nonce.
+	TxNonceTooLow,
+	/// The nonce is higher than the sender nonce.
+	TxNonceTooHigh,
+	/// The sender already has as many future transactions as the pool accepts.
+	TooManyFutureTransactions,
+}
+
+/// The fields of an Ethereum transaction that are checked before its execution.
+#[derive(Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct CheckEvmTransactionInput {
+	pub chain_id: Option<u64>,
+	pub to: Option<H160>,
+	pub input: Vec<u8>,
+	pub nonce: U256,
+	pub gas_limit: U256,
+	pub gas_price: Option<U256>,
+	pub max_fee_per_gas: Option<U256>,
+	pub max_priority_fee_per_gas: Option<U256>,
+	pub value: U256,
+	pub access_list: Vec<(H160, Vec<H256>)>,
+}
+
+/// The chain parameters an Ethereum transaction is checked against.
+pub struct CheckEvmTransactionConfig<'config> {
+	pub evm_config: &'config evm::Config,
+	pub block_gas_limit: U256,
+	pub base_fee: U256,
+	pub chain_id: u64,
+	/// Whether legacy transactions signed without chain id (pre EIP-155) are accepted.
+	pub allow_unprotected_txs: bool,
+	/// Maximum number of transactions a sender can have in the pool ahead of its nonce.
+	/// Only used by the pool.
+	pub max_future_transactions: Option<u32>,
+}
+
+/// Checks performed on an Ethereum transaction before its execution.
+///
+/// The same checks are used by the transaction pool and during block execution, the only
+/// difference being how the nonce is handled. Each caller picks its own error type `E`.
+pub struct CheckEvmTransaction<'config, E: From<InvalidEvmTransaction>> {
+	pub config: CheckEvmTransactionConfig<'config>,
+	pub transaction: CheckEvmTransactionInput,
+	/// Whether the sender can pay the fees, when they aren't paid from its balance.
+	can_pay_fee: Option<bool>,
+	/// Part of the fees paid by a sponsor of the transaction.
+	sponsored_fee: U256,
+	_marker: PhantomData<E>,
+}
+
+impl<'config, E: From<InvalidEvmTransaction>> CheckEvmTransaction<'config, E> {
+	pub fn new(
+		config: CheckEvmTransactionConfig<'config>,
+		transaction: CheckEvmTransactionInput,
+	) -> Self {
+		CheckEvmTransaction {
+			config,
+			transaction,
+			can_pay_fee: None,
+			sponsored_fee: U256::zero(),
+			_marker: Default::default(),
+		}
+	}
+
+	/// Part of the highest fee of the transaction paid by a sponsor, which the sender doesn't
+	/// have to pay. Must be set before [`Self::with_fee_payment`].
+	pub fn with_sponsored_fee(mut self, sponsored_fee: U256) -> Self {
+		self.sponsored_fee = sponsored_fee;
+		self
+	}
+
+	/// Checks the fees apart from the balance of the sender, for fees which aren't paid from
+	/// it. `can_pay` tells whether the sender can pay its part of the highest fee of the
+	/// transaction, and the balance then only has to cover its value.
+	pub fn with_fee_payment(mut self, can_pay: impl FnOnce(U256) -> bool) -> Self {
+		// Invalid fee fields are reported by `validate_common`.
+		self.can_pay_fee = Some(self.sender_fee().map(can_pay).unwrap_or(false));
+		self
+	}
+
+	/// Checks performed by the pool. A transaction with a nonce higher than the one of the
+	/// sender is valid, it just has to wait for the previous ones.
+	pub fn validate_in_pool_for(&self, who: &Account) -> Result<&Self, E> {
+		self.validate_common()?.with_balance_for(who)?;
+		if self.transaction.nonce < who.nonce {
+			return Err(InvalidEvmTransaction::TxNonceTooLow.into());
+		}
+		// A future transaction requires the one with the previous nonce, and a sender has a
+		// single transaction per nonce in the pool. Bounding the nonce gap thus bounds the
+		// number of future transactions of a sender.
+		if let Some(max_future_transactions) = self.config.max_future_transactions {
+			if self.transaction.nonce > who.nonce.saturating_add(max_future_transactions.into()) {
+				return Err(InvalidEvmTransaction::TooManyFutureTransactions.into());
+			}
+		}
+		Ok(self)
+	}
+
+	/// Checks performed during block execution. The nonce must be exactly the one of the sender.
+	pub fn validate_in_block_for(&self, who: &Account) -> Result<&Self, E> {
+		self.validate_common()?.with_balance_for(who)?;
+		if self.transaction.nonce > who.nonce {
+			return Err(InvalidEvmTransaction::TxNonceTooHigh.into());
+		} else if self.transaction.nonce < who.nonce {
+			return Err(InvalidEvmTransaction::TxNonceTooLow.into());
+		}
+		Ok(self)
+	}
+
+	/// Checks that don't depend on the sender.
+	pub fn validate_common(&self) -> Result<&Self, E> {
+		// We must ensure a transaction can pay the cost of its data bytes.
+		// If it can't it should not be included in a block.
+		let mut gasometer = evm::gasometer::Gasometer::new(
+			self.transaction.gas_limit.low_u64(),
+			self.config.evm_config,
+		);
+		let transaction_cost = match self.transaction.to {
+			Some(_) => evm::gasometer::call_transaction_cost(
+				&self.transaction.input,
+				&self.transaction.access_list,
+			),
+			None => evm::gasometer::create_transaction_cost(
+				&self.transaction.input,
+				&self.transaction.access_list,
+			),
+		};
+		if gasometer.record_transaction(transaction_cost).is_err() {
+			return Err(InvalidEvmTransaction::GasLimitTooLow.into());
+		}
+
+		self.with_chain_id()?;
+
+		if self.transaction.gas_limit >= self.config.block_gas_limit {
+			return Err(InvalidEvmTransaction::GasLimitTooHigh.into());
+		}
+
+		self.with_base_fee()
+	}
+
+	pub fn with_chain_id(&self) -> Result<&Self, E> {
+		match self.transaction.chain_id {
+			Some(chain_id) if chain_id != self.config.chain_id => {
+				Err(InvalidEvmTransaction::InvalidChainId.into())
+			}
+			None if !self.config.allow_unprotected_txs => {
+				Err(InvalidEvmTransaction::InvalidChainId.into())
+			}
+			_ => Ok(self),
+		}
+	}
+
+	pub fn with_base_fee(&self) -> Result<&Self, E> {
+		if self.max_fee_per_gas()? < self.config.base_fee {
+			return Err(InvalidEvmTransaction::GasPriceTooLow.into());
+		}
+		Ok(self)
+	}
+
+	pub fn with_balance_for(&self, who: &Account) -> Result<&Self, E> {
+		let fee = self.sender_fee()?;
+		let total_payment = match self.can_pay_fee {
+			None => self.transaction.value.saturating_add(fee),
+			Some(true) => self.transaction.value,
+			Some(false) => return Err(InvalidEvmTransaction::BalanceTooLow.into()),
+		};
+		if who.balance < total_payment {
+			return Err(InvalidEvmTransaction::BalanceTooLow.into());
+		}
+		Ok(self)
+	}
+
+	/// The highest fee of the transaction, for its whole gas limit.
+	pub fn max_fee(&self) -> Result<U256, E> {
+		Ok(self
+			.max_fee_per_gas()?
+			.saturating_mul(self.transaction.gas_limit))
+	}
+
+	/// The highest fee the sender may pay, without the sponsored part.
+	fn sender_fee(&self) -> Result<U256, E> {
+		Ok(self.max_fee()?.saturating_sub(self.sponsored_fee))
+	}
+
+	/// The highest price per gas the sender may pay.
+	fn max_fee_per_gas(&self) -> Result<U256, E> {
+		match (
+			self.transaction.gas_price,
+			self.transaction.max_fee_per_gas,
+			self.transaction.max_priority_fee_per_gas,
+		) {
+			// Legacy or EIP-2930 transaction.
+			(Some(gas_price), None, None) => Ok(gas_price),
+			// EIP-1559 transaction without tip.
+			(None, Some(max_fee_per_gas), None) => Ok(max_fee_per_gas),
+			// EIP-1559 transaction with tip.
+			(None, Some(max_fee_per_gas), Some(max_priority_fee_per_gas)) => {
+				if max_priority_fee_per_gas > max_fee_per_gas {
+					return Err(InvalidEvmTransaction::PriorityFeeTooHigh.into());
+				}
+				Ok(max_fee_per_gas)
+			}
+			_ => Err(InvalidEvmTransaction::InvalidPaymentInput.into()),
+		}
+	}
+}
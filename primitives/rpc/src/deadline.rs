@@ -0,0 +1,155 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ethereum transactions only valid until a block.
+
+use codec::{Decode, Encode};
+use ethereum::{AccessListItem, EIP1559Transaction};
+use rlp::{DecoderError, Rlp, RlpStream};
+use scale_info::TypeInfo;
+use sp_core::{H160, H256};
+use sp_runtime::RuntimeDebug;
+use sp_std::vec::Vec;
+
+/// EIP-2718 type of the envelope of the deadline transactions. It is specific to Frontier,
+/// away from the types of the Ethereum transactions.
+pub const DEADLINE_TRANSACTION_TYPE: u8 = 0x7d;
+
+/// Address of the access list item carrying the deadline of a transaction. It has no code and
+/// no known private key.
+pub const DEADLINE_ADDRESS: H160 = H160([0x7d; 20]);
+
+/// An EIP-1559 transaction only valid until a block.
+///
+/// The deadline is the last item of the access list of the transaction, at the
+/// [`DEADLINE_ADDRESS`] with the big endian `valid_until_block` as only storage key, so that the
+/// EIP-1559 signature of the transaction covers it. The transaction appears as is in the
+/// Ethereum blocks, with its EIP-1559 hash and signature.
+///
+/// The deadline is only enforced by the runtimes which enable it, others apply the transaction
+/// as any EIP-1559 transaction.
+///
+/// Its typed envelope is `0x7d || rlp([chain_id, nonce, max_priority_fee_per_gas,
+/// max_fee_per_gas, gas_limit, destination, amount, data, access_list, valid_until_block,
+/// signature_y_parity, signature_r, signature_s])`, where the access list doesn't include the
+/// deadline item.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct DeadlineTransaction {
+	/// The signed transaction, with the deadline item in its access list.
+	pub transaction: EIP1559Transaction,
+}
+
+impl DeadlineTransaction {
+	/// Last block in which the transaction can be included, if its access list carries it.
+	pub fn valid_until_block(&self) -> Option<u64> {
+		deadline_of(&self.transaction.access_list).ok().flatten()
+	}
+
+	/// Encodes the typed envelope, if the transaction carries a deadline.
+	pub fn encode_envelope(&self) -> Option<Vec<u8>> {
+		let t = &self.transaction;
+		let valid_until_block = self.valid_until_block()?;
+
+		let mut stream = RlpStream::new_list(13);
+		stream.append(&t.chain_id);
+		stream.append(&t.nonce);
+		stream.append(&t.max_priority_fee_per_gas);
+		stream.append(&t.max_fee_per_gas);
+		stream.append(&t.gas_limit);
+		stream.append(&t.action);
+		stream.append(&t.value);
+		stream.append(&t.input);
+		stream.append_list(&t.access_list[..t.access_list.len() - 1]);
+		stream.append(&valid_until_block);
+		stream.append(&t.odd_y_parity);
+		stream.append(&t.r);
+		stream.append(&t.s);
+
+		let payload = stream.out();
+		let mut envelope = Vec::with_capacity(1 + payload.len());
+		envelope.push(DEADLINE_TRANSACTION_TYPE);
+		envelope.extend_from_slice(&payload);
+		Some(envelope)
+	}
+
+	/// Decodes a typed envelope.
+	pub fn decode_envelope(bytes: &[u8]) -> Result<Self, DecoderError> {
+		match bytes.split_first() {
+			Some((&DEADLINE_TRANSACTION_TYPE, payload)) => {
+				let rlp = Rlp::new(payload);
+				if rlp.item_count()? != 13 {
+					return Err(DecoderError::RlpIncorrectListLen);
+				}
+
+				let mut access_list: Vec<AccessListItem> = rlp.list_at(8)?;
+				if access_list
+					.iter()
+					.any(|item| item.address == DEADLINE_ADDRESS)
+				{
+					return Err(DecoderError::Custom("deadline item in the access list"));
+				}
+				access_list.push(deadline_item(rlp.val_at(9)?));
+
+				Ok(Self {
+					transaction: EIP1559Transaction {
+						chain_id: rlp.val_at(0)?,
+						nonce: rlp.val_at(1)?,
+						max_priority_fee_per_gas: rlp.val_at(2)?,
+						max_fee_per_gas: rlp.val_at(3)?,
+						gas_limit: rlp.val_at(4)?,
+						action: rlp.val_at(5)?,
+						value: rlp.val_at(6)?,
+						input: rlp.val_at(7)?,
+						access_list,
+						odd_y_parity: rlp.val_at(10)?,
+						r: rlp.val_at(11)?,
+						s: rlp.val_at(12)?,
+					},
+				})
+			}
+			_ => Err(DecoderError::Custom("not a deadline transaction")),
+		}
+	}
+}
+
+/// Access list item carrying a deadline.
+pub fn deadline_item(valid_until_block: u64) -> AccessListItem {
+	AccessListItem {
+		address: DEADLINE_ADDRESS,
+		storage_keys: sp_std::vec![H256::from_low_u64_be(valid_until_block)],
+	}
+}
+
+/// Deadline carried by an access list, if any. An access list with a deadline item which isn't
+/// its last item, or which doesn't hold a single block number, is invalid.
+pub fn deadline_of(access_list: &[AccessListItem]) -> Result<Option<u64>, ()> {
+	let (last, others) = match access_list.split_last() {
+		Some(split) => split,
+		None => return Ok(None),
+	};
+	if others.iter().any(|item| item.address == DEADLINE_ADDRESS) {
+		return Err(());
+	}
+	if last.address != DEADLINE_ADDRESS {
+		return Ok(None);
+	}
+
+	match &last.storage_keys[..] {
+		[key] if key[..24].iter().all(|byte| *byte == 0) => Ok(Some(key.to_low_u64_be())),
+		_ => Err(()),
+	}
+}
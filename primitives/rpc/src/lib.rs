@@ -18,13 +18,19 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::too_many_arguments)]
 
+mod deadline;
+
+pub use self::deadline::{
+	deadline_item, deadline_of, DeadlineTransaction, DEADLINE_ADDRESS, DEADLINE_TRANSACTION_TYPE,
+};
+
 use codec::{Decode, Encode};
 use ethereum::Log;
 use ethereum_types::Bloom;
 use scale_info::TypeInfo;
 use sp_core::{H160, H256, U256};
 use sp_runtime::{traits::Block as BlockT, Permill, RuntimeDebug};
-use sp_std::vec::Vec;
+use sp_std::{boxed::Box, vec::Vec};
 
 #[derive(Eq, PartialEq, Clone, Default, RuntimeDebug, Encode, Decode, TypeInfo)]
 pub struct TransactionStatus {
@@ -37,9 +43,46 @@ pub struct TransactionStatus {
 	pub logs_bloom: Bloom,
 }
 
+/// Changes applied to an account before simulating calls.
+#[derive(Eq, PartialEq, Clone, Default, RuntimeDebug, Encode, Decode, TypeInfo)]
+pub struct AccountOverride {
+	/// Balance to set.
+	pub balance: Option<U256>,
+	/// Nonce to set.
+	pub nonce: Option<U256>,
+	/// Code to set.
+	pub code: Option<Vec<u8>>,
+	/// Storage replacing the whole account storage.
+	pub state: Option<Vec<(H256, H256)>>,
+	/// Storage slots to set, keeping the rest of the account storage.
+	pub state_diff: Vec<(H256, H256)>,
+}
+
+/// Metadata registered for a contract by its deployer, e.g. the IPFS CID of its verified
+/// sources.
+#[derive(Eq, PartialEq, Clone, Default, RuntimeDebug, Encode, Decode, TypeInfo)]
+pub struct ContractMetadata {
+	/// Account which deployed the contract.
+	pub deployer: H160,
+	/// Metadata, opaque to the runtime.
+	pub metadata: Vec<u8>,
+}
+
+/// A precompile of the runtime.
+#[derive(Eq, PartialEq, Clone, Default, RuntimeDebug, Encode, Decode, TypeInfo)]
+pub struct PrecompileInfo {
+	/// Address of the precompile.
+	pub address: H160,
+	/// Name of the precompile, UTF-8 encoded.
+	pub name: Vec<u8>,
+	/// Keccak-256 hash of the Solidity interface of the precompile, for precompiles called
+	/// through one.
+	pub interface_hash: Option<H256>,
+}
+
 sp_api::decl_runtime_apis! {
 	/// API necessary for Ethereum-compatibility layer.
-	#[api_version(4)]
+	#[api_version(13)]
 	pub trait EthereumRuntimeRPCApi {
 		/// Returns runtime defined pallet_evm::ChainId.
 		fn chain_id() -> u64;
@@ -51,7 +94,7 @@ sp_api::decl_runtime_apis! {
 		fn account_code_at(address: H160) -> Vec<u8>;
 		/// Returns the converted FindAuthor::find_author authority id.
 		fn author() -> H160;
-		/// For a given account address and index, returns pallet_evm::AccountStorages.
+		/// For a given account address and index, returns the storage slot of the account.
 		fn storage_at(address: H160, index: U256) -> H256;
 		/// Returns a frame_ethereum::call response. If `estimate` is true,
 		#[changed_in(2)]
@@ -164,6 +207,65 @@ sp_api::decl_runtime_apis! {
 		) -> Vec<ethereum::TransactionV2>;
 		/// Return the elasticity multiplier.
 		fn elasticity() -> Option<Permill>;
+		/// Applies the given extrinsics on top of the current state and returns the
+		/// Ethereum block that would be built from them.
+		fn pending_block(
+			xts: Vec<<Block as BlockT>::Extrinsic>,
+		) -> (
+			Option<ethereum::BlockV2>,
+			Option<Vec<ethereum::ReceiptV3>>,
+			Option<Vec<TransactionStatus>>
+		);
+		/// Returns the address a contract deployed with CREATE2 by `deployer` would get.
+		fn create2_address(deployer: H160, salt: H256, init_code_hash: H256) -> H160;
+		/// Applies the given changes to an account. Only meant for simulations, on top of
+		/// which subsequent calls of the same runtime api instance are executed. Fails, without
+		/// applying anything, when a value doesn't fit in its runtime type.
+		fn override_account(
+			address: H160,
+			account: AccountOverride,
+		) -> Result<(), sp_runtime::DispatchError>;
+		/// Sets the block number and the timestamp, in milliseconds, subsequent calls of the
+		/// same runtime api instance are executed with. Only meant for simulations. Fails when
+		/// the block number doesn't fit in the runtime block number.
+		fn override_block(
+			number: Option<U256>,
+			timestamp: Option<u64>,
+		) -> Result<(), sp_runtime::DispatchError>;
+		/// Runs the checks of the pool validation on the given transaction, returning the
+		/// exact failure cause.
+		fn check_transaction(
+			transaction: ethereum::TransactionV2,
+		) -> Result<(), fp_evm::InvalidEvmTransaction>;
+		/// Returns the RLP encoded trie nodes, from the root, proving the inclusion of the
+		/// receipt at the given index in the receipts root of the current block.
+		fn receipt_proof(index: u32) -> Option<Vec<Vec<u8>>>;
+		/// Returns the active precompiles.
+		fn precompiles() -> Vec<PrecompileInfo>;
+		/// Returns the effective costs of the overridable gas parameters, with the overrides
+		/// of the runtime applied to the ones of its hardfork.
+		fn gas_table() -> Vec<(fp_evm::GasParameter, u64)>;
+		/// Executes a call, or a create without `to`, without committing anything, and returns
+		/// it with the storage it accessed.
+		fn dry_run(
+			from: H160,
+			to: Option<H160>,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			estimate: bool,
+			access_list: Option<Vec<(H160, Vec<H256>)>>,
+		) -> Result<fp_evm::DryRunInfo, sp_runtime::DispatchError>;
+		/// Returns the pallet_evm::Accounts of the given addresses, in order.
+		fn accounts_basic(addresses: Vec<H160>) -> Vec<fp_evm::Account>;
+		/// Returns the pallet_evm::AccountCodes of the given addresses, in order.
+		fn accounts_code_at(addresses: Vec<H160>) -> Vec<Vec<u8>>;
+		/// Returns the gas charged before the execution of the transaction, with the EVM
+		/// configuration of the runtime.
+		fn intrinsic_gas(transaction: ethereum::TransactionV2) -> u64;
 	}
 
 	#[api_version(2)]
@@ -172,10 +274,45 @@ sp_api::decl_runtime_apis! {
 		#[changed_in(2)]
 		fn convert_transaction(transaction: ethereum::TransactionV0) -> <Block as BlockT>::Extrinsic;
 	}
+
+	/// API of the contract metadata registry, for runtimes including it.
+	pub trait ContractMetadataRuntimeApi {
+		/// Returns the metadata registered for a contract, if any.
+		fn contract_metadata(address: H160) -> Option<ContractMetadata>;
+	}
 }
 
+/// Converts Ethereum transactions to the extrinsics of a runtime which doesn't provide the
+/// `ConvertTransactionRuntimeApi`.
+///
+/// The trait is object safe, so that the converters of successive runtimes can be boxed
+/// together, eg. in a registry keyed by spec version.
 pub trait ConvertTransaction<E> {
 	fn convert_transaction(&self, transaction: ethereum::TransactionV2) -> E;
+
+	/// Converts `transaction` for the runtime with the spec version `spec_version`, or returns
+	/// `None` if that runtime isn't supported. Supports every runtime by default.
+	fn convert_transaction_for(
+		&self,
+		_spec_version: u32,
+		transaction: ethereum::TransactionV2,
+	) -> Option<E> {
+		Some(self.convert_transaction(transaction))
+	}
+}
+
+impl<E, C: ConvertTransaction<E> + ?Sized> ConvertTransaction<E> for Box<C> {
+	fn convert_transaction(&self, transaction: ethereum::TransactionV2) -> E {
+		(**self).convert_transaction(transaction)
+	}
+
+	fn convert_transaction_for(
+		&self,
+		spec_version: u32,
+		transaction: ethereum::TransactionV2,
+	) -> Option<E> {
+		(**self).convert_transaction_for(spec_version, transaction)
+	}
 }
 
 // `NoTransactionConverter` is a non-instantiable type (an enum with no variants),
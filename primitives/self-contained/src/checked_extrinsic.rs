@@ -22,12 +22,10 @@ use sp_runtime::{
 		self, DispatchInfoOf, Dispatchable, MaybeDisplay, Member, PostDispatchInfoOf,
 		SignedExtension, ValidateUnsigned,
 	},
-	transaction_validity::{
-		InvalidTransaction, TransactionSource, TransactionValidity, TransactionValidityError,
-	},
+	transaction_validity::{TransactionSource, TransactionValidity},
 };
 
-use crate::SelfContainedCall;
+use crate::{InvalidSelfContained, SelfContainedCall, SelfContainedExtensions};
 
 #[derive(PartialEq, Eq, Clone, RuntimeDebug)]
 pub enum CheckedSignature<AccountId, Extra, SelfContainedSignedInfo> {
@@ -87,12 +85,15 @@ where
 				let unsigned_validation = U::validate_unsigned(source, &self.function)?;
 				Ok(valid.combine_with(unsigned_validation))
 			}
-			CheckedSignature::SelfContained(signed_info) => self
-				.function
-				.validate_self_contained(signed_info, info, len)
-				.ok_or(TransactionValidityError::Invalid(
-					InvalidTransaction::BadProof,
-				))?,
+			CheckedSignature::SelfContained(signed_info) => {
+				let valid = self
+					.function
+					.validate_self_contained(signed_info, info, len)
+					.ok_or(InvalidSelfContained::NotHandled)??;
+				let extensions =
+					Call::Extensions::validate(signed_info, &self.function, info, len)?;
+				Ok(valid.combine_with(extensions))
+			}
 		}
 	}
 
@@ -140,13 +141,21 @@ where
 			CheckedSignature::SelfContained(signed_info) => {
 				// If pre-dispatch fail, the block must be considered invalid
 				self.function
-					.pre_dispatch_self_contained(&signed_info)
-					.ok_or(TransactionValidityError::Invalid(
-						InvalidTransaction::BadProof,
-					))??;
-				Ok(self.function.apply_self_contained(signed_info).ok_or(
-					TransactionValidityError::Invalid(InvalidTransaction::BadProof),
-				)?)
+					.pre_dispatch_self_contained(&signed_info, info, len)
+					.ok_or(InvalidSelfContained::NotHandled)??;
+				let pre = Call::Extensions::pre_dispatch(&signed_info, &self.function, info, len)?;
+				let res = self
+					.function
+					.apply_self_contained(signed_info)
+					.ok_or(InvalidSelfContained::NotHandled)?;
+				let post_info = match res {
+					Ok(info) => info,
+					Err(err) => err.post_info,
+				};
+				let result = res.map(|_| ()).map_err(|e| e.error);
+				Extra::post_dispatch(None, info, &post_info, len, &result)?;
+				Call::Extensions::post_dispatch(pre, info, &post_info, len, &result)?;
+				Ok(res)
 			}
 		}
 	}
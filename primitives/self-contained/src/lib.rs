@@ -25,16 +25,187 @@ pub use crate::{
 	unchecked_extrinsic::UncheckedExtrinsic,
 };
 
+use core::marker::PhantomData;
 use sp_runtime::{
-	traits::{DispatchInfoOf, Dispatchable, PostDispatchInfoOf},
-	transaction_validity::{TransactionValidity, TransactionValidityError},
+	traits::{Convert, DispatchInfoOf, Dispatchable, PostDispatchInfoOf, SignedExtension},
+	transaction_validity::{
+		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+	DispatchResult, RuntimeDebug,
 };
 
+/// Reason a self-contained extrinsic is invalid, before its own checks.
+///
+/// It is reported as an `InvalidTransaction::Custom` error, with the codes from 255 down, so
+/// as not to clash with the codes of the pallets. The errors of the checks of the call itself
+/// are reported as they are.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+#[repr(u8)]
+pub enum InvalidSelfContained {
+	/// The self-contained call carries a signature.
+	Signed = 255,
+	/// The call is self-contained, but `check_self_contained` doesn't handle it.
+	NotChecked = 254,
+	/// The call is self-contained, but `validate_self_contained`,
+	/// `pre_dispatch_self_contained` or `apply_self_contained` doesn't handle it.
+	NotHandled = 253,
+	/// The extensions of the self-contained calls include `CheckWeight`.
+	CheckWeight = 252,
+}
+
+impl InvalidSelfContained {
+	/// The reason of an `InvalidTransaction::Custom` error with the code `code`, if any.
+	pub fn from_code(code: u8) -> Option<Self> {
+		match code {
+			255 => Some(Self::Signed),
+			254 => Some(Self::NotChecked),
+			253 => Some(Self::NotHandled),
+			252 => Some(Self::CheckWeight),
+			_ => None,
+		}
+	}
+}
+
+impl From<InvalidSelfContained> for TransactionValidityError {
+	fn from(reason: InvalidSelfContained) -> Self {
+		TransactionValidityError::Invalid(InvalidTransaction::Custom(reason as u8))
+	}
+}
+
+/// Checks run for self-contained functions, along with their own checks, given the signed
+/// info `SignedInfo` of the function, eg. its recovered sender.
+pub trait SelfContainedExtensions<Call: Dispatchable, SignedInfo> {
+	/// The state carried from `pre_dispatch` to `post_dispatch`.
+	type Pre;
+
+	/// Validate a self-contained function for the transaction pool.
+	fn validate(
+		info: &SignedInfo,
+		call: &Call,
+		dispatch_info: &DispatchInfoOf<Call>,
+		len: usize,
+	) -> TransactionValidity;
+	/// Do any pre-flight stuff for a self-contained function.
+	fn pre_dispatch(
+		info: &SignedInfo,
+		call: &Call,
+		dispatch_info: &DispatchInfoOf<Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError>;
+	/// Do any post-flight stuff for a self-contained function.
+	fn post_dispatch(
+		pre: Self::Pre,
+		dispatch_info: &DispatchInfoOf<Call>,
+		post_info: &PostDispatchInfoOf<Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError>;
+}
+
+impl<Call: Dispatchable, SignedInfo> SelfContainedExtensions<Call, SignedInfo> for () {
+	type Pre = ();
+
+	fn validate(
+		_info: &SignedInfo,
+		_call: &Call,
+		_dispatch_info: &DispatchInfoOf<Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		Ok(ValidTransaction::default())
+	}
+
+	fn pre_dispatch(
+		_info: &SignedInfo,
+		_call: &Call,
+		_dispatch_info: &DispatchInfoOf<Call>,
+		_len: usize,
+	) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn post_dispatch(
+		_pre: (),
+		_dispatch_info: &DispatchInfoOf<Call>,
+		_post_info: &PostDispatchInfoOf<Call>,
+		_len: usize,
+		_result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+}
+
+/// Runs the signed extensions `E`, eg. a tuple of extensions, for the self-contained functions
+/// as for signed extrinsics, on behalf of the sender which `AccountOf` converts the signed info
+/// of the function into.
+///
+/// A self-contained function carries no data for the extensions, so they are built with
+/// `Default`, and the extensions checking such data, as `CheckNonce` or `CheckMortality`,
+/// don't apply. `CheckWeight` must not be part of `E` either: the self-contained functions,
+/// as the ones of `pallet-ethereum`, already check their weight, and the block weight would
+/// be counted twice. It is rejected as such.
+pub struct SenderExtensions<E, AccountOf>(PhantomData<(E, AccountOf)>);
+
+impl<E: SignedExtension, AccountOf> SenderExtensions<E, AccountOf> {
+	fn ensure_without_check_weight() -> Result<(), TransactionValidityError> {
+		if E::identifier().contains(&"CheckWeight") {
+			return Err(InvalidSelfContained::CheckWeight.into());
+		}
+		Ok(())
+	}
+}
+
+impl<Call, SignedInfo, E, AccountOf> SelfContainedExtensions<Call, SignedInfo>
+	for SenderExtensions<E, AccountOf>
+where
+	Call: Dispatchable,
+	E: SignedExtension<Call = Call> + Default,
+	AccountOf: Convert<SignedInfo, E::AccountId>,
+	SignedInfo: Clone,
+{
+	type Pre = E::Pre;
+
+	fn validate(
+		info: &SignedInfo,
+		call: &Call,
+		dispatch_info: &DispatchInfoOf<Call>,
+		len: usize,
+	) -> TransactionValidity {
+		Self::ensure_without_check_weight()?;
+		let who = AccountOf::convert(info.clone());
+		E::default().validate(&who, call, dispatch_info, len)
+	}
+
+	fn pre_dispatch(
+		info: &SignedInfo,
+		call: &Call,
+		dispatch_info: &DispatchInfoOf<Call>,
+		len: usize,
+	) -> Result<E::Pre, TransactionValidityError> {
+		Self::ensure_without_check_weight()?;
+		let who = AccountOf::convert(info.clone());
+		E::default().pre_dispatch(&who, call, dispatch_info, len)
+	}
+
+	fn post_dispatch(
+		pre: E::Pre,
+		dispatch_info: &DispatchInfoOf<Call>,
+		post_info: &PostDispatchInfoOf<Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		E::post_dispatch(Some(pre), dispatch_info, post_info, len, result)
+	}
+}
+
 /// A call that has self-contained functions. A self-contained
 /// function is something that has its signature embedded in its call.
 pub trait SelfContainedCall: Dispatchable {
 	/// Validated signature info.
 	type SignedInfo;
+	/// Checks also run for the self-contained functions, after their own. The signed
+	/// extensions of the runtime don't run for them otherwise, and neither do the guards they
+	/// implement. `()` runs none, and [`SenderExtensions`] opts signed extensions in.
+	type Extensions: SelfContainedExtensions<Self, Self::SignedInfo>;
 
 	/// Returns whether the current call is a self-contained function.
 	fn is_self_contained(&self) -> bool;
@@ -62,9 +233,14 @@ pub trait SelfContainedCall: Dispatchable {
 	fn pre_dispatch_self_contained(
 		&self,
 		info: &Self::SignedInfo,
+		dispatch_info: &DispatchInfoOf<Self>,
+		len: usize,
 	) -> Option<Result<(), TransactionValidityError>>;
 	/// Apply a self-contained function. Returns `None` if the
 	/// function is not a self-contained.
+	///
+	/// The returned post dispatch info is used to refund the unused
+	/// part of the pre-dispatch weight.
 	fn apply_self_contained(
 		self,
 		info: Self::SignedInfo,
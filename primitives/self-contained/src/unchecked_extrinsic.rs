@@ -26,11 +26,11 @@ use sp_runtime::{
 		self, Checkable, Extrinsic, ExtrinsicMetadata, IdentifyAccount, MaybeDisplay, Member,
 		SignedExtension,
 	},
-	transaction_validity::{InvalidTransaction, TransactionValidityError},
+	transaction_validity::TransactionValidityError,
 	OpaqueExtrinsic, RuntimeDebug,
 };
 
-use crate::{CheckedExtrinsic, CheckedSignature, SelfContainedCall};
+use crate::{CheckedExtrinsic, CheckedSignature, InvalidSelfContained, SelfContainedCall};
 
 /// A extrinsic right from the external world. This is unchecked and so
 /// can contain a signature.
@@ -105,14 +105,14 @@ where
 	fn check(self, lookup: &Lookup) -> Result<Self::Checked, TransactionValidityError> {
 		if self.0.function.is_self_contained() {
 			if self.0.signature.is_some() {
-				return Err(TransactionValidityError::Invalid(
-					InvalidTransaction::BadProof,
-				));
+				return Err(InvalidSelfContained::Signed.into());
 			}
 
-			let signed_info = self.0.function.check_self_contained().ok_or(
-				TransactionValidityError::Invalid(InvalidTransaction::BadProof),
-			)??;
+			let signed_info = self
+				.0
+				.function
+				.check_self_contained()
+				.ok_or(InvalidSelfContained::NotChecked)??;
 			Ok(CheckedExtrinsic {
 				signed: CheckedSignature::SelfContained(signed_info),
 				function: self.0.function,
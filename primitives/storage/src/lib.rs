@@ -24,6 +24,10 @@ pub const PALLET_ETHEREUM_SCHEMA: &[u8] = b":ethereum_schema";
 /// Cached version of pallet Ethereum's storage schema is stored under this key in the AuxStore.
 pub const PALLET_ETHEREUM_SCHEMA_CACHE: &[u8] = b":ethereum_schema_cache";
 
+/// Prefix of the child tries holding the storage of the EVM accounts, followed by the address of
+/// the account.
+pub const EVM_ACCOUNT_STORAGE_CHILD_PREFIX: &[u8] = b"evm:storage:";
+
 /// The schema version for Pallet Ethereum's storage
 #[derive(Clone, Copy, Debug, Encode, Decode, PartialEq, Eq, PartialOrd, Ord)]
 pub enum EthereumStorageSchema {
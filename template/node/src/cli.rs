@@ -15,21 +15,79 @@ impl Default for Sealing {
 	}
 }
 
-#[allow(missing_docs)]
-#[derive(Debug, clap::Parser)]
-pub struct RunCmd {
-	#[allow(missing_docs)]
-	#[clap(flatten)]
-	pub base: sc_cli::RunCmd,
+/// Namespaces of the Ethereum RPC.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ArgEnum)]
+pub enum EthRpcNamespace {
+	/// `eth_*` methods, except filters, subscriptions and the bundler methods.
+	Eth,
+	/// `eth_*Filter*` methods.
+	EthFilter,
+	/// `eth_subscribe` and `eth_unsubscribe`.
+	EthPubSub,
+	/// `net_*` methods.
+	Net,
+	/// `web3_*` methods.
+	Web3,
+	/// `engine_*` methods.
+	Engine,
+	/// `frontier_*` methods, along with the `debug_*` and `trace_*` methods.
+	Frontier,
+	/// The ERC-4337 bundler methods `eth_sendUserOperation`, `eth_estimateUserOperationGas`,
+	/// `eth_getUserOperationReceipt` and `eth_supportedEntryPoints`, see
+	/// `--bundler-entry-point`.
+	Bundler,
+}
 
-	/// Choose sealing method.
-	#[cfg(feature = "manual-seal")]
-	#[clap(long, arg_enum, ignore_case = true)]
-	pub sealing: Sealing,
+/// Indexing modes of the Ethereum transactions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ArgEnum)]
+pub enum FrontierTxIndex {
+	/// Transactions are not found by their hash once included in a block.
+	Off,
+	/// Transactions are found by their hash in the latest blocks only, see
+	/// `--frontier-tx-index-depth`.
+	Lazy,
+	/// All the transactions are indexed.
+	Full,
+}
 
+/// Ethereum RPC configuration.
+#[derive(Debug, clap::Parser)]
+pub struct EthConfiguration {
+	/// Sign transactions sent to `eth_sendTransaction` with the development accounts.
+	/// Always enabled with `--dev`.
 	#[clap(long)]
 	pub enable_dev_signer: bool,
 
+	/// Use the ECDSA keys of the keystore with the `ethk` key type as the Ethereum accounts of
+	/// `eth_accounts`, `eth_sendTransaction` and `eth_sign`, on the transports allowing unsafe
+	/// methods only (see `--rpc-methods`).
+	#[clap(long)]
+	pub enable_keystore_signer: bool,
+
+	/// Accounts of the keystore available to `--enable-keystore-signer`, all of them if not set.
+	#[clap(long, use_value_delimiter = true)]
+	pub keystore_signer_allowlist: Vec<sp_core::H160>,
+
+	/// Bundle the ERC-4337 user operations of this EntryPoint, which must implement the
+	/// EntryPoint v0.6 interface, enabling the bundler RPC methods.
+	#[clap(long, requires = "bundler-account")]
+	pub bundler_entry_point: Option<sp_core::H160>,
+
+	/// Account signing the `handleOps` transactions of the bundler, and receiving the fees of
+	/// the user operations. It must be an account of the dev signer or, on the transports
+	/// allowing unsafe methods, of the keystore signer.
+	#[clap(long)]
+	pub bundler_account: Option<sp_core::H160>,
+
+	/// Number of latest blocks in which `eth_getUserOperationReceipt` looks up the user
+	/// operations.
+	#[clap(long, default_value = "1024")]
+	pub bundler_receipt_lookup_depth: u32,
+
+	/// Enable the `engine_*` RPC methods.
+	#[clap(long)]
+	pub enable_engine_api: bool,
+
 	/// Maximum number of logs in a query.
 	#[clap(long, default_value = "10000")]
 	pub max_past_logs: u32,
@@ -38,6 +96,107 @@ pub struct RunCmd {
 	#[clap(long, default_value = "2048")]
 	pub fee_history_limit: u64,
 
+	/// Maximum number of dropped transactions whose status is kept for
+	/// `frontier_transactionStatus`.
+	#[clap(long, default_value = "1000")]
+	pub dropped_transactions_limit: usize,
+
+	/// Timeout in seconds of `eth_call`, `eth_estimateGas` and `eth_simulateV1`, 0 to disable.
+	#[clap(long, default_value = "5")]
+	pub execution_timeout: u64,
+
+	/// Number of threads executing `eth_call`, `eth_estimateGas` and `eth_simulateV1`.
+	#[clap(long, default_value = "4")]
+	pub execution_threads: usize,
+
+	/// Maximum gas available to `eth_call`, `eth_estimateGas` and `eth_simulateV1`, 0 to
+	/// disable.
+	#[clap(long, default_value = "50000000")]
+	pub rpc_gas_cap: u64,
+
+	/// Maximum number of accounts and storage slots in a trace. Larger traces are truncated.
+	#[clap(long, default_value = "10000")]
+	pub trace_max_entries: usize,
+
+	/// Timeout in seconds of the replay of a traced transaction, 0 to disable. The accounts
+	/// not read in time are left out of the trace.
+	#[clap(long, default_value = "30")]
+	pub trace_timeout: u64,
+
+	/// Maximum number of Ethereum subscriptions of a connection.
+	#[clap(long, default_value = "1024")]
+	pub max_subscriptions_per_connection: usize,
+
+	/// Maximum number of notifications buffered for an Ethereum subscriber. Further
+	/// notifications are dropped until the subscriber catches up.
+	#[clap(long, default_value = "4096")]
+	pub subscription_buffer_size: usize,
+
+	/// Indexing of the Ethereum transactions by their hash.
+	#[clap(long, arg_enum, ignore_case = true, default_value = "full")]
+	pub frontier_tx_index: FrontierTxIndex,
+
+	/// Number of latest blocks in which transactions are looked up by their hash with
+	/// `--frontier-tx-index lazy`.
+	#[clap(long, default_value = "256")]
+	pub frontier_tx_index_depth: u32,
+
+	/// Archive the raw payloads of the Ethereum transactions of the mapped blocks, served by
+	/// `eth_getRawTransactionByHash`.
+	#[clap(long)]
+	pub frontier_raw_tx_archive: bool,
+
+	/// Run the migrations of the Frontier database which rewrite a large part of it, and may
+	/// take a while. The node doesn't start when such a migration is required without it.
+	#[clap(long)]
+	pub frontier_db_migrate: bool,
+
+	/// Serve the Ethereum RPC from the Frontier RocksDB database of another node, at this path,
+	/// as a read-only replica. Many RPC nodes can share the database of a single node mapping
+	/// the blocks, and see its new blocks within a second.
+	#[clap(long)]
+	pub frontier_db_replica_of: Option<std::path::PathBuf>,
+
+	/// Ethereum RPC namespaces exposed on public transports, i.e. the HTTP and WS servers
+	/// when they deny unsafe methods (see `--rpc-methods`). The bundler, which spends the
+	/// funds of its account, isn't public by default.
+	#[clap(
+		long,
+		arg_enum,
+		ignore_case = true,
+		use_value_delimiter = true,
+		default_value = "eth,eth-filter,eth-pub-sub,net,web3,engine,frontier"
+	)]
+	pub eth_rpc_public: Vec<EthRpcNamespace>,
+
+	/// Ethereum RPC namespaces exposed on private transports, i.e. the IPC server and the
+	/// HTTP and WS servers when they allow unsafe methods (see `--rpc-methods`).
+	#[clap(
+		long,
+		arg_enum,
+		ignore_case = true,
+		use_value_delimiter = true,
+		default_value = "eth,eth-filter,eth-pub-sub,net,web3,engine,frontier,bundler"
+	)]
+	pub eth_rpc_private: Vec<EthRpcNamespace>,
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, clap::Parser)]
+pub struct RunCmd {
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub base: sc_cli::RunCmd,
+
+	/// Choose sealing method.
+	#[cfg(feature = "manual-seal")]
+	#[clap(long, arg_enum, ignore_case = true)]
+	pub sealing: Sealing,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub eth: EthConfiguration,
+
 	/// The dynamic-fee pallet target gas price set by block author
 	#[clap(long, default_value = "1")]
 	pub target_gas_price: u64,
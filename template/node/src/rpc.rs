@@ -7,6 +7,7 @@ use jsonrpc_pubsub::manager::SubscriptionManager;
 use sc_client_api::{
 	backend::{AuxStore, Backend, StateBackend, StorageProvider},
 	client::BlockchainEvents,
+	BlockBackend, ProofProvider,
 };
 #[cfg(feature = "manual-seal")]
 use sc_consensus_manual_seal::rpc::{ManualSeal, ManualSealApi};
@@ -21,16 +22,42 @@ use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 use sp_runtime::traits::BlakeTwo256;
 // Frontier
 use fc_rpc::{
-	EthBlockDataCacheTask, OverrideHandle, RuntimeApiStorageOverride, SchemaV1Override,
-	SchemaV2Override, SchemaV3Override, StorageOverride,
+	BundlerConfig, EthBlockDataCacheTask, EthKeystoreSigner, EthPubSubLimits, ExecutionPool,
+	OverrideHandle, RuntimeApiStorageOverride, SchemaV1Override, SchemaV2Override,
+	SchemaV3Override, StorageOverride, TraceLimits,
+};
+use fc_rpc_core::types::{
+	DroppedTransactionCache, FeeHistoryCache, FeeHistoryCacheLimit, FilterPool,
 };
-use fc_rpc_core::types::{FeeHistoryCache, FeeHistoryCacheLimit, FilterPool};
 use fp_storage::EthereumStorageSchema;
 // Runtime
 use frontier_template_runtime::{opaque::Block, AccountId, Balance, Hash, Index};
 
+use crate::cli::EthRpcNamespace;
+
+/// Ethereum RPC namespaces exposed on public and private transports.
+#[derive(Clone)]
+pub struct EthRpcExposure {
+	/// Namespaces exposed on transports denying unsafe methods.
+	pub public: Vec<EthRpcNamespace>,
+	/// Namespaces exposed on transports allowing unsafe methods.
+	pub private: Vec<EthRpcNamespace>,
+}
+
+impl EthRpcExposure {
+	/// Whether a namespace is exposed on a transport.
+	pub fn is_exposed(&self, namespace: EthRpcNamespace, deny_unsafe: DenyUnsafe) -> bool {
+		let namespaces = if deny_unsafe.check_if_safe().is_ok() {
+			&self.private
+		} else {
+			&self.public
+		};
+		namespaces.contains(&namespace)
+	}
+}
+
 /// Full client dependencies.
-pub struct FullDeps<C, P, A: ChainApi> {
+pub struct FullDeps<C, P, A: ChainApi, BE> {
 	/// The client instance to use.
 	pub client: Arc<C>,
 	/// Transaction pool instance.
@@ -43,12 +70,20 @@ pub struct FullDeps<C, P, A: ChainApi> {
 	pub is_authority: bool,
 	/// Whether to enable dev signer
 	pub enable_dev_signer: bool,
+	/// Signer of the keystore accounts, used on transports allowing unsafe methods
+	pub keystore_signer: Option<EthKeystoreSigner>,
+	/// Whether to enable the Engine API
+	pub enable_engine_api: bool,
+	/// Ethereum RPC namespaces exposed on this transport
+	pub eth_rpc_exposure: EthRpcExposure,
 	/// Network service
 	pub network: Arc<NetworkService<Block, Hash>>,
 	/// EthFilterApi pool.
 	pub filter_pool: Option<FilterPool>,
 	/// Backend.
 	pub backend: Arc<fc_db::Backend<Block>>,
+	/// Substrate backend, to replay the transactions of the trace RPC.
+	pub substrate_backend: Arc<BE>,
 	/// Maximum number of logs in a query.
 	pub max_past_logs: u32,
 	/// Fee history cache.
@@ -59,6 +94,18 @@ pub struct FullDeps<C, P, A: ChainApi> {
 	pub overrides: Arc<OverrideHandle<Block>>,
 	/// Cache for Ethereum block data.
 	pub block_data_cache: Arc<EthBlockDataCacheTask<Block>>,
+	/// Statuses of the transactions recently dropped from the pool.
+	pub dropped_transactions: DroppedTransactionCache,
+	/// Threads executing `eth_call`, `eth_estimateGas` and `eth_simulateV1`.
+	pub execution_pool: ExecutionPool,
+	/// Maximum gas available to read-only executions.
+	pub gas_cap: Option<u64>,
+	/// Limits of the Ethereum subscriptions.
+	pub pubsub_limits: EthPubSubLimits,
+	/// Limits of the traces.
+	pub trace_limits: TraceLimits,
+	/// Configuration of the ERC-4337 bundler, if enabled.
+	pub bundler: Option<BundlerConfig>,
 	/// Manual seal command sink
 	#[cfg(feature = "manual-seal")]
 	pub command_sink:
@@ -101,27 +148,31 @@ where
 
 /// Instantiate all Full RPC extensions.
 pub fn create_full<C, P, BE, A>(
-	deps: FullDeps<C, P, A>,
+	deps: FullDeps<C, P, A, BE>,
 	subscription_task_executor: SubscriptionTaskExecutor,
 ) -> jsonrpc_core::IoHandler<sc_rpc::Metadata>
 where
 	BE: Backend<Block> + 'static,
 	BE::State: StateBackend<BlakeTwo256>,
 	C: ProvideRuntimeApi<Block> + StorageProvider<Block, BE> + AuxStore,
-	C: BlockchainEvents<Block>,
+	C: BlockBackend<Block> + BlockchainEvents<Block> + ProofProvider<Block>,
 	C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError>,
 	C: Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
 	C::Api: BlockBuilder<Block>,
+	C::Api: sp_api::ApiExt<Block, StateBackend = BE::State>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+	C::Api: fp_rpc::ContractMetadataRuntimeApi<Block>,
 	C::Api: fp_rpc::ConvertTransactionRuntimeApi<Block>,
 	C::Api: fp_rpc::EthereumRuntimeRPCApi<Block>,
 	P: TransactionPool<Block = Block> + 'static,
 	A: ChainApi<Block = Block> + 'static,
 {
 	use fc_rpc::{
-		Eth, EthApi, EthDevSigner, EthFilter, EthFilterApi, EthPubSub, EthPubSubApi, EthSigner,
-		HexEncodedIdProvider, Net, NetApi, Web3, Web3Api,
+		Bundler, BundlerApi, Debug, DebugApi, Engine, EngineApi, Eth, EthApi, EthDevSigner,
+		EthFilter, EthFilterApi, EthPubSub, EthPubSubApi, EthSigner, ExtendedBlockApi,
+		ExtendedBlocks, Frontier, FrontierApi, HexEncodedIdProvider, Metadata, MetadataApi, Net,
+		NetApi, StateExport, StateExportApi, Stats, StatsApi, Trace, TraceApi, Web3, Web3Api,
 	};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
 	use substrate_frame_rpc_system::{FullSystem, SystemApi};
@@ -134,14 +185,24 @@ where
 		deny_unsafe,
 		is_authority,
 		enable_dev_signer,
+		keystore_signer,
+		enable_engine_api,
+		eth_rpc_exposure,
 		network,
 		filter_pool,
 		backend,
+		substrate_backend,
 		max_past_logs,
 		fee_history_cache,
 		fee_history_cache_limit,
 		overrides,
 		block_data_cache,
+		dropped_transactions,
+		execution_pool,
+		gas_cap,
+		pubsub_limits,
+		trace_limits,
+		bundler,
 		#[cfg(feature = "manual-seal")]
 		command_sink,
 	} = deps;
@@ -155,56 +216,149 @@ where
 		client.clone(),
 	)));
 
-	let mut signers = Vec::new();
-	if enable_dev_signer {
-		signers.push(Box::new(EthDevSigner::new()) as Box<dyn EthSigner>);
+	let signers = || {
+		let mut signers = Vec::new();
+		if enable_dev_signer {
+			signers.push(Box::new(EthDevSigner::with_keys(
+				crate::chain_spec::dev_account_keys(),
+			)) as Box<dyn EthSigner>);
+			signers.push(Box::new(EthDevSigner::new()) as Box<dyn EthSigner>);
+		}
+		if let Some(keystore_signer) = keystore_signer.clone() {
+			if deny_unsafe.check_if_safe().is_ok() {
+				signers.push(Box::new(keystore_signer) as Box<dyn EthSigner>);
+			}
+		}
+		signers
+	};
+
+	let is_exposed = |namespace| eth_rpc_exposure.is_exposed(namespace, deny_unsafe);
+
+	if is_exposed(EthRpcNamespace::Eth) {
+		io.extend_with(EthApi::to_delegate(Eth::new(
+			client.clone(),
+			pool.clone(),
+			graph.clone(),
+			Some(frontier_template_runtime::TransactionConverter),
+			network.clone(),
+			signers(),
+			overrides.clone(),
+			backend.clone(),
+			is_authority,
+			block_data_cache.clone(),
+			fee_history_cache,
+			fee_history_cache_limit,
+			execution_pool.clone(),
+			gas_cap,
+		)));
 	}
 
-	io.extend_with(EthApi::to_delegate(Eth::new(
-		client.clone(),
-		pool.clone(),
-		graph,
-		Some(frontier_template_runtime::TransactionConverter),
-		network.clone(),
-		signers,
-		overrides.clone(),
-		backend.clone(),
-		is_authority,
-		block_data_cache.clone(),
-		fee_history_cache,
-		fee_history_cache_limit,
-	)));
+	if let Some(bundler) = bundler.filter(|_| is_exposed(EthRpcNamespace::Bundler)) {
+		io.extend_with(BundlerApi::to_delegate(Bundler::new(
+			client.clone(),
+			pool.clone(),
+			graph.clone(),
+			Some(frontier_template_runtime::TransactionConverter),
+			signers(),
+			backend.clone(),
+			overrides.clone(),
+			block_data_cache.clone(),
+			execution_pool.clone(),
+			bundler,
+		)));
+	}
+
+	if enable_engine_api && is_exposed(EthRpcNamespace::Engine) {
+		io.extend_with(EngineApi::to_delegate(Engine::new(
+			client.clone(),
+			backend.clone(),
+			block_data_cache.clone(),
+		)));
+	}
 
-	if let Some(filter_pool) = filter_pool {
+	if let Some(filter_pool) = filter_pool
+		.clone()
+		.filter(|_| is_exposed(EthRpcNamespace::EthFilter))
+	{
 		io.extend_with(EthFilterApi::to_delegate(EthFilter::new(
 			client.clone(),
-			backend,
+			backend.clone(),
 			filter_pool,
 			500, // max stored filters
 			max_past_logs,
-			block_data_cache,
+			block_data_cache.clone(),
 		)));
 	}
 
-	io.extend_with(NetApi::to_delegate(Net::new(
-		client.clone(),
-		network.clone(),
-		// Whether to format the `peer_count` response as Hex (default) or not.
-		true,
-	)));
+	if is_exposed(EthRpcNamespace::Net) {
+		io.extend_with(NetApi::to_delegate(Net::new(
+			client.clone(),
+			network.clone(),
+			// Whether to format the `peer_count` response as Hex (default) or not.
+			true,
+		)));
+	}
 
-	io.extend_with(Web3Api::to_delegate(Web3::new(client.clone())));
+	if is_exposed(EthRpcNamespace::Web3) {
+		io.extend_with(Web3Api::to_delegate(Web3::new(client.clone())));
+	}
 
-	io.extend_with(EthPubSubApi::to_delegate(EthPubSub::new(
-		pool,
-		client,
-		network,
-		SubscriptionManager::<HexEncodedIdProvider>::with_id_provider(
-			HexEncodedIdProvider::default(),
-			Arc::new(subscription_task_executor),
-		),
-		overrides,
-	)));
+	if is_exposed(EthRpcNamespace::Frontier) {
+		io.extend_with(FrontierApi::to_delegate(Frontier::new(
+			client.clone(),
+			backend.clone(),
+			dropped_transactions,
+		)));
+		io.extend_with(MetadataApi::to_delegate(Metadata::new(
+			client.clone(),
+			backend.clone(),
+		)));
+		io.extend_with(ExtendedBlockApi::to_delegate(ExtendedBlocks::new(
+			client.clone(),
+			backend.clone(),
+			overrides.clone(),
+			block_data_cache.clone(),
+		)));
+		io.extend_with(DebugApi::to_delegate(Debug::new(
+			client.clone(),
+			backend.clone(),
+			overrides.clone(),
+		)));
+		io.extend_with(StateExportApi::to_delegate(StateExport::new(
+			client.clone(),
+			backend.clone(),
+		)));
+		// The traces and the statistics are only exposed to operators.
+		if deny_unsafe.check_if_safe().is_ok() {
+			io.extend_with(TraceApi::to_delegate(Trace::new(
+				client.clone(),
+				substrate_backend,
+				backend.clone(),
+				trace_limits,
+			)));
+			io.extend_with(StatsApi::to_delegate(Stats::new(
+				client.clone(),
+				backend,
+				graph,
+				block_data_cache,
+				filter_pool,
+			)));
+		}
+	}
+
+	if is_exposed(EthRpcNamespace::EthPubSub) {
+		io.extend_with(EthPubSubApi::to_delegate(EthPubSub::new(
+			pool,
+			client,
+			network,
+			SubscriptionManager::<HexEncodedIdProvider>::with_id_provider(
+				HexEncodedIdProvider::default(),
+				Arc::new(subscription_task_executor),
+			),
+			overrides,
+			pubsub_limits,
+		)));
+	}
 
 	#[cfg(feature = "manual-seal")]
 	if let Some(command_sink) = command_sink {
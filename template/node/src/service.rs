@@ -20,13 +20,15 @@ use fc_consensus::FrontierBlockImport;
 use fc_db::DatabaseSource;
 use fc_mapping_sync::{MappingSyncWorker, SyncStrategy};
 use fc_rpc::{EthTask, OverrideHandle};
-use fc_rpc_core::types::{FeeHistoryCache, FeeHistoryCacheLimit, FilterPool};
+use fc_rpc_core::types::{
+	DroppedTransactionCache, DroppedTransactions, FeeHistoryCache, FeeHistoryCacheLimit, FilterPool,
+};
 // Runtime
 use frontier_template_runtime::{opaque::Block, RuntimeApi};
 
-use crate::cli::Cli;
 #[cfg(feature = "manual-seal")]
 use crate::cli::Sealing;
+use crate::cli::{Cli, EthConfiguration, FrontierTxIndex};
 
 // Our native executor instance.
 pub struct ExecutorDispatch;
@@ -81,7 +83,39 @@ pub fn frontier_database_dir(config: &Configuration, path: &str) -> std::path::P
 	config_dir.join("frontier").join(path)
 }
 
-pub fn open_frontier_backend(config: &Configuration) -> Result<Arc<fc_db::Backend<Block>>, String> {
+pub fn open_frontier_backend(
+	config: &Configuration,
+	eth_config: &EthConfiguration,
+) -> Result<Arc<fc_db::Backend<Block>>, String> {
+	let transaction_indexing = match eth_config.frontier_tx_index {
+		FrontierTxIndex::Off => fc_db::TransactionIndexing::Off,
+		FrontierTxIndex::Lazy => fc_db::TransactionIndexing::Lazy {
+			depth: eth_config.frontier_tx_index_depth,
+		},
+		FrontierTxIndex::Full => fc_db::TransactionIndexing::Full,
+	};
+	if let Some(path) = &eth_config.frontier_db_replica_of {
+		return Ok(Arc::new(fc_db::Backend::<Block>::new(
+			&fc_db::DatabaseSettings {
+				source: DatabaseSource::RocksDb {
+					path: path.clone(),
+					cache_size: 0,
+				},
+				transaction_indexing,
+				archive_raw_transactions: eth_config.frontier_raw_tx_archive,
+				allow_large_migrations: false,
+				replica_path: Some(frontier_database_dir(config, "replica")),
+			},
+		)?));
+	}
+	// The database of a temporary node is removed on exit anyway.
+	if let Some(BasePath::Temporary(_)) = config.base_path {
+		return Ok(Arc::new(fc_db::Backend::<Block>::new_in_memory(
+			transaction_indexing,
+			eth_config.frontier_raw_tx_archive,
+		)));
+	}
+
 	Ok(Arc::new(fc_db::Backend::<Block>::new(
 		&fc_db::DatabaseSettings {
 			source: match config.database {
@@ -92,15 +126,38 @@ pub fn open_frontier_backend(config: &Configuration) -> Result<Arc<fc_db::Backen
 				DatabaseSource::ParityDb { .. } => DatabaseSource::ParityDb {
 					path: frontier_database_dir(config, "paritydb"),
 				},
-				DatabaseSource::Auto { .. } => DatabaseSource::Auto {
-					rocksdb_path: frontier_database_dir(config, "db"),
-					paritydb_path: frontier_database_dir(config, "paritydb"),
-					cache_size: 0,
-				},
+				DatabaseSource::Auto {
+					rocksdb_path: ref chain_rocksdb_path,
+					..
+				} => {
+					let rocksdb_path = frontier_database_dir(config, "db");
+					let paritydb_path = frontier_database_dir(config, "paritydb");
+					// A new Frontier database uses the engine of the chain database, which is
+					// RocksDB when it already existed.
+					if !rocksdb_path.exists()
+						&& !paritydb_path.exists()
+						&& chain_rocksdb_path.exists()
+					{
+						DatabaseSource::RocksDb {
+							path: rocksdb_path,
+							cache_size: 0,
+						}
+					} else {
+						DatabaseSource::Auto {
+							rocksdb_path,
+							paritydb_path,
+							cache_size: 0,
+						}
+					}
+				}
 				_ => {
 					return Err("Supported db sources: `rocksdb` | `paritydb` | `auto`".to_string())
 				}
 			},
+			transaction_indexing,
+			archive_raw_transactions: eth_config.frontier_raw_tx_archive,
+			allow_large_migrations: eth_config.frontier_db_migrate,
+			replica_path: None,
 		},
 	)?))
 }
@@ -174,10 +231,10 @@ pub fn new_partial(
 		client.clone(),
 	);
 
-	let frontier_backend = open_frontier_backend(config)?;
+	let frontier_backend = open_frontier_backend(config, &cli.run.eth)?;
 	let filter_pool: Option<FilterPool> = Some(Arc::new(Mutex::new(BTreeMap::new())));
 	let fee_history_cache: FeeHistoryCache = Arc::new(Mutex::new(BTreeMap::new()));
-	let fee_history_cache_limit: FeeHistoryCacheLimit = cli.run.fee_history_limit;
+	let fee_history_cache_limit: FeeHistoryCacheLimit = cli.run.eth.fee_history_limit;
 
 	#[cfg(feature = "aura")]
 	{
@@ -195,6 +252,8 @@ pub fn new_partial(
 			grandpa_block_import.clone(),
 			client.clone(),
 			frontier_backend.clone(),
+			// The blocks of a replica are mapped by its writer.
+			!frontier_backend.is_read_only(),
 		);
 
 		let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
@@ -247,8 +306,13 @@ pub fn new_partial(
 	{
 		let sealing = cli.run.sealing;
 
-		let frontier_block_import =
-			FrontierBlockImport::new(client.clone(), client.clone(), frontier_backend.clone());
+		let frontier_block_import = FrontierBlockImport::new(
+			client.clone(),
+			client.clone(),
+			frontier_backend.clone(),
+			// The blocks of a replica are mapped by its writer.
+			!frontier_backend.is_read_only(),
+		);
 
 		let import_queue = sc_consensus_manual_seal::import_queue(
 			Box::new(frontier_block_import.clone()),
@@ -368,6 +432,9 @@ pub fn new_full(mut config: Configuration, cli: &Cli) -> Result<TaskManager, Ser
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
 	let overrides = crate::rpc::overrides_handle(client.clone());
+	let dropped_transactions: DroppedTransactionCache = Arc::new(Mutex::new(
+		DroppedTransactions::new(cli.run.eth.dropped_transactions_limit),
+	));
 	let block_data_cache = Arc::new(fc_rpc::EthBlockDataCacheTask::new(
 		task_manager.spawn_handle(),
 		overrides.clone(),
@@ -375,18 +442,67 @@ pub fn new_full(mut config: Configuration, cli: &Cli) -> Result<TaskManager, Ser
 		50,
 		prometheus_registry.clone(),
 	));
+	let pubsub_limits = fc_rpc::EthPubSubLimits::new(
+		cli.run.eth.max_subscriptions_per_connection,
+		cli.run.eth.subscription_buffer_size,
+		prometheus_registry.clone(),
+	);
+	let execution_pool = fc_rpc::ExecutionPool::new(
+		cli.run.eth.execution_threads,
+		match cli.run.eth.execution_timeout {
+			0 => None,
+			timeout => Some(Duration::from_secs(timeout)),
+		},
+	);
 
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
 		let is_authority = role.is_authority();
-		let enable_dev_signer = cli.run.enable_dev_signer;
+		let enable_dev_signer = cli.run.eth.enable_dev_signer || cli.run.base.shared_params.dev;
+		let keystore_signer = cli.run.eth.enable_keystore_signer.then(|| {
+			let allowlist = &cli.run.eth.keystore_signer_allowlist;
+			fc_rpc::EthKeystoreSigner::new(
+				keystore_container.sync_keystore(),
+				(!allowlist.is_empty()).then(|| allowlist.clone()),
+			)
+		});
+		let enable_engine_api = cli.run.eth.enable_engine_api;
+		let eth_rpc_exposure = crate::rpc::EthRpcExposure {
+			public: cli.run.eth.eth_rpc_public.clone(),
+			private: cli.run.eth.eth_rpc_private.clone(),
+		};
 		let network = network.clone();
 		let filter_pool = filter_pool.clone();
+		let backend = backend.clone();
 		let frontier_backend = frontier_backend.clone();
 		let overrides = overrides.clone();
 		let fee_history_cache = fee_history_cache.clone();
-		let max_past_logs = cli.run.max_past_logs;
+		let dropped_transactions = dropped_transactions.clone();
+		let execution_pool = execution_pool.clone();
+		let pubsub_limits = pubsub_limits.clone();
+		let gas_cap = match cli.run.eth.rpc_gas_cap {
+			0 => None,
+			gas_cap => Some(gas_cap),
+		};
+		let max_past_logs = cli.run.eth.max_past_logs;
+		let trace_limits = fc_rpc::TraceLimits {
+			max_entries: cli.run.eth.trace_max_entries,
+			timeout: match cli.run.eth.trace_timeout {
+				0 => None,
+				timeout => Some(Duration::from_secs(timeout)),
+			},
+		};
+		let bundler = cli
+			.run
+			.eth
+			.bundler_entry_point
+			.zip(cli.run.eth.bundler_account)
+			.map(|(entry_point, account)| fc_rpc::BundlerConfig {
+				entry_point,
+				account,
+				receipt_lookup_depth: cli.run.eth.bundler_receipt_lookup_depth,
+			});
 		let subscription_task_executor =
 			sc_rpc::SubscriptionTaskExecutor::new(task_manager.spawn_handle());
 
@@ -398,14 +514,24 @@ pub fn new_full(mut config: Configuration, cli: &Cli) -> Result<TaskManager, Ser
 				deny_unsafe,
 				is_authority,
 				enable_dev_signer,
+				keystore_signer: keystore_signer.clone(),
+				enable_engine_api,
+				eth_rpc_exposure: eth_rpc_exposure.clone(),
 				network: network.clone(),
 				filter_pool: filter_pool.clone(),
 				backend: frontier_backend.clone(),
+				substrate_backend: backend.clone(),
 				max_past_logs,
 				fee_history_cache: fee_history_cache.clone(),
 				fee_history_cache_limit,
 				overrides: overrides.clone(),
 				block_data_cache: block_data_cache.clone(),
+				dropped_transactions: dropped_transactions.clone(),
+				execution_pool: execution_pool.clone(),
+				gas_cap,
+				pubsub_limits: pubsub_limits.clone(),
+				trace_limits,
+				bundler,
 			};
 
 			Ok(crate::rpc::create_full(
@@ -432,11 +558,13 @@ pub fn new_full(mut config: Configuration, cli: &Cli) -> Result<TaskManager, Ser
 		&task_manager,
 		client.clone(),
 		backend,
+		transaction_pool.clone(),
 		frontier_backend,
 		filter_pool,
 		overrides,
 		fee_history_cache,
 		fee_history_cache_limit,
+		dropped_transactions,
 	);
 
 	let (block_import, grandpa_link) = consensus_result;
@@ -595,6 +723,9 @@ pub fn new_full(config: Configuration, cli: &Cli) -> Result<TaskManager, Service
 	let role = config.role.clone();
 	let prometheus_registry = config.prometheus_registry().cloned();
 	let overrides = crate::rpc::overrides_handle(client.clone());
+	let dropped_transactions: DroppedTransactionCache = Arc::new(Mutex::new(
+		DroppedTransactions::new(cli.run.eth.dropped_transactions_limit),
+	));
 	let block_data_cache = Arc::new(fc_rpc::EthBlockDataCacheTask::new(
 		task_manager.spawn_handle(),
 		overrides.clone(),
@@ -602,6 +733,18 @@ pub fn new_full(config: Configuration, cli: &Cli) -> Result<TaskManager, Service
 		50,
 		prometheus_registry.clone(),
 	));
+	let pubsub_limits = fc_rpc::EthPubSubLimits::new(
+		cli.run.eth.max_subscriptions_per_connection,
+		cli.run.eth.subscription_buffer_size,
+		prometheus_registry.clone(),
+	);
+	let execution_pool = fc_rpc::ExecutionPool::new(
+		cli.run.eth.execution_threads,
+		match cli.run.eth.execution_timeout {
+			0 => None,
+			timeout => Some(Duration::from_secs(timeout)),
+		},
+	);
 	// Channel for the rpc handler to communicate with the authorship task.
 	let (command_sink, commands_stream) = futures::channel::mpsc::channel(1000);
 
@@ -609,13 +752,50 @@ pub fn new_full(config: Configuration, cli: &Cli) -> Result<TaskManager, Service
 		let client = client.clone();
 		let pool = transaction_pool.clone();
 		let is_authority = role.is_authority();
-		let enable_dev_signer = cli.run.enable_dev_signer;
+		let enable_dev_signer = cli.run.eth.enable_dev_signer || cli.run.base.shared_params.dev;
+		let keystore_signer = cli.run.eth.enable_keystore_signer.then(|| {
+			let allowlist = &cli.run.eth.keystore_signer_allowlist;
+			fc_rpc::EthKeystoreSigner::new(
+				keystore_container.sync_keystore(),
+				(!allowlist.is_empty()).then(|| allowlist.clone()),
+			)
+		});
+		let enable_engine_api = cli.run.eth.enable_engine_api;
+		let eth_rpc_exposure = crate::rpc::EthRpcExposure {
+			public: cli.run.eth.eth_rpc_public.clone(),
+			private: cli.run.eth.eth_rpc_private.clone(),
+		};
 		let network = network.clone();
 		let filter_pool = filter_pool.clone();
+		let backend = backend.clone();
 		let frontier_backend = frontier_backend.clone();
 		let overrides = overrides.clone();
 		let fee_history_cache = fee_history_cache.clone();
-		let max_past_logs = cli.run.max_past_logs;
+		let dropped_transactions = dropped_transactions.clone();
+		let execution_pool = execution_pool.clone();
+		let pubsub_limits = pubsub_limits.clone();
+		let gas_cap = match cli.run.eth.rpc_gas_cap {
+			0 => None,
+			gas_cap => Some(gas_cap),
+		};
+		let max_past_logs = cli.run.eth.max_past_logs;
+		let trace_limits = fc_rpc::TraceLimits {
+			max_entries: cli.run.eth.trace_max_entries,
+			timeout: match cli.run.eth.trace_timeout {
+				0 => None,
+				timeout => Some(Duration::from_secs(timeout)),
+			},
+		};
+		let bundler = cli
+			.run
+			.eth
+			.bundler_entry_point
+			.zip(cli.run.eth.bundler_account)
+			.map(|(entry_point, account)| fc_rpc::BundlerConfig {
+				entry_point,
+				account,
+				receipt_lookup_depth: cli.run.eth.bundler_receipt_lookup_depth,
+			});
 		let subscription_task_executor =
 			sc_rpc::SubscriptionTaskExecutor::new(task_manager.spawn_handle());
 
@@ -627,14 +807,24 @@ pub fn new_full(config: Configuration, cli: &Cli) -> Result<TaskManager, Service
 				deny_unsafe,
 				is_authority,
 				enable_dev_signer,
+				keystore_signer: keystore_signer.clone(),
+				enable_engine_api,
+				eth_rpc_exposure: eth_rpc_exposure.clone(),
 				network: network.clone(),
 				filter_pool: filter_pool.clone(),
 				backend: frontier_backend.clone(),
+				substrate_backend: backend.clone(),
 				max_past_logs,
 				fee_history_cache: fee_history_cache.clone(),
 				fee_history_cache_limit,
 				overrides: overrides.clone(),
 				block_data_cache: block_data_cache.clone(),
+				dropped_transactions: dropped_transactions.clone(),
+				execution_pool: execution_pool.clone(),
+				gas_cap,
+				pubsub_limits: pubsub_limits.clone(),
+				trace_limits,
+				bundler,
 				command_sink: Some(command_sink.clone()),
 			};
 
@@ -662,11 +852,13 @@ pub fn new_full(config: Configuration, cli: &Cli) -> Result<TaskManager, Service
 		&task_manager,
 		client.clone(),
 		backend,
+		transaction_pool.clone(),
 		frontier_backend,
 		filter_pool,
 		overrides,
 		fee_history_cache,
 		fee_history_cache_limit,
+		dropped_transactions,
 	);
 
 	if role.is_authority() {
@@ -757,27 +949,33 @@ fn spawn_frontier_tasks(
 	task_manager: &TaskManager,
 	client: Arc<FullClient>,
 	backend: Arc<FullBackend>,
+	transaction_pool: Arc<sc_transaction_pool::FullPool<Block, FullClient>>,
 	frontier_backend: Arc<fc_db::Backend<Block>>,
 	filter_pool: Option<FilterPool>,
 	overrides: Arc<OverrideHandle<Block>>,
 	fee_history_cache: FeeHistoryCache,
 	fee_history_cache_limit: FeeHistoryCacheLimit,
+	dropped_transactions: DroppedTransactionCache,
 ) {
-	task_manager.spawn_essential_handle().spawn(
-		"frontier-mapping-sync-worker",
-		None,
-		MappingSyncWorker::new(
-			client.import_notification_stream(),
-			Duration::new(6, 0),
-			client.clone(),
-			backend,
-			frontier_backend.clone(),
-			3,
-			0,
-			SyncStrategy::Normal,
-		)
-		.for_each(|()| future::ready(())),
-	);
+	// The blocks of a replica are mapped by its writer.
+	if !frontier_backend.is_read_only() {
+		task_manager.spawn_essential_handle().spawn(
+			"frontier-mapping-sync-worker",
+			None,
+			MappingSyncWorker::new(
+				client.import_notification_stream(),
+				Duration::new(6, 0),
+				client.clone(),
+				backend,
+				frontier_backend.clone(),
+				overrides.clone(),
+				3,
+				0,
+				SyncStrategy::Normal,
+			)
+			.for_each(|()| future::ready(())),
+		);
+	}
 
 	// Spawn Frontier EthFilterApi maintenance task.
 	if let Some(filter_pool) = filter_pool {
@@ -797,11 +995,24 @@ fn spawn_frontier_tasks(
 		EthTask::fee_history_task(
 			client.clone(),
 			overrides,
+			frontier_backend.clone(),
 			fee_history_cache,
 			fee_history_cache_limit,
 		),
 	);
 
+	// Spawn Frontier dropped transactions tracking task.
+	task_manager.spawn_essential_handle().spawn(
+		"frontier-dropped-transactions",
+		None,
+		EthTask::dropped_transactions_task(
+			client.clone(),
+			transaction_pool.clone(),
+			transaction_pool.pool().clone(),
+			dropped_transactions,
+		),
+	);
+
 	task_manager.spawn_essential_handle().spawn(
 		"frontier-schema-cache-task",
 		None,
@@ -310,6 +310,34 @@ impl<F: FindAuthor<u32>> FindAuthor<H160> for FindAuthorTruncated<F> {
 	}
 }
 
+/// Finds the Aura key of the block author.
+pub struct FindAuraAuthorId;
+impl FindAuthor<AuraId> for FindAuraAuthorId {
+	fn find_author<'a, I>(digests: I) -> Option<AuraId>
+	where
+		I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+	{
+		let author_index = Aura::find_author(digests)?;
+		Aura::authorities().get(author_index as usize).cloned()
+	}
+}
+
+/// Finds the author account registered for the Aura key of the block author, or derives one
+/// from its Aura key if it has none.
+pub struct FindAuthorMappedOrTruncated;
+impl FindAuthor<H160> for FindAuthorMappedOrTruncated {
+	fn find_author<'a, I>(digests: I) -> Option<H160>
+	where
+		I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+	{
+		let digests = digests.into_iter().collect::<Vec<_>>();
+		pallet_author_mapping::FindMappedAuthor::<Runtime, FindAuraAuthorId>::find_author(
+			digests.iter().map(|(id, data)| (*id, *data)),
+		)
+		.or_else(|| FindAuthorTruncated::<Aura>::find_author(digests))
+	}
+}
+
 pub struct FixedGasWeightMapping;
 impl GasWeightMapping for FixedGasWeightMapping {
 	fn gas_to_weight(gas: u64) -> Weight {
@@ -341,12 +369,30 @@ impl pallet_evm::Config for Runtime {
 	type ChainId = ChainId;
 	type BlockGasLimit = BlockGasLimit;
 	type OnChargeTransaction = ();
-	type FindAuthor = FindAuthorTruncated<Aura>;
+	type FindAuthor = FindAuthorMappedOrTruncated;
+
+	fn precompile_addresses() -> Vec<H160> {
+		FrontierPrecompiles::<Self>::used_addresses()
+	}
+
+	fn fee_sponsor(source: &H160, target: &H160) -> Option<(H160, Permill)> {
+		GasSubsidy::fee_sponsor(source, target)
+	}
+
+	fn on_fee_sponsored(sponsor: &H160, source: &H160, target: &H160, fee: U256) {
+		GasSubsidy::on_fee_sponsored(sponsor, source, target, fee)
+	}
 }
 
 impl pallet_ethereum::Config for Runtime {
 	type Event = Event;
 	type StateRoot = pallet_ethereum::IntermediateStateRoot<Self>;
+	type MaxBatchTransactions = ConstU32<64>;
+	type TransactionOrdering = ();
+
+	fn max_future_transactions_per_sender() -> Option<u32> {
+		Some(64)
+	}
 }
 
 frame_support::parameter_types! {
@@ -382,6 +428,32 @@ impl pallet_base_fee::Config for Runtime {
 	type DefaultBaseFeePerGas = DefaultBaseFeePerGas;
 }
 
+frame_support::parameter_types! {
+	pub const MaxMetadataLength: u32 = 128;
+}
+
+impl pallet_contract_metadata::Config for Runtime {
+	type Event = Event;
+	type DeployerOrigin = EnsureAddressTruncated;
+	type MaxMetadataLength = MaxMetadataLength;
+}
+
+impl pallet_gas_subsidy::Config for Runtime {
+	type Event = Event;
+}
+
+frame_support::parameter_types! {
+	pub const AuthorMappingDeposit: Balance = 1_000_000_000_000_000_000;
+}
+
+impl pallet_author_mapping::Config for Runtime {
+	type Event = Event;
+	type AuthorityId = AuraId;
+	type Currency = Balances;
+	type Deposit = AuthorMappingDeposit;
+	type WeightInfo = pallet_author_mapping::weights::SubstrateWeight<Runtime>;
+}
+
 impl pallet_randomness_collective_flip::Config for Runtime {}
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -403,6 +475,9 @@ construct_runtime!(
 		EVM: pallet_evm::{Pallet, Config, Call, Storage, Event<T>},
 		DynamicFee: pallet_dynamic_fee::{Pallet, Call, Storage, Config, Inherent},
 		BaseFee: pallet_base_fee::{Pallet, Call, Storage, Config<T>, Event},
+		ContractMetadata: pallet_contract_metadata::{Pallet, Call, Storage, Event},
+		AuthorMapping: pallet_author_mapping::{Pallet, Call, Storage, Event<T>},
+		GasSubsidy: pallet_gas_subsidy::{Pallet, Call, Storage, Event<T>},
 	}
 );
 
@@ -450,6 +525,7 @@ pub type SignedExtra = (
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
 	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	pallet_ethereum::CheckBatchTransactions<Runtime>,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic =
@@ -469,6 +545,8 @@ pub type Executive = frame_executive::Executive<
 
 impl fp_self_contained::SelfContainedCall for Call {
 	type SignedInfo = H160;
+	// The Ethereum pallet already checks the weight of its transactions.
+	type Extensions = ();
 
 	fn is_self_contained(&self) -> bool {
 		match self {
@@ -499,9 +577,11 @@ impl fp_self_contained::SelfContainedCall for Call {
 	fn pre_dispatch_self_contained(
 		&self,
 		info: &Self::SignedInfo,
+		dispatch_info: &DispatchInfoOf<Call>,
+		len: usize,
 	) -> Option<Result<(), TransactionValidityError>> {
 		match self {
-			Call::Ethereum(call) => call.pre_dispatch_self_contained(info),
+			Call::Ethereum(call) => call.pre_dispatch_self_contained(info, dispatch_info, len),
 			_ => None,
 		}
 	}
@@ -525,7 +605,10 @@ extern crate frame_benchmarking;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benches {
-	define_benchmarks!([pallet_evm, EVM]);
+	define_benchmarks!(
+		[pallet_evm, EVM]
+		[pallet_author_mapping, AuthorMapping]
+	);
 }
 
 impl_runtime_apis! {
@@ -621,6 +704,21 @@ impl_runtime_apis! {
 			EVM::account_codes(address)
 		}
 
+		fn accounts_basic(addresses: Vec<H160>) -> Vec<EVMAccount> {
+			addresses
+				.iter()
+				.map(|address| EVM::account_basic(address).0)
+				.collect()
+		}
+
+		fn accounts_code_at(addresses: Vec<H160>) -> Vec<Vec<u8>> {
+			addresses.into_iter().map(EVM::account_codes).collect()
+		}
+
+		fn intrinsic_gas(transaction: EthereumTransaction) -> u64 {
+			Ethereum::intrinsic_gas(&transaction)
+		}
+
 		fn author() -> H160 {
 			<pallet_evm::Pallet<Runtime>>::find_author()
 		}
@@ -643,13 +741,8 @@ impl_runtime_apis! {
 			estimate: bool,
 			access_list: Option<Vec<(H160, Vec<H256>)>>,
 		) -> Result<pallet_evm::CallInfo, sp_runtime::DispatchError> {
-			let config = if estimate {
-				let mut config = <Runtime as pallet_evm::Config>::config().clone();
-				config.estimate = true;
-				Some(config)
-			} else {
-				None
-			};
+			let mut config = pallet_evm::Pallet::<Runtime>::evm_config();
+			config.estimate = estimate;
 
 			let is_transactional = false;
 			<Runtime as pallet_evm::Config>::Runner::call(
@@ -663,7 +756,7 @@ impl_runtime_apis! {
 				nonce,
 				access_list.unwrap_or_default(),
 				is_transactional,
-				config.as_ref().unwrap_or(<Runtime as pallet_evm::Config>::config()),
+				&config,
 			).map_err(|err| err.error.into())
 		}
 
@@ -678,13 +771,8 @@ impl_runtime_apis! {
 			estimate: bool,
 			access_list: Option<Vec<(H160, Vec<H256>)>>,
 		) -> Result<pallet_evm::CreateInfo, sp_runtime::DispatchError> {
-			let config = if estimate {
-				let mut config = <Runtime as pallet_evm::Config>::config().clone();
-				config.estimate = true;
-				Some(config)
-			} else {
-				None
-			};
+			let mut config = pallet_evm::Pallet::<Runtime>::evm_config();
+			config.estimate = estimate;
 
 			let is_transactional = false;
 			<Runtime as pallet_evm::Config>::Runner::create(
@@ -697,7 +785,7 @@ impl_runtime_apis! {
 				nonce,
 				access_list.unwrap_or_default(),
 				is_transactional,
-				config.as_ref().unwrap_or(<Runtime as pallet_evm::Config>::config()),
+				&config,
 			).map_err(|err| err.error.into())
 		}
 
@@ -737,6 +825,142 @@ impl_runtime_apis! {
 		fn elasticity() -> Option<Permill> {
 			Some(BaseFee::elasticity())
 		}
+
+		fn pending_block(
+			xts: Vec<<Block as BlockT>::Extrinsic>,
+		) -> (
+			Option<pallet_ethereum::Block>,
+			Option<Vec<pallet_ethereum::Receipt>>,
+			Option<Vec<TransactionStatus>>
+		) {
+			use frame_support::traits::OnFinalize;
+
+			for ext in xts.into_iter() {
+				let _ = Executive::apply_extrinsic(ext);
+			}
+
+			// Build the Ethereum block out of the transactions recorded as pending.
+			Ethereum::on_finalize(System::block_number());
+
+			(
+				Ethereum::current_block(),
+				Ethereum::current_receipts(),
+				Ethereum::current_transaction_statuses()
+			)
+		}
+
+		fn create2_address(deployer: H160, salt: H256, init_code_hash: H256) -> H160 {
+			EVM::create2_address(deployer, salt, init_code_hash)
+		}
+
+		fn override_account(
+			address: H160,
+			account: fp_rpc::AccountOverride,
+		) -> Result<(), sp_runtime::DispatchError> {
+			use frame_support::traits::Currency;
+			use pallet_evm::AddressMapping;
+
+			let balance = account
+				.balance
+				.map(|balance| {
+					Balance::try_from(balance)
+						.map_err(|_| sp_runtime::DispatchError::Other("balance overflow"))
+				})
+				.transpose()?;
+			let nonce = account
+				.nonce
+				.map(|nonce| {
+					Index::try_from(nonce)
+						.map_err(|_| sp_runtime::DispatchError::Other("nonce overflow"))
+				})
+				.transpose()?;
+
+			let account_id =
+				<Runtime as pallet_evm::Config>::AddressMapping::into_account_id(address);
+			if let Some(balance) = balance {
+				Balances::make_free_balance_be(&account_id, balance);
+			}
+			if let Some(nonce) = nonce {
+				frame_system::Account::<Runtime>::mutate(&account_id, |info| {
+					info.nonce = nonce;
+				});
+			}
+			if let Some(code) = account.code {
+				EVM::create_account(address, code);
+			}
+			if let Some(state) = account.state {
+				EVM::remove_account_storage(&address);
+				for (index, value) in state {
+					EVM::set_account_storage(address, index, value);
+				}
+			}
+			for (index, value) in account.state_diff {
+				EVM::set_account_storage(address, index, value);
+			}
+			Ok(())
+		}
+
+		fn override_block(
+			number: Option<U256>,
+			timestamp: Option<u64>,
+		) -> Result<(), sp_runtime::DispatchError> {
+			if let Some(number) = number {
+				let number = BlockNumber::try_from(number)
+					.map_err(|_| sp_runtime::DispatchError::Other("block number overflow"))?;
+				System::set_block_number(number);
+			}
+			if let Some(timestamp) = timestamp {
+				pallet_timestamp::Now::<Runtime>::put(timestamp);
+			}
+			Ok(())
+		}
+
+		fn check_transaction(
+			transaction: EthereumTransaction,
+		) -> Result<(), pallet_evm::InvalidEvmTransaction> {
+			Ethereum::check_transaction(&transaction)
+		}
+
+		fn receipt_proof(index: u32) -> Option<Vec<Vec<u8>>> {
+			Ethereum::current_receipt_proof(index)
+		}
+
+		fn precompiles() -> Vec<fp_rpc::PrecompileInfo> {
+			FrontierPrecompiles::<Runtime>::info()
+		}
+
+		fn gas_table() -> Vec<(pallet_evm::GasParameter, u64)> {
+			EVM::gas_table()
+		}
+
+		fn dry_run(
+			from: H160,
+			to: Option<H160>,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			estimate: bool,
+			access_list: Option<Vec<(H160, Vec<H256>)>>,
+		) -> Result<pallet_evm::DryRunInfo, sp_runtime::DispatchError> {
+			let mut config = pallet_evm::Pallet::<Runtime>::evm_config();
+			config.estimate = estimate;
+
+			<Runtime as pallet_evm::Config>::Runner::dry_run(
+				from,
+				to,
+				data,
+				value,
+				gas_limit.low_u64(),
+				max_fee_per_gas,
+				max_priority_fee_per_gas,
+				nonce,
+				access_list.unwrap_or_default(),
+				&config,
+			).map_err(|err| err.error.into())
+		}
 	}
 
 	impl fp_rpc::ConvertTransactionRuntimeApi<Block> for Runtime {
@@ -747,6 +971,12 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl fp_rpc::ContractMetadataRuntimeApi<Block> for Runtime {
+		fn contract_metadata(address: H160) -> Option<fp_rpc::ContractMetadata> {
+			ContractMetadata::metadata(address)
+		}
+	}
+
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<
 		Block,
 		Balance,
@@ -828,6 +1058,7 @@ impl_runtime_apis! {
 			config: frame_benchmarking::BenchmarkConfig
 		) -> Result<Vec<frame_benchmarking::BenchmarkBatch>, sp_runtime::RuntimeString> {
 			use frame_benchmarking::{Benchmarking, BenchmarkBatch, add_benchmark, TrackedStorageKey};
+			use pallet_author_mapping::Pallet as PalletAuthorMappingBench;
 			use pallet_evm::Pallet as PalletEvmBench;
 			impl frame_system_benchmarking::Config for Runtime {}
 
@@ -837,6 +1068,7 @@ impl_runtime_apis! {
 			let params = (&config, &whitelist);
 
 			add_benchmark!(params, batches, pallet_evm, PalletEvmBench::<Runtime>);
+			add_benchmark!(params, batches, pallet_author_mapping, PalletAuthorMappingBench::<Runtime>);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)
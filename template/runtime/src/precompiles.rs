@@ -1,11 +1,22 @@
 use pallet_evm::{Context, Precompile, PrecompileResult, PrecompileSet};
 use sp_core::H160;
-use sp_std::marker::PhantomData;
+use sp_std::{marker::PhantomData, vec::Vec};
 
 use pallet_evm_precompile_modexp::Modexp;
 use pallet_evm_precompile_sha3fips::Sha3FIPS256;
 use pallet_evm_precompile_simple::{ECRecover, ECRecoverPublicKey, Identity, Ripemd160, Sha256};
 
+/// The addresses and names of the precompiles, which `execute` must match.
+const PRECOMPILES: [(u64, &str); 7] = [
+	(1, "ECRecover"),
+	(2, "Sha256"),
+	(3, "Ripemd160"),
+	(4, "Identity"),
+	(5, "Modexp"),
+	(1024, "Sha3FIPS256"),
+	(1025, "ECRecoverPublicKey"),
+];
+
 pub struct FrontierPrecompiles<R>(PhantomData<R>);
 
 impl<R> FrontierPrecompiles<R>
@@ -15,10 +26,21 @@ where
 	pub fn new() -> Self {
 		Self(Default::default())
 	}
-	pub fn used_addresses() -> sp_std::vec::Vec<H160> {
-		sp_std::vec![1, 2, 3, 4, 5, 1024, 1025]
+	pub fn used_addresses() -> Vec<H160> {
+		PRECOMPILES
+			.into_iter()
+			.map(|(address, _)| hash(address))
+			.collect()
+	}
+	/// The precompiles, none of which is called through a Solidity interface.
+	pub fn info() -> Vec<fp_rpc::PrecompileInfo> {
+		PRECOMPILES
 			.into_iter()
-			.map(hash)
+			.map(|(address, name)| fp_rpc::PrecompileInfo {
+				address: hash(address),
+				name: name.as_bytes().to_vec(),
+				interface_hash: None,
+			})
 			.collect()
 	}
 }
@@ -42,10 +64,10 @@ where
 			a if a == hash(4) => Some(Identity::execute(input, target_gas, context, is_static)),
 			a if a == hash(5) => Some(Modexp::execute(input, target_gas, context, is_static)),
 			// Non-Frontier specific nor Ethereum precompiles :
-			a if a == hash(1024) => {
-				Some(Sha3FIPS256::execute(input, target_gas, context, is_static))
-			}
-			a if a == hash(1025) => Some(ECRecoverPublicKey::execute(
+			a if a == hash(1024) => Some(<Sha3FIPS256>::execute(
+				input, target_gas, context, is_static,
+			)),
+			a if a == hash(1025) => Some(<ECRecoverPublicKey>::execute(
 				input, target_gas, context, is_static,
 			)),
 			_ => None,
@@ -0,0 +1,186 @@
+// Runs the RPC test fixtures of ethereum/execution-apis against the template node, and checks
+// that the responses have the shape of the expected ones.
+//
+// The fixtures are recorded on another chain, so the values of the responses can't be
+// compared. A response is instead conformant when it has the fields and the value types of the
+// expected one. Responses without result, because the requested data doesn't exist on the
+// template node chain, are skipped.
+//
+// Usage: npm run conformance -- <execution-apis>/tests [method...]
+
+import fs from "fs";
+import path from "path";
+
+import { createAndFinalizeBlock, customRequest, startFrontierNode } from "../tests/util";
+
+const GENESIS_ACCOUNT = "0x6be02d1d3665660d22ff9624b7be0551ee1ac91b";
+const GENESIS_ACCOUNT_PRIVATE_KEY = "0x99B3C12287537E38C90A9219D4CB074A89A16E9CDB20BF85728EBD97C343E342";
+const TEST_ACCOUNT = "0x1111111111111111111111111111111111111111";
+
+const METHOD_NOT_FOUND = -32601;
+
+// Lengths of the fixed size data types: bytes8 (nonce), address, bytes32 and bloom.
+const FIXED_HEX_LENGTHS = [18, 42, 66, 514];
+
+interface Fixture {
+	file: string;
+	request: any;
+	response: any;
+}
+
+type Outcome = "passed" | "failed" | "skipped" | "unimplemented";
+
+// Reads the `>>` requests and `<<` responses of the `.io` files of the given directory.
+function readFixtures(directory: string, methods: string[]): Fixture[] {
+	const fixtures: Fixture[] = [];
+	for (const method of fs.readdirSync(directory).sort()) {
+		const methodDirectory = path.join(directory, method);
+		if (!fs.statSync(methodDirectory).isDirectory() || (methods.length > 0 && methods.indexOf(method) < 0)) {
+			continue;
+		}
+		for (const file of fs.readdirSync(methodDirectory).sort()) {
+			if (path.extname(file) !== ".io") {
+				continue;
+			}
+			let request = null;
+			for (const line of fs.readFileSync(path.join(methodDirectory, file), "utf8").split("\n")) {
+				if (line.slice(0, 3) === ">> ") {
+					request = JSON.parse(line.slice(3));
+				} else if (line.slice(0, 3) === "<< " && request) {
+					fixtures.push({ file: `${method}/${file}`, request, response: JSON.parse(line.slice(3)) });
+					request = null;
+				}
+			}
+		}
+	}
+	return fixtures;
+}
+
+function flatten(lists: string[][]): string[] {
+	return [].concat(...lists);
+}
+
+function isHex(value: any): boolean {
+	return typeof value === "string" && /^0x[0-9a-fA-F]*$/.test(value);
+}
+
+// Differences between the shape of a value and the one of the expected value. Null values are
+// compatible with anything, as they depend on the chain content.
+function shapeMismatches(expected: any, actual: any, at: string): string[] {
+	if (expected === null || actual === null) {
+		return [];
+	}
+	if (Array.isArray(expected)) {
+		if (!Array.isArray(actual)) {
+			return [`${at}: expected an array, got ${JSON.stringify(actual)}`];
+		}
+		if (expected.length === 0) {
+			return [];
+		}
+		return flatten(actual.map((item, index) => shapeMismatches(expected[0], item, `${at}[${index}]`)));
+	}
+	if (typeof expected === "object") {
+		if (typeof actual !== "object" || Array.isArray(actual)) {
+			return [`${at}: expected an object, got ${JSON.stringify(actual)}`];
+		}
+		return flatten(
+			Object.keys(expected).map((key) =>
+				key in actual ? shapeMismatches(expected[key], actual[key], `${at}.${key}`) : [`${at}.${key}: missing`]
+			)
+		);
+	}
+	if (isHex(expected)) {
+		if (!isHex(actual)) {
+			return [`${at}: expected hex data, got ${JSON.stringify(actual)}`];
+		}
+		if (FIXED_HEX_LENGTHS.indexOf(expected.length) >= 0 && actual.length !== expected.length) {
+			return [`${at}: expected ${(expected.length - 2) / 2} bytes, got ${actual}`];
+		}
+		return [];
+	}
+	if (typeof expected !== typeof actual) {
+		return [`${at}: expected a ${typeof expected}, got ${JSON.stringify(actual)}`];
+	}
+	return [];
+}
+
+function check(fixture: Fixture, response: any): { outcome: Outcome; reasons: string[] } {
+	const expected = fixture.response;
+	if (response.error) {
+		if (response.error.code === METHOD_NOT_FOUND) {
+			return { outcome: "unimplemented", reasons: [] };
+		}
+		if (expected.error) {
+			return { outcome: "passed", reasons: [] };
+		}
+		return { outcome: "skipped", reasons: [response.error.message] };
+	}
+	if (expected.error) {
+		return { outcome: "failed", reasons: [`expected an error, got ${JSON.stringify(response.result)}`] };
+	}
+	if (response.result === null && expected.result !== null) {
+		return { outcome: "skipped", reasons: ["no result"] };
+	}
+	const reasons = shapeMismatches(expected.result, response.result, "result");
+	return { outcome: reasons.length > 0 ? "failed" : "passed", reasons };
+}
+
+// Gives the chain a block with a transaction, so that most requests have a result.
+async function seed(web3) {
+	const tx = await web3.eth.accounts.signTransaction(
+		{
+			from: GENESIS_ACCOUNT,
+			to: TEST_ACCOUNT,
+			value: "0x200",
+			gasPrice: "0x3B9ACA00",
+			gas: "0x100000",
+		},
+		GENESIS_ACCOUNT_PRIVATE_KEY
+	);
+	await customRequest(web3, "eth_sendRawTransaction", [tx.rawTransaction]);
+	await createAndFinalizeBlock(web3);
+}
+
+async function main() {
+	const [directory, ...methods] = process.argv.slice(2);
+	if (!directory) {
+		console.error("Usage: npm run conformance -- <execution-apis>/tests [method...]");
+		process.exit(2);
+	}
+	const fixtures = readFixtures(directory, methods);
+
+	const { web3, binary } = await startFrontierNode();
+	const tallies: { [method: string]: { [outcome in Outcome]?: number } } = {};
+	let failures = 0;
+	try {
+		await seed(web3);
+		for (const fixture of fixtures) {
+			const { method, params } = fixture.request;
+			const response = await customRequest(web3, method, params || []);
+			const { outcome, reasons } = check(fixture, response);
+			tallies[method] = tallies[method] || {};
+			tallies[method][outcome] = (tallies[method][outcome] || 0) + 1;
+			if (outcome === "failed") {
+				failures += 1;
+				console.log(`FAIL ${fixture.file}`);
+				reasons.forEach((reason) => console.log(`    ${reason}`));
+			}
+		}
+	} finally {
+		binary.kill();
+	}
+
+	for (const method of Object.keys(tallies)) {
+		const tally = tallies[method];
+		const counts = (["passed", "failed", "skipped", "unimplemented"] as Outcome[])
+			.filter((outcome) => tally[outcome])
+			.map((outcome) => `${tally[outcome]} ${outcome}`);
+		console.log(`${method}: ${counts.join(", ")}`);
+	}
+	process.exit(failures > 0 ? 1 : 0);
+}
+
+main().catch((err) => {
+	console.error(err);
+	process.exit(1);
+});
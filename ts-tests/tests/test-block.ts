@@ -37,6 +37,8 @@ describeWithFrontier("Frontier RPC (Block)", (context) => {
 			"0x0000000000000000000000000000000000000000000000000000000000000000",
 			"0x0000000000000000",
 		]);
+		expect(block.mixHash).to.equal("0x0000000000000000000000000000000000000000000000000000000000000000");
+		expect(block.nonce).to.equal("0x0000000000000000");
 		expect(block.hash).to.be.a("string").lengthOf(66);
 		expect(block.parentHash).to.be.a("string").lengthOf(66);
 		expect(block.timestamp).to.be.a("number");
@@ -0,0 +1,132 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+
+import { createAndFinalizeBlock, describeWithFrontier, customRequest } from "./util";
+
+describeWithFrontier("Frontier RPC (FrontierApi)", (context) => {
+	const GENESIS_ACCOUNT = "0x6be02d1d3665660d22ff9624b7be0551ee1ac91b";
+	const GENESIS_ACCOUNT_PRIVATE_KEY = "0x99B3C12287537E38C90A9219D4CB074A89A16E9CDB20BF85728EBD97C343E342";
+
+	step("should compute CREATE2 address", async function () {
+		// Example 0 of EIP-1014.
+		const deployer = "0x0000000000000000000000000000000000000000";
+		const salt = "0x0000000000000000000000000000000000000000000000000000000000000000";
+		const initCodeHash = context.web3.utils.sha3("0x00");
+		const address = await customRequest(context.web3, "frontier_computeCreate2Address", [
+			deployer,
+			salt,
+			initCodeHash,
+		]);
+		expect(address.result).to.be.equal("0x4d1a2e2bb4f88f0250f26ffff098b0b30b26bf38");
+	});
+
+	step("should validate a transaction", async function () {
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				to: GENESIS_ACCOUNT,
+				value: "0x00",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		const result = await customRequest(context.web3, "frontier_validateTransaction", [tx.rawTransaction]);
+		expect(result.result).to.deep.equal({ valid: true });
+	});
+
+	step("should report the cause of an invalid transaction", async function () {
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				to: GENESIS_ACCOUNT,
+				value: "0x00",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+				chainId: 1,
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		const result = await customRequest(context.web3, "frontier_validateTransaction", [tx.rawTransaction]);
+		expect(result.result).to.deep.equal({
+			valid: false,
+			reason: "INVALID_CHAIN_ID",
+			message: "invalid chain id",
+		});
+	});
+
+	step("should report a replaced transaction", async function () {
+		const signTransaction = (gasPrice: string) =>
+			context.web3.eth.accounts.signTransaction(
+				{
+					from: GENESIS_ACCOUNT,
+					to: GENESIS_ACCOUNT,
+					value: "0x00",
+					gasPrice,
+					gas: "0x100000",
+					nonce: 0,
+				},
+				GENESIS_ACCOUNT_PRIVATE_KEY
+			);
+		const tx = await signTransaction("0x3B9ACA00");
+		const replacement = await signTransaction("0x77359400");
+
+		await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction]);
+		await customRequest(context.web3, "eth_sendRawTransaction", [replacement.rawTransaction]);
+		// Pool statuses are recorded asynchronously.
+		await new Promise((resolve) => setTimeout(resolve, 500));
+
+		const result = await customRequest(context.web3, "frontier_transactionStatus", [tx.transactionHash]);
+		expect(result.result).to.deep.equal({
+			status: "replaced",
+			replacedBy: replacement.transactionHash,
+		});
+	});
+
+	step("should not report an unknown transaction", async function () {
+		const result = await customRequest(context.web3, "frontier_transactionStatus", [
+			"0x0000000000000000000000000000000000000000000000000000000000000001",
+		]);
+		expect(result.result).to.be.null;
+	});
+});
+
+describeWithFrontier("Frontier RPC (Receipt Proof)", (context) => {
+	const GENESIS_ACCOUNT = "0x6be02d1d3665660d22ff9624b7be0551ee1ac91b";
+	const GENESIS_ACCOUNT_PRIVATE_KEY = "0x99B3C12287537E38C90A9219D4CB074A89A16E9CDB20BF85728EBD97C343E342";
+
+	step("should prove the inclusion of a receipt", async function () {
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				to: GENESIS_ACCOUNT,
+				value: "0x00",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction]);
+		await createAndFinalizeBlock(context.web3);
+
+		const block = await context.web3.eth.getBlock(1);
+		const result = (
+			await customRequest(context.web3, "frontier_getTransactionReceiptProof", [tx.transactionHash])
+		).result;
+		expect(result).to.include({
+			transactionHash: tx.transactionHash,
+			transactionIndex: "0x0",
+			blockHash: block.hash,
+			blockNumber: "0x1",
+			receiptsRoot: block.receiptsRoot,
+		});
+		expect(context.web3.utils.sha3(result.proof[0])).to.be.equal(block.receiptsRoot);
+	});
+
+	step("should not prove an unknown transaction", async function () {
+		const result = await customRequest(context.web3, "frontier_getTransactionReceiptProof", [
+			"0x0000000000000000000000000000000000000000000000000000000000000001",
+		]);
+		expect(result.result).to.be.null;
+	});
+});
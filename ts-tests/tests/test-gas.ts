@@ -190,7 +190,7 @@ describeWithFrontier("Frontier RPC (Gas)", (context) => {
 		const createReceipt = await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction]);
 		await createAndFinalizeBlock(context.web3);
 		expect((createReceipt as any).error.message).to.equal(
-			"submit transaction to pool failed: Pool(InvalidTransaction(InvalidTransaction::ExhaustsResources))"
+			"exceeds block gas limit"
 		);
 	});
 });
@@ -0,0 +1,30 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+
+import { describeWithFrontier, customRequest } from "./util";
+
+describeWithFrontier("Frontier RPC (IPC)", (context) => {
+	step("should serve the eth namespace", async function () {
+		expect(await context.web3.eth.getChainId()).to.equal(42);
+	});
+
+	step("should serve the net namespace", async function () {
+		expect(await context.web3.eth.net.getId()).to.equal(42);
+	});
+
+	step("should serve the web3 namespace", async function () {
+		const version = await customRequest(context.web3, "web3_clientVersion", []);
+		expect(version.result).to.be.equal("node-frontier-template/v1.1/fc-rpc-2.0.0-dev");
+	});
+
+	step("should serve subscriptions", async function () {
+		const subscription = context.web3.eth.subscribe("newBlockHeaders", function (error, result) {});
+		const connected = await new Promise((resolve) => {
+			subscription.on("connected", (d: any) => {
+				resolve(d);
+			});
+		});
+		subscription.unsubscribe();
+		expect(connected).to.not.be.null;
+	});
+}, "ipc");
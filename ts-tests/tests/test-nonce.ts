@@ -31,4 +31,38 @@ describeWithFrontier("Frontier RPC (Nonce)", (context) => {
 		expect(await context.web3.eth.getTransactionCount(GENESIS_ACCOUNT, 'pending')).to.eq(1);
 		expect(await context.web3.eth.getTransactionCount(GENESIS_ACCOUNT, 'earliest')).to.eq(0);
 	});
+
+	step("should reject a transaction already in the pool", async function () {
+		const tx = await context.web3.eth.accounts.signTransaction({
+			from: GENESIS_ACCOUNT,
+			to: TEST_ACCOUNT,
+			value: "0x200",
+			gasPrice: "0x3B9ACA00",
+			gas: "0x100000",
+			nonce: 1,
+		}, GENESIS_ACCOUNT_PRIVATE_KEY);
+
+		await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction]);
+		const resubmitted = await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction]);
+		expect(resubmitted.error).to.include({
+			message: "already known",
+		});
+		await createAndFinalizeBlock(context.web3);
+	});
+
+	step("should reject a transaction with a nonce already used", async function () {
+		const tx = await context.web3.eth.accounts.signTransaction({
+			from: GENESIS_ACCOUNT,
+			to: TEST_ACCOUNT,
+			value: "0x300",
+			gasPrice: "0x3B9ACA00",
+			gas: "0x100000",
+			nonce: 0,
+		}, GENESIS_ACCOUNT_PRIVATE_KEY);
+
+		const result = await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction]);
+		expect(result.error).to.include({
+			message: "nonce too low",
+		});
+	});
 });
@@ -29,7 +29,9 @@ describeWithFrontier("Frontier RPC (Pending Pool)", (context) => {
 		const pending_transaction = (await customRequest(context.web3, "eth_getTransactionByHash", [tx_hash])).result;
 		// pending transactions do not know yet to which block they belong to
 		expect(pending_transaction).to.include({
+			blockHash: null,
 			blockNumber: null,
+			transactionIndex: null,
 			hash: tx_hash,
 			publicKey: "0x624f720eae676a04111631c9ca338c11d0f5a80ee42210c6be72983ceb620fbf645a96f951529fa2d70750432d11b7caba5270c4d677255be90b3871c8c58069",
 			r: "0x8e3759de96b00f8a05a95c24fa905963f86a82a0038cca0fde035762fb2d24f7",
@@ -119,3 +121,69 @@ describeWithFrontier("Frontier RPC (Pending Transaction Count)", (context) => {
 		}
 	});
 });
+
+describeWithFrontier("Frontier RPC (Pending Block)", (context) => {
+	const GENESIS_ACCOUNT = "0x6be02d1d3665660d22ff9624b7be0551ee1ac91b";
+	const GENESIS_ACCOUNT_PRIVATE_KEY = "0x99B3C12287537E38C90A9219D4CB074A89A16E9CDB20BF85728EBD97C343E342";
+	const TEST_ACCOUNT = "0x1111111111111111111111111111111111111111";
+
+	it("should return the pending block", async function () {
+		this.timeout(15000);
+
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				to: TEST_ACCOUNT,
+				value: "0x200",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		const tx_hash = (await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction])).result;
+
+		const pending_block = (await customRequest(context.web3, "eth_getBlockByNumber", ["pending", false])).result;
+		expect(pending_block.hash).to.be.null;
+		expect(pending_block.number).to.eq("0x1");
+		expect(pending_block.gasUsed).to.eq("0x5208");
+		expect(pending_block.transactions).to.eql([tx_hash]);
+
+		await createAndFinalizeBlock(context.web3);
+
+		const block = (await customRequest(context.web3, "eth_getBlockByNumber", ["latest", false])).result;
+		expect(block.transactions).to.eql([tx_hash]);
+	});
+});
+
+describeWithFrontier("Frontier RPC (Future Pool)", (context) => {
+	const GENESIS_ACCOUNT = "0x6be02d1d3665660d22ff9624b7be0551ee1ac91b";
+	const GENESIS_ACCOUNT_PRIVATE_KEY = "0x99B3C12287537E38C90A9219D4CB074A89A16E9CDB20BF85728EBD97C343E342";
+	const TEST_ACCOUNT = "0x1111111111111111111111111111111111111111";
+
+	it("should return a transaction waiting for a previous nonce", async function () {
+		this.timeout(15000);
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				to: TEST_ACCOUNT,
+				value: "0x200",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+				nonce: 1,
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+
+		const tx_hash = (await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction])).result;
+
+		const pending_transaction = (await customRequest(context.web3, "eth_getTransactionByHash", [tx_hash])).result;
+		expect(pending_transaction).to.include({
+			blockHash: null,
+			blockNumber: null,
+			transactionIndex: null,
+			hash: tx_hash,
+			from: GENESIS_ACCOUNT,
+			nonce: "0x1",
+		});
+	});
+});
@@ -77,4 +77,10 @@ describeWithFrontier("Frontier RPC (Precompile)", (context) => {
 		});
 		assert.equal(callResult, message);
 	});
+
+	it('should have a revert bytecode stub at precompile addresses', async () => {
+		for (const address of ['0x0000000000000000000000000000000000000001', '0x0000000000000000000000000000000000000400']) {
+			expect(await web3.eth.getCode(address)).to.equal('0x60006000fd');
+		}
+	});
 });
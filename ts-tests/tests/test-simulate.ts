@@ -0,0 +1,38 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+
+import { describeWithFrontier, customRequest } from "./util";
+
+describeWithFrontier("Frontier RPC (Simulate)", (context) => {
+	const GENESIS_ACCOUNT = "0x6be02d1d3665660d22ff9624b7be0551ee1ac91b";
+	const CONTRACT_ADDRESS = "0x1111111111111111111111111111111111111111";
+	// PUSH1 0x2a PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+	const CONTRACT_CODE = "0x602a60005260206000f3";
+
+	step("should simulate calls with state overrides", async function () {
+		const result = await customRequest(context.web3, "eth_simulateV1", [
+			{
+				blockStateCalls: [
+					{
+						stateOverrides: {
+							[CONTRACT_ADDRESS]: { code: CONTRACT_CODE },
+						},
+						calls: [{ from: GENESIS_ACCOUNT, to: CONTRACT_ADDRESS }],
+					},
+				],
+			},
+			"latest",
+		]);
+
+		expect(result.result.length).to.equal(1);
+		const call = result.result[0].calls[0];
+		expect(call.status).to.equal("0x1");
+		expect(call.returnData).to.equal(
+			"0x000000000000000000000000000000000000000000000000000000000000002a"
+		);
+	});
+
+	step("should not persist simulated state", async function () {
+		expect(await context.web3.eth.getCode(CONTRACT_ADDRESS)).to.equal("0x");
+	});
+});
@@ -78,7 +78,9 @@ describeWithFrontier("Frontier RPC (Subscription)", (context) => {
 			number: 2,
 			receiptsRoot: '0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421',
 			sha3Uncles: '0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347',
-			transactionsRoot: '0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421'
+			transactionsRoot: '0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421',
+			mixHash: '0x0000000000000000000000000000000000000000000000000000000000000000',
+			nonce: '0x0000000000000000'
 		});
 		expect((data as any).sealFields).to.eql([
 			"0x0000000000000000000000000000000000000000000000000000000000000000",
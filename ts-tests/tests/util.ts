@@ -2,10 +2,14 @@ import Web3 from "web3";
 import { ethers } from "ethers";
 import { JsonRpcResponse } from "web3-core-helpers";
 import { spawn, ChildProcess } from "child_process";
+import net from "net";
+import os from "os";
+import path from "path";
 
 export const PORT = 19931;
 export const RPC_PORT = 19932;
 export const WS_PORT = 19933;
+export const IPC_PATH = path.join(os.tmpdir(), `frontier-${process.pid}.ipc`);
 
 export const DISPLAY_LOG = process.env.FRONTIER_LOG || false;
 export const FRONTIER_LOG = process.env.FRONTIER_LOG || "info";
@@ -76,6 +80,7 @@ export async function startFrontierNode(provider?: string): Promise<{ web3: Web3
 		`--port=${PORT}`,
 		`--rpc-port=${RPC_PORT}`,
 		`--ws-port=${WS_PORT}`,
+		`--ipc-path=${IPC_PATH}`,
 		`--tmp`,
 	];
 	const binary = spawn(cmd, args);
@@ -129,6 +134,10 @@ export async function startFrontierNode(provider?: string): Promise<{ web3: Web3
 		web3 = new Web3(`ws://127.0.0.1:${WS_PORT}`);
 	}
 
+	if (provider == 'ipc') {
+		web3 = new Web3(new Web3.providers.IpcProvider(IPC_PATH, net));
+	}
+
 	let ethersjs = new ethers.providers.StaticJsonRpcProvider(`http://127.0.0.1:${RPC_PORT}`, {
 		chainId: 42,
 		name: "frontier-dev",